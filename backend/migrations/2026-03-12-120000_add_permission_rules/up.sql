@@ -0,0 +1,18 @@
+-- Server-side permission policy rules. Rules with a NULL session_id apply to
+-- every session owned by the user; session-scoped rules take precedence.
+CREATE TABLE permission_rules (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id         UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    session_id      UUID REFERENCES sessions(id) ON DELETE CASCADE,
+    tool_name       VARCHAR(255) NOT NULL,
+    path_glob       TEXT,
+    command_regex   TEXT,
+    verdict         VARCHAR(16) NOT NULL CHECK (verdict IN ('allow', 'deny', 'ask')),
+    priority        INTEGER NOT NULL DEFAULT 0,
+    description     TEXT,
+    created_at      TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at      TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_permission_rules_user_id ON permission_rules(user_id);
+CREATE INDEX idx_permission_rules_session_id ON permission_rules(session_id);
@@ -0,0 +1,11 @@
+ALTER TABLE pending_permission_requests
+    DROP CONSTRAINT pending_permission_requests_session_request_key;
+
+-- Keep only the most recent request per session before restoring the old constraint
+DELETE FROM pending_permission_requests p
+USING pending_permission_requests newer
+WHERE p.session_id = newer.session_id
+  AND (p.created_at, p.id) < (newer.created_at, newer.id);
+
+ALTER TABLE pending_permission_requests
+    ADD CONSTRAINT pending_permission_requests_session_id_key UNIQUE (session_id);
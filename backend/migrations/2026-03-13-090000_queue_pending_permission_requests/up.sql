@@ -0,0 +1,8 @@
+-- Allow several pending permission requests per session (e.g. parallel
+-- subagents), keyed by request_id instead of one row per session.
+ALTER TABLE pending_permission_requests
+    DROP CONSTRAINT pending_permission_requests_session_id_key;
+
+ALTER TABLE pending_permission_requests
+    ADD CONSTRAINT pending_permission_requests_session_request_key
+    UNIQUE (session_id, request_id);
@@ -0,0 +1,20 @@
+-- Audit log of every answered permission request. Rows outlive the pending
+-- request and record who (or what) made the decision.
+CREATE TABLE permission_decisions (
+    id                  UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    session_id          UUID NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    request_id          VARCHAR(255) NOT NULL,
+    tool_name           VARCHAR(255) NOT NULL,
+    input               JSONB NOT NULL,
+    decision            VARCHAR(16) NOT NULL CHECK (decision IN ('allow', 'deny')),
+    decided_by          VARCHAR(16) NOT NULL CHECK (decided_by IN ('user', 'policy', 'vscode')),
+    user_id             UUID REFERENCES users(id) ON DELETE SET NULL,
+    permission_rule_id  UUID REFERENCES permission_rules(id) ON DELETE SET NULL,
+    reason              TEXT,
+    requested_at        TIMESTAMP NOT NULL,
+    decided_at          TIMESTAMP NOT NULL DEFAULT NOW(),
+    latency_ms          BIGINT NOT NULL
+);
+
+CREATE INDEX idx_permission_decisions_session_decided
+    ON permission_decisions(session_id, decided_at DESC);
@@ -0,0 +1,16 @@
+DELETE FROM permission_decisions WHERE decided_by = 'timeout';
+
+ALTER TABLE permission_decisions
+    DROP CONSTRAINT permission_decisions_decided_by_check,
+    ADD CONSTRAINT permission_decisions_decided_by_check
+        CHECK (decided_by IN ('user', 'policy', 'vscode'));
+
+ALTER TABLE pending_permission_requests DROP COLUMN escalated_at;
+
+ALTER TABLE scheduled_tasks
+    DROP COLUMN permission_timeout_action,
+    DROP COLUMN permission_timeout_seconds;
+
+ALTER TABLE sessions
+    DROP COLUMN permission_timeout_action,
+    DROP COLUMN permission_timeout_seconds;
@@ -0,0 +1,21 @@
+-- Unanswered permission requests can be resolved automatically after a
+-- timeout. Sessions may set their own timeout; otherwise sessions spawned by a
+-- scheduled task use the task's default.
+ALTER TABLE sessions
+    ADD COLUMN permission_timeout_seconds INTEGER,
+    ADD COLUMN permission_timeout_action VARCHAR(16)
+        CHECK (permission_timeout_action IN ('deny', 'allow', 'escalate'));
+
+ALTER TABLE scheduled_tasks
+    ADD COLUMN permission_timeout_seconds INTEGER,
+    ADD COLUMN permission_timeout_action VARCHAR(16) NOT NULL DEFAULT 'deny'
+        CHECK (permission_timeout_action IN ('deny', 'allow', 'escalate'));
+
+-- Escalation notifies members once and leaves the request pending
+ALTER TABLE pending_permission_requests
+    ADD COLUMN escalated_at TIMESTAMP;
+
+ALTER TABLE permission_decisions
+    DROP CONSTRAINT permission_decisions_decided_by_check,
+    ADD CONSTRAINT permission_decisions_decided_by_check
+        CHECK (decided_by IN ('user', 'policy', 'vscode', 'timeout'));
@@ -0,0 +1,4 @@
+ALTER TABLE scheduled_tasks DROP COLUMN last_run_completed_at;
+ALTER TABLE scheduled_tasks DROP COLUMN last_exit_code;
+ALTER TABLE pending_permission_requests DROP COLUMN emailed_at;
+DROP TABLE email_notification_settings;
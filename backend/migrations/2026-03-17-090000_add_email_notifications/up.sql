@@ -0,0 +1,20 @@
+-- Per-user email notification preferences. A user without a row gets no mail.
+CREATE TABLE email_notification_settings (
+    user_id                 UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    -- Mail when a permission request has waited this long (NULL = off)
+    permission_wait_minutes INTEGER,
+    session_errors          BOOLEAN NOT NULL DEFAULT false,
+    scheduled_failures      BOOLEAN NOT NULL DEFAULT false,
+    daily_digest            BOOLEAN NOT NULL DEFAULT false,
+    -- UTC hour (0-23) at which the digest is sent
+    digest_hour             INTEGER NOT NULL DEFAULT 8 CHECK (digest_hour BETWEEN 0 AND 23),
+    last_digest_at          TIMESTAMP,
+    updated_at              TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- Each waiting permission request is mailed about at most once
+ALTER TABLE pending_permission_requests ADD COLUMN emailed_at TIMESTAMP;
+
+-- Outcome of the most recent scheduled run, for failure mails and the digest
+ALTER TABLE scheduled_tasks ADD COLUMN last_exit_code INTEGER;
+ALTER TABLE scheduled_tasks ADD COLUMN last_run_completed_at TIMESTAMP;
@@ -0,0 +1,18 @@
+-- Chat channels that receive permission requests and turn results through an
+-- incoming webhook, and send commands back through the inbound endpoint.
+-- `user_map` maps chat user IDs to portal account emails.
+CREATE TABLE chat_bridges (
+    id                   UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id              UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name                 VARCHAR(100) NOT NULL,
+    incoming_webhook_url TEXT NOT NULL,
+    signing_secret       VARCHAR(128) NOT NULL,
+    post_permissions     BOOLEAN NOT NULL DEFAULT true,
+    post_results         BOOLEAN NOT NULL DEFAULT true,
+    user_map             JSONB NOT NULL DEFAULT '{}',
+    enabled              BOOLEAN NOT NULL DEFAULT true,
+    created_at           TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at           TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_chat_bridges_user_id ON chat_bridges(user_id);
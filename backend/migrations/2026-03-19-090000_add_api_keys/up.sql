@@ -0,0 +1,17 @@
+-- Personal API keys for scripting against the REST API with a bearer header.
+-- Only the SHA-256 of the key is stored; `key_prefix` is kept for display.
+-- `scopes` is a JSON array of scope names.
+CREATE TABLE api_keys (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id      UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name         VARCHAR(255) NOT NULL,
+    key_prefix   VARCHAR(16) NOT NULL,
+    key_hash     VARCHAR(64) NOT NULL UNIQUE,
+    scopes       JSONB NOT NULL DEFAULT '[]',
+    created_at   TIMESTAMP NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMP,
+    expires_at   TIMESTAMP NOT NULL,
+    revoked      BOOLEAN NOT NULL DEFAULT false
+);
+
+CREATE INDEX idx_api_keys_user_id ON api_keys(user_id);
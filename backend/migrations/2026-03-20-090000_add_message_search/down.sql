@@ -0,0 +1,2 @@
+DROP INDEX idx_messages_search;
+DROP FUNCTION message_search_text(TEXT);
@@ -0,0 +1,50 @@
+-- Full-text search over session transcripts.
+--
+-- `messages.content` holds the raw JSON of each message. `message_search_text`
+-- extracts the parts worth searching: prose, tool inputs and tool results
+-- (Claude), portal text blocks, and completed items (Codex). Signatures, ids
+-- and thinking blocks are left out. Content that is not JSON is indexed as is.
+--
+-- Paths are lax so one odd block does not hide the rest of the message;
+-- recursive (`.**`) paths are strict because lax mode repeats array items.
+--
+-- The result is capped at 100k characters so a huge tool result stays under
+-- the tsvector size limit instead of failing the insert.
+CREATE FUNCTION message_search_text(content TEXT) RETURNS TEXT
+LANGUAGE plpgsql IMMUTABLE PARALLEL SAFE AS $$
+DECLARE
+    doc JSONB;
+    extracted TEXT;
+BEGIN
+    BEGIN
+        doc := content::jsonb;
+    EXCEPTION WHEN others THEN
+        RETURN left(content, 100000);
+    END;
+
+    SELECT string_agg(part #>> '{}', ' ')
+    INTO extracted
+    FROM unnest(ARRAY[
+        'lax $.result ? (@.type() == "string")',
+        'lax $.message.content ? (@.type() == "string")',
+        'lax $.message.content[*] ? (@.type == "text").text',
+        'strict $.message.content[*] ? (@.type == "tool_use").input.** ? (@.type() == "string")',
+        'lax $.message.content[*] ? (@.type == "tool_result").content ? (@.type() == "string")',
+        'lax $.message.content[*] ? (@.type == "tool_result").content[*] ? (@.type == "text").text',
+        'lax $ ? (@.type == "portal").content[*] ? (@.type == "text").text',
+        'lax $ ? (@.type == "item.completed").item.text',
+        'lax $ ? (@.type == "item.completed").item.command',
+        'lax $ ? (@.type == "item.completed").item.aggregated_output',
+        'lax $ ? (@.type == "item.completed").item.aggregatedOutput',
+        'lax $ ? (@.type == "item.completed").item.query',
+        'strict $ ? (@.type == "item.completed").item.arguments.** ? (@.type() == "string")'
+    ]::jsonpath[]) AS path,
+    LATERAL jsonb_path_query(doc, path, '{}', true) AS part
+    WHERE jsonb_typeof(part) = 'string';
+
+    RETURN left(coalesce(extracted, ''), 100000);
+END;
+$$;
+
+CREATE INDEX idx_messages_search ON messages
+    USING GIN (to_tsvector('english', message_search_text(content)));
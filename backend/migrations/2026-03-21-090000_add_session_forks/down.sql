@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_sessions_parent_session_id;
+
+ALTER TABLE sessions
+    DROP COLUMN forked_from_message_id,
+    DROP COLUMN parent_session_id;
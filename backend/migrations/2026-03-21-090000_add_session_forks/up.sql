@@ -0,0 +1,7 @@
+-- Lineage of sessions forked from another session's transcript.
+-- `forked_from_message_id` is the last message of the parent kept in the fork.
+ALTER TABLE sessions
+    ADD COLUMN parent_session_id UUID REFERENCES sessions(id) ON DELETE SET NULL,
+    ADD COLUMN forked_from_message_id UUID REFERENCES messages(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_sessions_parent_session_id ON sessions(parent_session_id);
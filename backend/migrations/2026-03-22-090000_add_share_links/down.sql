@@ -0,0 +1,2 @@
+DROP TABLE share_link_views;
+DROP TABLE share_links;
@@ -0,0 +1,26 @@
+-- Public read-only links to a session for people without a portal account.
+-- Only the SHA-256 of the link token is stored. `password_hash` is an argon2
+-- PHC string when the owner protected the link with a password.
+CREATE TABLE share_links (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    session_id    UUID NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    created_by    UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash    VARCHAR(64) NOT NULL UNIQUE,
+    password_hash VARCHAR(255),
+    created_at    TIMESTAMP NOT NULL DEFAULT NOW(),
+    expires_at    TIMESTAMP NOT NULL,
+    revoked       BOOLEAN NOT NULL DEFAULT false
+);
+
+CREATE INDEX idx_share_links_session_id ON share_links(session_id);
+
+-- One row per time a share link was opened
+CREATE TABLE share_link_views (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    share_link_id UUID NOT NULL REFERENCES share_links(id) ON DELETE CASCADE,
+    viewed_at     TIMESTAMP NOT NULL DEFAULT NOW(),
+    ip_address    VARCHAR(64),
+    user_agent    TEXT
+);
+
+CREATE INDEX idx_share_link_views_link_viewed ON share_link_views(share_link_id, viewed_at DESC);
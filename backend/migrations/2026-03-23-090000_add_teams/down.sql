@@ -0,0 +1,5 @@
+ALTER TABLE scheduled_tasks DROP COLUMN team_id;
+DROP TABLE team_launchers;
+DROP TABLE session_teams;
+DROP TABLE team_members;
+DROP TABLE teams;
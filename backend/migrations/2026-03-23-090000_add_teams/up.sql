@@ -0,0 +1,47 @@
+-- Teams: named groups of users that sessions, scheduled tasks and launchers
+-- can be shared with as a whole. A team `owner` manages membership; a
+-- `member` only receives what is shared with the team.
+CREATE TABLE teams (
+    id         UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name       VARCHAR(100) NOT NULL UNIQUE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE team_members (
+    id         UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    team_id    UUID NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    user_id    UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role       VARCHAR(20) NOT NULL DEFAULT 'member',
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (team_id, user_id)
+);
+
+CREATE INDEX idx_team_members_user_id ON team_members(user_id);
+
+-- A session shared with every member of a team, as a viewer or editor
+CREATE TABLE session_teams (
+    id         UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    session_id UUID NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    team_id    UUID NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    role       VARCHAR(20) NOT NULL DEFAULT 'viewer',
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (session_id, team_id)
+);
+
+CREATE INDEX idx_session_teams_team_id ON session_teams(team_id);
+
+-- Launchers get a fresh id on every start, so a shared launcher is named by
+-- its owner and launcher name.
+CREATE TABLE team_launchers (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    team_id       UUID NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    owner_id      UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    launcher_name VARCHAR(255) NOT NULL,
+    created_at    TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (team_id, owner_id, launcher_name)
+);
+
+-- Runs of a team task are shared with the team
+ALTER TABLE scheduled_tasks
+    ADD COLUMN team_id UUID REFERENCES teams(id) ON DELETE SET NULL;
@@ -0,0 +1,3 @@
+ALTER TABLE sessions DROP COLUMN max_cost_usd;
+DROP TABLE spend_events;
+DROP TABLE spend_budgets;
@@ -0,0 +1,38 @@
+-- Spend budgets: a daily or monthly limit on what a user, or all members of a
+-- team together, may spend. Crossing `soft_limit_usd` notifies; reaching
+-- `hard_limit_usd` interrupts running sessions and blocks input and launches
+-- until an admin raises the limit or the period rolls over.
+CREATE TABLE spend_budgets (
+    id               UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id          UUID REFERENCES users(id) ON DELETE CASCADE,
+    team_id          UUID REFERENCES teams(id) ON DELETE CASCADE,
+    period           VARCHAR(10) NOT NULL,
+    soft_limit_usd   FLOAT8,
+    hard_limit_usd   FLOAT8,
+    -- When the current period's alerts went out, so each is sent once
+    soft_notified_at TIMESTAMP,
+    hard_notified_at TIMESTAMP,
+    created_at       TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at       TIMESTAMP NOT NULL DEFAULT NOW(),
+    CHECK ((user_id IS NULL) <> (team_id IS NULL)),
+    CHECK (period IN ('daily', 'monthly'))
+);
+
+CREATE UNIQUE INDEX idx_spend_budgets_user_period
+    ON spend_budgets(user_id, period) WHERE user_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_spend_budgets_team_period
+    ON spend_budgets(team_id, period) WHERE team_id IS NOT NULL;
+
+-- What each turn added to a session's cost, so spend can be summed per period
+CREATE TABLE spend_events (
+    id         UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    session_id UUID REFERENCES sessions(id) ON DELETE SET NULL,
+    user_id    UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    cost_usd   FLOAT8 NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_spend_events_user_created ON spend_events(user_id, created_at);
+
+-- Hard cap on a single session's cost, chosen at launch
+ALTER TABLE sessions ADD COLUMN max_cost_usd FLOAT8;
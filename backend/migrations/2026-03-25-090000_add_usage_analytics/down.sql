@@ -0,0 +1,3 @@
+ALTER TABLE sessions DROP COLUMN model_usage;
+DROP TABLE usage_daily;
+DROP TABLE usage_records;
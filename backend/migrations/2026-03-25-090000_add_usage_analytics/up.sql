@@ -0,0 +1,51 @@
+-- Per-turn usage: one row per model used in a turn, taken from each `result`
+-- message. Session attributes are copied in so the rows still group by host,
+-- repository and task after the session is deleted.
+CREATE TABLE usage_records (
+    id                    UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    session_id            UUID REFERENCES sessions(id) ON DELETE SET NULL,
+    user_id               UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    model                 VARCHAR(100) NOT NULL,
+    hostname              VARCHAR(255) NOT NULL,
+    repo_url              VARCHAR(512),
+    agent_type            VARCHAR(16) NOT NULL,
+    scheduled_task_id     UUID REFERENCES scheduled_tasks(id) ON DELETE SET NULL,
+    input_tokens          INT8 NOT NULL DEFAULT 0,
+    output_tokens         INT8 NOT NULL DEFAULT 0,
+    cache_creation_tokens INT8 NOT NULL DEFAULT 0,
+    cache_read_tokens     INT8 NOT NULL DEFAULT 0,
+    cost_usd              FLOAT8 NOT NULL DEFAULT 0,
+    -- 1 and the turn's wall-clock time on the row of its costliest model, 0
+    -- on the others, so both add up across models
+    turns                 INT4 NOT NULL DEFAULT 0,
+    duration_ms           INT8 NOT NULL DEFAULT 0,
+    created_at            TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_usage_records_created ON usage_records(created_at);
+
+-- usage_records summed per UTC day and grouping attribute, rebuilt for recent
+-- days by a background task. Analytics read from here.
+CREATE TABLE usage_daily (
+    id                    UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    day                   DATE NOT NULL,
+    user_id               UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    model                 VARCHAR(100) NOT NULL,
+    hostname              VARCHAR(255) NOT NULL,
+    repo_url              VARCHAR(512),
+    agent_type            VARCHAR(16) NOT NULL,
+    scheduled_task_id     UUID REFERENCES scheduled_tasks(id) ON DELETE SET NULL,
+    turns                 INT8 NOT NULL,
+    input_tokens          INT8 NOT NULL,
+    output_tokens         INT8 NOT NULL,
+    cache_creation_tokens INT8 NOT NULL,
+    cache_read_tokens     INT8 NOT NULL,
+    cost_usd              FLOAT8 NOT NULL,
+    duration_ms           INT8 NOT NULL
+);
+
+CREATE INDEX idx_usage_daily_day ON usage_daily(day);
+CREATE INDEX idx_usage_daily_user_day ON usage_daily(user_id, day);
+
+-- The per-model totals of the last `result`, to work out what the next turn added
+ALTER TABLE sessions ADD COLUMN model_usage JSONB;
@@ -0,0 +1,31 @@
+-- Audit trail of admin actions, membership changes, credentials being issued
+-- or revoked and scheduled task edits. Rows are only ever inserted: a trigger
+-- rejects updates and deletes. There are no foreign keys, so events outlive
+-- the users, sessions and tokens they mention; `actor_email` keeps who acted
+-- readable after the account is gone.
+CREATE TABLE audit_events (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- NULL when the server acted on its own, e.g. automatic token renewal
+    actor_id    UUID,
+    actor_email VARCHAR(255),
+    action      VARCHAR(64) NOT NULL,
+    target_type VARCHAR(32) NOT NULL,
+    target_id   UUID,
+    details     JSONB NOT NULL DEFAULT '{}',
+    created_at  TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_events_created ON audit_events(created_at DESC);
+CREATE INDEX idx_audit_events_action_created ON audit_events(action, created_at DESC);
+CREATE INDEX idx_audit_events_actor_created ON audit_events(actor_id, created_at DESC);
+CREATE INDEX idx_audit_events_target ON audit_events(target_id);
+
+CREATE FUNCTION audit_events_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_events_append_only
+    BEFORE UPDATE OR DELETE ON audit_events
+    FOR EACH ROW EXECUTE FUNCTION audit_events_append_only();
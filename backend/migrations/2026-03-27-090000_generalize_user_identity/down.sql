@@ -0,0 +1,12 @@
+ALTER TABLE users DROP CONSTRAINT users_auth_identity_key;
+
+-- Subjects from other providers may collide with Google IDs; keep those
+-- accounts but make sure no Google login can land on them.
+UPDATE users
+SET auth_subject = auth_provider || ':' || auth_subject
+WHERE auth_provider NOT IN ('google', 'dev');
+
+ALTER TABLE users DROP COLUMN auth_provider;
+ALTER TABLE users RENAME COLUMN auth_subject TO google_id;
+ALTER TABLE users ADD CONSTRAINT users_google_id_key UNIQUE (google_id);
+CREATE INDEX idx_users_google_id ON users(google_id);
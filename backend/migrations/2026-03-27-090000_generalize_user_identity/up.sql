@@ -0,0 +1,11 @@
+-- Users are identified by the login provider that vouched for them plus that
+-- provider's subject, rather than a Google account ID. Existing accounts came
+-- from Google, except the dev mode test user.
+ALTER TABLE users RENAME COLUMN google_id TO auth_subject;
+ALTER TABLE users ADD COLUMN auth_provider VARCHAR(64) NOT NULL DEFAULT 'google';
+ALTER TABLE users ALTER COLUMN auth_provider DROP DEFAULT;
+UPDATE users SET auth_provider = 'dev' WHERE auth_subject = 'dev_mode_test_user';
+
+ALTER TABLE users DROP CONSTRAINT users_google_id_key;
+DROP INDEX IF EXISTS idx_users_google_id;
+ALTER TABLE users ADD CONSTRAINT users_auth_identity_key UNIQUE (auth_provider, auth_subject);
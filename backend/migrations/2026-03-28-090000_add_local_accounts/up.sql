@@ -0,0 +1,22 @@
+-- Username/password accounts for deployments with no route to a login
+-- provider. The user row has auth_provider 'local' and the username as its
+-- subject. An admin creates the account with a one-time setup link, whose
+-- token is stored hashed; following the link sets the password. A password
+-- reset by an admin clears the password and issues a new link.
+CREATE TABLE local_accounts (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    username VARCHAR(64) NOT NULL UNIQUE,
+    -- argon2 PHC string; NULL until the setup link is used
+    password_hash TEXT,
+    -- SHA-256 of the outstanding setup link token
+    setup_token_hash VARCHAR(64) UNIQUE,
+    setup_expires_at TIMESTAMP,
+    -- Base32 TOTP secret; set when enrolment starts, trusted once enabled
+    totp_secret VARCHAR(64),
+    totp_enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Last accepted time step, so a code can't be used twice
+    totp_last_step BIGINT,
+    password_changed_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
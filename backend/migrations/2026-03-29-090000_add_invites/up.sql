@@ -0,0 +1,31 @@
+-- Invite codes for instances closed by ALLOWED_EMAILS/ALLOWED_EMAIL_DOMAIN.
+-- Whoever signs in through an unexpired, unrevoked invite with uses left is
+-- let in regardless of the allowlist and given the invite's role. Only the
+-- SHA-256 of the code is stored, like share links.
+CREATE TABLE invites (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    -- Admin's note on who the invite is for
+    label VARCHAR(255),
+    role VARCHAR(16) NOT NULL CHECK (role IN ('user', 'admin')),
+    max_uses INTEGER NOT NULL CHECK (max_uses > 0),
+    use_count INTEGER NOT NULL DEFAULT 0,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP NOT NULL,
+    revoked BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX idx_invites_created ON invites(created_at DESC);
+
+-- Who joined through which invite. A user with a row here keeps passing the
+-- allowlist on later logins.
+CREATE TABLE invite_redemptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    invite_id UUID NOT NULL REFERENCES invites(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    redeemed_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (invite_id, user_id)
+);
+
+CREATE INDEX idx_invite_redemptions_user ON invite_redemptions(user_id);
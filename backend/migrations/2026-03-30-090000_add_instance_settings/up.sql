@@ -0,0 +1,9 @@
+-- Instance settings admins changed at runtime. The environment supplies the
+-- defaults; a row here overrides one setting until it is set back to its
+-- default, which deletes the row.
+CREATE TABLE instance_settings (
+    key VARCHAR(64) PRIMARY KEY,
+    value JSONB NOT NULL,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
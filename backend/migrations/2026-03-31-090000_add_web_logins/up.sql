@@ -0,0 +1,14 @@
+-- Browser sign-ins. The signed session cookie now carries the id of a row
+-- here instead of the user id, so a login can be listed and revoked; deleting
+-- the row signs that browser out. Cookies issued before this migration name
+-- no row and have to sign in again.
+CREATE TABLE web_logins (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    ip_address VARCHAR(64),
+    user_agent TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_web_logins_user ON web_logins(user_id, last_seen_at DESC);
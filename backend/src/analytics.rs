@@ -0,0 +1,316 @@
+//! Usage analytics
+//!
+//! Sessions only keep running totals, so each `result` message is also
+//! broken down into `usage_records`: one row per model the turn used, with
+//! the tokens and cost it added. Claude reports per-model totals for its whole
+//! process in `modelUsage`; what a turn added is the growth since the previous
+//! result, whose totals are kept in `sessions.model_usage`. Results without
+//! `modelUsage` are recorded as a single `unknown` model, from the growth of
+//! the session totals.
+//!
+//! A background task sums the rows per UTC day into `usage_daily`, which
+//! `/api/analytics` reads. It rebuilds the days since the last run, so a
+//! turn shows up in analytics within [`ROLLUP_INTERVAL_SECS`].
+
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use diesel::sql_types::Timestamp;
+use serde_json::Value;
+
+use crate::budgets::cost_delta;
+use crate::models::{NewUsageRecord, Session};
+use crate::schema::{sessions, usage_daily, usage_records};
+
+/// How often recent days are rolled up into `usage_daily`
+pub const ROLLUP_INTERVAL_SECS: u64 = 300;
+
+/// Model recorded when the agent does not say which one it used
+pub const UNKNOWN_MODEL: &str = "unknown";
+
+/// Longest model name stored, matching the column
+const MAX_MODEL_LEN: usize = 100;
+
+/// Tokens and cost of one model, either totals or what a turn added
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelUsage {
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cost_usd: f64,
+}
+
+impl ModelUsage {
+    /// Read one entry of `modelUsage`, e.g.
+    /// `{"inputTokens": 12, "outputTokens": 340, "costUSD": 0.02, ...}`.
+    fn from_totals(model: &str, totals: &Value) -> Self {
+        let tokens = |field: &str| totals.get(field).and_then(Value::as_i64).unwrap_or(0);
+        Self {
+            model: model.chars().take(MAX_MODEL_LEN).collect(),
+            input_tokens: tokens("inputTokens"),
+            output_tokens: tokens("outputTokens"),
+            cache_creation_tokens: tokens("cacheCreationInputTokens"),
+            cache_read_tokens: tokens("cacheReadInputTokens"),
+            cost_usd: totals.get("costUSD").and_then(Value::as_f64).unwrap_or(0.0),
+        }
+    }
+
+    /// What was added since `earlier`. Totals that went down mean the agent
+    /// restarted, and count from zero.
+    fn since(&self, earlier: &ModelUsage) -> Self {
+        let restarted = self.input_tokens < earlier.input_tokens
+            || self.output_tokens < earlier.output_tokens
+            || self.cache_creation_tokens < earlier.cache_creation_tokens
+            || self.cache_read_tokens < earlier.cache_read_tokens
+            || self.cost_usd < earlier.cost_usd;
+        if restarted {
+            return self.clone();
+        }
+        Self {
+            model: self.model.clone(),
+            input_tokens: self.input_tokens - earlier.input_tokens,
+            output_tokens: self.output_tokens - earlier.output_tokens,
+            cache_creation_tokens: self.cache_creation_tokens - earlier.cache_creation_tokens,
+            cache_read_tokens: self.cache_read_tokens - earlier.cache_read_tokens,
+            cost_usd: self.cost_usd - earlier.cost_usd,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.input_tokens == 0
+            && self.output_tokens == 0
+            && self.cache_creation_tokens == 0
+            && self.cache_read_tokens == 0
+            && self.cost_usd <= 0.0
+    }
+}
+
+/// What each model added in a turn, given the `modelUsage` totals of the
+/// previous result and of this one. Models the turn did not use are left out.
+pub fn model_deltas(previous: Option<&Value>, current: &Value) -> Vec<ModelUsage> {
+    let Some(models) = current.as_object() else {
+        return Vec::new();
+    };
+    models
+        .iter()
+        .map(|(model, totals)| {
+            let now = ModelUsage::from_totals(model, totals);
+            match previous.and_then(|p| p.get(model)) {
+                Some(before) => now.since(&ModelUsage::from_totals(model, before)),
+                None => now,
+            }
+        })
+        .filter(|usage| !usage.is_empty())
+        .collect()
+}
+
+/// What a turn added, from the session totals before it and the `result`
+/// message, for agents that do not report `modelUsage`.
+fn session_delta(session: &Session, content: &Value) -> ModelUsage {
+    let usage = content.get("usage");
+    let grown = |field: &str, before: i64| {
+        let now = usage
+            .and_then(|u| u.get(field))
+            .and_then(Value::as_i64)
+            .unwrap_or(before);
+        if now < before {
+            now
+        } else {
+            now - before
+        }
+    };
+    let reported = content
+        .get("total_cost_usd")
+        .and_then(Value::as_f64)
+        .unwrap_or(session.total_cost_usd);
+    ModelUsage {
+        model: UNKNOWN_MODEL.to_string(),
+        input_tokens: grown("input_tokens", session.input_tokens),
+        output_tokens: grown("output_tokens", session.output_tokens),
+        cache_creation_tokens: grown("cache_creation_input_tokens", session.cache_creation_tokens),
+        cache_read_tokens: grown("cache_read_input_tokens", session.cache_read_tokens),
+        cost_usd: cost_delta(session.total_cost_usd, reported),
+    }
+}
+
+/// Record the usage of a turn from its `result` message, given the session
+/// as it was before the turn's totals were stored.
+pub fn record_turn(conn: &mut PgConnection, session: &Session, content: &Value) -> QueryResult<()> {
+    let model_usage = content.get("modelUsage").filter(|m| m.is_object());
+    let mut turn = match model_usage {
+        Some(current) => model_deltas(session.model_usage.as_ref(), current),
+        None => vec![session_delta(session, content)],
+    };
+    if turn.is_empty() {
+        // Nothing was used, but the turn still took time
+        let model = model_usage
+            .and_then(Value::as_object)
+            .and_then(|m| m.keys().next().cloned())
+            .unwrap_or_else(|| UNKNOWN_MODEL.to_string());
+        turn.push(ModelUsage {
+            model,
+            ..Default::default()
+        });
+    }
+
+    // The costliest model carries the turn itself
+    let primary = turn
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.cost_usd.total_cmp(&b.cost_usd))
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    let duration_ms = content
+        .get("duration_ms")
+        .and_then(Value::as_i64)
+        .unwrap_or(0);
+
+    let records: Vec<NewUsageRecord> = turn
+        .into_iter()
+        .enumerate()
+        .map(|(i, usage)| NewUsageRecord {
+            session_id: Some(session.id),
+            user_id: session.user_id,
+            model: usage.model,
+            hostname: session.hostname.clone(),
+            repo_url: session.repo_url.clone(),
+            agent_type: session.agent_type.clone(),
+            scheduled_task_id: session.scheduled_task_id,
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_creation_tokens: usage.cache_creation_tokens,
+            cache_read_tokens: usage.cache_read_tokens,
+            cost_usd: usage.cost_usd,
+            turns: i32::from(i == primary),
+            duration_ms: if i == primary { duration_ms } else { 0 },
+        })
+        .collect();
+
+    conn.transaction(|conn| {
+        diesel::insert_into(usage_records::table)
+            .values(&records)
+            .execute(conn)?;
+        if let Some(totals) = model_usage {
+            diesel::update(sessions::table.find(session.id))
+                .set(sessions::model_usage.eq(totals))
+                .execute(conn)?;
+        }
+        Ok(())
+    })
+}
+
+const ROLLUP_SQL: &str = "\
+    INSERT INTO usage_daily (day, user_id, model, hostname, repo_url, agent_type, \
+        scheduled_task_id, turns, input_tokens, output_tokens, cache_creation_tokens, \
+        cache_read_tokens, cost_usd, duration_ms) \
+    SELECT created_at::date, user_id, model, hostname, repo_url, agent_type, scheduled_task_id, \
+        SUM(turns), SUM(input_tokens), SUM(output_tokens), SUM(cache_creation_tokens), \
+        SUM(cache_read_tokens), SUM(cost_usd), SUM(duration_ms) \
+    FROM usage_records \
+    WHERE created_at >= $1 \
+    GROUP BY 1, 2, 3, 4, 5, 6, 7";
+
+/// Rebuild the daily rollup from `from` on.
+pub fn roll_up_since(conn: &mut PgConnection, from: NaiveDate) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        diesel::delete(usage_daily::table.filter(usage_daily::day.ge(from))).execute(conn)?;
+        diesel::sql_query(ROLLUP_SQL)
+            .bind::<Timestamp, _>(from.and_hms_opt(0, 0, 0).unwrap_or_default())
+            .execute(conn)
+    })
+}
+
+/// The first day to roll up: the last day already rolled up, so it is
+/// completed, but no later than yesterday, so turns recorded just before
+/// midnight are not missed. Without a rollup yet, the first recorded day.
+pub fn rollup_start(
+    last_rolled_up: Option<NaiveDate>,
+    first_recorded: Option<NaiveDateTime>,
+    today: NaiveDate,
+) -> NaiveDate {
+    let yesterday = today.pred_opt().unwrap_or(today);
+    match last_rolled_up {
+        Some(day) => day.min(yesterday),
+        None => first_recorded.map(|at| at.date()).unwrap_or(yesterday),
+    }
+}
+
+/// Roll up the days that changed since the last run.
+pub fn roll_up_recent(conn: &mut PgConnection) -> QueryResult<usize> {
+    let last_rolled_up: Option<NaiveDate> = usage_daily::table
+        .select(diesel::dsl::max(usage_daily::day))
+        .first(conn)?;
+    let first_recorded: Option<NaiveDateTime> = if last_rolled_up.is_none() {
+        usage_records::table
+            .select(diesel::dsl::min(usage_records::created_at))
+            .first(conn)?
+    } else {
+        None
+    };
+    let today = chrono::Utc::now().date_naive();
+    roll_up_since(conn, rollup_start(last_rolled_up, first_recorded, today))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_model_deltas() {
+        let previous = json!({
+            "claude-sonnet-4": {"inputTokens": 100, "outputTokens": 50, "costUSD": 0.5},
+            "claude-haiku": {"inputTokens": 10, "outputTokens": 5, "costUSD": 0.01}
+        });
+        let current = json!({
+            "claude-sonnet-4": {"inputTokens": 160, "outputTokens": 90, "cacheReadInputTokens": 400, "costUSD": 0.75},
+            "claude-haiku": {"inputTokens": 10, "outputTokens": 5, "costUSD": 0.01}
+        });
+        let deltas = model_deltas(Some(&previous), &current);
+        assert_eq!(deltas.len(), 1, "unused models are left out");
+        assert_eq!(deltas[0].model, "claude-sonnet-4");
+        assert_eq!(deltas[0].input_tokens, 60);
+        assert_eq!(deltas[0].output_tokens, 40);
+        assert_eq!(deltas[0].cache_read_tokens, 400);
+        assert!((deltas[0].cost_usd - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_model_deltas_first_turn_and_restart() {
+        let current =
+            json!({"claude-opus-4": {"inputTokens": 20, "outputTokens": 8, "costUSD": 0.3}});
+        let first = model_deltas(None, &current);
+        assert_eq!(first[0].input_tokens, 20);
+
+        // The agent restarted, so its totals began again
+        let previous =
+            json!({"claude-opus-4": {"inputTokens": 900, "outputTokens": 300, "costUSD": 4.0}});
+        let restarted = model_deltas(Some(&previous), &current);
+        assert_eq!(restarted[0].input_tokens, 20);
+        assert!((restarted[0].cost_usd - 0.3).abs() < 1e-9);
+
+        assert!(model_deltas(None, &json!(null)).is_empty());
+    }
+
+    #[test]
+    fn test_rollup_start() {
+        let today = day("2026-03-25");
+        assert_eq!(
+            rollup_start(Some(day("2026-03-25")), None, today),
+            day("2026-03-24")
+        );
+        // After downtime, pick up where the last rollup stopped
+        assert_eq!(
+            rollup_start(Some(day("2026-03-20")), None, today),
+            day("2026-03-20")
+        );
+        let first = day("2026-02-01").and_hms_opt(13, 0, 0);
+        assert_eq!(rollup_start(None, first, today), day("2026-02-01"));
+        assert_eq!(rollup_start(None, None, today), day("2026-03-24"));
+    }
+}
@@ -0,0 +1,267 @@
+//! Personal API keys
+//!
+//! Scripts and CI authenticate to the REST API with `Authorization: Bearer
+//! cpk_...`. Keys are separate from the proxy JWTs in `proxy_auth_tokens`:
+//! only their SHA-256 is stored, and each carries a list of scopes.
+//!
+//! `authenticate` runs as a route layer. For a bearer request it looks up the
+//! scope the matched route needs (routes without one, such as key and token
+//! management, refuse API keys), checks the key, and runs the handler with
+//! the key owner recorded in a task-local that `auth::extract_user_id`
+//! consults before the session cookie. Handlers need no changes.
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderMap, Method},
+    middleware::Next,
+    response::Response,
+};
+use diesel::prelude::*;
+use shared::api::ApiKeyScope;
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::models::ApiKey;
+use crate::schema::{api_keys, users};
+use crate::AppState;
+
+/// Every key starts with this, so bearer keys are told apart from other tokens
+pub const KEY_PREFIX: &str = "cpk_";
+/// Characters of the key kept in the clear to tell keys apart
+const DISPLAY_PREFIX_LEN: usize = 12;
+/// `last_used_at` is only rewritten when older than this
+const LAST_USED_GRANULARITY_SECS: i64 = 60;
+
+tokio::task_local! {
+    static API_KEY_USER: Uuid;
+}
+
+/// Routes an API key may call, and the scope each needs. Every route under
+/// `/api/admin/` needs `ApiKeyScope::Admin`.
+const SCOPED_ROUTES: &[(&str, &str, ApiKeyScope)] = &[
+    ("GET", "/api/sessions", ApiKeyScope::ReadSessions),
+    ("GET", "/api/sessions/{id}", ApiKeyScope::ReadSessions),
+    (
+        "GET",
+        "/api/sessions/{id}/messages",
+        ApiKeyScope::ReadSessions,
+    ),
+    (
+        "GET",
+        "/api/sessions/{id}/export",
+        ApiKeyScope::ReadSessions,
+    ),
+    (
+        "GET",
+        "/api/sessions/{id}/members",
+        ApiKeyScope::ReadSessions,
+    ),
+    (
+        "GET",
+        "/api/sessions/{id}/permission-decisions",
+        ApiKeyScope::ReadSessions,
+    ),
+    ("GET", "/api/search", ApiKeyScope::ReadSessions),
+    ("POST", "/api/sessions/{id}/input", ApiKeyScope::SendInput),
+    ("POST", "/api/sessions/{id}/stop", ApiKeyScope::SendInput),
+    ("GET", "/api/launchers", ApiKeyScope::Launch),
+    (
+        "GET",
+        "/api/launchers/{launcher_id}/directories",
+        ApiKeyScope::Launch,
+    ),
+    (
+        "GET",
+        "/api/launchers/{launcher_id}/local-sessions",
+        ApiKeyScope::Launch,
+    ),
+    ("POST", "/api/launch", ApiKeyScope::Launch),
+    ("POST", "/api/sessions/{id}/fork", ApiKeyScope::Launch),
+    ("GET", "/api/scheduled-tasks", ApiKeyScope::ManageSchedules),
+    ("POST", "/api/scheduled-tasks", ApiKeyScope::ManageSchedules),
+    (
+        "PATCH",
+        "/api/scheduled-tasks/{id}",
+        ApiKeyScope::ManageSchedules,
+    ),
+    (
+        "DELETE",
+        "/api/scheduled-tasks/{id}",
+        ApiKeyScope::ManageSchedules,
+    ),
+    (
+        "GET",
+        "/api/scheduled-tasks/{id}/runs",
+        ApiKeyScope::ManageSchedules,
+    ),
+];
+
+/// Generate a new key.
+pub fn generate_key() -> String {
+    use rand::RngCore;
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("{}{}", KEY_PREFIX, hex::encode(bytes))
+}
+
+/// The part of a key shown in listings.
+pub fn display_prefix(key: &str) -> String {
+    key.chars().take(DISPLAY_PREFIX_LEN).collect()
+}
+
+/// The user of the API key authenticating the current request, if any.
+pub fn current_user() -> Option<Uuid> {
+    API_KEY_USER.try_with(|user_id| *user_id).ok()
+}
+
+/// The scope a route needs, or None if API keys may not call it.
+pub fn required_scope(method: &Method, route: &str) -> Option<ApiKeyScope> {
+    if route.starts_with("/api/admin/") {
+        return Some(ApiKeyScope::Admin);
+    }
+    SCOPED_ROUTES
+        .iter()
+        .find(|(m, r, _)| *m == method.as_str() && *r == route)
+        .map(|(_, _, scope)| *scope)
+}
+
+/// Whether a stored scope list grants `scope`.
+pub fn grants(scopes: &serde_json::Value, scope: ApiKeyScope) -> bool {
+    scopes
+        .as_array()
+        .is_some_and(|list| list.iter().any(|s| s.as_str() == Some(scope.as_str())))
+}
+
+/// The API key in an `Authorization: Bearer` header, if there is one.
+fn bearer_key(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+        .filter(|token| token.starts_with(KEY_PREFIX))
+}
+
+/// Route layer that authenticates bearer API keys. Requests without one pass
+/// through to cookie authentication.
+pub async fn authenticate(
+    State(app_state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let Some(key) = bearer_key(request.headers()) else {
+        return Ok(next.run(request).await);
+    };
+
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_default();
+    let scope = required_scope(request.method(), &route).ok_or(AppError::Forbidden)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let now = chrono::Utc::now().naive_utc();
+
+    let api_key: ApiKey = api_keys::table
+        .inner_join(users::table)
+        .filter(api_keys::key_hash.eq(crate::jwt::hash_token(key)))
+        .filter(api_keys::revoked.eq(false))
+        .filter(api_keys::expires_at.gt(now))
+        .filter(users::disabled.eq(false))
+        .select(ApiKey::as_select())
+        .first(&mut conn)
+        .optional()
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .ok_or(AppError::Unauthorized)?;
+
+    if !grants(&api_key.scopes, scope) {
+        warn!(
+            "API key {} lacks scope {} for {} {}",
+            api_key.id,
+            scope.as_str(),
+            request.method(),
+            route
+        );
+        return Err(AppError::Forbidden);
+    }
+
+    let stale = now - chrono::Duration::seconds(LAST_USED_GRANULARITY_SECS);
+    if api_key.last_used_at.is_none_or(|t| t < stale) {
+        let _ = diesel::update(api_keys::table.find(api_key.id))
+            .set(api_keys::last_used_at.eq(now))
+            .execute(&mut conn);
+    }
+    drop(conn);
+
+    Ok(API_KEY_USER.scope(api_key.user_id, next.run(request)).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(
+            required_scope(&Method::GET, "/api/sessions/{id}/messages"),
+            Some(ApiKeyScope::ReadSessions)
+        );
+        assert_eq!(
+            required_scope(&Method::POST, "/api/sessions/{id}/input"),
+            Some(ApiKeyScope::SendInput)
+        );
+        assert_eq!(
+            required_scope(&Method::DELETE, "/api/scheduled-tasks/{id}"),
+            Some(ApiKeyScope::ManageSchedules)
+        );
+        assert_eq!(
+            required_scope(&Method::PATCH, "/api/admin/users/{id}"),
+            Some(ApiKeyScope::Admin)
+        );
+        // Wrong method, and endpoints that never accept keys
+        assert_eq!(required_scope(&Method::DELETE, "/api/sessions/{id}"), None);
+        assert_eq!(required_scope(&Method::POST, "/api/api-keys"), None);
+        assert_eq!(required_scope(&Method::GET, "/api/proxy-tokens"), None);
+    }
+
+    #[test]
+    fn test_grants() {
+        let scopes = serde_json::json!(["sessions:read", "launch"]);
+        assert!(grants(&scopes, ApiKeyScope::ReadSessions));
+        assert!(grants(&scopes, ApiKeyScope::Launch));
+        assert!(!grants(&scopes, ApiKeyScope::Admin));
+        assert!(!grants(&serde_json::json!({}), ApiKeyScope::Launch));
+    }
+
+    #[test]
+    fn test_bearer_key() {
+        let key = generate_key();
+        assert!(key.starts_with(KEY_PREFIX));
+        assert_eq!(key.len(), KEY_PREFIX.len() + 64);
+        assert_eq!(display_prefix(&key).len(), DISPLAY_PREFIX_LEN);
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_key(&headers), None);
+        headers.insert(
+            header::AUTHORIZATION,
+            format!("Bearer {}", key).parse().unwrap(),
+        );
+        assert_eq!(bearer_key(&headers), Some(key.as_str()));
+        // Proxy JWTs and other bearer tokens are left alone
+        headers.insert(header::AUTHORIZATION, "Bearer eyJhbGciOi".parse().unwrap());
+        assert_eq!(bearer_key(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn test_current_user_is_scoped_to_the_request() {
+        assert_eq!(current_user(), None);
+        let user_id = Uuid::new_v4();
+        let seen = API_KEY_USER.scope(user_id, async { current_user() }).await;
+        assert_eq!(seen, Some(user_id));
+        assert_eq!(current_user(), None);
+    }
+}
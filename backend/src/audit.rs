@@ -0,0 +1,163 @@
+//! Audit trail
+//!
+//! Handlers call [`record`] after an admin action, a team or session
+//! membership change, a credential being issued, renewed or revoked, or a
+//! scheduled task edit. Events land in `audit_events`, which the database
+//! keeps append-only, and admins read them under `/api/admin/audit`.
+//!
+//! Recording never fails the request: the action has already happened by the
+//! time it is recorded, so a failed insert is logged instead.
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use serde_json::Value;
+use shared::api::{AuditAction, AuditEventInfo};
+use uuid::Uuid;
+
+use crate::models::{AuditEvent, NewAuditEvent};
+use crate::schema::{audit_events, users};
+
+/// Record that `actor_id` (or the server, when `None`) did `action` to
+/// `target_id`. `details` says what changed and should name the target, so
+/// the event stays readable after the target is deleted.
+pub fn record(
+    conn: &mut PgConnection,
+    actor_id: Option<Uuid>,
+    action: AuditAction,
+    target_id: Option<Uuid>,
+    details: Value,
+) {
+    let actor_email = actor_id.and_then(|id| {
+        users::table
+            .find(id)
+            .select(users::email)
+            .first::<String>(conn)
+            .ok()
+    });
+    let result = diesel::insert_into(audit_events::table)
+        .values(&NewAuditEvent {
+            actor_id,
+            actor_email,
+            action: action.as_str().to_string(),
+            target_type: action.target_type().to_string(),
+            target_id,
+            details,
+        })
+        .execute(conn);
+    if let Err(e) = result {
+        tracing::error!("Failed to record audit event {}: {}", action.as_str(), e);
+    }
+}
+
+/// Which events to list or export; unset fields match everything
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AuditFilter {
+    pub action: Option<AuditAction>,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    /// Part of the actor's email
+    pub actor: Option<String>,
+    /// First day included (UTC)
+    pub since: Option<NaiveDate>,
+    /// Last day included (UTC)
+    pub until: Option<NaiveDate>,
+}
+
+/// A pattern matching `text` anywhere, with `LIKE` wildcards in it escaped
+fn contains_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+fn day_start(day: NaiveDate) -> NaiveDateTime {
+    day.and_hms_opt(0, 0, 0).unwrap_or_default()
+}
+
+/// Events matching `filter`, newest first
+pub fn query(
+    conn: &mut PgConnection,
+    filter: &AuditFilter,
+    limit: i64,
+    offset: i64,
+) -> QueryResult<Vec<AuditEvent>> {
+    let mut query = audit_events::table.into_boxed();
+    if let Some(action) = filter.action {
+        query = query.filter(audit_events::action.eq(action.as_str()));
+    }
+    if let Some(target_type) = &filter.target_type {
+        query = query.filter(audit_events::target_type.eq(target_type.clone()));
+    }
+    if let Some(target_id) = filter.target_id {
+        query = query.filter(audit_events::target_id.eq(target_id));
+    }
+    if let Some(actor) = filter
+        .actor
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+    {
+        query = query.filter(audit_events::actor_email.ilike(contains_pattern(actor)));
+    }
+    if let Some(since) = filter.since {
+        query = query.filter(audit_events::created_at.ge(day_start(since)));
+    }
+    if let Some(until) = filter.until {
+        query = query.filter(audit_events::created_at.lt(day_start(until) + Duration::days(1)));
+    }
+    query
+        .order((audit_events::created_at.desc(), audit_events::id.desc()))
+        .limit(limit)
+        .offset(offset)
+        .select(AuditEvent::as_select())
+        .load(conn)
+}
+
+pub fn info(event: AuditEvent) -> AuditEventInfo {
+    AuditEventInfo {
+        id: event.id,
+        created_at: event.created_at.and_utc().to_rfc3339(),
+        actor_id: event.actor_id,
+        actor_email: event.actor_email,
+        action: event.action,
+        target_type: event.target_type,
+        target_id: event.target_id,
+        details: event.details,
+    }
+}
+
+/// Download name for an export taken at `now`
+pub fn export_file_name(now: NaiveDateTime) -> String {
+    format!("audit-events-{}.json", now.format("%Y-%m-%d"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actions_round_trip() {
+        for action in AuditAction::ALL {
+            assert_eq!(action.as_str().parse::<AuditAction>(), Ok(action));
+            assert!(action.as_str().len() <= 64);
+        }
+        assert!("user.delete".parse::<AuditAction>().is_err());
+    }
+
+    #[test]
+    fn test_contains_pattern_escapes_wildcards() {
+        assert_eq!(contains_pattern("alice"), "%alice%");
+        assert_eq!(contains_pattern("100%_a\\b"), "%100\\%\\_a\\\\b%");
+    }
+
+    #[test]
+    fn test_export_file_name() {
+        let now = NaiveDate::from_ymd_opt(2026, 3, 26)
+            .unwrap()
+            .and_hms_opt(15, 4, 5)
+            .unwrap();
+        assert_eq!(export_file_name(now), "audit-events-2026-03-26.json");
+    }
+}
@@ -7,9 +7,15 @@ use crate::AppState;
 
 /// Extract the authenticated user's ID from the session cookie.
 ///
-/// In dev mode, returns the test user's ID without checking cookies.
-/// In production, reads the signed session cookie and parses the user ID.
+/// Requests authenticated with a personal API key resolve to the key's owner
+/// (see `crate::api_keys`). Otherwise, in dev mode, returns the test user's
+/// ID without checking cookies. In production, reads the signed session
+/// cookie and resolves the browser login it names.
 pub fn extract_user_id(app_state: &AppState, cookies: &Cookies) -> Result<Uuid, AppError> {
+    if let Some(user_id) = crate::api_keys::current_user() {
+        return Ok(user_id);
+    }
+
     if app_state.dev_mode {
         let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
 
@@ -21,10 +27,24 @@ pub fn extract_user_id(app_state: &AppState, cookies: &Cookies) -> Result<Uuid,
             .map_err(|e| AppError::DbQuery(e.to_string()));
     }
 
+    current_login(app_state, cookies).map(|login| login.user_id)
+}
+
+/// The browser login the signed session cookie names, if it is still signed
+/// in (see `crate::web_logins`). Unlike [`extract_user_id`], this needs the
+/// cookie in dev mode too.
+pub fn current_login(
+    app_state: &AppState,
+    cookies: &Cookies,
+) -> Result<crate::models::WebLogin, AppError> {
     let cookie = cookies
         .signed(&app_state.cookie_key)
         .get(shared::protocol::SESSION_COOKIE_NAME)
         .ok_or(AppError::Unauthorized)?;
+    let login_id: Uuid = cookie.value().parse().map_err(|_| AppError::Unauthorized)?;
 
-    cookie.value().parse().map_err(|_| AppError::Unauthorized)
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    crate::web_logins::find_active(&mut conn, login_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .ok_or(AppError::Unauthorized)
 }
@@ -0,0 +1,409 @@
+//! Spend budgets
+//!
+//! Admins give a user, or a team as a whole, a daily or monthly budget in
+//! `spend_budgets`. Spend is attributed to the session owner: every `result`
+//! message adds the growth of the session's `total_cost_usd` to
+//! `spend_events`, and a budget's spend is the sum of its members' events
+//! since the period began (UTC midnight, or the first of the month). A user
+//! in several teams counts against each of them.
+//!
+//! Crossing `soft_limit_usd` notifies the members once per period. Reaching
+//! `hard_limit_usd` interrupts their sessions, and until an admin raises the
+//! limit or the period rolls over, input to their sessions and new launches
+//! are refused. A session can also carry its own `max_cost_usd`, chosen at
+//! launch, which only blocks that session. It is held against the session's
+//! summed spend events rather than `total_cost_usd`, which starts over when
+//! the proxy restarts or the session is resumed.
+
+use chrono::{Datelike, NaiveDateTime};
+use diesel::prelude::*;
+use shared::api::{BudgetPeriod, SpendBudgetInfo};
+use uuid::Uuid;
+
+use crate::models::{NewSpendEvent, Session, SpendBudget};
+use crate::schema::{sessions, spend_budgets, spend_events, team_members, teams, users};
+
+/// When the current `period` began.
+pub fn period_start(period: BudgetPeriod, now: NaiveDateTime) -> NaiveDateTime {
+    let date = match period {
+        BudgetPeriod::Daily => now.date(),
+        BudgetPeriod::Monthly => now.date().with_day(1).unwrap_or(now.date()),
+    };
+    date.and_hms_opt(0, 0, 0).unwrap_or(now)
+}
+
+/// What a turn added, given the session total before and after it. A proxy
+/// that restarted reports a total that starts over from zero.
+pub fn cost_delta(previous: f64, reported: f64) -> f64 {
+    if reported < previous {
+        reported
+    } else {
+        reported - previous
+    }
+}
+
+/// Whether an alert last sent at `notified_at` is due again.
+fn alert_due(notified_at: Option<NaiveDateTime>, period_start: NaiveDateTime) -> bool {
+    notified_at.is_none_or(|at| at < period_start)
+}
+
+fn parse_period(period: &str) -> BudgetPeriod {
+    period.parse().unwrap_or(BudgetPeriod::Daily)
+}
+
+/// A budget with what its members have spent in the current period
+pub struct BudgetUsage {
+    pub budget: SpendBudget,
+    pub period: BudgetPeriod,
+    /// The user's name or email, or the team name
+    pub target_name: String,
+    pub members: Vec<Uuid>,
+    pub spent_usd: f64,
+    pub period_start: NaiveDateTime,
+}
+
+impl BudgetUsage {
+    pub fn hard_limit_reached(&self) -> bool {
+        self.budget
+            .hard_limit_usd
+            .is_some_and(|limit| self.spent_usd >= limit)
+    }
+
+    fn soft_limit_reached(&self) -> bool {
+        self.budget
+            .soft_limit_usd
+            .is_some_and(|limit| self.spent_usd >= limit)
+    }
+
+    fn scope(&self) -> String {
+        if self.budget.team_id.is_some() {
+            format!(
+                "the {} budget of team \"{}\"",
+                self.period.as_str(),
+                self.target_name
+            )
+        } else {
+            format!(
+                "the {} budget of {}",
+                self.period.as_str(),
+                self.target_name
+            )
+        }
+    }
+
+    /// Why input and launches are refused. Amounts are left out, as the
+    /// reason is also shown in the session transcript.
+    pub fn block_reason(&self) -> String {
+        format!(
+            "Spend limit reached: {} is used up. New input and launches are blocked until an admin raises the limit or the period resets.",
+            self.scope()
+        )
+    }
+
+    pub fn info(&self) -> SpendBudgetInfo {
+        SpendBudgetInfo {
+            id: self.budget.id,
+            user_id: self.budget.user_id,
+            team_id: self.budget.team_id,
+            target_name: self.target_name.clone(),
+            period: self.period,
+            soft_limit_usd: self.budget.soft_limit_usd,
+            hard_limit_usd: self.budget.hard_limit_usd,
+            spent_usd: self.spent_usd,
+            blocked: self.hard_limit_reached(),
+            period_start: self.period_start.and_utc().to_rfc3339(),
+        }
+    }
+
+    /// Notification text for crossing the soft or hard limit.
+    pub fn alert_text(&self, hard: bool) -> String {
+        let (kind, limit) = if hard {
+            ("hard limit", self.budget.hard_limit_usd)
+        } else {
+            ("soft limit", self.budget.soft_limit_usd)
+        };
+        format!(
+            "${:.2} spent against {}, reaching its {} of ${:.2}.",
+            self.spent_usd,
+            self.scope(),
+            kind,
+            limit.unwrap_or_default()
+        )
+    }
+}
+
+/// Total spend of `user_ids` since `since`.
+pub fn spent_since(
+    conn: &mut PgConnection,
+    user_ids: &[Uuid],
+    since: NaiveDateTime,
+) -> QueryResult<f64> {
+    spend_events::table
+        .filter(spend_events::user_id.eq_any(user_ids))
+        .filter(spend_events::created_at.ge(since))
+        .select(diesel::dsl::sum(spend_events::cost_usd))
+        .first::<Option<f64>>(conn)
+        .map(|total| total.unwrap_or(0.0))
+}
+
+/// Work out a budget's members, display name and current spend.
+pub fn usage(
+    conn: &mut PgConnection,
+    budget: SpendBudget,
+    now: NaiveDateTime,
+) -> QueryResult<BudgetUsage> {
+    let (members, target_name) = match (budget.user_id, budget.team_id) {
+        (Some(user_id), _) => {
+            let (name, email): (Option<String>, String) = users::table
+                .find(user_id)
+                .select((users::name, users::email))
+                .first(conn)?;
+            (vec![user_id], name.unwrap_or(email))
+        }
+        (None, Some(team_id)) => {
+            let members = team_members::table
+                .filter(team_members::team_id.eq(team_id))
+                .select(team_members::user_id)
+                .load(conn)?;
+            let name = teams::table.find(team_id).select(teams::name).first(conn)?;
+            (members, name)
+        }
+        (None, None) => (Vec::new(), String::new()),
+    };
+    let period = parse_period(&budget.period);
+    let period_start = period_start(period, now);
+    let spent_usd = spent_since(conn, &members, period_start)?;
+    Ok(BudgetUsage {
+        budget,
+        period,
+        target_name,
+        members,
+        spent_usd,
+        period_start,
+    })
+}
+
+/// Every budget that applies to a user: their own and those of their teams.
+pub fn budgets_for_user(conn: &mut PgConnection, user_id: Uuid) -> QueryResult<Vec<SpendBudget>> {
+    let team_ids: Vec<Uuid> = team_members::table
+        .filter(team_members::user_id.eq(user_id))
+        .select(team_members::team_id)
+        .load(conn)?;
+    spend_budgets::table
+        .filter(
+            spend_budgets::user_id
+                .eq(user_id)
+                .or(spend_budgets::team_id.eq_any(team_ids)),
+        )
+        .order((spend_budgets::team_id.asc(), spend_budgets::period.asc()))
+        .load(conn)
+}
+
+/// Usage of every budget that applies to a user.
+pub fn usage_for_user(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    now: NaiveDateTime,
+) -> QueryResult<Vec<BudgetUsage>> {
+    budgets_for_user(conn, user_id)?
+        .into_iter()
+        .map(|budget| usage(conn, budget, now))
+        .collect()
+}
+
+/// Why the user may not start sessions or send input, if a hard limit of
+/// theirs is reached.
+pub fn launch_block(conn: &mut PgConnection, user_id: Uuid) -> QueryResult<Option<String>> {
+    let now = chrono::Utc::now().naive_utc();
+    Ok(usage_for_user(conn, user_id, now)?
+        .iter()
+        .find(|u| u.hard_limit_reached())
+        .map(BudgetUsage::block_reason))
+}
+
+/// What a session has cost over its whole life, across proxy restarts.
+/// Sessions that ran before spend was recorded fall back to their reported
+/// total.
+pub fn session_spend(conn: &mut PgConnection, session: &Session) -> QueryResult<f64> {
+    let recorded = spend_events::table
+        .filter(spend_events::session_id.eq(session.id))
+        .select(diesel::dsl::sum(spend_events::cost_usd))
+        .first::<Option<f64>>(conn)?;
+    Ok(lifetime_spend(recorded, session.total_cost_usd))
+}
+
+/// A session's spend from the sum of its recorded turns and the total its
+/// proxy last reported, which restarts from zero with the proxy.
+fn lifetime_spend(recorded: Option<f64>, reported_total: f64) -> f64 {
+    recorded.unwrap_or(0.0).max(reported_total)
+}
+
+/// Why a session that has spent `spent` is stopped by its cost cap, if it is.
+pub fn session_cap_reason(max_cost_usd: Option<f64>, spent: f64) -> Option<String> {
+    let cap = max_cost_usd?;
+    (spent >= cap).then(|| {
+        "Spend limit reached: this session hit the cost cap it was launched with. New input is blocked until an admin raises the cap.".to_string()
+    })
+}
+
+/// The cap reason when the turn that cost `delta` took the session's spend
+/// to `after_turn` across its cap, so a session is interrupted only once.
+pub fn cap_crossed_by_turn(
+    max_cost_usd: Option<f64>,
+    after_turn: f64,
+    delta: f64,
+) -> Option<String> {
+    if session_cap_reason(max_cost_usd, after_turn - delta).is_some() {
+        return None;
+    }
+    session_cap_reason(max_cost_usd, after_turn)
+}
+
+/// Why input to a session is refused: its own cost cap or a hard limit on
+/// its owner's budgets.
+pub fn session_block(conn: &mut PgConnection, session_id: Uuid) -> QueryResult<Option<String>> {
+    let Some(session) = sessions::table
+        .find(session_id)
+        .select(Session::as_select())
+        .first(conn)
+        .optional()?
+    else {
+        return Ok(None);
+    };
+    if session.max_cost_usd.is_some() {
+        let spent = session_spend(conn, &session)?;
+        if let Some(reason) = session_cap_reason(session.max_cost_usd, spent) {
+            return Ok(Some(reason));
+        }
+    }
+    launch_block(conn, session.user_id)
+}
+
+/// Record what a turn added to the owner's spend, given the session as it
+/// was before the turn's total was stored. Returns the amount recorded.
+pub fn record_spend(conn: &mut PgConnection, session: &Session, reported: f64) -> QueryResult<f64> {
+    let delta = cost_delta(session.total_cost_usd, reported);
+    if delta > 0.0 {
+        diesel::insert_into(spend_events::table)
+            .values(&NewSpendEvent {
+                session_id: Some(session.id),
+                user_id: session.user_id,
+                cost_usd: delta,
+            })
+            .execute(conn)?;
+    }
+    Ok(delta)
+}
+
+/// A budget whose soft or hard limit was newly crossed
+pub struct Crossing {
+    pub usage: BudgetUsage,
+    pub hard: bool,
+}
+
+/// Budgets of the user that crossed a limit and have not been alerted about
+/// it this period. Each is marked as alerted, so it is returned only once.
+pub fn take_crossings(conn: &mut PgConnection, user_id: Uuid) -> QueryResult<Vec<Crossing>> {
+    let now = chrono::Utc::now().naive_utc();
+    let mut crossings = Vec::new();
+    for usage in usage_for_user(conn, user_id, now)? {
+        let hard = if usage.hard_limit_reached()
+            && alert_due(usage.budget.hard_notified_at, usage.period_start)
+        {
+            true
+        } else if usage.soft_limit_reached()
+            && alert_due(usage.budget.soft_notified_at, usage.period_start)
+        {
+            false
+        } else {
+            continue;
+        };
+        let target = spend_budgets::table.find(usage.budget.id);
+        if hard {
+            // A hard alert covers the soft one
+            diesel::update(target)
+                .set((
+                    spend_budgets::hard_notified_at.eq(now),
+                    spend_budgets::soft_notified_at.eq(now),
+                ))
+                .execute(conn)?;
+        } else {
+            diesel::update(target)
+                .set(spend_budgets::soft_notified_at.eq(now))
+                .execute(conn)?;
+        }
+        crossings.push(Crossing { usage, hard });
+    }
+    Ok(crossings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_period_start() {
+        let now = at("2026-03-24 17:45:12");
+        assert_eq!(
+            period_start(BudgetPeriod::Daily, now),
+            at("2026-03-24 00:00:00")
+        );
+        assert_eq!(
+            period_start(BudgetPeriod::Monthly, now),
+            at("2026-03-01 00:00:00")
+        );
+    }
+
+    #[test]
+    fn test_cost_delta() {
+        assert!((cost_delta(1.25, 1.75) - 0.5).abs() < 1e-9);
+        assert_eq!(cost_delta(1.0, 1.0), 0.0);
+        // The proxy restarted and its total began again
+        assert_eq!(cost_delta(3.0, 0.4), 0.4);
+    }
+
+    #[test]
+    fn test_session_spend_survives_proxy_restart() {
+        // Turns recorded $2.50 in all; the restarted proxy reports only $0.50
+        let spent = lifetime_spend(Some(2.5), 0.5);
+        assert!((spent - 2.5).abs() < 1e-9);
+        assert!(session_cap_reason(Some(2.25), spent).is_some());
+        // Sessions from before spend was recorded use their reported total
+        assert!((lifetime_spend(None, 3.0) - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cap_crossed_only_by_the_turn_that_reaches_it() {
+        // A $0.50 turn after a restart takes the session from $2.00 to $2.50
+        assert!(cap_crossed_by_turn(Some(2.25), 2.5, 0.5).is_some());
+        assert!(cap_crossed_by_turn(Some(2.25), 2.0, 0.5).is_none());
+        // Already over the cap before the turn: interrupted earlier
+        assert!(cap_crossed_by_turn(Some(2.25), 3.0, 0.5).is_none());
+        assert!(cap_crossed_by_turn(None, 2.5, 0.5).is_none());
+    }
+
+    #[test]
+    fn test_validate_budget_limits() {
+        use shared::api::{validate_budget_limits, validate_cost_cap};
+        assert!(validate_budget_limits(Some(5.0), Some(10.0)).is_ok());
+        assert!(validate_budget_limits(None, Some(10.0)).is_ok());
+        assert!(validate_budget_limits(None, None).is_err());
+        assert!(validate_budget_limits(Some(10.0), Some(10.0)).is_err());
+        assert!(validate_budget_limits(Some(-1.0), None).is_err());
+        assert!(validate_budget_limits(Some(f64::NAN), None).is_err());
+        assert!(validate_cost_cap(None).is_ok());
+        assert!(validate_cost_cap(Some(0.0)).is_err());
+    }
+
+    #[test]
+    fn test_alert_due_once_per_period() {
+        let start = at("2026-03-24 00:00:00");
+        assert!(alert_due(None, start));
+        assert!(alert_due(Some(at("2026-03-23 22:00:00")), start));
+        assert!(!alert_due(Some(at("2026-03-24 09:00:00")), start));
+    }
+}
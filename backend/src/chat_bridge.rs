@@ -0,0 +1,477 @@
+//! Chat bridge
+//!
+//! Posts permission requests and turn results to a team chat channel through
+//! the channel's incoming webhook (`{"text": ...}`, understood by Slack,
+//! Mattermost and Rocket.Chat), and interprets commands sent back from the
+//! channel through an outgoing webhook or slash command:
+//!
+//! - `approve <ref>` / `deny <ref> [reason]` answer a pending permission
+//!   request, where `<ref>` is the short reference shown in the post
+//! - `send <session> <text>` sends input to a session, addressed by the
+//!   short session reference or its name
+//!
+//! Inbound requests must be signed with the bridge's signing secret, either
+//! Slack-style (`X-Slack-Signature: v0=<hex>` over `"v0:<ts>:<body>"`) or
+//! with the same scheme as outbound webhooks (`X-Portal-Signature:
+//! sha256=<hex>` over `"<ts>.<body>"`). Chat users are mapped to portal
+//! accounts by email.
+
+use axum::http::HeaderMap;
+use diesel::prelude::*;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::Duration;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::models::{ChatBridge, Session};
+use crate::schema::chat_bridges;
+use crate::webhooks::AllowedDestinations;
+
+/// Time allowed for the chat tool to accept a post
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Inbound requests signed further than this from now are rejected (replays)
+const MAX_CLOCK_SKEW_SECS: i64 = 300;
+/// Hex characters of a row ID shown as its short reference
+const PERMISSION_REF_LEN: usize = 4;
+const SESSION_REF_LEN: usize = 8;
+/// Longest turn result quoted in a post
+const MAX_RESULT_CHARS: usize = 600;
+/// Longest tool input summary quoted in a post
+const MAX_INPUT_CHARS: usize = 300;
+
+pub const SLACK_SIGNATURE_HEADER: &str = "X-Slack-Signature";
+pub const SLACK_TIMESTAMP_HEADER: &str = "X-Slack-Request-Timestamp";
+
+/// A command typed into the chat channel
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatCommand {
+    Approve {
+        reference: String,
+    },
+    Deny {
+        reference: String,
+        reason: Option<String>,
+    },
+    Send {
+        session: String,
+        text: String,
+    },
+    Help,
+}
+
+pub const USAGE: &str = "Commands: `approve <ref>`, `deny <ref> [reason]`, `send <session> <text>`";
+
+/// Parse a chat message into a command. Errors carry a usage hint.
+pub fn parse_command(text: &str) -> Result<ChatCommand, &'static str> {
+    let text = text.trim();
+    let (verb, rest) = split_word(text);
+    match verb.to_ascii_lowercase().as_str() {
+        "approve" => {
+            let (reference, _) = split_word(rest);
+            Ok(ChatCommand::Approve {
+                reference: parse_ref(reference).ok_or("Usage: `approve <ref>`")?,
+            })
+        }
+        "deny" => {
+            let (reference, reason) = split_word(rest);
+            Ok(ChatCommand::Deny {
+                reference: parse_ref(reference).ok_or("Usage: `deny <ref> [reason]`")?,
+                reason: (!reason.is_empty()).then(|| reason.to_string()),
+            })
+        }
+        "send" => {
+            let (session, text) = split_word(rest);
+            if session.is_empty() || text.is_empty() {
+                return Err("Usage: `send <session> <text>`");
+            }
+            Ok(ChatCommand::Send {
+                session: session.to_string(),
+                text: text.to_string(),
+            })
+        }
+        "help" | "" => Ok(ChatCommand::Help),
+        _ => Err(USAGE),
+    }
+}
+
+/// Split off the first whitespace-separated word; the rest is trimmed.
+fn split_word(text: &str) -> (&str, &str) {
+    match text.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim()),
+        None => (text, ""),
+    }
+}
+
+/// A reference is a prefix of a row ID's hex digits, at least as long as the
+/// ones the bridge posts.
+fn parse_ref(reference: &str) -> Option<String> {
+    let reference = reference.trim_matches('`').to_ascii_lowercase();
+    (reference.len() >= PERMISSION_REF_LEN && reference.chars().all(|c| c.is_ascii_hexdigit()))
+        .then_some(reference)
+}
+
+/// Whether `reference` (as returned by `parse_command`) names this row ID.
+pub fn matches_ref(id: Uuid, reference: &str) -> bool {
+    id.simple().to_string().starts_with(reference)
+}
+
+/// Whether `reference` names this session, by ID prefix or exact name.
+pub fn matches_session(session: &Session, reference: &str) -> bool {
+    parse_ref(reference).is_some_and(|r| matches_ref(session.id, &r))
+        || session.session_name.eq_ignore_ascii_case(reference)
+}
+
+pub fn permission_ref(id: Uuid) -> String {
+    id.simple().to_string()[..PERMISSION_REF_LEN].to_string()
+}
+
+pub fn session_ref(id: Uuid) -> String {
+    id.simple().to_string()[..SESSION_REF_LEN].to_string()
+}
+
+/// Check an inbound request's signature and timestamp against the secret.
+pub fn verify_signature(
+    secret: &str,
+    headers: &HeaderMap,
+    body: &[u8],
+    now: i64,
+) -> Result<(), &'static str> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    let (timestamp, signature, prefix, separator) =
+        if let Some(signature) = header(SLACK_SIGNATURE_HEADER) {
+            (
+                header(SLACK_TIMESTAMP_HEADER),
+                signature.strip_prefix("v0="),
+                "v0:",
+                b":",
+            )
+        } else if let Some(signature) = header(crate::webhooks::SIGNATURE_HEADER) {
+            (
+                header(crate::webhooks::TIMESTAMP_HEADER),
+                signature.strip_prefix("sha256="),
+                "",
+                b".",
+            )
+        } else {
+            return Err("Missing signature header");
+        };
+
+    let timestamp = timestamp.ok_or("Missing timestamp header")?;
+    let signed_at: i64 = timestamp.parse().map_err(|_| "Invalid timestamp")?;
+    if (now - signed_at).abs() > MAX_CLOCK_SKEW_SECS {
+        return Err("Request timestamp is too old");
+    }
+    let signature = signature
+        .and_then(|s| hex::decode(s).ok())
+        .ok_or("Malformed signature")?;
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(prefix.as_bytes());
+    mac.update(timestamp.as_bytes());
+    mac.update(separator);
+    mac.update(body);
+    mac.verify_slice(&signature)
+        .map_err(|_| "Signature does not match")
+}
+
+/// Truncate to `max` characters, marking the cut.
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+/// One-line description of what a tool wants to do.
+fn summarize_input(input: &serde_json::Value) -> String {
+    let summary = ["command", "file_path", "url", "pattern", "path"]
+        .iter()
+        .find_map(|key| input.get(key).and_then(|v| v.as_str()))
+        .map(str::to_string)
+        .unwrap_or_else(|| input.to_string());
+    truncate(&summary.replace('\n', " "), MAX_INPUT_CHARS)
+}
+
+pub fn permission_text(
+    session: &Session,
+    pending_id: Uuid,
+    tool_name: &str,
+    input: &serde_json::Value,
+) -> String {
+    let reference = permission_ref(pending_id);
+    format!(
+        "*{}* wants to use *{}*: `{}`\nReply `approve {}` or `deny {} <reason>`",
+        session.session_name,
+        tool_name,
+        summarize_input(input),
+        reference,
+        reference
+    )
+}
+
+pub fn turn_result_text(session: &Session, content: &serde_json::Value) -> String {
+    let result = content
+        .get("result")
+        .and_then(|r| r.as_str())
+        .map(|r| truncate(r, MAX_RESULT_CHARS))
+        .unwrap_or_default();
+    let headline = if content.get("is_error").and_then(|e| e.as_bool()) == Some(true) {
+        let subtype = content
+            .get("subtype")
+            .and_then(|s| s.as_str())
+            .unwrap_or("error");
+        format!(
+            "*{}* finished a turn with an error ({})",
+            session.session_name, subtype
+        )
+    } else {
+        let cost = content
+            .get("total_cost_usd")
+            .and_then(|c| c.as_f64())
+            .unwrap_or(0.0);
+        format!("*{}* finished a turn (${:.2})", session.session_name, cost)
+    };
+    format!(
+        "{}\n{}\nReply `send {} <text>` to continue.",
+        headline,
+        result,
+        session_ref(session.id)
+    )
+}
+
+/// Posts session events to users' chat bridges
+#[derive(Clone)]
+pub struct ChatBridgePoster {
+    db_pool: DbPool,
+    client: reqwest::Client,
+    allowed: AllowedDestinations,
+}
+
+impl ChatBridgePoster {
+    pub fn new(db_pool: DbPool, allowed: AllowedDestinations) -> Self {
+        let client = crate::webhooks::outbound_client(
+            concat!("agent-portal-chat-bridge/", env!("CARGO_PKG_VERSION")),
+            REQUEST_TIMEOUT,
+            allowed.clone(),
+        );
+        Self {
+            db_pool,
+            client,
+            allowed,
+        }
+    }
+
+    /// Post a new permission request to the session owner's bridges.
+    pub fn post_permission_request(
+        &self,
+        session_id: Uuid,
+        pending_id: Uuid,
+        tool_name: &str,
+        input: &serde_json::Value,
+    ) {
+        use crate::schema::sessions;
+        let Ok(mut conn) = self.db_pool.get() else {
+            return;
+        };
+        let Ok(session) = sessions::table.find(session_id).first::<Session>(&mut conn) else {
+            return;
+        };
+        let urls = self.bridge_urls(&mut conn, session.user_id, |b| b.post_permissions);
+        if !urls.is_empty() {
+            let text = permission_text(&session, pending_id, tool_name, input);
+            self.post_in_background(urls, text);
+        }
+    }
+
+    /// Post a finished turn to the session owner's bridges.
+    pub fn post_turn_result(&self, session: &Session, content: &serde_json::Value) {
+        let Ok(mut conn) = self.db_pool.get() else {
+            return;
+        };
+        let urls = self.bridge_urls(&mut conn, session.user_id, |b| b.post_results);
+        if !urls.is_empty() {
+            self.post_in_background(urls, turn_result_text(session, content));
+        }
+    }
+
+    /// Incoming webhook URLs of a user's enabled bridges that want a post.
+    fn bridge_urls(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        wants: fn(&ChatBridge) -> bool,
+    ) -> Vec<String> {
+        let bridges: Vec<ChatBridge> = chat_bridges::table
+            .filter(chat_bridges::user_id.eq(user_id))
+            .filter(chat_bridges::enabled.eq(true))
+            .load(conn)
+            .unwrap_or_else(|e| {
+                error!("Failed to load chat bridges: {}", e);
+                Vec::new()
+            });
+        bridges
+            .into_iter()
+            .filter(wants)
+            .map(|b| b.incoming_webhook_url)
+            .collect()
+    }
+
+    fn post_in_background(&self, urls: Vec<String>, text: String) {
+        let poster = self.clone();
+        tokio::spawn(async move {
+            for url in urls {
+                if let Err(e) = poster.post(&url, &text).await {
+                    warn!("Chat bridge post failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Post a message to an incoming webhook.
+    pub async fn post(&self, url: &str, text: &str) -> Result<(), String> {
+        crate::webhooks::validate_url(url, &self.allowed)?;
+        let response = self
+            .client
+            .post(url)
+            .json(&serde_json::json!({ "text": text }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("Chat tool answered HTTP {}", response.status()))
+        }
+    }
+}
+
+/// Look up the bridge a signed inbound request is addressed to.
+pub fn find_enabled(conn: &mut PgConnection, bridge_id: Uuid) -> Option<ChatBridge> {
+    chat_bridges::table
+        .filter(chat_bridges::id.eq(bridge_id))
+        .filter(chat_bridges::enabled.eq(true))
+        .first(conn)
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slack_headers(secret: &str, timestamp: i64, body: &str) -> HeaderMap {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("v0:{}:{}", timestamp, body).as_bytes());
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            SLACK_SIGNATURE_HEADER,
+            format!("v0={}", hex::encode(mac.finalize().into_bytes()))
+                .parse()
+                .unwrap(),
+        );
+        headers.insert(
+            SLACK_TIMESTAMP_HEADER,
+            timestamp.to_string().parse().unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(
+            parse_command("approve 3F2A"),
+            Ok(ChatCommand::Approve {
+                reference: "3f2a".to_string()
+            })
+        );
+        assert_eq!(
+            parse_command("  deny 3f2a   not on prod  "),
+            Ok(ChatCommand::Deny {
+                reference: "3f2a".to_string(),
+                reason: Some("not on prod".to_string())
+            })
+        );
+        assert_eq!(
+            parse_command("deny `3f2a`"),
+            Ok(ChatCommand::Deny {
+                reference: "3f2a".to_string(),
+                reason: None
+            })
+        );
+        assert_eq!(
+            parse_command("send api-server run the tests\nplease"),
+            Ok(ChatCommand::Send {
+                session: "api-server".to_string(),
+                text: "run the tests\nplease".to_string()
+            })
+        );
+        assert_eq!(parse_command(""), Ok(ChatCommand::Help));
+        assert!(parse_command("approve").is_err());
+        assert!(parse_command("approve xyz1").is_err());
+        assert!(parse_command("approve 3f").is_err());
+        assert!(parse_command("send api-server").is_err());
+        assert_eq!(parse_command("restart everything"), Err(USAGE));
+    }
+
+    #[test]
+    fn test_refs() {
+        let id = Uuid::parse_str("3f2a9c1e-0000-4000-8000-000000000000").unwrap();
+        assert_eq!(permission_ref(id), "3f2a");
+        assert_eq!(session_ref(id), "3f2a9c1e");
+        assert!(matches_ref(id, "3f2a"));
+        assert!(matches_ref(id, "3f2a9c"));
+        assert!(!matches_ref(id, "3f2b"));
+    }
+
+    #[test]
+    fn test_verify_slack_signature() {
+        let body = "text=approve+3f2a&user_id=U123";
+        let headers = slack_headers("secret", 1_700_000_000, body);
+        assert_eq!(
+            verify_signature("secret", &headers, body.as_bytes(), 1_700_000_060),
+            Ok(())
+        );
+        assert!(verify_signature("other", &headers, body.as_bytes(), 1_700_000_060).is_err());
+        assert!(verify_signature("secret", &headers, b"tampered", 1_700_000_060).is_err());
+        assert_eq!(
+            verify_signature("secret", &headers, body.as_bytes(), 1_700_001_000),
+            Err("Request timestamp is too old")
+        );
+        assert!(verify_signature("secret", &HeaderMap::new(), body.as_bytes(), 0).is_err());
+    }
+
+    #[test]
+    fn test_verify_portal_signature() {
+        let body = r#"{"text":"approve 3f2a","user_email":"a@example.com"}"#;
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            crate::webhooks::SIGNATURE_HEADER,
+            crate::webhooks::sign("secret", 1_700_000_000, body)
+                .parse()
+                .unwrap(),
+        );
+        headers.insert(
+            crate::webhooks::TIMESTAMP_HEADER,
+            "1700000000".parse().unwrap(),
+        );
+        assert_eq!(
+            verify_signature("secret", &headers, body.as_bytes(), 1_700_000_000),
+            Ok(())
+        );
+        assert!(verify_signature("wrong", &headers, body.as_bytes(), 1_700_000_000).is_err());
+    }
+
+    #[test]
+    fn test_summarize_input() {
+        assert_eq!(
+            summarize_input(&serde_json::json!({"command": "cargo test\n--all"})),
+            "cargo test --all"
+        );
+        assert_eq!(summarize_input(&serde_json::json!({"x": 1})), r#"{"x":1}"#);
+        assert_eq!(truncate("héllo", 2), "hé…");
+        assert_eq!(truncate("hi", 2), "hi");
+    }
+}
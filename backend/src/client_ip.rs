@@ -0,0 +1,166 @@
+//! Client addresses behind reverse proxies
+//!
+//! The address a request came from, as used for rate limits, login records
+//! and share link views. `X-Forwarded-For` and `X-Real-IP` are only believed
+//! from the proxies listed in `TRUSTED_PROXIES`.
+
+use axum::extract::ConnectInfo;
+use axum::http::{header, HeaderMap, Request};
+use ipnet::IpNet;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use tower_governor::key_extractor::KeyExtractor;
+use tower_governor::GovernorError;
+
+/// Longest user agent kept with a login or view
+const MAX_USER_AGENT_LEN: usize = 512;
+
+/// Reverse proxies whose `X-Forwarded-For` and `X-Real-IP` headers are
+/// believed, from `TRUSTED_PROXIES`. From anyone else those headers are
+/// ignored, since a client can put any address in them.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Arc<Vec<IpNet>>);
+
+impl TrustedProxies {
+    /// Comma-separated addresses or CIDR ranges
+    pub fn parse(list: &str) -> Result<Self, String> {
+        list.split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                item.parse::<IpNet>()
+                    .or_else(|_| item.parse::<IpAddr>().map(IpNet::from))
+                    .map_err(|_| format!("{:?} is not an IP address or CIDR range", item))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(|nets| Self(Arc::new(nets)))
+    }
+
+    pub fn from_env() -> anyhow::Result<Self> {
+        Self::parse(&std::env::var("TRUSTED_PROXIES").unwrap_or_default())
+            .map_err(|e| anyhow::anyhow!("TRUSTED_PROXIES: {}", e))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.0.iter().any(|net| net.contains(&ip))
+    }
+}
+
+/// Client address. Behind a trusted proxy it is the last `X-Forwarded-For`
+/// hop that isn't another trusted proxy (or `X-Real-IP`); otherwise it is
+/// the connecting peer.
+pub fn client_ip(
+    headers: &HeaderMap,
+    peer: Option<SocketAddr>,
+    trusted: &TrustedProxies,
+) -> Option<String> {
+    let peer = peer.map(|addr| addr.ip().to_canonical());
+    let forwarded = || {
+        let header_value = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        // Proxies append to the list, so only its tail is theirs; the first
+        // hop that isn't a trusted proxy is the client
+        let from_list = header_value("x-forwarded-for").and_then(|list| {
+            let hops: Vec<IpAddr> = list
+                .rsplit(',')
+                .map_while(|hop| hop.trim().parse::<IpAddr>().ok())
+                .collect();
+            hops.iter()
+                .find(|ip| !trusted.contains(**ip))
+                .or(hops.last())
+                .map(|ip| ip.to_canonical())
+        });
+        from_list.or_else(|| header_value("x-real-ip").and_then(|ip| ip.parse().ok()))
+    };
+    match peer {
+        Some(ip) if trusted.contains(ip) => forwarded().or(peer),
+        _ => peer,
+    }
+    .map(|ip| ip.to_string())
+}
+
+/// Rate limit key: the client address as [`client_ip`] works it out
+#[derive(Debug, Clone)]
+pub struct ClientIpKeyExtractor(pub TrustedProxies);
+
+impl KeyExtractor for ClientIpKeyExtractor {
+    type Key = String;
+
+    fn extract<T>(&self, req: &Request<T>) -> Result<Self::Key, GovernorError> {
+        let peer = req
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| *addr);
+        client_ip(req.headers(), peer, &self.0).ok_or(GovernorError::UnableToExtractKey)
+    }
+}
+
+pub fn user_agent(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|ua| ua.chars().take(MAX_USER_AGENT_LEN).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_ip_only_believes_trusted_proxies() {
+        let trusted = TrustedProxies::parse("10.0.0.0/8, 192.0.2.1").unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-real-ip", "198.51.100.4".parse().unwrap());
+        headers.insert(
+            "x-forwarded-for",
+            "1.2.3.4, 203.0.113.9, 10.0.0.2".parse().unwrap(),
+        );
+        let ip = |headers: &HeaderMap, peer: &str, trusted: &TrustedProxies| {
+            client_ip(headers, Some(peer.parse().unwrap()), trusted)
+        };
+
+        // A client connecting directly can't pick its address
+        assert_eq!(
+            ip(&headers, "203.0.113.50:5000", &trusted).as_deref(),
+            Some("203.0.113.50")
+        );
+        assert_eq!(
+            ip(&headers, "10.0.0.1:5000", &TrustedProxies::default()).as_deref(),
+            Some("10.0.0.1")
+        );
+        // Behind the proxy, the nearest untrusted hop; the client's own
+        // "1.2.3.4" is ignored
+        assert_eq!(
+            ip(&headers, "10.0.0.1:5000", &trusted).as_deref(),
+            Some("203.0.113.9")
+        );
+        assert_eq!(
+            ip(&headers, "[::ffff:192.0.2.1]:5000", &trusted).as_deref(),
+            Some("203.0.113.9")
+        );
+
+        headers.remove("x-forwarded-for");
+        assert_eq!(
+            ip(&headers, "10.0.0.1:5000", &trusted).as_deref(),
+            Some("198.51.100.4")
+        );
+        headers.remove("x-real-ip");
+        assert_eq!(
+            ip(&headers, "10.0.0.1:5000", &trusted).as_deref(),
+            Some("10.0.0.1")
+        );
+
+        assert!(TrustedProxies::parse("").unwrap().is_empty());
+        assert!(TrustedProxies::parse("10.0.0.0/8,nonsense").is_err());
+    }
+}
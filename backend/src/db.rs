@@ -17,6 +17,10 @@ pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 pub fn create_pool() -> Result<DbPool> {
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
 
+    // Time every query on pooled connections for /metrics
+    diesel::connection::set_default_instrumentation(crate::metrics::query_instrumentation)
+        .expect("Failed to install query instrumentation");
+
     let manager = ConnectionManager::<PgConnection>::new(database_url);
     let pool = r2d2::Pool::builder()
         .build(manager)
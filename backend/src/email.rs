@@ -0,0 +1,693 @@
+//! Email notifications
+//!
+//! Mail is sent through an SMTP relay configured with `SMTP_*` environment
+//! variables; without `SMTP_HOST` the mailer is a no-op. Each user chooses in
+//! `email_notification_settings` which events mail them: a permission request
+//! waiting longer than N minutes, a turn that ends in an error, a failed
+//! scheduled run, and an optional daily digest. Spend budget alerts go to
+//! every member of the budget.
+
+use chrono::{NaiveDateTime, Timelike};
+use diesel::prelude::*;
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::models::{EmailNotificationSettings, ScheduledTask, Session};
+use crate::schema::{email_notification_settings, spend_events, users};
+
+/// Give up on an SMTP exchange after this long
+const SMTP_TIMEOUT: Duration = Duration::from_secs(15);
+/// Sessions listed individually in the digest
+const DIGEST_SESSION_LIMIT: usize = 10;
+
+/// How the connection to the relay is secured
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SmtpTls {
+    /// Plain connection upgraded with STARTTLS (port 587)
+    StartTls,
+    /// TLS from the first byte (port 465)
+    Implicit,
+    /// No encryption; only for local relays and test sinks
+    None,
+}
+
+/// SMTP relay settings
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub tls: SmtpTls,
+    pub credentials: Option<(String, String)>,
+    pub from: Mailbox,
+}
+
+impl SmtpConfig {
+    /// Read the relay settings from the environment. Returns `Ok(None)` when
+    /// `SMTP_HOST` is unset.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let Ok(host) = env::var("SMTP_HOST") else {
+            return Ok(None);
+        };
+        let tls = match env::var("SMTP_TLS").as_deref() {
+            Ok("tls") => SmtpTls::Implicit,
+            Ok("none") => SmtpTls::None,
+            Ok("starttls") | Err(_) => SmtpTls::StartTls,
+            Ok(other) => anyhow::bail!("SMTP_TLS must be starttls, tls or none (got {})", other),
+        };
+        let port = match env::var("SMTP_PORT") {
+            Ok(port) => port.parse()?,
+            Err(_) => match tls {
+                SmtpTls::StartTls => 587,
+                SmtpTls::Implicit => 465,
+                SmtpTls::None => 25,
+            },
+        };
+        let credentials = match (env::var("SMTP_USERNAME"), env::var("SMTP_PASSWORD")) {
+            (Ok(user), Ok(password)) => Some((user, password)),
+            _ => None,
+        };
+        let from = env::var("SMTP_FROM")
+            .unwrap_or_else(|_| format!("Agent Portal <portal@{}>", host))
+            .parse()?;
+        Ok(Some(Self {
+            host,
+            port,
+            tls,
+            credentials,
+            from,
+        }))
+    }
+}
+
+/// Thin wrapper around the SMTP transport
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(config: SmtpConfig) -> anyhow::Result<Self> {
+        let builder = match config.tls {
+            SmtpTls::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?
+            }
+            SmtpTls::Implicit => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?,
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+        };
+        let mut builder = builder.port(config.port).timeout(Some(SMTP_TIMEOUT));
+        if let Some((user, password)) = config.credentials {
+            builder = builder.credentials(Credentials::new(user, password));
+        }
+        Ok(Self {
+            transport: builder.build(),
+            from: config.from,
+        })
+    }
+
+    /// Send a plain-text mail.
+    pub async fn send(&self, to: &str, subject: &str, body: String) -> Result<(), String> {
+        let to: Mailbox = to
+            .parse()
+            .map_err(|e| format!("Invalid recipient: {}", e))?;
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body)
+            .map_err(|e| e.to_string())?;
+        self.transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Decides who gets mail for which event and sends it
+#[derive(Clone)]
+pub struct Mailer {
+    smtp: Option<Arc<SmtpMailer>>,
+    db_pool: DbPool,
+    public_url: String,
+}
+
+impl Mailer {
+    pub fn new(smtp: Option<SmtpMailer>, db_pool: DbPool, public_url: String) -> Self {
+        Self {
+            smtp: smtp.map(Arc::new),
+            db_pool,
+            public_url,
+        }
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.smtp.is_some()
+    }
+
+    /// Send a mail now and report the outcome.
+    pub async fn send(&self, to: &str, subject: &str, body: String) -> Result<(), String> {
+        match &self.smtp {
+            Some(smtp) => smtp.send(to, subject, body).await,
+            None => Err("SMTP is not configured on this server".to_string()),
+        }
+    }
+
+    /// Send a mail without waiting for the relay; failures are logged.
+    fn send_in_background(&self, to: String, subject: String, body: String) {
+        let Some(smtp) = self.smtp.clone() else {
+            return;
+        };
+        tokio::spawn(async move {
+            match smtp.send(&to, &subject, body).await {
+                Ok(()) => info!("Sent \"{}\" mail to {}", subject, to),
+                Err(e) => warn!("Failed to send \"{}\" mail to {}: {}", subject, to, e),
+            }
+        });
+    }
+
+    /// Load a user's settings and address if they want mail for `wants`.
+    fn recipient(
+        &self,
+        user_id: Uuid,
+        wants: impl Fn(&EmailNotificationSettings) -> bool,
+    ) -> Option<String> {
+        self.smtp.as_ref()?;
+        let mut conn = self.db_pool.get().ok()?;
+        let (settings, email): (EmailNotificationSettings, String) =
+            email_notification_settings::table
+                .inner_join(users::table)
+                .filter(email_notification_settings::user_id.eq(user_id))
+                .select((EmailNotificationSettings::as_select(), users::email))
+                .first(&mut conn)
+                .ok()?;
+        wants(&settings).then_some(email)
+    }
+
+    /// Mail the owner when a turn ends with `is_error`.
+    pub fn notify_session_error(&self, session: &Session, content: &serde_json::Value) {
+        let Some(to) = self.recipient(session.user_id, |s| s.session_errors) else {
+            return;
+        };
+        let subtype = content
+            .get("subtype")
+            .and_then(|s| s.as_str())
+            .unwrap_or("error");
+        let detail = content
+            .get("result")
+            .and_then(|r| r.as_str())
+            .unwrap_or("(no details)");
+        let subject = format!("Session {} ended with an error", session.session_name);
+        let body = format!(
+            "The agent in session \"{}\" ({}) finished its turn with an error ({}).\n\n{}\n\nOpen the portal: {}/dashboard\n",
+            session.session_name, session.working_directory, subtype, detail, self.public_url
+        );
+        self.send_in_background(to, subject, body);
+    }
+
+    /// Mail the members of a budget that crossed its soft or hard limit.
+    /// Budget alerts go out whatever the per-user settings say.
+    pub fn notify_spend_alert(&self, user_ids: &[Uuid], message: &str, hard: bool) {
+        if self.smtp.is_none() {
+            return;
+        }
+        let Ok(mut conn) = self.db_pool.get() else {
+            return;
+        };
+        let emails: Vec<String> = match users::table
+            .filter(users::id.eq_any(user_ids))
+            .filter(users::disabled.eq(false))
+            .select(users::email)
+            .load(&mut conn)
+        {
+            Ok(emails) => emails,
+            Err(e) => {
+                error!("Failed to load budget alert recipients: {}", e);
+                return;
+            }
+        };
+        let (subject, consequence) = if hard {
+            (
+                "Spend limit reached",
+                "Running sessions were interrupted, and new input and launches are blocked until an admin raises the limit or the period resets.",
+            )
+        } else {
+            (
+                "Spend budget alert",
+                "Nothing is blocked yet; the hard limit, if there is one, will stop sessions.",
+            )
+        };
+        for to in emails {
+            let body = format!(
+                "{}\n\n{}\n\nOpen the portal: {}/dashboard\n",
+                message, consequence, self.public_url
+            );
+            self.send_in_background(to, subject.to_string(), body);
+        }
+    }
+
+    /// Mail the owner when a scheduled run exits unsuccessfully.
+    pub fn notify_scheduled_failure(&self, task: &ScheduledTask, exit_code: Option<i32>) {
+        let Some(to) = self.recipient(task.user_id, |s| s.scheduled_failures) else {
+            return;
+        };
+        let subject = format!("Scheduled task {} failed", task.name);
+        let body = format!(
+            "The scheduled task \"{}\" on {} {}.\n\nOpen the portal: {}/dashboard\n",
+            task.name,
+            task.hostname,
+            describe_exit(exit_code),
+            self.public_url
+        );
+        self.send_in_background(to, subject, body);
+    }
+
+    /// Mail owners about permission requests that have waited longer than
+    /// their configured threshold. Each request is mailed about once.
+    pub async fn notify_waiting_permissions(&self) {
+        if self.smtp.is_none() {
+            return;
+        }
+        let mails = match self.db_pool.get() {
+            Ok(mut conn) => self.collect_waiting_permissions(&mut conn),
+            Err(e) => {
+                error!("Failed to get database connection for email: {}", e);
+                return;
+            }
+        };
+        for (to, subject, body) in mails {
+            if let Err(e) = self.send(&to, &subject, body).await {
+                warn!("Failed to send permission mail to {}: {}", to, e);
+            }
+        }
+    }
+
+    fn collect_waiting_permissions(
+        &self,
+        conn: &mut PgConnection,
+    ) -> Vec<(String, String, String)> {
+        use crate::models::PendingPermissionRequest;
+        use crate::schema::{pending_permission_requests, sessions};
+
+        let thresholds: HashMap<Uuid, (i32, String)> = email_notification_settings::table
+            .inner_join(users::table)
+            .filter(email_notification_settings::permission_wait_minutes.is_not_null())
+            .select((
+                email_notification_settings::user_id,
+                email_notification_settings::permission_wait_minutes.assume_not_null(),
+                users::email,
+            ))
+            .load::<(Uuid, i32, String)>(conn)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(user_id, minutes, email)| (user_id, (minutes, email)))
+            .collect();
+        if thresholds.is_empty() {
+            return vec![];
+        }
+
+        let owners: Vec<Uuid> = thresholds.keys().copied().collect();
+        let waiting: Vec<(PendingPermissionRequest, Session)> =
+            match pending_permission_requests::table
+                .inner_join(sessions::table)
+                .filter(pending_permission_requests::emailed_at.is_null())
+                .filter(sessions::user_id.eq_any(&owners))
+                .select((PendingPermissionRequest::as_select(), Session::as_select()))
+                .load(conn)
+            {
+                Ok(rows) => rows,
+                Err(e) => {
+                    error!("Failed to load waiting permission requests: {}", e);
+                    return vec![];
+                }
+            };
+
+        let now = chrono::Utc::now().naive_utc();
+        let mut mails = Vec::new();
+        for (request, session) in waiting {
+            let Some((minutes, email)) = thresholds.get(&session.user_id) else {
+                continue;
+            };
+            let waited = now - request.created_at;
+            if waited.num_minutes() < i64::from(*minutes) {
+                continue;
+            }
+            // Claim the request so a concurrent sweep cannot mail it twice
+            let claimed = diesel::update(
+                pending_permission_requests::table
+                    .filter(pending_permission_requests::id.eq(request.id))
+                    .filter(pending_permission_requests::emailed_at.is_null()),
+            )
+            .set(pending_permission_requests::emailed_at.eq(diesel::dsl::now))
+            .execute(conn);
+            if !matches!(claimed, Ok(1)) {
+                continue;
+            }
+            mails.push((
+                email.clone(),
+                format!("{} is waiting for permission", session.session_name),
+                format!(
+                    "Session \"{}\" ({}) has been waiting {} minutes for permission to use {}.\n\nAnswer it in the portal: {}/dashboard\n",
+                    session.session_name,
+                    session.working_directory,
+                    waited.num_minutes(),
+                    request.tool_name,
+                    self.public_url
+                ),
+            ));
+        }
+        mails
+    }
+
+    /// Send the daily digest to every user whose digest hour has passed today.
+    pub async fn send_due_digests(&self) {
+        if self.smtp.is_none() {
+            return;
+        }
+        let now = chrono::Utc::now().naive_utc();
+        let digests = match self.db_pool.get() {
+            Ok(mut conn) => self.collect_digests(&mut conn, now),
+            Err(e) => {
+                error!("Failed to get database connection for digests: {}", e);
+                return;
+            }
+        };
+        for (to, body) in digests {
+            match self.send(&to, "Your daily portal digest", body).await {
+                Ok(()) => info!("Sent daily digest to {}", to),
+                Err(e) => warn!("Failed to send daily digest to {}: {}", to, e),
+            }
+        }
+    }
+
+    fn collect_digests(
+        &self,
+        conn: &mut PgConnection,
+        now: NaiveDateTime,
+    ) -> Vec<(String, String)> {
+        use crate::schema::{scheduled_tasks, sessions};
+
+        let subscribers: Vec<(EmailNotificationSettings, String)> =
+            match email_notification_settings::table
+                .inner_join(users::table)
+                .filter(email_notification_settings::daily_digest.eq(true))
+                .select((EmailNotificationSettings::as_select(), users::email))
+                .load(conn)
+            {
+                Ok(rows) => rows,
+                Err(e) => {
+                    error!("Failed to load digest subscribers: {}", e);
+                    return vec![];
+                }
+            };
+
+        let since = now - chrono::Duration::hours(24);
+        let mut digests = Vec::new();
+        for (settings, email) in subscribers {
+            if !digest_due(settings.digest_hour, settings.last_digest_at, now) {
+                continue;
+            }
+            // Mark first: a relay outage skips a day rather than mailing every minute
+            if let Err(e) =
+                diesel::update(email_notification_settings::table.find(settings.user_id))
+                    .set(email_notification_settings::last_digest_at.eq(now))
+                    .execute(conn)
+            {
+                error!("Failed to record digest for {}: {}", settings.user_id, e);
+                continue;
+            }
+
+            let active: Vec<Session> = sessions::table
+                .filter(sessions::user_id.eq(settings.user_id))
+                .filter(sessions::last_activity.ge(since))
+                .order(sessions::total_cost_usd.desc())
+                .load(conn)
+                .unwrap_or_default();
+            let runs: Vec<ScheduledTask> = scheduled_tasks::table
+                .filter(scheduled_tasks::user_id.eq(settings.user_id))
+                .filter(scheduled_tasks::last_run_completed_at.ge(since))
+                .order(scheduled_tasks::name.asc())
+                .load(conn)
+                .unwrap_or_default();
+            if active.is_empty() && runs.is_empty() {
+                continue;
+            }
+            // What was spent in the window, not over the sessions' lifetimes
+            let spent = crate::budgets::spent_since(conn, &[settings.user_id], since)
+                .unwrap_or_else(|e| {
+                    error!("Failed to total spend for {}: {}", settings.user_id, e);
+                    0.0
+                });
+            let session_ids: Vec<Uuid> = active.iter().map(|s| s.id).collect();
+            let session_spend: HashMap<Uuid, f64> = spend_events::table
+                .filter(spend_events::session_id.eq_any(&session_ids))
+                .filter(spend_events::created_at.ge(since))
+                .group_by(spend_events::session_id)
+                .select((
+                    spend_events::session_id,
+                    diesel::dsl::sum(spend_events::cost_usd),
+                ))
+                .load::<(Option<Uuid>, Option<f64>)>(conn)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|(id, cost)| Some((id?, cost.unwrap_or(0.0))))
+                .collect();
+            digests.push((
+                email,
+                digest_body(&active, &session_spend, spent, &runs, &self.public_url),
+            ));
+        }
+        digests
+    }
+}
+
+/// Whether today's digest (at `hour` UTC) is due and not yet sent.
+fn digest_due(hour: i32, last_sent: Option<NaiveDateTime>, now: NaiveDateTime) -> bool {
+    if now.hour() < hour as u32 {
+        return false;
+    }
+    let Some(slot) = now.date().and_hms_opt(hour as u32, 0, 0) else {
+        return false;
+    };
+    last_sent.is_none_or(|last| last < slot)
+}
+
+fn describe_exit(exit_code: Option<i32>) -> String {
+    match exit_code {
+        Some(0) => "succeeded".to_string(),
+        Some(code) => format!("failed with exit code {}", code),
+        None => "was killed before it could exit".to_string(),
+    }
+}
+
+/// Plain-text digest of the last day's sessions, spend and scheduled runs.
+/// `spent` is the user's spend in the last day and `session_spend` each
+/// session's share of it.
+fn digest_body(
+    sessions: &[Session],
+    session_spend: &HashMap<Uuid, f64>,
+    spent: f64,
+    runs: &[ScheduledTask],
+    public_url: &str,
+) -> String {
+    let mut body = format!(
+        "In the last 24 hours: {} active session(s), ${:.2} spent.\n",
+        sessions.len(),
+        spent
+    );
+
+    if !sessions.is_empty() {
+        let day_spend = |s: &Session| session_spend.get(&s.id).copied().unwrap_or(0.0);
+        let mut sessions: Vec<&Session> = sessions.iter().collect();
+        sessions.sort_by(|a, b| day_spend(b).total_cmp(&day_spend(a)));
+        body.push_str("\nSessions\n");
+        for s in sessions.iter().take(DIGEST_SESSION_LIMIT) {
+            body.push_str(&format!(
+                "  - {} ({}) — {}, ${:.2}\n",
+                s.session_name,
+                s.working_directory,
+                s.status,
+                day_spend(s)
+            ));
+        }
+        if sessions.len() > DIGEST_SESSION_LIMIT {
+            body.push_str(&format!(
+                "  … and {} more\n",
+                sessions.len() - DIGEST_SESSION_LIMIT
+            ));
+        }
+    }
+
+    if !runs.is_empty() {
+        body.push_str("\nScheduled runs\n");
+        for t in runs {
+            body.push_str(&format!(
+                "  - {} on {}: {}\n",
+                t.name,
+                t.hostname,
+                describe_exit(t.last_exit_code)
+            ));
+        }
+    }
+
+    body.push_str(&format!(
+        "\nOpen the portal: {}/dashboard\nChange these emails under Settings → Email.\n",
+        public_url
+    ));
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::sync::mpsc;
+
+    /// Minimal MailHog-style SMTP sink: accepts every message and hands the
+    /// raw DATA section to the test.
+    async fn start_smtp_sink() -> (u16, mpsc::UnboundedReceiver<String>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let (read, mut write) = stream.into_split();
+                    let mut lines = BufReader::new(read).lines();
+                    write.write_all(b"220 sink ESMTP\r\n").await.unwrap();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        let command = line.to_ascii_uppercase();
+                        let reply: &[u8] = if command.starts_with("DATA") {
+                            write.write_all(b"354 go ahead\r\n").await.unwrap();
+                            let mut data = String::new();
+                            while let Ok(Some(line)) = lines.next_line().await {
+                                if line == "." {
+                                    break;
+                                }
+                                data.push_str(&line);
+                                data.push('\n');
+                            }
+                            let _ = tx.send(data);
+                            b"250 queued\r\n"
+                        } else if command.starts_with("QUIT") {
+                            write.write_all(b"221 bye\r\n").await.unwrap();
+                            break;
+                        } else {
+                            b"250 OK\r\n"
+                        };
+                        write.write_all(reply).await.unwrap();
+                    }
+                });
+            }
+        });
+        (port, rx)
+    }
+
+    #[tokio::test]
+    async fn test_smtp_mailer_delivers_to_sink() {
+        let (port, mut received) = start_smtp_sink().await;
+        let mailer = SmtpMailer::new(SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            tls: SmtpTls::None,
+            credentials: None,
+            from: "Portal <portal@example.com>".parse().unwrap(),
+        })
+        .unwrap();
+
+        mailer
+            .send(
+                "dev@example.com",
+                "Session demo ended with an error",
+                "Something broke.".to_string(),
+            )
+            .await
+            .unwrap();
+
+        let data = received.recv().await.unwrap();
+        assert!(data.contains("To: dev@example.com"));
+        assert!(data.contains("Subject: Session demo ended with an error"));
+        assert!(data.contains("Something broke."));
+    }
+
+    #[test]
+    fn test_digest_due() {
+        let at = |h, m| {
+            chrono::NaiveDate::from_ymd_opt(2026, 3, 17)
+                .unwrap()
+                .and_hms_opt(h, m, 0)
+                .unwrap()
+        };
+        assert!(!digest_due(8, None, at(7, 59)));
+        assert!(digest_due(8, None, at(8, 0)));
+        assert!(digest_due(
+            8,
+            Some(at(8, 0) - chrono::Duration::days(1)),
+            at(9, 30)
+        ));
+        assert!(!digest_due(8, Some(at(8, 1)), at(9, 30)));
+    }
+
+    #[test]
+    fn test_describe_exit() {
+        assert_eq!(describe_exit(Some(0)), "succeeded");
+        assert_eq!(describe_exit(Some(2)), "failed with exit code 2");
+        assert_eq!(describe_exit(None), "was killed before it could exit");
+    }
+
+    #[test]
+    fn test_digest_counts_only_the_last_day() {
+        let now = chrono::Utc::now().naive_utc();
+        let session = |name: &str, total_cost_usd: f64| Session {
+            id: Uuid::new_v4(),
+            user_id: Uuid::nil(),
+            session_name: name.to_string(),
+            session_key: name.to_string(),
+            working_directory: "/src".to_string(),
+            status: "active".to_string(),
+            last_activity: now,
+            created_at: now,
+            updated_at: now,
+            git_branch: None,
+            total_cost_usd,
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            client_version: None,
+            input_seq: 0,
+            hostname: "host".to_string(),
+            launcher_id: None,
+            pr_url: None,
+            agent_type: "claude".to_string(),
+            repo_url: None,
+            scheduled_task_id: None,
+            permission_timeout_seconds: None,
+            permission_timeout_action: None,
+            parent_session_id: None,
+            forked_from_message_id: None,
+            max_cost_usd: None,
+            model_usage: None,
+        };
+        // A long-running session that cost little today, and a new one
+        let old = session("old", 40.0);
+        let new = session("new", 3.0);
+        let spend = HashMap::from([(old.id, 0.5), (new.id, 3.0)]);
+        let body = digest_body(&[old, new], &spend, 3.5, &[], "https://portal");
+
+        assert!(body.starts_with("In the last 24 hours: 2 active session(s), $3.50 spent."));
+        let new_line = body.find("new (/src) — active, $3.00").unwrap();
+        let old_line = body.find("old (/src) — active, $0.50").unwrap();
+        assert!(new_line < old_line);
+        assert!(!body.contains("40.00"));
+    }
+}
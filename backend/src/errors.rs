@@ -11,6 +11,10 @@ pub enum AppError {
     Forbidden,
     BadRequest(&'static str),
     NotFound(&'static str),
+    /// A budget's hard limit or a session's cost cap is reached
+    SpendLimitReached(String),
+    /// Too many wrong guesses; try again after this long
+    TooManyAttempts(std::time::Duration),
     Internal(String),
 }
 
@@ -29,6 +33,16 @@ impl IntoResponse for AppError {
             AppError::Forbidden => (StatusCode::FORBIDDEN, "Forbidden"),
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, *msg),
             AppError::NotFound(what) => (StatusCode::NOT_FOUND, *what),
+            AppError::SpendLimitReached(reason) => (StatusCode::FORBIDDEN, reason.as_str()),
+            AppError::TooManyAttempts(wait) => {
+                let retry_after = wait.as_secs().max(1).to_string();
+                return (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [(axum::http::header::RETRY_AFTER, retry_after)],
+                    "Too many attempts, try again later",
+                )
+                    .into_response();
+            }
             AppError::Internal(e) => {
                 tracing::error!("Internal error: {}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
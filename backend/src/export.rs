@@ -0,0 +1,1027 @@
+//! Transcript export
+//!
+//! Turns the stored messages of a session into a standalone Markdown or HTML
+//! document, or into JSONL that can be fed back to the agent. The documents
+//! follow what the web client shows: prompts with their sender, assistant
+//! prose, tool calls as collapsible blocks (with a diff for `Edit`) paired
+//! with their output, and the stats footer of every `result` message.
+//!
+//! Messages are read leniently as JSON values so that unknown or partial
+//! messages are skipped rather than failing the export.
+
+use chrono::NaiveDateTime;
+use pulldown_cmark::{html, Event, Options, Parser};
+use serde_json::Value;
+use shared::render::{compute_line_diff, format_duration, DiffLine};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::html::escape_html;
+
+/// Output format selected with `?format=`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Jsonl,
+}
+
+impl FromStr for ExportFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            "html" => Ok(ExportFormat::Html),
+            "jsonl" => Ok(ExportFormat::Jsonl),
+            _ => Err(()),
+        }
+    }
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "text/markdown; charset=utf-8",
+            ExportFormat::Html => "text/html; charset=utf-8",
+            ExportFormat::Jsonl => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// Session details printed at the top of Markdown and HTML exports
+pub struct ExportHeader<'a> {
+    pub session_name: &'a str,
+    pub working_directory: &'a str,
+    pub hostname: &'a str,
+    pub git_branch: Option<&'a str>,
+    pub agent_type: &'a str,
+    pub created_at: NaiveDateTime,
+    pub total_cost_usd: f64,
+}
+
+/// One stored message, oldest first
+pub struct ExportMessage<'a> {
+    pub content: &'a str,
+    /// Display name of whoever sent a user-role message
+    pub sender: Option<&'a str>,
+    pub created_at: NaiveDateTime,
+}
+
+/// Render a session in the requested format
+pub fn render(format: ExportFormat, header: &ExportHeader, messages: &[ExportMessage]) -> String {
+    match format {
+        ExportFormat::Markdown => to_markdown(header, &build_entries(messages)),
+        ExportFormat::Html => to_html(header, &build_entries(messages)),
+        ExportFormat::Jsonl => to_jsonl(messages),
+    }
+}
+
+/// Download name: the session name made filesystem-safe, plus its start date
+pub fn file_name(session_name: &str, created_at: NaiveDateTime, format: ExportFormat) -> String {
+    let stem: String = session_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let stem = stem.trim_matches(|c| c == '-' || c == '.');
+    let stem = if stem.is_empty() { "session" } else { stem };
+    format!(
+        "{}-{}.{}",
+        stem,
+        created_at.format("%Y-%m-%d"),
+        format.extension()
+    )
+}
+
+// --- Transcript model ---
+
+#[derive(Debug, PartialEq)]
+enum Entry {
+    Prompt {
+        sender: String,
+        at: NaiveDateTime,
+        text: String,
+    },
+    /// Assistant prose, in Markdown
+    Text(String),
+    Thinking(String),
+    Tool(ToolCall),
+    Notice {
+        label: &'static str,
+        text: String,
+    },
+    Footer {
+        is_error: bool,
+        stats: Vec<String>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+struct ToolCall {
+    icon: &'static str,
+    name: String,
+    /// Path, command or pattern shown as code next to the name
+    target: Option<String>,
+    meta: Option<String>,
+    body: Vec<Block>,
+    output: Option<ToolOutput>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Block {
+    /// Markdown prose
+    Text(String),
+    Code {
+        lang: &'static str,
+        text: String,
+    },
+    Diff(Vec<(char, String)>),
+}
+
+#[derive(Debug, PartialEq)]
+struct ToolOutput {
+    text: String,
+    is_error: bool,
+}
+
+fn str_field<'v>(value: &'v Value, key: &str) -> Option<&'v str> {
+    value.get(key).and_then(|v| v.as_str())
+}
+
+/// Text of a `tool_result` block, whose content is a string or text blocks
+fn tool_result_text(block: &Value) -> String {
+    match block.get("content") {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(|p| str_field(p, "text"))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn build_entries(messages: &[ExportMessage]) -> Vec<Entry> {
+    let values: Vec<Value> = messages
+        .iter()
+        .map(|m| serde_json::from_str(m.content).unwrap_or(Value::Null))
+        .collect();
+
+    // Tool results arrive in later user messages; index them so each call can
+    // be printed together with its output
+    let mut outputs: HashMap<&str, ToolOutput> = HashMap::new();
+    for value in &values {
+        if str_field(value, "type") != Some("user") {
+            continue;
+        }
+        let Some(blocks) = value.pointer("/message/content").and_then(|c| c.as_array()) else {
+            continue;
+        };
+        for block in blocks {
+            if str_field(block, "type") == Some("tool_result") {
+                if let Some(id) = str_field(block, "tool_use_id") {
+                    outputs.insert(
+                        id,
+                        ToolOutput {
+                            text: tool_result_text(block),
+                            is_error: block
+                                .get("is_error")
+                                .and_then(|v| v.as_bool())
+                                .unwrap_or(false),
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    let mut entries = Vec::new();
+    for (message, value) in messages.iter().zip(&values) {
+        match str_field(value, "type") {
+            Some("user") => {
+                let text = match value.pointer("/message/content") {
+                    Some(Value::String(s)) => s.clone(),
+                    Some(Value::Array(blocks)) => blocks
+                        .iter()
+                        .filter(|b| str_field(b, "type") == Some("text"))
+                        .filter_map(|b| str_field(b, "text"))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    _ => str_field(value, "content").unwrap_or_default().to_string(),
+                };
+                if !text.trim().is_empty() {
+                    entries.push(Entry::Prompt {
+                        sender: message.sender.unwrap_or("User").to_string(),
+                        at: message.created_at,
+                        text,
+                    });
+                }
+            }
+            Some("assistant") => {
+                let blocks = value
+                    .pointer("/message/content")
+                    .and_then(|c| c.as_array())
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                for block in blocks {
+                    match str_field(block, "type") {
+                        Some("text") => {
+                            if let Some(text) = str_field(block, "text") {
+                                entries.push(Entry::Text(text.to_string()));
+                            }
+                        }
+                        Some("thinking") => {
+                            if let Some(text) = str_field(block, "thinking") {
+                                entries.push(Entry::Thinking(text.to_string()));
+                            }
+                        }
+                        Some("tool_use") => {
+                            let name = str_field(block, "name").unwrap_or("tool");
+                            let input = block.get("input").cloned().unwrap_or(Value::Null);
+                            let mut call = describe_tool(name, &input);
+                            call.output = str_field(block, "id").and_then(|id| outputs.remove(id));
+                            entries.push(Entry::Tool(call));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Some("result") => {
+                let is_error = value
+                    .get("is_error")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                if is_error {
+                    if let Some(text) = str_field(value, "result") {
+                        entries.push(Entry::Notice {
+                            label: "Error",
+                            text: text.to_string(),
+                        });
+                    }
+                }
+                entries.push(Entry::Footer {
+                    is_error,
+                    stats: result_stats(value),
+                });
+            }
+            Some("error") => {
+                let text = value
+                    .pointer("/error/message")
+                    .and_then(|v| v.as_str())
+                    .or_else(|| str_field(value, "message"))
+                    .unwrap_or("Unknown error");
+                entries.push(Entry::Notice {
+                    label: "Error",
+                    text: text.to_string(),
+                });
+            }
+            Some("portal") => {
+                let parts = value
+                    .get("content")
+                    .and_then(|c| c.as_array())
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let text = parts
+                    .iter()
+                    .filter_map(|p| match str_field(p, "type") {
+                        Some("text") => str_field(p, "text").map(str::to_string),
+                        Some("image") => Some(format!(
+                            "[image: {}]",
+                            str_field(p, "file_path").unwrap_or("attachment")
+                        )),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n\n");
+                if !text.is_empty() {
+                    entries.push(Entry::Notice {
+                        label: "Portal",
+                        text,
+                    });
+                }
+            }
+            Some("item.completed") => {
+                if let Some(item) = value.get("item") {
+                    entries.extend(codex_item(item));
+                }
+            }
+            Some("turn.completed") => {
+                if let Some(usage) = value.get("usage") {
+                    entries.push(Entry::Footer {
+                        is_error: false,
+                        stats: vec![token_stats(usage)],
+                    });
+                }
+            }
+            Some("turn.failed") => {
+                entries.push(Entry::Notice {
+                    label: "Error",
+                    text: value
+                        .pointer("/error/message")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("Turn failed")
+                        .to_string(),
+                });
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+fn token_stats(usage: &Value) -> String {
+    let count = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    format!("{}↓ {}↑", count("input_tokens"), count("output_tokens"))
+}
+
+/// The stats bar of a `result` message: duration, tokens, turns, cost and
+/// any errors or permission denials
+fn result_stats(value: &Value) -> Vec<String> {
+    let mut stats = vec![format_duration(
+        value
+            .get("duration_ms")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0),
+    )];
+    if let Some(usage) = value.get("usage") {
+        stats.push(token_stats(usage));
+    }
+    let turns = value.get("num_turns").and_then(|v| v.as_u64()).unwrap_or(0);
+    if turns > 1 {
+        stats.push(format!("{} turns", turns));
+    }
+    if let Some(cost) = value.get("total_cost_usd").and_then(|v| v.as_f64()) {
+        stats.push(format!("${:.2}", cost));
+    }
+    if str_field(value, "stop_reason") == Some("max_tokens") {
+        stats.push("max tokens".to_string());
+    }
+    let count = |key: &str| {
+        value
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map_or(0, Vec::len)
+    };
+    match count("errors") {
+        0 => {}
+        1 => stats.push("1 error".to_string()),
+        n => stats.push(format!("{} errors", n)),
+    }
+    if count("permission_denials") > 0 {
+        stats.push(format!("{} denied", count("permission_denials")));
+    }
+    stats
+}
+
+/// Header and body of a tool call, mirroring the web client's tool renderers
+fn describe_tool(name: &str, input: &Value) -> ToolCall {
+    let field = |key: &str| str_field(input, key).map(str::to_string);
+    let mut call = ToolCall {
+        icon: "⚡",
+        name: name.to_string(),
+        target: None,
+        meta: None,
+        body: Vec::new(),
+        output: None,
+    };
+    match name {
+        "Edit" => {
+            call.icon = "✏️";
+            call.target = field("file_path");
+            if input.get("replace_all").and_then(|v| v.as_bool()) == Some(true) {
+                call.meta = Some("(replace all)".to_string());
+            }
+            let old = str_field(input, "old_string").unwrap_or_default();
+            let new = str_field(input, "new_string").unwrap_or_default();
+            call.body.push(Block::Diff(
+                compute_line_diff(old, new)
+                    .into_iter()
+                    .map(|line| match line {
+                        DiffLine::Context(l) => (' ', l.to_string()),
+                        DiffLine::Removed(l) => ('-', l.to_string()),
+                        DiffLine::Added(l) => ('+', l.to_string()),
+                    })
+                    .collect(),
+            ));
+        }
+        "Write" => {
+            call.icon = "📝";
+            call.target = field("file_path");
+            let content = str_field(input, "content").unwrap_or_default();
+            call.meta = Some(format!(
+                "({} lines, {} bytes)",
+                content.lines().count(),
+                content.len()
+            ));
+            call.body.push(Block::Code {
+                lang: "",
+                text: content.to_string(),
+            });
+        }
+        "Bash" => {
+            call.icon = "$";
+            let command = str_field(input, "command").unwrap_or_default();
+            match command.lines().next() {
+                Some(first) if first.len() < command.len() => {
+                    call.target = Some(format!("{} …", first));
+                    call.body.push(Block::Code {
+                        lang: "sh",
+                        text: command.to_string(),
+                    });
+                }
+                _ => call.target = Some(command.to_string()),
+            }
+            call.meta = field("description");
+        }
+        "Read" => {
+            call.icon = "📖";
+            call.target = field("file_path");
+            let offset = input.get("offset").and_then(|v| v.as_i64());
+            let limit = input.get("limit").and_then(|v| v.as_i64());
+            call.meta = match (offset, limit) {
+                (Some(o), Some(l)) => Some(format!("lines {}-{}", o, o + l)),
+                (Some(o), None) => Some(format!("from line {}", o)),
+                (None, Some(l)) => Some(format!("first {} lines", l)),
+                _ => None,
+            };
+        }
+        "Glob" => {
+            call.icon = "🔍";
+            call.target = field("pattern");
+            call.meta = field("path").map(|p| format!("in {}", p));
+        }
+        "Grep" => {
+            call.icon = "🔎";
+            call.target = field("pattern").map(|p| format!("/{}/", p));
+            call.meta = field("path")
+                .or_else(|| field("glob"))
+                .map(|p| format!("in {}", p));
+        }
+        "Task" => {
+            call.icon = "🤖";
+            call.target = field("subagent_type");
+            call.meta = field("description");
+            if let Some(prompt) = field("prompt") {
+                call.body.push(Block::Text(prompt));
+            }
+        }
+        "WebFetch" => {
+            call.icon = "🌐";
+            call.target = field("url");
+            if let Some(prompt) = field("prompt") {
+                call.body.push(Block::Text(prompt));
+            }
+        }
+        "WebSearch" => {
+            call.icon = "🔍";
+            call.target = field("query");
+        }
+        "TodoWrite" => {
+            call.icon = "📋";
+            let todos = input
+                .get("todos")
+                .and_then(|v| v.as_array())
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            call.meta = Some(format!("({} items)", todos.len()));
+            let list = todos
+                .iter()
+                .map(|todo| {
+                    let content = str_field(todo, "content").unwrap_or_default();
+                    match str_field(todo, "status") {
+                        Some("completed") => format!("- [x] {}", content),
+                        Some("in_progress") => format!("- [ ] **{}**", content),
+                        _ => format!("- [ ] {}", content),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            call.body.push(Block::Text(list));
+        }
+        _ => {
+            if input.as_object().is_some_and(|o| !o.is_empty()) {
+                call.body.push(Block::Code {
+                    lang: "json",
+                    text: serde_json::to_string_pretty(input).unwrap_or_default(),
+                });
+            }
+        }
+    }
+    call
+}
+
+/// Entries for a completed Codex item
+fn codex_item(item: &Value) -> Vec<Entry> {
+    let text = |key: &str| str_field(item, key).unwrap_or_default().to_string();
+    let call = |icon, name: &str, target: Option<String>| ToolCall {
+        icon,
+        name: name.to_string(),
+        target,
+        meta: None,
+        body: Vec::new(),
+        output: None,
+    };
+    match str_field(item, "type").unwrap_or_default() {
+        "agent_message" | "agentMessage" => vec![Entry::Text(text("text"))],
+        "reasoning" => vec![Entry::Thinking(text("text"))],
+        "command_execution" | "commandExecution" => {
+            let mut tool = call("$", "Bash", Some(text("command")));
+            let exit_code = item
+                .get("exit_code")
+                .or_else(|| item.get("exitCode"))
+                .and_then(|v| v.as_i64());
+            if let Some(code) = exit_code {
+                tool.meta = Some(format!("exit {}", code));
+            }
+            let output = item
+                .get("aggregated_output")
+                .or_else(|| item.get("aggregatedOutput"))
+                .and_then(|v| v.as_str());
+            tool.output = output.map(|o| ToolOutput {
+                text: o.to_string(),
+                is_error: exit_code.is_some_and(|c| c != 0),
+            });
+            vec![Entry::Tool(tool)]
+        }
+        "file_change" | "fileChange" => {
+            let changes = item
+                .get("changes")
+                .and_then(|v| v.as_array())
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let mut tool = call("📝", "Files changed", None);
+            tool.meta = Some(format!("({})", changes.len()));
+            tool.body.push(Block::Text(
+                changes
+                    .iter()
+                    .map(|c| {
+                        format!(
+                            "- `{}` {}",
+                            str_field(c, "path").unwrap_or("?"),
+                            str_field(c, "kind").unwrap_or_default()
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ));
+            vec![Entry::Tool(tool)]
+        }
+        "mcp_tool_call" | "mcpToolCall" => {
+            let mut tool = call("⚡", &format!("{}/{}", text("server"), text("tool")), None);
+            if let Some(arguments) = item.get("arguments").filter(|a| !a.is_null()) {
+                tool.body.push(Block::Code {
+                    lang: "json",
+                    text: serde_json::to_string_pretty(arguments).unwrap_or_default(),
+                });
+            }
+            vec![Entry::Tool(tool)]
+        }
+        "web_search" | "webSearch" => {
+            vec![Entry::Tool(call("🔍", "WebSearch", Some(text("query"))))]
+        }
+        "error" => vec![Entry::Notice {
+            label: "Error",
+            text: text("message"),
+        }],
+        _ => Vec::new(),
+    }
+}
+
+// --- Markdown ---
+
+/// A code fence longer than any run of backticks inside `text`
+fn fence(text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn code_block(out: &mut String, lang: &str, text: &str) {
+    let fence = fence(text);
+    let _ = writeln!(out, "{}{}\n{}\n{}\n", fence, lang, text.trim_end(), fence);
+}
+
+/// The `<summary>` line of a tool call, as HTML
+fn tool_summary(call: &ToolCall) -> String {
+    let mut summary = format!("{} {}", call.icon, escape_html(&call.name));
+    if let Some(target) = &call.target {
+        let _ = write!(summary, " <code>{}</code>", escape_html(target));
+    }
+    if let Some(meta) = &call.meta {
+        let _ = write!(summary, " {}", escape_html(meta));
+    }
+    if call.output.as_ref().is_some_and(|o| o.is_error) {
+        summary.push_str(" ✗");
+    }
+    summary
+}
+
+fn footer_line(is_error: bool, stats: &[String]) -> String {
+    format!("{} {}", if is_error { "✗" } else { "✓" }, stats.join(" · "))
+}
+
+fn header_lines(header: &ExportHeader) -> Vec<(&'static str, String)> {
+    let mut lines = vec![
+        ("Directory", header.working_directory.to_string()),
+        ("Host", header.hostname.to_string()),
+    ];
+    if let Some(branch) = header.git_branch {
+        lines.push(("Branch", branch.to_string()));
+    }
+    lines.push(("Agent", header.agent_type.to_string()));
+    lines.push((
+        "Started",
+        header.created_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+    ));
+    lines.push(("Total cost", format!("${:.2}", header.total_cost_usd)));
+    lines
+}
+
+fn to_markdown(header: &ExportHeader, entries: &[Entry]) -> String {
+    let mut out = format!("# {}\n\n", header.session_name);
+    for (label, value) in header_lines(header) {
+        let _ = writeln!(out, "- **{}:** {}", label, value);
+    }
+    out.push('\n');
+
+    for entry in entries {
+        match entry {
+            Entry::Prompt { sender, at, text } => {
+                let _ = writeln!(
+                    out,
+                    "---\n\n### {} · {}\n\n{}\n",
+                    sender,
+                    at.format("%Y-%m-%d %H:%M UTC"),
+                    text.trim_end()
+                );
+            }
+            Entry::Text(text) => {
+                let _ = writeln!(out, "{}\n", text.trim_end());
+            }
+            Entry::Thinking(text) => {
+                out.push_str("<details>\n<summary>Thinking</summary>\n\n");
+                let _ = writeln!(out, "{}\n\n</details>\n", text.trim_end());
+            }
+            Entry::Tool(call) => {
+                let _ = writeln!(
+                    out,
+                    "<details>\n<summary>{}</summary>\n",
+                    tool_summary(call)
+                );
+                for block in &call.body {
+                    match block {
+                        Block::Text(text) => {
+                            let _ = writeln!(out, "{}\n", text.trim_end());
+                        }
+                        Block::Code { lang, text } => code_block(&mut out, lang, text),
+                        Block::Diff(lines) => {
+                            let diff = lines
+                                .iter()
+                                .map(|(marker, line)| format!("{}{}", marker, line))
+                                .collect::<Vec<_>>()
+                                .join("\n");
+                            code_block(&mut out, "diff", &diff);
+                        }
+                    }
+                }
+                if let Some(output) = &call.output {
+                    if !output.text.trim().is_empty() {
+                        let label = if output.is_error { "Error" } else { "Output" };
+                        let _ = writeln!(out, "**{}**\n", label);
+                        code_block(&mut out, "", &output.text);
+                    }
+                }
+                out.push_str("</details>\n\n");
+            }
+            Entry::Notice { label, text } => {
+                let quoted = text
+                    .trim_end()
+                    .lines()
+                    .map(|l| format!("> {}", l))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let _ = writeln!(out, "> **{}**\n>\n{}\n", label, quoted);
+            }
+            Entry::Footer { is_error, stats } => {
+                let _ = writeln!(out, "_{}_\n", footer_line(*is_error, stats));
+            }
+        }
+    }
+    out
+}
+
+// --- HTML ---
+
+const HTML_STYLE: &str = "\
+body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;max-width:960px;\
+margin:2rem auto;padding:0 1rem;color:#1f2328;line-height:1.5}\
+header dl{display:grid;grid-template-columns:max-content 1fr;gap:.2rem 1rem;color:#59636e}\
+header dt{font-weight:600}header dd{margin:0}\
+pre{background:#f6f8fa;padding:.75rem;overflow-x:auto;border-radius:6px}\
+code{font-family:ui-monospace,SFMono-Regular,Menlo,monospace;font-size:.9em}\
+.prompt{border-top:1px solid #d1d9e0;margin-top:2rem;padding-top:1rem}\
+.prompt h3{margin:0 0 .5rem}.prompt time{color:#59636e;font-weight:normal;font-size:.85em}\
+details{border:1px solid #d1d9e0;border-radius:6px;padding:.4rem .75rem;margin:.5rem 0}\
+details.error{border-color:#cf222e}summary{cursor:pointer}\
+.diff .added{background:#dafbe1;display:block}.diff .removed{background:#ffebe9;display:block}\
+.diff .context{display:block}\
+.notice{border-left:4px solid #9a6700;padding:.25rem .75rem;margin:.5rem 0}\
+.notice.error{border-color:#cf222e}\
+.footer{color:#59636e;font-size:.85em;margin:.5rem 0 1rem}.footer.error{color:#cf222e}";
+
+/// Markdown to HTML with any raw HTML in the source shown as text
+fn markdown_html(text: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+    let events = Parser::new_ext(text, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        other => other,
+    });
+    let mut out = String::new();
+    html::push_html(&mut out, events);
+    out
+}
+
+fn pre(out: &mut String, class: &str, text: &str) {
+    let _ = writeln!(
+        out,
+        "<pre class=\"{}\"><code>{}</code></pre>",
+        class,
+        escape_html(text.trim_end())
+    );
+}
+
+fn to_html(header: &ExportHeader, entries: &[Entry]) -> String {
+    let title = escape_html(header.session_name);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<header>\n<h1>{}</h1>\n<dl>\n",
+        title, HTML_STYLE, title
+    );
+    for (label, value) in header_lines(header) {
+        let _ = writeln!(out, "<dt>{}</dt><dd>{}</dd>", label, escape_html(&value));
+    }
+    out.push_str("</dl>\n</header>\n<main>\n");
+
+    for entry in entries {
+        match entry {
+            Entry::Prompt { sender, at, text } => {
+                let _ = writeln!(
+                    out,
+                    "<section class=\"prompt\">\n<h3>{} <time>{}</time></h3>\n{}</section>",
+                    escape_html(sender),
+                    at.format("%Y-%m-%d %H:%M UTC"),
+                    markdown_html(text)
+                );
+            }
+            Entry::Text(text) => out.push_str(&markdown_html(text)),
+            Entry::Thinking(text) => {
+                let _ = writeln!(
+                    out,
+                    "<details class=\"thinking\">\n<summary>Thinking</summary>\n{}</details>",
+                    markdown_html(text)
+                );
+            }
+            Entry::Tool(call) => {
+                let failed = call.output.as_ref().is_some_and(|o| o.is_error);
+                let _ = writeln!(
+                    out,
+                    "<details class=\"tool{}\">\n<summary>{}</summary>",
+                    if failed { " error" } else { "" },
+                    tool_summary(call)
+                );
+                for block in &call.body {
+                    match block {
+                        Block::Text(text) => out.push_str(&markdown_html(text)),
+                        Block::Code { text, .. } => pre(&mut out, "code", text),
+                        Block::Diff(lines) => {
+                            out.push_str("<pre class=\"diff\"><code>");
+                            for (marker, line) in lines {
+                                let class = match marker {
+                                    '+' => "added",
+                                    '-' => "removed",
+                                    _ => "context",
+                                };
+                                let _ = write!(
+                                    out,
+                                    "<span class=\"{}\">{}{}</span>",
+                                    class,
+                                    marker,
+                                    escape_html(line)
+                                );
+                            }
+                            out.push_str("</code></pre>\n");
+                        }
+                    }
+                }
+                if let Some(output) = &call.output {
+                    if !output.text.trim().is_empty() {
+                        pre(&mut out, "output", &output.text);
+                    }
+                }
+                out.push_str("</details>\n");
+            }
+            Entry::Notice { label, text } => {
+                let _ = writeln!(
+                    out,
+                    "<div class=\"notice{}\"><strong>{}</strong>{}</div>",
+                    if *label == "Error" { " error" } else { "" },
+                    label,
+                    markdown_html(text)
+                );
+            }
+            Entry::Footer { is_error, stats } => {
+                let _ = writeln!(
+                    out,
+                    "<div class=\"footer{}\">{}</div>",
+                    if *is_error { " error" } else { "" },
+                    escape_html(&footer_line(*is_error, stats))
+                );
+            }
+        }
+    }
+    out.push_str("</main>\n</body>\n</html>\n");
+    out
+}
+
+// --- JSONL ---
+
+/// The stored messages, one compact JSON object per line
+fn to_jsonl(messages: &[ExportMessage]) -> String {
+    let mut out = String::new();
+    for message in messages {
+        if let Ok(value) = serde_json::from_str::<Value>(message.content) {
+            out.push_str(&value.to_string());
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn at() -> NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(2026, 3, 20)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap()
+    }
+
+    fn header() -> ExportHeader<'static> {
+        ExportHeader {
+            session_name: "fix <flaky> test",
+            working_directory: "/home/dev/app",
+            hostname: "devbox",
+            git_branch: Some("main"),
+            agent_type: "claude",
+            created_at: at(),
+            total_cost_usd: 0.1234,
+        }
+    }
+
+    fn transcript() -> Vec<String> {
+        vec![
+            json!({"type": "user", "message": {"role": "user", "content": "Fix the test"}}),
+            json!({"type": "assistant", "message": {"content": [
+                {"type": "text", "text": "Patching it."},
+                {"type": "tool_use", "id": "t1", "name": "Edit", "input": {
+                    "file_path": "src/lib.rs", "old_string": "a\nb", "new_string": "a\nc"
+                }}
+            ]}}),
+            json!({"type": "user", "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "t1", "content": [{"type": "text", "text": "ok"}]}
+            ]}}),
+            json!({"type": "result", "subtype": "success", "is_error": false, "duration_ms": 12300,
+                   "num_turns": 3, "total_cost_usd": 0.12,
+                   "usage": {"input_tokens": 1200, "output_tokens": 340}}),
+        ]
+        .into_iter()
+        .map(|v| v.to_string())
+        .collect()
+    }
+
+    fn messages(contents: &[String]) -> Vec<ExportMessage<'_>> {
+        contents
+            .iter()
+            .map(|c| ExportMessage {
+                content: c,
+                sender: Some("Ada"),
+                created_at: at(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("md".parse(), Ok(ExportFormat::Markdown));
+        assert_eq!("html".parse(), Ok(ExportFormat::Html));
+        assert_eq!("jsonl".parse(), Ok(ExportFormat::Jsonl));
+        assert!("pdf".parse::<ExportFormat>().is_err());
+    }
+
+    #[test]
+    fn test_tool_results_pair_with_calls() {
+        let contents = transcript();
+        let entries = build_entries(&messages(&contents));
+        assert_eq!(entries.len(), 4);
+        let Entry::Tool(call) = &entries[2] else {
+            panic!("expected a tool call, got {:?}", entries[2]);
+        };
+        assert_eq!(call.target.as_deref(), Some("src/lib.rs"));
+        assert_eq!(
+            call.output,
+            Some(ToolOutput {
+                text: "ok".to_string(),
+                is_error: false
+            })
+        );
+        assert_eq!(
+            entries[3],
+            Entry::Footer {
+                is_error: false,
+                stats: vec![
+                    "12.3s".to_string(),
+                    "1200↓ 340↑".to_string(),
+                    "3 turns".to_string(),
+                    "$0.12".to_string()
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn test_markdown_export() {
+        let contents = transcript();
+        let md = render(ExportFormat::Markdown, &header(), &messages(&contents));
+        assert!(md.starts_with("# fix <flaky> test\n"));
+        assert!(md.contains("### Ada · 2026-03-20 09:30 UTC\n\nFix the test"));
+        assert!(md.contains("<summary>✏️ Edit <code>src/lib.rs</code></summary>"));
+        assert!(md.contains("```diff\n a\n-b\n+c\n```"));
+        assert!(md.contains("**Output**\n\n```\nok\n```"));
+        assert!(md.contains("_✓ 12.3s · 1200↓ 340↑ · 3 turns · $0.12_"));
+    }
+
+    #[test]
+    fn test_html_export_escapes_content() {
+        let contents = vec![
+            json!({"type": "user", "message": {"content": "<script>alert(1)</script>"}})
+                .to_string(),
+        ];
+        let html = render(ExportFormat::Html, &header(), &messages(&contents));
+        assert!(html.contains("<title>fix &lt;flaky&gt; test</title>"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn test_jsonl_is_stored_messages() {
+        let mut contents = transcript();
+        contents.push("not json".to_string());
+        let jsonl = render(ExportFormat::Jsonl, &header(), &messages(&contents));
+        let lines: Vec<&str> = jsonl.lines().collect();
+        assert_eq!(lines.len(), 4);
+        for (line, original) in lines.iter().zip(&contents) {
+            assert_eq!(
+                serde_json::from_str::<Value>(line).unwrap(),
+                serde_json::from_str::<Value>(original).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_fence_outgrows_backticks() {
+        assert_eq!(fence("plain"), "```");
+        assert_eq!(fence("has ``` inside"), "````");
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(
+            file_name("fix <flaky> test", at(), ExportFormat::Html),
+            "fix--flaky--test-2026-03-20.html"
+        );
+        assert_eq!(
+            file_name("///", at(), ExportFormat::Jsonl),
+            "session-2026-03-20.jsonl"
+        );
+    }
+}
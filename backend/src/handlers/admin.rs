@@ -3,22 +3,28 @@
 //! These endpoints are restricted to users with is_admin=true.
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
     Json,
 };
 use diesel::prelude::*;
 use diesel::sql_types::{BigInt, Double};
-use serde::Serialize;
-use shared::api::UpdateUserRequest;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shared::api::{
+    AuditAction, AuditEventInfo, AuditEventsResponse, CreateInviteRequest, CreateInviteResponse,
+    CreateLocalAccountRequest, InstanceSettings, InstanceSettingsResponse, InvitesResponse,
+    LocalAccountSetupLink, ResetLocalPasswordRequest, SetSessionCostCapRequest,
+    SetSpendBudgetRequest, SpendBudgetInfo, SpendBudgetsResponse, UpdateUserRequest,
+    MAX_INVITE_DAYS, MAX_INVITE_USES,
+};
 use std::sync::Arc;
 use tower_cookies::Cookies;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
-use crate::{db::get_user_usage, models::User, schema, AppState};
-
-use shared::protocol::SESSION_COOKIE_NAME;
+use crate::{db::get_user_usage, invites, local_accounts, models::User, schema, AppState};
 
 // ============================================================================
 // Admin Guard - extracts and validates admin user from cookies
@@ -30,16 +36,10 @@ pub async fn require_admin(
     app_state: &Arc<AppState>,
     cookies: &Cookies,
 ) -> Result<User, StatusCode> {
-    // Extract user ID from signed session cookie
-    let cookie = cookies
-        .signed(&app_state.cookie_key)
-        .get(SESSION_COOKIE_NAME)
-        .ok_or(StatusCode::UNAUTHORIZED)?;
-
-    let user_id: Uuid = cookie
-        .value()
-        .parse()
-        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+    // Resolve the browser login named by the signed session cookie
+    let user_id = crate::auth::current_login(app_state, cookies)
+        .map_err(|_| StatusCode::UNAUTHORIZED)?
+        .user_id;
 
     // Fetch user from database
     let mut conn = app_state
@@ -71,7 +71,7 @@ pub async fn require_admin(
 // Stats Endpoint - System overview statistics
 // ============================================================================
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct AdminStats {
     /// Total number of registered users
     pub total_users: i64,
@@ -144,6 +144,12 @@ struct DeletedCostStats {
     sum_cache_read_tokens: i64,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/admin/stats",
+    tag = "admin",
+    responses((status = 200, body = AdminStats), (status = 403, description = "Not an admin"))
+)]
 pub async fn get_stats(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -233,7 +239,7 @@ pub async fn get_stats(
 // Users Endpoint - List and manage users
 // ============================================================================
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct AdminUserInfo {
     pub id: Uuid,
     pub email: String,
@@ -242,6 +248,8 @@ pub struct AdminUserInfo {
     pub is_admin: bool,
     pub disabled: bool,
     pub voice_enabled: bool,
+    /// How the user signs in: `google`, an OIDC provider ID, or `local`
+    pub auth_provider: String,
     pub created_at: String,
     pub session_count: i64,
     pub total_spend_usd: f64,
@@ -251,11 +259,19 @@ pub struct AdminUserInfo {
     pub total_cache_read_tokens: i64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct AdminUsersResponse {
     pub users: Vec<AdminUserInfo>,
+    /// Admins may create local username/password accounts
+    pub local_accounts_enabled: bool,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/admin/users",
+    tag = "admin",
+    responses((status = 200, body = AdminUsersResponse), (status = 403, description = "Not an admin"))
+)]
 pub async fn list_users(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -298,6 +314,7 @@ pub async fn list_users(
             is_admin: user.is_admin,
             disabled: user.disabled,
             voice_enabled: user.voice_enabled,
+            auth_provider: user.auth_provider,
             created_at: user.created_at.to_string(),
             session_count,
             total_spend_usd: usage.cost_usd,
@@ -308,9 +325,25 @@ pub async fn list_users(
         });
     }
 
-    Ok(Json(AdminUsersResponse { users: user_infos }))
+    Ok(Json(AdminUsersResponse {
+        users: user_infos,
+        local_accounts_enabled: app_state.local_accounts_enabled,
+    }))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/api/admin/users/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User ID")),
+    request_body = UpdateUserRequest,
+    responses(
+        (status = 204, description = "User updated"),
+        (status = 400, description = "Admins cannot demote or disable themselves"),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "User not found")
+    )
+)]
 pub async fn update_user(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -397,6 +430,21 @@ pub async fn update_user(
                 );
             }
 
+            // Sign out their browsers and close the dashboards they have open
+            let signed_out = super::web_logins::end_other_logins(
+                &app_state, &mut conn, user_id, None,
+            )
+            .map_err(|e| {
+                error!("Failed to sign out banned user: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            if signed_out > 0 {
+                info!(
+                    "Signed out {} browser(s) of banned user {}",
+                    signed_out, target_user.email
+                );
+            }
+
             // Delete all user's sessions and associated data
             let (deleted_sessions, deleted_messages, deleted_members) =
                 super::helpers::delete_user_sessions(&mut conn, user_id).map_err(|e| {
@@ -414,6 +462,7 @@ pub async fn update_user(
     }
 
     // Handle ban_reason update
+    let ban_reason = update.ban_reason.clone();
     if let Some(reason) = update.ban_reason {
         diesel::update(schema::users::table.find(user_id))
             .set(schema::users::ban_reason.eq(reason.as_ref()))
@@ -442,14 +491,178 @@ pub async fn update_user(
         );
     }
 
+    // Only what the request changed; a null ban_reason means it was cleared
+    let mut details = json!({ "email": target_user.email });
+    if let Some(is_admin_val) = update.is_admin {
+        details["is_admin"] = json!(is_admin_val);
+    }
+    if let Some(disabled_val) = update.disabled {
+        details["disabled"] = json!(disabled_val);
+    }
+    if let Some(reason) = ban_reason {
+        details["ban_reason"] = json!(reason);
+    }
+    if let Some(voice_enabled_val) = update.voice_enabled {
+        details["voice_enabled"] = json!(voice_enabled_val);
+    }
+    crate::audit::record(
+        &mut conn,
+        Some(admin.id),
+        AuditAction::UserUpdate,
+        Some(user_id),
+        details,
+    );
+
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/admin/local-accounts",
+    tag = "admin",
+    request_body = CreateLocalAccountRequest,
+    responses(
+        (status = 200, description = "Account created; the setup link is shown only once", body = LocalAccountSetupLink),
+        (status = 400, description = "Invalid username or email"),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "Local accounts are not enabled"),
+        (status = 409, description = "Username already taken")
+    )
+)]
+pub async fn create_local_account(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Json(req): Json<CreateLocalAccountRequest>,
+) -> Result<Json<LocalAccountSetupLink>, StatusCode> {
+    let admin = require_admin(&app_state, &cookies).await?;
+    if !app_state.local_accounts_enabled {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let username =
+        local_accounts::normalize_username(&req.username).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let email = req.email.trim().to_lowercase();
+    if !email.contains('@') || email.len() > 255 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let name = req
+        .name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty());
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if local_accounts::find_by_username(&mut conn, &username)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .is_some()
+    {
+        return Err(StatusCode::CONFLICT);
+    }
+    let (user, token, expires_at) =
+        local_accounts::create_account(&mut conn, &username, &email, name, req.is_admin).map_err(
+            |e| match e {
+                diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ) => StatusCode::CONFLICT,
+                e => {
+                    error!("Failed to create local account: {}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+            },
+        )?;
+
+    info!(
+        "Admin {} created local account {} ({})",
+        admin.email, username, email
+    );
+    crate::audit::record(
+        &mut conn,
+        Some(admin.id),
+        AuditAction::LocalAccountCreate,
+        Some(user.id),
+        json!({ "username": username, "email": email, "is_admin": req.is_admin }),
+    );
+
+    Ok(Json(LocalAccountSetupLink {
+        user_id: user.id,
+        setup_url: local_accounts::setup_url(&app_state.public_url, &token),
+        username,
+        expires_at: expires_at.and_utc().to_rfc3339(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{id}/password-reset",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User ID")),
+    request_body = ResetLocalPasswordRequest,
+    responses(
+        (status = 200, description = "Password cleared; the new setup link is shown only once", body = LocalAccountSetupLink),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "No local account for this user")
+    )
+)]
+pub async fn reset_local_password(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(user_id): Path<Uuid>,
+    Json(req): Json<ResetLocalPasswordRequest>,
+) -> Result<Json<LocalAccountSetupLink>, StatusCode> {
+    let admin = require_admin(&app_state, &cookies).await?;
+    if !app_state.local_accounts_enabled {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let account = local_accounts::find_by_user(&mut conn, user_id)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let (token, expires_at) = local_accounts::reset_password(&mut conn, user_id, req.reset_totp)
+        .map_err(|e| {
+            error!("Failed to reset local password: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    // The reset is for a lost or leaked password: end every browser it
+    // signed in
+    let signed_out = super::web_logins::end_other_logins(&app_state, &mut conn, user_id, None)
+        .map_err(|e| {
+            error!("Failed to sign out local account: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    info!(
+        "Admin {} reset the password of local account {}, signing out {} browser(s)",
+        admin.email, account.username, signed_out
+    );
+    crate::audit::record(
+        &mut conn,
+        Some(admin.id),
+        AuditAction::LocalAccountReset,
+        Some(user_id),
+        json!({ "username": account.username, "reset_totp": req.reset_totp }),
+    );
+
+    Ok(Json(LocalAccountSetupLink {
+        user_id,
+        setup_url: local_accounts::setup_url(&app_state.public_url, &token),
+        username: account.username,
+        expires_at: expires_at.and_utc().to_rfc3339(),
+    }))
+}
+
 // ============================================================================
 // Sessions Endpoint - List and manage all sessions
 // ============================================================================
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct AdminSessionInfo {
     pub id: Uuid,
     pub user_id: Uuid,
@@ -459,17 +672,26 @@ pub struct AdminSessionInfo {
     pub git_branch: Option<String>,
     pub status: String,
     pub total_cost_usd: f64,
+    /// Cost cap chosen at launch or set by an admin
+    pub max_cost_usd: Option<f64>,
     pub created_at: String,
     pub last_activity: String,
     pub is_connected: bool,
     pub hostname: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct AdminSessionsResponse {
     pub sessions: Vec<AdminSessionInfo>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/admin/sessions",
+    operation_id = "admin_list_sessions",
+    tag = "admin",
+    responses((status = 200, body = AdminSessionsResponse), (status = 403, description = "Not an admin"))
+)]
 pub async fn list_sessions(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -510,6 +732,7 @@ pub async fn list_sessions(
                 git_branch: session.git_branch,
                 status: session.status,
                 total_cost_usd: session.total_cost_usd,
+                max_cost_usd: session.max_cost_usd,
                 created_at: session.created_at.to_string(),
                 last_activity: session.last_activity.to_string(),
                 is_connected,
@@ -523,6 +746,18 @@ pub async fn list_sessions(
     }))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/admin/sessions/{id}",
+    operation_id = "admin_delete_session",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    responses(
+        (status = 204, description = "Session deleted"),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "Session not found")
+    )
+)]
 pub async fn delete_session(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -555,6 +790,803 @@ pub async fn delete_session(
         "Admin {} deleted session {} ({}) - cost ${:.4} recorded",
         admin.email, session_id, session.session_name, session.total_cost_usd
     );
+    crate::audit::record(
+        &mut conn,
+        Some(admin.id),
+        AuditAction::SessionDelete,
+        Some(session_id),
+        json!({
+            "session_name": session.session_name,
+            "owner_id": session.user_id,
+            "total_cost_usd": session.total_cost_usd,
+        }),
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Teams Endpoint - Team-scoped spend reporting
+// ============================================================================
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct AdminTeamInfo {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: String,
+    pub member_count: i64,
+    /// Sessions currently shared with the team
+    pub session_count: i64,
+    /// Spend of the sessions shared with the team
+    pub shared_spend_usd: f64,
+    pub shared_input_tokens: i64,
+    pub shared_output_tokens: i64,
+    /// Everything the team's members have spent, shared or not, including
+    /// deleted sessions
+    pub member_spend_usd: f64,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct AdminTeamsResponse {
+    pub teams: Vec<AdminTeamInfo>,
+}
+
+/// Per-team totals over the sessions shared with it, from a single query.
+#[derive(QueryableByName)]
+struct TeamSpendRow {
+    #[diesel(sql_type = diesel::sql_types::Uuid)]
+    id: Uuid,
+    #[diesel(sql_type = diesel::sql_types::Varchar)]
+    name: String,
+    #[diesel(sql_type = diesel::sql_types::Timestamp)]
+    created_at: chrono::NaiveDateTime,
+    #[diesel(sql_type = BigInt)]
+    session_count: i64,
+    #[diesel(sql_type = Double)]
+    spend_usd: f64,
+    #[diesel(sql_type = BigInt)]
+    sum_input_tokens: i64,
+    #[diesel(sql_type = BigInt)]
+    sum_output_tokens: i64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/teams",
+    operation_id = "admin_list_teams",
+    tag = "admin",
+    responses((status = 200, description = "Every team, highest shared spend first", body = AdminTeamsResponse), (status = 403, description = "Not an admin"))
+)]
+pub async fn list_teams(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<Json<AdminTeamsResponse>, StatusCode> {
+    let admin = require_admin(&app_state, &cookies).await?;
+    info!("Admin {} requested team spend", admin.email);
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let rows: Vec<TeamSpendRow> = diesel::sql_query(
+        "SELECT t.id, t.name, t.created_at, \
+         COUNT(s.id) as session_count, \
+         COALESCE(SUM(s.total_cost_usd), 0.0)::float8 as spend_usd, \
+         COALESCE(SUM(s.input_tokens), 0)::bigint as sum_input_tokens, \
+         COALESCE(SUM(s.output_tokens), 0)::bigint as sum_output_tokens \
+         FROM teams t \
+         LEFT JOIN session_teams st ON st.team_id = t.id \
+         LEFT JOIN sessions s ON s.id = st.session_id \
+         GROUP BY t.id, t.name, t.created_at \
+         ORDER BY spend_usd DESC, t.name",
+    )
+    .load(&mut conn)
+    .map_err(|e| {
+        error!("Failed to query team spend: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let memberships: Vec<(Uuid, Uuid)> = schema::team_members::table
+        .select((schema::team_members::team_id, schema::team_members::user_id))
+        .load(&mut conn)
+        .map_err(|e| {
+            error!("Failed to load team members: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    // A user in several teams counts towards each of them
+    let mut user_spend: std::collections::HashMap<Uuid, f64> = std::collections::HashMap::new();
+    let mut teams = Vec::with_capacity(rows.len());
+    for row in rows {
+        let members: Vec<Uuid> = memberships
+            .iter()
+            .filter(|(team_id, _)| *team_id == row.id)
+            .map(|(_, user_id)| *user_id)
+            .collect();
+        let mut member_spend_usd = 0.0;
+        for user_id in &members {
+            member_spend_usd += *user_spend.entry(*user_id).or_insert_with(|| {
+                get_user_usage(&mut conn, *user_id)
+                    .map(|u| u.cost_usd)
+                    .unwrap_or_default()
+            });
+        }
+        teams.push(AdminTeamInfo {
+            id: row.id,
+            name: row.name,
+            created_at: row.created_at.to_string(),
+            member_count: members.len() as i64,
+            session_count: row.session_count,
+            shared_spend_usd: row.spend_usd,
+            shared_input_tokens: row.sum_input_tokens,
+            shared_output_tokens: row.sum_output_tokens,
+            member_spend_usd,
+        });
+    }
+
+    Ok(Json(AdminTeamsResponse { teams }))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/admin/sessions/{id}/cost-cap",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    request_body = SetSessionCostCapRequest,
+    responses(
+        (status = 204, description = "Cost cap changed; null removes it"),
+        (status = 400, description = "Not a positive amount"),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "Session not found")
+    )
+)]
+pub async fn set_session_cost_cap(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(session_id): Path<Uuid>,
+    Json(req): Json<SetSessionCostCapRequest>,
+) -> Result<StatusCode, StatusCode> {
+    let admin = require_admin(&app_state, &cookies).await?;
+    shared::api::validate_cost_cap(req.max_cost_usd).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let updated = diesel::update(schema::sessions::table.find(session_id))
+        .set(schema::sessions::max_cost_usd.eq(req.max_cost_usd))
+        .execute(&mut conn)
+        .map_err(|e| {
+            error!("Failed to update session cost cap: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if updated == 0 {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    info!(
+        "Admin {} set the cost cap of session {} to {:?}",
+        admin.email, session_id, req.max_cost_usd
+    );
+    crate::audit::record(
+        &mut conn,
+        Some(admin.id),
+        AuditAction::SessionCostCap,
+        Some(session_id),
+        json!({ "max_cost_usd": req.max_cost_usd }),
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Budgets Endpoints - Spend limits per user and team
+// ============================================================================
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/budgets",
+    operation_id = "admin_list_budgets",
+    tag = "admin",
+    responses((status = 200, description = "Every budget with its spend this period", body = SpendBudgetsResponse), (status = 403, description = "Not an admin"))
+)]
+pub async fn list_budgets(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<Json<SpendBudgetsResponse>, StatusCode> {
+    let admin = require_admin(&app_state, &cookies).await?;
+    info!("Admin {} requested budgets", admin.email);
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let rows: Vec<crate::models::SpendBudget> = schema::spend_budgets::table
+        .order(schema::spend_budgets::created_at.asc())
+        .load(&mut conn)
+        .map_err(|e| {
+            error!("Failed to load budgets: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let now = chrono::Utc::now().naive_utc();
+    let mut budgets = Vec::with_capacity(rows.len());
+    for budget in rows {
+        let usage = crate::budgets::usage(&mut conn, budget, now).map_err(|e| {
+            error!("Failed to compute budget spend: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        budgets.push(usage.info());
+    }
+
+    Ok(Json(SpendBudgetsResponse { budgets }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/admin/budgets",
+    tag = "admin",
+    request_body = SetSpendBudgetRequest,
+    responses(
+        (status = 200, description = "Budget set, replacing the target's budget for the period", body = SpendBudgetInfo),
+        (status = 400, description = "Not exactly one of user_id and team_id, or invalid limits"),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "User or team not found")
+    )
+)]
+pub async fn set_budget(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Json(req): Json<SetSpendBudgetRequest>,
+) -> Result<Json<SpendBudgetInfo>, StatusCode> {
+    use schema::spend_budgets;
+
+    let admin = require_admin(&app_state, &cookies).await?;
+    if req.user_id.is_some() == req.team_id.is_some() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    shared::api::validate_budget_limits(req.soft_limit_usd, req.hard_limit_usd)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let target_exists = match (req.user_id, req.team_id) {
+        (Some(user_id), _) => {
+            diesel::select(diesel::dsl::exists(schema::users::table.find(user_id)))
+                .get_result::<bool>(&mut conn)
+        }
+        (None, Some(team_id)) => {
+            diesel::select(diesel::dsl::exists(schema::teams::table.find(team_id)))
+                .get_result::<bool>(&mut conn)
+        }
+        (None, None) => Ok(false),
+    }
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !target_exists {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let existing: Option<Uuid> = match (req.user_id, req.team_id) {
+        (Some(user_id), _) => spend_budgets::table
+            .filter(spend_budgets::user_id.eq(user_id))
+            .filter(spend_budgets::period.eq(req.period.as_str()))
+            .select(spend_budgets::id)
+            .first(&mut conn)
+            .optional(),
+        (None, Some(team_id)) => spend_budgets::table
+            .filter(spend_budgets::team_id.eq(team_id))
+            .filter(spend_budgets::period.eq(req.period.as_str()))
+            .select(spend_budgets::id)
+            .first(&mut conn)
+            .optional(),
+        (None, None) => Ok(None),
+    }
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // New limits start alerting afresh, even within the current period
+    let budget: crate::models::SpendBudget = match existing {
+        Some(id) => diesel::update(spend_budgets::table.find(id))
+            .set((
+                spend_budgets::soft_limit_usd.eq(req.soft_limit_usd),
+                spend_budgets::hard_limit_usd.eq(req.hard_limit_usd),
+                spend_budgets::soft_notified_at.eq(None::<chrono::NaiveDateTime>),
+                spend_budgets::hard_notified_at.eq(None::<chrono::NaiveDateTime>),
+                spend_budgets::updated_at.eq(diesel::dsl::now),
+            ))
+            .get_result(&mut conn),
+        None => diesel::insert_into(spend_budgets::table)
+            .values(&crate::models::NewSpendBudget {
+                user_id: req.user_id,
+                team_id: req.team_id,
+                period: req.period.as_str().to_string(),
+                soft_limit_usd: req.soft_limit_usd,
+                hard_limit_usd: req.hard_limit_usd,
+            })
+            .get_result(&mut conn),
+    }
+    .map_err(|e| {
+        error!("Failed to save budget: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let usage = crate::budgets::usage(&mut conn, budget, chrono::Utc::now().naive_utc())
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    info!(
+        "Admin {} set the {} budget of {}: soft {:?}, hard {:?}",
+        admin.email,
+        usage.period.as_str(),
+        usage.target_name,
+        req.soft_limit_usd,
+        req.hard_limit_usd
+    );
+    crate::audit::record(
+        &mut conn,
+        Some(admin.id),
+        AuditAction::BudgetSet,
+        Some(usage.budget.id),
+        json!({
+            "target": usage.target_name,
+            "user_id": req.user_id,
+            "team_id": req.team_id,
+            "period": usage.period.as_str(),
+            "soft_limit_usd": req.soft_limit_usd,
+            "hard_limit_usd": req.hard_limit_usd,
+        }),
+    );
+    Ok(Json(usage.info()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/budgets/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Budget ID")),
+    responses(
+        (status = 204, description = "Budget removed"),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "Budget not found")
+    )
+)]
+pub async fn delete_budget(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(budget_id): Path<Uuid>,
+) -> Result<StatusCode, StatusCode> {
+    let admin = require_admin(&app_state, &cookies).await?;
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let deleted: Option<crate::models::SpendBudget> =
+        diesel::delete(schema::spend_budgets::table.find(budget_id))
+            .get_result(&mut conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to delete budget: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+    let Some(deleted) = deleted else {
+        return Err(StatusCode::NOT_FOUND);
+    };
 
+    info!("Admin {} removed budget {}", admin.email, budget_id);
+    crate::audit::record(
+        &mut conn,
+        Some(admin.id),
+        AuditAction::BudgetDelete,
+        Some(budget_id),
+        json!({
+            "user_id": deleted.user_id,
+            "team_id": deleted.team_id,
+            "period": deleted.period,
+            "soft_limit_usd": deleted.soft_limit_usd,
+            "hard_limit_usd": deleted.hard_limit_usd,
+        }),
+    );
     Ok(StatusCode::NO_CONTENT)
 }
+
+// ============================================================================
+// Invites Endpoints - Codes that get past the email allowlist
+// ============================================================================
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/invites",
+    tag = "admin",
+    responses((status = 200, description = "Every invite with who redeemed it", body = InvitesResponse), (status = 403, description = "Not an admin"))
+)]
+pub async fn list_invites(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<Json<InvitesResponse>, StatusCode> {
+    let admin = require_admin(&app_state, &cookies).await?;
+    info!("Admin {} requested invites", admin.email);
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let invites = invites::list(&mut conn).map_err(|e| {
+        error!("Failed to load invites: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(InvitesResponse { invites }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/invites",
+    tag = "admin",
+    request_body = CreateInviteRequest,
+    responses(
+        (status = 200, description = "Invite opened; its link is shown only once", body = CreateInviteResponse),
+        (status = 400, description = "Uses or lifetime out of range"),
+        (status = 403, description = "Not an admin")
+    )
+)]
+pub async fn create_invite(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Json(req): Json<CreateInviteRequest>,
+) -> Result<Json<CreateInviteResponse>, StatusCode> {
+    let admin = require_admin(&app_state, &cookies).await?;
+    if !(1..=MAX_INVITE_USES).contains(&req.max_uses)
+        || !(1..=MAX_INVITE_DAYS).contains(&req.expires_in_days)
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let label = req
+        .label
+        .map(|l| l.trim().chars().take(255).collect::<String>())
+        .filter(|l| !l.is_empty());
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let code = invites::generate_code();
+    let expires_at =
+        chrono::Utc::now().naive_utc() + chrono::Duration::days(req.expires_in_days.into());
+    let invite: crate::models::Invite = diesel::insert_into(schema::invites::table)
+        .values(&crate::models::NewInvite {
+            token_hash: crate::jwt::hash_token(&code),
+            label,
+            role: req.role.as_str().to_string(),
+            max_uses: req.max_uses as i32,
+            created_by: Some(admin.id),
+            expires_at,
+        })
+        .returning(crate::models::Invite::as_returning())
+        .get_result(&mut conn)
+        .map_err(|e| {
+            error!("Failed to create invite: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    info!(
+        "Admin {} opened invite {} ({}, {} uses)",
+        admin.email,
+        invite.id,
+        req.role.as_str(),
+        req.max_uses
+    );
+    crate::audit::record(
+        &mut conn,
+        Some(admin.id),
+        AuditAction::InviteCreate,
+        Some(invite.id),
+        json!({
+            "label": invite.label,
+            "role": req.role.as_str(),
+            "max_uses": req.max_uses,
+            "expires_at": invite.expires_at.and_utc().to_rfc3339(),
+        }),
+    );
+
+    Ok(Json(CreateInviteResponse {
+        invite_url: invites::invite_url(&app_state.public_url, &code),
+        invite: invites::info(invite, Some(admin.email), Vec::new()),
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/invites/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Invite ID")),
+    responses(
+        (status = 204, description = "Invite revoked; people who already redeemed it keep their access"),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "Invite not found or already revoked")
+    )
+)]
+pub async fn revoke_invite(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(invite_id): Path<Uuid>,
+) -> Result<StatusCode, StatusCode> {
+    use schema::invites;
+
+    let admin = require_admin(&app_state, &cookies).await?;
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let revoked: Option<crate::models::Invite> = diesel::update(
+        invites::table
+            .find(invite_id)
+            .filter(invites::revoked.eq(false)),
+    )
+    .set(invites::revoked.eq(true))
+    .returning(crate::models::Invite::as_returning())
+    .get_result(&mut conn)
+    .optional()
+    .map_err(|e| {
+        error!("Failed to revoke invite: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let Some(revoked) = revoked else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+
+    info!("Admin {} revoked invite {}", admin.email, invite_id);
+    crate::audit::record(
+        &mut conn,
+        Some(admin.id),
+        AuditAction::InviteRevoke,
+        Some(invite_id),
+        json!({ "label": revoked.label, "use_count": revoked.use_count }),
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Settings Endpoints - Instance settings that apply without a restart
+// ============================================================================
+
+fn settings_response(app_state: &AppState) -> InstanceSettingsResponse {
+    let settings = app_state.settings.get();
+    let defaults = app_state.settings.defaults().clone();
+    InstanceSettingsResponse {
+        overridden: crate::settings::overridden(&settings, &defaults),
+        settings,
+        defaults,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/settings",
+    tag = "admin",
+    responses((status = 200, description = "Settings in effect and their defaults from the environment", body = InstanceSettingsResponse), (status = 403, description = "Not an admin"))
+)]
+pub async fn get_settings(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<Json<InstanceSettingsResponse>, StatusCode> {
+    let admin = require_admin(&app_state, &cookies).await?;
+    info!("Admin {} requested instance settings", admin.email);
+    Ok(Json(settings_response(&app_state)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/admin/settings",
+    tag = "admin",
+    request_body = InstanceSettings,
+    responses(
+        (status = 200, description = "Settings saved and in effect", body = InstanceSettingsResponse),
+        (status = 400, description = "A setting is out of range", body = String),
+        (status = 403, description = "Not an admin")
+    )
+)]
+pub async fn update_settings(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Json(req): Json<InstanceSettings>,
+) -> Result<Json<InstanceSettingsResponse>, (StatusCode, String)> {
+    let admin = require_admin(&app_state, &cookies)
+        .await
+        .map_err(|status| (status, String::new()))?;
+    let settings = crate::settings::normalize(req);
+    settings
+        .validate()
+        .map_err(|message| (StatusCode::BAD_REQUEST, message.to_string()))?;
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, String::new()))?;
+
+    let changed = app_state
+        .settings
+        .save(&mut conn, settings.clone(), admin.id)
+        .map_err(|e| {
+            error!("Failed to save instance settings: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+        })?;
+
+    if !changed.is_empty() {
+        info!(
+            "Admin {} changed settings: {}",
+            admin.email,
+            changed.join(", ")
+        );
+        let values = serde_json::to_value(&settings).unwrap_or_default();
+        let details: serde_json::Map<String, serde_json::Value> = changed
+            .iter()
+            .map(|key| (key.clone(), values[key.as_str()].clone()))
+            .collect();
+        crate::audit::record(
+            &mut conn,
+            Some(admin.id),
+            AuditAction::SettingsUpdate,
+            None,
+            json!({ "changed": details }),
+        );
+    }
+
+    Ok(Json(settings_response(&app_state)))
+}
+
+// ============================================================================
+// Audit Endpoints - Who changed what
+// ============================================================================
+
+/// Events returned per page unless `limit` says otherwise
+const AUDIT_PAGE_SIZE: i64 = 100;
+const AUDIT_MAX_PAGE_SIZE: i64 = 1000;
+/// Most events one export holds
+const AUDIT_EXPORT_LIMIT: i64 = 100_000;
+
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    /// Exact action, e.g. `user.update`
+    pub action: Option<String>,
+    /// `user`, `session`, `team`, `budget`, `proxy_token`, `api_key`,
+    /// `share_link`, `launcher`, `scheduled_task` or `invite`
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    /// Part of the actor's email
+    pub actor: Option<String>,
+    /// First day included, `YYYY-MM-DD` (UTC)
+    pub since: Option<String>,
+    /// Last day included, `YYYY-MM-DD` (UTC)
+    pub until: Option<String>,
+    /// Events per page (default 100, at most 1000; ignored by the export)
+    pub limit: Option<i64>,
+    /// Events to skip (ignored by the export)
+    pub offset: Option<i64>,
+}
+
+impl AuditQuery {
+    fn filter(&self) -> Result<crate::audit::AuditFilter, StatusCode> {
+        let non_empty = |v: &Option<String>| v.clone().filter(|v| !v.trim().is_empty());
+        let day = |v: &Option<String>| {
+            non_empty(v)
+                .map(|d| chrono::NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d"))
+                .transpose()
+                .map_err(|_| StatusCode::BAD_REQUEST)
+        };
+        let action = non_empty(&self.action)
+            .map(|a| a.parse::<AuditAction>())
+            .transpose()
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+        Ok(crate::audit::AuditFilter {
+            action,
+            target_type: non_empty(&self.target_type),
+            target_id: self.target_id,
+            actor: non_empty(&self.actor),
+            since: day(&self.since)?,
+            until: day(&self.until)?,
+        })
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/audit",
+    tag = "admin",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Matching audit events, newest first", body = AuditEventsResponse),
+        (status = 400, description = "Unknown action or malformed date"),
+        (status = 403, description = "Not an admin")
+    )
+)]
+pub async fn list_audit_events(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<AuditEventsResponse>, StatusCode> {
+    require_admin(&app_state, &cookies).await?;
+    let filter = query.filter()?;
+    let limit = query
+        .limit
+        .unwrap_or(AUDIT_PAGE_SIZE)
+        .clamp(1, AUDIT_MAX_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // One extra row tells whether there is another page
+    let mut events = crate::audit::query(&mut conn, &filter, limit + 1, offset).map_err(|e| {
+        error!("Failed to load audit events: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let has_more = events.len() as i64 > limit;
+    events.truncate(limit as usize);
+
+    Ok(Json(AuditEventsResponse {
+        events: events.into_iter().map(crate::audit::info).collect(),
+        has_more,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/audit/export",
+    tag = "admin",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Matching audit events as a JSON file, newest first", body = [AuditEventInfo]),
+        (status = 400, description = "Unknown action or malformed date"),
+        (status = 403, description = "Not an admin")
+    )
+)]
+pub async fn export_audit_events(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Query(query): Query<AuditQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let admin = require_admin(&app_state, &cookies).await?;
+    let filter = query.filter()?;
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let events = crate::audit::query(&mut conn, &filter, AUDIT_EXPORT_LIMIT, 0).map_err(|e| {
+        error!("Failed to load audit events: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let events: Vec<AuditEventInfo> = events.into_iter().map(crate::audit::info).collect();
+    let body = serde_json::to_string_pretty(&events).map_err(|e| {
+        error!("Failed to serialize audit events: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    info!(
+        "Admin {} exported {} audit events",
+        admin.email,
+        events.len()
+    );
+
+    let file_name = crate::audit::export_file_name(chrono::Utc::now().naive_utc());
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name),
+            ),
+        ],
+        body,
+    ))
+}
@@ -0,0 +1,302 @@
+//! Usage Analytics Handlers
+//!
+//! Daily cost and token usage from `usage_daily`, broken down by model, user,
+//! host, repository, agent type or scheduled task. Users see their own usage;
+//! admins can ask for everyone's.
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{Duration, NaiveDate};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Date, Double, Nullable, Text, Uuid as SqlUuid};
+use serde::Deserialize;
+use shared::api::{
+    AnalyticsGroup, AnalyticsGroupBy, AnalyticsPoint, AnalyticsResponse, UsageTotals,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tower_cookies::Cookies;
+use uuid::Uuid;
+
+use crate::schema::{scheduled_tasks, users};
+use crate::{auth::extract_user_id, errors::AppError, AppState};
+
+const DEFAULT_DAYS: i64 = 30;
+const MAX_DAYS: i64 = 366;
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AnalyticsQuery {
+    /// `model` (default), `user`, `host`, `repo`, `agent_type` or `task`
+    pub group_by: Option<String>,
+    /// Days to cover, ending today (default 30, at most 366)
+    pub days: Option<i64>,
+    /// Everyone's usage instead of only the caller's (admins only)
+    #[serde(default)]
+    pub all: bool,
+}
+
+#[derive(QueryableByName)]
+struct UsageRow {
+    #[diesel(sql_type = Date)]
+    day: NaiveDate,
+    #[diesel(sql_type = Text)]
+    key: String,
+    #[diesel(sql_type = BigInt)]
+    turns: i64,
+    #[diesel(sql_type = BigInt)]
+    input_tokens: i64,
+    #[diesel(sql_type = BigInt)]
+    output_tokens: i64,
+    #[diesel(sql_type = BigInt)]
+    cache_creation_tokens: i64,
+    #[diesel(sql_type = BigInt)]
+    cache_read_tokens: i64,
+    #[diesel(sql_type = Double)]
+    cost_usd: f64,
+    #[diesel(sql_type = BigInt)]
+    duration_ms: i64,
+}
+
+/// The column a grouping reads, as text. Empty when a session had no
+/// repository or task.
+fn group_key(group_by: AnalyticsGroupBy) -> &'static str {
+    match group_by {
+        AnalyticsGroupBy::Model => "model",
+        AnalyticsGroupBy::User => "user_id::text",
+        AnalyticsGroupBy::Host => "hostname",
+        AnalyticsGroupBy::Repo => "COALESCE(repo_url, '')",
+        AnalyticsGroupBy::AgentType => "agent_type",
+        AnalyticsGroupBy::Task => "COALESCE(scheduled_task_id::text, '')",
+    }
+}
+
+fn usage_sql(group_by: AnalyticsGroupBy) -> String {
+    format!(
+        "SELECT day, {} AS key, \
+                SUM(turns)::INT8 AS turns, \
+                SUM(input_tokens)::INT8 AS input_tokens, \
+                SUM(output_tokens)::INT8 AS output_tokens, \
+                SUM(cache_creation_tokens)::INT8 AS cache_creation_tokens, \
+                SUM(cache_read_tokens)::INT8 AS cache_read_tokens, \
+                SUM(cost_usd) AS cost_usd, \
+                SUM(duration_ms)::INT8 AS duration_ms \
+         FROM usage_daily \
+         WHERE day >= $1 AND ($2::uuid IS NULL OR user_id = $2) \
+         GROUP BY 1, 2 \
+         ORDER BY 1, 2",
+        group_key(group_by)
+    )
+}
+
+/// A repository URL without its scheme and `.git` suffix
+fn repo_label(url: &str) -> String {
+    let url = url.trim_end_matches(".git");
+    url.split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(url)
+        .to_string()
+}
+
+/// How to show each group: user and task IDs become names.
+fn group_labels(
+    conn: &mut PgConnection,
+    group_by: AnalyticsGroupBy,
+    keys: &[String],
+) -> QueryResult<HashMap<String, String>> {
+    let ids: Vec<Uuid> = keys.iter().filter_map(|k| k.parse().ok()).collect();
+    let mut labels: HashMap<String, String> = match group_by {
+        AnalyticsGroupBy::User => users::table
+            .filter(users::id.eq_any(&ids))
+            .select((users::id, users::name, users::email))
+            .load::<(Uuid, Option<String>, String)>(conn)?
+            .into_iter()
+            .map(|(id, name, email)| (id.to_string(), name.unwrap_or(email)))
+            .collect(),
+        AnalyticsGroupBy::Task => scheduled_tasks::table
+            .filter(scheduled_tasks::id.eq_any(&ids))
+            .select((scheduled_tasks::id, scheduled_tasks::name))
+            .load::<(Uuid, String)>(conn)?
+            .into_iter()
+            .map(|(id, name)| (id.to_string(), name))
+            .collect(),
+        _ => HashMap::new(),
+    };
+    for key in keys {
+        if labels.contains_key(key) {
+            continue;
+        }
+        let label = match (group_by, key.as_str()) {
+            (AnalyticsGroupBy::Repo, "") => "No repository".to_string(),
+            (AnalyticsGroupBy::Repo, url) => repo_label(url),
+            (AnalyticsGroupBy::Task, "") => "Not scheduled".to_string(),
+            (AnalyticsGroupBy::Task, _) => "Deleted task".to_string(),
+            (AnalyticsGroupBy::User, _) => "Deleted user".to_string(),
+            (_, key) => key.to_string(),
+        };
+        labels.insert(key.clone(), label);
+    }
+    Ok(labels)
+}
+
+/// Sum the daily points of each group, costliest first.
+fn summarize(points: &[AnalyticsPoint], labels: &HashMap<String, String>) -> Vec<AnalyticsGroup> {
+    let mut totals: HashMap<&str, UsageTotals> = HashMap::new();
+    for point in points {
+        totals.entry(&point.key).or_default().add(&point.usage);
+    }
+    let mut groups: Vec<AnalyticsGroup> = totals
+        .into_iter()
+        .map(|(key, usage)| AnalyticsGroup {
+            key: key.to_string(),
+            label: labels.get(key).cloned().unwrap_or_else(|| key.to_string()),
+            usage,
+        })
+        .collect();
+    groups.sort_by(|a, b| {
+        b.usage
+            .cost_usd
+            .total_cmp(&a.usage.cost_usd)
+            .then_with(|| a.label.cmp(&b.label))
+    });
+    groups
+}
+
+/// GET /api/analytics?group_by=model&days=30
+///
+/// Daily usage, broken down by `group_by`. Turns show up here within a few
+/// minutes, once the daily rollup has run.
+#[utoipa::path(
+    get,
+    path = "/api/analytics",
+    tag = "analytics",
+    params(AnalyticsQuery),
+    responses(
+        (status = 200, body = AnalyticsResponse),
+        (status = 400, description = "Unknown grouping"),
+        (status = 401, description = "Not signed in"),
+        (status = 403, description = "`all` was asked for by someone who is not an admin")
+    )
+)]
+pub async fn get_analytics(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Query(query): Query<AnalyticsQuery>,
+) -> Result<Json<AnalyticsResponse>, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    let group_by = match query.group_by.as_deref() {
+        None | Some("") => AnalyticsGroupBy::default(),
+        Some(value) => value
+            .parse()
+            .map_err(|_| AppError::BadRequest("Unknown grouping"))?,
+    };
+    let days = query.days.unwrap_or(DEFAULT_DAYS).clamp(1, MAX_DAYS);
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    if query.all {
+        let is_admin: bool = users::table
+            .find(user_id)
+            .select(users::is_admin)
+            .first(&mut conn)
+            .map_err(|e| AppError::DbQuery(e.to_string()))?;
+        if !is_admin {
+            return Err(AppError::Forbidden);
+        }
+    }
+
+    let today = chrono::Utc::now().date_naive();
+    let from = today - Duration::days(days - 1);
+    let rows: Vec<UsageRow> = diesel::sql_query(usage_sql(group_by))
+        .bind::<Date, _>(from)
+        .bind::<Nullable<SqlUuid>, _>((!query.all).then_some(user_id))
+        .load(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    let points: Vec<AnalyticsPoint> = rows
+        .into_iter()
+        .map(|r| AnalyticsPoint {
+            day: r.day.to_string(),
+            key: r.key,
+            usage: UsageTotals {
+                turns: r.turns,
+                input_tokens: r.input_tokens,
+                output_tokens: r.output_tokens,
+                cache_creation_tokens: r.cache_creation_tokens,
+                cache_read_tokens: r.cache_read_tokens,
+                cost_usd: r.cost_usd,
+                duration_ms: r.duration_ms,
+            },
+        })
+        .collect();
+
+    let mut keys: Vec<String> = points.iter().map(|p| p.key.clone()).collect();
+    keys.sort();
+    keys.dedup();
+    let labels =
+        group_labels(&mut conn, group_by, &keys).map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(Json(AnalyticsResponse {
+        group_by,
+        from: from.to_string(),
+        to: today.to_string(),
+        all_users: query.all,
+        groups: summarize(&points, &labels),
+        points,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(day: &str, key: &str, cost_usd: f64, turns: i64) -> AnalyticsPoint {
+        AnalyticsPoint {
+            day: day.to_string(),
+            key: key.to_string(),
+            usage: UsageTotals {
+                turns,
+                cost_usd,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_summarize_orders_by_cost() {
+        let points = vec![
+            point("2026-03-24", "claude-haiku", 0.5, 10),
+            point("2026-03-24", "claude-opus", 2.0, 3),
+            point("2026-03-25", "claude-haiku", 0.25, 4),
+        ];
+        let labels = HashMap::new();
+        let groups = summarize(&points, &labels);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].key, "claude-opus");
+        assert_eq!(groups[1].usage.turns, 14);
+        assert!((groups[1].usage.cost_usd - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_repo_label() {
+        assert_eq!(
+            repo_label("https://github.com/acme/portal.git"),
+            "github.com/acme/portal"
+        );
+        assert_eq!(
+            repo_label("git@github.com:acme/portal"),
+            "git@github.com:acme/portal"
+        );
+    }
+
+    #[test]
+    fn test_group_by_round_trips() {
+        for group_by in AnalyticsGroupBy::ALL {
+            assert_eq!(group_by.as_str().parse(), Ok(group_by));
+            assert!(!group_key(group_by).is_empty());
+        }
+        assert!("session".parse::<AnalyticsGroupBy>().is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! API Key Management Handlers
+//!
+//! Endpoints for creating, listing and revoking personal API keys. These are
+//! cookie-only: an API key cannot be used to mint or revoke keys.
+//! Authentication with a key lives in `crate::api_keys`.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use diesel::prelude::*;
+use shared::api::{
+    ApiKeyInfo, ApiKeyListResponse, ApiKeyScope, AuditAction, CreateApiKeyRequest,
+    CreateApiKeyResponse, MAX_API_KEY_DAYS,
+};
+use std::sync::Arc;
+use tower_cookies::Cookies;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    api_keys,
+    errors::AppError,
+    jwt::hash_token,
+    models::{ApiKey, NewApiKey},
+    schema::api_keys as api_keys_table,
+    AppState,
+};
+
+fn api_key_to_info(k: ApiKey) -> ApiKeyInfo {
+    let scopes: Vec<String> = serde_json::from_value(k.scopes).unwrap_or_default();
+    ApiKeyInfo {
+        id: k.id,
+        name: k.name,
+        prefix: k.key_prefix,
+        scopes: scopes.iter().filter_map(|s| s.parse().ok()).collect(),
+        created_at: k.created_at.and_utc().to_rfc3339(),
+        last_used_at: k.last_used_at.map(|t| t.and_utc().to_rfc3339()),
+        expires_at: k.expires_at.and_utc().to_rfc3339(),
+        revoked: k.revoked,
+    }
+}
+
+/// Validate a scope list and store it as a JSON array of scope names.
+fn scopes_to_json(scopes: &[ApiKeyScope]) -> Result<serde_json::Value, AppError> {
+    if scopes.is_empty() {
+        return Err(AppError::BadRequest("API key needs at least one scope"));
+    }
+    let mut names: Vec<&str> = scopes.iter().map(|s| s.as_str()).collect();
+    names.sort_unstable();
+    names.dedup();
+    Ok(serde_json::json!(names))
+}
+
+/// GET /api/api-keys
+#[utoipa::path(
+    get,
+    path = "/api/api-keys",
+    tag = "credentials",
+    responses((status = 200, body = ApiKeyListResponse))
+)]
+pub async fn list_api_keys_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<Json<ApiKeyListResponse>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let keys: Vec<ApiKey> = api_keys_table::table
+        .filter(api_keys_table::user_id.eq(user_id))
+        .order(api_keys_table::created_at.desc())
+        .load(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(Json(ApiKeyListResponse {
+        api_keys: keys.into_iter().map(api_key_to_info).collect(),
+    }))
+}
+
+/// POST /api/api-keys
+#[utoipa::path(
+    post,
+    path = "/api/api-keys",
+    tag = "credentials",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 200, description = "The key, shown only once", body = CreateApiKeyResponse),
+        (status = 400, description = "Invalid name, scopes or expiry")
+    )
+)]
+pub async fn create_api_key_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Json(req): Json<CreateApiKeyRequest>,
+) -> Result<Json<CreateApiKeyResponse>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let name = req.name.trim();
+    if name.is_empty() || name.chars().count() > 255 {
+        return Err(AppError::BadRequest(
+            "API key name must be between 1 and 255 characters",
+        ));
+    }
+    if !(1..=MAX_API_KEY_DAYS).contains(&req.expires_in_days) {
+        return Err(AppError::BadRequest(
+            "API keys must expire within 1 to 365 days",
+        ));
+    }
+    let scopes = scopes_to_json(&req.scopes)?;
+
+    let key = api_keys::generate_key();
+    let expires_at = chrono::Utc::now() + chrono::Duration::days(req.expires_in_days as i64);
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let new_key = NewApiKey {
+        user_id,
+        name: name.to_string(),
+        key_prefix: api_keys::display_prefix(&key),
+        key_hash: hash_token(&key),
+        scopes,
+        expires_at: expires_at.naive_utc(),
+    };
+
+    let saved: ApiKey = diesel::insert_into(api_keys_table::table)
+        .values(&new_key)
+        .get_result(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    info!(
+        "Created API key {} ({}) for user {}",
+        saved.id, saved.name, user_id
+    );
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::ApiKeyCreate,
+        Some(saved.id),
+        serde_json::json!({
+            "name": saved.name,
+            "scopes": saved.scopes,
+            "expires_at": saved.expires_at.and_utc().to_rfc3339(),
+        }),
+    );
+
+    Ok(Json(CreateApiKeyResponse {
+        api_key: api_key_to_info(saved),
+        key,
+    }))
+}
+
+/// DELETE /api/api-keys/:id - Revoke a key
+#[utoipa::path(
+    delete,
+    path = "/api/api-keys/{id}",
+    tag = "credentials",
+    params(("id" = Uuid, Path, description = "API key ID")),
+    responses(
+        (status = 204, description = "Key revoked"),
+        (status = 404, description = "Key not found")
+    )
+)]
+pub async fn revoke_api_key_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(key_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let name: Option<String> = diesel::update(
+        api_keys_table::table
+            .filter(api_keys_table::id.eq(key_id))
+            .filter(api_keys_table::user_id.eq(user_id)),
+    )
+    .set(api_keys_table::revoked.eq(true))
+    .returning(api_keys_table::name)
+    .get_result(&mut conn)
+    .optional()
+    .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    let Some(name) = name else {
+        return Err(AppError::NotFound("API key"));
+    };
+
+    info!("Revoked API key {}", key_id);
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::ApiKeyRevoke,
+        Some(key_id),
+        serde_json::json!({ "name": name }),
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes_to_json_dedups_and_rejects_empty() {
+        let json = scopes_to_json(&[
+            ApiKeyScope::Launch,
+            ApiKeyScope::ReadSessions,
+            ApiKeyScope::Launch,
+        ])
+        .unwrap();
+        assert_eq!(json, serde_json::json!(["launch", "sessions:read"]));
+        assert!(scopes_to_json(&[]).is_err());
+    }
+}
@@ -1,67 +1,247 @@
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
-    response::{IntoResponse, Redirect},
+    extract::{ConnectInfo, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
     Json,
 };
 use diesel::prelude::*;
-use oauth2::{AuthorizationCode, CsrfToken, Scope, TokenResponse};
+use oauth2::CsrfToken;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shared::api::{AuditAction, InviteCheckResponse};
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tower_cookies::{cookie::SameSite, Cookie, Cookies};
 use tracing::{error, info};
 use uuid::Uuid;
 
 use crate::{
+    errors::AppError,
+    invites::{self, Redemption},
+    local_accounts::LOCAL_PROVIDER_ID,
     models::{NewUser, User},
+    oidc::{PendingLogin, GOOGLE_PROVIDER_ID},
+    web_logins::{self, ClientInfo},
     AppState,
 };
 
 use shared::protocol::SESSION_COOKIE_NAME;
+use shared::LoginProvider;
+
+/// Remembers an in-progress login (state, nonce, PKCE verifier) until the
+/// provider redirects back
+const OAUTH_LOGIN_COOKIE: &str = "oauth_login";
+const OAUTH_LOGIN_COOKIE_PATH: &str = "/api/auth";
+/// Holds the code of an invite link opened in this browser until the login
+/// callback redeems it
+const INVITE_COOKIE: &str = "invite";
+
+/// Ways to sign in, in the order the splash page offers them
+pub fn login_providers(app_state: &AppState) -> Vec<LoginProvider> {
+    let mut providers = app_state.oidc.login_options();
+    if app_state.local_accounts_enabled {
+        providers.push(LoginProvider {
+            id: LOCAL_PROVIDER_ID.to_string(),
+            name: "username and password".to_string(),
+            login_url: "/login".to_string(),
+        });
+    }
+    providers
+}
 
-const OAUTH_CSRF_COOKIE: &str = "oauth_csrf";
+/// Sign the browser in as `user_id`, recording the login so it can be listed
+/// and revoked
+pub(crate) fn set_session_cookie(
+    app_state: &AppState,
+    cookies: &Cookies,
+    user_id: Uuid,
+    client: &ClientInfo,
+) -> Result<(), AppError> {
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let login_id = web_logins::create(&mut conn, user_id, client)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    let mut cookie = Cookie::new(SESSION_COOKIE_NAME, login_id.to_string());
+    cookie.set_path("/");
+    cookie.set_http_only(true);
+    cookie.set_secure(!app_state.dev_mode); // Don't require HTTPS in dev mode
+    cookie.set_same_site(SameSite::Lax);
+    cookies.signed(&app_state.cookie_key).add(cookie);
+    Ok(())
+}
 
-/// Regular web login - redirects to Google OAuth
-pub async fn login(State(app_state): State<Arc<AppState>>, cookies: Cookies) -> impl IntoResponse {
-    let client = match &app_state.oauth_basic_client {
-        Some(c) => c,
-        None => return Redirect::temporary("/api/auth/dev-login").into_response(),
-    };
+/// Remove the session cookie by setting it with empty value and immediate
+/// expiry
+pub(crate) fn clear_session_cookie(app_state: &AppState, cookies: &Cookies) {
+    let mut cookie = Cookie::new(SESSION_COOKIE_NAME, "");
+    cookie.set_path("/");
+    cookie.set_http_only(true);
+    cookie.set_secure(true);
+    cookie.set_same_site(SameSite::Lax);
+    cookie.set_max_age(tower_cookies::cookie::time::Duration::ZERO);
+    cookies.signed(&app_state.cookie_key).add(cookie);
+}
+
+fn login_failed(e: AppError) -> StatusCode {
+    error!("Failed to record login: {:?}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
 
-    let auth_request = client
-        .authorize_url(CsrfToken::new_random)
-        .add_scope(Scope::new("openid".to_string()))
-        .add_scope(Scope::new("email".to_string()))
-        .add_scope(Scope::new("profile".to_string()));
+/// Redirect the browser to `provider_id` to sign in. `state` comes back to
+/// the callback; device logins prefix it with `device:`.
+async fn start_login(
+    app_state: &AppState,
+    cookies: &Cookies,
+    provider_id: &str,
+    state: String,
+) -> Result<Response, StatusCode> {
+    let provider = app_state
+        .oidc
+        .get(provider_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let (auth_url, pending) = provider.authorization_url(state).await.map_err(|e| {
+        error!("Login provider {} is unavailable: {:#}", provider_id, e);
+        StatusCode::SERVICE_UNAVAILABLE
+    })?;
+    let pending = serde_json::to_string(&pending).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Keep the login in a short-lived signed cookie so the callback can check
+    // the state and redeem the code
+    let mut login_cookie = Cookie::new(OAUTH_LOGIN_COOKIE, pending);
+    login_cookie.set_path(OAUTH_LOGIN_COOKIE_PATH);
+    login_cookie.set_http_only(true);
+    login_cookie.set_secure(!app_state.dev_mode);
+    login_cookie.set_same_site(SameSite::Lax);
+    login_cookie.set_max_age(tower_cookies::cookie::time::Duration::minutes(10));
+    cookies.signed(&app_state.cookie_key).add(login_cookie);
+
+    Ok(Redirect::temporary(&auth_url).into_response())
+}
 
-    let (auth_url, csrf_token) = auth_request.url();
+/// Regular web login - redirects to Google
+#[utoipa::path(
+    get,
+    path = "/api/auth/google",
+    tag = "auth",
+    responses(
+        (status = 307, description = "Redirect to Google sign-in"),
+        (status = 404, description = "Google sign-in is not configured"),
+        (status = 503, description = "Google could not be reached")
+    ),
+    security(())
+)]
+pub async fn login(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<Response, StatusCode> {
+    if app_state.dev_mode {
+        return Ok(Redirect::temporary("/api/auth/dev-login").into_response());
+    }
+    let state = CsrfToken::new_random().secret().clone();
+    start_login(&app_state, &cookies, GOOGLE_PROVIDER_ID, state).await
+}
 
-    // Store the CSRF token in a short-lived signed cookie so we can verify it on callback.
-    let mut csrf_cookie = Cookie::new(OAUTH_CSRF_COOKIE, csrf_token.secret().clone());
-    csrf_cookie.set_path("/api/auth/google/callback");
-    csrf_cookie.set_http_only(true);
-    csrf_cookie.set_secure(!app_state.dev_mode);
-    csrf_cookie.set_same_site(SameSite::Lax);
-    csrf_cookie.set_max_age(tower_cookies::cookie::time::Duration::minutes(10));
-    cookies.signed(&app_state.cookie_key).add(csrf_cookie);
+/// Regular web login - redirects to an OIDC provider from `OIDC_PROVIDERS`
+#[utoipa::path(
+    get,
+    path = "/api/auth/oidc/{provider}",
+    tag = "auth",
+    params(("provider" = String, Path, description = "Provider ID")),
+    responses(
+        (status = 307, description = "Redirect to the provider's sign-in page"),
+        (status = 404, description = "No such provider"),
+        (status = 503, description = "The provider could not be reached")
+    ),
+    security(())
+)]
+pub async fn oidc_login(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(provider): Path<String>,
+) -> Result<Response, StatusCode> {
+    if app_state.dev_mode {
+        return Ok(Redirect::temporary("/api/auth/dev-login").into_response());
+    }
+    let state = CsrfToken::new_random().secret().clone();
+    start_login(&app_state, &cookies, &provider, state).await
+}
 
-    Redirect::temporary(auth_url.as_str()).into_response()
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct InviteQuery {
+    pub code: String,
+}
+
+/// Open an invite link: check the code and remember it for the login callback
+#[utoipa::path(
+    get,
+    path = "/api/auth/invite",
+    tag = "auth",
+    params(InviteQuery),
+    responses(
+        (status = 200, description = "The invite is open; sign in to redeem it", body = InviteCheckResponse),
+        (status = 404, description = "No such invite, or it is revoked, expired or used up")
+    ),
+    security(())
+)]
+pub async fn check_invite(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Query(query): Query<InviteQuery>,
+) -> Result<Json<InviteCheckResponse>, StatusCode> {
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let invite = invites::find_open(&mut conn, &query.code)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let mut invite_cookie = Cookie::new(INVITE_COOKIE, query.code);
+    invite_cookie.set_path(OAUTH_LOGIN_COOKIE_PATH);
+    invite_cookie.set_http_only(true);
+    invite_cookie.set_secure(!app_state.dev_mode);
+    invite_cookie.set_same_site(SameSite::Lax);
+    invite_cookie.set_max_age(tower_cookies::cookie::time::Duration::hours(1));
+    cookies.signed(&app_state.cookie_key).add(invite_cookie);
+
+    Ok(Json(InviteCheckResponse {
+        role: invites::role(&invite),
+        expires_at: invite.expires_at.and_utc().to_rfc3339(),
+    }))
 }
 
 /// Device flow login - separate endpoint that stores device_user_code in state
 /// This is used when the user needs to authenticate before approving a device
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct DeviceLoginQuery {
     pub device_user_code: String,
+    /// Provider to sign in with; defaults to the first one configured
+    pub provider: Option<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/auth/device-login",
+    tag = "auth",
+    params(DeviceLoginQuery),
+    responses(
+        (status = 307, description = "Redirect to sign-in, then back to the device approval page"),
+        (status = 404, description = "No such provider")
+    ),
+    security(())
+)]
 pub async fn device_login(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Query(query): Query<DeviceLoginQuery>,
-) -> Result<impl IntoResponse, StatusCode> {
+) -> Result<Response, StatusCode> {
     // In dev mode, auto-login and redirect to device approval page
-    if app_state.oauth_basic_client.is_none() {
+    if app_state.dev_mode {
         use crate::schema::users::dsl::*;
 
         let mut conn = app_state
@@ -80,13 +260,13 @@ pub async fn device_login(
         }
 
         info!("Dev mode: auto-logged in for device flow");
-
-        let mut cookie = Cookie::new(SESSION_COOKIE_NAME, user.id.to_string());
-        cookie.set_path("/");
-        cookie.set_http_only(true);
-        cookie.set_secure(false);
-        cookie.set_same_site(SameSite::Lax);
-        cookies.signed(&app_state.cookie_key).add(cookie);
+        set_session_cookie(
+            &app_state,
+            &cookies,
+            user.id,
+            &ClientInfo::new(&headers, peer, &app_state.trusted_proxies),
+        )
+        .map_err(login_failed)?;
 
         return Ok(Redirect::temporary(&format!(
             "/api/auth/device?user_code={}",
@@ -95,111 +275,146 @@ pub async fn device_login(
         .into_response());
     }
 
-    let client = app_state.oauth_basic_client.as_ref().unwrap();
-
-    // Use a prefixed state to identify this as a device flow callback
-    let state_value = format!("device:{}", query.device_user_code);
-
-    let auth_request = client
-        .authorize_url(|| CsrfToken::new(state_value))
-        .add_scope(Scope::new("openid".to_string()))
-        .add_scope(Scope::new("email".to_string()))
-        .add_scope(Scope::new("profile".to_string()));
+    let provider = match query.provider {
+        Some(provider) => provider,
+        None => match app_state.oidc.default_provider() {
+            Some(provider) => provider.config.id.clone(),
+            None if app_state.local_accounts_enabled => LOCAL_PROVIDER_ID.to_string(),
+            None => return Err(StatusCode::NOT_FOUND),
+        },
+    };
 
-    let (auth_url, _csrf_token) = auth_request.url();
+    // Local accounts sign in on the portal's own login page, which comes
+    // back to the approval page afterwards
+    if provider == LOCAL_PROVIDER_ID && app_state.local_accounts_enabled {
+        return Ok(Redirect::temporary(&format!(
+            "/login?device_user_code={}",
+            query.device_user_code
+        ))
+        .into_response());
+    }
 
-    Ok(Redirect::temporary(auth_url.as_str()).into_response())
+    // Use a prefixed state to identify this as a device flow callback
+    let state = format!("device:{}", query.device_user_code);
+    start_login(&app_state, &cookies, &provider, state).await
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct AuthCallbackQuery {
     code: String,
     state: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct GoogleUserInfo {
-    sub: String,
-    email: String,
-    name: Option<String>,
-    picture: Option<String>,
+#[utoipa::path(
+    get,
+    path = "/api/auth/google/callback",
+    tag = "auth",
+    params(AuthCallbackQuery),
+    responses(
+        (status = 307, description = "Signed in; redirect to the dashboard"),
+        (status = 403, description = "Sign-in refused"),
+        (status = 503, description = "OAuth is not configured")
+    ),
+    security(())
+)]
+pub async fn callback(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Query(query): Query<AuthCallbackQuery>,
+) -> Result<Redirect, StatusCode> {
+    let client = ClientInfo::new(&headers, peer, &app_state.trusted_proxies);
+    finish_login(&app_state, &cookies, &client, GOOGLE_PROVIDER_ID, query).await
 }
 
-pub async fn callback(
+#[utoipa::path(
+    get,
+    path = "/api/auth/oidc/{provider}/callback",
+    tag = "auth",
+    params(("provider" = String, Path, description = "Provider ID"), AuthCallbackQuery),
+    responses(
+        (status = 307, description = "Signed in; redirect to the dashboard"),
+        (status = 403, description = "Sign-in refused"),
+        (status = 404, description = "No such provider")
+    ),
+    security(())
+)]
+pub async fn oidc_callback(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Path(provider): Path<String>,
     Query(query): Query<AuthCallbackQuery>,
-) -> Result<impl IntoResponse, StatusCode> {
-    let client = app_state
-        .oauth_basic_client
-        .as_ref()
-        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
-
-    // Verify CSRF state token for regular web logins.
-    // Device flow uses a deterministic "device:{code}" state so we skip it there.
-    let is_device_flow = query
-        .state
-        .as_deref()
-        .is_some_and(|s| s.starts_with("device:"));
-
-    if !is_device_flow {
-        let csrf_cookie = cookies
-            .signed(&app_state.cookie_key)
-            .get(OAUTH_CSRF_COOKIE)
-            .ok_or_else(|| {
-                error!("OAuth callback: missing CSRF cookie");
-                StatusCode::FORBIDDEN
-            })?;
-
-        let state_value = query.state.as_deref().unwrap_or("");
-        if csrf_cookie.value() != state_value {
-            error!("OAuth callback: CSRF token mismatch");
-            return Err(StatusCode::FORBIDDEN);
-        }
+) -> Result<Redirect, StatusCode> {
+    let client = ClientInfo::new(&headers, peer, &app_state.trusted_proxies);
+    finish_login(&app_state, &cookies, &client, &provider, query).await
+}
 
-        // Consume the CSRF cookie
-        let mut remove_csrf = Cookie::new(OAUTH_CSRF_COOKIE, "");
-        remove_csrf.set_path("/api/auth/google/callback");
-        remove_csrf.set_max_age(tower_cookies::cookie::time::Duration::ZERO);
-        cookies.signed(&app_state.cookie_key).add(remove_csrf);
+/// Handle the provider redirecting back: check the login cookie, redeem the
+/// code, then find or create the user and sign them in
+async fn finish_login(
+    app_state: &AppState,
+    cookies: &Cookies,
+    client: &ClientInfo,
+    provider_id: &str,
+    query: AuthCallbackQuery,
+) -> Result<Redirect, StatusCode> {
+    if app_state.oidc.is_empty() {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
     }
-
-    // Exchange code for token
-    let token: oauth2::StandardTokenResponse<
-        oauth2::EmptyExtraTokenFields,
-        oauth2::basic::BasicTokenType,
-    > = client
-        .exchange_code(AuthorizationCode::new(query.code))
-        .request_async(oauth2::reqwest::async_http_client)
-        .await
-        .map_err(|e| {
-            error!("Failed to exchange code: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
+    let provider = app_state
+        .oidc
+        .get(provider_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    // The state must match the login this browser started, for device
+    // logins as much as web ones
+    let login_cookie = cookies
+        .signed(&app_state.cookie_key)
+        .get(OAUTH_LOGIN_COOKIE)
+        .ok_or_else(|| {
+            error!("OAuth callback: missing login cookie");
+            StatusCode::FORBIDDEN
         })?;
+    let pending: PendingLogin = serde_json::from_str(login_cookie.value()).map_err(|_| {
+        error!("OAuth callback: malformed login cookie");
+        StatusCode::FORBIDDEN
+    })?;
+    if pending.provider != provider_id || query.state.as_deref() != Some(pending.state.as_str()) {
+        error!("OAuth callback: state mismatch");
+        return Err(StatusCode::FORBIDDEN);
+    }
 
-    // Fetch user info from Google
-    let client = reqwest::Client::new();
-    let user_info: GoogleUserInfo = client
-        .get("https://www.googleapis.com/oauth2/v3/userinfo")
-        .bearer_auth(token.access_token().secret())
-        .send()
-        .await
-        .map_err(|e| {
-            error!("Failed to fetch user info: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?
-        .json()
+    // Consume the login cookie
+    let mut remove_login = Cookie::new(OAUTH_LOGIN_COOKIE, "");
+    remove_login.set_path(OAUTH_LOGIN_COOKIE_PATH);
+    remove_login.set_max_age(tower_cookies::cookie::time::Duration::ZERO);
+    cookies.signed(&app_state.cookie_key).add(remove_login);
+
+    let identity = provider
+        .complete_login(query.code, &pending)
         .await
         .map_err(|e| {
-            error!("Failed to parse user info: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
+            error!("Login with {} failed: {:#}", provider_id, e);
+            StatusCode::FORBIDDEN
         })?;
 
-    info!("User authenticated: {}", user_info.email);
-
-    // Check email access control
-    if let Err(redirect) = check_email_allowed(&app_state, &user_info.email) {
-        return Ok(redirect);
+    info!(
+        "User authenticated with {}: {}",
+        provider_id, identity.email
+    );
+
+    // Check group access control; the email allowlist is checked below,
+    // since invites get past it
+    if !provider.config.allows(&identity.groups) {
+        info!(
+            "Access denied for {} (not in an allowed {} group)",
+            identity.email, provider_id
+        );
+        return Ok(Redirect::temporary("/access-denied"));
     }
 
     // Save or update user in database
@@ -210,93 +425,179 @@ pub async fn callback(
 
     use crate::schema::users::dsl::*;
 
-    let user = users
-        .filter(google_id.eq(&user_info.sub))
+    let existing = users
+        .filter(auth_provider.eq(provider_id))
+        .filter(auth_subject.eq(&identity.subject))
         .first::<User>(&mut conn)
         .optional()
         .map_err(|e| {
-            error!("Failed to look up user by google_id: {}", e);
+            error!("Failed to look up user by identity: {}", e);
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
-    let user = match user {
-        Some(user) => user,
-        None => {
-            let new_user = NewUser {
-                google_id: user_info.sub,
-                email: user_info.email,
-                name: user_info.name,
-                avatar_url: user_info.picture,
-            };
-
-            diesel::insert_into(users)
-                .values(&new_user)
-                .get_result::<User>(&mut conn)
-                .map_err(|e| {
-                    error!("Failed to create user: {}", e);
-                    StatusCode::INTERNAL_SERVER_ERROR
-                })?
-        }
+    // Banned users go no further: no invite is used up and no admin status
+    // changes on their account
+    if let Some(user) = existing.as_ref().filter(|user| user.disabled) {
+        info!("Banned user {} attempted login", user.email);
+        return Ok(banned_redirect(user));
+    }
+
+    // An invite link opened in this browser is redeemed by whoever signs in
+    let invite = match take_invite_code(app_state, cookies) {
+        Some(code) => invites::find_open(&mut conn, &code).map_err(|e| {
+            error!("Failed to look up invite: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?,
+        None => None,
     };
 
-    // Check if user is banned
-    if user.disabled {
-        let reason = user.ban_reason.as_deref().unwrap_or("No reason provided");
-        // URL encode the reason for the query parameter
-        let encoded_reason: String = reason
-            .chars()
-            .map(|c| {
-                if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' || c == '~' {
-                    c.to_string()
-                } else if c == ' ' {
-                    "+".to_string()
-                } else {
-                    format!("%{:02X}", c as u8)
-                }
-            })
-            .collect();
-        info!("Banned user {} attempted login", user.email);
-        return Ok(Redirect::temporary(&format!(
-            "/banned?reason={}",
-            encoded_reason
-        )));
+    // Anyone who joined through an invite stays let in after it closes
+    let allowed = check_email_allowed(app_state, &identity.email)
+        || match &existing {
+            Some(user) => invites::has_redeemed_any(&mut conn, user.id).map_err(|e| {
+                error!("Failed to look up invite redemptions: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?,
+            None => false,
+        };
+    if !allowed && invite.is_none() {
+        info!(
+            "Access denied for email: {} (not in allowlist)",
+            identity.email
+        );
+        return Ok(Redirect::temporary("/access-denied"));
     }
 
-    // Check if this is part of a device flow (state starts with "device:")
-    if let Some(ref state) = query.state {
-        if let Some(device_user_code) = state.strip_prefix("device:") {
-            // Set session cookie first so user is logged in
-            let mut cookie = Cookie::new(SESSION_COOKIE_NAME, user.id.to_string());
-            cookie.set_path("/");
-            cookie.set_http_only(true);
-            cookie.set_secure(!app_state.dev_mode);
-            cookie.set_same_site(SameSite::Lax);
-            cookies.signed(&app_state.cookie_key).add(cookie);
-
-            // Redirect back to device verify page to show approval UI
+    // Create the user and redeem the invite together, so a user let in only
+    // by an invite that closed in the meantime is not left behind
+    let admitted = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        let user = match existing {
+            Some(user) => user,
+            None => {
+                let new_user = NewUser {
+                    auth_provider: provider_id.to_string(),
+                    auth_subject: identity.subject.clone(),
+                    email: identity.email.clone(),
+                    name: identity.name.clone(),
+                    avatar_url: identity.picture.clone(),
+                };
+                diesel::insert_into(users)
+                    .values(&new_user)
+                    .get_result::<User>(conn)?
+            }
+        };
+        let redemption = match &invite {
+            Some(invite) => Some(invites::redeem(conn, invite, user.id)?),
+            None => None,
+        };
+        if !allowed && redemption == Some(Redemption::Closed) {
+            return Err(diesel::result::Error::RollbackTransaction);
+        }
+        // Redeeming an admin invite may have changed the user
+        let user = match redemption {
+            Some(Redemption::Redeemed) => users.find(user.id).first::<User>(conn)?,
+            _ => user,
+        };
+        Ok((user, redemption))
+    });
+    let (mut user, redemption) = match admitted {
+        Ok(admitted) => admitted,
+        Err(diesel::result::Error::RollbackTransaction) => {
             info!(
-                "OAuth complete for device flow, redirecting to approval page for user: {}",
-                user.email
+                "Access denied for {} (invite closed before it was redeemed)",
+                identity.email
             );
-            return Ok(Redirect::temporary(&format!(
-                "/api/auth/device?user_code={}",
-                device_user_code
-            )));
+            return Ok(Redirect::temporary("/access-denied"));
         }
+        Err(e) => {
+            error!("Failed to create user: {}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    if let (Some(invite), Some(Redemption::Redeemed)) = (&invite, redemption) {
+        let invite_role = invites::role(invite);
+        info!(
+            "{} redeemed invite {} ({})",
+            user.email,
+            invite.id,
+            invite_role.as_str()
+        );
+        crate::audit::record(
+            &mut conn,
+            Some(user.id),
+            AuditAction::InviteRedeem,
+            Some(invite.id),
+            json!({ "email": user.email, "role": invite_role.as_str() }),
+        );
     }
 
-    // Set session cookie with user ID
-    let mut cookie = Cookie::new(SESSION_COOKIE_NAME, user.id.to_string());
-    cookie.set_path("/");
-    cookie.set_http_only(true);
-    cookie.set_secure(!app_state.dev_mode); // Don't require HTTPS in dev mode
-    cookie.set_same_site(SameSite::Lax);
-    cookies.signed(&app_state.cookie_key).add(cookie);
+    // Providers with admin groups decide admin status on every login
+    if let Some(admin) = provider.config.admin_status(&identity.groups) {
+        if admin != user.is_admin {
+            diesel::update(users.find(user.id))
+                .set((is_admin.eq(admin), updated_at.eq(diesel::dsl::now)))
+                .execute(&mut conn)
+                .map_err(|e| {
+                    error!("Failed to update admin status: {}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+            info!(
+                "Set is_admin={} for {} from {} groups",
+                admin, user.email, provider_id
+            );
+            crate::audit::record(
+                &mut conn,
+                None,
+                AuditAction::UserUpdate,
+                Some(user.id),
+                json!({ "email": user.email, "is_admin": admin, "via": provider_id }),
+            );
+            user.is_admin = admin;
+        }
+    }
+
+    // Check if this is part of a device flow (state starts with "device:")
+    if let Some(device_user_code) = pending.state.strip_prefix("device:") {
+        // Set session cookie first so user is logged in
+        set_session_cookie(app_state, cookies, user.id, client).map_err(login_failed)?;
+
+        // Redirect back to device verify page to show approval UI
+        info!(
+            "OAuth complete for device flow, redirecting to approval page for user: {}",
+            user.email
+        );
+        return Ok(Redirect::temporary(&format!(
+            "/api/auth/device?user_code={}",
+            device_user_code
+        )));
+    }
+
+    // Set session cookie naming the new login
+    set_session_cookie(app_state, cookies, user.id, client).map_err(login_failed)?;
 
     Ok(Redirect::temporary("/dashboard"))
 }
 
-#[derive(Debug, Serialize)]
+/// Send a banned user to the page explaining why
+fn banned_redirect(user: &User) -> Redirect {
+    let reason = user.ban_reason.as_deref().unwrap_or("No reason provided");
+    // URL encode the reason for the query parameter
+    let encoded_reason: String = reason
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' || c == '~' {
+                c.to_string()
+            } else if c == ' ' {
+                "+".to_string()
+            } else {
+                format!("%{:02X}", c as u8)
+            }
+        })
+        .collect();
+    Redirect::temporary(&format!("/banned?reason={}", encoded_reason))
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct UserResponse {
     pub id: Uuid,
     pub email: String,
@@ -306,20 +607,23 @@ pub struct UserResponse {
     pub voice_enabled: bool,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/auth/me",
+    tag = "auth",
+    responses(
+        (status = 200, body = UserResponse),
+        (status = 404, description = "User no longer exists")
+    )
+)]
 pub async fn me(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
 ) -> Result<Json<UserResponse>, StatusCode> {
-    // Extract user ID from signed session cookie
-    let cookie = cookies
-        .signed(&app_state.cookie_key)
-        .get(SESSION_COOKIE_NAME)
-        .ok_or(StatusCode::UNAUTHORIZED)?;
-
-    let user_id: Uuid = cookie
-        .value()
-        .parse()
-        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+    // Resolve the browser login named by the signed session cookie
+    let user_id = crate::auth::current_login(&app_state, &cookies)
+        .map_err(|_| StatusCode::UNAUTHORIZED)?
+        .user_id;
 
     // Fetch user from database
     use crate::schema::users::dsl::*;
@@ -343,27 +647,53 @@ pub async fn me(
     }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/auth/logout",
+    tag = "auth",
+    responses((status = 307, description = "Session cookie cleared; redirect home")),
+    security(())
+)]
 pub async fn logout(State(app_state): State<Arc<AppState>>, cookies: Cookies) -> impl IntoResponse {
-    // Remove session cookie by setting it with empty value and immediate expiry
-    let mut cookie = Cookie::new(SESSION_COOKIE_NAME, "");
-    cookie.set_path("/");
-    cookie.set_http_only(true);
-    cookie.set_secure(true);
-    cookie.set_same_site(SameSite::Lax);
-    cookie.set_max_age(tower_cookies::cookie::time::Duration::ZERO);
-    cookies.signed(&app_state.cookie_key).add(cookie);
+    // Forget the login, so the cookie stops working even if a copy survives
+    if let Ok(login) = crate::auth::current_login(&app_state, &cookies) {
+        if let Ok(mut conn) = app_state.db_pool.get() {
+            if let Err(e) = web_logins::revoke(&mut conn, login.user_id, login.id) {
+                error!("Failed to remove login {}: {}", login.id, e);
+            }
+        }
+        app_state.session_manager.end_web_login(login.id);
+    }
+
+    clear_session_cookie(&app_state, &cookies);
 
     info!("User logged out");
     Redirect::temporary("/")
 }
 
 // Development mode handlers (bypass OAuth)
+#[utoipa::path(
+    get,
+    path = "/api/auth/dev-login",
+    tag = "auth",
+    responses(
+        (status = 307, description = "Signed in as the dev user (dev mode only)"),
+        (status = 404, description = "Not running in dev mode")
+    ),
+    security(())
+)]
 pub async fn dev_login(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
 ) -> Result<impl IntoResponse, StatusCode> {
     use crate::schema::users::dsl::*;
 
+    if !app_state.dev_mode {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
     let mut conn = app_state
         .db_pool
         .get()
@@ -397,46 +727,57 @@ pub async fn dev_login(
     }
 
     info!("Dev mode: auto-logged in as testing@testing.local");
-
-    // Set session cookie with user ID
-    let mut cookie = Cookie::new(SESSION_COOKIE_NAME, user.id.to_string());
-    cookie.set_path("/");
-    cookie.set_http_only(true);
-    cookie.set_secure(!app_state.dev_mode); // Don't require HTTPS in dev mode
-    cookie.set_same_site(SameSite::Lax);
-    cookies.signed(&app_state.cookie_key).add(cookie);
+    set_session_cookie(
+        &app_state,
+        &cookies,
+        user.id,
+        &ClientInfo::new(&headers, peer, &app_state.trusted_proxies),
+    )
+    .map_err(login_failed)?;
 
     // Redirect to dashboard
     Ok(Redirect::temporary("/dashboard"))
 }
 
+/// The invite code this browser opened, if any; the cookie is removed so the
+/// invite is only offered to one login
+fn take_invite_code(app_state: &AppState, cookies: &Cookies) -> Option<String> {
+    let code = cookies
+        .signed(&app_state.cookie_key)
+        .get(INVITE_COOKIE)?
+        .value()
+        .to_string();
+    let mut remove_invite = Cookie::new(INVITE_COOKIE, "");
+    remove_invite.set_path(OAUTH_LOGIN_COOKIE_PATH);
+    remove_invite.set_max_age(tower_cookies::cookie::time::Duration::ZERO);
+    cookies.signed(&app_state.cookie_key).add(remove_invite);
+    Some(code)
+}
+
 /// Check if an email is allowed based on ALLOWED_EMAIL_DOMAIN and ALLOWED_EMAILS
-///
-/// Returns Ok(()) if allowed, or Err(Redirect) to the access denied page
-fn check_email_allowed(app_state: &AppState, email: &str) -> Result<(), Redirect> {
+fn check_email_allowed(app_state: &AppState, email: &str) -> bool {
     let email_lower = email.to_lowercase();
+    let settings = app_state.settings.get();
 
     // If no restrictions are set, allow all
-    if app_state.allowed_email_domain.is_none() && app_state.allowed_emails.is_none() {
-        return Ok(());
+    if settings.allowed_email_domain.is_none() && settings.allowed_emails.is_none() {
+        return true;
     }
 
     // Check domain allowlist
-    if let Some(ref domain) = app_state.allowed_email_domain {
+    if let Some(ref domain) = settings.allowed_email_domain {
         let domain_lower = domain.to_lowercase();
         if email_lower.ends_with(&format!("@{}", domain_lower)) {
-            return Ok(());
+            return true;
         }
     }
 
     // Check specific email allowlist
-    if let Some(ref emails) = app_state.allowed_emails {
+    if let Some(ref emails) = settings.allowed_emails {
         if emails.contains(&email_lower) {
-            return Ok(());
+            return true;
         }
     }
 
-    // Access denied
-    info!("Access denied for email: {} (not in allowlist)", email);
-    Err(Redirect::temporary("/access-denied"))
+    false
 }
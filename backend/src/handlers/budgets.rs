@@ -0,0 +1,41 @@
+//! Spend Budget Handlers
+//!
+//! Users see the budgets that apply to them here; admins set them through
+//! `/api/admin/budgets`. Enforcement lives in `crate::budgets`.
+
+use axum::{extract::State, Json};
+use shared::api::SpendBudgetsResponse;
+use std::sync::Arc;
+use tower_cookies::Cookies;
+
+use crate::{auth::extract_user_id, budgets, errors::AppError, AppState};
+
+/// GET /api/budgets
+///
+/// The caller's own budgets and those of their teams, with what has been
+/// spent against each in the current period.
+#[utoipa::path(
+    get,
+    path = "/api/budgets",
+    tag = "budgets",
+    responses(
+        (status = 200, body = SpendBudgetsResponse),
+        (status = 401, description = "Not signed in")
+    )
+)]
+pub async fn list_my_budgets(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<Json<SpendBudgetsResponse>, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let now = chrono::Utc::now().naive_utc();
+    let budgets = budgets::usage_for_user(&mut conn, user_id, now)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .iter()
+        .map(budgets::BudgetUsage::info)
+        .collect();
+
+    Ok(Json(SpendBudgetsResponse { budgets }))
+}
@@ -0,0 +1,673 @@
+//! Chat Bridge Handlers
+//!
+//! CRUD endpoints for a user's chat bridges, a test post, and the signed
+//! inbound endpoint the chat tool sends commands to. Posting and command
+//! parsing live in `crate::chat_bridge`.
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    Json,
+};
+use diesel::prelude::*;
+use serde::Deserialize;
+use shared::api::{
+    ChatBridgeInfo, ChatBridgeListResponse, CreateChatBridgeRequest, CreateChatBridgeResponse,
+    UpdateChatBridgeRequest,
+};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tower_cookies::Cookies;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    chat_bridge::{self, ChatCommand},
+    errors::AppError,
+    handlers::websocket,
+    models::{ChatBridge, NewChatBridge, PendingPermissionRequest, Session},
+    schema::{chat_bridges, pending_permission_requests, session_members, sessions, users},
+    AppState,
+};
+
+/// Longest bridge name accepted
+const MAX_NAME_LEN: usize = 100;
+/// Session roles that may answer permissions and send input from chat
+const WRITE_ROLES: [&str; 2] = ["owner", "editor"];
+
+fn bridge_to_info(b: ChatBridge, public_url: &str) -> ChatBridgeInfo {
+    ChatBridgeInfo {
+        id: b.id,
+        name: b.name,
+        incoming_webhook_url: b.incoming_webhook_url,
+        post_permissions: b.post_permissions,
+        post_results: b.post_results,
+        user_map: serde_json::from_value(b.user_map).unwrap_or_default(),
+        enabled: b.enabled,
+        inbound_url: format!("{}/api/chat-bridges/{}/inbound", public_url, b.id),
+        created_at: b.created_at.and_utc().to_rfc3339(),
+        updated_at: b.updated_at.and_utc().to_rfc3339(),
+    }
+}
+
+fn validate_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(AppError::BadRequest(
+            "Bridge name must be between 1 and 100 characters",
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Normalize a chat user -> email map: trimmed keys, lowercased emails.
+fn user_map_to_json(map: BTreeMap<String, String>) -> Result<serde_json::Value, AppError> {
+    let mut normalized = BTreeMap::new();
+    for (chat_user, email) in map {
+        let (chat_user, email) = (chat_user.trim(), email.trim().to_lowercase());
+        if chat_user.is_empty() || !email.contains('@') {
+            return Err(AppError::BadRequest(
+                "Each user mapping needs a chat user ID and an email address",
+            ));
+        }
+        normalized.insert(chat_user.to_string(), email);
+    }
+    Ok(serde_json::json!(normalized))
+}
+
+fn validate_secret(secret: &str) -> Result<String, AppError> {
+    let secret = secret.trim();
+    if secret.len() < 16 || secret.len() > 128 {
+        return Err(AppError::BadRequest(
+            "Signing secret must be between 16 and 128 characters",
+        ));
+    }
+    Ok(secret.to_string())
+}
+
+/// Load a bridge owned by the caller.
+fn find_bridge(
+    conn: &mut PgConnection,
+    bridge_id: Uuid,
+    user_id: Uuid,
+) -> Result<ChatBridge, AppError> {
+    chat_bridges::table
+        .filter(chat_bridges::id.eq(bridge_id))
+        .filter(chat_bridges::user_id.eq(user_id))
+        .first(conn)
+        .map_err(|_| AppError::NotFound("chat bridge"))
+}
+
+/// GET /api/chat-bridges
+#[utoipa::path(
+    get,
+    path = "/api/chat-bridges",
+    tag = "chat-bridges",
+    responses((status = 200, body = ChatBridgeListResponse))
+)]
+pub async fn list_chat_bridges_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<Json<ChatBridgeListResponse>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let list: Vec<ChatBridge> = chat_bridges::table
+        .filter(chat_bridges::user_id.eq(user_id))
+        .order(chat_bridges::created_at.asc())
+        .load(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(Json(ChatBridgeListResponse {
+        bridges: list
+            .into_iter()
+            .map(|b| bridge_to_info(b, &app_state.public_url))
+            .collect(),
+    }))
+}
+
+/// POST /api/chat-bridges
+#[utoipa::path(
+    post,
+    path = "/api/chat-bridges",
+    tag = "chat-bridges",
+    request_body = CreateChatBridgeRequest,
+    responses(
+        (status = 200, description = "The bridge and its signing secret, shown only once", body = CreateChatBridgeResponse),
+        (status = 400, description = "Invalid name, URL or user map")
+    )
+)]
+pub async fn create_chat_bridge_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Json(req): Json<CreateChatBridgeRequest>,
+) -> Result<Json<CreateChatBridgeResponse>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let name = validate_name(&req.name)?;
+    let url = req.incoming_webhook_url.trim().to_string();
+    crate::webhooks::check_destination(&url, &app_state.webhook_destinations)
+        .await
+        .map_err(AppError::BadRequest)?;
+    let signing_secret = match req.signing_secret.filter(|s| !s.trim().is_empty()) {
+        Some(secret) => validate_secret(&secret)?,
+        None => crate::webhooks::generate_secret(),
+    };
+    let user_map = user_map_to_json(req.user_map)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let new_bridge = NewChatBridge {
+        user_id,
+        name,
+        incoming_webhook_url: url,
+        signing_secret: signing_secret.clone(),
+        post_permissions: req.post_permissions,
+        post_results: req.post_results,
+        user_map,
+    };
+
+    let saved: ChatBridge = diesel::insert_into(chat_bridges::table)
+        .values(&new_bridge)
+        .get_result(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    info!("Created chat bridge {} ({})", saved.id, saved.name);
+
+    Ok(Json(CreateChatBridgeResponse {
+        bridge: bridge_to_info(saved, &app_state.public_url),
+        signing_secret,
+    }))
+}
+
+/// PATCH /api/chat-bridges/:id
+#[utoipa::path(
+    patch,
+    path = "/api/chat-bridges/{id}",
+    tag = "chat-bridges",
+    params(("id" = Uuid, Path, description = "Chat bridge ID")),
+    request_body = UpdateChatBridgeRequest,
+    responses(
+        (status = 200, body = ChatBridgeInfo),
+        (status = 400, description = "Invalid name, URL or user map"),
+        (status = 404, description = "Chat bridge not found")
+    )
+)]
+pub async fn update_chat_bridge_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(bridge_id): Path<Uuid>,
+    Json(req): Json<UpdateChatBridgeRequest>,
+) -> Result<Json<ChatBridgeInfo>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let existing = find_bridge(&mut conn, bridge_id, user_id)?;
+
+    let name = match req.name {
+        Some(name) => validate_name(&name)?,
+        None => existing.name,
+    };
+    let url = match req.incoming_webhook_url {
+        Some(url) => {
+            let url = url.trim().to_string();
+            crate::webhooks::check_destination(&url, &app_state.webhook_destinations)
+                .await
+                .map_err(AppError::BadRequest)?;
+            url
+        }
+        None => existing.incoming_webhook_url,
+    };
+    let signing_secret = match req.signing_secret {
+        Some(secret) => validate_secret(&secret)?,
+        None => existing.signing_secret,
+    };
+    let user_map = match req.user_map {
+        Some(map) => user_map_to_json(map)?,
+        None => existing.user_map,
+    };
+
+    let updated: ChatBridge = diesel::update(chat_bridges::table.find(existing.id))
+        .set((
+            chat_bridges::name.eq(&name),
+            chat_bridges::incoming_webhook_url.eq(&url),
+            chat_bridges::signing_secret.eq(&signing_secret),
+            chat_bridges::post_permissions
+                .eq(req.post_permissions.unwrap_or(existing.post_permissions)),
+            chat_bridges::post_results.eq(req.post_results.unwrap_or(existing.post_results)),
+            chat_bridges::user_map.eq(&user_map),
+            chat_bridges::enabled.eq(req.enabled.unwrap_or(existing.enabled)),
+            chat_bridges::updated_at.eq(diesel::dsl::now),
+        ))
+        .get_result(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    info!("Updated chat bridge {}", updated.id);
+
+    Ok(Json(bridge_to_info(updated, &app_state.public_url)))
+}
+
+/// DELETE /api/chat-bridges/:id
+#[utoipa::path(
+    delete,
+    path = "/api/chat-bridges/{id}",
+    tag = "chat-bridges",
+    params(("id" = Uuid, Path, description = "Chat bridge ID")),
+    responses(
+        (status = 204, description = "Chat bridge deleted"),
+        (status = 404, description = "Chat bridge not found")
+    )
+)]
+pub async fn delete_chat_bridge_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(bridge_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let deleted = diesel::delete(
+        chat_bridges::table
+            .filter(chat_bridges::id.eq(bridge_id))
+            .filter(chat_bridges::user_id.eq(user_id)),
+    )
+    .execute(&mut conn)
+    .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    if deleted == 0 {
+        return Err(AppError::NotFound("chat bridge"));
+    }
+
+    info!("Deleted chat bridge {}", bridge_id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/chat-bridges/:id/test
+///
+/// Posts a test message to the channel and reports whether it was accepted.
+#[utoipa::path(
+    post,
+    path = "/api/chat-bridges/{id}/test",
+    tag = "chat-bridges",
+    params(("id" = Uuid, Path, description = "Chat bridge ID")),
+    responses(
+        (status = 204, description = "Test message posted"),
+        (status = 404, description = "Chat bridge not found")
+    )
+)]
+pub async fn test_chat_bridge_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(bridge_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let bridge = {
+        let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+        find_bridge(&mut conn, bridge_id, user_id)?
+    };
+
+    let text = format!(
+        "Connected to {}. {}",
+        app_state.settings.get().app_title,
+        chat_bridge::USAGE
+    );
+    app_state
+        .chat_bridges
+        .post(&bridge.incoming_webhook_url, &text)
+        .await
+        .map_err(AppError::Internal)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Fields read from an outgoing webhook or slash command payload. Slack and
+/// Mattermost send these form-encoded; custom relays may send JSON.
+#[derive(Debug, Default, Deserialize)]
+struct InboundMessage {
+    #[serde(default)]
+    text: String,
+    /// Chat user ID, looked up in the bridge's user map
+    #[serde(default)]
+    user_id: Option<String>,
+    /// Email of the chat user, when the relay knows it
+    #[serde(default)]
+    user_email: Option<String>,
+    /// Outgoing webhooks prefix `text` with the word that triggered them
+    #[serde(default)]
+    trigger_word: Option<String>,
+}
+
+impl InboundMessage {
+    fn parse(headers: &HeaderMap, body: &[u8]) -> Option<Self> {
+        let is_json = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("application/json"));
+        if is_json {
+            serde_json::from_slice(body).ok()
+        } else {
+            serde_urlencoded::from_bytes(body).ok()
+        }
+    }
+
+    /// The command text without the trigger word.
+    fn command_text(&self) -> &str {
+        let text = self.text.trim();
+        self.trigger_word
+            .as_deref()
+            .and_then(|word| text.strip_prefix(word))
+            .unwrap_or(text)
+    }
+}
+
+/// Reply shown in the channel
+fn reply(text: impl Into<String>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "response_type": "in_channel",
+        "text": text.into(),
+    }))
+}
+
+/// POST /api/chat-bridges/:id/inbound
+///
+/// Called by the chat tool, not the browser: authenticated by the bridge's
+/// signing secret instead of a session cookie. The sender is mapped to a
+/// portal account by email and may only act on sessions where both they and
+/// the bridge owner are an owner or editor.
+#[utoipa::path(
+    post,
+    path = "/api/chat-bridges/{id}/inbound",
+    tag = "chat-bridges",
+    params(("id" = Uuid, Path, description = "Chat bridge ID")),
+    request_body(
+        content = String,
+        content_type = "application/x-www-form-urlencoded",
+        description = "Slash command or outgoing webhook payload (form-encoded or JSON)"
+    ),
+    responses(
+        (status = 200, description = "The reply, as `{\"text\": ...}`", body = Object),
+        (status = 400, description = "Unrecognized payload"),
+        (status = 401, description = "Missing or invalid signature"),
+        (status = 404, description = "Chat bridge not found or paused")
+    ),
+    security(())
+)]
+pub async fn inbound_handler(
+    State(app_state): State<Arc<AppState>>,
+    Path(bridge_id): Path<Uuid>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let bridge =
+        chat_bridge::find_enabled(&mut conn, bridge_id).ok_or(AppError::NotFound("chat bridge"))?;
+    if let Err(e) = chat_bridge::verify_signature(
+        &bridge.signing_secret,
+        &headers,
+        &body,
+        chrono::Utc::now().timestamp(),
+    ) {
+        warn!(
+            "Rejected inbound chat message for bridge {}: {}",
+            bridge.id, e
+        );
+        return Err(AppError::Unauthorized);
+    }
+
+    let message = InboundMessage::parse(&headers, &body)
+        .ok_or(AppError::BadRequest("Unrecognized chat payload"))?;
+
+    let actor = match resolve_sender(&mut conn, &bridge, &message) {
+        Ok(actor) => actor,
+        Err(AppError::BadRequest(reason)) => return Ok(reply(reason.to_string())),
+        Err(e) => return Err(e),
+    };
+    let Some(actor) = actor else {
+        return Ok(reply(format!(
+            "I don't know who you are. Ask the owner of this bridge to map your chat user ID ({}) to your portal email.",
+            message.user_id.as_deref().unwrap_or("unknown")
+        )));
+    };
+
+    let command = match chat_bridge::parse_command(message.command_text()) {
+        Ok(command) => command,
+        Err(usage) => return Ok(reply(usage)),
+    };
+
+    let sessions = writable_sessions(&mut conn, actor, bridge.user_id)?;
+
+    let text = match command {
+        ChatCommand::Help => chat_bridge::USAGE.to_string(),
+        ChatCommand::Approve { reference } => {
+            answer_permission(&app_state, &mut conn, &sessions, actor, &reference, None)?
+        }
+        ChatCommand::Deny { reference, reason } => answer_permission(
+            &app_state,
+            &mut conn,
+            &sessions,
+            actor,
+            &reference,
+            Some(reason.unwrap_or_else(|| "Denied from chat".to_string())),
+        )?,
+        ChatCommand::Send { session, text } => {
+            let matching: Vec<&Session> = sessions
+                .iter()
+                .filter(|s| chat_bridge::matches_session(s, &session))
+                .collect();
+            match matching.as_slice() {
+                [] => format!("No session of yours matches `{}`.", session),
+                [target] => match websocket::submit_input(
+                    &app_state.session_manager,
+                    &app_state.db_pool,
+                    &target.id.to_string(),
+                    target.id,
+                    serde_json::Value::String(text),
+                    None,
+                    actor,
+                ) {
+                    Ok(()) => format!("Sent to *{}*.", target.session_name),
+                    Err(reason) => format!("Not sent to *{}*. {}", target.session_name, reason),
+                },
+                _ => format!(
+                    "`{}` matches several sessions; use the session reference instead.",
+                    session
+                ),
+            }
+        }
+    };
+
+    Ok(reply(text))
+}
+
+/// The portal email the bridge owner mapped the sender's chat user ID to.
+/// Only the owner's map says who someone is: a `user_email` in the payload
+/// is merely checked against it, and a sender whose payload claims another
+/// email is not recognised.
+fn sender_email(user_map: &serde_json::Value, message: &InboundMessage) -> Option<String> {
+    let mapped = message
+        .user_id
+        .as_deref()
+        .and_then(|chat_user| user_map.get(chat_user.trim()))
+        .and_then(|e| e.as_str())?
+        .trim()
+        .to_lowercase();
+    match message.user_email.as_deref().map(str::trim) {
+        Some(claimed) if !claimed.is_empty() && !claimed.eq_ignore_ascii_case(&mapped) => None,
+        _ => Some(mapped),
+    }
+}
+
+/// Map the sender to an enabled portal user by email. An email several
+/// accounts share is refused with `BadRequest`, as when sharing by email.
+fn resolve_sender(
+    conn: &mut PgConnection,
+    bridge: &ChatBridge,
+    message: &InboundMessage,
+) -> Result<Option<Uuid>, AppError> {
+    let Some(email) = sender_email(&bridge.user_map, message) else {
+        return Ok(None);
+    };
+    let user_id = match super::helpers::find_user_by_email(conn, &email) {
+        Ok(user_id) => user_id,
+        Err(AppError::NotFound(_)) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let disabled: bool = users::table
+        .find(user_id)
+        .select(users::disabled)
+        .first(conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    Ok((!disabled).then_some(user_id))
+}
+
+/// Sessions where `user_id` may act, as an owner or editor, among the ones
+/// the bridge owner can also act on.
+fn writable_sessions(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    bridge_owner: Uuid,
+) -> Result<Vec<Session>, AppError> {
+    let owner_sessions: Vec<Uuid> = session_members::table
+        .filter(session_members::user_id.eq(bridge_owner))
+        .filter(session_members::role.eq_any(WRITE_ROLES))
+        .select(session_members::session_id)
+        .load(conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    sessions::table
+        .inner_join(session_members::table.on(session_members::session_id.eq(sessions::id)))
+        .filter(session_members::user_id.eq(user_id))
+        .filter(session_members::role.eq_any(WRITE_ROLES))
+        .filter(sessions::id.eq_any(&owner_sessions))
+        .select(Session::as_select())
+        .load(conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))
+}
+
+/// Approve (no reason) or deny (with reason) the pending request `reference`
+/// names, and describe the outcome.
+fn answer_permission(
+    app_state: &AppState,
+    conn: &mut PgConnection,
+    sessions: &[Session],
+    actor: Uuid,
+    reference: &str,
+    deny_reason: Option<String>,
+) -> Result<String, AppError> {
+    let session_ids: Vec<Uuid> = sessions.iter().map(|s| s.id).collect();
+    let pending: Vec<PendingPermissionRequest> = pending_permission_requests::table
+        .filter(pending_permission_requests::session_id.eq_any(&session_ids))
+        .load(conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    let matching: Vec<PendingPermissionRequest> = pending
+        .into_iter()
+        .filter(|p| chat_bridge::matches_ref(p.id, reference))
+        .collect();
+    let request = match <[PendingPermissionRequest; 1]>::try_from(matching) {
+        Ok([request]) => request,
+        Err(matching) if matching.is_empty() => {
+            return Ok(format!(
+                "No pending request matches `{}`; it may already be answered.",
+                reference
+            ))
+        }
+        Err(_) => {
+            return Ok(format!(
+                "`{}` matches several pending requests; use more characters of the reference.",
+                reference
+            ))
+        }
+    };
+
+    let allow = deny_reason.is_none();
+    let verb = if allow { "Approved" } else { "Denied" };
+    let outcome = format!("{} *{}* (`{}`).", verb, request.tool_name, reference);
+    websocket::handle_permission_response(
+        &app_state.session_manager,
+        &request.session_id.to_string(),
+        request.session_id,
+        actor,
+        &app_state.db_pool,
+        request.request_id,
+        allow,
+        allow.then_some(request.input),
+        Vec::new(),
+        deny_reason,
+    );
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_map_to_json_normalizes() {
+        let map = BTreeMap::from([(" U123 ".to_string(), " Alice@Example.com".to_string())]);
+        assert_eq!(
+            user_map_to_json(map).unwrap(),
+            serde_json::json!({"U123": "alice@example.com"})
+        );
+        let bad = BTreeMap::from([("U123".to_string(), "alice".to_string())]);
+        assert!(user_map_to_json(bad).is_err());
+    }
+
+    #[test]
+    fn test_sender_email_comes_from_the_user_map() {
+        let user_map = serde_json::json!({"U123": "alice@example.com"});
+        let message = |user_id: Option<&str>, user_email: Option<&str>| InboundMessage {
+            text: "approve 3f2a".to_string(),
+            user_id: user_id.map(str::to_string),
+            user_email: user_email.map(str::to_string),
+            trigger_word: None,
+        };
+
+        assert_eq!(
+            sender_email(&user_map, &message(Some("U123"), None)).as_deref(),
+            Some("alice@example.com")
+        );
+        assert_eq!(
+            sender_email(&user_map, &message(Some("U123"), Some("Alice@Example.com"))).as_deref(),
+            Some("alice@example.com")
+        );
+        // A payload can't claim to be someone the owner didn't map
+        assert_eq!(
+            sender_email(&user_map, &message(None, Some("admin@example.com"))),
+            None
+        );
+        assert_eq!(
+            sender_email(&user_map, &message(Some("U999"), Some("admin@example.com"))),
+            None
+        );
+        assert_eq!(
+            sender_email(&user_map, &message(Some("U123"), Some("admin@example.com"))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_inbound_message_parsing() {
+        let mut headers = HeaderMap::new();
+        let form = InboundMessage::parse(
+            &headers,
+            b"text=portal+approve+3f2a&user_id=U123&trigger_word=portal",
+        )
+        .unwrap();
+        assert_eq!(form.command_text().trim(), "approve 3f2a");
+        assert_eq!(form.user_id.as_deref(), Some("U123"));
+
+        headers.insert(header::CONTENT_TYPE, "application/json".parse().unwrap());
+        let json =
+            InboundMessage::parse(&headers, br#"{"text":"help","user_email":"a@b.c"}"#).unwrap();
+        assert_eq!(json.command_text(), "help");
+        assert_eq!(json.user_email.as_deref(), Some("a@b.c"));
+    }
+}
@@ -8,10 +8,31 @@ use shared::AppConfig;
 use std::sync::Arc;
 
 /// GET /api/config - Returns application configuration
+#[utoipa::path(
+    get,
+    path = "/api/config",
+    tag = "meta",
+    responses((status = 200, body = AppConfig)),
+    security(())
+)]
 pub async fn get_config(State(app_state): State<Arc<AppState>>) -> Json<AppConfig> {
+    let settings = app_state.settings.get();
     Json(AppConfig {
-        app_title: app_state.app_title.clone(),
+        app_title: settings.app_title,
         server_version: env!("CARGO_PKG_VERSION").to_string(),
-        splash_text: app_state.splash_text.clone(),
+        splash_text: settings.splash_text,
+        login_providers: crate::handlers::auth::login_providers(&app_state),
     })
 }
+
+/// GET /api/health - Liveness check
+#[utoipa::path(
+    get,
+    path = "/api/health",
+    tag = "meta",
+    responses((status = 200, description = "The server is up", body = String, content_type = "text/plain")),
+    security(())
+)]
+pub async fn health() -> &'static str {
+    "OK"
+}
@@ -7,7 +7,9 @@ use axum::{
 use diesel::prelude::*;
 use rand::{distributions::Alphanumeric, Rng};
 use serde::{Deserialize, Serialize};
-use shared::api::{DeviceCodeRequest, DeviceFlowActionResponse, DeviceFlowPollRequest};
+use shared::api::{
+    AuditAction, DeviceCodeRequest, DeviceFlowActionResponse, DeviceFlowPollRequest,
+};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -22,10 +24,11 @@ use crate::{
     AppState,
 };
 
-use shared::protocol::{DEVICE_CODE_EXPIRES_SECS, SESSION_COOKIE_NAME};
+use shared::protocol::DEVICE_CODE_EXPIRES_SECS;
+use shared::LoginProvider;
 
 /// Error response for device flow endpoints
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct DeviceFlowError {
     pub error: String,
     pub message: String,
@@ -103,7 +106,7 @@ pub enum DeviceFlowStatus {
     Denied,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct DeviceCodeResponse {
     pub device_code: String,
     pub user_code: String,
@@ -112,7 +115,7 @@ pub struct DeviceCodeResponse {
     pub interval: u64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 #[serde(tag = "status")]
 pub enum PollResponse {
     #[serde(rename = "pending")]
@@ -132,7 +135,8 @@ pub enum PollResponse {
     Denied,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct VerifyQuery {
     pub user_code: Option<String>,
 }
@@ -158,6 +162,17 @@ fn generate_device_code() -> String {
 }
 
 // POST /auth/device/code
+#[utoipa::path(
+    post,
+    path = "/api/auth/device/code",
+    tag = "device-flow",
+    request_body(content = Option<DeviceCodeRequest>, description = "Optional details shown on the approval page"),
+    responses(
+        (status = 200, body = DeviceCodeResponse),
+        (status = 503, description = "Device flow is not available", body = DeviceFlowError)
+    ),
+    security(())
+)]
 pub async fn device_code(
     State(app_state): State<Arc<AppState>>,
     body: Option<Json<DeviceCodeRequest>>,
@@ -200,6 +215,17 @@ pub async fn device_code(
 }
 
 // POST /auth/device/poll
+#[utoipa::path(
+    post,
+    path = "/api/auth/device/poll",
+    tag = "device-flow",
+    request_body = DeviceFlowPollRequest,
+    responses(
+        (status = 200, body = PollResponse),
+        (status = 404, description = "Unknown device code", body = DeviceFlowError)
+    ),
+    security(())
+)]
 pub async fn device_poll(
     State(app_state): State<Arc<AppState>>,
     Json(req): Json<DeviceFlowPollRequest>,
@@ -254,6 +280,17 @@ pub async fn device_poll(
 }
 
 // GET /auth/device - Show verification page
+#[utoipa::path(
+    get,
+    path = "/api/auth/device",
+    tag = "device-flow",
+    params(VerifyQuery),
+    responses(
+        (status = 200, description = "Approval page", content_type = "text/html", body = String),
+        (status = 307, description = "Not signed in; redirect to sign-in")
+    ),
+    security(())
+)]
 pub async fn device_verify_page(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -288,19 +325,20 @@ pub async fn device_verify_page(
     drop(store_lock);
 
     // Check if user is already logged in via session cookie
-    if let Some(cookie) = cookies
-        .signed(&app_state.cookie_key)
-        .get(SESSION_COOKIE_NAME)
-    {
-        if cookie.value().parse::<Uuid>().is_ok() {
-            // User is logged in - show approval page
-            let html = render_approval_page(
-                &user_code,
-                hostname.as_deref(),
-                working_directory.as_deref(),
-            );
-            return axum::response::Html(html).into_response();
-        }
+    if crate::auth::current_login(&app_state, &cookies).is_ok() {
+        // User is logged in - show approval page
+        let html = render_approval_page(
+            &user_code,
+            hostname.as_deref(),
+            working_directory.as_deref(),
+        );
+        return axum::response::Html(html).into_response();
+    }
+
+    // With several login providers, let the user pick one
+    let providers = crate::handlers::auth::login_providers(&app_state);
+    if providers.len() > 1 {
+        return axum::response::Html(render_sign_in_page(&user_code, &providers)).into_response();
     }
 
     // User not logged in - redirect to device-specific login endpoint
@@ -313,31 +351,35 @@ pub async fn device_verify_page(
 }
 
 /// POST /auth/device/approve - Approve device authorization
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct ApproveRequest {
     pub user_code: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/auth/device/approve",
+    tag = "device-flow",
+    request_body = ApproveRequest,
+    responses(
+        (status = 200, body = DeviceFlowActionResponse),
+        (status = 401, description = "Not signed in", body = DeviceFlowError),
+        (status = 404, description = "Unknown or expired user code", body = DeviceFlowError)
+    )
+)]
 pub async fn device_approve(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
     Json(req): Json<ApproveRequest>,
 ) -> Result<Json<DeviceFlowActionResponse>, DeviceFlowApiError> {
     // Verify user is logged in
-    let cookie = cookies
-        .signed(&app_state.cookie_key)
-        .get(SESSION_COOKIE_NAME)
-        .ok_or_else(|| DeviceFlowApiError {
+    let user_id = crate::auth::current_login(&app_state, &cookies)
+        .map_err(|_| DeviceFlowApiError {
             status: StatusCode::UNAUTHORIZED,
             error: "unauthorized".to_string(),
             message: "You must be logged in to approve device authorization".to_string(),
-        })?;
-
-    let user_id: Uuid = cookie.value().parse().map_err(|_| DeviceFlowApiError {
-        status: StatusCode::UNAUTHORIZED,
-        error: "unauthorized".to_string(),
-        message: "Invalid session".to_string(),
-    })?;
+        })?
+        .user_id;
 
     let store = app_state
         .device_flow_store
@@ -361,25 +403,27 @@ pub async fn device_approve(
 }
 
 /// POST /auth/device/deny - Deny device authorization
+#[utoipa::path(
+    post,
+    path = "/api/auth/device/deny",
+    tag = "device-flow",
+    request_body = ApproveRequest,
+    responses(
+        (status = 200, body = DeviceFlowActionResponse),
+        (status = 401, description = "Not signed in", body = DeviceFlowError),
+        (status = 404, description = "Unknown or expired user code", body = DeviceFlowError)
+    )
+)]
 pub async fn device_deny(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
     Json(req): Json<ApproveRequest>,
 ) -> Result<Json<DeviceFlowActionResponse>, DeviceFlowApiError> {
     // Verify user is logged in
-    let cookie = cookies
-        .signed(&app_state.cookie_key)
-        .get(SESSION_COOKIE_NAME)
-        .ok_or_else(|| DeviceFlowApiError {
-            status: StatusCode::UNAUTHORIZED,
-            error: "unauthorized".to_string(),
-            message: "You must be logged in to deny device authorization".to_string(),
-        })?;
-
-    let _user_id: Uuid = cookie.value().parse().map_err(|_| DeviceFlowApiError {
+    crate::auth::current_login(&app_state, &cookies).map_err(|_| DeviceFlowApiError {
         status: StatusCode::UNAUTHORIZED,
         error: "unauthorized".to_string(),
-        message: "Invalid session".to_string(),
+        message: "You must be logged in to deny device authorization".to_string(),
     })?;
 
     let store = app_state
@@ -635,6 +679,93 @@ fn render_approval_page(
     )
 }
 
+/// Sign-in choices for a device approval when more than one provider is set up
+fn render_sign_in_page(user_code: &str, providers: &[LoginProvider]) -> String {
+    let user_code = crate::html::escape_html(user_code);
+    let buttons: String = providers
+        .iter()
+        .map(|provider| {
+            format!(
+                r#"<a class="provider" href="/api/auth/device-login?device_user_code={}&amp;provider={}">Sign in with {}</a>"#,
+                user_code,
+                crate::html::escape_html(&provider.id),
+                crate::html::escape_html(&provider.name)
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Sign In - Agent Portal</title>
+    <style>
+        :root {{
+            --bg-dark: #1a1b26;
+            --bg-darker: #16161e;
+            --text-primary: #c0caf5;
+            --text-secondary: #7f849c;
+            --accent: #7aa2f7;
+            --accent-hover: #9eb3ff;
+            --border: #292e42;
+        }}
+        * {{ margin: 0; padding: 0; box-sizing: border-box; }}
+        body {{
+            font-family: 'Segoe UI', Tahoma, Geneva, Verdana, sans-serif;
+            background: var(--bg-dark);
+            color: var(--text-primary);
+            min-height: 100vh;
+            display: flex;
+            align-items: center;
+            justify-content: center;
+        }}
+        .container {{
+            background: var(--bg-darker);
+            border: 1px solid var(--border);
+            border-radius: 12px;
+            padding: 2rem;
+            max-width: 400px;
+            width: 90%;
+            text-align: center;
+        }}
+        h1 {{
+            font-size: 1.5rem;
+            margin-bottom: 0.5rem;
+            color: var(--accent);
+        }}
+        p {{
+            color: var(--text-secondary);
+            margin-bottom: 1.5rem;
+            font-size: 0.9rem;
+        }}
+        .provider {{
+            display: block;
+            padding: 0.75rem 1.5rem;
+            margin-bottom: 0.75rem;
+            background: var(--accent);
+            color: var(--bg-dark);
+            border-radius: 8px;
+            font-weight: 600;
+            text-decoration: none;
+        }}
+        .provider:hover {{
+            background: var(--accent-hover);
+        }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <h1>Sign In</h1>
+        <p>Sign in to approve device code <strong>{user_code}</strong></p>
+        {buttons}
+    </div>
+</body>
+</html>"#
+    )
+}
+
 const DEVICE_CODE_FORM_HTML: &str = r#"<!DOCTYPE html>
 <html lang="en">
 <head>
@@ -796,12 +927,24 @@ pub async fn complete_device_flow(
         expires_at: expires_at.naive_utc(),
     };
 
-    diesel::insert_into(proxy_auth_tokens::table)
+    let saved_id: Uuid = diesel::insert_into(proxy_auth_tokens::table)
         .values(&new_token)
-        .execute(&mut conn)
+        .returning(proxy_auth_tokens::id)
+        .get_result(&mut conn)
         .map_err(|e| {
             error!("Failed to save token to database: {}", e);
         })?;
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::ProxyTokenCreate,
+        Some(saved_id),
+        serde_json::json!({
+            "name": new_token.name,
+            "expires_at": expires_at.to_rfc3339(),
+            "via": "device_flow",
+        }),
+    );
 
     // Now update the in-memory store with the JWT token
     let mut store_lock = store.write().await;
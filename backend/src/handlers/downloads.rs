@@ -13,13 +13,22 @@ use tokio_util::io::ReaderStream;
 
 use crate::AppState;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct InstallScriptParams {
     /// Backend URL (WebSocket URL for runtime connection)
     backend_url: Option<String>,
 }
 
 /// Serve the install script that downloads and sets up the portal
+#[utoipa::path(
+    get,
+    path = "/api/download/install.sh",
+    tag = "downloads",
+    params(InstallScriptParams),
+    responses((status = 200, description = "Shell script that installs the proxy", content_type = "text/plain", body = String)),
+    security(())
+)]
 pub async fn install_script(
     State(app_state): State<Arc<AppState>>,
     Query(params): Query<InstallScriptParams>,
@@ -276,6 +285,13 @@ async fn compute_binary_sha256(path: &std::path::Path) -> Result<String, (Status
 ///
 /// GET: Returns the binary file with X-Binary-SHA256 header
 /// HEAD: Returns empty body with X-Binary-SHA256 header (for update checks)
+#[utoipa::path(
+    get,
+    path = "/api/download/proxy",
+    tag = "downloads",
+    responses((status = 200, description = "Proxy binary", content_type = "application/octet-stream", body = Vec<u8>)),
+    security(())
+)]
 pub async fn proxy_binary(
     method: Method,
     State(app_state): State<Arc<AppState>>,
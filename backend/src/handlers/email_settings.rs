@@ -0,0 +1,155 @@
+//! Email Notification Settings Handlers
+//!
+//! Per-user choice of which events send mail. Delivery lives in `crate::email`.
+
+use axum::{extract::State, http::StatusCode, Json};
+use diesel::prelude::*;
+use shared::api::{EmailSettings, EmailSettingsResponse};
+use std::sync::Arc;
+use tower_cookies::Cookies;
+
+use crate::auth::extract_user_id;
+use crate::errors::AppError;
+use crate::models::EmailNotificationSettings;
+use crate::schema::{email_notification_settings, users};
+use crate::AppState;
+
+fn settings_response(
+    conn: &mut PgConnection,
+    app_state: &AppState,
+    user_id: uuid::Uuid,
+) -> Result<EmailSettingsResponse, AppError> {
+    let email: String = users::table
+        .find(user_id)
+        .select(users::email)
+        .first(conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    let stored: Option<EmailNotificationSettings> = email_notification_settings::table
+        .find(user_id)
+        .select(EmailNotificationSettings::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    let settings = stored
+        .map(|s| EmailSettings {
+            permission_wait_minutes: s.permission_wait_minutes,
+            session_errors: s.session_errors,
+            scheduled_failures: s.scheduled_failures,
+            daily_digest: s.daily_digest,
+            digest_hour: s.digest_hour,
+        })
+        .unwrap_or_default();
+
+    Ok(EmailSettingsResponse {
+        settings,
+        email,
+        smtp_configured: app_state.mailer.is_configured(),
+    })
+}
+
+/// GET /api/settings/email
+#[utoipa::path(
+    get,
+    path = "/api/settings/email",
+    tag = "settings",
+    responses((status = 200, body = EmailSettingsResponse))
+)]
+pub async fn get_email_settings(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<Json<EmailSettingsResponse>, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    Ok(Json(settings_response(&mut conn, &app_state, user_id)?))
+}
+
+/// PUT /api/settings/email
+#[utoipa::path(
+    put,
+    path = "/api/settings/email",
+    tag = "settings",
+    request_body = EmailSettings,
+    responses(
+        (status = 200, body = EmailSettingsResponse),
+        (status = 400, description = "Invalid settings")
+    )
+)]
+pub async fn save_email_settings(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Json(settings): Json<EmailSettings>,
+) -> Result<Json<EmailSettingsResponse>, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    settings.validate().map_err(AppError::BadRequest)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let values = (
+        email_notification_settings::permission_wait_minutes.eq(settings.permission_wait_minutes),
+        email_notification_settings::session_errors.eq(settings.session_errors),
+        email_notification_settings::scheduled_failures.eq(settings.scheduled_failures),
+        email_notification_settings::daily_digest.eq(settings.daily_digest),
+        email_notification_settings::digest_hour.eq(settings.digest_hour),
+        email_notification_settings::updated_at.eq(diesel::dsl::now),
+    );
+    diesel::insert_into(email_notification_settings::table)
+        .values((email_notification_settings::user_id.eq(user_id), values))
+        .on_conflict(email_notification_settings::user_id)
+        .do_update()
+        .set(values)
+        .execute(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(Json(settings_response(&mut conn, &app_state, user_id)?))
+}
+
+/// POST /api/settings/email/test
+///
+/// Sends a test mail to the account address and reports relay errors.
+#[utoipa::path(
+    post,
+    path = "/api/settings/email/test",
+    tag = "settings",
+    responses(
+        (status = 204, description = "Test email sent"),
+        (status = 400, description = "SMTP is not configured on this server")
+    )
+)]
+pub async fn send_test_email(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<StatusCode, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    if !app_state.mailer.is_configured() {
+        return Err(AppError::BadRequest(
+            "SMTP is not configured on this server",
+        ));
+    }
+
+    let email: String = {
+        let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+        users::table
+            .find(user_id)
+            .select(users::email)
+            .first(&mut conn)
+            .map_err(|e| AppError::DbQuery(e.to_string()))?
+    };
+
+    app_state
+        .mailer
+        .send(
+            &email,
+            "Test email from the portal",
+            format!(
+                "Email notifications are working.\n\nManage them at {}/settings\n",
+                app_state.public_url
+            ),
+        )
+        .await
+        .map_err(AppError::Internal)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,7 +1,8 @@
+use crate::errors::AppError;
 use crate::models::{NewDeletedSessionCosts, Session};
 use crate::schema::{
     deleted_session_costs, messages, pending_inputs, pending_permission_requests, session_members,
-    sessions,
+    sessions, users,
 };
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, PooledConnection};
@@ -24,6 +25,28 @@ impl From<diesel::result::Error> for DeleteSessionError {
     }
 }
 
+diesel::define_sql_function!(fn lower(x: diesel::sql_types::Text) -> diesel::sql_types::Text);
+
+/// The user with `email`, ignoring case, for sharing by address or naming a
+/// chat sender. Emails are only unique per sign-in provider, so an address
+/// shared by several accounts is refused rather than handing access to
+/// whichever row comes first.
+pub fn find_user_by_email(conn: &mut PgConnection, email: &str) -> Result<Uuid, AppError> {
+    let ids: Vec<Uuid> = users::table
+        .filter(lower(users::email).eq(email.trim().to_lowercase()))
+        .select(users::id)
+        .limit(2)
+        .load(conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    match ids[..] {
+        [id] => Ok(id),
+        [] => Err(AppError::NotFound("User not found")),
+        _ => Err(AppError::BadRequest(
+            "More than one account uses that email; ask an admin to merge or rename them",
+        )),
+    }
+}
+
 /// Delete a session and all associated data (messages, session_members).
 /// Optionally records the session costs to deleted_session_costs for the owner.
 ///
@@ -5,7 +5,7 @@ use axum::{
 };
 use serde::Deserialize;
 use shared::api::LaunchRequest;
-use shared::{DirectoryEntry, LauncherInfo, LauncherToServer, ServerToLauncher};
+use shared::{DirectoryEntry, LauncherInfo, LauncherToServer, LocalSession, ServerToLauncher};
 use std::sync::Arc;
 use tower_cookies::Cookies;
 use tracing::{error, info, warn};
@@ -15,22 +15,89 @@ use crate::auth::extract_user_id;
 use crate::errors::AppError;
 use crate::AppState;
 
-/// GET /api/launchers - List connected launchers for the current user
+/// How a user may use a connected launcher
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LauncherAccess {
+    /// The launcher is the user's own
+    Own,
+    /// Its owner shared it with one of the user's teams
+    Shared,
+    Denied,
+}
+
+/// Whether a user may launch on a connected launcher: it is their own, or its
+/// owner shared it with one of their teams.
+pub(crate) fn launcher_access(
+    app_state: &AppState,
+    user_id: Uuid,
+    launcher_id: Uuid,
+) -> Result<LauncherAccess, AppError> {
+    let (owner_id, launcher_name) = {
+        let launcher = app_state
+            .session_manager
+            .launchers
+            .get(&launcher_id)
+            .ok_or(AppError::NotFound("Launcher not connected"))?;
+        (launcher.user_id, launcher.launcher_name.clone())
+    };
+    if owner_id == user_id {
+        return Ok(LauncherAccess::Own);
+    }
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let shared = crate::teams::shared_launchers(&mut conn, user_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    if shared
+        .iter()
+        .any(|(owner, name, _)| *owner == owner_id && *name == launcher_name)
+    {
+        Ok(LauncherAccess::Shared)
+    } else {
+        Ok(LauncherAccess::Denied)
+    }
+}
+
+/// GET /api/launchers - List connected launchers for the current user,
+/// including those shared with their teams
+#[utoipa::path(
+    get,
+    path = "/api/launchers",
+    tag = "launchers",
+    responses((status = 200, description = "The user's launchers, then launchers shared with their teams", body = Vec<LauncherInfo>))
+)]
 pub async fn list_launchers(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
 ) -> Result<Json<Vec<LauncherInfo>>, AppError> {
     let user_id = extract_user_id(&app_state, &cookies)?;
-    let launchers = app_state.session_manager.get_launchers_for_user(&user_id);
+    let mut launchers = app_state.session_manager.get_launchers_for_user(&user_id);
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let shared = crate::teams::shared_launchers(&mut conn, user_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    if !shared.is_empty() {
+        launchers.extend(app_state.session_manager.get_shared_launchers(&shared));
+    }
     Ok(Json(launchers))
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, utoipa::ToSchema)]
 pub struct LaunchResponse {
     pub request_id: Uuid,
 }
 
 /// POST /api/launch - Request launching a new session
+#[utoipa::path(
+    post,
+    path = "/api/launch",
+    tag = "launchers",
+    request_body = LaunchRequest,
+    responses(
+        (status = 200, description = "Launch requested", body = LaunchResponse),
+        (status = 400, description = "Invalid cost cap"),
+        (status = 403, description = "The launcher is neither the user's nor shared with their team, a teammate's launcher was asked to resume a session or pass arguments, or a spend limit is reached"),
+        (status = 404, description = "No connected launcher")
+    )
+)]
 pub async fn launch_session(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -38,8 +105,26 @@ pub async fn launch_session(
 ) -> Result<Json<LaunchResponse>, AppError> {
     let user_id = extract_user_id(&app_state, &cookies)?;
 
+    if req.resume_session_id.is_some() && req.agent_type != shared::AgentType::Claude {
+        return Err(AppError::BadRequest("Only Claude sessions can be adopted"));
+    }
+    shared::api::validate_cost_cap(req.max_cost_usd).map_err(AppError::BadRequest)?;
+
     // Find the right launcher
     let launcher_id = if let Some(id) = req.launcher_id {
+        match launcher_access(&app_state, user_id, id)? {
+            LauncherAccess::Own => {}
+            // A teammate's launcher runs fresh sessions only: resuming would
+            // reach the owner's own sessions, and extra arguments could do
+            // the same or more on the owner's machine
+            LauncherAccess::Shared
+                if req.resume_session_id.is_some() || !req.claude_args.is_empty() =>
+            {
+                return Err(AppError::Forbidden);
+            }
+            LauncherAccess::Shared => {}
+            LauncherAccess::Denied => return Err(AppError::Forbidden),
+        }
         id
     } else {
         // Auto-select: pick the first connected launcher for this user
@@ -52,6 +137,11 @@ pub async fn launch_session(
 
     // Create a fresh short-lived proxy token for the child process
     let auth_token = mint_launch_token(&app_state, user_id)?;
+    if let Some(cap) = req.max_cost_usd {
+        app_state
+            .session_manager
+            .hold_cost_cap(crate::jwt::hash_token(&auth_token), cap);
+    }
 
     let request_id = Uuid::new_v4();
     let launch_msg = ServerToLauncher::LaunchSession {
@@ -63,6 +153,8 @@ pub async fn launch_session(
         claude_args: req.claude_args,
         agent_type: req.agent_type,
         scheduled_task_id: None,
+        resume_session_id: req.resume_session_id,
+        fork: None,
     };
 
     if !app_state
@@ -83,18 +175,33 @@ pub async fn launch_session(
     Ok(Json(LaunchResponse { request_id }))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct DirectoryQuery {
     pub path: String,
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, utoipa::ToSchema)]
 pub struct DirectoryListingResponse {
     pub entries: Vec<DirectoryEntry>,
     pub resolved_path: Option<String>,
 }
 
 /// GET /api/launchers/:launcher_id/directories?path=/some/path
+#[utoipa::path(
+    get,
+    path = "/api/launchers/{launcher_id}/directories",
+    tag = "launchers",
+    params(("launcher_id" = Uuid, Path, description = "Launcher ID"), DirectoryQuery),
+    responses(
+        (status = 200, body = DirectoryListingResponse),
+        (status = 400, description = "The launcher could not list the path"),
+        (status = 403, description = "Launcher belongs to another user and is not shared with the user's team"),
+        (status = 404, description = "Launcher not connected"),
+        (status = 502, description = "The launcher disconnected"),
+        (status = 504, description = "The launcher did not answer in time")
+    )
+)]
 pub async fn list_directories(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -103,19 +210,18 @@ pub async fn list_directories(
 ) -> Result<Json<DirectoryListingResponse>, StatusCode> {
     let user_id = extract_user_id(&app_state, &cookies).map_err(|_| StatusCode::UNAUTHORIZED)?;
 
-    // Verify the launcher belongs to this user
-    let launcher = app_state
-        .session_manager
-        .launchers
-        .get(&launcher_id)
-        .ok_or(StatusCode::NOT_FOUND)?;
-    if launcher.user_id != user_id {
-        return Err(StatusCode::FORBIDDEN);
+    // Team members browse a shared launcher to pick a directory to launch in
+    match launcher_access(&app_state, user_id, launcher_id) {
+        Ok(LauncherAccess::Own | LauncherAccess::Shared) => {}
+        Ok(LauncherAccess::Denied) => return Err(StatusCode::FORBIDDEN),
+        Err(AppError::NotFound(_)) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
-    drop(launcher);
 
     let request_id = Uuid::new_v4();
-    let rx = app_state.session_manager.register_dir_request(request_id);
+    let rx = app_state
+        .session_manager
+        .register_launcher_request(request_id);
 
     let sent = app_state.session_manager.send_to_launcher(
         &launcher_id,
@@ -128,7 +234,7 @@ pub async fn list_directories(
     if !sent {
         app_state
             .session_manager
-            .pending_dir_requests
+            .pending_launcher_requests
             .remove(&request_id);
         error!("Failed to send ListDirectories to launcher {}", launcher_id);
         return Err(StatusCode::BAD_GATEWAY);
@@ -155,7 +261,7 @@ pub async fn list_directories(
         Err(_) => {
             app_state
                 .session_manager
-                .pending_dir_requests
+                .pending_launcher_requests
                 .remove(&request_id);
             warn!("Directory listing timed out for launcher {}", launcher_id);
             Err(StatusCode::GATEWAY_TIMEOUT)
@@ -163,9 +269,117 @@ pub async fn list_directories(
     }
 }
 
+/// GET /api/launchers/:launcher_id/local-sessions - Recent Claude Code sessions
+/// started outside the portal on the launcher's host, newest first
+#[utoipa::path(
+    get,
+    path = "/api/launchers/{launcher_id}/local-sessions",
+    tag = "launchers",
+    params(("launcher_id" = Uuid, Path, description = "Launcher ID")),
+    responses(
+        (status = 200, description = "Sessions that can be adopted", body = Vec<LocalSession>),
+        (status = 403, description = "Launcher belongs to another user"),
+        (status = 404, description = "Launcher not connected"),
+        (status = 500, description = "The launcher could not read its transcripts"),
+        (status = 502, description = "The launcher disconnected"),
+        (status = 504, description = "The launcher did not answer in time")
+    )
+)]
+pub async fn list_local_sessions(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(launcher_id): Path<Uuid>,
+) -> Result<Json<Vec<LocalSession>>, StatusCode> {
+    let user_id = extract_user_id(&app_state, &cookies).map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    let launcher = app_state
+        .session_manager
+        .launchers
+        .get(&launcher_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if launcher.user_id != user_id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    drop(launcher);
+
+    let request_id = Uuid::new_v4();
+    let rx = app_state
+        .session_manager
+        .register_launcher_request(request_id);
+
+    if !app_state.session_manager.send_to_launcher(
+        &launcher_id,
+        ServerToLauncher::ListLocalSessions { request_id },
+    ) {
+        app_state
+            .session_manager
+            .pending_launcher_requests
+            .remove(&request_id);
+        error!(
+            "Failed to send ListLocalSessions to launcher {}",
+            launcher_id
+        );
+        return Err(StatusCode::BAD_GATEWAY);
+    }
+
+    let sessions = match tokio::time::timeout(std::time::Duration::from_secs(10), rx).await {
+        Ok(Ok(LauncherToServer::ListLocalSessionsResult {
+            sessions, error, ..
+        })) => {
+            if let Some(err) = error {
+                warn!("Local session listing error: {}", err);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+            sessions
+        }
+        Ok(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+        Err(_) => {
+            app_state
+                .session_manager
+                .pending_launcher_requests
+                .remove(&request_id);
+            warn!(
+                "Local session listing timed out for launcher {}",
+                launcher_id
+            );
+            return Err(StatusCode::GATEWAY_TIMEOUT);
+        }
+    };
+
+    // Sessions the portal launched also leave transcripts behind; only offer
+    // the ones it has never seen
+    use crate::schema::sessions;
+    use diesel::prelude::*;
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let ids: Vec<Uuid> = sessions.iter().map(|s| s.session_id).collect();
+    let known: Vec<Uuid> = sessions::table
+        .filter(sessions::id.eq_any(&ids))
+        .select(sessions::id)
+        .load(&mut conn)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(
+        sessions
+            .into_iter()
+            .filter(|s| !known.contains(&s.session_id))
+            .collect(),
+    ))
+}
+
+/// Create a short-lived proxy token for a session a launcher starts for
+/// `user_id`. Refused while one of the user's budgets is at its hard limit.
 pub(crate) fn mint_launch_token(app_state: &AppState, user_id: Uuid) -> Result<String, AppError> {
     let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
 
+    if let Some(reason) = crate::budgets::launch_block(&mut conn, user_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+    {
+        return Err(AppError::SpendLimitReached(reason));
+    }
+
     use crate::schema::users;
     use diesel::prelude::*;
 
@@ -203,6 +417,17 @@ pub(crate) fn mint_launch_token(app_state: &AppState, user_id: Uuid) -> Result<S
 }
 
 /// POST /api/launchers/:launcher_id/renew-token - Manually renew a launcher's auth token
+#[utoipa::path(
+    post,
+    path = "/api/launchers/{launcher_id}/renew-token",
+    tag = "launchers",
+    params(("launcher_id" = Uuid, Path, description = "Launcher ID")),
+    responses(
+        (status = 200, description = "A new token was sent to the launcher"),
+        (status = 403, description = "Launcher belongs to another user"),
+        (status = 404, description = "Launcher not found")
+    )
+)]
 pub async fn renew_launcher_token(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -229,6 +454,7 @@ pub async fn renew_launcher_token(
         user_id,
         old_token_hash,
         sender,
+        Some(user_id),
     )
     .map_err(|e| AppError::Internal(format!("{:?}", e)))?;
 
@@ -0,0 +1,453 @@
+//! Local Account Handlers
+//!
+//! Sign-in with a username and password (plus an authenticator code when the
+//! account has one), the setup link an admin hands out, and the account page
+//! where a local user changes their password or manages their second factor.
+//! Successful sign-ins get the same session cookie as provider logins.
+
+use axum::{
+    extract::{ConnectInfo, Query, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use chrono::Utc;
+use diesel::prelude::*;
+use serde::Deserialize;
+use shared::api::{
+    AccountSetupInfo, AccountSetupRequest, ChangePasswordRequest, LocalAccountInfo,
+    LocalLoginRequest, LocalLoginResponse, TotpCodeRequest, TotpSetupResponse,
+};
+use std::net::SocketAddr;
+use std::sync::{Arc, LazyLock};
+use tower_cookies::Cookies;
+use tracing::{info, warn};
+
+use crate::{
+    errors::AppError,
+    handlers::auth::set_session_cookie,
+    local_accounts as accounts,
+    models::{LocalAccount, User},
+    schema::{local_accounts, users},
+    web_logins::ClientInfo,
+    AppState,
+};
+
+/// Checked against when the username is unknown or has no password yet, so
+/// those answer as slowly as a wrong password does
+static DUMMY_PASSWORD_HASH: LazyLock<String> = LazyLock::new(|| {
+    accounts::hash_password("no such account").expect("argon2 hashes a fixed password")
+});
+
+fn require_enabled(app_state: &AppState) -> Result<(), AppError> {
+    if app_state.local_accounts_enabled {
+        Ok(())
+    } else {
+        Err(AppError::NotFound("Local accounts are not enabled"))
+    }
+}
+
+/// Argon2 is deliberately slow; keep it off the async workers
+async fn password_matches(password: String, hash: Option<String>) -> bool {
+    tokio::task::spawn_blocking(move || match hash {
+        Some(hash) => accounts::verify_password(&password, &hash),
+        None => {
+            accounts::verify_password(&password, &DUMMY_PASSWORD_HASH);
+            false
+        }
+    })
+    .await
+    .unwrap_or(false)
+}
+
+async fn hash_new_password(password: String) -> Result<String, AppError> {
+    accounts::validate_password(&password).map_err(AppError::BadRequest)?;
+    tokio::task::spawn_blocking(move || accounts::hash_password(&password))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// The signed-in user's local account
+fn current_account(
+    app_state: &AppState,
+    cookies: &Cookies,
+    conn: &mut PgConnection,
+) -> Result<LocalAccount, AppError> {
+    require_enabled(app_state)?;
+    let user_id = crate::auth::extract_user_id(app_state, cookies)?;
+    accounts::find_by_user(conn, user_id)?.ok_or(AppError::NotFound(
+        "You do not sign in with a local password",
+    ))
+}
+
+/// POST /api/auth/local/login
+#[utoipa::path(
+    post,
+    path = "/api/auth/local/login",
+    operation_id = "local_login",
+    tag = "auth",
+    request_body = LocalLoginRequest,
+    responses(
+        (status = 200, description = "Signed in, unless an authenticator code is still needed", body = LocalLoginResponse),
+        (status = 401, description = "Wrong username, password or code"),
+        (status = 403, description = "The account is disabled"),
+        (status = 404, description = "Local accounts are not enabled"),
+        (status = 429, description = "Too many attempts for this username or from this address; see Retry-After")
+    ),
+    security(())
+)]
+pub async fn login(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(req): Json<LocalLoginRequest>,
+) -> Result<Json<LocalLoginResponse>, AppError> {
+    require_enabled(&app_state)?;
+
+    // Both the password and the authenticator code are guesses: each request
+    // counts against the username and the IP until one signs in
+    let client = ClientInfo::new(&headers, peer, &app_state.trusted_proxies);
+    let throttle_key = req.username.trim().to_lowercase();
+    app_state
+        .login_throttle
+        .attempt(&throttle_key, client.ip_address.as_deref())?;
+
+    let account = {
+        let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+        accounts::find_by_username(&mut conn, &req.username)?
+    };
+    let hash = account.as_ref().and_then(|a| a.password_hash.clone());
+    let matches = password_matches(req.password, hash).await;
+    let Some(account) = account.filter(|_| matches) else {
+        warn!("Failed local sign-in for {:?}", req.username.trim());
+        return Err(AppError::Unauthorized);
+    };
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let user: User = users::table
+        .find(account.user_id)
+        .first(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    if user.disabled {
+        info!("Banned user {} attempted local sign-in", user.email);
+        return Err(AppError::Forbidden);
+    }
+
+    if account.totp_enabled {
+        let Some(code) = req.totp_code.filter(|c| !c.trim().is_empty()) else {
+            return Ok(Json(LocalLoginResponse {
+                totp_required: true,
+            }));
+        };
+        let secret = account.totp_secret.clone().unwrap_or_default();
+        if !accounts::check_totp(&mut conn, &account, &secret, &code)? {
+            warn!("Wrong authenticator code for {}", account.username);
+            return Err(AppError::Unauthorized);
+        }
+    }
+
+    info!("User signed in with local account {}", account.username);
+    app_state.login_throttle.succeeded(&throttle_key);
+    set_session_cookie(&app_state, &cookies, user.id, &client)?;
+    Ok(Json(LocalLoginResponse {
+        totp_required: false,
+    }))
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SetupQuery {
+    pub token: String,
+}
+
+/// GET /api/auth/local/setup
+#[utoipa::path(
+    get,
+    path = "/api/auth/local/setup",
+    tag = "auth",
+    params(SetupQuery),
+    responses(
+        (status = 200, body = AccountSetupInfo),
+        (status = 404, description = "The link is invalid or has expired")
+    ),
+    security(())
+)]
+pub async fn setup_info(
+    State(app_state): State<Arc<AppState>>,
+    Query(query): Query<SetupQuery>,
+) -> Result<Json<AccountSetupInfo>, AppError> {
+    require_enabled(&app_state)?;
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let account = accounts::find_by_setup_token(&mut conn, &query.token)?
+        .ok_or(AppError::NotFound("This link is invalid or has expired"))?;
+    let email: String = users::table
+        .find(account.user_id)
+        .select(users::email)
+        .first(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(Json(AccountSetupInfo {
+        username: account.username,
+        email,
+        expires_at: account
+            .setup_expires_at
+            .map(|t| t.and_utc().to_rfc3339())
+            .unwrap_or_default(),
+    }))
+}
+
+/// POST /api/auth/local/setup
+#[utoipa::path(
+    post,
+    path = "/api/auth/local/setup",
+    tag = "auth",
+    request_body = AccountSetupRequest,
+    responses(
+        (status = 200, description = "Password set; signed in unless the account needs an authenticator code", body = LocalLoginResponse),
+        (status = 400, description = "Password too short or too long"),
+        (status = 403, description = "The account is disabled"),
+        (status = 404, description = "The link is invalid or has expired")
+    ),
+    security(())
+)]
+pub async fn complete_setup(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(req): Json<AccountSetupRequest>,
+) -> Result<Json<LocalLoginResponse>, AppError> {
+    require_enabled(&app_state)?;
+    let account = {
+        let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+        accounts::find_by_setup_token(&mut conn, &req.token)?
+            .ok_or(AppError::NotFound("This link is invalid or has expired"))?
+    };
+    let hash = hash_new_password(req.password).await?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    accounts::set_password(&mut conn, account.user_id, &hash)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    info!("Password set for local account {}", account.username);
+
+    let disabled: bool = users::table
+        .find(account.user_id)
+        .select(users::disabled)
+        .first(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    if disabled {
+        return Err(AppError::Forbidden);
+    }
+
+    // A link only replaces the password; the second factor still applies
+    if !account.totp_enabled {
+        set_session_cookie(
+            &app_state,
+            &cookies,
+            account.user_id,
+            &ClientInfo::new(&headers, peer, &app_state.trusted_proxies),
+        )?;
+    }
+    Ok(Json(LocalLoginResponse {
+        totp_required: account.totp_enabled,
+    }))
+}
+
+/// GET /api/auth/local/account
+#[utoipa::path(
+    get,
+    path = "/api/auth/local/account",
+    tag = "auth",
+    responses((status = 200, body = LocalAccountInfo))
+)]
+pub async fn get_account(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<Json<LocalAccountInfo>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let account = accounts::find_by_user(&mut conn, user_id)?;
+
+    Ok(Json(LocalAccountInfo {
+        is_local: account.is_some(),
+        totp_enabled: account.as_ref().is_some_and(|a| a.totp_enabled),
+        password_changed_at: account
+            .as_ref()
+            .and_then(|a| a.password_changed_at)
+            .map(|t| t.and_utc().to_rfc3339()),
+        username: account.map(|a| a.username),
+    }))
+}
+
+/// POST /api/auth/local/password
+#[utoipa::path(
+    post,
+    path = "/api/auth/local/password",
+    tag = "auth",
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 204, description = "Password changed"),
+        (status = 400, description = "Wrong current password, or the new one is too short"),
+        (status = 404, description = "Not a local account")
+    )
+)]
+pub async fn change_password(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Json(req): Json<ChangePasswordRequest>,
+) -> Result<StatusCode, AppError> {
+    let account = {
+        let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+        current_account(&app_state, &cookies, &mut conn)?
+    };
+    let current_login = crate::auth::current_login(&app_state, &cookies)?;
+    if !password_matches(req.current_password, account.password_hash.clone()).await {
+        return Err(AppError::BadRequest("Current password is incorrect"));
+    }
+    let hash = hash_new_password(req.new_password).await?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    accounts::set_password(&mut conn, account.user_id, &hash)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    // Whoever else knew the old password loses the browsers it signed in
+    let signed_out = crate::handlers::web_logins::end_other_logins(
+        &app_state,
+        &mut conn,
+        account.user_id,
+        Some(current_login.id),
+    )
+    .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    info!(
+        "Local account {} changed its password, signing out {} other browser(s)",
+        account.username, signed_out
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/auth/local/totp/setup
+#[utoipa::path(
+    post,
+    path = "/api/auth/local/totp/setup",
+    tag = "auth",
+    responses(
+        (status = 200, description = "A new secret, enabled once a code from it is confirmed", body = TotpSetupResponse),
+        (status = 400, description = "A second factor is already enabled"),
+        (status = 404, description = "Not a local account")
+    )
+)]
+pub async fn totp_setup(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<Json<TotpSetupResponse>, AppError> {
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let account = current_account(&app_state, &cookies, &mut conn)?;
+    if account.totp_enabled {
+        return Err(AppError::BadRequest(
+            "Two-factor authentication is already enabled",
+        ));
+    }
+
+    let secret = accounts::generate_totp_secret();
+    diesel::update(local_accounts::table.find(account.user_id))
+        .set((
+            local_accounts::totp_secret.eq(&secret),
+            local_accounts::totp_last_step.eq(None::<i64>),
+            local_accounts::updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(Json(TotpSetupResponse {
+        otpauth_uri: accounts::totp_uri(
+            &app_state.settings.get().app_title,
+            &account.username,
+            &secret,
+        ),
+        secret,
+    }))
+}
+
+/// POST /api/auth/local/totp/enable
+#[utoipa::path(
+    post,
+    path = "/api/auth/local/totp/enable",
+    tag = "auth",
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 204, description = "Second factor enabled"),
+        (status = 400, description = "Wrong code, or no secret was set up"),
+        (status = 404, description = "Not a local account")
+    )
+)]
+pub async fn totp_enable(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Json(req): Json<TotpCodeRequest>,
+) -> Result<StatusCode, AppError> {
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let account = current_account(&app_state, &cookies, &mut conn)?;
+    let secret = match (&account.totp_secret, account.totp_enabled) {
+        (Some(secret), false) => secret.clone(),
+        _ => {
+            return Err(AppError::BadRequest(
+                "Set up two-factor authentication first",
+            ))
+        }
+    };
+    if !accounts::check_totp(&mut conn, &account, &secret, &req.code)? {
+        return Err(AppError::BadRequest("That code is not valid"));
+    }
+
+    diesel::update(local_accounts::table.find(account.user_id))
+        .set((
+            local_accounts::totp_enabled.eq(true),
+            local_accounts::updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    info!(
+        "Local account {} enabled two-factor authentication",
+        account.username
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/auth/local/totp/disable
+#[utoipa::path(
+    post,
+    path = "/api/auth/local/totp/disable",
+    tag = "auth",
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 204, description = "Second factor removed"),
+        (status = 400, description = "Wrong code, or no second factor is enabled"),
+        (status = 404, description = "Not a local account")
+    )
+)]
+pub async fn totp_disable(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Json(req): Json<TotpCodeRequest>,
+) -> Result<StatusCode, AppError> {
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let account = current_account(&app_state, &cookies, &mut conn)?;
+    let secret = match (&account.totp_secret, account.totp_enabled) {
+        (Some(secret), true) => secret.clone(),
+        _ => {
+            return Err(AppError::BadRequest(
+                "Two-factor authentication is not enabled",
+            ))
+        }
+    };
+    if !accounts::check_totp(&mut conn, &account, &secret, &req.code)? {
+        return Err(AppError::BadRequest("That code is not valid"));
+    }
+
+    accounts::disable_totp(&mut conn, account.user_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    info!(
+        "Local account {} disabled two-factor authentication",
+        account.username
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,10 +1,13 @@
 use crate::auth::extract_user_id;
 use crate::errors::AppError;
+use crate::export::{self, ExportFormat, ExportHeader, ExportMessage};
 use crate::models::{Message, NewMessage};
 use crate::schema::messages;
 use crate::AppState;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
     Json,
 };
 use diesel::prelude::*;
@@ -14,20 +17,20 @@ use std::sync::Arc;
 use tower_cookies::Cookies;
 
 /// Request body for creating a new message
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct CreateMessageRequest {
     pub role: String,
     pub content: String,
 }
 
 /// Response for message operations
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct MessageResponse {
     pub message: Message,
 }
 
 /// A message with optional sender name (for user-role messages in shared sessions)
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct MessageWithSender {
     #[serde(flatten)]
     pub message: Message,
@@ -36,29 +39,63 @@ pub struct MessageWithSender {
 }
 
 /// Response for listing messages
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct MessagesListResponse {
     pub messages: Vec<MessageWithSender>,
     pub total: i64,
 }
 
-/// Verify that a user has access to a session (is a member with any role)
+/// Verify that a user has access to a session, as a member with any role or
+/// through a team the session is shared with
 fn verify_session_access(
     conn: &mut diesel::pg::PgConnection,
     session_id: uuid::Uuid,
     user_id: uuid::Uuid,
 ) -> Result<crate::models::Session, AppError> {
-    use crate::schema::{session_members, sessions};
-    sessions::table
-        .inner_join(session_members::table.on(session_members::session_id.eq(sessions::id)))
-        .filter(sessions::id.eq(session_id))
-        .filter(session_members::user_id.eq(user_id))
-        .select(crate::models::Session::as_select())
-        .first::<crate::models::Session>(conn)
-        .map_err(|_| AppError::NotFound("Session not found"))
+    crate::teams::accessible_session(conn, session_id, user_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .map(|(session, _role)| session)
+        .ok_or(AppError::NotFound("Session not found"))
+}
+
+/// Display names of the users who sent the user-role messages in `messages`
+fn sender_names(
+    conn: &mut diesel::pg::PgConnection,
+    messages: &[Message],
+) -> HashMap<uuid::Uuid, String> {
+    use crate::schema::users;
+    let user_ids: Vec<uuid::Uuid> = messages
+        .iter()
+        .filter(|m| m.role == "user")
+        .map(|m| m.user_id)
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .collect();
+    if user_ids.is_empty() {
+        return HashMap::new();
+    }
+    users::table
+        .filter(users::id.eq_any(&user_ids))
+        .select((users::id, users::name, users::email))
+        .load::<(uuid::Uuid, Option<String>, String)>(conn)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(id, name, email)| (id, name.unwrap_or(email)))
+        .collect()
 }
 
 /// Create a new message for a session
+#[utoipa::path(
+    post,
+    path = "/api/sessions/{id}/messages",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    request_body = CreateMessageRequest,
+    responses(
+        (status = 200, body = MessageResponse),
+        (status = 404, description = "Session not found")
+    )
+)]
 pub async fn create_message(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -89,6 +126,16 @@ pub async fn create_message(
 }
 
 /// List messages for a session
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{id}/messages",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    responses(
+        (status = 200, body = MessagesListResponse),
+        (status = 404, description = "Session not found")
+    )
+)]
 pub async fn list_messages(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -106,27 +153,7 @@ pub async fn list_messages(
         .load(&mut conn)
         .map_err(|e| AppError::DbQuery(e.to_string()))?;
 
-    // Look up sender names for user-role messages
-    use crate::schema::users;
-    let user_ids: Vec<uuid::Uuid> = message_list
-        .iter()
-        .filter(|m| m.role == "user")
-        .map(|m| m.user_id)
-        .collect::<std::collections::HashSet<_>>()
-        .into_iter()
-        .collect();
-    let user_names: HashMap<uuid::Uuid, String> = if !user_ids.is_empty() {
-        users::table
-            .filter(users::id.eq_any(&user_ids))
-            .select((users::id, users::name, users::email))
-            .load::<(uuid::Uuid, Option<String>, String)>(&mut conn)
-            .unwrap_or_default()
-            .into_iter()
-            .map(|(id, name, email)| (id, name.unwrap_or(email)))
-            .collect()
-    } else {
-        HashMap::new()
-    };
+    let user_names = sender_names(&mut conn, &message_list);
 
     let total = message_list.len() as i64;
     let enriched: Vec<MessageWithSender> = message_list
@@ -149,3 +176,84 @@ pub async fn list_messages(
         total,
     }))
 }
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportQuery {
+    /// `md`, `html` or `jsonl` (default `md`)
+    pub format: Option<String>,
+}
+
+/// Download a session transcript as Markdown, HTML or JSONL
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{id}/export",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID"), ExportQuery),
+    responses(
+        (status = 200, description = "Transcript file, sent as an attachment", body = String),
+        (status = 400, description = "Unknown format"),
+        (status = 404, description = "Session not found")
+    )
+)]
+pub async fn export_messages(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(session_id): Path<uuid::Uuid>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, AppError> {
+    let current_user_id = extract_user_id(&app_state, &cookies)?;
+    let format: ExportFormat = query
+        .format
+        .as_deref()
+        .unwrap_or("md")
+        .parse()
+        .map_err(|_| AppError::BadRequest("Format must be md, html or jsonl"))?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let session = verify_session_access(&mut conn, session_id, current_user_id)?;
+
+    let message_list: Vec<Message> = messages::table
+        .filter(messages::session_id.eq(session_id))
+        .order(messages::created_at.asc())
+        .load(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    let user_names = sender_names(&mut conn, &message_list);
+
+    let entries: Vec<ExportMessage> = message_list
+        .iter()
+        .map(|msg| ExportMessage {
+            content: &msg.content,
+            sender: if msg.role == "user" {
+                user_names.get(&msg.user_id).map(String::as_str)
+            } else {
+                None
+            },
+            created_at: msg.created_at,
+        })
+        .collect();
+    let details = ExportHeader {
+        session_name: &session.session_name,
+        working_directory: &session.working_directory,
+        hostname: &session.hostname,
+        git_branch: session.git_branch.as_deref(),
+        agent_type: &session.agent_type,
+        created_at: session.created_at,
+        total_cost_usd: session.total_cost_usd,
+    };
+    let body = export::render(format, &details, &entries);
+    let file_name = export::file_name(&session.session_name, session.created_at, format);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
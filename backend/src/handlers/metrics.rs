@@ -0,0 +1,99 @@
+//! Prometheus Metrics Endpoint
+//!
+//! Serves `/metrics` for scraping. When `METRICS_TOKEN` is set, scrapers must
+//! send it as a bearer token; otherwise the endpoint is open, so keep it off
+//! the public internet.
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use diesel::prelude::*;
+use std::sync::Arc;
+
+use crate::metrics::{self, Gauges};
+use crate::schema::{pending_inputs, pending_permission_requests};
+use crate::AppState;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Whether the request carries the configured token, if there is one
+fn authorized(expected: Option<&str>, headers: &HeaderMap) -> bool {
+    let Some(expected) = expected else {
+        return true;
+    };
+    let presented = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+    // Compare digests so the check does not leak how much of the token matched
+    presented.is_some_and(|token| crate::jwt::hash_token(token) == crate::jwt::hash_token(expected))
+}
+
+fn gauges(app_state: &AppState) -> Gauges {
+    let session_manager = &app_state.session_manager;
+    let pool_state = app_state.db_pool.state();
+    let mut gauges = Gauges {
+        proxies: session_manager.sessions.len(),
+        web_clients: session_manager
+            .user_clients
+            .iter()
+            .map(|entry| entry.value().len())
+            .sum(),
+        launchers: session_manager.launchers.len(),
+        pool_connections: pool_state.connections,
+        pool_idle_connections: pool_state.idle_connections,
+        pool_max_connections: app_state.db_pool.max_size(),
+        ..Default::default()
+    };
+
+    match app_state.db_pool.get() {
+        Ok(mut conn) => {
+            gauges.pending_permissions = pending_permission_requests::table
+                .count()
+                .get_result(&mut conn)
+                .ok();
+            gauges.pending_inputs = pending_inputs::table.count().get_result(&mut conn).ok();
+        }
+        Err(e) => tracing::warn!("Metrics scrape could not reach the database: {}", e),
+    }
+    gauges
+}
+
+/// GET /metrics - Prometheus text exposition
+pub async fn metrics_handler(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, StatusCode> {
+    if !authorized(app_state.metrics_token.as_deref(), &headers) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    let body = metrics::render(&gauges(&app_state));
+    Ok(([(header::CONTENT_TYPE, CONTENT_TYPE)], body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_auth(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_open_without_token() {
+        assert!(authorized(None, &HeaderMap::new()));
+    }
+
+    #[test]
+    fn test_token_required_when_configured() {
+        assert!(!authorized(Some("s3cret"), &HeaderMap::new()));
+        assert!(!authorized(Some("s3cret"), &with_auth("Bearer wrong")));
+        assert!(!authorized(Some("s3cret"), &with_auth("s3cret")));
+        assert!(authorized(Some("s3cret"), &with_auth("Bearer s3cret")));
+    }
+}
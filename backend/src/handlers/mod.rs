@@ -1,15 +1,30 @@
 pub mod admin;
+pub mod analytics;
+pub mod api_keys;
 pub mod auth;
+pub mod budgets;
+pub mod chat_bridges;
 pub mod config;
 pub mod device_flow;
 pub mod downloads;
+pub mod email_settings;
 pub mod helpers;
 pub mod launchers;
+pub mod local_auth;
 pub mod messages;
+pub mod metrics;
+pub mod permission_decisions;
+pub mod permission_rules;
+pub mod permission_timeouts;
 pub mod proxy_tokens;
 pub mod retention;
 pub mod scheduled_tasks;
+pub mod search;
 pub mod sessions;
+pub mod share_links;
 pub mod sound_settings;
+pub mod teams;
 pub mod voice;
+pub mod web_logins;
+pub mod webhooks;
 pub mod websocket;
@@ -0,0 +1,101 @@
+//! Permission Decision Audit Handlers
+//!
+//! Read-only access to the log of answered permission requests. Any member of
+//! a session can see who approved or denied what in that session.
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use diesel::prelude::*;
+use shared::api::{PermissionDecider, PermissionDecisionInfo, PermissionDecisionListResponse};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tower_cookies::Cookies;
+use uuid::Uuid;
+
+use crate::{errors::AppError, models::PermissionDecision, schema::permission_decisions, AppState};
+
+/// Convert a PermissionDecision model to a PermissionDecisionInfo API response.
+fn decision_to_info(
+    d: PermissionDecision,
+    user_names: &HashMap<Uuid, String>,
+) -> PermissionDecisionInfo {
+    PermissionDecisionInfo {
+        id: d.id,
+        request_id: d.request_id,
+        tool_name: d.tool_name,
+        input: d.input,
+        allowed: match d.decision.as_str() {
+            "allow" => Some(true),
+            "deny" => Some(false),
+            _ => None,
+        },
+        decided_by: d.decided_by.parse().unwrap_or(PermissionDecider::User),
+        user_id: d.user_id,
+        user_name: d.user_id.and_then(|id| user_names.get(&id).cloned()),
+        permission_rule_id: d.permission_rule_id,
+        reason: d.reason,
+        requested_at: d.requested_at.and_utc().to_rfc3339(),
+        decided_at: d.decided_at.and_utc().to_rfc3339(),
+        latency_ms: d.latency_ms,
+    }
+}
+
+/// GET /api/sessions/:id/permission-decisions
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{id}/permission-decisions",
+    tag = "permissions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    responses(
+        (status = 200, body = PermissionDecisionListResponse),
+        (status = 404, description = "Session not found")
+    )
+)]
+pub async fn list_decisions_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<PermissionDecisionListResponse>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    use crate::schema::users;
+    crate::teams::session_role(&mut conn, session_id, user_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .ok_or(AppError::NotFound("Session not found"))?;
+
+    let decisions: Vec<PermissionDecision> = permission_decisions::table
+        .filter(permission_decisions::session_id.eq(session_id))
+        .order(permission_decisions::decided_at.desc())
+        .load(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    let user_ids: Vec<Uuid> = decisions
+        .iter()
+        .filter_map(|d| d.user_id)
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .collect();
+    let user_names: HashMap<Uuid, String> = if user_ids.is_empty() {
+        HashMap::new()
+    } else {
+        users::table
+            .filter(users::id.eq_any(&user_ids))
+            .select((users::id, users::name, users::email))
+            .load::<(Uuid, Option<String>, String)>(&mut conn)
+            .map_err(|e| AppError::DbQuery(e.to_string()))?
+            .into_iter()
+            .map(|(id, name, email)| (id, name.unwrap_or(email)))
+            .collect()
+    };
+
+    Ok(Json(PermissionDecisionListResponse {
+        decisions: decisions
+            .into_iter()
+            .map(|d| decision_to_info(d, &user_names))
+            .collect(),
+    }))
+}
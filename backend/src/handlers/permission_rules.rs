@@ -0,0 +1,253 @@
+//! Permission Rule Management Handlers
+//!
+//! CRUD endpoints for the server-side permission policy. Rules belong to the
+//! user who created them and are evaluated for every session they own.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use diesel::prelude::*;
+use shared::api::{
+    CreatePermissionRuleRequest, PermissionRuleInfo, PermissionRuleListResponse, PermissionVerdict,
+    UpdatePermissionRuleRequest,
+};
+use std::sync::Arc;
+use tower_cookies::Cookies;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::{NewPermissionRule, PermissionRule},
+    permission_policy,
+    schema::permission_rules,
+    AppState,
+};
+
+/// Convert a PermissionRule model to a PermissionRuleInfo API response.
+fn rule_to_info(r: PermissionRule) -> PermissionRuleInfo {
+    PermissionRuleInfo {
+        id: r.id,
+        session_id: r.session_id,
+        tool_name: r.tool_name,
+        path_glob: r.path_glob,
+        command_regex: r.command_regex,
+        verdict: r.verdict.parse().unwrap_or(PermissionVerdict::Ask),
+        priority: r.priority,
+        description: r.description,
+        created_at: r.created_at.and_utc().to_rfc3339(),
+        updated_at: r.updated_at.and_utc().to_rfc3339(),
+    }
+}
+
+/// Treat blank optional patterns as absent
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty())
+}
+
+/// GET /api/permission-rules
+#[utoipa::path(
+    get,
+    path = "/api/permission-rules",
+    tag = "permissions",
+    responses((status = 200, body = PermissionRuleListResponse))
+)]
+pub async fn list_rules_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<Json<PermissionRuleListResponse>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let rules: Vec<PermissionRule> = permission_rules::table
+        .filter(permission_rules::user_id.eq(user_id))
+        .order((
+            permission_rules::priority.desc(),
+            permission_rules::created_at.asc(),
+        ))
+        .load(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(Json(PermissionRuleListResponse {
+        rules: rules.into_iter().map(rule_to_info).collect(),
+    }))
+}
+
+/// POST /api/permission-rules
+#[utoipa::path(
+    post,
+    path = "/api/permission-rules",
+    tag = "permissions",
+    request_body = CreatePermissionRuleRequest,
+    responses(
+        (status = 200, body = PermissionRuleInfo),
+        (status = 400, description = "Invalid rule"),
+        (status = 404, description = "Session not found")
+    )
+)]
+pub async fn create_rule_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Json(req): Json<CreatePermissionRuleRequest>,
+) -> Result<Json<PermissionRuleInfo>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let tool_name = req.tool_name.trim().to_string();
+    let path_glob = non_empty(req.path_glob);
+    let command_regex = non_empty(req.command_regex);
+    permission_policy::validate_rule(&tool_name, path_glob.as_deref(), command_regex.as_deref())
+        .map_err(AppError::BadRequest)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    // Session-scoped rules may only target sessions the user owns
+    if let Some(session_id) = req.session_id {
+        use crate::schema::sessions;
+        sessions::table
+            .filter(sessions::id.eq(session_id))
+            .filter(sessions::user_id.eq(user_id))
+            .select(sessions::id)
+            .first::<Uuid>(&mut conn)
+            .map_err(|_| AppError::NotFound("session"))?;
+    }
+
+    let new_rule = NewPermissionRule {
+        user_id,
+        session_id: req.session_id,
+        tool_name,
+        path_glob,
+        command_regex,
+        verdict: req.verdict.as_str().to_string(),
+        priority: req.priority,
+        description: non_empty(req.description),
+    };
+
+    let saved: PermissionRule = diesel::insert_into(permission_rules::table)
+        .values(&new_rule)
+        .get_result(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    info!(
+        "Created permission rule {} ({} -> {})",
+        saved.id, saved.tool_name, saved.verdict
+    );
+
+    Ok(Json(rule_to_info(saved)))
+}
+
+/// PATCH /api/permission-rules/:id
+#[utoipa::path(
+    patch,
+    path = "/api/permission-rules/{id}",
+    tag = "permissions",
+    params(("id" = Uuid, Path, description = "Rule ID")),
+    request_body = UpdatePermissionRuleRequest,
+    responses(
+        (status = 200, body = PermissionRuleInfo),
+        (status = 400, description = "Invalid rule"),
+        (status = 404, description = "Rule not found")
+    )
+)]
+pub async fn update_rule_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(rule_id): Path<Uuid>,
+    Json(req): Json<UpdatePermissionRuleRequest>,
+) -> Result<Json<PermissionRuleInfo>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let existing: PermissionRule = permission_rules::table
+        .filter(permission_rules::id.eq(rule_id))
+        .filter(permission_rules::user_id.eq(user_id))
+        .first(&mut conn)
+        .map_err(|_| AppError::NotFound("permission rule"))?;
+
+    let tool_name = req
+        .tool_name
+        .map(|t| t.trim().to_string())
+        .unwrap_or(existing.tool_name);
+    let path_glob = match req.path_glob {
+        Some(glob) => non_empty(glob),
+        None => existing.path_glob,
+    };
+    let command_regex = match req.command_regex {
+        Some(re) => non_empty(re),
+        None => existing.command_regex,
+    };
+    let verdict = req
+        .verdict
+        .map(|v| v.as_str().to_string())
+        .unwrap_or(existing.verdict);
+    let priority = req.priority.unwrap_or(existing.priority);
+    let description = match req.description {
+        Some(d) => non_empty(d),
+        None => existing.description,
+    };
+
+    permission_policy::validate_rule(&tool_name, path_glob.as_deref(), command_regex.as_deref())
+        .map_err(AppError::BadRequest)?;
+
+    let updated: PermissionRule = diesel::update(
+        permission_rules::table
+            .filter(permission_rules::id.eq(rule_id))
+            .filter(permission_rules::user_id.eq(user_id)),
+    )
+    .set((
+        permission_rules::tool_name.eq(&tool_name),
+        permission_rules::path_glob.eq(&path_glob),
+        permission_rules::command_regex.eq(&command_regex),
+        permission_rules::verdict.eq(&verdict),
+        permission_rules::priority.eq(priority),
+        permission_rules::description.eq(&description),
+        permission_rules::updated_at.eq(diesel::dsl::now),
+    ))
+    .get_result(&mut conn)
+    .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    info!("Updated permission rule {}", updated.id);
+
+    Ok(Json(rule_to_info(updated)))
+}
+
+/// DELETE /api/permission-rules/:id
+#[utoipa::path(
+    delete,
+    path = "/api/permission-rules/{id}",
+    tag = "permissions",
+    params(("id" = Uuid, Path, description = "Rule ID")),
+    responses(
+        (status = 204, description = "Rule deleted"),
+        (status = 404, description = "Rule not found")
+    )
+)]
+pub async fn delete_rule_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(rule_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let deleted = diesel::delete(
+        permission_rules::table
+            .filter(permission_rules::id.eq(rule_id))
+            .filter(permission_rules::user_id.eq(user_id)),
+    )
+    .execute(&mut conn)
+    .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    if deleted == 0 {
+        return Err(AppError::NotFound("permission rule"));
+    }
+    permission_policy::forget_rule(rule_id);
+
+    info!("Deleted permission rule {}", rule_id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
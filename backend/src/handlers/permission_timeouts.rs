@@ -0,0 +1,153 @@
+//! Permission Timeout Handlers
+//!
+//! Per-session setting for how long a permission request may wait for an
+//! answer before it is denied, allowed, or escalated. Sessions without their
+//! own setting inherit the default of the scheduled task that spawned them.
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use diesel::prelude::*;
+use shared::api::{
+    validate_permission_timeout, PermissionTimeoutAction, PermissionTimeoutInfo,
+    UpdatePermissionTimeoutRequest,
+};
+use std::sync::Arc;
+use tower_cookies::Cookies;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::Session,
+    permission_policy,
+    schema::{scheduled_tasks, sessions},
+    AppState,
+};
+
+/// Look up the caller's role in a session.
+fn member_role(
+    conn: &mut PgConnection,
+    session_id: Uuid,
+    user_id: Uuid,
+) -> Result<String, AppError> {
+    crate::teams::session_role(conn, session_id, user_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .ok_or(AppError::NotFound("Session not found"))
+}
+
+/// Resolve the timeout that currently applies to a session.
+fn timeout_info(
+    conn: &mut PgConnection,
+    session: &Session,
+) -> Result<PermissionTimeoutInfo, AppError> {
+    let task_default: Option<(Option<i32>, String)> = match session.scheduled_task_id {
+        Some(task_id) => scheduled_tasks::table
+            .find(task_id)
+            .select((
+                scheduled_tasks::permission_timeout_seconds,
+                scheduled_tasks::permission_timeout_action,
+            ))
+            .first(conn)
+            .optional()
+            .map_err(|e| AppError::DbQuery(e.to_string()))?,
+        None => None,
+    };
+
+    let effective = permission_policy::effective_timeout(
+        session.permission_timeout_seconds,
+        session.permission_timeout_action.as_deref(),
+        task_default
+            .as_ref()
+            .map(|(seconds, action)| (*seconds, action.as_str())),
+    );
+    Ok(match effective {
+        Some(t) => PermissionTimeoutInfo {
+            timeout_seconds: Some(t.seconds),
+            action: t.action,
+            inherited: t.inherited,
+        },
+        None => PermissionTimeoutInfo {
+            timeout_seconds: None,
+            action: PermissionTimeoutAction::default(),
+            inherited: false,
+        },
+    })
+}
+
+/// GET /api/sessions/:id/permission-timeout
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{id}/permission-timeout",
+    tag = "permissions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    responses(
+        (status = 200, body = PermissionTimeoutInfo),
+        (status = 404, description = "Session not found")
+    )
+)]
+pub async fn get_timeout_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<PermissionTimeoutInfo>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    member_role(&mut conn, session_id, user_id)?;
+
+    let session: Session = sessions::table
+        .find(session_id)
+        .first(&mut conn)
+        .map_err(|_| AppError::NotFound("Session not found"))?;
+
+    Ok(Json(timeout_info(&mut conn, &session)?))
+}
+
+/// PUT /api/sessions/:id/permission-timeout (owner only)
+#[utoipa::path(
+    put,
+    path = "/api/sessions/{id}/permission-timeout",
+    tag = "permissions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    request_body = UpdatePermissionTimeoutRequest,
+    responses(
+        (status = 200, body = PermissionTimeoutInfo),
+        (status = 400, description = "Invalid timeout"),
+        (status = 403, description = "Only the owner can change the timeout"),
+        (status = 404, description = "Session not found")
+    )
+)]
+pub async fn update_timeout_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(session_id): Path<Uuid>,
+    Json(req): Json<UpdatePermissionTimeoutRequest>,
+) -> Result<Json<PermissionTimeoutInfo>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+    validate_permission_timeout(req.timeout_seconds).map_err(AppError::BadRequest)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    if member_role(&mut conn, session_id, user_id)? != "owner" {
+        return Err(AppError::Forbidden);
+    }
+
+    // Clearing the timeout also clears the action so the task default applies
+    let action = req.timeout_seconds.map(|_| req.action.as_str().to_string());
+    let session: Session = diesel::update(sessions::table.find(session_id))
+        .set((
+            sessions::permission_timeout_seconds.eq(req.timeout_seconds),
+            sessions::permission_timeout_action.eq(action),
+        ))
+        .get_result(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    tracing::info!(
+        "Permission timeout for session {} set to {:?} ({})",
+        session_id,
+        req.timeout_seconds,
+        req.action.as_str()
+    );
+
+    Ok(Json(timeout_info(&mut conn, &session)?))
+}
@@ -9,6 +9,8 @@ use axum::{
     Json,
 };
 use diesel::prelude::*;
+use serde_json::json;
+use shared::api::AuditAction;
 use shared::{
     CreateProxyTokenRequest, CreateProxyTokenResponse, ProxyInitConfig, ProxyTokenInfo,
     ProxyTokenListResponse, RenewProxyTokenRequest,
@@ -27,6 +29,15 @@ use crate::{
 };
 
 /// POST /api/proxy-tokens - Create a new proxy token
+#[utoipa::path(
+    post,
+    path = "/api/proxy-tokens",
+    tag = "credentials",
+    request_body = CreateProxyTokenRequest,
+    responses(
+        (status = 200, description = "The token, shown only once", body = CreateProxyTokenResponse)
+    )
+)]
 pub async fn create_token_handler(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -87,6 +98,13 @@ pub async fn create_token_handler(
     let init_url = format!("{}/p/{}", app_state.public_url, encoded_config);
 
     info!("Created proxy token '{}' for user {}", req.name, user.email);
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::ProxyTokenCreate,
+        Some(saved_token.id),
+        json!({ "name": req.name, "expires_at": expires_at.to_rfc3339() }),
+    );
 
     Ok(Json(CreateProxyTokenResponse {
         id: saved_token.id,
@@ -97,6 +115,12 @@ pub async fn create_token_handler(
 }
 
 /// GET /api/proxy-tokens - List all tokens for the current user
+#[utoipa::path(
+    get,
+    path = "/api/proxy-tokens",
+    tag = "credentials",
+    responses((status = 200, body = ProxyTokenListResponse))
+)]
 pub async fn list_tokens_handler(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -129,6 +153,16 @@ pub async fn list_tokens_handler(
 }
 
 /// DELETE /api/proxy-tokens/:id - Revoke a token
+#[utoipa::path(
+    delete,
+    path = "/api/proxy-tokens/{id}",
+    tag = "credentials",
+    params(("id" = Uuid, Path, description = "Token ID")),
+    responses(
+        (status = 204, description = "Token revoked"),
+        (status = 404, description = "Token not found")
+    )
+)]
 pub async fn revoke_token_handler(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -139,24 +173,45 @@ pub async fn revoke_token_handler(
     let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
 
     // Update token to revoked (only if owned by user)
-    let updated = diesel::update(
+    let name: Option<String> = diesel::update(
         proxy_auth_tokens::table
             .filter(proxy_auth_tokens::id.eq(token_id))
             .filter(proxy_auth_tokens::user_id.eq(user_id)),
     )
     .set(proxy_auth_tokens::revoked.eq(true))
-    .execute(&mut conn)
+    .returning(proxy_auth_tokens::name)
+    .get_result(&mut conn)
+    .optional()
     .map_err(|e| AppError::DbQuery(e.to_string()))?;
 
-    if updated == 0 {
+    let Some(name) = name else {
         return Err(AppError::NotFound("proxy token"));
-    }
+    };
 
     info!("Revoked proxy token {}", token_id);
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::ProxyTokenRevoke,
+        Some(token_id),
+        json!({ "name": name }),
+    );
     Ok(StatusCode::NO_CONTENT)
 }
 
 /// POST /api/proxy-tokens/:id/renew - Renew a token with a new expiration
+#[utoipa::path(
+    post,
+    path = "/api/proxy-tokens/{id}/renew",
+    tag = "credentials",
+    params(("id" = Uuid, Path, description = "Token ID")),
+    request_body = RenewProxyTokenRequest,
+    responses(
+        (status = 200, description = "A replacement token, shown only once", body = CreateProxyTokenResponse),
+        (status = 400, description = "Token is revoked"),
+        (status = 404, description = "Token not found")
+    )
+)]
 pub async fn renew_token_handler(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -223,6 +278,13 @@ pub async fn renew_token_handler(
         "Renewed proxy token '{}' for user {}",
         existing.name, user.email
     );
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::ProxyTokenRenew,
+        Some(token_id),
+        json!({ "name": existing.name, "expires_at": expires_at.to_rfc3339() }),
+    );
 
     Ok(Json(CreateProxyTokenResponse {
         id: token_id,
@@ -1,6 +1,8 @@
 //! Scheduled Task Management Handlers
 //!
-//! CRUD endpoints for managing scheduled (cron) tasks.
+//! CRUD endpoints for managing scheduled (cron) tasks. A task may be shared
+//! with one of its owner's teams: members then see it and its runs, and each
+//! run is shared with the team, but only the owner can change the task.
 
 use axum::{
     extract::{Path, State},
@@ -8,9 +10,10 @@ use axum::{
     Json,
 };
 use diesel::prelude::*;
+use serde_json::json;
 use shared::api::{
-    CreateScheduledTaskRequest, ScheduledTaskInfo, ScheduledTaskListResponse,
-    UpdateScheduledTaskRequest,
+    validate_permission_timeout, AuditAction, CreateScheduledTaskRequest, ScheduledTaskInfo,
+    ScheduledTaskListResponse, UpdateScheduledTaskRequest,
 };
 use shared::{AgentType, ScheduledTaskConfig, ServerToLauncher};
 use std::sync::Arc;
@@ -21,12 +24,41 @@ use uuid::Uuid;
 use crate::{
     errors::AppError,
     models::{NewScheduledTask, ScheduledTask},
-    schema::scheduled_tasks,
+    schema::{scheduled_tasks, team_members},
     AppState,
 };
 
-/// Convert a ScheduledTask model to a ScheduledTaskInfo API response.
-fn task_to_info(t: ScheduledTask) -> ScheduledTaskInfo {
+/// Fields the scheduler changes on its own, left out of audit events
+const RUN_STATE_FIELDS: [&str; 5] = [
+    "updated_at",
+    "last_session_id",
+    "last_run_at",
+    "last_exit_code",
+    "last_run_completed_at",
+];
+
+/// What an edit changed, as `{"field": {"from": ..., "to": ...}}`
+fn task_changes(before: &ScheduledTask, after: &ScheduledTask) -> serde_json::Value {
+    let (Ok(serde_json::Value::Object(before)), Ok(serde_json::Value::Object(after))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return json!({});
+    };
+    let changes: serde_json::Map<String, serde_json::Value> = after
+        .into_iter()
+        .filter(|(field, _)| !RUN_STATE_FIELDS.contains(&field.as_str()))
+        .filter(|(field, value)| before.get(field) != Some(value))
+        .map(|(field, value)| {
+            let from = before.get(&field).cloned().unwrap_or_default();
+            (field, json!({ "from": from, "to": value }))
+        })
+        .collect();
+    serde_json::Value::Object(changes)
+}
+
+/// Convert a ScheduledTask model to a ScheduledTaskInfo API response for
+/// `viewer`.
+fn task_to_info(t: ScheduledTask, viewer: Uuid) -> ScheduledTaskInfo {
     ScheduledTaskInfo {
         id: t.id,
         name: t.name,
@@ -39,13 +71,29 @@ fn task_to_info(t: ScheduledTask) -> ScheduledTaskInfo {
         agent_type: t.agent_type.parse().unwrap_or(AgentType::Claude),
         enabled: t.enabled,
         max_runtime_minutes: t.max_runtime_minutes,
+        permission_timeout_seconds: t.permission_timeout_seconds,
+        permission_timeout_action: t.permission_timeout_action.parse().unwrap_or_default(),
         last_session_id: t.last_session_id,
         last_run_at: t.last_run_at.map(|dt| dt.and_utc().to_rfc3339()),
         created_at: t.created_at.and_utc().to_rfc3339(),
         updated_at: t.updated_at.and_utc().to_rfc3339(),
+        team_id: t.team_id,
+        owned: t.user_id == viewer,
     }
 }
 
+/// A task can only be shared with a team its owner belongs to.
+fn require_team_member(
+    conn: &mut PgConnection,
+    team_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), AppError> {
+    crate::teams::team_role(conn, team_id, user_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .map(|_| ())
+        .ok_or(AppError::BadRequest("You are not a member of that team"))
+}
+
 /// Convert a ScheduledTask model to a ScheduledTaskConfig protocol message.
 fn task_to_config(t: &ScheduledTask) -> ScheduledTaskConfig {
     ScheduledTaskConfig {
@@ -60,6 +108,8 @@ fn task_to_config(t: &ScheduledTask) -> ScheduledTaskConfig {
         enabled: t.enabled,
         max_runtime_minutes: t.max_runtime_minutes,
         last_session_id: t.last_session_id,
+        permission_timeout_seconds: t.permission_timeout_seconds,
+        permission_timeout_action: t.permission_timeout_action.parse().unwrap_or_default(),
     }
 }
 
@@ -103,6 +153,12 @@ fn send_schedule_sync(app_state: &AppState, user_id: Uuid) {
 // ============================================================================
 
 /// GET /api/scheduled-tasks
+#[utoipa::path(
+    get,
+    path = "/api/scheduled-tasks",
+    tag = "scheduled-tasks",
+    responses((status = 200, description = "The user's tasks and tasks shared with their teams", body = ScheduledTaskListResponse))
+)]
 pub async fn list_tasks_handler(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -111,17 +167,37 @@ pub async fn list_tasks_handler(
 
     let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
 
+    let my_teams = team_members::table
+        .filter(team_members::user_id.eq(user_id))
+        .select(team_members::team_id);
     let tasks: Vec<ScheduledTask> = scheduled_tasks::table
-        .filter(scheduled_tasks::user_id.eq(user_id))
+        .filter(
+            scheduled_tasks::user_id
+                .eq(user_id)
+                .or(scheduled_tasks::team_id.eq_any(my_teams.nullable())),
+        )
         .order(scheduled_tasks::created_at.desc())
         .load(&mut conn)
         .map_err(|e| AppError::DbQuery(e.to_string()))?;
 
-    let infos: Vec<ScheduledTaskInfo> = tasks.into_iter().map(task_to_info).collect();
+    let infos: Vec<ScheduledTaskInfo> = tasks
+        .into_iter()
+        .map(|t| task_to_info(t, user_id))
+        .collect();
     Ok(Json(ScheduledTaskListResponse { tasks: infos }))
 }
 
 /// POST /api/scheduled-tasks
+#[utoipa::path(
+    post,
+    path = "/api/scheduled-tasks",
+    tag = "scheduled-tasks",
+    request_body = CreateScheduledTaskRequest,
+    responses(
+        (status = 200, body = ScheduledTaskInfo),
+        (status = 400, description = "Invalid schedule or task, or not a member of the team")
+    )
+)]
 pub async fn create_task_handler(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -135,9 +211,14 @@ pub async fn create_task_handler(
         warn!("Invalid cron expression: {}", req.cron_expression);
         return Err(AppError::Internal("Invalid cron expression".to_string()));
     }
+    validate_permission_timeout(req.permission_timeout_seconds).map_err(AppError::BadRequest)?;
 
     let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
 
+    if let Some(team_id) = req.team_id {
+        require_team_member(&mut conn, team_id, user_id)?;
+    }
+
     let new_task = NewScheduledTask {
         user_id,
         name: req.name,
@@ -149,6 +230,9 @@ pub async fn create_task_handler(
         claude_args: serde_json::to_value(req.claude_args).unwrap_or_default(),
         agent_type: req.agent_type.as_str().to_string(),
         max_runtime_minutes: req.max_runtime_minutes,
+        permission_timeout_seconds: req.permission_timeout_seconds,
+        permission_timeout_action: req.permission_timeout_action.as_str().to_string(),
+        team_id: req.team_id,
     };
 
     let saved: ScheduledTask = diesel::insert_into(scheduled_tasks::table)
@@ -157,14 +241,39 @@ pub async fn create_task_handler(
         .map_err(|e| AppError::DbQuery(e.to_string()))?;
 
     info!("Created scheduled task '{}' ({})", saved.name, saved.id);
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::ScheduledTaskCreate,
+        Some(saved.id),
+        json!({
+            "name": saved.name,
+            "cron_expression": saved.cron_expression,
+            "hostname": saved.hostname,
+            "working_directory": saved.working_directory,
+            "team_id": saved.team_id,
+        }),
+    );
 
     // Notify connected launchers
     send_schedule_sync(&app_state, user_id);
 
-    Ok(Json(task_to_info(saved)))
+    Ok(Json(task_to_info(saved, user_id)))
 }
 
 /// PATCH /api/scheduled-tasks/:id
+#[utoipa::path(
+    patch,
+    path = "/api/scheduled-tasks/{id}",
+    tag = "scheduled-tasks",
+    params(("id" = Uuid, Path, description = "Scheduled task ID")),
+    request_body = UpdateScheduledTaskRequest,
+    responses(
+        (status = 200, body = ScheduledTaskInfo),
+        (status = 400, description = "Invalid schedule or task"),
+        (status = 404, description = "Task not found")
+    )
+)]
 pub async fn update_task_handler(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -181,6 +290,7 @@ pub async fn update_task_handler(
         .filter(scheduled_tasks::user_id.eq(user_id))
         .first(&mut conn)
         .map_err(|_| AppError::NotFound("scheduled task"))?;
+    let before = existing.clone();
 
     // Validate cron if provided
     if let Some(ref cron) = req.cron_expression {
@@ -190,6 +300,12 @@ pub async fn update_task_handler(
             return Err(AppError::Internal("Invalid cron expression".to_string()));
         }
     }
+    if let Some(seconds) = req.permission_timeout_seconds {
+        validate_permission_timeout(seconds).map_err(AppError::BadRequest)?;
+    }
+    if let Some(Some(team_id)) = req.team_id {
+        require_team_member(&mut conn, team_id, user_id)?;
+    }
 
     // Apply updates field by field (load-modify-save pattern)
     let name = req.name.unwrap_or(existing.name);
@@ -213,6 +329,14 @@ pub async fn update_task_handler(
     let max_runtime_minutes = req
         .max_runtime_minutes
         .unwrap_or(existing.max_runtime_minutes);
+    let permission_timeout_seconds = req
+        .permission_timeout_seconds
+        .unwrap_or(existing.permission_timeout_seconds);
+    let permission_timeout_action = req
+        .permission_timeout_action
+        .map(|a| a.as_str().to_string())
+        .unwrap_or(existing.permission_timeout_action);
+    let team_id = req.team_id.unwrap_or(existing.team_id);
 
     let updated: ScheduledTask = diesel::update(
         scheduled_tasks::table
@@ -230,20 +354,40 @@ pub async fn update_task_handler(
         scheduled_tasks::agent_type.eq(&agent_type),
         scheduled_tasks::enabled.eq(enabled),
         scheduled_tasks::max_runtime_minutes.eq(max_runtime_minutes),
+        scheduled_tasks::permission_timeout_seconds.eq(permission_timeout_seconds),
+        scheduled_tasks::permission_timeout_action.eq(&permission_timeout_action),
+        scheduled_tasks::team_id.eq(team_id),
         scheduled_tasks::updated_at.eq(diesel::dsl::now),
     ))
     .get_result(&mut conn)
     .map_err(|e| AppError::DbQuery(e.to_string()))?;
 
     info!("Updated scheduled task '{}' ({})", updated.name, updated.id);
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::ScheduledTaskUpdate,
+        Some(task_id),
+        json!({ "name": updated.name, "changes": task_changes(&before, &updated) }),
+    );
 
     // Notify connected launchers
     send_schedule_sync(&app_state, user_id);
 
-    Ok(Json(task_to_info(updated)))
+    Ok(Json(task_to_info(updated, user_id)))
 }
 
 /// DELETE /api/scheduled-tasks/:id
+#[utoipa::path(
+    delete,
+    path = "/api/scheduled-tasks/{id}",
+    tag = "scheduled-tasks",
+    params(("id" = Uuid, Path, description = "Scheduled task ID")),
+    responses(
+        (status = 204, description = "Task deleted"),
+        (status = 404, description = "Task not found")
+    )
+)]
 pub async fn delete_task_handler(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -271,6 +415,13 @@ pub async fn delete_task_handler(
         .map_err(|e| AppError::DbQuery(e.to_string()))?;
 
     info!("Deleted scheduled task '{}' ({})", task.name, task_id);
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::ScheduledTaskDelete,
+        Some(task_id),
+        json!({ "name": task.name }),
+    );
 
     // Notify connected launchers
     send_schedule_sync(&app_state, user_id);
@@ -279,6 +430,16 @@ pub async fn delete_task_handler(
 }
 
 /// GET /api/scheduled-tasks/:id/runs
+#[utoipa::path(
+    get,
+    path = "/api/scheduled-tasks/{id}/runs",
+    tag = "scheduled-tasks",
+    params(("id" = Uuid, Path, description = "Scheduled task ID")),
+    responses(
+        (status = 200, description = "The 50 most recent sessions the task started", body = Vec<crate::models::Session>),
+        (status = 404, description = "Task not found")
+    )
+)]
 pub async fn list_runs_handler(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -288,12 +449,22 @@ pub async fn list_runs_handler(
 
     let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
 
-    // Verify task ownership
-    let _task: ScheduledTask = scheduled_tasks::table
+    // The task's owner and members of its team can see its runs
+    let task: ScheduledTask = scheduled_tasks::table
         .filter(scheduled_tasks::id.eq(task_id))
-        .filter(scheduled_tasks::user_id.eq(user_id))
         .first(&mut conn)
         .map_err(|_| AppError::NotFound("scheduled task"))?;
+    if task.user_id != user_id {
+        let in_team = match task.team_id {
+            Some(team_id) => crate::teams::team_role(&mut conn, team_id, user_id)
+                .map_err(|e| AppError::DbQuery(e.to_string()))?
+                .is_some(),
+            None => false,
+        };
+        if !in_team {
+            return Err(AppError::NotFound("scheduled task"));
+        }
+    }
 
     use crate::schema::sessions;
     let runs: Vec<crate::models::Session> = sessions::table
@@ -305,3 +476,54 @@ pub async fn list_runs_handler(
 
     Ok(Json(serde_json::to_value(runs).unwrap_or_default()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task() -> ScheduledTask {
+        let now = chrono::Utc::now().naive_utc();
+        ScheduledTask {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            name: "nightly".to_string(),
+            cron_expression: "0 3 * * *".to_string(),
+            timezone: "UTC".to_string(),
+            hostname: "build-1".to_string(),
+            working_directory: "/srv/app".to_string(),
+            prompt: "Run the tests".to_string(),
+            claude_args: json!([]),
+            agent_type: "claude".to_string(),
+            enabled: true,
+            max_runtime_minutes: 30,
+            last_session_id: None,
+            last_run_at: None,
+            created_at: now,
+            updated_at: now,
+            permission_timeout_seconds: None,
+            permission_timeout_action: "deny".to_string(),
+            last_exit_code: None,
+            last_run_completed_at: None,
+            team_id: None,
+        }
+    }
+
+    #[test]
+    fn test_task_changes_lists_edited_fields_only() {
+        let before = task();
+        let mut after = before.clone();
+        after.cron_expression = "0 4 * * *".to_string();
+        after.enabled = false;
+        after.updated_at += chrono::Duration::seconds(5);
+        after.last_run_at = Some(after.updated_at);
+
+        assert_eq!(
+            task_changes(&before, &after),
+            json!({
+                "cron_expression": { "from": "0 3 * * *", "to": "0 4 * * *" },
+                "enabled": { "from": true, "to": false },
+            })
+        );
+        assert_eq!(task_changes(&before, &before), json!({}));
+    }
+}
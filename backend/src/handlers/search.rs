@@ -0,0 +1,224 @@
+//! Transcript Search Handlers
+//!
+//! Full-text search over the messages of every session the user is a member
+//! of, directly or through a team. Matching runs against the `idx_messages_search` expression index, which
+//! covers the prose, tool inputs and tool results that `message_search_text`
+//! extracts from the stored JSON.
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Float4, Nullable, Text, Timestamp, Uuid as SqlUuid, Varchar};
+use serde::Deserialize;
+use shared::api::{SearchHit, SearchResponse};
+use shared::AgentType;
+use std::sync::Arc;
+use tower_cookies::Cookies;
+use uuid::Uuid;
+
+use crate::{errors::AppError, AppState};
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchQuery {
+    /// Search terms. Supports `"quoted phrases"`, `or` and `-excluded` words
+    pub q: String,
+    /// Only this session
+    pub session_id: Option<Uuid>,
+    /// Only sessions on this host
+    pub hostname: Option<String>,
+    /// Only sessions on this git branch
+    pub branch: Option<String>,
+    /// Only sessions of this agent (`claude` or `codex`)
+    pub agent_type: Option<String>,
+    /// Messages from this day on (`YYYY-MM-DD`, UTC)
+    pub from: Option<String>,
+    /// Messages up to and including this day (`YYYY-MM-DD`, UTC)
+    pub to: Option<String>,
+    /// Page size (default 20, at most 100)
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(QueryableByName)]
+struct SearchRow {
+    #[diesel(sql_type = SqlUuid)]
+    message_id: Uuid,
+    #[diesel(sql_type = SqlUuid)]
+    session_id: Uuid,
+    #[diesel(sql_type = Varchar)]
+    session_name: String,
+    #[diesel(sql_type = Text)]
+    working_directory: String,
+    #[diesel(sql_type = Varchar)]
+    hostname: String,
+    #[diesel(sql_type = Nullable<Varchar>)]
+    git_branch: Option<String>,
+    #[diesel(sql_type = Varchar)]
+    agent_type: String,
+    #[diesel(sql_type = Varchar)]
+    role: String,
+    #[diesel(sql_type = Timestamp)]
+    created_at: NaiveDateTime,
+    #[diesel(sql_type = Text)]
+    snippet: String,
+    #[diesel(sql_type = Float4)]
+    rank: f32,
+}
+
+/// Ranks matches in an inner query so `ts_headline`, which re-reads the whole
+/// message, only runs for the page being returned.
+const SEARCH_SQL: &str = "\
+    SELECT hit.id AS message_id, hit.session_id, s.session_name, s.working_directory, \
+           s.hostname, s.git_branch, s.agent_type, hit.role, hit.created_at, hit.rank, \
+           ts_headline('english', message_search_text(hit.content), \
+                       websearch_to_tsquery('english', $2), \
+                       'StartSel=«, StopSel=», MaxFragments=2, MinWords=8, MaxWords=24, \
+                        FragmentDelimiter=\" … \"') AS snippet \
+    FROM ( \
+        SELECT m.id, m.session_id, m.role, m.content, m.created_at, \
+               ts_rank(to_tsvector('english', message_search_text(m.content)), \
+                       websearch_to_tsquery('english', $2)) AS rank \
+        FROM messages m \
+        JOIN sessions s ON s.id = m.session_id \
+        WHERE m.session_id IN ( \
+              SELECT session_id FROM session_members WHERE user_id = $1 \
+              UNION \
+              SELECT st.session_id FROM session_teams st \
+              JOIN team_members tm ON tm.team_id = st.team_id AND tm.user_id = $1) \
+          AND to_tsvector('english', message_search_text(m.content)) \
+              @@ websearch_to_tsquery('english', $2) \
+          AND s.status <> 'replaced' \
+          AND ($3::uuid IS NULL OR m.session_id = $3) \
+          AND ($4::text IS NULL OR s.hostname = $4) \
+          AND ($5::text IS NULL OR s.git_branch = $5) \
+          AND ($6::text IS NULL OR s.agent_type = $6) \
+          AND ($7::timestamp IS NULL OR m.created_at >= $7) \
+          AND ($8::timestamp IS NULL OR m.created_at < $8) \
+        ORDER BY rank DESC, m.created_at DESC \
+        LIMIT $9 OFFSET $10 \
+    ) hit \
+    JOIN sessions s ON s.id = hit.session_id \
+    ORDER BY hit.rank DESC, hit.created_at DESC";
+
+/// Treat blank form fields as absent.
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Parse a `YYYY-MM-DD` filter into the start of that day.
+fn parse_day(value: Option<String>) -> Result<Option<NaiveDateTime>, AppError> {
+    non_empty(value)
+        .map(|day| {
+            NaiveDate::parse_from_str(&day, "%Y-%m-%d")
+                .map(|d| d.and_hms_opt(0, 0, 0).unwrap_or_default())
+                .map_err(|_| AppError::BadRequest("Dates must be YYYY-MM-DD"))
+        })
+        .transpose()
+}
+
+/// GET /api/search?q=flaky+migration
+#[utoipa::path(
+    get,
+    path = "/api/search",
+    tag = "sessions",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Matching messages, best first", body = SearchResponse),
+        (status = 400, description = "Empty query, unknown agent type or malformed date")
+    )
+)]
+pub async fn search_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<SearchResponse>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let terms = query.q.trim().to_string();
+    if terms.is_empty() {
+        return Err(AppError::BadRequest("Search query is empty"));
+    }
+    let agent_type = non_empty(query.agent_type)
+        .map(|a| {
+            a.parse::<AgentType>()
+                .map_err(|_| AppError::BadRequest("Unknown agent type"))
+        })
+        .transpose()?;
+    let from = parse_day(query.from)?;
+    // `to` is inclusive, so stop at the start of the following day
+    let until = parse_day(query.to)?.map(|day| day + chrono::Duration::days(1));
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    // Fetch one extra row to learn whether another page follows
+    let mut rows: Vec<SearchRow> = diesel::sql_query(SEARCH_SQL)
+        .bind::<SqlUuid, _>(user_id)
+        .bind::<Text, _>(&terms)
+        .bind::<Nullable<SqlUuid>, _>(query.session_id)
+        .bind::<Nullable<Text>, _>(non_empty(query.hostname))
+        .bind::<Nullable<Text>, _>(non_empty(query.branch))
+        .bind::<Nullable<Text>, _>(agent_type.map(|a| a.as_str().to_string()))
+        .bind::<Nullable<Timestamp>, _>(from)
+        .bind::<Nullable<Timestamp>, _>(until)
+        .bind::<BigInt, _>(limit + 1)
+        .bind::<BigInt, _>(offset)
+        .load(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    let has_more = rows.len() as i64 > limit;
+    rows.truncate(limit as usize);
+
+    Ok(Json(SearchResponse {
+        hits: rows
+            .into_iter()
+            .map(|r| SearchHit {
+                message_id: r.message_id,
+                session_id: r.session_id,
+                session_name: r.session_name,
+                working_directory: r.working_directory,
+                hostname: r.hostname,
+                git_branch: r.git_branch,
+                agent_type: r.agent_type.parse().unwrap_or(AgentType::Claude),
+                role: r.role,
+                created_at: r.created_at.and_utc().to_rfc3339(),
+                snippet: r.snippet,
+                rank: r.rank,
+            })
+            .collect(),
+        has_more,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blank_filters_are_ignored() {
+        assert_eq!(non_empty(None), None);
+        assert_eq!(non_empty(Some("   ".to_string())), None);
+        assert_eq!(
+            non_empty(Some(" main ".to_string())),
+            Some("main".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_day() {
+        let day = parse_day(Some("2026-03-20".to_string())).unwrap().unwrap();
+        assert_eq!(day.to_string(), "2026-03-20 00:00:00");
+        assert!(parse_day(Some(String::new())).unwrap().is_none());
+        assert!(parse_day(Some("20/03/2026".to_string())).is_err());
+    }
+}
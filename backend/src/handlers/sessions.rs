@@ -5,9 +5,15 @@ use axum::{
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use serde::Serialize;
-use shared::api::{AddMemberRequest, UpdateMemberRoleRequest};
+use serde_json::json;
+use shared::api::{
+    AddMemberRequest, AuditAction, ForkSessionRequest, ForkSessionResponse, SendInputRequest,
+    UpdateMemberRoleRequest,
+};
+use shared::{AgentType, ServerToLauncher, SessionFork};
 use std::sync::Arc;
 use tower_cookies::Cookies;
+use tracing::info;
 use uuid::Uuid;
 
 use crate::{
@@ -18,18 +24,24 @@ use crate::{
 };
 
 /// Session with the current user's role included
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct SessionWithRole {
     #[serde(flatten)]
     pub session: Session,
     pub my_role: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct SessionListResponse {
     pub sessions: Vec<SessionWithRole>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/sessions",
+    tag = "sessions",
+    responses((status = 200, description = "Sessions the user is a member of or that are shared with their teams", body = SessionListResponse))
+)]
 pub async fn list_sessions(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -49,25 +61,50 @@ pub async fn list_sessions(
         .load(&mut conn)
         .map_err(|e| AppError::DbQuery(e.to_string()))?;
 
-    let sessions_with_role = results
-        .into_iter()
-        .map(|(session, role)| SessionWithRole {
-            session,
-            my_role: role,
-        })
-        .collect();
+    let shared = crate::teams::team_shared_sessions(&mut conn, current_user_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    // A session reached both directly and through teams is listed once, with
+    // the strongest role
+    let mut sessions_with_role: Vec<SessionWithRole> = Vec::with_capacity(results.len());
+    for (session, role) in results.into_iter().chain(shared) {
+        match sessions_with_role
+            .iter_mut()
+            .find(|s| s.session.id == session.id)
+        {
+            Some(existing) => {
+                existing.my_role =
+                    crate::teams::stronger_role(std::mem::take(&mut existing.my_role), role);
+            }
+            None => sessions_with_role.push(SessionWithRole {
+                session,
+                my_role: role,
+            }),
+        }
+    }
+    sessions_with_role.sort_by_key(|s| std::cmp::Reverse(s.session.last_activity));
 
     Ok(Json(SessionListResponse {
         sessions: sessions_with_role,
     }))
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct SessionDetailResponse {
     pub session: Session,
     pub recent_messages: Vec<Message>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{id}",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    responses(
+        (status = 200, description = "The session and its recent messages", body = SessionDetailResponse),
+        (status = 404, description = "Session not found")
+    )
+)]
 pub async fn get_session(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -77,15 +114,9 @@ pub async fn get_session(
 
     let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
 
-    use crate::schema::{messages, session_members, sessions};
+    use crate::schema::messages;
 
-    let session = sessions::table
-        .inner_join(session_members::table.on(session_members::session_id.eq(sessions::id)))
-        .filter(sessions::id.eq(session_id))
-        .filter(session_members::user_id.eq(current_user_id))
-        .select(Session::as_select())
-        .first::<Session>(&mut conn)
-        .optional()
+    let (session, _role) = crate::teams::accessible_session(&mut conn, session_id, current_user_id)
         .map_err(|e| AppError::DbQuery(e.to_string()))?
         .ok_or(AppError::NotFound("Session not found"))?;
 
@@ -102,6 +133,16 @@ pub async fn get_session(
     }))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/sessions/{id}",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    responses(
+        (status = 204, description = "Session deleted"),
+        (status = 404, description = "Session not found or not owned by the user")
+    )
+)]
 pub async fn delete_session(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -130,6 +171,16 @@ pub async fn delete_session(
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/sessions/{id}/stop",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    responses(
+        (status = 202, description = "Stop requested"),
+        (status = 404, description = "Session not found or not connected")
+    )
+)]
 pub async fn stop_session(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -139,15 +190,7 @@ pub async fn stop_session(
 
     let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
 
-    use crate::schema::{session_members, sessions};
-
-    sessions::table
-        .inner_join(session_members::table.on(session_members::session_id.eq(sessions::id)))
-        .filter(sessions::id.eq(session_id))
-        .filter(session_members::user_id.eq(current_user_id))
-        .select(Session::as_select())
-        .first::<Session>(&mut conn)
-        .optional()
+    crate::teams::session_role(&mut conn, session_id, current_user_id)
         .map_err(|e| AppError::DbQuery(e.to_string()))?
         .ok_or(AppError::NotFound("Session not found"))?;
 
@@ -163,11 +206,205 @@ pub async fn stop_session(
     }
 }
 
+/// POST /api/sessions/:id/input
+///
+/// Send input to a session as if it were typed in the web client. Input is
+/// queued and delivered when the proxy is (re)connected.
+#[utoipa::path(
+    post,
+    path = "/api/sessions/{id}/input",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    request_body = SendInputRequest,
+    responses(
+        (status = 202, description = "Input accepted; queued if the session is not connected"),
+        (status = 400, description = "Empty content"),
+        (status = 403, description = "Viewers cannot send input, or a spend limit is reached"),
+        (status = 404, description = "Session not found")
+    )
+)]
+pub async fn send_input(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(session_id): Path<Uuid>,
+    Json(req): Json<SendInputRequest>,
+) -> Result<axum::http::StatusCode, AppError> {
+    let current_user_id = extract_user_id(&app_state, &cookies)?;
+
+    match &req.content {
+        serde_json::Value::String(text) if !text.trim().is_empty() => {}
+        serde_json::Value::Array(blocks) if !blocks.is_empty() => {}
+        _ => {
+            return Err(AppError::BadRequest(
+                "content must be non-empty text or an array of content blocks",
+            ))
+        }
+    }
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let role = crate::teams::session_role(&mut conn, session_id, current_user_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .ok_or(AppError::NotFound("Session not found"))?;
+    if role == "viewer" {
+        return Err(AppError::Forbidden);
+    }
+
+    crate::handlers::websocket::submit_input(
+        &app_state.session_manager,
+        &app_state.db_pool,
+        &session_id.to_string(),
+        session_id,
+        req.content,
+        req.send_mode,
+        current_user_id,
+    )
+    .map_err(AppError::SpendLimitReached)?;
+
+    Ok(axum::http::StatusCode::ACCEPTED)
+}
+
+/// Claude's own id for a stored assistant message, which `--resume-session-at`
+/// takes as the point to cut the resumed conversation.
+fn claude_message_uuid(role: &str, content: &str) -> Option<String> {
+    if role != "assistant" {
+        return None;
+    }
+    let value: serde_json::Value = serde_json::from_str(content).ok()?;
+    if value.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+        return None;
+    }
+    value
+        .get("uuid")
+        .and_then(|u| u.as_str())
+        .map(str::to_string)
+}
+
+/// POST /api/sessions/:id/fork
+///
+/// Start a new session on the same launcher that continues this session's
+/// conversation from `message_id`, leaving the original untouched. The fork
+/// gets a copy of the transcript up to that message and is recorded as a
+/// child of this session when it registers.
+#[utoipa::path(
+    post,
+    path = "/api/sessions/{id}/fork",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    request_body = ForkSessionRequest,
+    responses(
+        (status = 200, description = "Fork launch requested", body = ForkSessionResponse),
+        (status = 400, description = "Not a launcher-started Claude session, or the message is not a Claude reply"),
+        (status = 403, description = "A spend limit is reached, or the launcher is not the caller's own"),
+        (status = 404, description = "Session or message not found, or the launcher is not connected")
+    )
+)]
+pub async fn fork_session(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(session_id): Path<Uuid>,
+    Json(req): Json<ForkSessionRequest>,
+) -> Result<Json<ForkSessionResponse>, AppError> {
+    let current_user_id = extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    use crate::schema::{messages, session_members, sessions};
+
+    // The fork runs on the owner's launcher, so only the owner can fork
+    let session = sessions::table
+        .inner_join(session_members::table.on(session_members::session_id.eq(sessions::id)))
+        .filter(sessions::id.eq(session_id))
+        .filter(session_members::user_id.eq(current_user_id))
+        .filter(session_members::role.eq("owner"))
+        .select(Session::as_select())
+        .first::<Session>(&mut conn)
+        .optional()
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .ok_or(AppError::NotFound("Session not found"))?;
+
+    if session.agent_type != AgentType::Claude.as_str() {
+        return Err(AppError::BadRequest("Only Claude sessions can be forked"));
+    }
+    let Some(launcher_id) = session.launcher_id else {
+        return Err(AppError::BadRequest(
+            "Only sessions started by a launcher can be forked",
+        ));
+    };
+    // A fork resumes the parent's conversation, which a teammate's launcher
+    // doesn't run; the launcher may also have stopped being shared
+    use super::launchers::{launcher_access, LauncherAccess};
+    match launcher_access(&app_state, current_user_id, launcher_id)? {
+        LauncherAccess::Own => {}
+        LauncherAccess::Shared | LauncherAccess::Denied => return Err(AppError::Forbidden),
+    }
+
+    let (role, content, message_created_at): (String, String, NaiveDateTime) = messages::table
+        .filter(messages::id.eq(req.message_id))
+        .filter(messages::session_id.eq(session_id))
+        .select((messages::role, messages::content, messages::created_at))
+        .first(&mut conn)
+        .optional()
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .ok_or(AppError::NotFound("Message not found"))?;
+    let resume_at = claude_message_uuid(&role, &content).ok_or(AppError::BadRequest(
+        "Sessions can only be forked at a Claude reply",
+    ))?;
+
+    let auth_token = super::launchers::mint_launch_token(&app_state, current_user_id)?;
+    let request_id = Uuid::new_v4();
+    let fork_id = Uuid::new_v4();
+    app_state.session_manager.pending_forks.insert(
+        fork_id,
+        crate::handlers::websocket::PendingFork {
+            request_id,
+            user_id: current_user_id,
+            parent_session_id: session_id,
+            message_id: req.message_id,
+            message_created_at,
+        },
+    );
+
+    let launch_msg = ServerToLauncher::LaunchSession {
+        request_id,
+        user_id: current_user_id,
+        auth_token,
+        working_directory: session.working_directory.clone(),
+        session_name: Some(format!("{} (fork)", session.session_name)),
+        claude_args: Vec::new(),
+        agent_type: AgentType::Claude,
+        scheduled_task_id: None,
+        resume_session_id: None,
+        fork: Some(SessionFork {
+            parent_session_id: session_id,
+            session_id: fork_id,
+            resume_at: Some(resume_at),
+        }),
+    };
+    if !app_state
+        .session_manager
+        .send_to_launcher(&launcher_id, launch_msg)
+    {
+        app_state.session_manager.pending_forks.remove(&fork_id);
+        return Err(AppError::NotFound("Launcher not connected"));
+    }
+
+    info!(
+        "Fork requested: session {} at message {} -> {}",
+        session_id, req.message_id, fork_id
+    );
+
+    Ok(Json(ForkSessionResponse {
+        request_id,
+        session_id: fork_id,
+    }))
+}
+
 // ============================================================================
 // Session Member Management
 // ============================================================================
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct SessionMemberInfo {
     pub user_id: Uuid,
     pub email: String,
@@ -176,7 +413,7 @@ pub struct SessionMemberInfo {
     pub created_at: NaiveDateTime,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct SessionMembersResponse {
     pub members: Vec<SessionMemberInfo>,
 }
@@ -190,6 +427,16 @@ struct UserBasicInfo {
 }
 
 /// List all members of a session
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{id}/members",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    responses(
+        (status = 200, body = SessionMembersResponse),
+        (status = 404, description = "Session not found")
+    )
+)]
 pub async fn list_session_members(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -201,11 +448,7 @@ pub async fn list_session_members(
 
     use crate::schema::{session_members, users};
 
-    session_members::table
-        .filter(session_members::session_id.eq(session_id))
-        .filter(session_members::user_id.eq(current_user_id))
-        .first::<SessionMember>(&mut conn)
-        .optional()
+    crate::teams::session_role(&mut conn, session_id, current_user_id)
         .map_err(|e| AppError::DbQuery(e.to_string()))?
         .ok_or(AppError::NotFound("Session not found"))?;
 
@@ -236,6 +479,19 @@ pub async fn list_session_members(
 }
 
 /// Add a member to a session (owner only)
+#[utoipa::path(
+    post,
+    path = "/api/sessions/{id}/members",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    request_body = AddMemberRequest,
+    responses(
+        (status = 201, description = "Member added"),
+        (status = 400, description = "Several accounts share the email"),
+        (status = 403, description = "Only owners can add members"),
+        (status = 404, description = "Session or user not found")
+    )
+)]
 pub async fn add_session_member(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -250,7 +506,7 @@ pub async fn add_session_member(
 
     let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
 
-    use crate::schema::{session_members, users};
+    use crate::schema::session_members;
 
     session_members::table
         .filter(session_members::session_id.eq(session_id))
@@ -261,13 +517,7 @@ pub async fn add_session_member(
         .map_err(|e| AppError::DbQuery(e.to_string()))?
         .ok_or(AppError::Forbidden)?;
 
-    let target_user_id: Uuid = users::table
-        .filter(users::email.eq(&req.email))
-        .select(users::id)
-        .first(&mut conn)
-        .optional()
-        .map_err(|e| AppError::DbQuery(e.to_string()))?
-        .ok_or(AppError::NotFound("User not found"))?;
+    let target_user_id = super::helpers::find_user_by_email(&mut conn, &req.email)?;
 
     let existing = session_members::table
         .filter(session_members::session_id.eq(session_id))
@@ -283,7 +533,7 @@ pub async fn add_session_member(
     let new_member = NewSessionMember {
         session_id,
         user_id: target_user_id,
-        role: req.role,
+        role: req.role.clone(),
     };
 
     diesel::insert_into(session_members::table)
@@ -291,11 +541,29 @@ pub async fn add_session_member(
         .execute(&mut conn)
         .map_err(|e| AppError::DbQuery(e.to_string()))?;
 
+    crate::audit::record(
+        &mut conn,
+        Some(current_user_id),
+        AuditAction::SessionMemberAdd,
+        Some(session_id),
+        json!({ "user_id": target_user_id, "email": req.email, "role": req.role }),
+    );
     Ok(axum::http::StatusCode::CREATED)
 }
 
 /// Remove a member from a session
 /// Owner can remove anyone; non-owner can only remove themselves (leave)
+#[utoipa::path(
+    delete,
+    path = "/api/sessions/{id}/members/{user_id}",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID"), ("user_id" = Uuid, Path, description = "Member's user ID")),
+    responses(
+        (status = 204, description = "Member removed"),
+        (status = 403, description = "Only owners can remove members"),
+        (status = 404, description = "Session or member not found")
+    )
+)]
 pub async fn remove_session_member(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -327,22 +595,43 @@ pub async fn remove_session_member(
         ));
     }
 
-    let deleted = diesel::delete(
+    let removed_role: Option<String> = diesel::delete(
         session_members::table
             .filter(session_members::session_id.eq(session_id))
             .filter(session_members::user_id.eq(target_user_id)),
     )
-    .execute(&mut conn)
+    .returning(session_members::role)
+    .get_result(&mut conn)
+    .optional()
     .map_err(|e| AppError::DbQuery(e.to_string()))?;
 
-    if deleted == 0 {
+    let Some(removed_role) = removed_role else {
         return Err(AppError::NotFound("Member not found"));
-    }
+    };
 
+    crate::audit::record(
+        &mut conn,
+        Some(current_user_id),
+        AuditAction::SessionMemberRemove,
+        Some(session_id),
+        json!({ "user_id": target_user_id, "role": removed_role }),
+    );
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
 
 /// Update a member's role (owner only)
+#[utoipa::path(
+    patch,
+    path = "/api/sessions/{id}/members/{user_id}",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID"), ("user_id" = Uuid, Path, description = "Member's user ID")),
+    request_body = UpdateMemberRoleRequest,
+    responses(
+        (status = 200, description = "Role updated"),
+        (status = 403, description = "Only owners can change roles"),
+        (status = 404, description = "Session or member not found")
+    )
+)]
 pub async fn update_session_member_role(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -385,5 +674,35 @@ pub async fn update_session_member_role(
         return Err(AppError::NotFound("Member not found"));
     }
 
+    crate::audit::record(
+        &mut conn,
+        Some(current_user_id),
+        AuditAction::SessionMemberUpdate,
+        Some(session_id),
+        json!({ "user_id": target_user_id, "role": req.role }),
+    );
     Ok(axum::http::StatusCode::OK)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fork_point_must_be_a_claude_reply() {
+        let reply = r#"{"type":"assistant","uuid":"a1","message":{"content":[]}}"#;
+        assert_eq!(
+            claude_message_uuid("assistant", reply),
+            Some("a1".to_string())
+        );
+
+        let prompt = r#"{"type":"user","uuid":"u1","message":{"content":"hi"}}"#;
+        assert_eq!(claude_message_uuid("user", prompt), None);
+        // Portal-generated and older messages carry no Claude uuid
+        assert_eq!(
+            claude_message_uuid("assistant", r#"{"type":"assistant"}"#),
+            None
+        );
+        assert_eq!(claude_message_uuid("assistant", "not json"), None);
+    }
+}
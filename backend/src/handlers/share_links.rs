@@ -0,0 +1,334 @@
+//! Public Share Link Handlers
+//!
+//! Owners create, list and revoke read-only links to a session, and see who
+//! opened them. These are cookie-only: an API key cannot mint a link. The one
+//! public route, `GET /api/shared/{token}`, tells a visitor what a link opens
+//! before they join over `/ws/shared`.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use shared::api::{
+    AuditAction, CreateShareLinkRequest, CreateShareLinkResponse, ShareLinkInfo,
+    ShareLinkListResponse, ShareLinkView, ShareLinkViewsResponse, SharedSessionInfo,
+    MAX_SHARE_LINK_HOURS,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tower_cookies::Cookies;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    jwt::hash_token,
+    models::{NewShareLink, ShareLink},
+    schema::{session_members, sessions, share_link_views, share_links},
+    share_links::{self as links, MAX_PASSWORD_LEN},
+    AppState,
+};
+
+/// View count and latest view per link
+type ViewStats = HashMap<Uuid, (i64, Option<NaiveDateTime>)>;
+
+fn share_link_to_info(link: ShareLink, stats: &ViewStats) -> ShareLinkInfo {
+    let (view_count, last_viewed_at) = stats.get(&link.id).copied().unwrap_or((0, None));
+    ShareLinkInfo {
+        id: link.id,
+        session_id: link.session_id,
+        has_password: link.password_hash.is_some(),
+        created_at: link.created_at.and_utc().to_rfc3339(),
+        expires_at: link.expires_at.and_utc().to_rfc3339(),
+        revoked: link.revoked,
+        view_count,
+        last_viewed_at: last_viewed_at.map(|t| t.and_utc().to_rfc3339()),
+    }
+}
+
+/// Only the session owner manages its links.
+fn require_owner(conn: &mut PgConnection, session_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+    let role: Option<String> = session_members::table
+        .filter(session_members::session_id.eq(session_id))
+        .filter(session_members::user_id.eq(user_id))
+        .select(session_members::role)
+        .first(conn)
+        .optional()
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    match role.as_deref() {
+        Some("owner") => Ok(()),
+        Some(_) => Err(AppError::Forbidden),
+        None => Err(AppError::NotFound("Session not found")),
+    }
+}
+
+fn view_stats(conn: &mut PgConnection, link_ids: &[Uuid]) -> Result<ViewStats, AppError> {
+    let rows: Vec<(Uuid, i64, Option<NaiveDateTime>)> = share_link_views::table
+        .filter(share_link_views::share_link_id.eq_any(link_ids))
+        .group_by(share_link_views::share_link_id)
+        .select((
+            share_link_views::share_link_id,
+            diesel::dsl::count_star(),
+            diesel::dsl::max(share_link_views::viewed_at),
+        ))
+        .load(conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, count, last)| (id, (count, last)))
+        .collect())
+}
+
+/// GET /api/sessions/:id/share-links
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{id}/share-links",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    responses(
+        (status = 200, body = ShareLinkListResponse),
+        (status = 403, description = "Only the owner can manage share links"),
+        (status = 404, description = "Session not found")
+    )
+)]
+pub async fn list_share_links(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<ShareLinkListResponse>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    require_owner(&mut conn, session_id, user_id)?;
+
+    let share_links: Vec<ShareLink> = share_links::table
+        .filter(share_links::session_id.eq(session_id))
+        .order(share_links::created_at.desc())
+        .load(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    let ids: Vec<Uuid> = share_links.iter().map(|l| l.id).collect();
+    let stats = view_stats(&mut conn, &ids)?;
+
+    Ok(Json(ShareLinkListResponse {
+        share_links: share_links
+            .into_iter()
+            .map(|l| share_link_to_info(l, &stats))
+            .collect(),
+    }))
+}
+
+/// POST /api/sessions/:id/share-links
+#[utoipa::path(
+    post,
+    path = "/api/sessions/{id}/share-links",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    request_body = CreateShareLinkRequest,
+    responses(
+        (status = 200, description = "The link token, shown only once", body = CreateShareLinkResponse),
+        (status = 400, description = "Invalid expiry or password"),
+        (status = 403, description = "Only the owner can manage share links"),
+        (status = 404, description = "Session not found")
+    )
+)]
+pub async fn create_share_link(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(session_id): Path<Uuid>,
+    Json(req): Json<CreateShareLinkRequest>,
+) -> Result<Json<CreateShareLinkResponse>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    if !(1..=MAX_SHARE_LINK_HOURS).contains(&req.expires_in_hours) {
+        return Err(AppError::BadRequest(
+            "Share links must expire within 1 hour to 30 days",
+        ));
+    }
+    let password = req.password.filter(|p| !p.is_empty());
+    if password
+        .as_ref()
+        .is_some_and(|p| p.len() > MAX_PASSWORD_LEN)
+    {
+        return Err(AppError::BadRequest("Password is too long"));
+    }
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    require_owner(&mut conn, session_id, user_id)?;
+
+    let token = links::generate_token();
+    let expires_at = chrono::Utc::now() + chrono::Duration::hours(req.expires_in_hours as i64);
+    let new_link = NewShareLink {
+        session_id,
+        created_by: user_id,
+        token_hash: hash_token(&token),
+        password_hash: password.as_deref().map(links::hash_password).transpose()?,
+        expires_at: expires_at.naive_utc(),
+    };
+
+    let saved: ShareLink = diesel::insert_into(share_links::table)
+        .values(&new_link)
+        .get_result(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    info!(
+        "Created share link {} for session {} (expires {})",
+        saved.id, session_id, saved.expires_at
+    );
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::ShareLinkCreate,
+        Some(saved.id),
+        serde_json::json!({
+            "session_id": session_id,
+            "expires_at": saved.expires_at.and_utc().to_rfc3339(),
+            "password": saved.password_hash.is_some(),
+        }),
+    );
+
+    Ok(Json(CreateShareLinkResponse {
+        share_link: share_link_to_info(saved, &ViewStats::new()),
+        token,
+    }))
+}
+
+/// DELETE /api/sessions/:id/share-links/:link_id - Revoke a link
+#[utoipa::path(
+    delete,
+    path = "/api/sessions/{id}/share-links/{link_id}",
+    tag = "sessions",
+    params(
+        ("id" = Uuid, Path, description = "Session ID"),
+        ("link_id" = Uuid, Path, description = "Share link ID")
+    ),
+    responses(
+        (status = 204, description = "Link revoked; open viewers are disconnected"),
+        (status = 403, description = "Only the owner can manage share links"),
+        (status = 404, description = "Session or link not found")
+    )
+)]
+pub async fn revoke_share_link(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path((session_id, link_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    require_owner(&mut conn, session_id, user_id)?;
+
+    let updated = diesel::update(
+        share_links::table
+            .filter(share_links::id.eq(link_id))
+            .filter(share_links::session_id.eq(session_id)),
+    )
+    .set(share_links::revoked.eq(true))
+    .execute(&mut conn)
+    .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    if updated == 0 {
+        return Err(AppError::NotFound("Share link"));
+    }
+
+    info!("Revoked share link {}", link_id);
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::ShareLinkRevoke,
+        Some(link_id),
+        serde_json::json!({ "session_id": session_id }),
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/sessions/:id/share-links/:link_id/views
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{id}/share-links/{link_id}/views",
+    tag = "sessions",
+    params(
+        ("id" = Uuid, Path, description = "Session ID"),
+        ("link_id" = Uuid, Path, description = "Share link ID")
+    ),
+    responses(
+        (status = 200, description = "Latest views first", body = ShareLinkViewsResponse),
+        (status = 403, description = "Only the owner can manage share links"),
+        (status = 404, description = "Session or link not found")
+    )
+)]
+pub async fn list_share_link_views(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path((session_id, link_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ShareLinkViewsResponse>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    require_owner(&mut conn, session_id, user_id)?;
+
+    share_links::table
+        .filter(share_links::id.eq(link_id))
+        .filter(share_links::session_id.eq(session_id))
+        .select(share_links::id)
+        .first::<Uuid>(&mut conn)
+        .optional()
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .ok_or(AppError::NotFound("Share link"))?;
+
+    let views: Vec<crate::models::ShareLinkView> = share_link_views::table
+        .filter(share_link_views::share_link_id.eq(link_id))
+        .order(share_link_views::viewed_at.desc())
+        .limit(500)
+        .load(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(Json(ShareLinkViewsResponse {
+        views: views
+            .into_iter()
+            .map(|v| ShareLinkView {
+                viewed_at: v.viewed_at.and_utc().to_rfc3339(),
+                ip_address: v.ip_address,
+                user_agent: v.user_agent,
+            })
+            .collect(),
+    }))
+}
+
+/// GET /api/shared/:token - Public; what a share link opens
+#[utoipa::path(
+    get,
+    path = "/api/shared/{token}",
+    tag = "sessions",
+    params(("token" = String, Path, description = "Share link token")),
+    security(()),
+    responses(
+        (status = 200, body = SharedSessionInfo),
+        (status = 404, description = "Unknown, expired or revoked link")
+    )
+)]
+pub async fn get_shared_session(
+    State(app_state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> Result<Json<SharedSessionInfo>, AppError> {
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let link =
+        links::find_active_link(&mut conn, &token)?.ok_or(AppError::NotFound("Share link"))?;
+
+    let (session_name, agent_type): (String, String) = sessions::table
+        .filter(sessions::id.eq(link.session_id))
+        .select((sessions::session_name, sessions::agent_type))
+        .first(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(Json(SharedSessionInfo {
+        session_name,
+        agent_type: agent_type.parse().unwrap_or(shared::AgentType::Claude),
+        expires_at: link.expires_at.and_utc().to_rfc3339(),
+        requires_password: link.password_hash.is_some(),
+    }))
+}
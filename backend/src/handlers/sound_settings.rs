@@ -8,6 +8,12 @@ use crate::auth::extract_user_id;
 use crate::errors::AppError;
 use crate::AppState;
 
+#[utoipa::path(
+    get,
+    path = "/api/settings/sound",
+    tag = "settings",
+    responses((status = 200, body = SoundSettingsResponse))
+)]
 pub async fn get_sound_settings(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -26,6 +32,13 @@ pub async fn get_sound_settings(
     Ok(Json(SoundSettingsResponse { sound_config }))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/settings/sound",
+    tag = "settings",
+    request_body(content = Object, description = "Sound configuration, stored as given"),
+    responses((status = 200, description = "Saved"))
+)]
 pub async fn save_sound_settings(
     State(app_state): State<Arc<AppState>>,
     cookies: Cookies,
@@ -0,0 +1,749 @@
+//! Team Handlers
+//!
+//! Any user can create a team and becomes its owner. Team owners add and
+//! remove members; members share their own sessions and launchers with the
+//! team. Access that sharing grants is resolved in `crate::teams`. These are
+//! cookie-only routes.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use diesel::prelude::*;
+use serde_json::json;
+use shared::api::{
+    AddTeamMemberRequest, AuditAction, CreateTeamRequest, SessionTeamInfo, SessionTeamsResponse,
+    ShareLauncherRequest, ShareSessionWithTeamRequest, TeamInfo, TeamLauncherInfo,
+    TeamLaunchersResponse, TeamListResponse, TeamMemberInfo, TeamMembersResponse,
+    UpdateTeamMemberRequest,
+};
+use std::sync::Arc;
+use tower_cookies::Cookies;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    auth::extract_user_id,
+    errors::AppError,
+    models::{NewSessionTeam, NewTeam, NewTeamLauncher, NewTeamMember, Team, TeamLauncher},
+    schema::{session_members, session_teams, team_launchers, team_members, teams, users},
+    teams::{self as team_access, SHARE_ROLES, TEAM_ROLES},
+    AppState,
+};
+
+use super::helpers::find_user_by_email;
+
+/// The caller's role in a team; teams they are not in do not exist to them.
+fn require_member(
+    conn: &mut PgConnection,
+    team_id: Uuid,
+    user_id: Uuid,
+) -> Result<String, AppError> {
+    team_access::team_role(conn, team_id, user_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .ok_or(AppError::NotFound("Team not found"))
+}
+
+fn require_team_owner(
+    conn: &mut PgConnection,
+    team_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), AppError> {
+    match require_member(conn, team_id, user_id)?.as_str() {
+        "owner" => Ok(()),
+        _ => Err(AppError::Forbidden),
+    }
+}
+
+/// Only the session owner shares it with teams.
+fn require_session_owner(
+    conn: &mut PgConnection,
+    session_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), AppError> {
+    let role: Option<String> = session_members::table
+        .filter(session_members::session_id.eq(session_id))
+        .filter(session_members::user_id.eq(user_id))
+        .select(session_members::role)
+        .first(conn)
+        .optional()
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    match role.as_deref() {
+        Some("owner") => Ok(()),
+        Some(_) => Err(AppError::Forbidden),
+        None => Err(AppError::NotFound("Session not found")),
+    }
+}
+
+fn owner_count(conn: &mut PgConnection, team_id: Uuid) -> Result<i64, AppError> {
+    team_members::table
+        .filter(team_members::team_id.eq(team_id))
+        .filter(team_members::role.eq("owner"))
+        .count()
+        .get_result(conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))
+}
+
+// ============================================================================
+// Teams and membership
+// ============================================================================
+
+/// GET /api/teams - Teams the caller belongs to
+#[utoipa::path(
+    get,
+    path = "/api/teams",
+    tag = "teams",
+    responses((status = 200, body = TeamListResponse))
+)]
+pub async fn list_teams(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<Json<TeamListResponse>, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let memberships: Vec<(Team, String)> = teams::table
+        .inner_join(team_members::table)
+        .filter(team_members::user_id.eq(user_id))
+        .select((Team::as_select(), team_members::role))
+        .order(teams::name.asc())
+        .load(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    let ids: Vec<Uuid> = memberships.iter().map(|(t, _)| t.id).collect();
+    let counts: Vec<(Uuid, i64)> = team_members::table
+        .filter(team_members::team_id.eq_any(&ids))
+        .group_by(team_members::team_id)
+        .select((team_members::team_id, diesel::dsl::count_star()))
+        .load(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(Json(TeamListResponse {
+        teams: memberships
+            .into_iter()
+            .map(|(team, my_role)| TeamInfo {
+                member_count: counts
+                    .iter()
+                    .find(|(id, _)| *id == team.id)
+                    .map(|(_, n)| *n)
+                    .unwrap_or(0),
+                id: team.id,
+                name: team.name,
+                my_role,
+                created_at: team.created_at.and_utc().to_rfc3339(),
+            })
+            .collect(),
+    }))
+}
+
+/// POST /api/teams - Create a team owned by the caller
+#[utoipa::path(
+    post,
+    path = "/api/teams",
+    tag = "teams",
+    request_body = CreateTeamRequest,
+    responses(
+        (status = 200, body = TeamInfo),
+        (status = 400, description = "Invalid name, or a team with that name exists")
+    )
+)]
+pub async fn create_team(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Json(req): Json<CreateTeamRequest>,
+) -> Result<Json<TeamInfo>, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    let name = team_access::validate_team_name(&req.name).map_err(AppError::BadRequest)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let team: Team = conn
+        .transaction(|conn| {
+            let team: Team = diesel::insert_into(teams::table)
+                .values(&NewTeam {
+                    name,
+                    created_by: Some(user_id),
+                })
+                .get_result(conn)?;
+            diesel::insert_into(team_members::table)
+                .values(&NewTeamMember {
+                    team_id: team.id,
+                    user_id,
+                    role: "owner".to_string(),
+                })
+                .execute(conn)?;
+            Ok(team)
+        })
+        .map_err(|e| match e {
+            diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UniqueViolation,
+                _,
+            ) => AppError::BadRequest("A team with that name already exists"),
+            e => AppError::DbQuery(e.to_string()),
+        })?;
+
+    info!("Created team '{}' ({})", team.name, team.id);
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::TeamCreate,
+        Some(team.id),
+        json!({ "name": team.name }),
+    );
+
+    Ok(Json(TeamInfo {
+        id: team.id,
+        name: team.name,
+        my_role: "owner".to_string(),
+        member_count: 1,
+        created_at: team.created_at.and_utc().to_rfc3339(),
+    }))
+}
+
+/// DELETE /api/teams/:id - Delete a team and everything shared with it
+#[utoipa::path(
+    delete,
+    path = "/api/teams/{id}",
+    tag = "teams",
+    params(("id" = Uuid, Path, description = "Team ID")),
+    responses(
+        (status = 204, description = "Team deleted; its sessions and launchers are no longer shared"),
+        (status = 403, description = "Only team owners can delete a team"),
+        (status = 404, description = "Team not found")
+    )
+)]
+pub async fn delete_team(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(team_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    require_team_owner(&mut conn, team_id, user_id)?;
+
+    let name: String = diesel::delete(teams::table.find(team_id))
+        .returning(teams::name)
+        .get_result(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    info!("Deleted team {}", team_id);
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::TeamDelete,
+        Some(team_id),
+        json!({ "name": name }),
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/teams/:id/members
+#[utoipa::path(
+    get,
+    path = "/api/teams/{id}/members",
+    tag = "teams",
+    params(("id" = Uuid, Path, description = "Team ID")),
+    responses(
+        (status = 200, body = TeamMembersResponse),
+        (status = 404, description = "Team not found")
+    )
+)]
+pub async fn list_team_members(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(team_id): Path<Uuid>,
+) -> Result<Json<TeamMembersResponse>, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    require_member(&mut conn, team_id, user_id)?;
+
+    let rows: Vec<(Uuid, String, Option<String>, String, chrono::NaiveDateTime)> =
+        team_members::table
+            .inner_join(users::table)
+            .filter(team_members::team_id.eq(team_id))
+            .select((
+                users::id,
+                users::email,
+                users::name,
+                team_members::role,
+                team_members::created_at,
+            ))
+            .order(team_members::created_at.asc())
+            .load(&mut conn)
+            .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(Json(TeamMembersResponse {
+        members: rows
+            .into_iter()
+            .map(|(user_id, email, name, role, created_at)| TeamMemberInfo {
+                user_id,
+                email,
+                name,
+                role,
+                created_at: created_at.and_utc().to_rfc3339(),
+            })
+            .collect(),
+    }))
+}
+
+/// POST /api/teams/:id/members - Add a user by email (team owners only)
+#[utoipa::path(
+    post,
+    path = "/api/teams/{id}/members",
+    tag = "teams",
+    params(("id" = Uuid, Path, description = "Team ID")),
+    request_body = AddTeamMemberRequest,
+    responses(
+        (status = 201, description = "Member added"),
+        (status = 400, description = "Invalid role, the user is already a member, or several accounts share the email"),
+        (status = 403, description = "Only team owners can add members"),
+        (status = 404, description = "Team or user not found")
+    )
+)]
+pub async fn add_team_member(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(team_id): Path<Uuid>,
+    Json(req): Json<AddTeamMemberRequest>,
+) -> Result<StatusCode, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    if !TEAM_ROLES.contains(&req.role.as_str()) {
+        return Err(AppError::BadRequest("Role must be owner or member"));
+    }
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    require_team_owner(&mut conn, team_id, user_id)?;
+    let target_user_id = find_user_by_email(&mut conn, &req.email)?;
+
+    let inserted = diesel::insert_into(team_members::table)
+        .values(&NewTeamMember {
+            team_id,
+            user_id: target_user_id,
+            role: req.role.clone(),
+        })
+        .on_conflict_do_nothing()
+        .execute(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    if inserted == 0 {
+        return Err(AppError::BadRequest("User is already a member"));
+    }
+
+    info!("Added user {} to team {}", target_user_id, team_id);
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::TeamMemberAdd,
+        Some(team_id),
+        json!({ "user_id": target_user_id, "email": req.email, "role": req.role }),
+    );
+    Ok(StatusCode::CREATED)
+}
+
+/// PATCH /api/teams/:id/members/:user_id - Change a member's role (team
+/// owners only)
+#[utoipa::path(
+    patch,
+    path = "/api/teams/{id}/members/{user_id}",
+    tag = "teams",
+    params(
+        ("id" = Uuid, Path, description = "Team ID"),
+        ("user_id" = Uuid, Path, description = "Member's user ID")
+    ),
+    request_body = UpdateTeamMemberRequest,
+    responses(
+        (status = 200, description = "Role updated"),
+        (status = 400, description = "Invalid role, or it would leave the team without an owner"),
+        (status = 403, description = "Only team owners can change roles"),
+        (status = 404, description = "Team or member not found")
+    )
+)]
+pub async fn update_team_member(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path((team_id, target_user_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateTeamMemberRequest>,
+) -> Result<StatusCode, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    if !TEAM_ROLES.contains(&req.role.as_str()) {
+        return Err(AppError::BadRequest("Role must be owner or member"));
+    }
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    require_team_owner(&mut conn, team_id, user_id)?;
+
+    let current = team_access::team_role(&mut conn, team_id, target_user_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .ok_or(AppError::NotFound("Member not found"))?;
+    if current == "owner" && req.role != "owner" && owner_count(&mut conn, team_id)? <= 1 {
+        return Err(AppError::BadRequest("A team needs at least one owner"));
+    }
+
+    diesel::update(
+        team_members::table
+            .filter(team_members::team_id.eq(team_id))
+            .filter(team_members::user_id.eq(target_user_id)),
+    )
+    .set(team_members::role.eq(&req.role))
+    .execute(&mut conn)
+    .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::TeamMemberUpdate,
+        Some(team_id),
+        json!({ "user_id": target_user_id, "from_role": current, "role": req.role }),
+    );
+    Ok(StatusCode::OK)
+}
+
+/// DELETE /api/teams/:id/members/:user_id - Remove a member
+/// Team owners can remove anyone; members can only remove themselves (leave)
+#[utoipa::path(
+    delete,
+    path = "/api/teams/{id}/members/{user_id}",
+    tag = "teams",
+    params(
+        ("id" = Uuid, Path, description = "Team ID"),
+        ("user_id" = Uuid, Path, description = "Member's user ID")
+    ),
+    responses(
+        (status = 204, description = "Member removed"),
+        (status = 400, description = "It would leave the team without an owner"),
+        (status = 403, description = "Only team owners can remove other members"),
+        (status = 404, description = "Team or member not found")
+    )
+)]
+pub async fn remove_team_member(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path((team_id, target_user_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let my_role = require_member(&mut conn, team_id, user_id)?;
+    if my_role != "owner" && user_id != target_user_id {
+        return Err(AppError::Forbidden);
+    }
+
+    let target_role = team_access::team_role(&mut conn, team_id, target_user_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .ok_or(AppError::NotFound("Member not found"))?;
+    if target_role == "owner" && owner_count(&mut conn, team_id)? <= 1 {
+        return Err(AppError::BadRequest("A team needs at least one owner"));
+    }
+
+    conn.transaction(|conn| {
+        diesel::delete(
+            team_members::table
+                .filter(team_members::team_id.eq(team_id))
+                .filter(team_members::user_id.eq(target_user_id)),
+        )
+        .execute(conn)?;
+        // Launchers they shared leave with them
+        diesel::delete(
+            team_launchers::table
+                .filter(team_launchers::team_id.eq(team_id))
+                .filter(team_launchers::owner_id.eq(target_user_id)),
+        )
+        .execute(conn)
+    })
+    .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    info!("Removed user {} from team {}", target_user_id, team_id);
+    crate::audit::record(
+        &mut conn,
+        Some(user_id),
+        AuditAction::TeamMemberRemove,
+        Some(team_id),
+        json!({ "user_id": target_user_id, "role": target_role }),
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Launchers shared with a team
+// ============================================================================
+
+/// GET /api/teams/:id/launchers
+#[utoipa::path(
+    get,
+    path = "/api/teams/{id}/launchers",
+    tag = "teams",
+    params(("id" = Uuid, Path, description = "Team ID")),
+    responses(
+        (status = 200, body = TeamLaunchersResponse),
+        (status = 404, description = "Team not found")
+    )
+)]
+pub async fn list_team_launchers(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(team_id): Path<Uuid>,
+) -> Result<Json<TeamLaunchersResponse>, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    require_member(&mut conn, team_id, user_id)?;
+
+    let rows: Vec<(TeamLauncher, Option<String>, String)> = team_launchers::table
+        .inner_join(users::table)
+        .filter(team_launchers::team_id.eq(team_id))
+        .select((TeamLauncher::as_select(), users::name, users::email))
+        .order(team_launchers::launcher_name.asc())
+        .load(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    let launchers = rows
+        .into_iter()
+        .map(|(share, name, email)| {
+            let connected = app_state.session_manager.launchers.iter().any(|l| {
+                l.value().user_id == share.owner_id
+                    && l.value().launcher_name == share.launcher_name
+            });
+            TeamLauncherInfo {
+                id: share.id,
+                owner_id: share.owner_id,
+                owner_name: name.unwrap_or(email),
+                launcher_name: share.launcher_name,
+                connected,
+            }
+        })
+        .collect();
+
+    Ok(Json(TeamLaunchersResponse { launchers }))
+}
+
+/// POST /api/teams/:id/launchers - Let the team launch on one of the
+/// caller's connected launchers
+#[utoipa::path(
+    post,
+    path = "/api/teams/{id}/launchers",
+    tag = "teams",
+    params(("id" = Uuid, Path, description = "Team ID")),
+    request_body = ShareLauncherRequest,
+    responses(
+        (status = 201, description = "Launcher shared"),
+        (status = 400, description = "No connected launcher of the caller has that name"),
+        (status = 404, description = "Team not found")
+    )
+)]
+pub async fn share_launcher(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(team_id): Path<Uuid>,
+    Json(req): Json<ShareLauncherRequest>,
+) -> Result<StatusCode, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    require_member(&mut conn, team_id, user_id)?;
+
+    let connected = app_state
+        .session_manager
+        .get_launchers_for_user(&user_id)
+        .iter()
+        .any(|l| l.launcher_name == req.launcher_name);
+    if !connected {
+        return Err(AppError::BadRequest(
+            "You have no connected launcher with that name",
+        ));
+    }
+
+    diesel::insert_into(team_launchers::table)
+        .values(&NewTeamLauncher {
+            team_id,
+            owner_id: user_id,
+            launcher_name: req.launcher_name.clone(),
+        })
+        .on_conflict_do_nothing()
+        .execute(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    info!(
+        "User {} shared launcher '{}' with team {}",
+        user_id, req.launcher_name, team_id
+    );
+    Ok(StatusCode::CREATED)
+}
+
+/// DELETE /api/teams/:id/launchers/:share_id - Stop sharing a launcher
+/// Its owner or a team owner can stop sharing it
+#[utoipa::path(
+    delete,
+    path = "/api/teams/{id}/launchers/{share_id}",
+    tag = "teams",
+    params(
+        ("id" = Uuid, Path, description = "Team ID"),
+        ("share_id" = Uuid, Path, description = "Shared launcher ID")
+    ),
+    responses(
+        (status = 204, description = "Launcher no longer shared"),
+        (status = 403, description = "Neither the launcher's owner nor a team owner"),
+        (status = 404, description = "Team or shared launcher not found")
+    )
+)]
+pub async fn unshare_launcher(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path((team_id, share_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let my_role = require_member(&mut conn, team_id, user_id)?;
+
+    let share: TeamLauncher = team_launchers::table
+        .filter(team_launchers::id.eq(share_id))
+        .filter(team_launchers::team_id.eq(team_id))
+        .select(TeamLauncher::as_select())
+        .first(&mut conn)
+        .optional()
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .ok_or(AppError::NotFound("Shared launcher not found"))?;
+    if share.owner_id != user_id && my_role != "owner" {
+        return Err(AppError::Forbidden);
+    }
+
+    diesel::delete(team_launchers::table.find(share_id))
+        .execute(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Sessions shared with a team
+// ============================================================================
+
+/// GET /api/sessions/:id/teams - Teams a session is shared with
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{id}/teams",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    responses(
+        (status = 200, body = SessionTeamsResponse),
+        (status = 404, description = "Session not found")
+    )
+)]
+pub async fn list_session_teams(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<SessionTeamsResponse>, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    team_access::session_role(&mut conn, session_id, user_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .ok_or(AppError::NotFound("Session not found"))?;
+
+    let rows: Vec<(Uuid, String, String)> = session_teams::table
+        .inner_join(teams::table)
+        .filter(session_teams::session_id.eq(session_id))
+        .select((teams::id, teams::name, session_teams::role))
+        .order(teams::name.asc())
+        .load(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(Json(SessionTeamsResponse {
+        teams: rows
+            .into_iter()
+            .map(|(team_id, team_name, role)| SessionTeamInfo {
+                team_id,
+                team_name,
+                role,
+            })
+            .collect(),
+    }))
+}
+
+/// POST /api/sessions/:id/teams - Share a session with one of the owner's
+/// teams, or change the role it is shared with
+#[utoipa::path(
+    post,
+    path = "/api/sessions/{id}/teams",
+    tag = "sessions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    request_body = ShareSessionWithTeamRequest,
+    responses(
+        (status = 200, description = "Session shared with the team"),
+        (status = 400, description = "Invalid role, or the owner is not in the team"),
+        (status = 403, description = "Only the owner can share a session"),
+        (status = 404, description = "Session not found")
+    )
+)]
+pub async fn share_session_with_team(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(session_id): Path<Uuid>,
+    Json(req): Json<ShareSessionWithTeamRequest>,
+) -> Result<StatusCode, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    if !SHARE_ROLES.contains(&req.role.as_str()) {
+        return Err(AppError::BadRequest("Role must be viewer or editor"));
+    }
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    require_session_owner(&mut conn, session_id, user_id)?;
+    team_access::team_role(&mut conn, req.team_id, user_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .ok_or(AppError::BadRequest("You are not a member of that team"))?;
+
+    diesel::insert_into(session_teams::table)
+        .values(&NewSessionTeam {
+            session_id,
+            team_id: req.team_id,
+            role: req.role.clone(),
+        })
+        .on_conflict((session_teams::session_id, session_teams::team_id))
+        .do_update()
+        .set(session_teams::role.eq(&req.role))
+        .execute(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    info!(
+        "Shared session {} with team {} as {}",
+        session_id, req.team_id, req.role
+    );
+    Ok(StatusCode::OK)
+}
+
+/// DELETE /api/sessions/:id/teams/:team_id - Stop sharing a session with a
+/// team
+#[utoipa::path(
+    delete,
+    path = "/api/sessions/{id}/teams/{team_id}",
+    tag = "sessions",
+    params(
+        ("id" = Uuid, Path, description = "Session ID"),
+        ("team_id" = Uuid, Path, description = "Team ID")
+    ),
+    responses(
+        (status = 204, description = "No longer shared with the team"),
+        (status = 403, description = "Only the owner can unshare a session"),
+        (status = 404, description = "Session not found or not shared with the team")
+    )
+)]
+pub async fn unshare_session_with_team(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path((session_id, team_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    let user_id = extract_user_id(&app_state, &cookies)?;
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    require_session_owner(&mut conn, session_id, user_id)?;
+
+    let deleted = diesel::delete(
+        session_teams::table
+            .filter(session_teams::session_id.eq(session_id))
+            .filter(session_teams::team_id.eq(team_id)),
+    )
+    .execute(&mut conn)
+    .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    if deleted == 0 {
+        return Err(AppError::NotFound("Session is not shared with that team"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
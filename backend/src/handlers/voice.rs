@@ -41,7 +41,7 @@ fn check_voice_enabled(app_state: &AppState, user_id: Uuid) -> bool {
         .unwrap_or(false)
 }
 
-/// Verify that a user has access to a session (is a member with any role)
+/// Verify that a user has access to a session, directly or through a team
 fn verify_session_access(app_state: &AppState, session_id: Uuid, user_id: Uuid) -> bool {
     let mut conn = match app_state.db_pool.get() {
         Ok(c) => c,
@@ -54,15 +54,10 @@ fn verify_session_access(app_state: &AppState, session_id: Uuid, user_id: Uuid)
         }
     };
 
-    use crate::schema::{session_members, sessions};
-    sessions::table
-        .inner_join(session_members::table.on(session_members::session_id.eq(sessions::id)))
-        .filter(sessions::id.eq(session_id))
-        .filter(session_members::user_id.eq(user_id))
-        .count()
-        .get_result::<i64>(&mut conn)
-        .unwrap_or(0)
-        > 0
+    crate::teams::session_role(&mut conn, session_id, user_id)
+        .ok()
+        .flatten()
+        .is_some()
 }
 
 /// WebSocket endpoint for voice audio streaming
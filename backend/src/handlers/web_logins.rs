@@ -0,0 +1,129 @@
+//! Signed-in Device Handlers
+//!
+//! Lists the browsers signed in as the current user and signs them out, one
+//! at a time or all at once. Like key management these are cookie-only, and
+//! revoking a login also closes the client websockets it opened. The login
+//! records themselves live in `crate::web_logins`.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde_json::json;
+use shared::api::{AuditAction, WebLoginsResponse};
+use std::sync::Arc;
+use tower_cookies::Cookies;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{errors::AppError, models::WebLogin, web_logins, AppState};
+
+/// Record the revocation and close the login's sockets
+fn signed_out(
+    app_state: &AppState,
+    conn: &mut diesel::PgConnection,
+    user_id: Uuid,
+    login: &WebLogin,
+) {
+    app_state.session_manager.end_web_login(login.id);
+    crate::audit::record(
+        conn,
+        Some(user_id),
+        AuditAction::WebLoginRevoke,
+        Some(login.id),
+        json!({
+            "device": web_logins::describe_device(login.user_agent.as_deref()),
+            "ip_address": login.ip_address,
+        }),
+    );
+}
+
+/// Sign out the user's browsers other than `keep` and close their sockets,
+/// after their password changed or their account was disabled. Returns how
+/// many were signed out.
+pub(crate) fn end_other_logins(
+    app_state: &AppState,
+    conn: &mut diesel::PgConnection,
+    user_id: Uuid,
+    keep: Option<Uuid>,
+) -> diesel::QueryResult<usize> {
+    let logins = web_logins::revoke_all_except(conn, user_id, keep)?;
+    for login in &logins {
+        app_state.session_manager.end_web_login(login.id);
+    }
+    Ok(logins.len())
+}
+
+/// GET /api/logins
+#[utoipa::path(
+    get,
+    path = "/api/logins",
+    tag = "credentials",
+    responses((status = 200, body = WebLoginsResponse))
+)]
+pub async fn list_logins_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<Json<WebLoginsResponse>, AppError> {
+    let current = crate::auth::current_login(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let logins = web_logins::list(&mut conn, current.user_id, Some(current.id))
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(Json(WebLoginsResponse { logins }))
+}
+
+/// DELETE /api/logins/:id - Sign one browser out
+#[utoipa::path(
+    delete,
+    path = "/api/logins/{id}",
+    tag = "credentials",
+    params(("id" = Uuid, Path, description = "Login ID")),
+    responses(
+        (status = 204, description = "Browser signed out and its open tabs disconnected"),
+        (status = 404, description = "Login not found")
+    )
+)]
+pub async fn revoke_login_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(login_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let user_id = crate::auth::current_login(&app_state, &cookies)?.user_id;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let login = web_logins::revoke(&mut conn, user_id, login_id)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?
+        .ok_or(AppError::NotFound("Login"))?;
+
+    info!("User {} signed out login {}", user_id, login.id);
+    signed_out(&app_state, &mut conn, user_id, &login);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/logins/revoke-all - Sign out everywhere, this browser included
+#[utoipa::path(
+    post,
+    path = "/api/logins/revoke-all",
+    tag = "credentials",
+    responses((status = 204, description = "Every browser signed out, including this one"))
+)]
+pub async fn revoke_all_logins_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<StatusCode, AppError> {
+    let user_id = crate::auth::current_login(&app_state, &cookies)?.user_id;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let logins =
+        web_logins::revoke_all(&mut conn, user_id).map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    info!("User {} signed out {} login(s)", user_id, logins.len());
+    for login in &logins {
+        signed_out(&app_state, &mut conn, user_id, login);
+    }
+    crate::handlers::auth::clear_session_cookie(&app_state, &cookies);
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,353 @@
+//! Webhook Management Handlers
+//!
+//! CRUD endpoints for a user's outbound webhooks plus their delivery log.
+//! Delivery itself lives in `crate::webhooks`.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use diesel::prelude::*;
+use shared::api::{
+    CreateWebhookRequest, CreateWebhookResponse, UpdateWebhookRequest, WebhookDeliveryInfo,
+    WebhookDeliveryListResponse, WebhookDeliveryStatus, WebhookEvent, WebhookInfo,
+    WebhookListResponse,
+};
+use std::sync::Arc;
+use tower_cookies::Cookies;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::{NewWebhook, Webhook, WebhookDelivery},
+    schema::{webhook_deliveries, webhooks},
+    webhooks as dispatch, AppState,
+};
+
+/// Number of deliveries returned by the delivery log
+const DELIVERY_LOG_LIMIT: i64 = 50;
+
+/// Convert a Webhook model to a WebhookInfo API response.
+fn webhook_to_info(w: Webhook) -> WebhookInfo {
+    let events: Vec<String> = serde_json::from_value(w.events).unwrap_or_default();
+    WebhookInfo {
+        id: w.id,
+        url: w.url,
+        events: events.iter().filter_map(|e| e.parse().ok()).collect(),
+        description: w.description,
+        enabled: w.enabled,
+        created_at: w.created_at.and_utc().to_rfc3339(),
+        updated_at: w.updated_at.and_utc().to_rfc3339(),
+    }
+}
+
+fn delivery_to_info(d: WebhookDelivery) -> WebhookDeliveryInfo {
+    let status = d.status.parse().unwrap_or(WebhookDeliveryStatus::Pending);
+    WebhookDeliveryInfo {
+        id: d.id,
+        event: d.event,
+        status,
+        attempts: d.attempts,
+        response_status: d.response_status,
+        last_error: d.last_error,
+        next_attempt_at: (status == WebhookDeliveryStatus::Pending)
+            .then(|| d.next_attempt_at.and_utc().to_rfc3339()),
+        created_at: d.created_at.and_utc().to_rfc3339(),
+        delivered_at: d.delivered_at.map(|t| t.and_utc().to_rfc3339()),
+    }
+}
+
+/// Validate a subscription list and store it as a JSON array of event names.
+fn events_to_json(events: &[WebhookEvent]) -> Result<serde_json::Value, AppError> {
+    if events.is_empty() {
+        return Err(AppError::BadRequest(
+            "Webhook must subscribe to at least one event",
+        ));
+    }
+    if events.contains(&WebhookEvent::Ping) {
+        return Err(AppError::BadRequest(
+            "The ping event cannot be subscribed to",
+        ));
+    }
+    let mut names: Vec<&str> = events.iter().map(|e| e.as_str()).collect();
+    names.sort_unstable();
+    names.dedup();
+    Ok(serde_json::json!(names))
+}
+
+/// Treat a blank description as absent
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty())
+}
+
+/// Load a webhook owned by the caller.
+fn find_webhook(
+    conn: &mut PgConnection,
+    webhook_id: Uuid,
+    user_id: Uuid,
+) -> Result<Webhook, AppError> {
+    webhooks::table
+        .filter(webhooks::id.eq(webhook_id))
+        .filter(webhooks::user_id.eq(user_id))
+        .first(conn)
+        .map_err(|_| AppError::NotFound("webhook"))
+}
+
+/// GET /api/webhooks
+#[utoipa::path(
+    get,
+    path = "/api/webhooks",
+    tag = "webhooks",
+    responses((status = 200, body = WebhookListResponse))
+)]
+pub async fn list_webhooks_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+) -> Result<Json<WebhookListResponse>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let list: Vec<Webhook> = webhooks::table
+        .filter(webhooks::user_id.eq(user_id))
+        .order(webhooks::created_at.asc())
+        .load(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(Json(WebhookListResponse {
+        webhooks: list.into_iter().map(webhook_to_info).collect(),
+    }))
+}
+
+/// POST /api/webhooks
+#[utoipa::path(
+    post,
+    path = "/api/webhooks",
+    tag = "webhooks",
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 200, description = "The webhook and its signing secret, shown only once", body = CreateWebhookResponse),
+        (status = 400, description = "Invalid URL or events")
+    )
+)]
+pub async fn create_webhook_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Json(req): Json<CreateWebhookRequest>,
+) -> Result<Json<CreateWebhookResponse>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let url = req.url.trim().to_string();
+    dispatch::check_destination(&url, &app_state.webhook_destinations)
+        .await
+        .map_err(AppError::BadRequest)?;
+    let events = events_to_json(&req.events)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let secret = dispatch::generate_secret();
+    let new_webhook = NewWebhook {
+        user_id,
+        url,
+        secret: secret.clone(),
+        events,
+        description: non_empty(req.description),
+    };
+
+    let saved: Webhook = diesel::insert_into(webhooks::table)
+        .values(&new_webhook)
+        .get_result(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    info!("Created webhook {} -> {}", saved.id, saved.url);
+
+    Ok(Json(CreateWebhookResponse {
+        webhook: webhook_to_info(saved),
+        secret,
+    }))
+}
+
+/// PATCH /api/webhooks/:id
+#[utoipa::path(
+    patch,
+    path = "/api/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = Uuid, Path, description = "Webhook ID")),
+    request_body = UpdateWebhookRequest,
+    responses(
+        (status = 200, body = WebhookInfo),
+        (status = 400, description = "Invalid URL or events"),
+        (status = 404, description = "Webhook not found")
+    )
+)]
+pub async fn update_webhook_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(webhook_id): Path<Uuid>,
+    Json(req): Json<UpdateWebhookRequest>,
+) -> Result<Json<WebhookInfo>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let existing = find_webhook(&mut conn, webhook_id, user_id)?;
+
+    let url = match req.url {
+        Some(url) => {
+            let url = url.trim().to_string();
+            dispatch::check_destination(&url, &app_state.webhook_destinations)
+                .await
+                .map_err(AppError::BadRequest)?;
+            url
+        }
+        None => existing.url,
+    };
+    let events = match req.events {
+        Some(events) => events_to_json(&events)?,
+        None => existing.events,
+    };
+    let description = match req.description {
+        Some(d) => non_empty(d),
+        None => existing.description,
+    };
+    let enabled = req.enabled.unwrap_or(existing.enabled);
+
+    let updated: Webhook = diesel::update(webhooks::table.find(existing.id))
+        .set((
+            webhooks::url.eq(&url),
+            webhooks::events.eq(&events),
+            webhooks::description.eq(&description),
+            webhooks::enabled.eq(enabled),
+            webhooks::updated_at.eq(diesel::dsl::now),
+        ))
+        .get_result(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    info!("Updated webhook {}", updated.id);
+
+    Ok(Json(webhook_to_info(updated)))
+}
+
+/// DELETE /api/webhooks/:id
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = Uuid, Path, description = "Webhook ID")),
+    responses(
+        (status = 204, description = "Webhook deleted"),
+        (status = 404, description = "Webhook not found")
+    )
+)]
+pub async fn delete_webhook_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+
+    let deleted = diesel::delete(
+        webhooks::table
+            .filter(webhooks::id.eq(webhook_id))
+            .filter(webhooks::user_id.eq(user_id)),
+    )
+    .execute(&mut conn)
+    .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    if deleted == 0 {
+        return Err(AppError::NotFound("webhook"));
+    }
+
+    info!("Deleted webhook {}", webhook_id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/webhooks/:id/deliveries
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/{id}/deliveries",
+    tag = "webhooks",
+    params(("id" = Uuid, Path, description = "Webhook ID")),
+    responses(
+        (status = 200, body = WebhookDeliveryListResponse),
+        (status = 404, description = "Webhook not found")
+    )
+)]
+pub async fn list_deliveries_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<Json<WebhookDeliveryListResponse>, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let webhook = find_webhook(&mut conn, webhook_id, user_id)?;
+
+    let deliveries: Vec<WebhookDelivery> = webhook_deliveries::table
+        .filter(webhook_deliveries::webhook_id.eq(webhook.id))
+        .order(webhook_deliveries::created_at.desc())
+        .limit(DELIVERY_LOG_LIMIT)
+        .load(&mut conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+
+    Ok(Json(WebhookDeliveryListResponse {
+        deliveries: deliveries.into_iter().map(delivery_to_info).collect(),
+    }))
+}
+
+/// POST /api/webhooks/:id/test
+///
+/// Queues a `ping` delivery; its outcome shows up in the delivery log.
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/{id}/test",
+    tag = "webhooks",
+    params(("id" = Uuid, Path, description = "Webhook ID")),
+    responses(
+        (status = 202, description = "Ping queued for delivery"),
+        (status = 400, description = "Webhook is disabled"),
+        (status = 404, description = "Webhook not found")
+    )
+)]
+pub async fn test_webhook_handler(
+    State(app_state): State<Arc<AppState>>,
+    cookies: Cookies,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let user_id = crate::auth::extract_user_id(&app_state, &cookies)?;
+
+    let mut conn = app_state.db_pool.get().map_err(|_| AppError::DbPool)?;
+    let webhook = find_webhook(&mut conn, webhook_id, user_id)?;
+    if !webhook.enabled {
+        return Err(AppError::BadRequest("Enable the webhook before testing it"));
+    }
+
+    app_state.webhooks.ping(&webhook);
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_to_json_dedups_and_rejects_invalid() {
+        let json = events_to_json(&[
+            WebhookEvent::SessionExited,
+            WebhookEvent::TurnCompleted,
+            WebhookEvent::SessionExited,
+        ])
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!(["session.exited", "session.turn_completed"])
+        );
+        assert!(events_to_json(&[]).is_err());
+        assert!(events_to_json(&[WebhookEvent::Ping]).is_err());
+    }
+}
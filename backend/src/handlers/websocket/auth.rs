@@ -1,9 +1,9 @@
 use crate::AppState;
-use diesel::prelude::*;
 use tracing::{error, warn};
 use uuid::Uuid;
 
-/// Verify that a user has access to a session (is a member with any role)
+/// Verify that a user has access to a session, as a member with any role or
+/// through a team the session is shared with
 pub fn verify_session_access(
     app_state: &AppState,
     session_id: Uuid,
@@ -15,17 +15,21 @@ pub fn verify_session_access(
             e
         );
     })?;
-    use crate::schema::{session_members, sessions};
-    sessions::table
-        .inner_join(session_members::table.on(session_members::session_id.eq(sessions::id)))
-        .filter(sessions::id.eq(session_id))
-        .filter(session_members::user_id.eq(user_id))
-        .select(crate::models::Session::as_select())
-        .first::<crate::models::Session>(&mut conn)
-        .map_err(|e| {
+    match crate::teams::accessible_session(&mut conn, session_id, user_id) {
+        Ok(Some((session, _role))) => Ok(session),
+        Ok(None) => {
+            warn!(
+                "Session access check failed for user {} on session {}: no access",
+                user_id, session_id
+            );
+            Err(())
+        }
+        Err(e) => {
             warn!(
                 "Session access check failed for user {} on session {}: {}",
                 user_id, session_id, e
             );
-        })
+            Err(())
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use axum::extract::ws::WebSocket;
 use diesel::prelude::*;
+use shared::api::WebhookEvent;
 use shared::{
     AgentType, LauncherEndpoint, LauncherToServer, ScheduledTaskConfig, ServerToClient,
     ServerToLauncher, ServerToProxy,
@@ -10,6 +11,7 @@ use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use super::LauncherConnection;
+use crate::metrics::{self, Direction, Peer};
 use crate::AppState;
 
 pub async fn handle_launcher_socket(socket: WebSocket, app_state: Arc<AppState>) {
@@ -212,6 +214,8 @@ pub async fn handle_launcher_socket(socket: WebSocket, app_state: Arc<AppState>)
                 enabled: t.enabled,
                 max_runtime_minutes: t.max_runtime_minutes,
                 last_session_id: t.last_session_id,
+                permission_timeout_seconds: t.permission_timeout_seconds,
+                permission_timeout_action: t.permission_timeout_action.parse().unwrap_or_default(),
             })
             .collect();
 
@@ -234,6 +238,7 @@ pub async fn handle_launcher_socket(socket: WebSocket, app_state: Arc<AppState>)
             result = ws_receiver.recv() => {
                 match result {
                     Some(Ok(msg)) => {
+                        metrics::record_message(Peer::Launcher, Direction::Received);
                         handle_launcher_message(
                             msg,
                             launcher_id,
@@ -255,8 +260,10 @@ pub async fn handle_launcher_socket(socket: WebSocket, app_state: Arc<AppState>)
             // Messages to forward to the launcher
             Some(msg) = rx.recv() => {
                 if ws_sender.send(msg).await.is_err() {
+                    metrics::record_send_failure(Peer::Launcher);
                     break;
                 }
+                metrics::record_message(Peer::Launcher, Direction::Sent);
             }
         }
     }
@@ -285,6 +292,10 @@ fn handle_launcher_message(
                 );
             } else {
                 warn!("Launch failed: request={}, error={:?}", request_id, error);
+                app_state
+                    .session_manager
+                    .pending_forks
+                    .retain(|_, fork| fork.request_id != request_id);
             }
             // Forward to web clients as ServerToClient
             app_state.session_manager.broadcast_to_user(
@@ -318,6 +329,7 @@ fn handle_launcher_message(
                             user_id,
                             old_hash,
                             sender,
+                            None,
                         );
                     }
                 }
@@ -346,11 +358,35 @@ fn handle_launcher_message(
                     exit_code,
                 },
             );
+            app_state.webhooks.dispatch(
+                user_id,
+                WebhookEvent::SessionExited,
+                serde_json::json!({
+                    "session_id": session_id,
+                    "exit_code": exit_code,
+                }),
+            );
         }
-        LauncherToServer::ListDirectoriesResult { request_id, .. } => {
+        LauncherToServer::ListDirectoriesResult { request_id, .. }
+        | LauncherToServer::ListLocalSessionsResult { request_id, .. } => {
             app_state
                 .session_manager
-                .complete_dir_request(request_id, msg);
+                .complete_launcher_request(request_id, msg);
+        }
+        LauncherToServer::SessionHistory {
+            session_id,
+            messages,
+        } => {
+            info!(
+                "Launcher sent {} history messages for adopted session {}",
+                messages.len(),
+                session_id
+            );
+            app_state
+                .session_manager
+                .pending_histories
+                .insert(session_id, (user_id, messages));
+            super::registration::apply_pending_history(app_state, session_id);
         }
         LauncherToServer::RequestLaunch {
             request_id,
@@ -375,6 +411,8 @@ fn handle_launcher_message(
                         claude_args,
                         agent_type,
                         scheduled_task_id,
+                        resume_session_id: None,
+                        fork: None,
                     };
                     if !app_state
                         .session_manager
@@ -415,6 +453,18 @@ fn handle_launcher_message(
             if let Ok(mut db_conn) = app_state.db_pool.get() {
                 use crate::schema::{pending_inputs, sessions};
 
+                if let Ok(Some(reason)) = crate::budgets::session_block(&mut db_conn, session_id) {
+                    warn!("Refusing scheduled input to {}: {}", session_id, reason);
+                    super::permissions::post_portal_marker(
+                        &app_state.session_manager,
+                        &session_key,
+                        &mut db_conn,
+                        session_id,
+                        &shared::PortalMessage::spend_limit(reason),
+                    );
+                    return;
+                }
+
                 let next_seq: i64 = diesel::update(sessions::table.find(session_id))
                     .set(sessions::input_seq.eq(sessions::input_seq + 1))
                     .returning(sessions::input_seq)
@@ -438,6 +488,7 @@ fn handle_launcher_message(
                             seq: next_seq,
                             content: content_value,
                             send_mode: None,
+                            traceparent: None,
                         },
                     );
                 }
@@ -477,6 +528,49 @@ fn handle_launcher_message(
                 task_id, session_id, exit_code, duration_secs
             );
 
+            // Record the outcome for failure mails and the daily digest
+            if let Ok(mut db_conn) = app_state.db_pool.get() {
+                use crate::schema::scheduled_tasks;
+                match diesel::update(
+                    scheduled_tasks::table
+                        .filter(scheduled_tasks::id.eq(task_id))
+                        .filter(scheduled_tasks::user_id.eq(user_id)),
+                )
+                .set((
+                    scheduled_tasks::last_exit_code.eq(exit_code),
+                    scheduled_tasks::last_run_completed_at.eq(diesel::dsl::now),
+                ))
+                .get_result::<crate::models::ScheduledTask>(&mut db_conn)
+                {
+                    Ok(task) if exit_code != Some(0) => {
+                        app_state.mailer.notify_scheduled_failure(&task, exit_code)
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Failed to record scheduled run outcome: {}", e),
+                }
+            }
+
+            // Notify before the session (and its cost) is cleaned up below
+            let total_cost_usd = app_state.db_pool.get().ok().and_then(|mut db_conn| {
+                use crate::schema::sessions;
+                sessions::table
+                    .find(session_id)
+                    .select(sessions::total_cost_usd)
+                    .first::<f64>(&mut db_conn)
+                    .ok()
+            });
+            app_state.webhooks.dispatch(
+                user_id,
+                WebhookEvent::ScheduledRunCompleted,
+                serde_json::json!({
+                    "task_id": task_id,
+                    "session_id": session_id,
+                    "exit_code": exit_code,
+                    "duration_secs": duration_secs,
+                    "total_cost_usd": total_cost_usd,
+                }),
+            );
+
             // Auto-delete completed scheduled sessions to avoid cluttering the UI.
             // Costs are preserved in deleted_session_costs.
             if let Ok(mut db_conn) = app_state.db_pool.get() {
@@ -513,13 +607,15 @@ fn handle_launcher_message(
 }
 
 /// Mint a new 30-day token for a launcher, revoke the old one, and push it over WS.
-/// Used by both the heartbeat auto-renewal and the manual renew API endpoint.
+/// Used by both the heartbeat auto-renewal and the manual renew API endpoint;
+/// `requested_by` is the user who asked, or `None` for the auto-renewal.
 pub fn renew_launcher_token_for(
     app_state: &AppState,
     launcher_id: Uuid,
     user_id: Uuid,
     old_token_hash: Option<String>,
     sender: mpsc::UnboundedSender<ServerToLauncher>,
+    requested_by: Option<Uuid>,
 ) -> Result<(), ()> {
     let mut conn = app_state.db_pool.get().map_err(|e| {
         error!("Failed to get DB connection for token renewal: {}", e);
@@ -575,6 +671,18 @@ pub fn renew_launcher_token_for(
         .execute(&mut conn);
     }
 
+    crate::audit::record(
+        &mut conn,
+        requested_by,
+        shared::api::AuditAction::LauncherTokenRenew,
+        Some(launcher_id),
+        serde_json::json!({
+            "owner_id": user_id,
+            "expires_at": new_expires_at.and_utc().to_rfc3339(),
+            "automatic": requested_by.is_none(),
+        }),
+    );
+
     // Update launcher connection with new token info
     if let Some(mut launcher) = app_state.session_manager.launchers.get_mut(&launcher_id) {
         launcher.token_hash = Some(new_hash);
@@ -1,6 +1,8 @@
 use super::{ProxySender, SessionManager};
 use crate::db::DbPool;
+use crate::AppState;
 use diesel::prelude::*;
+use shared::api::WebhookEvent;
 use shared::{ServerToClient, ServerToProxy};
 use tracing::{error, info, warn};
 use uuid::Uuid;
@@ -55,6 +57,7 @@ pub fn replay_pending_inputs_from_db(
             seq: input.seq_num,
             content,
             send_mode: None,
+            traceparent: None,
         };
 
         if sender.send(msg).is_ok() {
@@ -78,7 +81,9 @@ pub fn replay_pending_inputs_from_db(
 /// Handle Claude output (both legacy ClaudeOutput and new SequencedOutput).
 /// Broadcasts to web clients, deduplicates sequenced messages, stores in DB,
 /// and sends acknowledgments.
+#[allow(clippy::too_many_arguments)]
 pub fn handle_claude_output(
+    app_state: &AppState,
     session_manager: &SessionManager,
     session_key: &Option<String>,
     db_session_id: Option<Uuid>,
@@ -205,6 +210,21 @@ pub fn handle_claude_output(
 
             if role == shared::MessageRole::Result {
                 store_result_metadata(&mut conn, session_id, &content);
+                if let Err(e) = crate::analytics::record_turn(&mut conn, &session, &content) {
+                    error!("Failed to record usage of session {}: {}", session_id, e);
+                }
+                if let Some(reported) = content.get("total_cost_usd").and_then(|c| c.as_f64()) {
+                    enforce_spend_limits(app_state, session_manager, &mut conn, &session, reported);
+                }
+                if content.get("is_error").and_then(|e| e.as_bool()) == Some(true) {
+                    app_state.mailer.notify_session_error(&session, &content);
+                }
+                app_state.chat_bridges.post_turn_result(&session, &content);
+                app_state.webhooks.dispatch(
+                    session.user_id,
+                    WebhookEvent::TurnCompleted,
+                    turn_completed_payload(&session, &content),
+                );
             }
 
             session_manager.queue_truncation(session_id);
@@ -235,6 +255,138 @@ pub fn handle_claude_output(
     }
 }
 
+/// Record what a turn cost and act on the limits it crossed. Members of a
+/// budget past its soft limit are alerted; at its hard limit their running
+/// sessions are interrupted as well. A session that reached its own cost cap
+/// is interrupted too. `session` is the row from before the turn's total was
+/// stored.
+fn enforce_spend_limits(
+    app_state: &AppState,
+    session_manager: &SessionManager,
+    conn: &mut diesel::PgConnection,
+    session: &crate::models::Session,
+    reported: f64,
+) {
+    let delta = match crate::budgets::record_spend(conn, session, reported) {
+        Ok(delta) if delta > 0.0 => delta,
+        Ok(_) => return,
+        Err(e) => {
+            error!("Failed to record spend of session {}: {}", session.id, e);
+            return;
+        }
+    };
+
+    let crossings = match crate::budgets::take_crossings(conn, session.user_id) {
+        Ok(crossings) => crossings,
+        Err(e) => {
+            error!("Failed to check spend budgets: {}", e);
+            Vec::new()
+        }
+    };
+    for crossing in crossings {
+        let usage = &crossing.usage;
+        let message = usage.alert_text(crossing.hard);
+        info!("Spend alert for budget {}: {}", usage.budget.id, message);
+        for user_id in &usage.members {
+            session_manager.broadcast_to_user(
+                user_id,
+                ServerToClient::SpendAlert {
+                    message: message.clone(),
+                    hard: crossing.hard,
+                },
+            );
+        }
+        app_state
+            .mailer
+            .notify_spend_alert(&usage.members, &message, crossing.hard);
+        if crossing.hard {
+            interrupt_sessions_of(session_manager, conn, &usage.members, &usage.block_reason());
+        }
+    }
+
+    if session.max_cost_usd.is_none() {
+        return;
+    }
+    let after_turn = match crate::budgets::session_spend(conn, session) {
+        Ok(spent) => spent,
+        Err(e) => {
+            error!("Failed to total spend of session {}: {}", session.id, e);
+            return;
+        }
+    };
+    if let Some(reason) =
+        crate::budgets::cap_crossed_by_turn(session.max_cost_usd, after_turn, delta)
+    {
+        interrupt_session(session_manager, conn, session.id, &reason);
+    }
+}
+
+/// Interrupt every connected session owned by one of `user_ids`.
+fn interrupt_sessions_of(
+    session_manager: &SessionManager,
+    conn: &mut diesel::PgConnection,
+    user_ids: &[Uuid],
+    reason: &str,
+) {
+    use crate::schema::sessions;
+
+    let session_ids: Vec<Uuid> = match sessions::table
+        .filter(sessions::user_id.eq_any(user_ids))
+        .filter(sessions::status.eq("active"))
+        .select(sessions::id)
+        .load(conn)
+    {
+        Ok(ids) => ids,
+        Err(e) => {
+            error!("Failed to load sessions to interrupt: {}", e);
+            return;
+        }
+    };
+    for session_id in session_ids {
+        interrupt_session(session_manager, conn, session_id, reason);
+    }
+}
+
+/// Interrupt a session's agent and say why in its transcript.
+fn interrupt_session(
+    session_manager: &SessionManager,
+    conn: &mut diesel::PgConnection,
+    session_id: Uuid,
+    reason: &str,
+) {
+    let key = session_id.to_string();
+    if !session_manager.sessions.contains_key(&key) {
+        return;
+    }
+    info!("Interrupting session {}: {}", session_id, reason);
+    session_manager.send_to_session(&key, ServerToProxy::Interrupt);
+    super::permissions::post_portal_marker(
+        session_manager,
+        &key,
+        conn,
+        session_id,
+        &shared::PortalMessage::spend_limit(reason.to_string()),
+    );
+}
+
+/// Webhook data for a finished turn, taken from the `result` message.
+fn turn_completed_payload(
+    session: &crate::models::Session,
+    content: &serde_json::Value,
+) -> serde_json::Value {
+    serde_json::json!({
+        "session_id": session.id,
+        "session_name": session.session_name,
+        "working_directory": session.working_directory,
+        "subtype": content.get("subtype"),
+        "is_error": content.get("is_error"),
+        "num_turns": content.get("num_turns"),
+        "duration_ms": content.get("duration_ms"),
+        "total_cost_usd": content.get("total_cost_usd"),
+        "result": content.get("result"),
+    })
+}
+
 /// Extract and store cost and token usage from result messages.
 /// Tries typed deserialization via `claude_codes::io::ResultMessage` first,
 /// falls back to manual JSON extraction for forward compatibility.
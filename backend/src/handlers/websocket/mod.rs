@@ -1,21 +1,27 @@
 mod auth;
 pub mod launcher_socket;
 mod message_handlers;
+mod permission_timeouts;
 mod permissions;
 mod proxy_socket;
 mod registration;
 mod session_manager;
+mod shared_socket;
 mod web_client_socket;
 
+pub use permission_timeouts::expire_timed_out_permissions;
+pub(crate) use permissions::handle_permission_response;
 pub use session_manager::{
-    LauncherConnection, ProxySender, SessionId, SessionManager, WebClientSender,
+    LauncherConnection, PendingFork, ProxySender, SessionId, SessionManager, WebClientSender,
 };
+pub(crate) use web_client_socket::submit_input;
 
 use axum::{
-    extract::{ws::WebSocketUpgrade, State},
-    http::StatusCode,
+    extract::{ws::WebSocketUpgrade, ConnectInfo, State},
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
 };
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tower_cookies::Cookies;
 use tracing::{info, warn};
@@ -49,8 +55,28 @@ pub async fn handle_web_client_websocket(
         }
     };
 
+    // The login is watched so revoking it closes the socket
+    let login_id = crate::auth::current_login(&app_state, &cookies)
+        .ok()
+        .map(|login| login.id);
+
     info!("Authenticated WebSocket upgrade for user: {}", user_id);
     ws.on_upgrade(move |socket| {
-        web_client_socket::handle_web_client_socket(socket, app_state, user_id)
+        web_client_socket::handle_web_client_socket(socket, app_state, user_id, login_id)
+    })
+}
+
+/// Anonymous, read-only viewers holding a public share link. The link itself
+/// is checked once the socket is open and the viewer has joined.
+pub async fn handle_shared_websocket(
+    ws: WebSocketUpgrade,
+    State(app_state): State<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Response {
+    let ip_address = crate::client_ip::client_ip(&headers, Some(peer), &app_state.trusted_proxies);
+    let user_agent = crate::client_ip::user_agent(&headers);
+    ws.on_upgrade(move |socket| {
+        shared_socket::handle_shared_socket(socket, app_state, ip_address, user_agent)
     })
 }
@@ -0,0 +1,263 @@
+//! Permission request timeouts
+//!
+//! A periodic sweep resolves permission requests that have waited longer than
+//! their session's timeout. Deny and allow answer the proxy exactly as a user
+//! would; escalate notifies every member of the session once and keeps the
+//! request pending. Only sessions with a connected proxy are swept, since an
+//! answer cannot be delivered to a disconnected one.
+
+use super::permissions::{
+    decision_for_pending, post_portal_marker, record_decision, take_pending_request,
+};
+use super::{SessionId, SessionManager};
+use crate::db::DbPool;
+use crate::models::{PendingPermissionRequest, ScheduledTask, Session};
+use crate::permission_policy::{self, EffectiveTimeout};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use shared::api::{PermissionDecider, PermissionTimeoutAction};
+use shared::{PortalMessage, ServerToClient, ServerToProxy};
+use std::collections::HashMap;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Whether a request created at `created_at` has outlived its timeout.
+fn is_overdue(created_at: NaiveDateTime, timeout_seconds: i32, now: NaiveDateTime) -> bool {
+    (now - created_at).num_seconds() >= i64::from(timeout_seconds)
+}
+
+/// Resolve or escalate every overdue permission request in connected sessions.
+pub fn expire_timed_out_permissions(session_manager: &SessionManager, db_pool: &DbPool) {
+    let connected: Vec<Uuid> = session_manager
+        .registered_session_keys()
+        .iter()
+        .filter_map(|key| key.parse().ok())
+        .collect();
+    if connected.is_empty() {
+        return;
+    }
+
+    let mut conn = match db_pool.get() {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!(
+                "Failed to get database connection for permission timeouts: {}",
+                e
+            );
+            return;
+        }
+    };
+
+    use crate::schema::{pending_permission_requests, scheduled_tasks, sessions};
+
+    let pending: Vec<PendingPermissionRequest> = match pending_permission_requests::table
+        .filter(pending_permission_requests::session_id.eq_any(&connected))
+        .filter(pending_permission_requests::escalated_at.is_null())
+        .order(pending_permission_requests::created_at.asc())
+        .load(&mut conn)
+    {
+        Ok(pending) => pending,
+        Err(e) => {
+            error!("Failed to load pending permission requests: {}", e);
+            return;
+        }
+    };
+    if pending.is_empty() {
+        return;
+    }
+
+    let session_ids: Vec<Uuid> = pending.iter().map(|p| p.session_id).collect();
+    let sessions: HashMap<Uuid, Session> = sessions::table
+        .filter(sessions::id.eq_any(&session_ids))
+        .load::<Session>(&mut conn)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|s| (s.id, s))
+        .collect();
+
+    let task_ids: Vec<Uuid> = sessions
+        .values()
+        .filter_map(|s| s.scheduled_task_id)
+        .collect();
+    let tasks: HashMap<Uuid, ScheduledTask> = if task_ids.is_empty() {
+        HashMap::new()
+    } else {
+        scheduled_tasks::table
+            .filter(scheduled_tasks::id.eq_any(&task_ids))
+            .load::<ScheduledTask>(&mut conn)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|t| (t.id, t))
+            .collect()
+    };
+
+    let now = chrono::Utc::now().naive_utc();
+    for request in pending {
+        let Some(session) = sessions.get(&request.session_id) else {
+            continue;
+        };
+        let task = session.scheduled_task_id.and_then(|id| tasks.get(&id));
+        let Some(timeout) = permission_policy::effective_timeout(
+            session.permission_timeout_seconds,
+            session.permission_timeout_action.as_deref(),
+            task.map(|t| {
+                (
+                    t.permission_timeout_seconds,
+                    t.permission_timeout_action.as_str(),
+                )
+            }),
+        ) else {
+            continue;
+        };
+        if !is_overdue(request.created_at, timeout.seconds, now) {
+            continue;
+        }
+
+        let session_key = session.id.to_string();
+        match timeout.action {
+            PermissionTimeoutAction::Escalate => escalate_request(
+                session_manager,
+                &session_key,
+                &mut conn,
+                session,
+                request,
+                timeout,
+            ),
+            PermissionTimeoutAction::Allow | PermissionTimeoutAction::Deny => {
+                resolve_request(session_manager, &session_key, &mut conn, request, timeout)
+            }
+        }
+    }
+}
+
+/// Answer an overdue request with the timeout's allow/deny fallback.
+fn resolve_request(
+    session_manager: &SessionManager,
+    session_key: &SessionId,
+    conn: &mut PgConnection,
+    request: PendingPermissionRequest,
+    timeout: EffectiveTimeout,
+) {
+    // Someone may have answered between the load and now
+    let Some(pending) = take_pending_request(conn, request.session_id, &request.request_id) else {
+        return;
+    };
+
+    let allow = timeout.action == PermissionTimeoutAction::Allow;
+    let reason = format!("No answer within {} seconds", timeout.seconds);
+    info!(
+        "Permission request {} for tool {} timed out -> {}",
+        pending.request_id,
+        pending.tool_name,
+        timeout.action.as_str()
+    );
+
+    let response = shared::PermissionResponseFields {
+        request_id: pending.request_id.clone(),
+        allow,
+        input: allow.then(|| pending.input.clone()),
+        permissions: vec![],
+        reason: (!allow).then(|| format!("Permission request timed out: {}", reason)),
+    };
+    if !session_manager.send_to_session(session_key, ServerToProxy::PermissionResponse(response)) {
+        warn!(
+            "Failed to send timeout PermissionResponse to session '{}', session not connected",
+            session_key
+        );
+    }
+    session_manager.broadcast_to_web_clients(
+        session_key,
+        ServerToClient::PermissionResolved {
+            request_id: pending.request_id.clone(),
+        },
+    );
+
+    let session_id = pending.session_id;
+    let marker = PortalMessage::permission_timeout(
+        pending.tool_name.clone(),
+        timeout.action,
+        timeout.seconds,
+    );
+    let decision = decision_for_pending(
+        pending,
+        allow,
+        PermissionDecider::Timeout,
+        None,
+        None,
+        Some(reason),
+    );
+    record_decision(conn, decision);
+    post_portal_marker(session_manager, session_key, conn, session_id, &marker);
+}
+
+/// Notify every session member about an overdue request, once, and leave it
+/// pending.
+fn escalate_request(
+    session_manager: &SessionManager,
+    session_key: &SessionId,
+    conn: &mut PgConnection,
+    session: &Session,
+    request: PendingPermissionRequest,
+    timeout: EffectiveTimeout,
+) {
+    use crate::schema::pending_permission_requests;
+
+    let marked = diesel::update(
+        pending_permission_requests::table
+            .filter(pending_permission_requests::id.eq(request.id))
+            .filter(pending_permission_requests::escalated_at.is_null()),
+    )
+    .set(pending_permission_requests::escalated_at.eq(diesel::dsl::now))
+    .execute(conn);
+    match marked {
+        Ok(0) => return,
+        Ok(_) => {}
+        Err(e) => {
+            error!("Failed to mark permission request as escalated: {}", e);
+            return;
+        }
+    }
+
+    info!(
+        "Escalating permission request {} for tool {} in session {}",
+        request.request_id, request.tool_name, session.id
+    );
+
+    let waiting_seconds = (chrono::Utc::now().naive_utc() - request.created_at).num_seconds();
+    let members = crate::teams::session_audience(conn, session.id).unwrap_or_default();
+    for user_id in members {
+        session_manager.broadcast_to_user(
+            &user_id,
+            ServerToClient::PermissionEscalated {
+                session_id: session.id,
+                session_name: session.session_name.clone(),
+                request_id: request.request_id.clone(),
+                tool_name: request.tool_name.clone(),
+                waiting_seconds,
+            },
+        );
+    }
+
+    let marker = PortalMessage::permission_timeout(
+        request.tool_name,
+        PermissionTimeoutAction::Escalate,
+        timeout.seconds,
+    );
+    post_portal_marker(session_manager, session_key, conn, session.id, &marker);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_overdue() {
+        let created = chrono::DateTime::from_timestamp(1_700_000_000, 0)
+            .unwrap()
+            .naive_utc();
+        let later = |secs| created + chrono::Duration::seconds(secs);
+        assert!(!is_overdue(created, 60, later(59)));
+        assert!(is_overdue(created, 60, later(60)));
+        assert!(is_overdue(created, 60, later(3600)));
+    }
+}
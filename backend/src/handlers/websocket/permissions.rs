@@ -1,13 +1,18 @@
 use super::{SessionId, SessionManager, WebClientSender};
 use crate::db::DbPool;
+use crate::models::{NewPermissionDecision, PendingPermissionRequest};
+use crate::permission_policy::{self, PolicyDecision};
+use crate::AppState;
 use diesel::prelude::*;
-use shared::{ServerToClient, ServerToProxy};
+use shared::api::{PermissionDecider, PermissionVerdict, WebhookEvent};
+use shared::{PortalMessage, ServerToClient, ServerToProxy};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
 /// Store a permission request in the database and forward it to web clients.
 #[allow(clippy::too_many_arguments)]
 pub fn handle_permission_request(
+    app_state: &AppState,
     session_manager: &SessionManager,
     session_key: &Option<String>,
     db_session_id: Option<Uuid>,
@@ -17,6 +22,25 @@ pub fn handle_permission_request(
     input: serde_json::Value,
     permission_suggestions: Vec<shared::PermissionSuggestion>,
 ) {
+    // Answer directly if one of the owner's policy rules decides the request
+    if let (Some(key), Some(session_id)) = (session_key, db_session_id) {
+        if let Some(decision) = evaluate_policy(db_pool, session_id, &tool_name, &input) {
+            if decision.verdict != PermissionVerdict::Ask {
+                apply_policy_decision(
+                    session_manager,
+                    key,
+                    session_id,
+                    db_pool,
+                    request_id,
+                    tool_name,
+                    input,
+                    decision,
+                );
+                return;
+            }
+        }
+    }
+
     // Store in database for replay on reconnect
     if let Some(session_id) = db_session_id {
         match db_pool.get() {
@@ -37,20 +61,48 @@ pub fn handle_permission_request(
                     permission_suggestions: suggestions_json.clone(),
                 };
 
-                if let Err(e) = diesel::insert_into(pending_permission_requests::table)
+                let already_pending = diesel::select(diesel::dsl::exists(
+                    pending_permission_requests::table
+                        .filter(pending_permission_requests::session_id.eq(session_id))
+                        .filter(pending_permission_requests::request_id.eq(&request_id)),
+                ))
+                .get_result::<bool>(&mut conn)
+                .unwrap_or(false);
+
+                // A request re-sent after a proxy reconnect keeps its original
+                // position in the queue (created_at is not bumped)
+                match diesel::insert_into(pending_permission_requests::table)
                     .values(&new_request)
-                    .on_conflict(pending_permission_requests::session_id)
+                    .on_conflict((
+                        pending_permission_requests::session_id,
+                        pending_permission_requests::request_id,
+                    ))
                     .do_update()
                     .set((
-                        pending_permission_requests::request_id.eq(&request_id),
                         pending_permission_requests::tool_name.eq(&tool_name),
                         pending_permission_requests::input.eq(&input),
                         pending_permission_requests::permission_suggestions.eq(suggestions_json),
-                        pending_permission_requests::created_at.eq(diesel::dsl::now),
                     ))
-                    .execute(&mut conn)
+                    .returning(pending_permission_requests::id)
+                    .get_result::<Uuid>(&mut conn)
                 {
-                    error!("Failed to store pending permission request: {}", e);
+                    Err(e) => error!("Failed to store pending permission request: {}", e),
+                    Ok(_) if already_pending => {}
+                    Ok(pending_id) => {
+                        app_state
+                            .chat_bridges
+                            .post_permission_request(session_id, pending_id, &tool_name, &input);
+                        app_state.webhooks.dispatch_for_session(
+                            session_id,
+                            WebhookEvent::PermissionRequested,
+                            serde_json::json!({
+                                "session_id": session_id,
+                                "request_id": request_id,
+                                "tool_name": tool_name,
+                                "input": input,
+                            }),
+                        );
+                    }
                 }
             }
             Err(e) => {
@@ -82,12 +134,232 @@ pub fn handle_permission_request(
     }
 }
 
-/// Handle a permission response from a web client: clear from DB and forward to proxy.
+/// Look up the session owner's rules and evaluate them against a request.
+fn evaluate_policy(
+    db_pool: &DbPool,
+    session_id: Uuid,
+    tool_name: &str,
+    input: &serde_json::Value,
+) -> Option<PolicyDecision> {
+    use crate::schema::sessions;
+
+    let mut conn = match db_pool.get() {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!(
+                "Failed to get database connection for permission policy: {}",
+                e
+            );
+            return None;
+        }
+    };
+
+    let (owner_id, working_directory) = sessions::table
+        .find(session_id)
+        .select((sessions::user_id, sessions::working_directory))
+        .first::<(Uuid, String)>(&mut conn)
+        .ok()?;
+
+    let rules = match permission_policy::load_rules(&mut conn, owner_id, session_id) {
+        Ok(rules) => rules,
+        Err(e) => {
+            error!("Failed to load permission rules: {}", e);
+            return None;
+        }
+    };
+
+    permission_policy::evaluate(&rules, tool_name, input, Some(&working_directory))
+}
+
+/// Answer the proxy on behalf of a policy rule and leave a marker in the
+/// transcript explaining why nobody was asked.
+#[allow(clippy::too_many_arguments)]
+fn apply_policy_decision(
+    session_manager: &SessionManager,
+    session_key: &SessionId,
+    session_id: Uuid,
+    db_pool: &DbPool,
+    request_id: String,
+    tool_name: String,
+    input: serde_json::Value,
+    decision: PolicyDecision,
+) {
+    let allow = decision.verdict == PermissionVerdict::Allow;
+    let requested_at = chrono::Utc::now().naive_utc();
+    info!(
+        "Permission request {} for tool {} auto-{} by rule {}",
+        request_id,
+        tool_name,
+        if allow { "approved" } else { "denied" },
+        decision.rule_id
+    );
+
+    let audit_input = input.clone();
+    let response = if allow {
+        shared::PermissionResponseFields {
+            request_id: request_id.clone(),
+            allow: true,
+            input: Some(input),
+            permissions: vec![],
+            reason: None,
+        }
+    } else {
+        shared::PermissionResponseFields {
+            request_id: request_id.clone(),
+            allow: false,
+            input: None,
+            permissions: vec![],
+            reason: Some(format!(
+                "Denied by portal permission rule: {}",
+                decision.rule_summary
+            )),
+        }
+    };
+    let audit = NewPermissionDecision {
+        session_id,
+        request_id,
+        tool_name: tool_name.clone(),
+        input: audit_input,
+        decision: decision.verdict.as_str().to_string(),
+        decided_by: PermissionDecider::Policy.as_str().to_string(),
+        user_id: None,
+        permission_rule_id: Some(decision.rule_id),
+        reason: Some(decision.rule_summary.clone()),
+        requested_at,
+        latency_ms: 0,
+    };
+    if !session_manager.send_to_session(session_key, ServerToProxy::PermissionResponse(response)) {
+        warn!(
+            "Failed to send policy PermissionResponse to session '{}', session not connected",
+            session_key
+        );
+    }
+
+    let marker = PortalMessage::permission_decision(tool_name, allow, decision.rule_summary);
+    match db_pool.get() {
+        Ok(mut conn) => {
+            record_decision(&mut conn, audit);
+            post_portal_marker(session_manager, session_key, &mut conn, session_id, &marker);
+        }
+        Err(e) => error!(
+            "Failed to get database connection for policy decision: {}",
+            e
+        ),
+    }
+}
+
+/// Show a portal marker to connected web clients and store it so it appears
+/// in history.
+pub(super) fn post_portal_marker(
+    session_manager: &SessionManager,
+    session_key: &SessionId,
+    conn: &mut PgConnection,
+    session_id: Uuid,
+    marker: &PortalMessage,
+) {
+    session_manager.broadcast_to_web_clients(
+        session_key,
+        ServerToClient::ClaudeOutput {
+            content: marker.to_json(),
+            sender_user_id: None,
+            sender_name: None,
+        },
+    );
+
+    use crate::schema::{messages, sessions};
+    if let Ok(owner_id) = sessions::table
+        .find(session_id)
+        .select(sessions::user_id)
+        .first::<Uuid>(conn)
+    {
+        let new_message = crate::models::NewMessage {
+            session_id,
+            role: "portal".to_string(),
+            content: marker.to_json().to_string(),
+            user_id: owner_id,
+        };
+        if let Err(e) = diesel::insert_into(messages::table)
+            .values(&new_message)
+            .execute(conn)
+        {
+            error!("Failed to store portal marker: {}", e);
+        }
+    }
+}
+
+/// Remove a pending request from the queue, returning it if it was still there.
+pub(super) fn take_pending_request(
+    conn: &mut PgConnection,
+    session_id: Uuid,
+    request_id: &str,
+) -> Option<PendingPermissionRequest> {
+    use crate::schema::pending_permission_requests;
+    match diesel::delete(
+        pending_permission_requests::table
+            .filter(pending_permission_requests::session_id.eq(session_id))
+            .filter(pending_permission_requests::request_id.eq(request_id)),
+    )
+    .get_results::<PendingPermissionRequest>(conn)
+    {
+        Ok(mut rows) => rows.pop(),
+        Err(e) => {
+            error!("Failed to clear pending permission request: {}", e);
+            None
+        }
+    }
+}
+
+/// Build an audit row for a request that was answered from the pending queue.
+/// Recorded decision for an answer the VS Code shim saw but couldn't read
+const UNKNOWN_DECISION: &str = "unknown";
+
+pub(super) fn decision_for_pending(
+    pending: PendingPermissionRequest,
+    allow: bool,
+    decided_by: PermissionDecider,
+    user_id: Option<Uuid>,
+    input: Option<serde_json::Value>,
+    reason: Option<String>,
+) -> NewPermissionDecision {
+    let latency_ms = (chrono::Utc::now().naive_utc() - pending.created_at)
+        .num_milliseconds()
+        .max(0);
+    NewPermissionDecision {
+        session_id: pending.session_id,
+        request_id: pending.request_id,
+        tool_name: pending.tool_name,
+        // Record the input that was actually approved, which may have been edited
+        input: input.unwrap_or(pending.input),
+        decision: if allow { "allow" } else { "deny" }.to_string(),
+        decided_by: decided_by.as_str().to_string(),
+        user_id,
+        permission_rule_id: None,
+        reason,
+        requested_at: pending.created_at,
+        latency_ms,
+    }
+}
+
+/// Append a row to the permission decision audit log.
+pub(super) fn record_decision(conn: &mut PgConnection, decision: NewPermissionDecision) {
+    use crate::schema::permission_decisions;
+    if let Err(e) = diesel::insert_into(permission_decisions::table)
+        .values(&decision)
+        .execute(conn)
+    {
+        error!("Failed to record permission decision: {}", e);
+    }
+}
+
+/// Handle a permission response from a web client: clear that request from the
+/// DB, record who answered it, tell other viewers it was answered, and forward
+/// it to the proxy.
 #[allow(clippy::too_many_arguments)]
 pub fn handle_permission_response(
     session_manager: &SessionManager,
     session_key: &SessionId,
     session_id: Uuid,
+    user_id: Uuid,
     db_pool: &DbPool,
     request_id: String,
     allow: bool,
@@ -103,17 +375,20 @@ pub fn handle_permission_response(
         reason
     );
 
-    // Clear pending permission request from database
+    // Clear this pending permission request from the database. Only the first
+    // answer is audited; a late duplicate from another tab finds no row.
     match db_pool.get() {
         Ok(mut conn) => {
-            use crate::schema::pending_permission_requests;
-            if let Err(e) = diesel::delete(
-                pending_permission_requests::table
-                    .filter(pending_permission_requests::session_id.eq(session_id)),
-            )
-            .execute(&mut conn)
-            {
-                error!("Failed to clear pending permission request: {}", e);
+            if let Some(pending) = take_pending_request(&mut conn, session_id, &request_id) {
+                let decision = decision_for_pending(
+                    pending,
+                    allow,
+                    PermissionDecider::User,
+                    Some(user_id),
+                    input.clone(),
+                    reason.clone(),
+                );
+                record_decision(&mut conn, decision);
             }
         }
         Err(e) => {
@@ -124,6 +399,13 @@ pub fn handle_permission_response(
         }
     }
 
+    session_manager.broadcast_to_web_clients(
+        session_key,
+        ServerToClient::PermissionResolved {
+            request_id: request_id.clone(),
+        },
+    );
+
     if !session_manager.send_to_session(
         session_key,
         ServerToProxy::PermissionResponse(shared::PermissionResponseFields {
@@ -141,13 +423,68 @@ pub fn handle_permission_response(
     }
 }
 
-/// Replay a pending permission request from the database to a newly connected web client.
-pub fn replay_pending_permission(db_pool: &DbPool, session_id: Uuid, tx: &WebClientSender) {
+/// Handle a permission request answered outside the portal (VS Code via the
+/// shim): drop it from the queue, record it and tell web clients.
+pub fn handle_local_permission_answer(
+    session_manager: &SessionManager,
+    session_key: &Option<String>,
+    db_session_id: Option<Uuid>,
+    db_pool: &DbPool,
+    request_id: String,
+    allow: Option<bool>,
+    reason: Option<String>,
+) {
+    let Some(session_id) = db_session_id else {
+        return;
+    };
+
+    let outcome = match allow {
+        Some(true) => "allow",
+        Some(false) => "deny",
+        None => UNKNOWN_DECISION,
+    };
+    info!(
+        "Permission request {} answered locally -> {}",
+        request_id, outcome
+    );
+
+    match db_pool.get() {
+        Ok(mut conn) => {
+            if let Some(pending) = take_pending_request(&mut conn, session_id, &request_id) {
+                let mut decision = decision_for_pending(
+                    pending,
+                    allow.unwrap_or(false),
+                    PermissionDecider::Vscode,
+                    None,
+                    None,
+                    reason,
+                );
+                decision.decision = outcome.to_string();
+                record_decision(&mut conn, decision);
+            }
+        }
+        Err(e) => {
+            error!(
+                "Failed to get database connection for local permission answer: {}",
+                e
+            );
+        }
+    }
+
+    if let Some(key) = session_key {
+        session_manager
+            .broadcast_to_web_clients(key, ServerToClient::PermissionResolved { request_id });
+    }
+}
+
+/// Replay pending permission requests from the database to a newly connected
+/// web client, oldest first.
+pub fn replay_pending_permissions(db_pool: &DbPool, session_id: Uuid, tx: &WebClientSender) {
     let mut conn = match db_pool.get() {
         Ok(conn) => conn,
         Err(e) => {
             warn!(
-                "Failed to get database connection for replaying permission request: {}",
+                "Failed to get database connection for replaying permission requests: {}",
                 e
             );
             return;
@@ -155,25 +492,46 @@ pub fn replay_pending_permission(db_pool: &DbPool, session_id: Uuid, tx: &WebCli
     };
 
     use crate::schema::pending_permission_requests;
-    if let Ok(pending) = pending_permission_requests::table
-        .filter(pending_permission_requests::session_id.eq(session_id))
-        .first::<crate::models::PendingPermissionRequest>(&mut conn)
-    {
+    let pending: Vec<crate::models::PendingPermissionRequest> =
+        match pending_permission_requests::table
+            .filter(pending_permission_requests::session_id.eq(session_id))
+            .order((
+                pending_permission_requests::created_at.asc(),
+                pending_permission_requests::id.asc(),
+            ))
+            .load(&mut conn)
+        {
+            Ok(pending) => pending,
+            Err(e) => {
+                error!("Failed to load pending permission requests: {}", e);
+                return;
+            }
+        };
+
+    if !pending.is_empty() {
         info!(
-            "Replaying pending permission request for session {}: {} ({})",
-            session_id, pending.tool_name, pending.request_id
+            "Replaying {} pending permission request(s) for session {}",
+            pending.len(),
+            session_id
         );
+    }
 
-        let suggestions: Vec<shared::PermissionSuggestion> = pending
+    for request in pending {
+        let suggestions: Vec<shared::PermissionSuggestion> = request
             .permission_suggestions
             .and_then(|v| serde_json::from_value(v).ok())
             .unwrap_or_default();
 
-        let _ = tx.send(ServerToClient::PermissionRequest {
-            request_id: pending.request_id,
-            tool_name: pending.tool_name,
-            input: pending.input,
-            permission_suggestions: suggestions,
-        });
+        if tx
+            .send(ServerToClient::PermissionRequest {
+                request_id: request.request_id,
+                tool_name: request.tool_name,
+                input: request.input,
+                permission_suggestions: suggestions,
+            })
+            .is_err()
+        {
+            break;
+        }
     }
 }
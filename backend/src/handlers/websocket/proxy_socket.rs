@@ -1,7 +1,10 @@
 use super::message_handlers::{handle_claude_output, replay_pending_inputs_from_db};
-use super::permissions::handle_permission_request;
-use super::registration::{register_or_update_session, RegistrationParams};
+use super::permissions::{handle_local_permission_answer, handle_permission_request};
+use super::registration::{
+    apply_pending_fork, apply_pending_history, register_or_update_session, RegistrationParams,
+};
 use super::{ProxySender, SessionId, SessionManager};
+use crate::metrics::{self, Direction, Peer};
 use crate::AppState;
 use axum::extract::ws::WebSocket;
 use diesel::prelude::*;
@@ -25,14 +28,17 @@ pub async fn handle_session_socket(socket: WebSocket, app_state: Arc<AppState>)
     let send_task = tokio::spawn(async move {
         while let Some(msg) = rx.recv().await {
             if ws_sender.send(msg).await.is_err() {
+                metrics::record_send_failure(Peer::Proxy);
                 break;
             }
+            metrics::record_message(Peer::Proxy, Direction::Sent);
         }
     });
 
     while let Some(result) = ws_receiver.recv().await {
         match result {
             Ok(proxy_msg) => {
+                metrics::record_message(Peer::Proxy, Direction::Received);
                 handle_proxy_message(
                     proxy_msg,
                     &app_state,
@@ -115,10 +121,6 @@ fn handle_proxy_message(
             scheduled_task_id,
         }) => {
             let key = claude_session_id.to_string();
-            *session_key = Some(key.clone());
-
-            let gen = session_manager.register_session(key.clone(), tx.clone());
-            *connection_gen = Some(gen);
 
             let params = RegistrationParams {
                 claude_session_id,
@@ -138,13 +140,20 @@ fn handle_proxy_message(
             };
             let result = register_or_update_session(app_state, &params);
 
+            // Only a registered proxy gets the session's inputs, so one that
+            // was refused can't pick up another user's queued messages
+            if result.success {
+                *session_key = Some(key.clone());
+                let gen = session_manager.register_session(key, tx.clone());
+                *connection_gen = Some(gen);
+            }
             *db_session_id = result.session_id;
 
             let _ = tx.send(ServerToProxy::RegisterAck {
                 success: result.success,
                 session_id: claude_session_id,
                 error: result.error,
-                max_image_mb: app_state.max_image_mb,
+                max_image_mb: app_state.settings.get().max_image_mb,
             });
 
             info!(
@@ -155,11 +164,14 @@ fn handle_proxy_message(
             if result.success {
                 if let Some(session_id) = *db_session_id {
                     replay_pending_inputs_from_db(db_pool, session_id, tx);
+                    apply_pending_history(app_state, session_id);
+                    apply_pending_fork(app_state, session_id);
                 }
             }
         }
         ProxyToServer::ClaudeOutput { content } => {
             handle_claude_output(
+                app_state,
                 session_manager,
                 session_key,
                 *db_session_id,
@@ -169,8 +181,27 @@ fn handle_proxy_message(
                 None,
             );
         }
-        ProxyToServer::SequencedOutput { seq, content } => {
+        ProxyToServer::SequencedOutput {
+            seq,
+            content,
+            traceparent,
+        } => {
+            // Output of a traced turn continues the proxy's trace
+            let span = match traceparent {
+                Some(traceparent) => {
+                    let span = tracing::info_span!(
+                        "handle_claude_output",
+                        session_id = ?db_session_id,
+                        seq
+                    );
+                    portal_telemetry::set_parent(&span, Some(&traceparent));
+                    span
+                }
+                None => tracing::Span::none(),
+            };
+            let _entered = span.enter();
             handle_claude_output(
+                app_state,
                 session_manager,
                 session_key,
                 *db_session_id,
@@ -190,6 +221,7 @@ fn handle_proxy_message(
             permission_suggestions,
         } => {
             handle_permission_request(
+                app_state,
                 session_manager,
                 session_key,
                 *db_session_id,
@@ -200,6 +232,21 @@ fn handle_proxy_message(
                 permission_suggestions,
             );
         }
+        ProxyToServer::PermissionAnsweredLocally {
+            request_id,
+            allow,
+            reason,
+        } => {
+            handle_local_permission_answer(
+                session_manager,
+                session_key,
+                *db_session_id,
+                db_pool,
+                request_id,
+                allow,
+                reason,
+            );
+        }
         ProxyToServer::SessionUpdate {
             session_id: update_session_id,
             git_branch,
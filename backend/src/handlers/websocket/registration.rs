@@ -1,4 +1,4 @@
-use crate::models::{NewSessionMember, NewSessionWithId};
+use crate::models::{NewSessionMember, NewSessionTeam, NewSessionWithId};
 use crate::AppState;
 use diesel::prelude::*;
 use shared::AgentType;
@@ -76,6 +76,20 @@ pub fn register_or_update_session(
         .unwrap_or(None);
 
     if let Some(existing_session) = existing {
+        // A proxy may only take over a session its own user owns
+        let token_user = get_user_id_from_token(app_state, params.auth_token);
+        if token_user != Some(existing_session.user_id) {
+            warn!(
+                "Refusing to reactivate session {} for {:?}: owned by {}",
+                existing_session.id, token_user, existing_session.user_id
+            );
+            return RegistrationResult {
+                success: false,
+                session_id: None,
+                error: Some("Session belongs to another user".to_string()),
+            };
+        }
+
         match diesel::update(sessions::table.find(existing_session.id))
             .set((
                 sessions::status.eq("active"),
@@ -135,6 +149,28 @@ fn create_new_session(
         };
     };
 
+    // Proxies started outside a launcher are held to the owner's budgets too
+    match crate::budgets::launch_block(conn, user_id) {
+        Ok(Some(reason)) => {
+            warn!(
+                "Refusing session {} of user {}: {}",
+                params.claude_session_id, user_id, reason
+            );
+            return RegistrationResult {
+                success: false,
+                session_id: None,
+                error: Some(reason),
+            };
+        }
+        Ok(None) => {}
+        Err(e) => error!("Failed to check spend budgets: {}", e),
+    }
+    let max_cost_usd = params.auth_token.and_then(|token| {
+        app_state
+            .session_manager
+            .take_cost_cap(&crate::jwt::hash_token(token))
+    });
+
     use crate::schema::{session_members, sessions};
 
     let new_session = NewSessionWithId {
@@ -151,6 +187,7 @@ fn create_new_session(
         agent_type: params.agent_type.as_str().to_string(),
         repo_url: params.repo_url.clone(),
         scheduled_task_id: params.scheduled_task_id,
+        max_cost_usd,
     };
 
     match diesel::insert_into(sessions::table)
@@ -169,6 +206,9 @@ fn create_new_session(
             {
                 error!("Failed to create session_member: {}", e);
             }
+            if let Some(task_id) = params.scheduled_task_id {
+                share_task_run_with_team(conn, task_id, session.id);
+            }
 
             info!(
                 "Session persisted to DB: {} ({}) branch: {:?} agent: {}",
@@ -191,6 +231,37 @@ fn create_new_session(
     }
 }
 
+/// Runs of a team task are shared with the task's team, as editors so they
+/// can answer the run's permission prompts.
+fn share_task_run_with_team(conn: &mut diesel::PgConnection, task_id: Uuid, session_id: Uuid) {
+    use crate::schema::{scheduled_tasks, session_teams};
+
+    let team_id: Option<Uuid> = scheduled_tasks::table
+        .find(task_id)
+        .select(scheduled_tasks::team_id)
+        .first(conn)
+        .optional()
+        .ok()
+        .flatten()
+        .flatten();
+    let Some(team_id) = team_id else { return };
+
+    if let Err(e) = diesel::insert_into(session_teams::table)
+        .values(&NewSessionTeam {
+            session_id,
+            team_id,
+            role: "editor".to_string(),
+        })
+        .on_conflict_do_nothing()
+        .execute(conn)
+    {
+        error!(
+            "Failed to share task run {} with team {}: {}",
+            session_id, team_id, e
+        );
+    }
+}
+
 /// Get user_id from auth token using JWT verification
 fn get_user_id_from_token(app_state: &AppState, auth_token: Option<&str>) -> Option<Uuid> {
     let mut conn = app_state.db_pool.get().ok()?;
@@ -218,3 +289,156 @@ fn get_user_id_from_token(app_state: &AppState, auth_token: Option<&str>) -> Opt
         None
     }
 }
+
+/// Rows inserted per statement when backfilling history (Postgres allows at
+/// most 65535 bind parameters per statement)
+const BACKFILL_BATCH: usize = 1000;
+
+/// Store the history a launcher sent for an adopted session.
+///
+/// The history and the session's registration arrive over different sockets
+/// in either order, so this runs on both; it does nothing until the session
+/// row exists and the history is waiting. Messages keep their transcript
+/// timestamps so they sort before the session's new output.
+pub fn apply_pending_history(app_state: &AppState, session_id: Uuid) {
+    use crate::schema::{messages, sessions};
+
+    let Ok(mut conn) = app_state.db_pool.get() else {
+        return;
+    };
+    let Ok(Some(owner)) = sessions::table
+        .find(session_id)
+        .select(sessions::user_id)
+        .first::<Uuid>(&mut conn)
+        .optional()
+    else {
+        return;
+    };
+    let Some((_, (user_id, history))) = app_state
+        .session_manager
+        .pending_histories
+        .remove(&session_id)
+    else {
+        return;
+    };
+    if user_id != owner {
+        warn!(
+            "Ignoring history for session {} sent by a launcher of another user",
+            session_id
+        );
+        return;
+    }
+
+    let mut created_at = chrono::Utc::now().naive_utc();
+    let rows: Vec<_> = history
+        .iter()
+        .rev()
+        .map(|message| {
+            // Untimestamped messages take the time of the message after them
+            if let Some(ts) = message
+                .timestamp
+                .as_deref()
+                .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+            {
+                created_at = ts.naive_utc();
+            }
+            let role = shared::MessageRole::from_type_str(
+                message
+                    .content
+                    .get("type")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or("assistant"),
+            );
+            (
+                messages::session_id.eq(session_id),
+                messages::role.eq(role.to_string()),
+                messages::content.eq(message.content.to_string()),
+                messages::user_id.eq(owner),
+                messages::created_at.eq(created_at),
+            )
+        })
+        .collect();
+
+    for batch in rows.chunks(BACKFILL_BATCH) {
+        if let Err(e) = diesel::insert_into(messages::table)
+            .values(batch)
+            .execute(&mut conn)
+        {
+            error!("Failed to backfill history of {}: {}", session_id, e);
+            return;
+        }
+    }
+    info!(
+        "Backfilled {} messages into adopted session {}",
+        rows.len(),
+        session_id
+    );
+    app_state.session_manager.queue_truncation(session_id);
+}
+
+/// Record a fork's lineage and copy the parent's conversation up to the fork
+/// point into it. Runs when a session registers; does nothing unless the
+/// session was launched as a fork.
+pub fn apply_pending_fork(app_state: &AppState, session_id: Uuid) {
+    use crate::schema::{messages, sessions};
+
+    let Some((_, fork)) = app_state.session_manager.pending_forks.remove(&session_id) else {
+        return;
+    };
+    let Ok(mut conn) = app_state.db_pool.get() else {
+        return;
+    };
+    let owner = sessions::table
+        .find(session_id)
+        .select(sessions::user_id)
+        .first::<Uuid>(&mut conn)
+        .optional();
+    if !matches!(owner, Ok(Some(owner)) if owner == fork.user_id) {
+        warn!(
+            "Ignoring fork of {} for session {} registered by another user",
+            fork.parent_session_id, session_id
+        );
+        return;
+    }
+
+    let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::update(sessions::table.find(session_id))
+            .set((
+                sessions::parent_session_id.eq(fork.parent_session_id),
+                sessions::forked_from_message_id.eq(fork.message_id),
+            ))
+            .execute(conn)?;
+        // Copies keep their timestamps so they sort before the fork's output
+        diesel::insert_into(messages::table)
+            .values(
+                messages::table
+                    .filter(messages::session_id.eq(fork.parent_session_id))
+                    .filter(messages::created_at.le(fork.message_created_at))
+                    .select((
+                        session_id.into_sql::<diesel::sql_types::Uuid>(),
+                        messages::role,
+                        messages::content,
+                        messages::created_at,
+                        messages::user_id,
+                    )),
+            )
+            .into_columns((
+                messages::session_id,
+                messages::role,
+                messages::content,
+                messages::created_at,
+                messages::user_id,
+            ))
+            .execute(conn)
+    });
+    match result {
+        Ok(copied) => {
+            info!(
+                "Session {} forked from {} with {} messages",
+                session_id, fork.parent_session_id, copied
+            );
+            app_state.session_manager.queue_truncation(session_id);
+        }
+        Err(e) => error!("Failed to record fork {}: {}", session_id, e),
+    }
+}
@@ -1,17 +1,21 @@
 use dashmap::{DashMap, DashSet};
-use shared::{LauncherToServer, ServerToClient, ServerToLauncher, ServerToProxy};
+use shared::{HistoryMessage, LauncherToServer, ServerToClient, ServerToLauncher, ServerToProxy};
 use std::collections::VecDeque;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{mpsc, oneshot, watch};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use shared::protocol::{MAX_PENDING_MESSAGES_PER_SESSION, MAX_PENDING_MESSAGE_AGE_SECS};
 
+use crate::metrics::{self, Peer};
+
 /// Maximum age of pending messages before they're dropped
 const MAX_PENDING_MESSAGE_AGE: Duration = Duration::from_secs(MAX_PENDING_MESSAGE_AGE_SECS);
+/// How long a launch token stays valid, and so how long its cost cap is held
+const LAUNCH_TOKEN_LIFETIME: Duration = Duration::from_secs(24 * 3600);
 
 /// A message queued for a disconnected proxy
 #[derive(Clone)]
@@ -40,6 +44,46 @@ pub struct LauncherConnection {
     pub token_expires_at: Option<chrono::NaiveDateTime>,
 }
 
+/// A fork that has been sent to a launcher but has not registered yet
+pub struct PendingFork {
+    pub request_id: Uuid,
+    pub user_id: Uuid,
+    pub parent_session_id: Uuid,
+    /// Last message of the parent copied into the fork
+    pub message_id: Uuid,
+    pub message_created_at: chrono::NaiveDateTime,
+}
+
+/// Hand a message to a browser connection, counting it when the connection
+/// has gone away. Returns whether the client should be kept.
+fn deliver_to_web_client(sender: &WebClientSender, msg: &ServerToClient) -> bool {
+    let delivered = sender.send(msg.clone()).is_ok();
+    if !delivered {
+        metrics::record_send_failure(Peer::Web);
+    }
+    delivered
+}
+
+fn launcher_info(
+    launcher_id: Uuid,
+    launcher: &LauncherConnection,
+    shared_by: Option<String>,
+) -> shared::LauncherInfo {
+    shared::LauncherInfo {
+        launcher_id,
+        launcher_name: launcher.launcher_name.clone(),
+        hostname: launcher.hostname.clone(),
+        connected: true,
+        running_sessions: launcher.running_sessions.len() as u32,
+        working_directory: launcher.working_directory.clone(),
+        version: launcher.version.clone(),
+        token_expires_at: launcher
+            .token_expires_at
+            .map(|dt| dt.and_utc().to_rfc3339()),
+        shared_by,
+    }
+}
+
 #[derive(Clone)]
 pub struct SessionManager {
     pub sessions: Arc<DashMap<SessionId, ProxySender>>,
@@ -49,13 +93,25 @@ pub struct SessionManager {
     pending_messages: Arc<DashMap<SessionId, VecDeque<PendingMessage>>>,
     pub pending_truncations: Arc<DashSet<Uuid>>,
     pub launchers: Arc<DashMap<Uuid, LauncherConnection>>,
-    pub pending_dir_requests: Arc<DashMap<Uuid, oneshot::Sender<LauncherToServer>>>,
+    /// Launcher round trips awaiting an answer (directory listings, local sessions)
+    pub pending_launcher_requests: Arc<DashMap<Uuid, oneshot::Sender<LauncherToServer>>>,
+    /// History of adopted sessions, held until the session registers
+    /// (session_id → (user_id, messages))
+    pub pending_histories: Arc<DashMap<Uuid, (Uuid, Vec<HistoryMessage>)>>,
+    /// Forks held until they register (fork session_id → lineage)
+    pub pending_forks: Arc<DashMap<Uuid, PendingFork>>,
+    /// Cost caps chosen at launch, held until the session registers
+    /// (launch token hash → (cap in USD, when it was chosen))
+    pending_cost_caps: Arc<DashMap<String, (f64, Instant)>>,
     /// Tracks who sent the last input for each session (session_id → (user_id, display_name))
     pub last_input_sender: Arc<DashMap<Uuid, (Uuid, String)>>,
     /// Monotonic counter for connection generations (prevents stale cleanup)
     gen_counter: Arc<AtomicU64>,
     /// Current connection generation per session
     connection_gen: Arc<DashMap<SessionId, u64>>,
+    /// Browser logins with open client sockets (web login id → signal that
+    /// closes them when the login is revoked)
+    web_logins: Arc<DashMap<Uuid, watch::Sender<bool>>>,
 }
 
 impl Default for SessionManager {
@@ -68,10 +124,14 @@ impl Default for SessionManager {
             pending_messages: Arc::new(DashMap::new()),
             pending_truncations: Arc::new(DashSet::new()),
             launchers: Arc::new(DashMap::new()),
-            pending_dir_requests: Arc::new(DashMap::new()),
+            pending_launcher_requests: Arc::new(DashMap::new()),
+            pending_histories: Arc::new(DashMap::new()),
+            pending_forks: Arc::new(DashMap::new()),
+            pending_cost_caps: Arc::new(DashMap::new()),
             last_input_sender: Arc::new(DashMap::new()),
             gen_counter: Arc::new(AtomicU64::new(1)),
             connection_gen: Arc::new(DashMap::new()),
+            web_logins: Arc::new(DashMap::new()),
         }
     }
 }
@@ -166,16 +226,38 @@ impl SessionManager {
     }
 
     pub fn broadcast_to_web_clients(&self, session_key: &SessionId, msg: ServerToClient) {
+        // Only broadcasts made for a traced input or output get a span;
+        // untraced ones would each start a trace of their own
+        let span = if tracing::Span::current().is_none() {
+            tracing::Span::none()
+        } else {
+            tracing::info_span!("broadcast_to_web_clients", session = %session_key)
+        };
+        let _entered = span.enter();
         if let Some(mut clients) = self.web_clients.get_mut(session_key) {
-            clients.retain(|sender| sender.send(msg.clone()).is_ok());
+            clients.retain(|sender| deliver_to_web_client(sender, &msg));
         }
     }
 
-    pub fn send_to_session(&self, session_key: &SessionId, msg: ServerToProxy) -> bool {
+    pub fn send_to_session(&self, session_key: &SessionId, mut msg: ServerToProxy) -> bool {
+        // Input is traced from this span, so the proxy's spans hang off it.
+        // It continues the browser's trace if there is one, or starts one.
+        let span = match msg.traceparent_mut() {
+            Some(traceparent) => {
+                let span = tracing::info_span!("send_to_session", session = %session_key);
+                if let Some(current) = span.in_scope(portal_telemetry::current_traceparent) {
+                    *traceparent = Some(current);
+                }
+                span
+            }
+            _ => tracing::Span::none(),
+        };
+        let _entered = span.enter();
         if let Some(sender) = self.sessions.get(session_key) {
             if sender.send(msg.clone()).is_ok() {
                 return true;
             }
+            metrics::record_send_failure(Peer::Proxy);
         }
 
         self.queue_pending_message(session_key, msg)
@@ -218,7 +300,40 @@ impl SessionManager {
 
     pub fn broadcast_to_user(&self, user_id: &Uuid, msg: ServerToClient) {
         if let Some(mut clients) = self.user_clients.get_mut(user_id) {
-            clients.retain(|sender| sender.send(msg.clone()).is_ok());
+            clients.retain(|sender| deliver_to_web_client(sender, &msg));
+        }
+    }
+
+    /// Watch for `login_id` being revoked; the receiver sees `true` then.
+    /// Call `release_web_login` once the socket closes.
+    pub fn watch_web_login(&self, login_id: Uuid) -> watch::Receiver<bool> {
+        self.web_logins
+            .entry(login_id)
+            .or_insert_with(|| watch::channel(false).0)
+            .subscribe()
+    }
+
+    /// Forget the login once none of its sockets are open
+    pub fn release_web_login(&self, login_id: Uuid) {
+        self.web_logins
+            .remove_if(&login_id, |_, sender| sender.receiver_count() == 0);
+    }
+
+    /// Whether any socket is still watching `login_id`
+    #[cfg(test)]
+    pub fn has_web_login(&self, login_id: Uuid) -> bool {
+        self.web_logins.contains_key(&login_id)
+    }
+
+    /// Close every client socket opened with `login_id`
+    pub fn end_web_login(&self, login_id: Uuid) {
+        if let Some((_, sender)) = self.web_logins.remove(&login_id) {
+            info!(
+                "Closing {} client socket(s) of revoked login {}",
+                sender.receiver_count(),
+                login_id
+            );
+            let _ = sender.send(true);
         }
     }
 
@@ -243,12 +358,12 @@ impl SessionManager {
         for mut entry in self.web_clients.iter_mut() {
             entry
                 .value_mut()
-                .retain(|sender| sender.send(client_msg.clone()).is_ok());
+                .retain(|sender| deliver_to_web_client(sender, &client_msg));
         }
         for mut entry in self.user_clients.iter_mut() {
             entry
                 .value_mut()
-                .retain(|sender| sender.send(client_msg.clone()).is_ok());
+                .retain(|sender| deliver_to_web_client(sender, &client_msg));
         }
 
         let launcher_msg = ServerToLauncher::ServerShutdown {
@@ -260,6 +375,22 @@ impl SessionManager {
         }
     }
 
+    /// Remember the cost cap for the session a launch token will register.
+    /// Caps whose token has expired unused are dropped on the way.
+    pub fn hold_cost_cap(&self, token_hash: String, max_cost_usd: f64) {
+        self.pending_cost_caps
+            .retain(|_, (_, held_at)| held_at.elapsed() < LAUNCH_TOKEN_LIFETIME);
+        self.pending_cost_caps
+            .insert(token_hash, (max_cost_usd, Instant::now()));
+    }
+
+    /// The cost cap chosen when the session behind this token was launched.
+    pub fn take_cost_cap(&self, token_hash: &str) -> Option<f64> {
+        self.pending_cost_caps
+            .remove(token_hash)
+            .map(|(_, (cap, _))| cap)
+    }
+
     pub fn queue_truncation(&self, session_id: Uuid) {
         self.pending_truncations.insert(session_id);
     }
@@ -300,25 +431,35 @@ impl SessionManager {
         self.launchers
             .iter()
             .filter(|entry| entry.value().user_id == *user_id)
-            .map(|entry| shared::LauncherInfo {
-                launcher_id: *entry.key(),
-                launcher_name: entry.value().launcher_name.clone(),
-                hostname: entry.value().hostname.clone(),
-                connected: true,
-                running_sessions: entry.value().running_sessions.len() as u32,
-                working_directory: entry.value().working_directory.clone(),
-                version: entry.value().version.clone(),
-                token_expires_at: entry
-                    .value()
-                    .token_expires_at
-                    .map(|dt| dt.and_utc().to_rfc3339()),
+            .map(|entry| launcher_info(*entry.key(), entry.value(), None))
+            .collect()
+    }
+
+    /// Connected launchers among those shared with a user through teams,
+    /// given as (owner, launcher name, owner's display name).
+    pub fn get_shared_launchers(
+        &self,
+        shares: &[(Uuid, String, String)],
+    ) -> Vec<shared::LauncherInfo> {
+        self.launchers
+            .iter()
+            .filter_map(|entry| {
+                let launcher = entry.value();
+                let (_, _, owner) = shares.iter().find(|(owner_id, name, _)| {
+                    *owner_id == launcher.user_id && *name == launcher.launcher_name
+                })?;
+                Some(launcher_info(*entry.key(), launcher, Some(owner.clone())))
             })
             .collect()
     }
 
     pub fn send_to_launcher(&self, launcher_id: &Uuid, msg: ServerToLauncher) -> bool {
         if let Some(launcher) = self.launchers.get(launcher_id) {
-            launcher.sender.send(msg).is_ok()
+            let sent = launcher.sender.send(msg).is_ok();
+            if !sent {
+                metrics::record_send_failure(Peer::Launcher);
+            }
+            sent
         } else {
             false
         }
@@ -360,14 +501,17 @@ impl SessionManager {
         self.sessions.iter().map(|r| r.key().clone()).collect()
     }
 
-    pub fn register_dir_request(&self, request_id: Uuid) -> oneshot::Receiver<LauncherToServer> {
+    pub fn register_launcher_request(
+        &self,
+        request_id: Uuid,
+    ) -> oneshot::Receiver<LauncherToServer> {
         let (tx, rx) = oneshot::channel();
-        self.pending_dir_requests.insert(request_id, tx);
+        self.pending_launcher_requests.insert(request_id, tx);
         rx
     }
 
-    pub fn complete_dir_request(&self, request_id: Uuid, msg: LauncherToServer) {
-        if let Some((_, tx)) = self.pending_dir_requests.remove(&request_id) {
+    pub fn complete_launcher_request(&self, request_id: Uuid, msg: LauncherToServer) {
+        if let Some((_, tx)) = self.pending_launcher_requests.remove(&request_id) {
             let _ = tx.send(msg);
         }
     }
@@ -387,6 +531,7 @@ mod tests {
             seq: n as i64,
             content: serde_json::json!({"n": n}),
             send_mode: None,
+            traceparent: None,
         }
     }
 
@@ -678,4 +823,24 @@ mod tests {
         assert!(ids.contains(&id1));
         assert!(ids.contains(&id2));
     }
+
+    #[test]
+    fn end_web_login_signals_its_sockets() {
+        let mgr = SessionManager::new();
+        let login = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let first = mgr.watch_web_login(login);
+        let second = mgr.watch_web_login(login);
+        let unrelated = mgr.watch_web_login(other);
+
+        mgr.end_web_login(login);
+        assert!(*first.borrow());
+        assert!(*second.borrow());
+        assert!(!*unrelated.borrow());
+
+        // Released once its last socket is gone
+        drop(unrelated);
+        mgr.release_web_login(other);
+        assert!(mgr.web_logins.is_empty());
+    }
 }
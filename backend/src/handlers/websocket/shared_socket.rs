@@ -0,0 +1,217 @@
+//! Read-only viewer socket for public share links (`/ws/shared`).
+//!
+//! The viewer joins with a link token (and password, if the link has one),
+//! then receives the session's history and live output. Everything sent to
+//! the viewer passes through `filter_for_viewer`, so input, permission
+//! prompts, cost and member identities never reach it. Anything the viewer
+//! sends after joining is ignored.
+
+use super::web_client_socket::replay_history;
+use crate::errors::AppError;
+use crate::metrics::{self, Direction, Peer};
+use crate::share_links::{self, redact};
+use crate::AppState;
+use shared::{ServerToClient, SharedClientToServer, SharedEndpoint};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// How long a new connection has to send its join message
+const JOIN_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often an open viewer re-checks that its link is still valid
+const RECHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+const LINK_GONE: &str = "This share link has expired or been revoked";
+
+pub async fn handle_shared_socket(
+    socket: axum::extract::ws::WebSocket,
+    app_state: Arc<AppState>,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+) {
+    let conn = ws_bridge::server::into_connection::<SharedEndpoint>(socket);
+    let (mut ws_sender, mut ws_receiver) = conn.split();
+
+    let (token, password) = match tokio::time::timeout(JOIN_TIMEOUT, ws_receiver.recv()).await {
+        Ok(Some(Ok(SharedClientToServer::Join { token, password }))) => (token, password),
+        Ok(Some(Err(e))) => {
+            warn!("Shared viewer sent an invalid join message: {}", e);
+            return;
+        }
+        _ => return,
+    };
+
+    let (link_id, session_id) =
+        match authorize(&app_state, &token, password, ip_address.as_deref()).await {
+            Ok(link) => link,
+            Err(message) => {
+                let _ = ws_sender.send(ServerToClient::Error { message }).await;
+                return;
+            }
+        };
+
+    match app_state.db_pool.get() {
+        Ok(mut conn) => {
+            if let Err(e) = share_links::record_view(&mut conn, link_id, ip_address, user_agent) {
+                error!("Failed to record view of share link {}: {:?}", link_id, e);
+            }
+        }
+        Err(e) => error!("Failed to get database connection to record view: {}", e),
+    }
+    info!("Share link {} opened for session {}", link_id, session_id);
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<ServerToClient>();
+    app_state
+        .session_manager
+        .add_web_client(session_id.to_string(), tx.clone());
+    replay_history(&app_state.db_pool, &tx, session_id, None);
+    drop(tx);
+
+    let mut recheck = tokio::time::interval_at(
+        tokio::time::Instant::now() + RECHECK_INTERVAL,
+        RECHECK_INTERVAL,
+    );
+
+    loop {
+        tokio::select! {
+            msg = rx.recv() => {
+                let Some(msg) = msg else { break };
+                if let Some(msg) = filter_for_viewer(msg) {
+                    if ws_sender.send(msg).await.is_err() {
+                        metrics::record_send_failure(Peer::Web);
+                        break;
+                    }
+                    metrics::record_message(Peer::Web, Direction::Sent);
+                }
+            }
+            incoming = ws_receiver.recv() => {
+                // Viewers cannot act on the session; only notice the close
+                if incoming.is_none() {
+                    break;
+                }
+            }
+            _ = recheck.tick() => {
+                let active = app_state
+                    .db_pool
+                    .get()
+                    .ok()
+                    .and_then(|mut conn| share_links::is_link_active(&mut conn, link_id).ok());
+                if active == Some(false) {
+                    let _ = ws_sender
+                        .send(ServerToClient::Error { message: LINK_GONE.to_string() })
+                        .await;
+                    break;
+                }
+            }
+        }
+    }
+
+    info!("Share link {} viewer disconnected", link_id);
+}
+
+/// Check the token and password, returning the link and session ids, or the
+/// message to show the viewer. Password guesses are throttled per link and
+/// per viewer IP.
+async fn authorize(
+    app_state: &AppState,
+    token: &str,
+    password: Option<String>,
+    ip_address: Option<&str>,
+) -> Result<(Uuid, Uuid), String> {
+    let link = {
+        let mut conn = app_state
+            .db_pool
+            .get()
+            .map_err(|_| "Service unavailable".to_string())?;
+        share_links::find_active_link(&mut conn, token)
+            .map_err(|_| "Service unavailable".to_string())?
+            .ok_or_else(|| LINK_GONE.to_string())?
+    };
+
+    if let Some(hash) = link.password_hash {
+        let Some(password) = password else {
+            return Err("This link needs a password".to_string());
+        };
+        let throttle_key = link.id.to_string();
+        if let Err(AppError::TooManyAttempts(wait)) = app_state
+            .share_link_throttle
+            .attempt(&throttle_key, ip_address)
+        {
+            warn!("Throttling password guesses for share link {}", link.id);
+            return Err(format!(
+                "Too many wrong passwords, try again in {} seconds",
+                wait.as_secs().max(1)
+            ));
+        }
+        // Argon2 is deliberately slow; keep it off the async workers
+        let matches =
+            tokio::task::spawn_blocking(move || share_links::verify_password(&password, &hash))
+                .await
+                .unwrap_or(false);
+        if !matches {
+            warn!("Wrong password for share link {}", link.id);
+            return Err("Incorrect password".to_string());
+        }
+        app_state.share_link_throttle.succeeded(&throttle_key);
+    }
+
+    Ok((link.id, link.session_id))
+}
+
+/// What a share link viewer may see of a message meant for session members.
+fn filter_for_viewer(msg: ServerToClient) -> Option<ServerToClient> {
+    match msg {
+        ServerToClient::ClaudeOutput { content, .. } => Some(ServerToClient::ClaudeOutput {
+            content: redact(content),
+            sender_user_id: None,
+            sender_name: None,
+        }),
+        ServerToClient::HistoryBatch { messages } => Some(ServerToClient::HistoryBatch {
+            messages: messages.into_iter().map(redact).collect(),
+        }),
+        ServerToClient::SessionStatus { .. }
+        | ServerToClient::ServerShutdown { .. }
+        | ServerToClient::Error { .. } => Some(msg),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_viewer_gets_redacted_output_only() {
+        let output = filter_for_viewer(ServerToClient::ClaudeOutput {
+            content: serde_json::json!({"type": "result", "total_cost_usd": 1.5}),
+            sender_user_id: Some("u".to_string()),
+            sender_name: Some("Ada".to_string()),
+        });
+        match output {
+            Some(ServerToClient::ClaudeOutput {
+                content,
+                sender_user_id,
+                sender_name,
+            }) => {
+                assert_eq!(content, serde_json::json!({"type": "result"}));
+                assert!(sender_user_id.is_none() && sender_name.is_none());
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        assert!(filter_for_viewer(ServerToClient::PermissionRequest {
+            request_id: "r".to_string(),
+            tool_name: "Bash".to_string(),
+            input: serde_json::json!({}),
+            permission_suggestions: vec![],
+        })
+        .is_none());
+        assert!(filter_for_viewer(ServerToClient::UserSpendUpdate {
+            total_spend_usd: 3.0,
+            session_costs: vec![],
+        })
+        .is_none());
+    }
+}
@@ -1,5 +1,6 @@
-use super::permissions::{handle_permission_response, replay_pending_permission};
+use super::permissions::{handle_permission_response, replay_pending_permissions};
 use super::{SessionId, SessionManager, WebClientSender};
+use crate::metrics::{self, Direction, Peer};
 use crate::models::NewPendingInput;
 use crate::AppState;
 use axum::extract::ws::WebSocket;
@@ -9,10 +10,14 @@ use shared::{
 };
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+/// How long a revoked login's socket gets to deliver `SignedOut` before it
+/// is closed
+const SIGNED_OUT_FLUSH: std::time::Duration = std::time::Duration::from_secs(1);
+
 /// Maximum total upload size: 50 MB decoded (~68 MB base64)
 const MAX_TOTAL_CHUNKS: u32 = 51_200; // 50 MB / 1 KB
 
@@ -22,7 +27,14 @@ struct PendingUpload {
     received_count: u32,
 }
 
-pub async fn handle_web_client_socket(socket: WebSocket, app_state: Arc<AppState>, user_id: Uuid) {
+/// `login_id` is the browser login the socket was opened with; revoking it
+/// closes the socket. Dev mode sockets may have none.
+pub async fn handle_web_client_socket(
+    socket: WebSocket,
+    app_state: Arc<AppState>,
+    user_id: Uuid,
+    login_id: Option<Uuid>,
+) {
     let session_manager = app_state.session_manager.clone();
     let db_pool = app_state.db_pool.clone();
     let conn = ws_bridge::server::into_connection::<ClientEndpoint>(socket);
@@ -35,17 +47,51 @@ pub async fn handle_web_client_socket(socket: WebSocket, app_state: Arc<AppState
 
     session_manager.add_user_client(user_id, tx.clone());
 
-    let send_task = tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
+    // Never signalled when the socket has no login
+    let (_no_login, unwatched) = watch::channel(false);
+    let mut signed_out = login_id
+        .map(|id| session_manager.watch_web_login(id))
+        .unwrap_or(unwatched);
+    let mut sender_signed_out = signed_out.clone();
+
+    let mut send_task = tokio::spawn(async move {
+        loop {
+            let msg = tokio::select! {
+                msg = rx.recv() => match msg {
+                    Some(msg) => msg,
+                    None => break,
+                },
+                _ = revoked(&mut sender_signed_out) => {
+                    let _ = ws_sender.send(ServerToClient::SignedOut).await;
+                    break;
+                }
+            };
             if ws_sender.send(msg).await.is_err() {
+                metrics::record_send_failure(Peer::Web);
                 break;
             }
+            metrics::record_message(Peer::Web, Direction::Sent);
         }
     });
 
-    while let Some(result) = ws_receiver.recv().await {
+    let mut send_finished = false;
+    loop {
+        let result = tokio::select! {
+            result = ws_receiver.recv() => match result {
+                Some(result) => result,
+                None => break,
+            },
+            _ = revoked(&mut signed_out) => {
+                info!("Closing client socket for user {}: login revoked", user_id);
+                send_finished = tokio::time::timeout(SIGNED_OUT_FLUSH, &mut send_task)
+                    .await
+                    .is_ok();
+                break;
+            }
+        };
         match result {
             Ok(client_msg) => {
+                metrics::record_message(Peer::Web, Direction::Received);
                 let should_break = handle_web_client_message(
                     client_msg,
                     &app_state,
@@ -68,7 +114,34 @@ pub async fn handle_web_client_socket(socket: WebSocket, app_state: Arc<AppState
         }
     }
 
-    send_task.abort();
+    let send_task = (!send_finished).then_some(send_task);
+    release_login(&session_manager, login_id, signed_out, send_task).await;
+}
+
+/// Stop the send task and forget the login if no other socket still uses it.
+/// The task holds its own watch on the login until it has actually finished,
+/// so it is awaited after aborting rather than left to wind down.
+async fn release_login(
+    session_manager: &SessionManager,
+    login_id: Option<Uuid>,
+    signed_out: watch::Receiver<bool>,
+    send_task: Option<tokio::task::JoinHandle<()>>,
+) {
+    if let Some(send_task) = send_task {
+        send_task.abort();
+        let _ = send_task.await;
+    }
+    drop(signed_out);
+    if let Some(login_id) = login_id {
+        session_manager.release_web_login(login_id);
+    }
+}
+
+/// Resolves once the socket's login is revoked
+async fn revoked(signed_out: &mut watch::Receiver<bool>) {
+    if signed_out.wait_for(|revoked| *revoked).await.is_err() {
+        std::future::pending::<()>().await;
+    }
 }
 
 /// Returns true if the connection should be closed
@@ -102,7 +175,14 @@ fn handle_web_client_message(
             session_key,
             verified_session_id,
         ),
-        ClientToServer::ClaudeInput { content, send_mode } => {
+        ClientToServer::ClaudeInput {
+            content,
+            send_mode,
+            traceparent,
+        } => {
+            let span = tracing::info_span!("web.claude_input", session_id = ?verified_session_id);
+            portal_telemetry::set_parent(&span, traceparent.as_deref());
+            let _entered = span.enter();
             handle_web_input(
                 session_manager,
                 db_pool,
@@ -160,6 +240,7 @@ fn handle_web_client_message(
                     session_manager,
                     key,
                     session_id,
+                    user_id,
                     db_pool,
                     request_id,
                     allow,
@@ -211,7 +292,7 @@ fn handle_web_register(
             );
 
             replay_history(db_pool, tx, session_id, replay_after);
-            replay_pending_permission(db_pool, session_id, tx);
+            replay_pending_permissions(db_pool, session_id, tx);
             false
         }
         Err(_) => {
@@ -228,7 +309,7 @@ fn handle_web_register(
 }
 
 /// Send historical messages from DB to a newly connected web client
-fn replay_history(
+pub(super) fn replay_history(
     db_pool: &crate::db::DbPool,
     tx: &WebClientSender,
     session_id: Uuid,
@@ -350,6 +431,49 @@ fn handle_web_input(
     };
 
     info!("Web client sending ClaudeInput to session: {}", key);
+    // A refusal is shown to the client as a portal marker
+    let _ = submit_input(
+        session_manager,
+        db_pool,
+        key,
+        session_id,
+        content,
+        send_mode,
+        user_id,
+    );
+}
+
+/// Queue input for a session and forward it to the proxy, attributing it to
+/// `user_id`. Shared by web clients, the REST API and the chat bridge.
+///
+/// Input to a session whose cost cap or owner's budget is used up is refused
+/// with a portal marker, and the reason is returned.
+pub(crate) fn submit_input(
+    session_manager: &SessionManager,
+    db_pool: &crate::db::DbPool,
+    key: &SessionId,
+    session_id: Uuid,
+    content: serde_json::Value,
+    send_mode: Option<SendMode>,
+    user_id: Uuid,
+) -> Result<(), String> {
+    if let Ok(mut conn) = db_pool.get() {
+        match crate::budgets::session_block(&mut conn, session_id) {
+            Ok(Some(reason)) => {
+                info!("Refusing input to session {}: {}", session_id, reason);
+                super::permissions::post_portal_marker(
+                    session_manager,
+                    key,
+                    &mut conn,
+                    session_id,
+                    &PortalMessage::spend_limit(reason.clone()),
+                );
+                return Err(reason);
+            }
+            Ok(None) => {}
+            Err(e) => error!("Failed to check spend limits: {}", e),
+        }
+    }
 
     // Track who sent this input so we can attribute the echoed user message
     if let Ok(mut conn) = db_pool.get() {
@@ -435,6 +559,8 @@ fn handle_web_input(
         }
     };
 
+    // Set when the input arrived in a traced span, e.g. from a browser
+    let traceparent = portal_telemetry::current_traceparent();
     if seq > 0 {
         if !session_manager.send_to_session(
             key,
@@ -443,6 +569,7 @@ fn handle_web_input(
                 seq,
                 content,
                 send_mode,
+                traceparent,
             },
         ) {
             warn!(
@@ -450,14 +577,20 @@ fn handle_web_input(
                 key
             );
         }
-    } else if !session_manager
-        .send_to_session(key, ServerToProxy::ClaudeInput { content, send_mode })
-    {
+    } else if !session_manager.send_to_session(
+        key,
+        ServerToProxy::ClaudeInput {
+            content,
+            send_mode,
+            traceparent,
+        },
+    ) {
         warn!(
             "Failed to send to session '{}', session not found in SessionManager",
             key
         );
     }
+    Ok(())
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -571,3 +704,35 @@ fn sanitize_filename(name: &str) -> String {
         clean
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A socket's watch and the send task's copy of it
+    fn open_socket(
+        mgr: &SessionManager,
+        login: Uuid,
+    ) -> (watch::Receiver<bool>, tokio::task::JoinHandle<()>) {
+        let signed_out = mgr.watch_web_login(login);
+        let mut sender_signed_out = signed_out.clone();
+        let send_task = tokio::spawn(async move {
+            revoked(&mut sender_signed_out).await;
+        });
+        (signed_out, send_task)
+    }
+
+    #[tokio::test]
+    async fn test_login_released_after_last_socket_closes() {
+        let mgr = SessionManager::new();
+        let login = Uuid::new_v4();
+        let (first, first_task) = open_socket(&mgr, login);
+        let (second, second_task) = open_socket(&mgr, login);
+
+        release_login(&mgr, Some(login), first, Some(first_task)).await;
+        assert!(mgr.has_web_login(login));
+
+        release_login(&mgr, Some(login), second, Some(second_task)).await;
+        assert!(!mgr.has_web_login(login));
+    }
+}
@@ -0,0 +1,251 @@
+//! Invite codes
+//!
+//! Instances closed with `ALLOWED_EMAILS` or `ALLOWED_EMAIL_DOMAIN` can let
+//! someone in without a restart: an admin opens an invite with an expiry, a
+//! number of uses and a role, and sends its `/invite?code=cpi_...` link.
+//! Opening the link leaves the code in a cookie; the login callback then
+//! admits whoever signs in, records the redemption and applies the role.
+//! Like share links, only the SHA-256 of the code is stored.
+
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use shared::api::{InviteInfo, InviteRedemptionInfo, InviteRole};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::jwt::hash_token;
+use crate::models::{Invite, NewInviteRedemption};
+use crate::schema::{invite_redemptions, invites, users};
+
+/// Every invite code starts with this, so it is told apart from other tokens
+pub const CODE_PREFIX: &str = "cpi_";
+
+/// Outcome of signing in with an invite
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Redemption {
+    /// Used up one of the invite's uses
+    Redeemed,
+    /// This user had already redeemed it; no use is taken
+    AlreadyRedeemed,
+    /// Revoked, expired or used up since it was looked up
+    Closed,
+}
+
+/// A new random invite code.
+pub fn generate_code() -> String {
+    use rand::RngCore;
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("{}{}", CODE_PREFIX, hex::encode(bytes))
+}
+
+/// The link to send to whoever the invite is for
+pub fn invite_url(public_url: &str, code: &str) -> String {
+    format!("{}/invite?code={}", public_url.trim_end_matches('/'), code)
+}
+
+/// Whether the invite still lets people in at `now`
+pub fn is_open(invite: &Invite, now: NaiveDateTime) -> bool {
+    !invite.revoked && invite.expires_at > now && invite.use_count < invite.max_uses
+}
+
+pub fn role(invite: &Invite) -> InviteRole {
+    invite.role.parse().unwrap_or_default()
+}
+
+/// The invite a code opens, if it still lets people in.
+pub fn find_open(conn: &mut PgConnection, code: &str) -> Result<Option<Invite>, AppError> {
+    if !code.starts_with(CODE_PREFIX) {
+        return Ok(None);
+    }
+    let invite: Option<Invite> = invites::table
+        .filter(invites::token_hash.eq(hash_token(code)))
+        .select(Invite::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    Ok(invite.filter(|invite| is_open(invite, Utc::now().naive_utc())))
+}
+
+/// Whether the user ever joined through an invite, which keeps them past the
+/// email allowlist even after the invite closes
+pub fn has_redeemed_any(conn: &mut PgConnection, user_id: Uuid) -> QueryResult<bool> {
+    diesel::select(diesel::dsl::exists(
+        invite_redemptions::table.filter(invite_redemptions::user_id.eq(user_id)),
+    ))
+    .get_result(conn)
+}
+
+/// Record `user_id` signing in through `invite` and give them its role.
+/// Claims the use with a conditional update, so concurrent sign-ins cannot
+/// exceed `max_uses`; run it in the transaction that admits the user.
+pub fn redeem(conn: &mut PgConnection, invite: &Invite, user_id: Uuid) -> QueryResult<Redemption> {
+    let already: bool = diesel::select(diesel::dsl::exists(
+        invite_redemptions::table
+            .filter(invite_redemptions::invite_id.eq(invite.id))
+            .filter(invite_redemptions::user_id.eq(user_id)),
+    ))
+    .get_result(conn)?;
+    if already {
+        return Ok(Redemption::AlreadyRedeemed);
+    }
+
+    let claimed = diesel::update(
+        invites::table
+            .find(invite.id)
+            .filter(invites::revoked.eq(false))
+            .filter(invites::expires_at.gt(Utc::now().naive_utc()))
+            .filter(invites::use_count.lt(invites::max_uses)),
+    )
+    .set(invites::use_count.eq(invites::use_count + 1))
+    .execute(conn)?;
+    if claimed == 0 {
+        return Ok(Redemption::Closed);
+    }
+
+    diesel::insert_into(invite_redemptions::table)
+        .values(&NewInviteRedemption {
+            invite_id: invite.id,
+            user_id,
+        })
+        .execute(conn)?;
+    if role(invite) == InviteRole::Admin {
+        diesel::update(users::table.find(user_id))
+            .set((
+                users::is_admin.eq(true),
+                users::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn)?;
+    }
+    Ok(Redemption::Redeemed)
+}
+
+/// Every invite with its creator and redemptions, newest first
+pub fn list(conn: &mut PgConnection) -> QueryResult<Vec<InviteInfo>> {
+    let rows: Vec<(Invite, Option<String>)> = invites::table
+        .left_join(users::table)
+        .order(invites::created_at.desc())
+        .select((Invite::as_select(), users::email.nullable()))
+        .load(conn)?;
+
+    let ids: Vec<Uuid> = rows.iter().map(|(invite, _)| invite.id).collect();
+    let redeemed: Vec<(Uuid, Uuid, String, Option<String>, NaiveDateTime)> =
+        invite_redemptions::table
+            .inner_join(users::table)
+            .filter(invite_redemptions::invite_id.eq_any(&ids))
+            .order(invite_redemptions::redeemed_at.asc())
+            .select((
+                invite_redemptions::invite_id,
+                users::id,
+                users::email,
+                users::name,
+                invite_redemptions::redeemed_at,
+            ))
+            .load(conn)?;
+    let mut redemptions: HashMap<Uuid, Vec<InviteRedemptionInfo>> = HashMap::new();
+    for (invite_id, user_id, email, name, redeemed_at) in redeemed {
+        redemptions
+            .entry(invite_id)
+            .or_default()
+            .push(InviteRedemptionInfo {
+                user_id,
+                email,
+                name,
+                redeemed_at: redeemed_at.and_utc().to_rfc3339(),
+            });
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(|(invite, created_by_email)| {
+            let redemptions = redemptions.remove(&invite.id).unwrap_or_default();
+            info(invite, created_by_email, redemptions)
+        })
+        .collect())
+}
+
+pub fn info(
+    invite: Invite,
+    created_by_email: Option<String>,
+    redemptions: Vec<InviteRedemptionInfo>,
+) -> InviteInfo {
+    InviteInfo {
+        id: invite.id,
+        role: role(&invite),
+        label: invite.label,
+        max_uses: invite.max_uses,
+        use_count: invite.use_count,
+        created_by_email,
+        created_at: invite.created_at.and_utc().to_rfc3339(),
+        expires_at: invite.expires_at.and_utc().to_rfc3339(),
+        revoked: invite.revoked,
+        redemptions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn invite(now: NaiveDateTime) -> Invite {
+        Invite {
+            id: Uuid::new_v4(),
+            label: None,
+            role: "user".to_string(),
+            max_uses: 2,
+            use_count: 0,
+            created_at: now,
+            expires_at: now + Duration::days(1),
+            revoked: false,
+        }
+    }
+
+    #[test]
+    fn test_generate_code() {
+        let code = generate_code();
+        assert!(code.starts_with(CODE_PREFIX));
+        assert_eq!(code.len(), CODE_PREFIX.len() + 64);
+        assert_ne!(code, generate_code());
+    }
+
+    #[test]
+    fn test_invite_url() {
+        assert_eq!(
+            invite_url("https://portal.example.com/", "cpi_abc"),
+            "https://portal.example.com/invite?code=cpi_abc"
+        );
+    }
+
+    #[test]
+    fn test_is_open() {
+        let now = Utc::now().naive_utc();
+        assert!(is_open(&invite(now), now));
+
+        let used_up = Invite {
+            use_count: 2,
+            ..invite(now)
+        };
+        assert!(!is_open(&used_up, now));
+
+        let revoked = Invite {
+            revoked: true,
+            ..invite(now)
+        };
+        assert!(!is_open(&revoked, now));
+
+        assert!(!is_open(&invite(now), now + Duration::days(2)));
+    }
+
+    #[test]
+    fn test_role() {
+        let now = Utc::now().naive_utc();
+        assert_eq!(role(&invite(now)), InviteRole::User);
+        let admin = Invite {
+            role: "admin".to_string(),
+            ..invite(now)
+        };
+        assert_eq!(role(&admin), InviteRole::Admin);
+    }
+}
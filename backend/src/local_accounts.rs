@@ -0,0 +1,392 @@
+//! Local username/password accounts
+//!
+//! For deployments that cannot reach an identity provider. An admin creates
+//! the account and hands its owner a one-time setup link; the owner picks a
+//! password there. Resetting a password clears it and issues a new link.
+//! Passwords are argon2-hashed with the share link helpers, and an account
+//! may add a TOTP second factor (RFC 6238: HMAC-SHA1, six digits, 30 s steps).
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use data_encoding::BASE32_NOPAD;
+use diesel::prelude::*;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::jwt::hash_token;
+use crate::models::{LocalAccount, NewLocalAccount, NewUser, User};
+use crate::schema::{local_accounts, users};
+
+pub use crate::share_links::{hash_password, verify_password, MAX_PASSWORD_LEN};
+
+/// Provider ID local users carry in `users.auth_provider`
+pub const LOCAL_PROVIDER_ID: &str = "local";
+/// Every setup token starts with this, so it is told apart from other tokens
+pub const SETUP_TOKEN_PREFIX: &str = "cpa_";
+/// How long a setup link stays valid
+pub const SETUP_LINK_DAYS: i64 = 7;
+/// Shortest password accepted
+pub const MIN_PASSWORD_LEN: usize = 12;
+/// Longest username accepted (matches the column)
+pub const MAX_USERNAME_LEN: usize = 64;
+
+/// Seconds per TOTP step
+const TOTP_STEP_SECS: u64 = 30;
+/// Digits in a TOTP code
+const TOTP_DIGITS: u32 = 6;
+/// Steps either side of now a code may come from, to allow for clock drift
+const TOTP_WINDOW: u64 = 1;
+/// Bytes of a generated TOTP secret (160 bits, as RFC 4226 recommends)
+const TOTP_SECRET_BYTES: usize = 20;
+
+/// Usernames are lowercased; returns the stored form or why it is invalid.
+pub fn normalize_username(username: &str) -> Result<String, &'static str> {
+    let username = username.trim().to_lowercase();
+    if username.len() < 3 || username.len() > MAX_USERNAME_LEN {
+        return Err("Username must be 3 to 64 characters");
+    }
+    if !username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+    {
+        return Err("Username may only contain letters, digits, '.', '_' and '-'");
+    }
+    Ok(username)
+}
+
+pub fn validate_password(password: &str) -> Result<(), &'static str> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err("Password must be at least 12 characters");
+    }
+    if password.len() > MAX_PASSWORD_LEN {
+        return Err("Password is too long");
+    }
+    Ok(())
+}
+
+/// A new setup token and its expiry. Only the token's hash is stored.
+pub fn generate_setup_token(now: NaiveDateTime) -> (String, NaiveDateTime) {
+    use rand::RngCore;
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    (
+        format!("{}{}", SETUP_TOKEN_PREFIX, hex::encode(bytes)),
+        now + Duration::days(SETUP_LINK_DAYS),
+    )
+}
+
+/// Where the owner of `token` sets their password
+pub fn setup_url(public_url: &str, token: &str) -> String {
+    format!(
+        "{}/setup-account?token={}",
+        public_url.trim_end_matches('/'),
+        token
+    )
+}
+
+pub fn find_by_username(
+    conn: &mut PgConnection,
+    username: &str,
+) -> Result<Option<LocalAccount>, AppError> {
+    let Ok(username) = normalize_username(username) else {
+        return Ok(None);
+    };
+    local_accounts::table
+        .filter(local_accounts::username.eq(username))
+        .select(LocalAccount::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| AppError::DbQuery(e.to_string()))
+}
+
+pub fn find_by_user(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+) -> Result<Option<LocalAccount>, AppError> {
+    local_accounts::table
+        .find(user_id)
+        .select(LocalAccount::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| AppError::DbQuery(e.to_string()))
+}
+
+/// The account an unexpired setup token belongs to
+pub fn find_by_setup_token(
+    conn: &mut PgConnection,
+    token: &str,
+) -> Result<Option<LocalAccount>, AppError> {
+    if !token.starts_with(SETUP_TOKEN_PREFIX) {
+        return Ok(None);
+    }
+    local_accounts::table
+        .filter(local_accounts::setup_token_hash.eq(hash_token(token)))
+        .filter(local_accounts::setup_expires_at.gt(Utc::now().naive_utc()))
+        .select(LocalAccount::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| AppError::DbQuery(e.to_string()))
+}
+
+/// Create a user signing in with `username` (already normalized), with no
+/// password yet. Returns the user and a setup token with its expiry.
+pub fn create_account(
+    conn: &mut PgConnection,
+    username: &str,
+    email: &str,
+    name: Option<String>,
+    is_admin: bool,
+) -> QueryResult<(User, String, NaiveDateTime)> {
+    let (token, expires_at) = generate_setup_token(Utc::now().naive_utc());
+    conn.transaction(|conn| {
+        let user: User = diesel::insert_into(users::table)
+            .values(&NewUser {
+                auth_provider: LOCAL_PROVIDER_ID.to_string(),
+                auth_subject: username.to_string(),
+                email: email.to_string(),
+                name,
+                avatar_url: None,
+            })
+            .get_result(conn)?;
+        let user = if is_admin {
+            diesel::update(users::table.find(user.id))
+                .set(users::is_admin.eq(true))
+                .get_result(conn)?
+        } else {
+            user
+        };
+        diesel::insert_into(local_accounts::table)
+            .values(&NewLocalAccount {
+                user_id: user.id,
+                username: username.to_string(),
+                setup_token_hash: Some(hash_token(&token)),
+                setup_expires_at: Some(expires_at),
+            })
+            .execute(conn)?;
+        Ok((user, token, expires_at))
+    })
+}
+
+/// Clear the account's password (and second factor, with `reset_totp`) and
+/// issue a new setup token, replacing any earlier one
+pub fn reset_password(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    reset_totp: bool,
+) -> QueryResult<(String, NaiveDateTime)> {
+    let now = Utc::now().naive_utc();
+    let (token, expires_at) = generate_setup_token(now);
+    diesel::update(local_accounts::table.find(user_id))
+        .set((
+            local_accounts::password_hash.eq(None::<String>),
+            local_accounts::setup_token_hash.eq(hash_token(&token)),
+            local_accounts::setup_expires_at.eq(expires_at),
+            local_accounts::updated_at.eq(now),
+        ))
+        .execute(conn)?;
+    if reset_totp {
+        disable_totp(conn, user_id)?;
+    }
+    Ok((token, expires_at))
+}
+
+/// Set the password chosen through a setup link or the account page; any
+/// outstanding setup link stops working
+pub fn set_password(conn: &mut PgConnection, user_id: Uuid, hash: &str) -> QueryResult<usize> {
+    let now = Utc::now().naive_utc();
+    diesel::update(local_accounts::table.find(user_id))
+        .set((
+            local_accounts::password_hash.eq(hash),
+            local_accounts::setup_token_hash.eq(None::<String>),
+            local_accounts::setup_expires_at.eq(None::<NaiveDateTime>),
+            local_accounts::password_changed_at.eq(now),
+            local_accounts::updated_at.eq(now),
+        ))
+        .execute(conn)
+}
+
+pub fn disable_totp(conn: &mut PgConnection, user_id: Uuid) -> QueryResult<usize> {
+    diesel::update(local_accounts::table.find(user_id))
+        .set((
+            local_accounts::totp_enabled.eq(false),
+            local_accounts::totp_secret.eq(None::<String>),
+            local_accounts::totp_last_step.eq(None::<i64>),
+            local_accounts::updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(conn)
+}
+
+/// A new random TOTP secret, base32 as authenticator apps expect it
+pub fn generate_totp_secret() -> String {
+    use rand::RngCore;
+    let mut bytes = [0u8; TOTP_SECRET_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    BASE32_NOPAD.encode(&bytes)
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect()
+}
+
+/// `otpauth://` URI an authenticator app can import (usually as a QR code)
+pub fn totp_uri(issuer: &str, username: &str, secret: &str) -> String {
+    let issuer = percent_encode(issuer);
+    format!(
+        "otpauth://totp/{issuer}:{}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_STEP_SECS}",
+        percent_encode(username)
+    )
+}
+
+/// The code for `step` (RFC 4226 HOTP with the step as counter)
+fn totp_code(key: &[u8], step: u64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    binary % 10u32.pow(TOTP_DIGITS)
+}
+
+/// The step `code` matches at `unix_time`, if any. Steps at or before
+/// `last_step` are refused so a code cannot be replayed.
+pub fn verify_totp(
+    secret: &str,
+    code: &str,
+    unix_time: u64,
+    last_step: Option<i64>,
+) -> Option<i64> {
+    let code = code.trim().replace(' ', "");
+    if code.len() != TOTP_DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let code: u32 = code.parse().ok()?;
+    let key = BASE32_NOPAD
+        .decode(secret.trim_end_matches('=').as_bytes())
+        .ok()?;
+
+    let now = unix_time / TOTP_STEP_SECS;
+    (now.saturating_sub(TOTP_WINDOW)..=now + TOTP_WINDOW)
+        .filter(|step| last_step.is_none_or(|last| *step as i64 > last))
+        .find(|step| totp_code(&key, *step) == code)
+        .map(|step| step as i64)
+}
+
+/// Check `code` against the account's secret and record the step it used
+pub fn check_totp(
+    conn: &mut PgConnection,
+    account: &LocalAccount,
+    secret: &str,
+    code: &str,
+) -> Result<bool, AppError> {
+    let unix_time = Utc::now().timestamp().max(0) as u64;
+    let Some(step) = verify_totp(secret, code, unix_time, account.totp_last_step) else {
+        return Ok(false);
+    };
+    diesel::update(local_accounts::table.find(account.user_id))
+        .set(local_accounts::totp_last_step.eq(step))
+        .execute(conn)
+        .map_err(|e| AppError::DbQuery(e.to_string()))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The RFC 6238 SHA-1 test secret, "12345678901234567890"
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn test_totp_rfc_vectors() {
+        // RFC 6238 appendix B, truncated to six digits
+        for (time, code) in [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1111111111, "050471"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+        ] {
+            let step = verify_totp(RFC_SECRET, code, time, None);
+            assert_eq!(step, Some((time / 30) as i64), "code at {}", time);
+        }
+    }
+
+    #[test]
+    fn test_totp_window_and_replay() {
+        // 287082 is the code for step 1 (t=30..59)
+        assert_eq!(verify_totp(RFC_SECRET, "287082", 89, None), Some(1));
+        assert_eq!(verify_totp(RFC_SECRET, "287082", 0, None), Some(1));
+        assert_eq!(verify_totp(RFC_SECRET, "287082", 120, None), None);
+        // A step already used is refused
+        assert_eq!(verify_totp(RFC_SECRET, "287082", 59, Some(1)), None);
+        assert_eq!(verify_totp(RFC_SECRET, "287 082", 59, Some(0)), Some(1));
+        assert_eq!(verify_totp(RFC_SECRET, "28708", 59, None), None);
+        assert_eq!(verify_totp(RFC_SECRET, "abcdef", 59, None), None);
+        assert_eq!(verify_totp("not base32!", "287082", 59, None), None);
+    }
+
+    #[test]
+    fn test_generated_secret_round_trips() {
+        let secret = generate_totp_secret();
+        assert_eq!(secret.len(), 32);
+        let key = BASE32_NOPAD.decode(secret.as_bytes()).unwrap();
+        let code = format!("{:06}", totp_code(&key, 1000));
+        assert_eq!(verify_totp(&secret, &code, 30_000, None), Some(1000));
+    }
+
+    #[test]
+    fn test_normalize_username() {
+        assert_eq!(normalize_username(" Alice.B ").as_deref(), Ok("alice.b"));
+        assert_eq!(
+            normalize_username("ops_team-2").as_deref(),
+            Ok("ops_team-2")
+        );
+        assert!(normalize_username("ab").is_err());
+        assert!(normalize_username("alice@example.com").is_err());
+        assert!(normalize_username("al ice").is_err());
+        assert!(normalize_username(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_validate_password() {
+        assert!(validate_password("correct horse").is_ok());
+        assert!(validate_password("short").is_err());
+        assert!(validate_password(&"x".repeat(MAX_PASSWORD_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_setup_links() {
+        let now = Utc::now().naive_utc();
+        let (token, expires_at) = generate_setup_token(now);
+        assert!(token.starts_with(SETUP_TOKEN_PREFIX));
+        assert_eq!(token.len(), SETUP_TOKEN_PREFIX.len() + 64);
+        assert_eq!(expires_at - now, Duration::days(7));
+        assert_eq!(
+            setup_url("https://portal.example.com/", &token),
+            format!("https://portal.example.com/setup-account?token={}", token)
+        );
+    }
+
+    #[test]
+    fn test_totp_uri() {
+        assert_eq!(
+            totp_uri("Agent Portal", "alice", "ABC"),
+            "otpauth://totp/Agent%20Portal:alice?secret=ABC&issuer=Agent%20Portal&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+}
@@ -1,11 +1,32 @@
+mod analytics;
+mod api_keys;
+mod audit;
 mod auth;
+mod budgets;
+mod chat_bridge;
+mod client_ip;
 mod db;
+mod email;
 mod errors;
+mod export;
 mod handlers;
+mod html;
+mod invites;
 mod jwt;
+mod local_accounts;
+mod metrics;
 mod models;
+mod oidc;
+mod openapi;
+mod permission_policy;
 mod schema;
+mod settings;
+mod share_links;
 mod speech;
+mod teams;
+mod throttle;
+mod web_logins;
+mod webhooks;
 
 use crate::db::DbPool;
 use crate::handlers::device_flow::DeviceFlowStore;
@@ -14,15 +35,15 @@ use axum::{
     Router,
 };
 use clap::Parser;
-use oauth2::{basic::BasicClient, AuthUrl, ClientId, ClientSecret, RedirectUrl, TokenUrl};
 use shared::WsEndpoint;
 use std::{env, net::SocketAddr, sync::Arc};
 use tower_cookies::{CookieManagerLayer, Key};
 use tower_governor::governor::GovernorConfigBuilder;
-use tower_governor::key_extractor::SmartIpKeyExtractor;
 use tower_governor::GovernorLayer;
 use tower_http::cors::{Any, CorsLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use utoipa::OpenApi;
+use utoipa_scalar::{Scalar, Servable};
 
 use handlers::websocket::SessionManager;
 
@@ -33,6 +54,15 @@ struct Args {
     /// Enable development mode (bypasses OAuth, creates test user)
     #[arg(long)]
     dev_mode: bool,
+
+    /// Create a local admin account with this username, print its setup
+    /// link and exit (needs LOCAL_ACCOUNTS=true)
+    #[arg(long, value_name = "USERNAME", requires = "admin_email")]
+    create_local_admin: Option<String>,
+
+    /// Email of the account made by --create-local-admin
+    #[arg(long, value_name = "EMAIL")]
+    admin_email: Option<String>,
 }
 
 #[derive(Clone)]
@@ -40,26 +70,33 @@ pub struct AppState {
     pub dev_mode: bool,
     pub db_pool: DbPool,
     pub session_manager: SessionManager,
-    pub oauth_basic_client: Option<BasicClient>,
+    /// Login providers; empty in dev mode
+    pub oidc: oidc::OidcProviders,
+    /// Users may sign in with local usernames and passwords (`LOCAL_ACCOUNTS`)
+    pub local_accounts_enabled: bool,
     pub device_flow_store: Option<DeviceFlowStore>,
     pub public_url: String,
     pub cookie_key: Key,
     pub jwt_secret: String,
     pub speech_credentials_path: Option<String>,
-    pub app_title: String,
-    pub splash_text: Option<String>,
-    /// Allowed email domain (e.g., "company.com")
-    pub allowed_email_domain: Option<String>,
-    /// Allowed email addresses (comma-separated in env var)
-    pub allowed_emails: Option<Vec<String>>,
-    /// Maximum messages to keep per session (default: 100)
-    pub message_retention_count: i64,
-    /// Days to retain messages before deletion (default: 30, 0 = disabled)
-    pub message_retention_days: u32,
-    /// Days to keep sessions before auto-deletion (default: 14, 0 = disabled)
-    pub session_max_age_days: u32,
-    /// Maximum image size in MB that proxies should inline (default: 10)
-    pub max_image_mb: u32,
+    /// Title, allowlist, retention and limits; admins can change them live
+    pub settings: settings::SettingsStore,
+    /// Queues and delivers outbound webhook events
+    pub webhooks: webhooks::WebhookDispatcher,
+    /// Sends notification mail through the configured SMTP relay
+    pub mailer: email::Mailer,
+    /// Posts permission requests and turn results to chat channels
+    pub chat_bridges: chat_bridge::ChatBridgePoster,
+    /// Bearer token scrapers must send to read `/metrics` (open when unset)
+    pub metrics_token: Option<String>,
+    /// Failed local sign-ins per username and per IP
+    pub login_throttle: throttle::GuessThrottle,
+    /// Wrong share link passwords per link and per IP
+    pub share_link_throttle: throttle::GuessThrottle,
+    /// Reverse proxies whose forwarded client addresses are believed
+    pub trusted_proxies: client_ip::TrustedProxies,
+    /// Internal webhook and chat bridge receivers admins have allowed
+    pub webhook_destinations: webhooks::AllowedDestinations,
 }
 
 #[tokio::main]
@@ -67,22 +104,28 @@ async fn main() -> anyhow::Result<()> {
     // Parse CLI arguments
     let args = Args::parse();
 
-    // Initialize tracing with info level by default
+    // Load environment variables (before tracing so RUST_LOG and the
+    // OTEL_* settings can come from .env)
+    dotenvy::dotenv().ok();
+
+    // Initialize tracing with info level by default, exporting spans when an
+    // OTLP endpoint is configured
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "info,tower_http=info".into()),
         )
         .with(tracing_subscriber::fmt::layer())
+        .with(portal_telemetry::layer(
+            "portal-backend",
+            env!("CARGO_PKG_VERSION"),
+        ))
         .init();
 
     if args.dev_mode {
         tracing::warn!("🚧 DEV MODE ENABLED - OAuth is bypassed, test user will be used");
     }
 
-    // Load environment variables
-    dotenvy::dotenv().ok();
-
     // Create database pool
     let pool = db::create_pool()?;
 
@@ -108,27 +151,6 @@ async fn main() -> anyhow::Result<()> {
     // Create device flow store
     let device_flow_store = handlers::device_flow::DeviceFlowStore::default();
 
-    // Create OAuth client (skip in dev mode)
-    let oauth_basic_client = if !args.dev_mode {
-        let client_id =
-            ClientId::new(env::var("GOOGLE_CLIENT_ID").expect("GOOGLE_CLIENT_ID must be set"));
-        let client_secret = ClientSecret::new(
-            env::var("GOOGLE_CLIENT_SECRET").expect("GOOGLE_CLIENT_SECRET must be set"),
-        );
-        let auth_url = AuthUrl::new("https://accounts.google.com/o/oauth2/v2/auth".to_string())?;
-        let token_url = TokenUrl::new("https://oauth2.googleapis.com/token".to_string())?;
-        let redirect_uri = RedirectUrl::new(
-            env::var("GOOGLE_REDIRECT_URI").expect("GOOGLE_REDIRECT_URI must be set"),
-        )?;
-
-        Some(
-            BasicClient::new(client_id, Some(client_secret), auth_url, Some(token_url))
-                .set_redirect_uri(redirect_uri),
-        )
-    } else {
-        None
-    };
-
     // Create test user in dev mode
     if args.dev_mode {
         use diesel::prelude::*;
@@ -143,7 +165,8 @@ async fn main() -> anyhow::Result<()> {
 
         if test_user.is_none() {
             let new_user = NewUser {
-                google_id: "dev_mode_test_user".to_string(),
+                auth_provider: "dev".to_string(),
+                auth_subject: "dev_mode_test_user".to_string(),
                 email: "testing@testing.local".to_string(),
                 name: Some("Test User".to_string()),
                 avatar_url: None,
@@ -238,6 +261,53 @@ async fn main() -> anyhow::Result<()> {
         format!("http://localhost:{}", port)
     });
 
+    // Local username/password accounts, for instances without an identity
+    // provider
+    let local_accounts_enabled = env::var("LOCAL_ACCOUNTS")
+        .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+        .unwrap_or(false);
+
+    if let Some(username) = &args.create_local_admin {
+        if !local_accounts_enabled {
+            anyhow::bail!("--create-local-admin needs LOCAL_ACCOUNTS=true");
+        }
+        let username = local_accounts::normalize_username(username).map_err(anyhow::Error::msg)?;
+        let admin_email = args.admin_email.clone().unwrap_or_default().to_lowercase();
+        let mut conn = pool.get()?;
+        let (_, token, expires_at) =
+            local_accounts::create_account(&mut conn, &username, &admin_email, None, true)?;
+        println!(
+            "Created local admin {}. Set its password before {} at:\n{}",
+            username,
+            expires_at.and_utc().to_rfc3339(),
+            local_accounts::setup_url(&public_url, &token)
+        );
+        return Ok(());
+    }
+
+    // Login providers (skip in dev mode)
+    let oidc = if !args.dev_mode {
+        let configs = oidc::configs_from_env(&public_url)?;
+        if configs.is_empty() && !local_accounts_enabled {
+            anyhow::bail!(
+                "No login providers configured: set GOOGLE_CLIENT_ID, OIDC_PROVIDERS or LOCAL_ACCOUNTS=true (or run with --dev-mode)"
+            );
+        }
+        for config in &configs {
+            tracing::info!(
+                "Login provider {} ({}) at {}",
+                config.id,
+                config.display_name,
+                config.issuer
+            );
+        }
+        let providers = oidc::OidcProviders::new(configs);
+        providers.warm_up();
+        providers
+    } else {
+        oidc::OidcProviders::default()
+    };
+
     // Create cookie signing key from SESSION_SECRET or generate random for dev
     let session_secret = env::var("SESSION_SECRET").ok();
     let cookie_key = match &session_secret {
@@ -279,83 +349,93 @@ async fn main() -> anyhow::Result<()> {
         }
     });
 
-    // App title (customizable via environment variable)
-    // In dev mode, override with a warning to make it obvious
-    let app_title = if args.dev_mode {
-        "⚠️ INSECURE DEV MODE ⚠️".to_string()
-    } else {
-        env::var("APP_TITLE").unwrap_or_else(|_| "Agent Portal".to_string())
-    };
-
-    let splash_text = env::var("SPLASH_TEXT").ok();
-
-    // Email access control (optional)
-    let allowed_email_domain = env::var("ALLOWED_EMAIL_DOMAIN").ok();
-    let allowed_emails = env::var("ALLOWED_EMAILS").ok().map(|s| {
-        s.split(',')
-            .map(|e| e.trim().to_lowercase())
-            .filter(|e| !e.is_empty())
-            .collect::<Vec<_>>()
-    });
-
-    if allowed_email_domain.is_some() || allowed_emails.is_some() {
+    // Instance settings: the environment gives the defaults, admins'
+    // changes stored in the database override them
+    let settings = settings::SettingsStore::new(settings::defaults_from_env(args.dev_mode));
+    {
+        let mut conn = pool.get()?;
+        settings.load(&mut conn)?;
+    }
+    let current = settings.get();
+    if current.allowed_email_domain.is_some() || current.allowed_emails.is_some() {
         tracing::info!(
             "Email access control enabled: domain={:?}, specific_emails={}",
-            allowed_email_domain,
-            allowed_emails.as_ref().map(|e| e.len()).unwrap_or(0)
+            current.allowed_email_domain,
+            current
+                .allowed_emails
+                .as_ref()
+                .map(|e| e.len())
+                .unwrap_or(0)
         );
     }
-
-    // Message retention settings
-    let message_retention_count: i64 = env::var("MESSAGE_RETENTION_COUNT")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(100);
-    let message_retention_days: u32 = env::var("MESSAGE_RETENTION_DAYS")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(30);
-
-    let session_max_age_days: u32 = env::var("SESSION_MAX_AGE_DAYS")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(14);
-
-    let max_image_mb: u32 = env::var("PORTAL_MAX_IMAGE_MB")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(10);
-
     tracing::info!(
         "Message retention: max {} messages/session, {} days",
-        message_retention_count,
-        message_retention_days
+        current.message_retention_count,
+        current.message_retention_days
     );
     tracing::info!(
         "Session max age: {} days (0 = disabled)",
-        session_max_age_days
+        current.session_max_age_days
     );
-    tracing::info!("Max image size: {} MB", max_image_mb);
+    tracing::info!("Max image size: {} MB", current.max_image_mb);
+
+    // Optional bearer token protecting the Prometheus endpoint
+    let metrics_token = env::var("METRICS_TOKEN").ok().filter(|t| !t.is_empty());
+    if metrics_token.is_none() {
+        tracing::info!("METRICS_TOKEN not set, /metrics is open to anyone who can reach it");
+    }
+
+    // Optional SMTP relay for email notifications
+    let smtp = match email::SmtpConfig::from_env()? {
+        Some(config) => {
+            tracing::info!(
+                "Email notifications via {}:{} ({:?})",
+                config.host,
+                config.port,
+                config.tls
+            );
+            Some(email::SmtpMailer::new(config)?)
+        }
+        None => {
+            tracing::info!("SMTP_HOST not set, email notifications disabled");
+            None
+        }
+    };
+    let mailer = email::Mailer::new(smtp, pool.clone(), public_url.clone());
+
+    // Reverse proxies allowed to report the client address
+    let trusted_proxies = client_ip::TrustedProxies::from_env()?;
+    if trusted_proxies.is_empty() {
+        tracing::info!("TRUSTED_PROXIES not set, forwarded client addresses are ignored");
+    }
+
+    // Internal receivers webhooks and chat bridges may post to
+    let webhook_destinations = webhooks::AllowedDestinations::from_env()?;
 
     // Create app state
     let app_state = Arc::new(AppState {
         dev_mode: args.dev_mode,
         db_pool: pool.clone(),
         session_manager: session_manager.clone(),
-        oauth_basic_client,
+        oidc,
+        local_accounts_enabled,
         device_flow_store: Some(device_flow_store.clone()),
         public_url: public_url.clone(),
         cookie_key,
         jwt_secret,
         speech_credentials_path,
-        app_title,
-        splash_text,
-        allowed_email_domain,
-        allowed_emails,
-        message_retention_count,
-        message_retention_days,
-        session_max_age_days,
-        max_image_mb,
+        settings,
+        webhooks: webhooks::WebhookDispatcher::new(pool.clone(), webhook_destinations.clone()),
+        mailer,
+        chat_bridges: chat_bridge::ChatBridgePoster::new(
+            pool.clone(),
+            webhook_destinations.clone(),
+        ),
+        metrics_token,
+        login_throttle: throttle::GuessThrottle::default(),
+        share_link_throttle: throttle::GuessThrottle::default(),
+        trusted_proxies: trusted_proxies.clone(),
+        webhook_destinations,
     });
 
     // Setup CORS
@@ -364,24 +444,24 @@ async fn main() -> anyhow::Result<()> {
         .allow_methods(Any)
         .allow_headers(Any);
 
-    // Rate limiting configs (per IP via SmartIpKeyExtractor for proxy support)
-    let auth_rate_limit = Arc::new(
-        GovernorConfigBuilder::default()
-            .per_second(6)
-            .burst_size(10)
-            .key_extractor(SmartIpKeyExtractor)
-            .finish()
-            .unwrap(),
-    );
-
-    let download_rate_limit = Arc::new(
-        GovernorConfigBuilder::default()
-            .per_second(6)
-            .burst_size(10)
-            .key_extractor(SmartIpKeyExtractor)
-            .finish()
-            .unwrap(),
-    );
+    // Rate limiting configs (per client IP, as reported by trusted proxies)
+    let client_ip_key = client_ip::ClientIpKeyExtractor(trusted_proxies);
+    // Each group of routes gets its own limiter: 6 requests a second per
+    // client, in bursts of up to 10
+    let per_client_limit = || {
+        Arc::new(
+            GovernorConfigBuilder::default()
+                .per_second(6)
+                .burst_size(10)
+                .key_extractor(client_ip_key.clone())
+                .finish()
+                .unwrap(),
+        )
+    };
+    let auth_rate_limit = per_client_limit();
+    let local_auth_rate_limit = per_client_limit();
+    let download_rate_limit = per_client_limit();
+    let shared_rate_limit = per_client_limit();
 
     // Rate-limited device flow auth routes
     let auth_device_routes = Router::new()
@@ -396,6 +476,16 @@ async fn main() -> anyhow::Result<()> {
         .layer(GovernorLayer::new(auth_rate_limit))
         .with_state(app_state.clone());
 
+    // Rate-limited local sign-in routes, to slow password guessing
+    let local_auth_routes = Router::new()
+        .route("/api/auth/local/login", post(handlers::local_auth::login))
+        .route(
+            "/api/auth/local/setup",
+            get(handlers::local_auth::setup_info).post(handlers::local_auth::complete_setup),
+        )
+        .layer(GovernorLayer::new(local_auth_rate_limit))
+        .with_state(app_state.clone());
+
     // Rate-limited download routes
     let download_routes = Router::new()
         .route(
@@ -409,12 +499,29 @@ async fn main() -> anyhow::Result<()> {
         .layer(GovernorLayer::new(download_rate_limit))
         .with_state(app_state.clone());
 
+    // Rate-limited public share link routes, to slow password guessing
+    let shared_routes = Router::new()
+        .route(
+            "/api/shared/{token}",
+            get(handlers::share_links::get_shared_session),
+        )
+        .route(
+            shared::SharedEndpoint::PATH,
+            get(handlers::websocket::handle_shared_websocket),
+        )
+        .layer(GovernorLayer::new(shared_rate_limit))
+        .with_state(app_state.clone());
+
     // Build our application with routes
     let app = Router::new()
         // Health check endpoint
-        .route("/api/health", get(|| async { "OK" }))
+        .route("/api/health", get(handlers::config::health))
+        // Prometheus scrape endpoint (optionally behind METRICS_TOKEN)
+        .route("/metrics", get(handlers::metrics::metrics_handler))
         // App configuration (public, no auth required)
         .route("/api/config", get(handlers::config::get_config))
+        // OpenAPI document (public); the browsable UI is merged below
+        .route("/api/openapi.json", get(openapi::openapi_json))
         // Session API routes
         .route("/api/sessions", get(handlers::sessions::list_sessions))
         .route("/api/sessions/{id}", get(handlers::sessions::get_session))
@@ -426,6 +533,14 @@ async fn main() -> anyhow::Result<()> {
             "/api/sessions/{id}/stop",
             post(handlers::sessions::stop_session),
         )
+        .route(
+            "/api/sessions/{id}/input",
+            post(handlers::sessions::send_input),
+        )
+        .route(
+            "/api/sessions/{id}/fork",
+            post(handlers::sessions::fork_session),
+        )
         // Session member management routes
         .route(
             "/api/sessions/{id}/members",
@@ -437,10 +552,47 @@ async fn main() -> anyhow::Result<()> {
             axum::routing::delete(handlers::sessions::remove_session_member)
                 .patch(handlers::sessions::update_session_member_role),
         )
+        // Teams a session is shared with
+        .route(
+            "/api/sessions/{id}/teams",
+            get(handlers::teams::list_session_teams).post(handlers::teams::share_session_with_team),
+        )
+        .route(
+            "/api/sessions/{id}/teams/{team_id}",
+            axum::routing::delete(handlers::teams::unshare_session_with_team),
+        )
+        // Public share link management (owner only)
+        .route(
+            "/api/sessions/{id}/share-links",
+            get(handlers::share_links::list_share_links)
+                .post(handlers::share_links::create_share_link),
+        )
+        .route(
+            "/api/sessions/{id}/share-links/{link_id}",
+            axum::routing::delete(handlers::share_links::revoke_share_link),
+        )
+        .route(
+            "/api/sessions/{id}/share-links/{link_id}/views",
+            get(handlers::share_links::list_share_link_views),
+        )
         .route(
             "/api/sessions/{id}/messages",
             get(handlers::messages::list_messages).post(handlers::messages::create_message),
         )
+        .route(
+            "/api/sessions/{id}/export",
+            get(handlers::messages::export_messages),
+        )
+        .route(
+            "/api/sessions/{id}/permission-decisions",
+            get(handlers::permission_decisions::list_decisions_handler),
+        )
+        .route(
+            "/api/sessions/{id}/permission-timeout",
+            get(handlers::permission_timeouts::get_timeout_handler)
+                .put(handlers::permission_timeouts::update_timeout_handler),
+        )
+        .route("/api/search", get(handlers::search::search_handler))
         // Proxy token management endpoints
         .route(
             "/api/proxy-tokens",
@@ -455,6 +607,58 @@ async fn main() -> anyhow::Result<()> {
             "/api/proxy-tokens/{id}/renew",
             post(handlers::proxy_tokens::renew_token_handler),
         )
+        // Personal API key management endpoints
+        .route(
+            "/api/api-keys",
+            get(handlers::api_keys::list_api_keys_handler)
+                .post(handlers::api_keys::create_api_key_handler),
+        )
+        .route(
+            "/api/api-keys/{id}",
+            axum::routing::delete(handlers::api_keys::revoke_api_key_handler),
+        )
+        .route(
+            "/api/logins",
+            get(handlers::web_logins::list_logins_handler),
+        )
+        .route(
+            "/api/logins/{id}",
+            axum::routing::delete(handlers::web_logins::revoke_login_handler),
+        )
+        .route(
+            "/api/logins/revoke-all",
+            post(handlers::web_logins::revoke_all_logins_handler),
+        )
+        // Daily cost and token usage
+        .route("/api/analytics", get(handlers::analytics::get_analytics))
+        // Spend budgets that apply to the caller
+        .route("/api/budgets", get(handlers::budgets::list_my_budgets))
+        // Teams, their members and shared launchers
+        .route(
+            "/api/teams",
+            get(handlers::teams::list_teams).post(handlers::teams::create_team),
+        )
+        .route(
+            "/api/teams/{id}",
+            axum::routing::delete(handlers::teams::delete_team),
+        )
+        .route(
+            "/api/teams/{id}/members",
+            get(handlers::teams::list_team_members).post(handlers::teams::add_team_member),
+        )
+        .route(
+            "/api/teams/{id}/members/{user_id}",
+            axum::routing::patch(handlers::teams::update_team_member)
+                .delete(handlers::teams::remove_team_member),
+        )
+        .route(
+            "/api/teams/{id}/launchers",
+            get(handlers::teams::list_team_launchers).post(handlers::teams::share_launcher),
+        )
+        .route(
+            "/api/teams/{id}/launchers/{share_id}",
+            axum::routing::delete(handlers::teams::unshare_launcher),
+        )
         // Scheduled task management endpoints
         .route(
             "/api/scheduled-tasks",
@@ -470,20 +674,107 @@ async fn main() -> anyhow::Result<()> {
             "/api/scheduled-tasks/{id}/runs",
             get(handlers::scheduled_tasks::list_runs_handler),
         )
+        // Permission policy rules
+        .route(
+            "/api/permission-rules",
+            get(handlers::permission_rules::list_rules_handler)
+                .post(handlers::permission_rules::create_rule_handler),
+        )
+        .route(
+            "/api/permission-rules/{id}",
+            axum::routing::patch(handlers::permission_rules::update_rule_handler)
+                .delete(handlers::permission_rules::delete_rule_handler),
+        )
+        // Outbound webhooks
+        .route(
+            "/api/webhooks",
+            get(handlers::webhooks::list_webhooks_handler)
+                .post(handlers::webhooks::create_webhook_handler),
+        )
+        .route(
+            "/api/webhooks/{id}",
+            axum::routing::patch(handlers::webhooks::update_webhook_handler)
+                .delete(handlers::webhooks::delete_webhook_handler),
+        )
+        .route(
+            "/api/webhooks/{id}/deliveries",
+            get(handlers::webhooks::list_deliveries_handler),
+        )
+        .route(
+            "/api/webhooks/{id}/test",
+            post(handlers::webhooks::test_webhook_handler),
+        )
+        // Chat bridges
+        .route(
+            "/api/chat-bridges",
+            get(handlers::chat_bridges::list_chat_bridges_handler)
+                .post(handlers::chat_bridges::create_chat_bridge_handler),
+        )
+        .route(
+            "/api/chat-bridges/{id}",
+            axum::routing::patch(handlers::chat_bridges::update_chat_bridge_handler)
+                .delete(handlers::chat_bridges::delete_chat_bridge_handler),
+        )
+        .route(
+            "/api/chat-bridges/{id}/test",
+            post(handlers::chat_bridges::test_chat_bridge_handler),
+        )
+        .route(
+            "/api/chat-bridges/{id}/inbound",
+            post(handlers::chat_bridges::inbound_handler),
+        )
         // Sound settings
         .route(
             "/api/settings/sound",
             get(handlers::sound_settings::get_sound_settings)
                 .put(handlers::sound_settings::save_sound_settings),
         )
+        // Email notification settings
+        .route(
+            "/api/settings/email",
+            get(handlers::email_settings::get_email_settings)
+                .put(handlers::email_settings::save_email_settings),
+        )
+        .route(
+            "/api/settings/email/test",
+            post(handlers::email_settings::send_test_email),
+        )
         // Auth routes (under /api/auth)
         .route("/api/auth/google", get(handlers::auth::login))
         .route("/api/auth/google/callback", get(handlers::auth::callback))
+        .route("/api/auth/oidc/{provider}", get(handlers::auth::oidc_login))
+        .route(
+            "/api/auth/oidc/{provider}/callback",
+            get(handlers::auth::oidc_callback),
+        )
         .route("/api/auth/me", get(handlers::auth::me))
         .route("/api/auth/logout", get(handlers::auth::logout))
         .route("/api/auth/dev-login", get(handlers::auth::dev_login))
         // Device-specific login endpoint (separate from regular web login)
         .route("/api/auth/device-login", get(handlers::auth::device_login))
+        // Invite links remember their code for the login callback
+        .route("/api/auth/invite", get(handlers::auth::check_invite))
+        // Local account settings (sign-in and setup are rate-limited below)
+        .route(
+            "/api/auth/local/account",
+            get(handlers::local_auth::get_account),
+        )
+        .route(
+            "/api/auth/local/password",
+            post(handlers::local_auth::change_password),
+        )
+        .route(
+            "/api/auth/local/totp/setup",
+            post(handlers::local_auth::totp_setup),
+        )
+        .route(
+            "/api/auth/local/totp/enable",
+            post(handlers::local_auth::totp_enable),
+        )
+        .route(
+            "/api/auth/local/totp/disable",
+            post(handlers::local_auth::totp_disable),
+        )
         // Non-rate-limited device flow endpoints (verify page, approve, deny are user-facing)
         .route(
             "/api/auth/device",
@@ -520,6 +811,10 @@ async fn main() -> anyhow::Result<()> {
             "/api/launchers/{launcher_id}/directories",
             get(handlers::launchers::list_directories),
         )
+        .route(
+            "/api/launchers/{launcher_id}/local-sessions",
+            get(handlers::launchers::list_local_sessions),
+        )
         .route("/api/launch", post(handlers::launchers::launch_session))
         .route(
             "/api/launchers/{launcher_id}/renew-token",
@@ -532,16 +827,63 @@ async fn main() -> anyhow::Result<()> {
             "/api/admin/users/{id}",
             axum::routing::patch(handlers::admin::update_user),
         )
+        .route(
+            "/api/admin/users/{id}/password-reset",
+            post(handlers::admin::reset_local_password),
+        )
+        .route(
+            "/api/admin/local-accounts",
+            post(handlers::admin::create_local_account),
+        )
         .route("/api/admin/sessions", get(handlers::admin::list_sessions))
         .route(
             "/api/admin/sessions/{id}",
             axum::routing::delete(handlers::admin::delete_session),
         )
+        .route(
+            "/api/admin/sessions/{id}/cost-cap",
+            axum::routing::patch(handlers::admin::set_session_cost_cap),
+        )
+        .route("/api/admin/teams", get(handlers::admin::list_teams))
+        .route(
+            "/api/admin/budgets",
+            get(handlers::admin::list_budgets).put(handlers::admin::set_budget),
+        )
+        .route(
+            "/api/admin/budgets/{id}",
+            axum::routing::delete(handlers::admin::delete_budget),
+        )
+        .route(
+            "/api/admin/invites",
+            get(handlers::admin::list_invites).post(handlers::admin::create_invite),
+        )
+        .route(
+            "/api/admin/invites/{id}",
+            axum::routing::delete(handlers::admin::revoke_invite),
+        )
+        .route(
+            "/api/admin/settings",
+            get(handlers::admin::get_settings).put(handlers::admin::update_settings),
+        )
+        .route("/api/admin/audit", get(handlers::admin::list_audit_events))
+        .route(
+            "/api/admin/audit/export",
+            get(handlers::admin::export_audit_events),
+        )
+        // Bearer API keys, checked against the scope each route needs
+        .route_layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            api_keys::authenticate,
+        ))
         // Add single unified state
         .with_state(app_state.clone())
         // Merge rate-limited route groups
         .merge(auth_device_routes)
+        .merge(local_auth_routes)
         .merge(download_routes)
+        .merge(shared_routes)
+        // Browsable API reference for /api/openapi.json
+        .merge(Scalar::with_url("/api/docs", openapi::ApiDoc::openapi()))
         // Serve embedded frontend assets with SPA fallback
         .merge(
             memory_serve::load!()
@@ -569,6 +911,61 @@ async fn main() -> anyhow::Result<()> {
         tracing::info!("Started user spend broadcast task (every 5 seconds)");
     }
 
+    // Spawn background task to roll usage records up into daily analytics
+    {
+        let app_state = app_state.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+                analytics::ROLLUP_INTERVAL_SECS,
+            ));
+            loop {
+                interval.tick().await;
+                run_usage_rollup(&app_state).await;
+            }
+        });
+        tracing::info!(
+            "Started usage rollup task (every {} seconds)",
+            analytics::ROLLUP_INTERVAL_SECS
+        );
+    }
+
+    // Spawn background task to resolve permission requests nobody answered in time
+    {
+        let app_state = app_state.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(5));
+            loop {
+                interval.tick().await;
+                handlers::websocket::expire_timed_out_permissions(
+                    &app_state.session_manager,
+                    &app_state.db_pool,
+                );
+            }
+        });
+        tracing::info!("Started permission timeout task (every 5 seconds)");
+    }
+
+    // Spawn background task to deliver queued webhook events and retry failures
+    {
+        let dispatcher = app_state.webhooks.clone();
+        tokio::spawn(dispatcher.run());
+        tracing::info!("Started webhook delivery task");
+    }
+
+    // Spawn background task for permission-wait mails and daily digests
+    if app_state.mailer.is_configured() {
+        let mailer = app_state.mailer.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                mailer.notify_waiting_permissions().await;
+                mailer.send_due_digests().await;
+            }
+        });
+        tracing::info!("Started email notification task (every 60 seconds)");
+    }
+
     // Spawn background task to purge expired device flow codes (runs every 60 seconds)
     {
         let store = device_flow_store.clone();
@@ -601,9 +998,9 @@ async fn main() -> anyhow::Result<()> {
         tracing::info!("Started message retention task (every 60 seconds)");
     }
 
-    // Spawn background task for session age cleanup (runs every hour)
-    if app_state.session_max_age_days > 0 {
-        let max_age_days = app_state.session_max_age_days;
+    // Spawn background task for session age cleanup (runs every hour; skips
+    // passes while SESSION_MAX_AGE_DAYS is 0)
+    {
         let app_state_clone = app_state.clone();
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600));
@@ -612,10 +1009,7 @@ async fn main() -> anyhow::Result<()> {
                 run_session_age_cleanup(&app_state_clone).await;
             }
         });
-        tracing::info!(
-            "Started session age cleanup task (every hour, max age {} days)",
-            max_age_days
-        );
+        tracing::info!("Started session age cleanup task (every hour)");
     }
 
     // Run the server with graceful shutdown
@@ -738,6 +1132,17 @@ async fn broadcast_user_spend_updates(app_state: &Arc<AppState>) {
     }
 }
 
+/// Sum recent usage records into `usage_daily`
+async fn run_usage_rollup(app_state: &Arc<AppState>) {
+    let Ok(mut conn) = app_state.db_pool.get() else {
+        tracing::error!("Failed to get DB connection for usage rollup");
+        return;
+    };
+    if let Err(e) = analytics::roll_up_recent(&mut conn) {
+        tracing::error!("Usage rollup failed: {}", e);
+    }
+}
+
 /// Run retention cleanup: delete old messages and truncate per-session counts
 async fn run_retention_cleanup(app_state: &Arc<AppState>) {
     use handlers::retention::{run_retention_cleanup, RetentionConfig};
@@ -749,12 +1154,14 @@ async fn run_retention_cleanup(app_state: &Arc<AppState>) {
         return;
     };
 
+    let settings = app_state.settings.get();
     let config = RetentionConfig::new(
-        app_state.message_retention_count,
-        app_state.message_retention_days,
+        settings.message_retention_count,
+        settings.message_retention_days,
     );
 
     let (age_deleted, count_deleted) = run_retention_cleanup(&mut conn, session_ids, config);
+    metrics::record_retention(age_deleted, count_deleted);
 
     if age_deleted > 0 || count_deleted > 0 {
         tracing::info!(
@@ -770,7 +1177,7 @@ async fn run_session_age_cleanup(app_state: &Arc<AppState>) {
     use diesel::prelude::*;
     use handlers::helpers::delete_session_with_data;
 
-    let max_days = app_state.session_max_age_days;
+    let max_days = app_state.settings.get().session_max_age_days;
     if max_days == 0 {
         return;
     }
@@ -0,0 +1,394 @@
+//! Prometheus Metrics
+//!
+//! Process-wide counters for WebSocket traffic, retention cleanup and
+//! database queries, rendered in the Prometheus text exposition format
+//! together with gauges read at scrape time. Counters are plain atomics so
+//! the hot paths that bump them never take a lock.
+
+use diesel::connection::{Instrumentation, InstrumentationEvent};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Upper bounds (seconds) of the query latency histogram buckets
+const QUERY_BUCKETS: [f64; 11] = [
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5,
+];
+
+/// The other end of a WebSocket connection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Peer {
+    Proxy,
+    Web,
+    Launcher,
+}
+
+impl Peer {
+    const ALL: [Peer; 3] = [Peer::Proxy, Peer::Web, Peer::Launcher];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Peer::Proxy => "proxy",
+            Peer::Web => "web",
+            Peer::Launcher => "launcher",
+        }
+    }
+}
+
+/// Which way a message travelled, seen from the backend
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Received,
+    Sent,
+}
+
+impl Direction {
+    const ALL: [Direction; 2] = [Direction::Received, Direction::Sent];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Direction::Received => "received",
+            Direction::Sent => "sent",
+        }
+    }
+}
+
+/// A latency histogram with fixed buckets
+struct Histogram {
+    buckets: [AtomicU64; QUERY_BUCKETS.len()],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    const fn new() -> Self {
+        Self {
+            buckets: [const { AtomicU64::new(0) }; QUERY_BUCKETS.len()],
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    fn observe(&self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        if let Some(i) = QUERY_BUCKETS.iter().position(|le| seconds <= *le) {
+            self.buckets[i].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        header(out, name, "histogram", help);
+        // Buckets are stored individually and made cumulative here
+        let mut cumulative = 0;
+        for (le, bucket) in QUERY_BUCKETS.iter().zip(&self.buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, le, cumulative);
+        }
+        let count = self.count.load(Ordering::Relaxed);
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, count);
+        let sum = self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let _ = writeln!(out, "{}_sum {}", name, sum);
+        let _ = writeln!(out, "{}_count {}", name, count);
+    }
+}
+
+struct Counters {
+    /// Indexed by `[peer][direction]`
+    messages: [[AtomicU64; 2]; 3],
+    /// Indexed by peer
+    send_failures: [AtomicU64; 3],
+    retention_runs: AtomicU64,
+    retention_deleted_age: AtomicU64,
+    retention_deleted_count: AtomicU64,
+    retention_last_run: AtomicU64,
+    query_errors: AtomicU64,
+    query_latency: Histogram,
+}
+
+static COUNTERS: Counters = Counters {
+    messages: [const { [const { AtomicU64::new(0) }; 2] }; 3],
+    send_failures: [const { AtomicU64::new(0) }; 3],
+    retention_runs: AtomicU64::new(0),
+    retention_deleted_age: AtomicU64::new(0),
+    retention_deleted_count: AtomicU64::new(0),
+    retention_last_run: AtomicU64::new(0),
+    query_errors: AtomicU64::new(0),
+    query_latency: Histogram::new(),
+};
+
+/// Count one WebSocket message to or from a peer
+pub fn record_message(peer: Peer, direction: Direction) {
+    COUNTERS.messages[peer as usize][direction as usize].fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count a message that could not be handed to a peer's connection
+pub fn record_send_failure(peer: Peer) {
+    COUNTERS.send_failures[peer as usize].fetch_add(1, Ordering::Relaxed);
+}
+
+/// Record the outcome of one retention cleanup pass
+pub fn record_retention(age_deleted: usize, count_deleted: usize) {
+    COUNTERS.retention_runs.fetch_add(1, Ordering::Relaxed);
+    COUNTERS
+        .retention_deleted_age
+        .fetch_add(age_deleted as u64, Ordering::Relaxed);
+    COUNTERS
+        .retention_deleted_count
+        .fetch_add(count_deleted as u64, Ordering::Relaxed);
+    COUNTERS.retention_last_run.store(
+        chrono::Utc::now().timestamp().max(0) as u64,
+        Ordering::Relaxed,
+    );
+}
+
+/// Times every query run on a connection
+#[derive(Default)]
+struct QueryTimer {
+    started: Option<Instant>,
+}
+
+impl Instrumentation for QueryTimer {
+    fn on_connection_event(&mut self, event: InstrumentationEvent<'_>) {
+        match event {
+            InstrumentationEvent::StartQuery { .. } => self.started = Some(Instant::now()),
+            InstrumentationEvent::FinishQuery { error, .. } => {
+                if let Some(started) = self.started.take() {
+                    COUNTERS.query_latency.observe(started.elapsed());
+                }
+                if error.is_some() {
+                    COUNTERS.query_errors.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Instrumentation for new database connections; see
+/// `diesel::connection::set_default_instrumentation`
+pub fn query_instrumentation() -> Option<Box<dyn Instrumentation>> {
+    Some(Box::new(QueryTimer::default()))
+}
+
+/// Values read at scrape time rather than counted as they happen
+#[derive(Debug, Default)]
+pub struct Gauges {
+    pub proxies: usize,
+    pub web_clients: usize,
+    pub launchers: usize,
+    pub pool_connections: u32,
+    pub pool_idle_connections: u32,
+    pub pool_max_connections: u32,
+    /// `None` when the database could not be asked
+    pub pending_permissions: Option<i64>,
+    pub pending_inputs: Option<i64>,
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: impl std::fmt::Display) {
+    header(out, name, "gauge", help);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: &AtomicU64) {
+    header(out, name, "counter", help);
+    let _ = writeln!(out, "{} {}", name, value.load(Ordering::Relaxed));
+}
+
+/// Render every metric in the Prometheus text exposition format
+pub fn render(gauges: &Gauges) -> String {
+    let mut out = String::new();
+
+    gauge(
+        &mut out,
+        "portal_connected_proxies",
+        "Proxies with an open WebSocket connection",
+        gauges.proxies,
+    );
+    gauge(
+        &mut out,
+        "portal_connected_web_clients",
+        "Browser tabs with an open WebSocket connection",
+        gauges.web_clients,
+    );
+    gauge(
+        &mut out,
+        "portal_connected_launchers",
+        "Launcher daemons with an open WebSocket connection",
+        gauges.launchers,
+    );
+
+    header(
+        &mut out,
+        "portal_websocket_messages_total",
+        "counter",
+        "WebSocket messages exchanged with each kind of peer",
+    );
+    for peer in Peer::ALL {
+        for direction in Direction::ALL {
+            let value =
+                COUNTERS.messages[peer as usize][direction as usize].load(Ordering::Relaxed);
+            let _ = writeln!(
+                out,
+                "portal_websocket_messages_total{{peer=\"{}\",direction=\"{}\"}} {}",
+                peer.as_str(),
+                direction.as_str(),
+                value
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "portal_websocket_send_failures_total",
+        "counter",
+        "Messages that could not be delivered to a peer's connection",
+    );
+    for peer in Peer::ALL {
+        let value = COUNTERS.send_failures[peer as usize].load(Ordering::Relaxed);
+        let _ = writeln!(
+            out,
+            "portal_websocket_send_failures_total{{peer=\"{}\"}} {}",
+            peer.as_str(),
+            value
+        );
+    }
+
+    if let Some(count) = gauges.pending_permissions {
+        gauge(
+            &mut out,
+            "portal_pending_permission_requests",
+            "Permission requests waiting for an answer",
+            count,
+        );
+    }
+    if let Some(count) = gauges.pending_inputs {
+        gauge(
+            &mut out,
+            "portal_pending_inputs",
+            "Inputs queued for proxies that have not acknowledged them",
+            count,
+        );
+    }
+
+    gauge(
+        &mut out,
+        "portal_db_pool_connections",
+        "Open database connections, idle or in use",
+        gauges.pool_connections,
+    );
+    gauge(
+        &mut out,
+        "portal_db_pool_idle_connections",
+        "Open database connections not in use",
+        gauges.pool_idle_connections,
+    );
+    gauge(
+        &mut out,
+        "portal_db_pool_max_connections",
+        "Largest number of connections the pool will open",
+        gauges.pool_max_connections,
+    );
+    COUNTERS.query_latency.render(
+        &mut out,
+        "portal_db_query_duration_seconds",
+        "Time spent running database queries",
+    );
+    counter(
+        &mut out,
+        "portal_db_query_errors_total",
+        "Database queries that returned an error",
+        &COUNTERS.query_errors,
+    );
+
+    counter(
+        &mut out,
+        "portal_retention_runs_total",
+        "Message retention cleanup passes",
+        &COUNTERS.retention_runs,
+    );
+    header(
+        &mut out,
+        "portal_retention_deleted_messages_total",
+        "counter",
+        "Messages deleted by retention cleanup, by the rule that removed them",
+    );
+    for (reason, value) in [
+        ("age", &COUNTERS.retention_deleted_age),
+        ("count", &COUNTERS.retention_deleted_count),
+    ] {
+        let _ = writeln!(
+            out,
+            "portal_retention_deleted_messages_total{{reason=\"{}\"}} {}",
+            reason,
+            value.load(Ordering::Relaxed)
+        );
+    }
+    gauge(
+        &mut out,
+        "portal_retention_last_run_timestamp_seconds",
+        "Unix time the last retention cleanup pass finished",
+        COUNTERS.retention_last_run.load(Ordering::Relaxed),
+    );
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let histogram = Histogram::new();
+        histogram.observe(Duration::from_micros(500));
+        histogram.observe(Duration::from_millis(20));
+        histogram.observe(Duration::from_secs(10));
+
+        let mut out = String::new();
+        histogram.render(&mut out, "q", "help");
+        assert!(out.contains("q_bucket{le=\"0.001\"} 1\n"));
+        assert!(out.contains("q_bucket{le=\"0.01\"} 1\n"));
+        assert!(out.contains("q_bucket{le=\"0.025\"} 2\n"));
+        assert!(out.contains("q_bucket{le=\"2.5\"} 2\n"));
+        assert!(out.contains("q_bucket{le=\"+Inf\"} 3\n"));
+        assert!(out.contains("q_count 3\n"));
+        assert!(out.contains("q_sum 10.0205\n"));
+    }
+
+    #[test]
+    fn test_render_includes_labels_and_gauges() {
+        record_message(Peer::Launcher, Direction::Sent);
+        let out = render(&Gauges {
+            proxies: 2,
+            pending_permissions: Some(4),
+            ..Default::default()
+        });
+        assert!(out.contains("portal_connected_proxies 2\n"));
+        assert!(out.contains("portal_pending_permission_requests 4\n"));
+        assert!(!out.contains("portal_pending_inputs "));
+        assert!(
+            out.contains("portal_websocket_messages_total{peer=\"launcher\",direction=\"sent\"}")
+        );
+        // Every sample follows its own HELP and TYPE lines
+        for line in out.lines().filter(|l| !l.starts_with('#')) {
+            let name = line.split(['{', ' ']).next().unwrap();
+            let family = name
+                .trim_end_matches("_bucket")
+                .trim_end_matches("_sum")
+                .trim_end_matches("_count");
+            assert!(
+                out.contains(&format!("# TYPE {} ", family)),
+                "missing TYPE for {}",
+                name
+            );
+        }
+    }
+}
@@ -8,7 +8,8 @@ use uuid::Uuid;
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct User {
     pub id: Uuid,
-    pub google_id: String,
+    /// The login provider's ID for this user, unique within `auth_provider`
+    pub auth_subject: String,
     pub email: String,
     pub name: Option<String>,
     pub avatar_url: Option<String>,
@@ -19,18 +20,22 @@ pub struct User {
     pub voice_enabled: bool,
     pub ban_reason: Option<String>,
     pub sound_config: Option<serde_json::Value>,
+    /// Which login provider vouched for this user (`google`, an OIDC provider
+    /// ID, or `dev`)
+    pub auth_provider: String,
 }
 
 #[derive(Debug, Insertable)]
 #[diesel(table_name = crate::schema::users)]
 pub struct NewUser {
-    pub google_id: String,
+    pub auth_provider: String,
+    pub auth_subject: String,
     pub email: String,
     pub name: Option<String>,
     pub avatar_url: Option<String>,
 }
 
-#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone, utoipa::ToSchema)]
 #[diesel(table_name = crate::schema::sessions)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct Session {
@@ -57,6 +62,14 @@ pub struct Session {
     pub agent_type: String,
     pub repo_url: Option<String>,
     pub scheduled_task_id: Option<Uuid>,
+    pub permission_timeout_seconds: Option<i32>,
+    pub permission_timeout_action: Option<String>,
+    pub parent_session_id: Option<Uuid>,
+    pub forked_from_message_id: Option<Uuid>,
+    pub max_cost_usd: Option<f64>,
+    /// Per-model totals of the last `result`, see `crate::analytics`
+    #[serde(skip)]
+    pub model_usage: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Insertable)]
@@ -87,9 +100,10 @@ pub struct NewSessionWithId {
     pub agent_type: String,
     pub repo_url: Option<String>,
     pub scheduled_task_id: Option<Uuid>,
+    pub max_cost_usd: Option<f64>,
 }
 
-#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone, utoipa::ToSchema)]
 #[diesel(table_name = crate::schema::messages)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct Message {
@@ -152,6 +166,8 @@ pub struct PendingPermissionRequest {
     pub input: serde_json::Value,
     pub permission_suggestions: Option<serde_json::Value>,
     pub created_at: NaiveDateTime,
+    pub escalated_at: Option<NaiveDateTime>,
+    pub emailed_at: Option<NaiveDateTime>,
 }
 
 #[derive(Debug, Insertable)]
@@ -250,6 +266,11 @@ pub struct ScheduledTask {
     pub last_run_at: Option<NaiveDateTime>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub permission_timeout_seconds: Option<i32>,
+    pub permission_timeout_action: String,
+    pub last_exit_code: Option<i32>,
+    pub last_run_completed_at: Option<NaiveDateTime>,
+    pub team_id: Option<Uuid>,
 }
 
 #[derive(Debug, Insertable)]
@@ -265,4 +286,490 @@ pub struct NewScheduledTask {
     pub claude_args: serde_json::Value,
     pub agent_type: String,
     pub max_runtime_minutes: i32,
+    pub permission_timeout_seconds: Option<i32>,
+    pub permission_timeout_action: String,
+    pub team_id: Option<Uuid>,
+}
+
+// ============================================================================
+// Permission Rule Models
+// ============================================================================
+
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::permission_rules)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PermissionRule {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub session_id: Option<Uuid>,
+    pub tool_name: String,
+    pub path_glob: Option<String>,
+    pub command_regex: Option<String>,
+    pub verdict: String,
+    pub priority: i32,
+    pub description: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::permission_rules)]
+pub struct NewPermissionRule {
+    pub user_id: Uuid,
+    pub session_id: Option<Uuid>,
+    pub tool_name: String,
+    pub path_glob: Option<String>,
+    pub command_regex: Option<String>,
+    pub verdict: String,
+    pub priority: i32,
+    pub description: Option<String>,
+}
+
+// ============================================================================
+// Permission Decision Models (audit log)
+// ============================================================================
+
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::permission_decisions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PermissionDecision {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub request_id: String,
+    pub tool_name: String,
+    pub input: serde_json::Value,
+    pub decision: String,
+    pub decided_by: String,
+    pub user_id: Option<Uuid>,
+    pub permission_rule_id: Option<Uuid>,
+    pub reason: Option<String>,
+    pub requested_at: NaiveDateTime,
+    pub decided_at: NaiveDateTime,
+    pub latency_ms: i64,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::permission_decisions)]
+pub struct NewPermissionDecision {
+    pub session_id: Uuid,
+    pub request_id: String,
+    pub tool_name: String,
+    pub input: serde_json::Value,
+    pub decision: String,
+    pub decided_by: String,
+    pub user_id: Option<Uuid>,
+    pub permission_rule_id: Option<Uuid>,
+    pub reason: Option<String>,
+    pub requested_at: NaiveDateTime,
+    pub latency_ms: i64,
+}
+
+// ============================================================================
+// Webhook Models
+// ============================================================================
+
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::webhooks)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Webhook {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub events: serde_json::Value,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::webhooks)]
+pub struct NewWebhook {
+    pub user_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub events: serde_json::Value,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::webhook_deliveries)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub delivered_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::webhook_deliveries)]
+pub struct NewWebhookDelivery {
+    pub webhook_id: Uuid,
+    pub event: String,
+    pub payload: serde_json::Value,
+}
+
+// ============================================================================
+// Email Notification Models
+// ============================================================================
+
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::email_notification_settings)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct EmailNotificationSettings {
+    pub user_id: Uuid,
+    pub permission_wait_minutes: Option<i32>,
+    pub session_errors: bool,
+    pub scheduled_failures: bool,
+    pub daily_digest: bool,
+    pub digest_hour: i32,
+    pub last_digest_at: Option<NaiveDateTime>,
+    pub updated_at: NaiveDateTime,
+}
+
+// ============================================================================
+// Chat Bridge Models
+// ============================================================================
+
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::chat_bridges)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ChatBridge {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub incoming_webhook_url: String,
+    pub signing_secret: String,
+    pub post_permissions: bool,
+    pub post_results: bool,
+    pub user_map: serde_json::Value,
+    pub enabled: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::chat_bridges)]
+pub struct NewChatBridge {
+    pub user_id: Uuid,
+    pub name: String,
+    pub incoming_webhook_url: String,
+    pub signing_secret: String,
+    pub post_permissions: bool,
+    pub post_results: bool,
+    pub user_map: serde_json::Value,
+}
+
+// ============================================================================
+// API Key Models
+// ============================================================================
+
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::api_keys)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub key_prefix: String,
+    pub key_hash: String,
+    pub scopes: serde_json::Value,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub expires_at: NaiveDateTime,
+    pub revoked: bool,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::api_keys)]
+pub struct NewApiKey {
+    pub user_id: Uuid,
+    pub name: String,
+    pub key_prefix: String,
+    pub key_hash: String,
+    pub scopes: serde_json::Value,
+    pub expires_at: NaiveDateTime,
+}
+
+// ============================================================================
+// Share Link Models
+// ============================================================================
+
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::share_links)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ShareLink {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub created_by: Uuid,
+    pub token_hash: String,
+    pub password_hash: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub revoked: bool,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::share_links)]
+pub struct NewShareLink {
+    pub session_id: Uuid,
+    pub created_by: Uuid,
+    pub token_hash: String,
+    pub password_hash: Option<String>,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::share_link_views)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ShareLinkView {
+    pub id: Uuid,
+    pub share_link_id: Uuid,
+    pub viewed_at: NaiveDateTime,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::share_link_views)]
+pub struct NewShareLinkView {
+    pub share_link_id: Uuid,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+// ============================================================================
+// Team Models
+// ============================================================================
+
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::teams)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Team {
+    pub id: Uuid,
+    pub name: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::teams)]
+pub struct NewTeam {
+    pub name: String,
+    pub created_by: Option<Uuid>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::team_members)]
+pub struct NewTeamMember {
+    pub team_id: Uuid,
+    pub user_id: Uuid,
+    pub role: String,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::session_teams)]
+pub struct NewSessionTeam {
+    pub session_id: Uuid,
+    pub team_id: Uuid,
+    pub role: String,
+}
+
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::team_launchers)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct TeamLauncher {
+    pub id: Uuid,
+    pub team_id: Uuid,
+    pub owner_id: Uuid,
+    pub launcher_name: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::team_launchers)]
+pub struct NewTeamLauncher {
+    pub team_id: Uuid,
+    pub owner_id: Uuid,
+    pub launcher_name: String,
+}
+
+// ============================================================================
+// Spend Budget Models
+// ============================================================================
+
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::spend_budgets)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct SpendBudget {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub team_id: Option<Uuid>,
+    pub period: String,
+    pub soft_limit_usd: Option<f64>,
+    pub hard_limit_usd: Option<f64>,
+    pub soft_notified_at: Option<NaiveDateTime>,
+    pub hard_notified_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::spend_budgets)]
+pub struct NewSpendBudget {
+    pub user_id: Option<Uuid>,
+    pub team_id: Option<Uuid>,
+    pub period: String,
+    pub soft_limit_usd: Option<f64>,
+    pub hard_limit_usd: Option<f64>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::spend_events)]
+pub struct NewSpendEvent {
+    pub session_id: Option<Uuid>,
+    pub user_id: Uuid,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::usage_records)]
+pub struct NewUsageRecord {
+    pub session_id: Option<Uuid>,
+    pub user_id: Uuid,
+    pub model: String,
+    pub hostname: String,
+    pub repo_url: Option<String>,
+    pub agent_type: String,
+    pub scheduled_task_id: Option<Uuid>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cost_usd: f64,
+    pub turns: i32,
+    pub duration_ms: i64,
+}
+
+#[derive(Debug, Queryable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::audit_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AuditEvent {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub actor_email: Option<String>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<Uuid>,
+    pub details: serde_json::Value,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::audit_events)]
+pub struct NewAuditEvent {
+    pub actor_id: Option<Uuid>,
+    pub actor_email: Option<String>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<Uuid>,
+    pub details: serde_json::Value,
+}
+
+#[derive(Debug, Queryable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::local_accounts)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct LocalAccount {
+    pub user_id: Uuid,
+    pub username: String,
+    pub password_hash: Option<String>,
+    pub setup_expires_at: Option<NaiveDateTime>,
+    pub totp_secret: Option<String>,
+    pub totp_enabled: bool,
+    pub totp_last_step: Option<i64>,
+    pub password_changed_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::local_accounts)]
+pub struct NewLocalAccount {
+    pub user_id: Uuid,
+    pub username: String,
+    pub setup_token_hash: Option<String>,
+    pub setup_expires_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Queryable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::invites)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Invite {
+    pub id: Uuid,
+    pub label: Option<String>,
+    pub role: String,
+    pub max_uses: i32,
+    pub use_count: i32,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub revoked: bool,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::invites)]
+pub struct NewInvite {
+    pub token_hash: String,
+    pub label: Option<String>,
+    pub role: String,
+    pub max_uses: i32,
+    pub created_by: Option<Uuid>,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::invite_redemptions)]
+pub struct NewInviteRedemption {
+    pub invite_id: Uuid,
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Queryable, Selectable)]
+#[diesel(table_name = crate::schema::instance_settings)]
+pub struct InstanceSettingRow {
+    pub key: String,
+    pub value: serde_json::Value,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::instance_settings)]
+pub struct NewInstanceSetting {
+    pub key: String,
+    pub value: serde_json::Value,
+    pub updated_by: Option<Uuid>,
+}
+
+#[derive(Debug, Queryable, Selectable)]
+#[diesel(table_name = crate::schema::web_logins)]
+pub struct WebLogin {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_seen_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::web_logins)]
+pub struct NewWebLogin {
+    pub user_id: Uuid,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
 }
@@ -0,0 +1,369 @@
+//! OpenAPI document for the REST API
+//!
+//! Each handler describes its route with `#[utoipa::path]`, and request and
+//! response types derive `utoipa::ToSchema` (types in `shared` behind its
+//! `openapi` feature). `ApiDoc` collects them into one document, served at
+//! `/api/openapi.json` and browsable at `/api/docs`.
+//!
+//! Operations are authenticated unless they declare `security(())`. The
+//! `Security` modifier fills in how: the session cookie, plus an API key
+//! wherever `api_keys::required_scope` allows one.
+
+use axum::{http::Method, Json};
+use shared::protocol::SESSION_COOKIE_NAME;
+use utoipa::openapi::path::{Operation, PathItem};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::openapi::{ResponseBuilder, SecurityRequirement};
+use utoipa::{Modify, OpenApi};
+
+use crate::{api_keys, handlers};
+
+const COOKIE_SCHEME: &str = "session_cookie";
+const API_KEY_SCHEME: &str = "api_key";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Agent Portal API",
+        description = "REST API of the portal. Sign in through the browser to use the session \
+                       cookie, or send a personal API key as `Authorization: Bearer cpk_...`."
+    ),
+    paths(
+        handlers::config::health,
+        handlers::config::get_config,
+        handlers::auth::login,
+        handlers::auth::callback,
+        handlers::auth::oidc_login,
+        handlers::auth::oidc_callback,
+        handlers::auth::me,
+        handlers::auth::logout,
+        handlers::auth::dev_login,
+        handlers::auth::device_login,
+        handlers::auth::check_invite,
+        handlers::local_auth::login,
+        handlers::local_auth::setup_info,
+        handlers::local_auth::complete_setup,
+        handlers::local_auth::get_account,
+        handlers::local_auth::change_password,
+        handlers::local_auth::totp_setup,
+        handlers::local_auth::totp_enable,
+        handlers::local_auth::totp_disable,
+        handlers::device_flow::device_code,
+        handlers::device_flow::device_poll,
+        handlers::device_flow::device_verify_page,
+        handlers::device_flow::device_approve,
+        handlers::device_flow::device_deny,
+        handlers::downloads::install_script,
+        handlers::downloads::proxy_binary,
+        handlers::sessions::list_sessions,
+        handlers::sessions::get_session,
+        handlers::sessions::delete_session,
+        handlers::sessions::stop_session,
+        handlers::sessions::send_input,
+        handlers::sessions::fork_session,
+        handlers::sessions::list_session_members,
+        handlers::sessions::add_session_member,
+        handlers::sessions::remove_session_member,
+        handlers::sessions::update_session_member_role,
+        handlers::teams::list_session_teams,
+        handlers::teams::share_session_with_team,
+        handlers::teams::unshare_session_with_team,
+        handlers::share_links::list_share_links,
+        handlers::share_links::create_share_link,
+        handlers::share_links::revoke_share_link,
+        handlers::share_links::list_share_link_views,
+        handlers::share_links::get_shared_session,
+        handlers::messages::list_messages,
+        handlers::messages::create_message,
+        handlers::messages::export_messages,
+        handlers::search::search_handler,
+        handlers::permission_decisions::list_decisions_handler,
+        handlers::permission_timeouts::get_timeout_handler,
+        handlers::permission_timeouts::update_timeout_handler,
+        handlers::permission_rules::list_rules_handler,
+        handlers::permission_rules::create_rule_handler,
+        handlers::permission_rules::update_rule_handler,
+        handlers::permission_rules::delete_rule_handler,
+        handlers::proxy_tokens::list_tokens_handler,
+        handlers::proxy_tokens::create_token_handler,
+        handlers::proxy_tokens::revoke_token_handler,
+        handlers::proxy_tokens::renew_token_handler,
+        handlers::api_keys::list_api_keys_handler,
+        handlers::api_keys::create_api_key_handler,
+        handlers::api_keys::revoke_api_key_handler,
+        handlers::web_logins::list_logins_handler,
+        handlers::web_logins::revoke_login_handler,
+        handlers::web_logins::revoke_all_logins_handler,
+        handlers::teams::list_teams,
+        handlers::teams::create_team,
+        handlers::teams::delete_team,
+        handlers::teams::list_team_members,
+        handlers::teams::add_team_member,
+        handlers::teams::update_team_member,
+        handlers::teams::remove_team_member,
+        handlers::teams::list_team_launchers,
+        handlers::teams::share_launcher,
+        handlers::teams::unshare_launcher,
+        handlers::budgets::list_my_budgets,
+        handlers::analytics::get_analytics,
+        handlers::scheduled_tasks::list_tasks_handler,
+        handlers::scheduled_tasks::create_task_handler,
+        handlers::scheduled_tasks::update_task_handler,
+        handlers::scheduled_tasks::delete_task_handler,
+        handlers::scheduled_tasks::list_runs_handler,
+        handlers::webhooks::list_webhooks_handler,
+        handlers::webhooks::create_webhook_handler,
+        handlers::webhooks::update_webhook_handler,
+        handlers::webhooks::delete_webhook_handler,
+        handlers::webhooks::list_deliveries_handler,
+        handlers::webhooks::test_webhook_handler,
+        handlers::chat_bridges::list_chat_bridges_handler,
+        handlers::chat_bridges::create_chat_bridge_handler,
+        handlers::chat_bridges::update_chat_bridge_handler,
+        handlers::chat_bridges::delete_chat_bridge_handler,
+        handlers::chat_bridges::test_chat_bridge_handler,
+        handlers::chat_bridges::inbound_handler,
+        handlers::sound_settings::get_sound_settings,
+        handlers::sound_settings::save_sound_settings,
+        handlers::email_settings::get_email_settings,
+        handlers::email_settings::save_email_settings,
+        handlers::email_settings::send_test_email,
+        handlers::launchers::list_launchers,
+        handlers::launchers::list_directories,
+        handlers::launchers::list_local_sessions,
+        handlers::launchers::launch_session,
+        handlers::launchers::renew_launcher_token,
+        handlers::admin::get_stats,
+        handlers::admin::list_users,
+        handlers::admin::update_user,
+        handlers::admin::create_local_account,
+        handlers::admin::reset_local_password,
+        handlers::admin::list_sessions,
+        handlers::admin::delete_session,
+        handlers::admin::set_session_cost_cap,
+        handlers::admin::list_teams,
+        handlers::admin::list_budgets,
+        handlers::admin::set_budget,
+        handlers::admin::delete_budget,
+        handlers::admin::list_invites,
+        handlers::admin::create_invite,
+        handlers::admin::revoke_invite,
+        handlers::admin::get_settings,
+        handlers::admin::update_settings,
+        handlers::admin::list_audit_events,
+        handlers::admin::export_audit_events,
+    ),
+    modifiers(&Metadata, &Security),
+    tags(
+        (name = "sessions", description = "Sessions, their messages and members"),
+        (name = "permissions", description = "Permission rules, timeouts and the decision log"),
+        (name = "launchers", description = "Launcher daemons and starting sessions on them"),
+        (name = "scheduled-tasks", description = "Recurring tasks run by launchers"),
+        (name = "teams", description = "Teams, their members and the launchers shared with them"),
+        (name = "budgets", description = "Spend budgets and what has been spent against them"),
+        (name = "analytics", description = "Daily cost and token usage"),
+        (name = "credentials", description = "Proxy tokens and personal API keys"),
+        (name = "webhooks", description = "Outbound webhooks and their delivery log"),
+        (name = "chat-bridges", description = "Chat channels that relay permissions and input"),
+        (name = "settings", description = "Per-user notification settings"),
+        (name = "admin", description = "Admin-only user, session, team and budget management"),
+        (name = "auth", description = "Browser sign-in"),
+        (name = "device-flow", description = "Proxy sign-in with a device code"),
+        (name = "downloads", description = "Proxy installer and binary"),
+        (name = "meta", description = "Health and public configuration"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Drops the contact and license utoipa fills in from Cargo metadata: the
+/// workspace declares no license, and its author is not a support contact.
+struct Metadata;
+
+impl Modify for Metadata {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        openapi.info.contact = None;
+        openapi.info.license = None;
+    }
+}
+
+/// Adds the security schemes, and for each authenticated operation, which
+/// of them it accepts and a 401 response.
+struct Security;
+
+impl Modify for Security {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            COOKIE_SCHEME,
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::with_description(
+                SESSION_COOKIE_NAME,
+                "Signed session cookie set by browser sign-in",
+            ))),
+        );
+        components.add_security_scheme(
+            API_KEY_SCHEME,
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some(
+                        "Personal API key (`cpk_...`). Requirements list the scope the \
+                         operation needs.",
+                    ))
+                    .build(),
+            ),
+        );
+
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            for (method, operation) in operations(item) {
+                // Public operations declare an empty requirement
+                if operation.security.is_some() {
+                    continue;
+                }
+                let mut security = vec![SecurityRequirement::new(
+                    COOKIE_SCHEME,
+                    Vec::<String>::new(),
+                )];
+                if let Some(scope) = api_keys::required_scope(&method, path) {
+                    security.push(SecurityRequirement::new(API_KEY_SCHEME, [scope.as_str()]));
+                }
+                operation.security = Some(security);
+                operation
+                    .responses
+                    .responses
+                    .entry("401".to_string())
+                    .or_insert_with(|| {
+                        ResponseBuilder::new()
+                            .description("Not signed in, or the API key is invalid")
+                            .build()
+                            .into()
+                    });
+            }
+        }
+    }
+}
+
+fn operations(item: &mut PathItem) -> Vec<(Method, &mut Operation)> {
+    [
+        (Method::GET, item.get.as_mut()),
+        (Method::POST, item.post.as_mut()),
+        (Method::PUT, item.put.as_mut()),
+        (Method::PATCH, item.patch.as_mut()),
+        (Method::DELETE, item.delete.as_mut()),
+    ]
+    .into_iter()
+    .filter_map(|(method, operation)| Some((method, operation?)))
+    .collect()
+}
+
+/// GET /api/openapi.json
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// Routes registered in `main.rs`, as (method, path) pairs.
+    fn registered_routes() -> BTreeSet<(String, String)> {
+        let main = include_str!("main.rs");
+        let mut routes = BTreeSet::new();
+        for chunk in main.split(".route(").skip(1) {
+            let Some(path) = chunk.split('"').nth(1) else {
+                continue;
+            };
+            // The handlers are everything up to the next builder call
+            let handlers = chunk.split("\n        .").next().unwrap_or_default();
+            for method in ["get", "post", "put", "patch", "delete"] {
+                if handlers.contains(&format!("{method}(")) {
+                    routes.insert((method.to_uppercase(), path.to_string()));
+                }
+            }
+        }
+        routes
+    }
+
+    fn documented_routes(doc: &mut utoipa::openapi::OpenApi) -> BTreeSet<(String, String)> {
+        let mut routes = BTreeSet::new();
+        for (path, item) in doc.paths.paths.iter_mut() {
+            for (method, _) in operations(item) {
+                routes.insert((method.to_string(), path.clone()));
+            }
+        }
+        routes
+    }
+
+    #[test]
+    fn test_every_rest_route_is_documented() {
+        let registered: BTreeSet<_> = registered_routes()
+            .into_iter()
+            .filter(|(_, path)| path.starts_with("/api/") && path != "/api/openapi.json")
+            .collect();
+        assert!(registered.len() > 50, "route parsing found {registered:?}");
+
+        let documented = documented_routes(&mut ApiDoc::openapi());
+        let undocumented: Vec<_> = registered.difference(&documented).collect();
+        let stale: Vec<_> = documented.difference(&registered).collect();
+        assert!(undocumented.is_empty(), "undocumented: {undocumented:?}");
+        assert!(stale.is_empty(), "documented but not routed: {stale:?}");
+    }
+
+    #[test]
+    fn test_operation_ids_are_unique() {
+        let mut doc = ApiDoc::openapi();
+        let mut seen = BTreeSet::new();
+        for item in doc.paths.paths.values_mut() {
+            for (_, operation) in operations(item) {
+                let id = operation.operation_id.clone().unwrap_or_default();
+                assert!(seen.insert(id.clone()), "duplicate operationId {id}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_schema_references_resolve() {
+        let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let schemas = doc["components"]["schemas"].as_object().unwrap();
+
+        fn refs(value: &serde_json::Value, out: &mut Vec<String>) {
+            match value {
+                serde_json::Value::Object(map) => {
+                    if let Some(r) = map.get("$ref").and_then(|r| r.as_str()) {
+                        out.push(r.to_string());
+                    }
+                    map.values().for_each(|v| refs(v, out));
+                }
+                serde_json::Value::Array(items) => items.iter().for_each(|v| refs(v, out)),
+                _ => {}
+            }
+        }
+        let mut found = Vec::new();
+        refs(&doc, &mut found);
+        assert!(!found.is_empty());
+        for r in found {
+            let name = r.strip_prefix("#/components/schemas/").unwrap_or(&r);
+            assert!(schemas.contains_key(name), "dangling reference {r}");
+        }
+    }
+
+    #[test]
+    fn test_security_follows_api_key_scopes() {
+        let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let security = |path: &str, method: &str| doc["paths"][path][method]["security"].clone();
+
+        assert_eq!(
+            security("/api/sessions/{id}/input", "post"),
+            serde_json::json!([{ "session_cookie": [] }, { "api_key": ["sessions:input"] }])
+        );
+        // Key management is cookie-only
+        assert_eq!(
+            security("/api/api-keys", "post"),
+            serde_json::json!([{ "session_cookie": [] }])
+        );
+        // Public endpoints need nothing
+        assert_eq!(security("/api/health", "get"), serde_json::json!([{}]));
+        assert!(doc["paths"]["/api/sessions"]["get"]["responses"]["401"].is_object());
+        assert!(doc["paths"]["/api/health"]["get"]["responses"]["401"].is_null());
+    }
+}
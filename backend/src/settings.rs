@@ -0,0 +1,317 @@
+//! Runtime-editable instance settings
+//!
+//! The title, splash text, email allowlist, retention limits, session age
+//! and image size used to be read from the environment once at startup. The
+//! environment now only supplies defaults: admins change settings under
+//! `/api/admin/settings`, changed values are kept in `instance_settings`, and
+//! everything that needs one reads it from [`SettingsStore::get`] when it
+//! runs, so a change applies without a restart.
+
+use diesel::prelude::*;
+use serde_json::{Map, Value};
+use shared::api::InstanceSettings;
+use std::env;
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+use crate::models::{InstanceSettingRow, NewInstanceSetting};
+use crate::schema::instance_settings;
+
+/// Settings from the environment, used for anything admins haven't changed
+pub fn defaults_from_env(dev_mode: bool) -> InstanceSettings {
+    fn parse_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+        env::var(name)
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(default)
+    }
+
+    // In dev mode, override the title with a warning to make it obvious
+    let app_title = if dev_mode {
+        "⚠️ INSECURE DEV MODE ⚠️".to_string()
+    } else {
+        env::var("APP_TITLE").unwrap_or_else(|_| "Agent Portal".to_string())
+    };
+
+    InstanceSettings {
+        app_title,
+        splash_text: env::var("SPLASH_TEXT").ok(),
+        allowed_email_domain: env::var("ALLOWED_EMAIL_DOMAIN").ok(),
+        allowed_emails: env::var("ALLOWED_EMAILS").ok().map(|s| {
+            s.split(',')
+                .map(|e| e.trim().to_lowercase())
+                .filter(|e| !e.is_empty())
+                .collect()
+        }),
+        message_retention_count: parse_or("MESSAGE_RETENTION_COUNT", 100),
+        message_retention_days: parse_or("MESSAGE_RETENTION_DAYS", 30),
+        session_max_age_days: parse_or("SESSION_MAX_AGE_DAYS", 14),
+        max_image_mb: parse_or("PORTAL_MAX_IMAGE_MB", 10),
+    }
+}
+
+/// Blank text means unset, and allowlist entries compare lowercased
+pub fn normalize(settings: InstanceSettings) -> InstanceSettings {
+    fn non_empty(value: Option<String>) -> Option<String> {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    }
+
+    InstanceSettings {
+        app_title: settings.app_title.trim().to_string(),
+        splash_text: non_empty(settings.splash_text),
+        allowed_email_domain: non_empty(settings.allowed_email_domain).map(|d| d.to_lowercase()),
+        allowed_emails: settings
+            .allowed_emails
+            .map(|emails| {
+                emails
+                    .into_iter()
+                    .map(|e| e.trim().to_lowercase())
+                    .filter(|e| !e.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|emails| !emails.is_empty()),
+        ..settings
+    }
+}
+
+fn to_map(settings: &InstanceSettings) -> Map<String, Value> {
+    match serde_json::to_value(settings) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// `defaults` with each stored value applied over it. A row for a setting
+/// that no longer exists, or whose value doesn't fit, is skipped.
+pub fn overlay(defaults: &InstanceSettings, rows: &[(String, Value)]) -> InstanceSettings {
+    let mut map = to_map(defaults);
+    let mut settings = defaults.clone();
+    for (key, value) in rows {
+        if !map.contains_key(key) {
+            tracing::warn!("Ignoring unknown instance setting {}", key);
+            continue;
+        }
+        let previous = map.insert(key.clone(), value.clone());
+        match serde_json::from_value(Value::Object(map.clone())) {
+            Ok(applied) => settings = applied,
+            Err(e) => {
+                tracing::warn!("Ignoring stored instance setting {}: {}", key, e);
+                if let Some(previous) = previous {
+                    map.insert(key.clone(), previous);
+                }
+            }
+        }
+    }
+    settings
+}
+
+/// Names of the settings that differ between `settings` and `defaults`
+pub fn overridden(settings: &InstanceSettings, defaults: &InstanceSettings) -> Vec<String> {
+    let defaults = to_map(defaults);
+    to_map(settings)
+        .into_iter()
+        .filter(|(key, value)| defaults.get(key) != Some(value))
+        .map(|(key, _)| key)
+        .collect()
+}
+
+/// The settings in effect, shared by every handler and background task
+#[derive(Clone)]
+pub struct SettingsStore {
+    defaults: Arc<InstanceSettings>,
+    current: Arc<RwLock<InstanceSettings>>,
+}
+
+impl SettingsStore {
+    pub fn new(defaults: InstanceSettings) -> Self {
+        Self {
+            current: Arc::new(RwLock::new(defaults.clone())),
+            defaults: Arc::new(defaults),
+        }
+    }
+
+    /// The settings in effect right now
+    pub fn get(&self) -> InstanceSettings {
+        self.current
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn defaults(&self) -> &InstanceSettings {
+        &self.defaults
+    }
+
+    fn set(&self, settings: InstanceSettings) {
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = settings;
+    }
+
+    /// Apply the stored overrides over the defaults
+    pub fn load(&self, conn: &mut PgConnection) -> QueryResult<()> {
+        let rows: Vec<InstanceSettingRow> = instance_settings::table
+            .select(InstanceSettingRow::as_select())
+            .load(conn)?;
+        let rows: Vec<(String, Value)> = rows.into_iter().map(|r| (r.key, r.value)).collect();
+        self.set(overlay(&self.defaults, &rows));
+        Ok(())
+    }
+
+    /// Store `settings` as the ones in effect. Settings equal to their
+    /// default lose their row, so a later change to the environment applies
+    /// to them again. Returns the names of the settings that changed.
+    pub fn save(
+        &self,
+        conn: &mut PgConnection,
+        settings: InstanceSettings,
+        admin_id: Uuid,
+    ) -> QueryResult<Vec<String>> {
+        let changed = overridden(&settings, &self.get());
+        let defaults = to_map(&self.defaults);
+        conn.transaction(|conn| {
+            for (key, value) in to_map(&settings) {
+                if defaults.get(&key) == Some(&value) {
+                    diesel::delete(instance_settings::table.find(&key)).execute(conn)?;
+                } else {
+                    diesel::insert_into(instance_settings::table)
+                        .values(&NewInstanceSetting {
+                            key,
+                            value: value.clone(),
+                            updated_by: Some(admin_id),
+                        })
+                        .on_conflict(instance_settings::key)
+                        .do_update()
+                        .set((
+                            instance_settings::value.eq(value),
+                            instance_settings::updated_by.eq(Some(admin_id)),
+                            instance_settings::updated_at.eq(diesel::dsl::now),
+                        ))
+                        .execute(conn)?;
+                }
+            }
+            Ok::<_, diesel::result::Error>(())
+        })?;
+        self.set(settings);
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn defaults() -> InstanceSettings {
+        InstanceSettings {
+            app_title: "Agent Portal".to_string(),
+            splash_text: None,
+            allowed_email_domain: None,
+            allowed_emails: None,
+            message_retention_count: 100,
+            message_retention_days: 30,
+            session_max_age_days: 14,
+            max_image_mb: 10,
+        }
+    }
+
+    #[test]
+    fn test_overlay_applies_stored_values() {
+        let rows = vec![
+            ("app_title".to_string(), json!("Team Portal")),
+            ("allowed_emails".to_string(), json!(["a@example.com"])),
+            ("message_retention_days".to_string(), json!(0)),
+        ];
+        let settings = overlay(&defaults(), &rows);
+        assert_eq!(settings.app_title, "Team Portal");
+        assert_eq!(
+            settings.allowed_emails,
+            Some(vec!["a@example.com".to_string()])
+        );
+        assert_eq!(settings.message_retention_days, 0);
+        assert_eq!(settings.max_image_mb, 10);
+    }
+
+    #[test]
+    fn test_overlay_skips_bad_rows() {
+        let rows = vec![
+            ("retired_setting".to_string(), json!(true)),
+            ("max_image_mb".to_string(), json!("lots")),
+            ("session_max_age_days".to_string(), json!(7)),
+        ];
+        let settings = overlay(&defaults(), &rows);
+        assert_eq!(settings.max_image_mb, 10);
+        assert_eq!(settings.session_max_age_days, 7);
+    }
+
+    #[test]
+    fn test_overridden() {
+        assert!(overridden(&defaults(), &defaults()).is_empty());
+        let changed = InstanceSettings {
+            splash_text: Some("Welcome".to_string()),
+            max_image_mb: 20,
+            ..defaults()
+        };
+        assert_eq!(
+            overridden(&changed, &defaults()),
+            vec!["max_image_mb".to_string(), "splash_text".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        let settings = normalize(InstanceSettings {
+            app_title: "  Portal ".to_string(),
+            splash_text: Some("   ".to_string()),
+            allowed_email_domain: Some(" Example.COM".to_string()),
+            allowed_emails: Some(vec![" Bob@Example.com".to_string(), "".to_string()]),
+            ..defaults()
+        });
+        assert_eq!(settings.app_title, "Portal");
+        assert_eq!(settings.splash_text, None);
+        assert_eq!(
+            settings.allowed_email_domain.as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            settings.allowed_emails,
+            Some(vec!["bob@example.com".to_string()])
+        );
+
+        let cleared = normalize(InstanceSettings {
+            allowed_emails: Some(vec![" ".to_string()]),
+            ..defaults()
+        });
+        assert_eq!(cleared.allowed_emails, None);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(defaults().validate().is_ok());
+        let bad = [
+            InstanceSettings {
+                app_title: String::new(),
+                ..defaults()
+            },
+            InstanceSettings {
+                allowed_email_domain: Some("@example.com".to_string()),
+                ..defaults()
+            },
+            InstanceSettings {
+                allowed_emails: Some(vec!["not an email".to_string()]),
+                ..defaults()
+            },
+            InstanceSettings {
+                message_retention_count: 0,
+                ..defaults()
+            },
+            InstanceSettings {
+                max_image_mb: 0,
+                ..defaults()
+            },
+        ];
+        for settings in bad {
+            assert!(settings.validate().is_err(), "{:?}", settings);
+        }
+    }
+}
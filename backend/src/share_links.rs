@@ -0,0 +1,170 @@
+//! Public share links
+//!
+//! A session owner can hand out `/shared/cps_...` links that let anyone read
+//! the session without a portal account. Like API keys, only the SHA-256 of
+//! the token is stored. A link may also carry an argon2-hashed password.
+//!
+//! Viewers stream the session over `/ws/shared`, which only ever sends
+//! output passed through `redact`. Each time a link is opened, a row is written
+//! to `share_link_views`.
+
+use argon2::password_hash::{PasswordHash, SaltString};
+use argon2::{Argon2, PasswordHasher, PasswordVerifier};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::jwt::hash_token;
+use crate::models::{NewShareLinkView, ShareLink};
+use crate::schema::{share_link_views, share_links};
+
+/// Every link token starts with this, so it is told apart from other tokens
+pub const TOKEN_PREFIX: &str = "cps_";
+/// Longest password accepted, to bound the hashing work per request
+pub const MAX_PASSWORD_LEN: usize = 256;
+
+/// Top-level message fields that reveal spend or token usage
+const REDACTED_FIELDS: &[&str] = &[
+    "total_cost_usd",
+    "cost_usd",
+    "usage",
+    "modelUsage",
+    "model_usage",
+    "_sender",
+];
+
+/// A new random link token.
+pub fn generate_token() -> String {
+    use rand::RngCore;
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("{}{}", TOKEN_PREFIX, hex::encode(bytes))
+}
+
+/// PHC string for a link password.
+pub fn hash_password(password: &str) -> Result<String, AppError> {
+    let salt = SaltString::generate(&mut rand::thread_rng());
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::Internal(format!("Failed to hash password: {}", e)))
+}
+
+/// Whether `password` matches a PHC string from `hash_password`.
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    if password.len() > MAX_PASSWORD_LEN {
+        return false;
+    }
+    PasswordHash::new(hash)
+        .map(|parsed| {
+            Argon2::default()
+                .verify_password(password.as_bytes(), &parsed)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
+/// The link a token opens, if it is neither revoked nor expired.
+pub fn find_active_link(
+    conn: &mut PgConnection,
+    token: &str,
+) -> Result<Option<ShareLink>, AppError> {
+    if !token.starts_with(TOKEN_PREFIX) {
+        return Ok(None);
+    }
+    share_links::table
+        .filter(share_links::token_hash.eq(hash_token(token)))
+        .filter(share_links::revoked.eq(false))
+        .filter(share_links::expires_at.gt(chrono::Utc::now().naive_utc()))
+        .first::<ShareLink>(conn)
+        .optional()
+        .map_err(|e| AppError::DbQuery(e.to_string()))
+}
+
+/// Whether a link is still usable, for re-checking open viewer sockets.
+pub fn is_link_active(conn: &mut PgConnection, link_id: Uuid) -> Result<bool, AppError> {
+    diesel::select(diesel::dsl::exists(
+        share_links::table
+            .filter(share_links::id.eq(link_id))
+            .filter(share_links::revoked.eq(false))
+            .filter(share_links::expires_at.gt(chrono::Utc::now().naive_utc())),
+    ))
+    .get_result(conn)
+    .map_err(|e| AppError::DbQuery(e.to_string()))
+}
+
+/// Log that a link was opened.
+pub fn record_view(
+    conn: &mut PgConnection,
+    share_link_id: Uuid,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+) -> Result<(), AppError> {
+    diesel::insert_into(share_link_views::table)
+        .values(&NewShareLinkView {
+            share_link_id,
+            ip_address,
+            user_agent,
+        })
+        .execute(conn)
+        .map(|_| ())
+        .map_err(|e| AppError::DbQuery(e.to_string()))
+}
+
+/// Strip cost, token usage and sender identity from a stored or live message
+/// before it goes to an anonymous viewer.
+pub fn redact(mut message: serde_json::Value) -> serde_json::Value {
+    if let Some(obj) = message.as_object_mut() {
+        for field in REDACTED_FIELDS {
+            obj.remove(*field);
+        }
+        if let Some(inner) = obj.get_mut("message").and_then(|m| m.as_object_mut()) {
+            inner.remove("usage");
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_format() {
+        let token = generate_token();
+        assert!(token.starts_with(TOKEN_PREFIX));
+        assert_eq!(token.len(), TOKEN_PREFIX.len() + 64);
+        assert_ne!(token, generate_token());
+    }
+
+    #[test]
+    fn test_password_roundtrip() {
+        let hash = hash_password("hunter2").unwrap();
+        assert!(hash.starts_with("$argon2"));
+        assert!(verify_password("hunter2", &hash));
+        assert!(!verify_password("hunter3", &hash));
+        assert!(!verify_password("hunter2", "not a hash"));
+    }
+
+    #[test]
+    fn test_redact_removes_cost_and_sender() {
+        let result = redact(serde_json::json!({
+            "type": "result",
+            "result": "Done",
+            "total_cost_usd": 0.42,
+            "usage": {"input_tokens": 10},
+            "modelUsage": {},
+            "_sender": {"user_id": "u", "name": "Ada"},
+        }));
+        assert_eq!(
+            result,
+            serde_json::json!({"type": "result", "result": "Done"})
+        );
+
+        let assistant = redact(serde_json::json!({
+            "type": "assistant",
+            "message": {"content": [], "usage": {"output_tokens": 5}},
+        }));
+        assert_eq!(assistant["message"], serde_json::json!({"content": []}));
+    }
+}
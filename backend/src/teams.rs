@@ -0,0 +1,197 @@
+//! Teams
+//!
+//! A team is a named group of users. Its `owner`s manage membership; every
+//! member gets what is shared with the team:
+//!
+//! - sessions, through `session_teams`, as a viewer or editor
+//! - scheduled tasks, whose runs are shared with the task's team
+//! - launchers, through `team_launchers`, which members may launch on
+//!
+//! Session access is resolved here so every handler agrees on it: a user's
+//! role in a session is their own `session_members` role or the strongest role
+//! the session was shared with to one of their teams, whichever grants more.
+//! Sharing never makes anyone an owner.
+
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::models::Session;
+use crate::schema::{
+    session_members, session_teams, sessions, team_launchers, team_members, users,
+};
+
+/// Roles within a team
+pub const TEAM_ROLES: [&str; 2] = ["owner", "member"];
+/// Roles a session can be shared with a team as
+pub const SHARE_ROLES: [&str; 2] = ["viewer", "editor"];
+/// Longest team name accepted, matching the column
+pub const MAX_TEAM_NAME_LEN: usize = 100;
+
+fn role_rank(role: &str) -> u8 {
+    match role {
+        "owner" => 3,
+        "editor" => 2,
+        "viewer" => 1,
+        _ => 0,
+    }
+}
+
+/// The session role that grants more of the two.
+pub fn stronger_role(a: String, b: String) -> String {
+    if role_rank(&b) > role_rank(&a) {
+        b
+    } else {
+        a
+    }
+}
+
+/// A trimmed team name, or why it is unusable.
+pub fn validate_team_name(name: &str) -> Result<String, &'static str> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Team name must not be empty");
+    }
+    if name.chars().count() > MAX_TEAM_NAME_LEN {
+        return Err("Team name is too long");
+    }
+    Ok(name.to_string())
+}
+
+/// The user's role in a team, if they belong to it.
+pub fn team_role(
+    conn: &mut PgConnection,
+    team_id: Uuid,
+    user_id: Uuid,
+) -> QueryResult<Option<String>> {
+    team_members::table
+        .filter(team_members::team_id.eq(team_id))
+        .filter(team_members::user_id.eq(user_id))
+        .select(team_members::role)
+        .first(conn)
+        .optional()
+}
+
+/// The user's effective role in a session, or `None` without access.
+pub fn session_role(
+    conn: &mut PgConnection,
+    session_id: Uuid,
+    user_id: Uuid,
+) -> QueryResult<Option<String>> {
+    let direct: Option<String> = session_members::table
+        .filter(session_members::session_id.eq(session_id))
+        .filter(session_members::user_id.eq(user_id))
+        .select(session_members::role)
+        .first(conn)
+        .optional()?;
+
+    let via_teams: Vec<String> = session_teams::table
+        .inner_join(team_members::table.on(team_members::team_id.eq(session_teams::team_id)))
+        .filter(session_teams::session_id.eq(session_id))
+        .filter(team_members::user_id.eq(user_id))
+        .select(session_teams::role)
+        .load(conn)?;
+
+    Ok(direct.into_iter().chain(via_teams).reduce(stronger_role))
+}
+
+/// The session and the user's effective role in it, if they have access.
+pub fn accessible_session(
+    conn: &mut PgConnection,
+    session_id: Uuid,
+    user_id: Uuid,
+) -> QueryResult<Option<(Session, String)>> {
+    let Some(role) = session_role(conn, session_id, user_id)? else {
+        return Ok(None);
+    };
+    let session = sessions::table
+        .find(session_id)
+        .select(Session::as_select())
+        .first(conn)
+        .optional()?;
+    Ok(session.map(|s| (s, role)))
+}
+
+/// Sessions shared with any of the user's teams, with the role each share
+/// grants. A session shared with several of their teams appears once per team.
+pub fn team_shared_sessions(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+) -> QueryResult<Vec<(Session, String)>> {
+    sessions::table
+        .inner_join(session_teams::table.on(session_teams::session_id.eq(sessions::id)))
+        .inner_join(team_members::table.on(team_members::team_id.eq(session_teams::team_id)))
+        .filter(team_members::user_id.eq(user_id))
+        .filter(sessions::status.ne("replaced"))
+        .select((Session::as_select(), session_teams::role))
+        .load(conn)
+}
+
+/// Everyone with access to a session: its members and the members of every
+/// team it is shared with.
+pub fn session_audience(conn: &mut PgConnection, session_id: Uuid) -> QueryResult<Vec<Uuid>> {
+    let mut user_ids: Vec<Uuid> = session_members::table
+        .filter(session_members::session_id.eq(session_id))
+        .select(session_members::user_id)
+        .load(conn)?;
+    let team_users: Vec<Uuid> = team_members::table
+        .inner_join(session_teams::table.on(session_teams::team_id.eq(team_members::team_id)))
+        .filter(session_teams::session_id.eq(session_id))
+        .select(team_members::user_id)
+        .load(conn)?;
+    user_ids.extend(team_users);
+    user_ids.sort();
+    user_ids.dedup();
+    Ok(user_ids)
+}
+
+/// Launchers shared with the user through their teams, as (owner, launcher
+/// name, owner's display name). Their own launchers are left out.
+pub fn shared_launchers(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+) -> QueryResult<Vec<(Uuid, String, String)>> {
+    let rows: Vec<(Uuid, String, Option<String>, String)> = team_launchers::table
+        .inner_join(team_members::table.on(team_members::team_id.eq(team_launchers::team_id)))
+        .inner_join(users::table.on(users::id.eq(team_launchers::owner_id)))
+        .filter(team_members::user_id.eq(user_id))
+        .filter(team_launchers::owner_id.ne(user_id))
+        .filter(users::disabled.eq(false))
+        .select((
+            team_launchers::owner_id,
+            team_launchers::launcher_name,
+            users::name,
+            users::email,
+        ))
+        .distinct()
+        .load(conn)?;
+    Ok(rows
+        .into_iter()
+        .map(|(owner, launcher, name, email)| (owner, launcher, name.unwrap_or(email)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stronger_role() {
+        let pick = |a: &str, b: &str| stronger_role(a.to_string(), b.to_string());
+        assert_eq!(pick("viewer", "editor"), "editor");
+        assert_eq!(pick("editor", "viewer"), "editor");
+        assert_eq!(pick("owner", "editor"), "owner");
+        assert_eq!(pick("viewer", "viewer"), "viewer");
+        assert_eq!(pick("bogus", "viewer"), "viewer");
+    }
+
+    #[test]
+    fn test_validate_team_name() {
+        assert_eq!(
+            validate_team_name("  Platform  "),
+            Ok("Platform".to_string())
+        );
+        assert!(validate_team_name("   ").is_err());
+        assert!(validate_team_name(&"x".repeat(MAX_TEAM_NAME_LEN + 1)).is_err());
+        assert!(validate_team_name(&"é".repeat(MAX_TEAM_NAME_LEN)).is_ok());
+    }
+}
@@ -0,0 +1,259 @@
+//! Throttling of password guesses
+//!
+//! The per-IP request rate limits in `main.rs` slow floods, but leave a
+//! single account or share link open to a steady trickle of guesses from many
+//! addresses. A [`GuessThrottle`] counts attempts per target (a username or a
+//! share link) and per client IP: after a few free attempts each further one
+//! must wait twice as long as the last, up to a cap, and a success clears the
+//! target's count. Attempts are refused before any password hashing happens,
+//! so a locked target costs nothing to hammer.
+//!
+//! Counts live in memory and reset when the backend restarts.
+
+use dashmap::DashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::errors::AppError;
+
+/// Entries idle this long are forgotten
+const FORGET_AFTER: Duration = Duration::from_secs(3600);
+/// Above this many entries, idle ones are pruned on the next attempt
+const PRUNE_ABOVE: usize = 10_000;
+
+/// Guesses at one username or share link
+const PER_TARGET: ThrottlePolicy = ThrottlePolicy {
+    free_attempts: 5,
+    first_delay: Duration::from_secs(2),
+    max_delay: Duration::from_secs(15 * 60),
+};
+/// Guesses from one client IP, whatever they target
+const PER_IP: ThrottlePolicy = ThrottlePolicy {
+    free_attempts: 20,
+    first_delay: Duration::from_secs(2),
+    max_delay: Duration::from_secs(15 * 60),
+};
+
+/// How many guesses a key gets before it has to wait
+#[derive(Debug, Clone, Copy)]
+pub struct ThrottlePolicy {
+    pub free_attempts: u32,
+    /// Wait after the first attempt past the free ones; doubles after each
+    pub first_delay: Duration,
+    pub max_delay: Duration,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    attempts: u32,
+    last_attempt: Instant,
+    locked_until: Option<Instant>,
+}
+
+/// Attempt counts for one kind of key
+#[derive(Debug)]
+struct FailureThrottle {
+    policy: ThrottlePolicy,
+    entries: DashMap<String, Entry>,
+}
+
+impl FailureThrottle {
+    fn new(policy: ThrottlePolicy) -> Self {
+        Self {
+            policy,
+            entries: DashMap::new(),
+        }
+    }
+
+    /// How long `key` must wait before its next attempt, if at all
+    fn wait(&self, key: &str, now: Instant) -> Option<Duration> {
+        let entry = self.entries.get(key)?;
+        if now.duration_since(entry.last_attempt) > FORGET_AFTER {
+            return None;
+        }
+        entry
+            .locked_until
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+
+    /// Count an attempt for `key`, locking it once its free attempts are used
+    fn record(&self, key: &str, now: Instant) {
+        if self.entries.len() > PRUNE_ABOVE {
+            self.entries
+                .retain(|_, e| now.duration_since(e.last_attempt) <= FORGET_AFTER);
+        }
+
+        let mut entry = self.entries.entry(key.to_string()).or_insert(Entry {
+            attempts: 0,
+            last_attempt: now,
+            locked_until: None,
+        });
+        if now.duration_since(entry.last_attempt) > FORGET_AFTER {
+            entry.attempts = 0;
+            entry.locked_until = None;
+        }
+        entry.attempts += 1;
+        entry.last_attempt = now;
+        if let Some(over) = entry.attempts.checked_sub(self.policy.free_attempts) {
+            let delay = self
+                .policy
+                .first_delay
+                .saturating_mul(1 << over.min(20))
+                .min(self.policy.max_delay);
+            entry.locked_until = Some(now + delay);
+        }
+    }
+
+    fn clear(&self, key: &str) {
+        self.entries.remove(key);
+    }
+}
+
+/// Attempt counts per target and per client IP
+#[derive(Debug, Clone)]
+pub struct GuessThrottle {
+    targets: Arc<FailureThrottle>,
+    ips: Arc<FailureThrottle>,
+}
+
+/// The policy local sign-in and share link passwords both use
+impl Default for GuessThrottle {
+    fn default() -> Self {
+        Self::new(PER_TARGET, PER_IP)
+    }
+}
+
+impl GuessThrottle {
+    pub fn new(per_target: ThrottlePolicy, per_ip: ThrottlePolicy) -> Self {
+        Self {
+            targets: Arc::new(FailureThrottle::new(per_target)),
+            ips: Arc::new(FailureThrottle::new(per_ip)),
+        }
+    }
+
+    /// Start a guess at `target` from `ip`, refusing it while either has to
+    /// wait. Every attempt counts until [`GuessThrottle::succeeded`] is called.
+    pub fn attempt(&self, target: &str, ip: Option<&str>) -> Result<(), AppError> {
+        self.attempt_at(target, ip, Instant::now())
+    }
+
+    fn attempt_at(&self, target: &str, ip: Option<&str>, now: Instant) -> Result<(), AppError> {
+        let wait = self
+            .targets
+            .wait(target, now)
+            .into_iter()
+            .chain(ip.and_then(|ip| self.ips.wait(ip, now)))
+            .max();
+        if let Some(wait) = wait {
+            return Err(AppError::TooManyAttempts(wait));
+        }
+        self.targets.record(target, now);
+        if let Some(ip) = ip {
+            self.ips.record(ip, now);
+        }
+        Ok(())
+    }
+
+    /// The guess at `target` was right; its earlier attempts no longer count.
+    /// The IP's count is left to expire, so a valid account of an attacker's
+    /// own can't be used to reset it.
+    pub fn succeeded(&self, target: &str) {
+        self.targets.clear(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn throttle() -> GuessThrottle {
+        let policy = |free_attempts| ThrottlePolicy {
+            free_attempts,
+            first_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(8),
+        };
+        GuessThrottle::new(policy(3), policy(5))
+    }
+
+    fn refused_for(result: Result<(), AppError>) -> Option<Duration> {
+        match result {
+            Ok(()) => None,
+            Err(AppError::TooManyAttempts(wait)) => Some(wait),
+            Err(e) => panic!("unexpected error {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_target_locks_after_free_attempts_and_backs_off() {
+        let throttle = throttle();
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert!(throttle.attempt_at("alice", None, start).is_ok());
+        }
+        assert_eq!(
+            refused_for(throttle.attempt_at("alice", None, start)),
+            Some(Duration::from_secs(1))
+        );
+        // Other targets are unaffected
+        assert!(throttle.attempt_at("bob", None, start).is_ok());
+
+        let later = start + Duration::from_secs(1);
+        assert!(throttle.attempt_at("alice", None, later).is_ok());
+        assert_eq!(
+            refused_for(throttle.attempt_at("alice", None, later)),
+            Some(Duration::from_secs(2))
+        );
+
+        // The wait is capped
+        let mut now = later;
+        for _ in 0..6 {
+            now += Duration::from_secs(8);
+            assert!(throttle.attempt_at("alice", None, now).is_ok());
+        }
+        assert_eq!(
+            refused_for(throttle.attempt_at("alice", None, now)),
+            Some(Duration::from_secs(8))
+        );
+    }
+
+    #[test]
+    fn test_ip_locks_across_targets() {
+        let throttle = throttle();
+        let now = Instant::now();
+        for target in ["a", "b", "c", "d", "e"] {
+            assert!(throttle
+                .attempt_at(target, Some("203.0.113.9"), now)
+                .is_ok());
+        }
+        assert!(refused_for(throttle.attempt_at("f", Some("203.0.113.9"), now)).is_some());
+        assert!(throttle.attempt_at("f", Some("198.51.100.2"), now).is_ok());
+    }
+
+    #[test]
+    fn test_success_clears_target_but_not_ip() {
+        let throttle = throttle();
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(throttle.attempt_at("alice", Some("ip"), now).is_ok());
+        }
+        throttle.succeeded("alice");
+        assert!(throttle.attempt_at("alice", Some("ip"), now).is_ok());
+        assert!(throttle.attempt_at("alice", Some("ip"), now).is_ok());
+        // Six attempts from the IP so far, over its five free ones
+        assert!(refused_for(throttle.attempt_at("bob", Some("ip"), now)).is_some());
+    }
+
+    #[test]
+    fn test_idle_entries_are_forgotten() {
+        let throttle = throttle();
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert!(throttle.attempt_at("alice", None, start).is_ok());
+        }
+        let later = start + FORGET_AFTER + Duration::from_secs(1);
+        for _ in 0..3 {
+            assert!(throttle.attempt_at("alice", None, later).is_ok());
+        }
+    }
+}
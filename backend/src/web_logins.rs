@@ -0,0 +1,232 @@
+//! Browser logins
+//!
+//! Each browser sign-in gets a row in `web_logins`, and the signed
+//! `cc_session` cookie carries the row's id rather than the user's.
+//! `auth::current_login` looks the row up on every request, so deleting it
+//! signs that browser out: users list their logins under Settings and revoke
+//! one or all of them, and the handler then closes the client websockets the
+//! login opened (see `SessionManager::end_web_login`).
+//!
+//! Logins unused for [`IDLE_DAYS`] stop working and are pruned the next time
+//! their user signs in.
+
+use axum::http::HeaderMap;
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use shared::api::WebLoginInfo;
+use std::net::SocketAddr;
+use uuid::Uuid;
+
+use crate::models::{NewWebLogin, WebLogin};
+use crate::schema::web_logins;
+
+/// Days a login may go unused before it is signed out
+pub const IDLE_DAYS: i64 = 90;
+/// `last_seen_at` is only rewritten when older than this
+const LAST_SEEN_GRANULARITY_SECS: i64 = 60;
+
+/// Where a browser is signing in from
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl ClientInfo {
+    pub fn new(
+        headers: &HeaderMap,
+        peer: SocketAddr,
+        trusted: &crate::client_ip::TrustedProxies,
+    ) -> Self {
+        Self {
+            ip_address: crate::client_ip::client_ip(headers, Some(peer), trusted),
+            user_agent: crate::client_ip::user_agent(headers),
+        }
+    }
+}
+
+/// "Browser on OS" from a user agent, for telling logins apart at a glance
+pub fn describe_device(user_agent: Option<&str>) -> String {
+    let Some(ua) = user_agent.filter(|ua| !ua.is_empty()) else {
+        return "Unknown device".to_string();
+    };
+
+    // Order matters: Edge and Opera also claim Chrome, Chrome claims Safari
+    let browser = [
+        ("Edg/", "Edge"),
+        ("OPR/", "Opera"),
+        ("Firefox/", "Firefox"),
+        ("Chrome/", "Chrome"),
+        ("CriOS/", "Chrome"),
+        ("Safari/", "Safari"),
+    ]
+    .iter()
+    .find(|(token, _)| ua.contains(token))
+    .map(|(_, name)| *name);
+
+    let os = [
+        ("iPhone", "iOS"),
+        ("iPad", "iPadOS"),
+        ("Android", "Android"),
+        ("CrOS", "ChromeOS"),
+        ("Mac OS X", "macOS"),
+        ("Windows", "Windows"),
+        ("Linux", "Linux"),
+    ]
+    .iter()
+    .find(|(token, _)| ua.contains(token))
+    .map(|(_, name)| *name);
+
+    match (browser, os) {
+        (Some(browser), Some(os)) => format!("{} on {}", browser, os),
+        (Some(name), None) | (None, Some(name)) => name.to_string(),
+        (None, None) => ua.split('/').next().unwrap_or(ua).trim().to_string(),
+    }
+}
+
+fn idle_cutoff(now: NaiveDateTime) -> NaiveDateTime {
+    now - chrono::Duration::days(IDLE_DAYS)
+}
+
+/// Record a new sign-in for `user_id` and return the login's id, dropping
+/// the user's logins that have gone idle
+pub fn create(conn: &mut PgConnection, user_id: Uuid, client: &ClientInfo) -> QueryResult<Uuid> {
+    diesel::delete(
+        web_logins::table
+            .filter(web_logins::user_id.eq(user_id))
+            .filter(web_logins::last_seen_at.lt(idle_cutoff(Utc::now().naive_utc()))),
+    )
+    .execute(conn)?;
+
+    diesel::insert_into(web_logins::table)
+        .values(&NewWebLogin {
+            user_id,
+            ip_address: client.ip_address.clone(),
+            user_agent: client.user_agent.clone(),
+        })
+        .returning(web_logins::id)
+        .get_result(conn)
+}
+
+/// The login if it is still signed in, noting that it was just used
+pub fn find_active(conn: &mut PgConnection, login_id: Uuid) -> QueryResult<Option<WebLogin>> {
+    let now = Utc::now().naive_utc();
+    let login: Option<WebLogin> = web_logins::table
+        .find(login_id)
+        .filter(web_logins::last_seen_at.gt(idle_cutoff(now)))
+        .select(WebLogin::as_select())
+        .first(conn)
+        .optional()?;
+
+    if let Some(login) = &login {
+        if login.last_seen_at < now - chrono::Duration::seconds(LAST_SEEN_GRANULARITY_SECS) {
+            let _ = diesel::update(web_logins::table.find(login.id))
+                .set(web_logins::last_seen_at.eq(now))
+                .execute(conn);
+        }
+    }
+    Ok(login)
+}
+
+/// The user's signed-in browsers, most recently used first
+pub fn list(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    current: Option<Uuid>,
+) -> QueryResult<Vec<WebLoginInfo>> {
+    let logins: Vec<WebLogin> = web_logins::table
+        .filter(web_logins::user_id.eq(user_id))
+        .filter(web_logins::last_seen_at.gt(idle_cutoff(Utc::now().naive_utc())))
+        .order(web_logins::last_seen_at.desc())
+        .select(WebLogin::as_select())
+        .load(conn)?;
+    Ok(logins
+        .into_iter()
+        .map(|login| info(login, current))
+        .collect())
+}
+
+pub fn info(login: WebLogin, current: Option<Uuid>) -> WebLoginInfo {
+    WebLoginInfo {
+        id: login.id,
+        device: describe_device(login.user_agent.as_deref()),
+        current: current == Some(login.id),
+        ip_address: login.ip_address,
+        user_agent: login.user_agent,
+        created_at: login.created_at.and_utc().to_rfc3339(),
+        last_seen_at: login.last_seen_at.and_utc().to_rfc3339(),
+    }
+}
+
+/// Sign one of the user's browsers out. Returns the login, if it existed.
+pub fn revoke(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    login_id: Uuid,
+) -> QueryResult<Option<WebLogin>> {
+    diesel::delete(
+        web_logins::table
+            .find(login_id)
+            .filter(web_logins::user_id.eq(user_id)),
+    )
+    .returning(WebLogin::as_returning())
+    .get_result(conn)
+    .optional()
+}
+
+/// Sign every one of the user's browsers out
+pub fn revoke_all(conn: &mut PgConnection, user_id: Uuid) -> QueryResult<Vec<WebLogin>> {
+    revoke_all_except(conn, user_id, None)
+}
+
+/// Sign the user's browsers out, apart from `keep`
+pub fn revoke_all_except(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    keep: Option<Uuid>,
+) -> QueryResult<Vec<WebLogin>> {
+    let mut query = diesel::delete(web_logins::table)
+        .filter(web_logins::user_id.eq(user_id))
+        .into_boxed();
+    if let Some(keep) = keep {
+        query = query.filter(web_logins::id.ne(keep));
+    }
+    query.returning(WebLogin::as_returning()).get_results(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_device() {
+        let cases = [
+            (
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_4) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Safari/605.1.15",
+                "Safari on macOS",
+            ),
+            (
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36 Edg/124.0.0.0",
+                "Edge on Windows",
+            ),
+            (
+                "Mozilla/5.0 (X11; Linux x86_64; rv:125.0) Gecko/20100101 Firefox/125.0",
+                "Firefox on Linux",
+            ),
+            (
+                "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Mobile Safari/537.36",
+                "Chrome on Android",
+            ),
+            (
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) CriOS/124.0 Mobile/15E148 Safari/604.1",
+                "Chrome on iOS",
+            ),
+            ("curl/8.5.0", "curl"),
+        ];
+        for (ua, expected) in cases {
+            assert_eq!(describe_device(Some(ua)), expected, "{}", ua);
+        }
+        assert_eq!(describe_device(None), "Unknown device");
+        assert_eq!(describe_device(Some("")), "Unknown device");
+    }
+}
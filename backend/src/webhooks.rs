@@ -0,0 +1,834 @@
+//! Outbound webhooks
+//!
+//! Users register HTTP endpoints that receive a signed JSON payload when one
+//! of their sessions finishes a turn, asks for permission or exits, and when a
+//! scheduled run completes. The websocket handlers queue events as rows in
+//! `webhook_deliveries`; a background worker sends them and retries failures
+//! with exponential backoff until they succeed or run out of attempts.
+//!
+//! Every request carries `X-Portal-Signature: sha256=<hex>`, the HMAC-SHA256
+//! of `"<timestamp>.<body>"` keyed with the webhook's secret, where the
+//! timestamp is the `X-Portal-Timestamp` header (Unix seconds).
+//!
+//! Receivers must be public: URLs that point at loopback, private,
+//! link-local or other internal addresses are refused when a webhook is saved
+//! and again at delivery, the client resolves names only to public addresses
+//! and redirects are not followed. Admins can open up internal receivers with
+//! `WEBHOOK_ALLOWED_NETWORKS`, a comma-separated list of addresses, CIDR
+//! ranges and host names.
+
+use diesel::prelude::*;
+use futures_util::future::BoxFuture;
+use hmac::{Hmac, Mac};
+use ipnet::IpNet;
+use sha2::Sha256;
+use shared::api::WebhookEvent;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{Notify, Semaphore};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::models::{NewWebhookDelivery, Webhook, WebhookDelivery};
+use crate::schema::{webhook_deliveries, webhooks};
+
+/// Give up on a delivery after this many attempts
+pub const MAX_ATTEMPTS: i32 = 6;
+/// Time allowed for the receiver to answer
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often the worker looks for due retries when nothing wakes it
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+/// Deliveries handled per worker pass
+const BATCH_SIZE: i64 = 50;
+/// Webhooks delivered to at once. Each webhook's deliveries are sent in
+/// order by their own task, so a slow receiver only holds up itself.
+const MAX_CONCURRENT_WEBHOOKS: usize = 8;
+/// Finished deliveries are kept this long for the delivery log
+const DELIVERY_RETENTION_DAYS: i64 = 14;
+
+pub const EVENT_HEADER: &str = "X-Portal-Event";
+pub const DELIVERY_HEADER: &str = "X-Portal-Delivery";
+pub const TIMESTAMP_HEADER: &str = "X-Portal-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-Portal-Signature";
+
+/// Compute the `X-Portal-Signature` value for a payload.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Generate a new signing secret.
+pub fn generate_secret() -> String {
+    use rand::RngCore;
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("whsec_{}", hex::encode(bytes))
+}
+
+/// Delay before the next attempt after `attempts` failed ones:
+/// 30s, 1m, 2m, 4m, 8m, capped at one hour.
+pub fn retry_delay(attempts: i32) -> chrono::Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 7) as u32;
+    chrono::Duration::seconds((30i64 << exponent).min(3600))
+}
+
+const PRIVATE_DESTINATION: &str =
+    "Webhook URL must point at a public address, not a loopback, private or link-local one";
+
+/// Whether an address is reachable on the public internet. Loopback,
+/// private, link-local (including cloud metadata), shared, unspecified,
+/// multicast and documentation ranges are not.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || v4.is_documentation()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 192 && b == 0 && v4.octets()[2] == 0)
+                || a >= 240)
+        }
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public_ip(IpAddr::V4(v4)),
+            None => {
+                !(v6.is_loopback()
+                    || v6.is_unspecified()
+                    || v6.is_multicast()
+                    || v6.is_unique_local()
+                    || v6.is_unicast_link_local()
+                    || v6.segments()[0] == 0x2001 && v6.segments()[1] == 0x0db8)
+            }
+        },
+    }
+}
+
+/// Internal receivers allowed despite not being public, from
+/// `WEBHOOK_ALLOWED_NETWORKS`. A host name listed here may resolve anywhere;
+/// any other name must resolve to public or listed addresses.
+#[derive(Debug, Clone, Default)]
+pub struct AllowedDestinations {
+    networks: Arc<Vec<IpNet>>,
+    hosts: Arc<Vec<String>>,
+}
+
+impl AllowedDestinations {
+    /// Comma-separated addresses, CIDR ranges and host names
+    pub fn parse(list: &str) -> Result<Self, String> {
+        let mut networks = Vec::new();
+        let mut hosts = Vec::new();
+        for item in list.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+            if let Ok(net) = item.parse::<IpNet>() {
+                networks.push(net);
+            } else if let Ok(ip) = item.parse::<IpAddr>() {
+                networks.push(IpNet::from(ip));
+            } else if item
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+            {
+                hosts.push(normalize_host(item));
+            } else {
+                return Err(format!(
+                    "{:?} is not an IP address, CIDR range or host name",
+                    item
+                ));
+            }
+        }
+        Ok(Self {
+            networks: Arc::new(networks),
+            hosts: Arc::new(hosts),
+        })
+    }
+
+    pub fn from_env() -> anyhow::Result<Self> {
+        Self::parse(&std::env::var("WEBHOOK_ALLOWED_NETWORKS").unwrap_or_default())
+            .map_err(|e| anyhow::anyhow!("WEBHOOK_ALLOWED_NETWORKS: {}", e))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.networks.is_empty() && self.hosts.is_empty()
+    }
+
+    /// Whether requests may go to this address
+    fn permits_ip(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        is_public_ip(ip) || self.networks.iter().any(|net| net.contains(&ip))
+    }
+
+    fn permits_host(&self, host: &str) -> bool {
+        self.hosts.contains(&normalize_host(host))
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Check that a webhook URL is an absolute http(s) URL that doesn't name an
+/// internal address outright. Host names are checked by [`check_destination`].
+pub fn validate_url(url: &str, allowed: &AllowedDestinations) -> Result<(), &'static str> {
+    let parsed = match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => parsed,
+        _ => return Err("Webhook URL must be an absolute http:// or https:// URL"),
+    };
+    match literal_ip(&parsed) {
+        Some(ip) if !allowed.permits_ip(ip) => Err(PRIVATE_DESTINATION),
+        _ => Ok(()),
+    }
+}
+
+/// The URL's host, if it is an IP address rather than a name
+fn literal_ip(url: &reqwest::Url) -> Option<IpAddr> {
+    let host = url.host_str()?;
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+/// Resolve `host` to its addresses, refusing it if any of them is internal
+/// and not allowed
+async fn resolve_public(
+    host: &str,
+    port: u16,
+    allowed: &AllowedDestinations,
+) -> Result<Vec<SocketAddr>, &'static str> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|_| "Webhook URL host could not be resolved")?
+        .collect();
+    if addrs.is_empty() {
+        return Err("Webhook URL host could not be resolved");
+    }
+    if !allowed.permits_host(host) && addrs.iter().any(|addr| !allowed.permits_ip(addr.ip())) {
+        return Err(PRIVATE_DESTINATION);
+    }
+    Ok(addrs)
+}
+
+/// [`validate_url`], then resolve the host and check where it points.
+/// Used when a webhook or chat bridge is saved and before each delivery.
+pub async fn check_destination(
+    url: &str,
+    allowed: &AllowedDestinations,
+) -> Result<(), &'static str> {
+    validate_url(url, allowed)?;
+    let parsed = reqwest::Url::parse(url).map_err(|_| PRIVATE_DESTINATION)?;
+    match (literal_ip(&parsed), parsed.host_str()) {
+        (None, Some(domain)) => {
+            let port = parsed.port_or_known_default().unwrap_or(443);
+            resolve_public(domain, port, allowed).await?;
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// DNS resolver that only hands out public or allowed addresses, so a name
+/// that is re-pointed at an internal address after it was checked still
+/// can't be reached
+struct PublicResolver(AllowedDestinations);
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let allowed = self.0.clone();
+        let fut: BoxFuture<'static, _> = Box::pin(async move {
+            let addrs = resolve_public(name.as_str(), 0, &allowed).await?;
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        });
+        fut
+    }
+}
+
+/// HTTP client for posting to user-supplied URLs: public or allowed
+/// addresses only and no redirects
+pub fn outbound_client(
+    user_agent: &'static str,
+    timeout: Duration,
+    allowed: AllowedDestinations,
+) -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .user_agent(user_agent)
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicResolver(allowed)))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Wrap event data in the envelope every receiver gets.
+fn envelope(event: WebhookEvent, data: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "event": event.as_str(),
+        "created_at": chrono::Utc::now().to_rfc3339(),
+        "data": data,
+    })
+}
+
+/// Result of a single HTTP attempt
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttemptOutcome {
+    /// HTTP status, if the receiver answered at all
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+impl AttemptOutcome {
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// POST a signed payload to a receiver. Any 2xx response counts as delivered.
+pub async fn post_signed(
+    client: &reqwest::Client,
+    url: &str,
+    secret: &str,
+    delivery_id: Uuid,
+    event: &str,
+    body: String,
+) -> AttemptOutcome {
+    let timestamp = chrono::Utc::now().timestamp();
+    let signature = sign(secret, timestamp, &body);
+    let result = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, event)
+        .header(DELIVERY_HEADER, delivery_id.to_string())
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, signature)
+        .body(body)
+        .send()
+        .await;
+
+    match result {
+        Ok(response) if response.status().is_success() => AttemptOutcome {
+            status: Some(response.status().as_u16()),
+            error: None,
+        },
+        Ok(response) => AttemptOutcome {
+            status: Some(response.status().as_u16()),
+            error: Some(format!("Receiver answered HTTP {}", response.status())),
+        },
+        Err(e) => AttemptOutcome {
+            status: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+/// Queues webhook events and delivers them in the background
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    db_pool: DbPool,
+    client: reqwest::Client,
+    allowed: AllowedDestinations,
+    wake: Arc<Notify>,
+    /// Webhooks whose deliveries a task is currently sending
+    in_flight: Arc<Mutex<HashSet<Uuid>>>,
+    slots: Arc<Semaphore>,
+}
+
+impl WebhookDispatcher {
+    pub fn new(db_pool: DbPool, allowed: AllowedDestinations) -> Self {
+        Self {
+            db_pool,
+            client: outbound_client(
+                concat!("agent-portal-webhooks/", env!("CARGO_PKG_VERSION")),
+                REQUEST_TIMEOUT,
+                allowed.clone(),
+            ),
+            allowed,
+            wake: Arc::new(Notify::new()),
+            in_flight: Arc::new(Mutex::new(HashSet::new())),
+            slots: Arc::new(Semaphore::new(MAX_CONCURRENT_WEBHOOKS)),
+        }
+    }
+
+    /// Queue an event for every enabled webhook of `user_id` subscribed to it.
+    pub fn dispatch(&self, user_id: Uuid, event: WebhookEvent, data: serde_json::Value) {
+        let mut conn = match self.db_pool.get() {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!("Failed to get database connection for webhooks: {}", e);
+                return;
+            }
+        };
+
+        let subscribed: Vec<Uuid> = match webhooks::table
+            .filter(webhooks::user_id.eq(user_id))
+            .filter(webhooks::enabled.eq(true))
+            .filter(webhooks::events.contains(serde_json::json!([event.as_str()])))
+            .select(webhooks::id)
+            .load(&mut conn)
+        {
+            Ok(ids) => ids,
+            Err(e) => {
+                error!("Failed to load webhooks: {}", e);
+                return;
+            }
+        };
+        if subscribed.is_empty() {
+            return;
+        }
+
+        self.enqueue(&mut conn, &subscribed, event, data);
+    }
+
+    /// Queue an event for the owner of a session.
+    pub fn dispatch_for_session(
+        &self,
+        session_id: Uuid,
+        event: WebhookEvent,
+        data: serde_json::Value,
+    ) {
+        use crate::schema::sessions;
+        let owner = self.db_pool.get().ok().and_then(|mut conn| {
+            sessions::table
+                .find(session_id)
+                .select(sessions::user_id)
+                .first::<Uuid>(&mut conn)
+                .ok()
+        });
+        if let Some(user_id) = owner {
+            self.dispatch(user_id, event, data);
+        }
+    }
+
+    /// Queue a test delivery to one webhook, regardless of its subscriptions.
+    pub fn ping(&self, webhook: &Webhook) {
+        if let Ok(mut conn) = self.db_pool.get() {
+            self.enqueue(
+                &mut conn,
+                &[webhook.id],
+                WebhookEvent::Ping,
+                serde_json::json!({ "webhook_id": webhook.id }),
+            );
+        }
+    }
+
+    fn enqueue(
+        &self,
+        conn: &mut PgConnection,
+        webhook_ids: &[Uuid],
+        event: WebhookEvent,
+        data: serde_json::Value,
+    ) {
+        let payload = envelope(event, data);
+        let rows: Vec<NewWebhookDelivery> = webhook_ids
+            .iter()
+            .map(|id| NewWebhookDelivery {
+                webhook_id: *id,
+                event: event.as_str().to_string(),
+                payload: payload.clone(),
+            })
+            .collect();
+        match diesel::insert_into(webhook_deliveries::table)
+            .values(&rows)
+            .execute(conn)
+        {
+            Ok(_) => self.wake.notify_one(),
+            Err(e) => error!("Failed to queue webhook deliveries: {}", e),
+        }
+    }
+
+    /// Deliver queued events until the process exits.
+    pub async fn run(self) {
+        let mut last_prune = Instant::now();
+        loop {
+            tokio::select! {
+                _ = self.wake.notified() => {}
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            }
+            self.deliver_due().await;
+
+            if last_prune.elapsed() > Duration::from_secs(3600) {
+                self.prune_old_deliveries();
+                last_prune = Instant::now();
+            }
+        }
+    }
+
+    /// Hand every pending delivery whose next attempt is due to a task for
+    /// its webhook, skipping webhooks that already have one running.
+    async fn deliver_due(&self) {
+        let busy: Vec<Uuid> = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .copied()
+            .collect();
+        let due: Vec<(WebhookDelivery, Webhook)> = {
+            let Ok(mut conn) = self.db_pool.get() else {
+                error!("Failed to get database connection for webhook delivery");
+                return;
+            };
+            match webhook_deliveries::table
+                .inner_join(webhooks::table)
+                .filter(webhook_deliveries::status.eq("pending"))
+                .filter(webhook_deliveries::next_attempt_at.le(diesel::dsl::now))
+                .filter(webhook_deliveries::webhook_id.ne_all(&busy))
+                .order(webhook_deliveries::next_attempt_at.asc())
+                .limit(BATCH_SIZE)
+                .select((WebhookDelivery::as_select(), Webhook::as_select()))
+                .load(&mut conn)
+            {
+                Ok(rows) => rows,
+                Err(e) => {
+                    error!("Failed to load due webhook deliveries: {}", e);
+                    return;
+                }
+            }
+        };
+
+        let mut by_webhook: HashMap<Uuid, (Webhook, Vec<WebhookDelivery>)> = HashMap::new();
+        for (delivery, webhook) in due {
+            by_webhook
+                .entry(webhook.id)
+                .or_insert_with(|| (webhook, Vec::new()))
+                .1
+                .push(delivery);
+        }
+
+        for (webhook_id, (webhook, deliveries)) in by_webhook {
+            self.in_flight
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(webhook_id);
+            let this = self.clone();
+            tokio::spawn(async move {
+                if let Ok(_permit) = this.slots.clone().acquire_owned().await {
+                    this.deliver_to(&webhook, deliveries).await;
+                }
+                this.in_flight
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(&webhook_id);
+                this.wake.notify_one();
+            });
+        }
+    }
+
+    /// Send one webhook's due deliveries, oldest first
+    async fn deliver_to(&self, webhook: &Webhook, deliveries: Vec<WebhookDelivery>) {
+        let refused = if webhook.enabled {
+            check_destination(&webhook.url, &self.allowed).await.err()
+        } else {
+            Some("Webhook is disabled")
+        };
+
+        for delivery in deliveries {
+            let outcome = match refused {
+                Some(reason) => AttemptOutcome {
+                    status: None,
+                    error: Some(reason.to_string()),
+                },
+                None => {
+                    post_signed(
+                        &self.client,
+                        &webhook.url,
+                        &webhook.secret,
+                        delivery.id,
+                        &delivery.event,
+                        delivery.payload.to_string(),
+                    )
+                    .await
+                }
+            };
+            self.record_attempt(&delivery, refused.is_none(), outcome);
+        }
+    }
+
+    fn record_attempt(&self, delivery: &WebhookDelivery, retry: bool, outcome: AttemptOutcome) {
+        let Ok(mut conn) = self.db_pool.get() else {
+            error!("Failed to get database connection to record webhook attempt");
+            return;
+        };
+
+        let attempts = delivery.attempts + 1;
+        let now = chrono::Utc::now().naive_utc();
+        let status = if outcome.is_success() {
+            "succeeded"
+        } else if !retry || attempts >= MAX_ATTEMPTS {
+            "failed"
+        } else {
+            "pending"
+        };
+        match status {
+            "succeeded" => info!(
+                "Delivered webhook {} ({}) on attempt {}",
+                delivery.id, delivery.event, attempts
+            ),
+            "failed" => warn!(
+                "Giving up on webhook {} ({}) after {} attempt(s): {}",
+                delivery.id,
+                delivery.event,
+                attempts,
+                outcome.error.as_deref().unwrap_or_default()
+            ),
+            _ => warn!(
+                "Webhook {} ({}) attempt {} failed, will retry: {}",
+                delivery.id,
+                delivery.event,
+                attempts,
+                outcome.error.as_deref().unwrap_or_default()
+            ),
+        }
+
+        if let Err(e) = diesel::update(webhook_deliveries::table.find(delivery.id))
+            .set((
+                webhook_deliveries::status.eq(status),
+                webhook_deliveries::attempts.eq(attempts),
+                webhook_deliveries::response_status.eq(outcome.status.map(i32::from)),
+                webhook_deliveries::last_error.eq(&outcome.error),
+                webhook_deliveries::next_attempt_at.eq(now + retry_delay(attempts)),
+                webhook_deliveries::delivered_at.eq(outcome.is_success().then_some(now)),
+            ))
+            .execute(&mut conn)
+        {
+            error!("Failed to record webhook attempt: {}", e);
+        }
+    }
+
+    fn prune_old_deliveries(&self) {
+        let Ok(mut conn) = self.db_pool.get() else {
+            return;
+        };
+        let cutoff =
+            chrono::Utc::now().naive_utc() - chrono::Duration::days(DELIVERY_RETENTION_DAYS);
+        match diesel::delete(
+            webhook_deliveries::table
+                .filter(webhook_deliveries::status.ne("pending"))
+                .filter(webhook_deliveries::created_at.lt(cutoff)),
+        )
+        .execute(&mut conn)
+        {
+            Ok(0) => {}
+            Ok(n) => info!("Pruned {} old webhook deliveries", n),
+            Err(e) => error!("Failed to prune webhook deliveries: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::HeaderMap, http::StatusCode, routing::post, Router};
+    use tokio::sync::mpsc;
+
+    /// Start a local receiver that records each request and answers `status`.
+    async fn start_receiver(
+        status: StatusCode,
+    ) -> (String, mpsc::UnboundedReceiver<(HeaderMap, String)>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let app = Router::new().route(
+            "/hook",
+            post(move |headers: HeaderMap, body: String| {
+                let tx = tx.clone();
+                async move {
+                    let _ = tx.send((headers, body));
+                    status
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/hook", addr), rx)
+    }
+
+    #[tokio::test]
+    async fn test_post_signed_delivers_verifiable_payload() {
+        let (url, mut received) = start_receiver(StatusCode::NO_CONTENT).await;
+        let secret = generate_secret();
+        let delivery_id = Uuid::new_v4();
+        let body = envelope(
+            WebhookEvent::TurnCompleted,
+            serde_json::json!({"session_id": Uuid::nil()}),
+        )
+        .to_string();
+
+        let outcome = post_signed(
+            &reqwest::Client::new(),
+            &url,
+            &secret,
+            delivery_id,
+            WebhookEvent::TurnCompleted.as_str(),
+            body.clone(),
+        )
+        .await;
+        assert!(outcome.is_success());
+        assert_eq!(outcome.status, Some(204));
+
+        let (headers, received_body) = received.recv().await.unwrap();
+        assert_eq!(received_body, body);
+        assert_eq!(headers[EVENT_HEADER], "session.turn_completed");
+        assert_eq!(headers[DELIVERY_HEADER], delivery_id.to_string().as_str());
+        let timestamp: i64 = headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+        assert_eq!(
+            headers[SIGNATURE_HEADER].to_str().unwrap(),
+            sign(&secret, timestamp, &received_body)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_signed_reports_receiver_errors() {
+        let (url, _received) = start_receiver(StatusCode::INTERNAL_SERVER_ERROR).await;
+        let outcome = post_signed(
+            &reqwest::Client::new(),
+            &url,
+            "secret",
+            Uuid::nil(),
+            "ping",
+            "{}".to_string(),
+        )
+        .await;
+        assert!(!outcome.is_success());
+        assert_eq!(outcome.status, Some(500));
+    }
+
+    #[test]
+    fn test_sign_depends_on_secret_timestamp_and_body() {
+        let base = sign("secret", 1_700_000_000, "{}");
+        assert!(base.starts_with("sha256="));
+        assert_eq!(base.len(), "sha256=".len() + 64);
+        assert_ne!(base, sign("other", 1_700_000_000, "{}"));
+        assert_ne!(base, sign("secret", 1_700_000_001, "{}"));
+        assert_ne!(base, sign("secret", 1_700_000_000, "[]"));
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_and_caps() {
+        assert_eq!(retry_delay(1).num_seconds(), 30);
+        assert_eq!(retry_delay(2).num_seconds(), 60);
+        assert_eq!(retry_delay(5).num_seconds(), 480);
+        assert_eq!(retry_delay(20).num_seconds(), 3600);
+    }
+
+    #[test]
+    fn test_validate_url() {
+        let none = AllowedDestinations::default();
+        assert!(validate_url("https://example.com/hooks/portal", &none).is_ok());
+        assert!(validate_url("https://93.184.216.34/hook", &none).is_ok());
+        assert!(validate_url("ftp://example.com", &none).is_err());
+        assert!(validate_url("/relative", &none).is_err());
+    }
+
+    #[test]
+    fn test_validate_url_rejects_internal_addresses() {
+        for url in [
+            "http://127.0.0.1",
+            "http://127.0.0.1:9000/hook",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://[::1]",
+            "http://[::ffff:127.0.0.1]/",
+            "http://10.0.0.5/hook",
+            "http://192.168.1.1/",
+            "http://172.16.0.1/",
+            "http://100.64.0.1/",
+            "http://0.0.0.0/",
+            "http://[fd00:ec2::254]/",
+            "http://[fe80::1]/",
+        ] {
+            assert_eq!(
+                validate_url(url, &AllowedDestinations::default()),
+                Err(PRIVATE_DESTINATION),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_destination_rejects_internal_addresses() {
+        for url in [
+            "http://127.0.0.1",
+            "http://169.254.169.254",
+            "http://[::1]",
+            "http://localhost:8080/hook",
+        ] {
+            assert_eq!(
+                check_destination(url, &AllowedDestinations::default()).await,
+                Err(PRIVATE_DESTINATION),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_outbound_client_refuses_internal_names() {
+        let (url, _received) = start_receiver(StatusCode::NO_CONTENT).await;
+        let url = url.replace("127.0.0.1", "localhost");
+        let outcome = post_signed(
+            &outbound_client("test", REQUEST_TIMEOUT, AllowedDestinations::default()),
+            &url,
+            "secret",
+            Uuid::nil(),
+            "ping",
+            "{}".to_string(),
+        )
+        .await;
+        assert!(!outcome.is_success());
+        assert_eq!(outcome.status, None);
+    }
+
+    #[test]
+    fn test_allowed_destinations_parse() {
+        let allowed =
+            AllowedDestinations::parse(" 10.0.0.0/8, 127.0.0.1 ,Hooks.Internal. ").unwrap();
+        assert!(allowed.permits_ip("10.1.2.3".parse().unwrap()));
+        assert!(allowed.permits_ip("::ffff:127.0.0.1".parse().unwrap()));
+        assert!(!allowed.permits_ip("192.168.1.1".parse().unwrap()));
+        assert!(allowed.permits_host("hooks.internal"));
+        assert!(!allowed.permits_host("internal"));
+        assert!(AllowedDestinations::parse("").unwrap().is_empty());
+        assert!(AllowedDestinations::parse("10.0.0.0/33").is_err());
+        assert!(AllowedDestinations::parse("http://hooks").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_allowed_destinations_open_internal_receivers() {
+        let allowed = AllowedDestinations::parse("10.0.0.0/8,localhost").unwrap();
+        assert!(validate_url("http://10.0.0.5/hook", &allowed).is_ok());
+        assert_eq!(
+            validate_url("http://192.168.1.1/", &allowed),
+            Err(PRIVATE_DESTINATION)
+        );
+        assert!(check_destination("http://localhost:8080/hook", &allowed)
+            .await
+            .is_ok());
+        assert_eq!(
+            check_destination("http://127.0.0.1/", &allowed).await,
+            Err(PRIVATE_DESTINATION)
+        );
+
+        // The delivery client honors the same list
+        let (url, mut received) = start_receiver(StatusCode::NO_CONTENT).await;
+        let url = url.replace("127.0.0.1", "localhost");
+        let outcome = post_signed(
+            &outbound_client("test", REQUEST_TIMEOUT, allowed),
+            &url,
+            "secret",
+            Uuid::nil(),
+            "ping",
+            "{}".to_string(),
+        )
+        .await;
+        assert!(outcome.is_success(), "{:?}", outcome);
+        assert!(received.try_recv().is_ok());
+    }
+}
@@ -32,7 +32,7 @@ pub use snapshot::{PendingPermission, SessionConfig, SessionSnapshot};
 
 // Re-export proxy session types
 pub use proxy_session::{
-    run_connection_loop, ConnectionResult, LoopResult, ProxySessionConfig, SessionState,
+    run_connection_loop, ConnectionResult, LoopResult, ProxySessionConfig, SessionState, UserInput,
 };
 
 // Re-export claude_codes types that appear in our public API
@@ -13,7 +13,7 @@ use anyhow::Result;
 use claude_codes::ClaudeOutput;
 use shared::{ProxyToServer, ServerToProxy, SessionEndpoint};
 use tokio::sync::{mpsc, Mutex};
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, warn, Instrument};
 use uuid::Uuid;
 
 use output_forwarder::{get_git_branch, get_pr_url, get_repo_url, spawn_output_forwarder};
@@ -131,6 +131,17 @@ pub enum LoopResult {
     SessionNotFound,
 }
 
+/// User input on its way from the WebSocket reader to Claude
+#[derive(Debug)]
+pub struct UserInput {
+    pub text: String,
+    /// Trace context of the span that received the input, if it was traced
+    pub traceparent: Option<String>,
+}
+
+/// Trace context of the turn in progress, stamped on its output
+type TurnTrace = Arc<std::sync::Mutex<Option<String>>>;
+
 /// Permission response data (from frontend to Claude)
 #[derive(Debug)]
 pub struct PermissionResponseData {
@@ -154,9 +165,9 @@ pub struct SessionState<'a> {
     /// Claude session from claude-session-lib
     pub claude_session: &'a mut ClaudeSession,
     /// Sender for input messages (cloned per connection)
-    pub input_tx: mpsc::UnboundedSender<String>,
+    pub input_tx: mpsc::UnboundedSender<UserInput>,
     /// Receiver for input messages (persists across connections)
-    pub input_rx: &'a mut mpsc::UnboundedReceiver<String>,
+    pub input_rx: &'a mut mpsc::UnboundedReceiver<UserInput>,
     /// Output buffer with persistence
     pub output_buffer: Arc<Mutex<PendingOutputBuffer>>,
     /// Backoff state for reconnection
@@ -167,6 +178,8 @@ pub struct SessionState<'a> {
     pub disconnected_at: Option<Instant>,
     /// Whether the last disconnect was a graceful server shutdown
     pub last_disconnect_graceful: bool,
+    /// Trace of the turn in progress (kept across reconnections)
+    turn_trace: TurnTrace,
 }
 
 impl<'a> SessionState<'a> {
@@ -174,8 +187,8 @@ impl<'a> SessionState<'a> {
     pub fn new(
         config: &'a ProxySessionConfig,
         claude_session: &'a mut ClaudeSession,
-        input_tx: mpsc::UnboundedSender<String>,
-        input_rx: &'a mut mpsc::UnboundedReceiver<String>,
+        input_tx: mpsc::UnboundedSender<UserInput>,
+        input_rx: &'a mut mpsc::UnboundedReceiver<UserInput>,
     ) -> Result<Self> {
         let output_buffer = match PendingOutputBuffer::new(config.session_id) {
             Ok(buf) => buf,
@@ -199,6 +212,7 @@ impl<'a> SessionState<'a> {
             first_connection: true,
             disconnected_at: None,
             last_disconnect_graceful: false,
+            turn_trace: TurnTrace::default(),
         })
     }
 
@@ -261,14 +275,16 @@ struct ConnectionState {
     working_directory: String,
     /// Active file uploads being received in chunks
     active_uploads: std::collections::HashMap<String, FileReceiveState>,
+    /// Trace of the turn in progress, shared with the output forwarder
+    turn_trace: TurnTrace,
 }
 
 /// Run the WebSocket connection loop with auto-reconnect
 pub async fn run_connection_loop(
     config: &ProxySessionConfig,
     claude_session: &mut ClaudeSession,
-    input_tx: mpsc::UnboundedSender<String>,
-    input_rx: &mut mpsc::UnboundedReceiver<String>,
+    input_tx: mpsc::UnboundedSender<UserInput>,
+    input_rx: &mut mpsc::UnboundedReceiver<UserInput>,
 ) -> Result<LoopResult> {
     let mut session = SessionState::new(config, claude_session, input_tx, input_rx)?;
     session.log_pending_messages().await;
@@ -386,6 +402,7 @@ async fn run_single_connection(session: &mut SessionState<'_>) -> ConnectionResu
                 let msg = ProxyToServer::SequencedOutput {
                     seq: pending.seq,
                     content: pending.content.clone(),
+                    traceparent: None,
                 };
                 if conn.send(msg).await.is_err() {
                     error!("Failed to replay pending message seq={}", pending.seq);
@@ -448,6 +465,7 @@ async fn run_single_connection(session: &mut SessionState<'_>) -> ConnectionResu
         let msg = ProxyToServer::SequencedOutput {
             seq,
             content: portal_content,
+            traceparent: None,
         };
         if conn.send(msg).await.is_err() {
             error!("Failed to send connection portal message");
@@ -626,6 +644,7 @@ async fn run_message_loop(
         current_repo_url,
         session.output_buffer.clone(),
         max_image_mb,
+        session.turn_trace.clone(),
     );
 
     // Spawn WebSocket reader task
@@ -660,6 +679,7 @@ async fn run_message_loop(
         file_upload_rx,
         working_directory: config.working_directory.clone(),
         active_uploads: std::collections::HashMap::new(),
+        turn_trace: session.turn_trace.clone(),
     };
 
     // Main loop
@@ -685,7 +705,7 @@ async fn recv_option(rx: &mut tokio::sync::oneshot::Receiver<()>) -> Option<()>
 /// See: https://github.com/meawoppl/agent-portal/issues/278
 async fn run_main_loop(
     claude_session: &mut ClaudeSession,
-    input_rx: &mut mpsc::UnboundedReceiver<String>,
+    input_rx: &mut mpsc::UnboundedReceiver<UserInput>,
     state: &mut ConnectionState,
 ) -> ConnectionResult {
     use crate::session::PermissionResponse as LibPermissionResponse;
@@ -727,10 +747,27 @@ async fn run_main_loop(
                 return ConnectionResult::ServerShutdown(Duration::from_millis(shutdown.reconnect_delay_ms));
             }
 
-            Some(text) = input_rx.recv() => {
-                debug!("sending to claude process: {}", truncate(&text, 100));
+            Some(input) = input_rx.recv() => {
+                debug!("sending to claude process: {}", truncate(&input.text, 100));
 
-                if let Err(e) = claude_session.send_input(serde_json::Value::String(text)).await {
+                // The turn's output is traced under this span
+                let span = match input.traceparent {
+                    Some(ref traceparent) => {
+                        let span = tracing::info_span!("send_input");
+                        portal_telemetry::set_parent(&span, Some(traceparent));
+                        span
+                    }
+                    None => tracing::Span::none(),
+                };
+                *state.turn_trace.lock().unwrap() = span
+                    .in_scope(portal_telemetry::current_traceparent)
+                    .or(input.traceparent);
+
+                if let Err(e) = claude_session
+                    .send_input(serde_json::Value::String(input.text))
+                    .instrument(span)
+                    .await
+                {
                     error!("Failed to send to Claude: {}", e);
                     return ConnectionResult::ClaudeExited;
                 }
@@ -807,6 +844,7 @@ async fn run_main_loop(
                     let msg = ProxyToServer::SequencedOutput {
                         seq,
                         content: value.clone(),
+                        traceparent: state.turn_trace.lock().unwrap().clone(),
                     };
                     let mut ws = state.ws_write.lock().await;
                     if ws.send(msg).await.is_err() {
@@ -15,7 +15,7 @@ use uuid::Uuid;
 
 use crate::output_buffer::PendingOutputBuffer;
 
-use super::{format_duration, truncate, SharedWsWrite};
+use super::{format_duration, truncate, SharedWsWrite, TurnTrace};
 
 /// Spawn the output forwarder task
 ///
@@ -31,6 +31,7 @@ pub fn spawn_output_forwarder(
     current_repo_url: Arc<Mutex<Option<String>>>,
     output_buffer: Arc<Mutex<PendingOutputBuffer>>,
     max_image_mb: u32,
+    turn_trace: TurnTrace,
 ) -> tokio::task::JoinHandle<()> {
     let max_bytes = max_image_mb as usize * 1024 * 1024;
     tokio::spawn(async move {
@@ -83,8 +84,31 @@ pub fn spawn_output_forwarder(
                 buf.push(content.clone())
             };
 
+            // Output of a traced turn gets a span of its own; the turn ends
+            // with its result
+            let turn = if matches!(output, ClaudeOutput::Result(_)) {
+                turn_trace.lock().unwrap().take()
+            } else {
+                turn_trace.lock().unwrap().clone()
+            };
+            let span = match turn {
+                Some(ref traceparent) => {
+                    let span = tracing::info_span!("forward_output", seq);
+                    portal_telemetry::set_parent(&span, Some(traceparent));
+                    span
+                }
+                None => tracing::Span::none(),
+            };
+            let traceparent = span
+                .in_scope(portal_telemetry::current_traceparent)
+                .or(turn);
+
             // Send as sequenced output
-            let msg = ProxyToServer::SequencedOutput { seq, content };
+            let msg = ProxyToServer::SequencedOutput {
+                seq,
+                content,
+                traceparent: traceparent.clone(),
+            };
 
             {
                 let mut ws = ws_write.lock().await;
@@ -104,6 +128,7 @@ pub fn spawn_output_forwarder(
                 let portal_ws_msg = ProxyToServer::SequencedOutput {
                     seq: portal_seq,
                     content: portal_content,
+                    traceparent: traceparent.clone(),
                 };
                 let mut ws = ws_write.lock().await;
                 if ws.send(portal_ws_msg).await.is_err() {
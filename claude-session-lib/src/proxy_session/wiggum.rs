@@ -100,6 +100,7 @@ pub(super) async fn handle_session_event_with_wiggum(
                         let msg = ProxyToServer::SequencedOutput {
                             seq,
                             content: portal_content,
+                            traceparent: None,
                         };
                         let mut ws = ws_write.lock().await;
                         if ws.send(msg).await.is_err() {
@@ -151,6 +152,7 @@ pub(super) async fn handle_session_event_with_wiggum(
                     let msg = ProxyToServer::SequencedOutput {
                         seq,
                         content: portal_content,
+                        traceparent: None,
                     };
                     let mut ws = ws_write.lock().await;
                     if ws.send(msg).await.is_err() {
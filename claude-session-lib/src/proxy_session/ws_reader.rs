@@ -4,7 +4,7 @@ use shared::{ProxyToServer, SendMode, ServerToProxy};
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, trace, warn};
 
-use super::{truncate, GracefulShutdown, PermissionResponseData, SharedWsWrite, WsRead};
+use super::{truncate, GracefulShutdown, PermissionResponseData, SharedWsWrite, UserInput, WsRead};
 
 /// Events sent through the file upload channel from the WS reader to the main loop
 pub enum FileUploadEvent {
@@ -47,7 +47,7 @@ pub(super) enum WsMessageResult {
 #[allow(clippy::too_many_arguments)] // TODO: refactor to event enum (issue #271)
 pub(super) fn spawn_ws_reader(
     mut ws_read: WsRead,
-    input_tx: mpsc::UnboundedSender<String>,
+    input_tx: mpsc::UnboundedSender<UserInput>,
     perm_tx: mpsc::UnboundedSender<PermissionResponseData>,
     ack_tx: mpsc::UnboundedSender<u64>,
     ws_write: SharedWsWrite,
@@ -99,11 +99,33 @@ pub(super) fn spawn_ws_reader(
     })
 }
 
+/// A span for an input that arrived with a trace context, continuing it
+fn input_span(seq: Option<i64>, traceparent: Option<&str>) -> tracing::Span {
+    match traceparent {
+        Some(traceparent) => {
+            let span = tracing::info_span!("ws_reader", seq);
+            portal_telemetry::set_parent(&span, Some(traceparent));
+            span
+        }
+        None => tracing::Span::none(),
+    }
+}
+
+/// Input for the main loop, carrying the current span's trace context, or
+/// the received one when spans are not exported
+fn user_input(text: String, received: Option<&str>) -> UserInput {
+    UserInput {
+        text,
+        traceparent: portal_telemetry::current_traceparent()
+            .or_else(|| received.map(str::to_string)),
+    }
+}
+
 /// Handle a typed message from the WebSocket
 #[allow(clippy::too_many_arguments)]
 async fn handle_ws_message(
     proxy_msg: ServerToProxy,
-    input_tx: &mpsc::UnboundedSender<String>,
+    input_tx: &mpsc::UnboundedSender<UserInput>,
     perm_tx: &mpsc::UnboundedSender<PermissionResponseData>,
     ack_tx: &mpsc::UnboundedSender<u64>,
     ws_write: &SharedWsWrite,
@@ -119,7 +141,13 @@ async fn handle_ws_message(
     }
 
     match proxy_msg {
-        ServerToProxy::ClaudeInput { content, send_mode } => {
+        ServerToProxy::ClaudeInput {
+            content,
+            send_mode,
+            traceparent,
+        } => {
+            let span = input_span(None, traceparent.as_deref());
+            let _entered = span.enter();
             let user_text = match &content {
                 serde_json::Value::String(s) => s.clone(),
                 other => other.to_string(),
@@ -134,7 +162,10 @@ async fn handle_ws_message(
                 }
             } else {
                 debug!("→ [input] {}", truncate(&user_text, 80));
-                if input_tx.send(user_text).is_err() {
+                if input_tx
+                    .send(user_input(user_text, traceparent.as_deref()))
+                    .is_err()
+                {
                     error!("Failed to send input to channel");
                     return WsMessageResult::Disconnect;
                 }
@@ -145,7 +176,10 @@ async fn handle_ws_message(
             seq,
             content,
             send_mode,
+            traceparent,
         } => {
+            let span = input_span(Some(seq), traceparent.as_deref());
+            let _entered = span.enter();
             let user_text = match &content {
                 serde_json::Value::String(s) => s.clone(),
                 other => other.to_string(),
@@ -164,7 +198,10 @@ async fn handle_ws_message(
                 }
             } else {
                 debug!("→ [seq_input] seq={} {}", seq, truncate(&user_text, 80));
-                if input_tx.send(user_text).is_err() {
+                if input_tx
+                    .send(user_input(user_text, traceparent.as_deref()))
+                    .is_err()
+                {
                     error!("Failed to send input to channel");
                     return WsMessageResult::Disconnect;
                 }
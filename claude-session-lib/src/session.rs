@@ -171,7 +171,8 @@ pub struct Session {
     command_tx: Option<mpsc::UnboundedSender<IoCommand>>,
     buffer: OutputBuffer,
     state: SessionState,
-    pending_permission: Option<PendingPermission>,
+    /// Unanswered permission requests, oldest first
+    pending_permissions: Vec<PendingPermission>,
     /// Receiver for events from the I/O task
     event_rx: Option<mpsc::UnboundedReceiver<IoEvent>>,
 }
@@ -200,7 +201,7 @@ impl Session {
                 command_tx: Some(command_tx),
                 buffer,
                 state: SessionState::Running,
-                pending_permission: None,
+                pending_permissions: Vec::new(),
                 event_rx: Some(event_rx),
             });
         }
@@ -220,7 +221,7 @@ impl Session {
             command_tx: Some(command_tx),
             buffer,
             state: SessionState::Running,
-            pending_permission: None,
+            pending_permissions: Vec::new(),
             event_rx: Some(event_rx),
         })
     }
@@ -396,7 +397,7 @@ impl Session {
             command_tx,
             buffer,
             state,
-            pending_permission: snapshot.pending_permission,
+            pending_permissions: snapshot.pending_permissions,
             event_rx,
         })
     }
@@ -411,7 +412,7 @@ impl Session {
             self.id,
             self.config.clone(),
             self.buffer.to_snapshot(),
-            self.pending_permission.clone(),
+            self.pending_permissions.clone(),
             was_running,
         )
     }
@@ -468,13 +469,12 @@ impl Session {
                             req.request
                         {
                             let request_id = req.request_id.clone();
-                            self.pending_permission = Some(PendingPermission {
+                            self.queue_permission(PendingPermission {
                                 request_id: request_id.clone(),
                                 tool_name: tool_req.tool_name.clone(),
                                 input: tool_req.input.clone(),
                                 requested_at: Utc::now(),
                             });
-                            self.state = SessionState::WaitingForPermission;
 
                             // Emit PermissionRequest (not Output) for permission requests
                             return Some(SessionEvent::PermissionRequest {
@@ -504,13 +504,12 @@ impl Session {
                     tool_name,
                     input,
                 }) => {
-                    self.pending_permission = Some(PendingPermission {
+                    self.queue_permission(PendingPermission {
                         request_id: request_id.clone(),
                         tool_name: tool_name.clone(),
                         input: input.clone(),
                         requested_at: Utc::now(),
                     });
-                    self.state = SessionState::WaitingForPermission;
                     return Some(SessionEvent::PermissionRequest {
                         request_id,
                         tool_name,
@@ -570,15 +569,16 @@ impl Session {
         request_id: &str,
         response: PermissionResponse,
     ) -> Result<(), SessionError> {
-        // Verify this is the pending request
-        match &self.pending_permission {
-            Some(perm) if perm.request_id == request_id => {}
-            _ => {
-                return Err(SessionError::InvalidPermissionResponse(
-                    request_id.to_string(),
-                ));
-            }
-        }
+        // Verify this is one of the pending requests
+        let Some(index) = self
+            .pending_permissions
+            .iter()
+            .position(|perm| perm.request_id == request_id)
+        else {
+            return Err(SessionError::InvalidPermissionResponse(
+                request_id.to_string(),
+            ));
+        };
 
         if let Some(ref command_tx) = self.command_tx {
             if self.config.agent_type == shared::AgentType::Codex {
@@ -622,8 +622,10 @@ impl Session {
             }
         }
 
-        self.pending_permission = None;
-        self.state = SessionState::Running;
+        self.pending_permissions.remove(index);
+        if self.pending_permissions.is_empty() {
+            self.state = SessionState::Running;
+        }
 
         Ok(())
     }
@@ -648,12 +650,26 @@ impl Session {
 
     /// Check if session has a pending permission request
     pub fn has_pending_permission(&self) -> bool {
-        self.pending_permission.is_some()
+        !self.pending_permissions.is_empty()
+    }
+
+    /// Get the pending permission requests, oldest first
+    pub fn pending_permissions(&self) -> &[PendingPermission] {
+        &self.pending_permissions
     }
 
-    /// Get the pending permission request, if any
-    pub fn pending_permission(&self) -> Option<&PendingPermission> {
-        self.pending_permission.as_ref()
+    /// Add a permission request to the queue. A request re-sent with the same
+    /// ID replaces the earlier entry in place so ordering is preserved.
+    fn queue_permission(&mut self, perm: PendingPermission) {
+        match self
+            .pending_permissions
+            .iter_mut()
+            .find(|p| p.request_id == perm.request_id)
+        {
+            Some(existing) => *existing = perm,
+            None => self.pending_permissions.push(perm),
+        }
+        self.state = SessionState::WaitingForPermission;
     }
 
     /// Acknowledge outputs up to the given sequence number
@@ -1001,6 +1017,32 @@ impl Session {
         }
     }
 
+    /// Arguments selecting which Claude session the process continues.
+    ///
+    /// A fork resumes the parent under the new session id, optionally cut
+    /// short after the fork point. Restarts of a fork resume the fork itself.
+    fn session_args(config: &SessionConfig) -> Vec<String> {
+        let session_id = config.session_id.to_string();
+        if config.resume {
+            return vec!["--resume".to_string(), session_id];
+        }
+        let Some(fork) = &config.fork else {
+            return vec!["--session-id".to_string(), session_id];
+        };
+        let mut args = vec![
+            "--resume".to_string(),
+            fork.parent_session_id.to_string(),
+            "--fork-session".to_string(),
+            "--session-id".to_string(),
+            session_id,
+        ];
+        if let Some(message) = &fork.resume_at {
+            args.push("--resume-session-at".to_string());
+            args.push(message.clone());
+        }
+        args
+    }
+
     /// Spawn the Claude process
     async fn spawn_claude(config: &SessionConfig) -> Result<ClaudeAsyncClient, SessionError> {
         let claude_path = config.claude_path.as_deref().unwrap_or(Path::new("claude"));
@@ -1018,11 +1060,8 @@ impl Session {
             .arg("stdio")
             .arg("--replay-user-messages");
 
-        if config.resume {
-            cmd.arg("--resume").arg(config.session_id.to_string());
-        } else {
-            cmd.arg("--session-id").arg(config.session_id.to_string());
-        }
+        let session_args = Self::session_args(config);
+        cmd.args(&session_args);
 
         // Add extra arguments
         for arg in &config.extra_args {
@@ -1048,11 +1087,7 @@ impl Session {
                 .iter()
                 .map(|s| s.to_string()),
             )
-            .chain(if config.resume {
-                vec!["--resume".to_string(), config.session_id.to_string()]
-            } else {
-                vec!["--session-id".to_string(), config.session_id.to_string()]
-            })
+            .chain(session_args)
             .chain(config.extra_args.iter().cloned())
             .collect();
         tracing::info!("Spawning Claude: {}", args.join(" "));
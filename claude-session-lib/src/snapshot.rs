@@ -26,6 +26,9 @@ pub struct SessionConfig {
     /// Which agent CLI to use
     #[serde(default)]
     pub agent_type: shared::AgentType,
+    /// Start as a fork of another Claude session (ignored when resuming)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork: Option<shared::SessionFork>,
 }
 
 /// A pending permission request that hasn't been responded to
@@ -53,8 +56,16 @@ pub struct SessionSnapshot {
     pub config: SessionConfig,
     /// Buffered outputs not yet acknowledged by consumers
     pub pending_outputs: Vec<BufferedOutput>,
-    /// Pending permission request (if any)
-    pub pending_permission: Option<PendingPermission>,
+    /// Unanswered permission requests, oldest first
+    ///
+    /// Snapshots written before requests were queued stored a single
+    /// `pending_permission` (object or null); those are still accepted.
+    #[serde(
+        default,
+        alias = "pending_permission",
+        deserialize_with = "deserialize_pending_permissions"
+    )]
+    pub pending_permissions: Vec<PendingPermission>,
     /// Timestamp of last activity
     pub last_activity: DateTime<Utc>,
     /// Whether the Claude process was running when snapshot was taken
@@ -67,14 +78,14 @@ impl SessionSnapshot {
         id: Uuid,
         config: SessionConfig,
         pending_outputs: Vec<BufferedOutput>,
-        pending_permission: Option<PendingPermission>,
+        pending_permissions: Vec<PendingPermission>,
         was_running: bool,
     ) -> Self {
         Self {
             id,
             config,
             pending_outputs,
-            pending_permission,
+            pending_permissions,
             last_activity: Utc::now(),
             was_running,
         }
@@ -91,6 +102,27 @@ impl SessionSnapshot {
     }
 }
 
+/// Accept either the current list form or the legacy single (nullable) entry.
+fn deserialize_pending_permissions<'de, D>(
+    deserializer: D,
+) -> Result<Vec<PendingPermission>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        Many(Vec<PendingPermission>),
+        One(PendingPermission),
+    }
+
+    Ok(match Option::<OneOrMany>::deserialize(deserializer)? {
+        Some(OneOrMany::Many(perms)) => perms,
+        Some(OneOrMany::One(perm)) => vec![perm],
+        None => Vec::new(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,6 +135,7 @@ mod tests {
             resume: false,
             claude_path: None,
             extra_args: vec![],
+            fork: None,
             agent_type: Default::default(),
         }
     }
@@ -155,14 +188,22 @@ mod tests {
             },
         ];
 
-        let pending_permission = Some(PendingPermission {
-            request_id: "perm-456".to_string(),
-            tool_name: "Write".to_string(),
-            input: serde_json::json!({"file_path": "/tmp/test.txt"}),
-            requested_at: Utc::now(),
-        });
+        let pending_permissions = vec![
+            PendingPermission {
+                request_id: "perm-456".to_string(),
+                tool_name: "Write".to_string(),
+                input: serde_json::json!({"file_path": "/tmp/test.txt"}),
+                requested_at: Utc::now(),
+            },
+            PendingPermission {
+                request_id: "perm-789".to_string(),
+                tool_name: "Bash".to_string(),
+                input: serde_json::json!({"command": "ls"}),
+                requested_at: Utc::now(),
+            },
+        ];
 
-        let snapshot = SessionSnapshot::new(id, config, pending_outputs, pending_permission, true);
+        let snapshot = SessionSnapshot::new(id, config, pending_outputs, pending_permissions, true);
 
         // Serialize to bytes
         let bytes = snapshot.to_bytes().unwrap();
@@ -172,9 +213,10 @@ mod tests {
 
         assert_eq!(restored.id, id);
         assert_eq!(restored.pending_outputs.len(), 2);
-        assert!(restored.pending_permission.is_some());
         assert!(restored.was_running);
-        assert_eq!(restored.pending_permission.unwrap().tool_name, "Write");
+        assert_eq!(restored.pending_permissions.len(), 2);
+        assert_eq!(restored.pending_permissions[0].tool_name, "Write");
+        assert_eq!(restored.pending_permissions[1].request_id, "perm-789");
     }
 
     #[test]
@@ -182,14 +224,41 @@ mod tests {
         let config = sample_config();
         let id = config.session_id;
 
-        let snapshot = SessionSnapshot::new(id, config, vec![], None, false);
+        let snapshot = SessionSnapshot::new(id, config, vec![], vec![], false);
 
         let bytes = snapshot.to_bytes().unwrap();
         let restored = SessionSnapshot::from_bytes(&bytes).unwrap();
 
         assert_eq!(restored.id, id);
         assert!(restored.pending_outputs.is_empty());
-        assert!(restored.pending_permission.is_none());
+        assert!(restored.pending_permissions.is_empty());
         assert!(!restored.was_running);
     }
+
+    #[test]
+    fn test_legacy_single_pending_permission() {
+        let config = sample_config();
+        let id = config.session_id;
+        let mut value =
+            serde_json::to_value(SessionSnapshot::new(id, config, vec![], vec![], true)).unwrap();
+        let obj = value.as_object_mut().unwrap();
+        obj.remove("pending_permissions");
+        obj.insert(
+            "pending_permission".to_string(),
+            serde_json::json!({
+                "request_id": "legacy-1",
+                "tool_name": "Edit",
+                "input": {},
+                "requested_at": Utc::now(),
+            }),
+        );
+
+        let restored: SessionSnapshot = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(restored.pending_permissions.len(), 1);
+        assert_eq!(restored.pending_permissions[0].request_id, "legacy-1");
+
+        value["pending_permission"] = serde_json::Value::Null;
+        let restored: SessionSnapshot = serde_json::from_value(value).unwrap();
+        assert!(restored.pending_permissions.is_empty());
+    }
 }
@@ -1,9 +1,10 @@
 use crate::components::ProxyTokenSetup;
+use crate::utils;
 use gloo::timers::callback::Timeout;
 use gloo_net::http::Request;
 use serde::Deserialize;
 use shared::api::LaunchRequest;
-use shared::{DirectoryEntry, LauncherInfo};
+use shared::{DirectoryEntry, LauncherInfo, LocalSession};
 use uuid::Uuid;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::spawn_local;
@@ -110,6 +111,22 @@ impl DirBrowser {
     }
 }
 
+/// Fetch the Claude Code sessions started outside the portal on a launcher's host.
+/// Failures just leave the list empty; adopting is optional.
+fn fetch_local_sessions(launcher_id: Uuid, sessions: UseStateHandle<Vec<LocalSession>>) {
+    sessions.set(Vec::new());
+    spawn_local(async move {
+        let url = format!("/api/launchers/{}/local-sessions", launcher_id);
+        if let Ok(resp) = Request::get(&url).send().await {
+            if resp.ok() {
+                if let Ok(list) = resp.json::<Vec<LocalSession>>().await {
+                    sessions.set(list);
+                }
+            }
+        }
+    });
+}
+
 fn parent_path(path: &str) -> String {
     let trimmed = path.trim_end_matches('/');
     match trimmed.rfind('/') {
@@ -137,7 +154,9 @@ pub fn launch_dialog(props: &LaunchDialogProps) -> Html {
         loading: use_state(|| false),
         error: use_state(|| None::<String>),
     };
+    let local_sessions = use_state(Vec::<LocalSession>::new);
     let extra_args = use_state(String::new);
+    let cost_cap = use_state(String::new);
     let agent_type = use_state(|| shared::AgentType::Claude);
     let skip_permissions = use_state(|| false);
     let launching = use_state(|| false);
@@ -150,6 +169,7 @@ pub fn launch_dialog(props: &LaunchDialogProps) -> Html {
         let selected_launcher = selected_launcher.clone();
         let show_install = show_install.clone();
         let dir = dir.clone();
+        let local_sessions = local_sessions.clone();
         use_effect_with((), move |_| {
             spawn_local(async move {
                 if let Ok(resp) = Request::get("/api/launchers").send().await {
@@ -158,6 +178,7 @@ pub fn launch_dialog(props: &LaunchDialogProps) -> Html {
                             let lid = first.launcher_id;
                             selected_launcher.set(Some(lid));
                             dir.fetch(lid, "~".to_string(), true);
+                            fetch_local_sessions(lid, local_sessions);
                         } else {
                             show_install.set(true);
                         }
@@ -199,6 +220,15 @@ pub fn launch_dialog(props: &LaunchDialogProps) -> Html {
         })
     };
 
+    let on_cost_cap_input = {
+        let cost_cap = cost_cap.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                cost_cap.set(input.value());
+            }
+        })
+    };
+
     let on_agent_type_change = {
         let agent_type = agent_type.clone();
         Callback::from(move |e: Event| {
@@ -257,6 +287,7 @@ pub fn launch_dialog(props: &LaunchDialogProps) -> Html {
         let selected_launcher = selected_launcher.clone();
         let show_install = show_install.clone();
         let dir = dir.clone();
+        let local_sessions = local_sessions.clone();
         Callback::from(move |e: Event| {
             if let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() {
                 if select.value() == CONNECT_NEW {
@@ -266,28 +297,43 @@ pub fn launch_dialog(props: &LaunchDialogProps) -> Html {
                     show_install.set(false);
                     selected_launcher.set(Some(id));
                     dir.navigate(Some(id), "~".to_string());
+                    fetch_local_sessions(id, local_sessions.clone());
                 }
             }
         })
     };
 
-    let on_launch = {
-        let dir_path = dir.path.clone();
+    // Launch in `working_dir`, adopting the local session `resume` when given
+    let start_launch = {
         let extra_args = extra_args.clone();
         let agent_type = agent_type.clone();
         let skip_permissions = skip_permissions.clone();
+        let cost_cap = cost_cap.clone();
         let selected_launcher = selected_launcher.clone();
+        let launchers = launchers.clone();
         let launching = launching.clone();
         let error_msg = error_msg.clone();
         let on_close = props.on_close.clone();
         let on_launched = props.on_launched.clone();
-        Callback::from(move |_| {
-            let working_dir = (*dir_path).clone();
+        Callback::from(move |(working_dir, resume): (String, Option<Uuid>)| {
             if working_dir.is_empty() {
                 error_msg.set(Some("Working directory is required".to_string()));
                 return;
             }
 
+            let cap = cost_cap.trim().trim_start_matches('$');
+            let max_cost_usd = if cap.is_empty() {
+                None
+            } else {
+                match cap.parse::<f64>() {
+                    Ok(v) if shared::api::validate_cost_cap(Some(v)).is_ok() => Some(v),
+                    _ => {
+                        error_msg.set(Some("The cost cap must be a positive amount".to_string()));
+                        return;
+                    }
+                }
+            };
+
             let mut claude_args: Vec<String> = (*extra_args)
                 .split_whitespace()
                 .map(|s| s.to_string())
@@ -300,7 +346,18 @@ pub fn launch_dialog(props: &LaunchDialogProps) -> Html {
             }
 
             let launcher_id = *selected_launcher;
-            let selected_agent_type = *agent_type;
+            // A teammate's launcher only takes the defaults
+            let shared_launcher = launchers
+                .iter()
+                .any(|l| Some(l.launcher_id) == launcher_id && l.shared_by.is_some());
+            if shared_launcher {
+                claude_args.clear();
+            }
+            let selected_agent_type = if resume.is_some() {
+                shared::AgentType::Claude
+            } else {
+                *agent_type
+            };
             let launching = launching.clone();
             let error_msg = error_msg.clone();
             let on_close = on_close.clone();
@@ -315,6 +372,8 @@ pub fn launch_dialog(props: &LaunchDialogProps) -> Html {
                     launcher_id,
                     claude_args,
                     agent_type: selected_agent_type,
+                    resume_session_id: resume,
+                    max_cost_usd,
                 };
 
                 match Request::post("/api/launch")
@@ -332,6 +391,9 @@ pub fn launch_dialog(props: &LaunchDialogProps) -> Html {
                         let text = resp.text().await.unwrap_or_default();
                         if status == 404 {
                             error_msg.set(Some("No connected launchers".to_string()));
+                        } else if status == 403 && !text.is_empty() {
+                            // Spend limits explain themselves
+                            error_msg.set(Some(text));
                         } else {
                             error_msg.set(Some(format!("Error {}: {}", status, text)));
                         }
@@ -345,6 +407,12 @@ pub fn launch_dialog(props: &LaunchDialogProps) -> Html {
         })
     };
 
+    let on_launch = {
+        let dir_path = dir.path.clone();
+        let start_launch = start_launch.clone();
+        Callback::from(move |_| start_launch.emit(((*dir_path).clone(), None)))
+    };
+
     let on_backdrop = {
         let on_close = props.on_close.clone();
         Callback::from(move |_| on_close.emit(()))
@@ -390,6 +458,9 @@ pub fn launch_dialog(props: &LaunchDialogProps) -> Html {
         .and_then(|lid| launchers.iter().find(|l| l.launcher_id == lid).cloned());
 
     let cfg = agent_config(*agent_type);
+    let on_shared_launcher = selected_info
+        .as_ref()
+        .is_some_and(|info| info.shared_by.is_some());
 
     // Pre-compute directory listing HTML
     let dir_listing_html = if *dir.loading {
@@ -458,6 +529,9 @@ pub fn launch_dialog(props: &LaunchDialogProps) -> Html {
                     html! {
                         <option value={l.launcher_id.to_string()} {selected}>
                             { &l.launcher_name }
+                            if let Some(owner) = &l.shared_by {
+                                { format!(" (shared by {})", owner) }
+                            }
                         </option>
                     }
                 }).collect::<Html>() }
@@ -471,6 +545,9 @@ pub fn launch_dialog(props: &LaunchDialogProps) -> Html {
             if let Some(ref info) = selected_info {
                 <span class="launcher-subtitle">
                     { format!("{} running", info.running_sessions) }
+                    if let Some(owner) = &info.shared_by {
+                        { format!(" · sessions run as {}", owner) }
+                    }
                 </span>
             }
         </div>
@@ -559,19 +636,75 @@ pub fn launch_dialog(props: &LaunchDialogProps) -> Html {
                         </div>
                     </div>
 
-                    // Extra CLI arguments
+                    // Sessions started from a terminal on the launcher's host
+                    if *agent_type == shared::AgentType::Claude && !local_sessions.is_empty() {
+                        <div class="launch-field">
+                            <label>{ "Or adopt a terminal session" }</label>
+                            <div class="dir-browser local-sessions">
+                                { local_sessions.iter().map(|session| {
+                                    let onclick = {
+                                        let start_launch = start_launch.clone();
+                                        let dir = session.working_directory.clone();
+                                        let id = session.session_id;
+                                        Callback::from(move |_: MouseEvent| {
+                                            start_launch.emit((dir.clone(), Some(id)))
+                                        })
+                                    };
+                                    html! {
+                                        <div class="local-session" title={session.working_directory.clone()}>
+                                            <div class="local-session-info">
+                                                <span class="local-session-summary">
+                                                    { session.summary.clone().unwrap_or_else(|| "(no prompt)".to_string()) }
+                                                </span>
+                                                <span class="local-session-meta">
+                                                    { utils::extract_folder(&session.working_directory) }
+                                                    if let Some(ref branch) = session.git_branch {
+                                                        { format!(" · {}", branch) }
+                                                    }
+                                                    { format!(" · {}", utils::format_timestamp(&session.last_activity)) }
+                                                </span>
+                                            </div>
+                                            <button
+                                                class="local-session-adopt"
+                                                {onclick}
+                                                disabled={*launching}
+                                            >
+                                                { "Adopt" }
+                                            </button>
+                                        </div>
+                                    }
+                                }).collect::<Html>() }
+                            </div>
+                        </div>
+                    }
+
+                    // Extra CLI arguments, on the user's own launchers only
+                    if !on_shared_launcher {
+                        <div class="launch-field">
+                            <label>{ "Extra CLI Arguments (optional)" }</label>
+                            <input
+                                type="text"
+                                placeholder={cfg.args_placeholder}
+                                value={(*extra_args).clone()}
+                                oninput={on_args_input}
+                            />
+                        </div>
+                    }
+
+                    // Per-session spend cap
                     <div class="launch-field">
-                        <label>{ "Extra CLI Arguments (optional)" }</label>
+                        <label>{ "Cost Cap in USD (optional)" }</label>
                         <input
                             type="text"
-                            placeholder={cfg.args_placeholder}
-                            value={(*extra_args).clone()}
-                            oninput={on_args_input}
+                            inputmode="decimal"
+                            placeholder="Stop the session once it has cost this much"
+                            value={(*cost_cap).clone()}
+                            oninput={on_cost_cap_input}
                         />
                     </div>
 
                     // Permission bypass checkbox (agent-specific)
-                    if let Some(flag) = cfg.skip_permissions_flag {
+                    if let Some(flag) = cfg.skip_permissions_flag.filter(|_| !on_shared_launcher) {
                         <div class="launch-field launch-checkbox">
                             <label>
                                 <input
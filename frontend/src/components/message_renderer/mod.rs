@@ -83,6 +83,26 @@ pub fn group_messages(messages: &[String]) -> Vec<MessageGroup> {
     groups
 }
 
+/// Index of the group `group_messages` puts `messages[index]` in
+pub fn group_containing(messages: &[String], index: usize) -> usize {
+    group_messages(&messages[..=index]).len() - 1
+}
+
+/// Portal id of the last Claude reply in a group, where the conversation can
+/// be forked. Only messages loaded from history carry an `_id`.
+pub fn fork_point(group: &MessageGroup) -> Option<Uuid> {
+    let MessageGroup::AssistantGroup(messages) = group else {
+        return None;
+    };
+    messages.iter().rev().find_map(|json| {
+        let value: Value = serde_json::from_str(json).ok()?;
+        if value.get("type")?.as_str()? != "assistant" || value.get("uuid").is_none() {
+            return None;
+        }
+        value.get("_id")?.as_str()?.parse().ok()
+    })
+}
+
 // --- Components ---
 
 #[derive(Properties, PartialEq)]
@@ -230,22 +250,46 @@ pub(crate) fn shorten_model_name(model: &str) -> Option<String> {
     })
 }
 
-pub fn format_duration(ms: u64) -> String {
-    if ms < 1000 {
-        format!("{}ms", ms)
-    } else if ms < 60000 {
-        format!("{:.1}s", ms as f64 / 1000.0)
-    } else {
-        let mins = ms / 60000;
-        let secs = (ms % 60000) / 1000;
-        format!("{}m {}s", mins, secs)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_group_containing() {
+        let messages: Vec<String> = [
+            r#"{"type":"user","message":{"content":"hi"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"a"}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"b"}]}}"#,
+            r#"{"type":"result","result":"done"}"#,
+        ]
+        .iter()
+        .map(|m| m.to_string())
+        .collect();
+        assert_eq!(group_containing(&messages, 0), 0);
+        assert_eq!(group_containing(&messages, 1), 1);
+        assert_eq!(group_containing(&messages, 2), 1);
+        assert_eq!(group_containing(&messages, 3), 2);
+    }
+
+    #[test]
+    fn test_fork_point_is_last_reply_in_group() {
+        let id = Uuid::new_v4();
+        let group = MessageGroup::AssistantGroup(vec![
+            r#"{"type":"assistant","uuid":"a1","_id":"00000000-0000-0000-0000-000000000001"}"#
+                .to_string(),
+            format!(r#"{{"type":"assistant","uuid":"a2","_id":"{}"}}"#, id),
+            r#"{"type":"user","uuid":"u1","_id":"00000000-0000-0000-0000-000000000003","message":{"content":[{"type":"tool_result","tool_use_id":"t","content":"ok"}]}}"#
+                .to_string(),
+        ]);
+        assert_eq!(fork_point(&group), Some(id));
+
+        // Live output has no portal id yet
+        let live = MessageGroup::AssistantGroup(vec![r#"{"type":"assistant","uuid":"a3"}"#.into()]);
+        assert_eq!(fork_point(&live), None);
+        let prompt = MessageGroup::Single(format!(r#"{{"type":"user","_id":"{}"}}"#, id));
+        assert_eq!(fork_point(&prompt), None);
+    }
+
     #[test]
     fn test_shorten_model_name() {
         assert_eq!(
@@ -1,12 +1,13 @@
 //! Rendering functions for each message type.
 
+use super::shorten_model_name;
 use super::types::*;
-use super::{format_duration, shorten_model_name};
 use crate::components::expandable::ExpandableText;
 use crate::components::markdown::render_markdown;
 use crate::components::tool_renderers::render_tool_use;
 use serde::Deserialize;
 use serde_json::Value;
+use shared::render::format_duration;
 use shared::ToolResultContent;
 use wasm_bindgen::JsCast;
 use yew::prelude::*;
@@ -282,6 +283,56 @@ pub fn render_portal_message(msg: &PortalMessage, timestamp: Option<&str>) -> Ht
 fn render_portal_content(content: &shared::PortalContent) -> Html {
     match content {
         shared::PortalContent::Text { text } => render_markdown(text),
+        shared::PortalContent::PermissionDecision {
+            tool_name,
+            allowed,
+            reason,
+        } => {
+            let (class, verdict) = if *allowed {
+                ("policy-decision allowed", "Auto-approved")
+            } else {
+                ("policy-decision denied", "Auto-denied")
+            };
+            html! {
+                <div class={class} title={reason.clone()}>
+                    <span class="policy-decision-verdict">{ verdict }</span>
+                    <span class="policy-decision-tool">{ tool_name }</span>
+                    <span class="policy-decision-reason">{ format!("by rule: {}", reason) }</span>
+                </div>
+            }
+        }
+        shared::PortalContent::PermissionTimeout {
+            tool_name,
+            action,
+            timeout_seconds,
+        } => {
+            let (class, verdict) = match action {
+                shared::api::PermissionTimeoutAction::Allow => {
+                    ("policy-decision allowed", "Timed out: approved")
+                }
+                shared::api::PermissionTimeoutAction::Deny => {
+                    ("policy-decision denied", "Timed out: denied")
+                }
+                shared::api::PermissionTimeoutAction::Escalate => {
+                    ("policy-decision escalated", "Escalated")
+                }
+            };
+            html! {
+                <div class={class}>
+                    <span class="policy-decision-verdict">{ verdict }</span>
+                    <span class="policy-decision-tool">{ tool_name }</span>
+                    <span class="policy-decision-reason">
+                        { format!("no answer within {} seconds", timeout_seconds) }
+                    </span>
+                </div>
+            }
+        }
+        shared::PortalContent::SpendLimit { reason } => html! {
+            <div class="policy-decision denied">
+                <span class="policy-decision-verdict">{ "Spend limit" }</span>
+                <span class="policy-decision-reason">{ reason }</span>
+            </div>
+        },
         shared::PortalContent::Image {
             media_type,
             data,
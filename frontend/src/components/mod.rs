@@ -10,12 +10,14 @@ mod proxy_token_setup;
 mod schedule_dialog;
 mod share_dialog;
 mod tool_renderers;
+mod usage_charts;
 mod voice_input;
 
 pub use copy_command::CopyCommand;
 pub use launch_dialog::LaunchDialog;
-pub use message_renderer::{group_messages, MessageGroupRenderer};
+pub use message_renderer::{fork_point, group_containing, group_messages, MessageGroupRenderer};
 pub use proxy_token_setup::ProxyTokenSetup;
 pub use schedule_dialog::ScheduleDialog;
 pub use share_dialog::ShareDialog;
+pub use usage_charts::UsageCharts;
 pub use voice_input::VoiceInput;
@@ -1,8 +1,8 @@
 use crate::utils;
 use gloo_net::http::Request;
 use shared::api::{
-    CreateScheduledTaskRequest, ScheduledTaskInfo, ScheduledTaskListResponse,
-    UpdateScheduledTaskRequest,
+    CreateScheduledTaskRequest, PermissionTimeoutAction, ScheduledTaskInfo,
+    ScheduledTaskListResponse, TeamInfo, TeamListResponse, UpdateScheduledTaskRequest,
 };
 use shared::{LauncherInfo, SessionInfo};
 use uuid::Uuid;
@@ -45,6 +45,24 @@ struct TaskForm {
     max_runtime_minutes: i32,
     extra_args: String,
     skip_permissions: bool,
+    /// Seconds before an unanswered permission request falls back (empty = never)
+    permission_timeout: String,
+    permission_timeout_action: PermissionTimeoutAction,
+    /// Team the task and its runs are shared with
+    team_id: Option<Uuid>,
+}
+
+impl TaskForm {
+    fn permission_timeout_seconds(&self) -> Result<Option<i32>, String> {
+        let trimmed = self.permission_timeout.trim();
+        if trimmed.is_empty() {
+            return Ok(None);
+        }
+        trimmed
+            .parse()
+            .map(Some)
+            .map_err(|_| "Permission timeout must be a number of seconds".to_string())
+    }
 }
 
 #[derive(Clone, PartialEq)]
@@ -64,6 +82,7 @@ pub fn schedule_dialog(props: &ScheduleDialogProps) -> Html {
     let error_msg = use_state(|| None::<String>);
     let confirm_delete = use_state(|| None::<Uuid>);
     let launcher_version = use_state(String::new);
+    let teams = use_state(Vec::<TeamInfo>::new);
 
     let working_directory = props.session.working_directory.clone();
     let hostname = props.session.hostname.clone();
@@ -107,6 +126,21 @@ pub fn schedule_dialog(props: &ScheduleDialogProps) -> Html {
         });
     }
 
+    // Teams a task can be shared with
+    {
+        let teams = teams.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                if let Ok(resp) = Request::get(&utils::api_url("/api/teams")).send().await {
+                    if let Ok(data) = resp.json::<TeamListResponse>().await {
+                        teams.set(data.teams);
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
     let can_schedule = version_sufficient(&launcher_version);
 
     let reload_tasks = {
@@ -184,6 +218,12 @@ pub fn schedule_dialog(props: &ScheduleDialogProps) -> Html {
                     max_runtime_minutes: task.max_runtime_minutes,
                     extra_args: other_args.join(" "),
                     skip_permissions: has_skip,
+                    permission_timeout: task
+                        .permission_timeout_seconds
+                        .map(|s| s.to_string())
+                        .unwrap_or_default(),
+                    permission_timeout_action: task.permission_timeout_action,
+                    team_id: task.team_id,
                 });
                 error_msg.set(None);
                 form_mode.set(Some(FormMode::Edit(task_id)));
@@ -216,6 +256,13 @@ pub fn schedule_dialog(props: &ScheduleDialogProps) -> Html {
             if data.name.trim().is_empty() || data.cron_expression.trim().is_empty() {
                 return;
             }
+            let permission_timeout_seconds = match data.permission_timeout_seconds() {
+                Ok(seconds) => seconds,
+                Err(msg) => {
+                    error_msg.set(Some(msg));
+                    return;
+                }
+            };
 
             spawn_local(async move {
                 let mut claude_args: Vec<String> = Vec::new();
@@ -239,6 +286,9 @@ pub fn schedule_dialog(props: &ScheduleDialogProps) -> Html {
                             claude_args: claude_args.clone(),
                             agent_type: shared::AgentType::Claude,
                             max_runtime_minutes: data.max_runtime_minutes,
+                            permission_timeout_seconds,
+                            permission_timeout_action: data.permission_timeout_action,
+                            team_id: data.team_id,
                         };
                         Request::post(&utils::api_url("/api/scheduled-tasks"))
                             .json(&body)
@@ -254,6 +304,9 @@ pub fn schedule_dialog(props: &ScheduleDialogProps) -> Html {
                             prompt: Some(data.prompt.clone()),
                             max_runtime_minutes: Some(data.max_runtime_minutes),
                             claude_args: Some(claude_args.clone()),
+                            permission_timeout_seconds: Some(permission_timeout_seconds),
+                            permission_timeout_action: Some(data.permission_timeout_action),
+                            team_id: Some(data.team_id),
                             ..Default::default()
                         };
                         Request::patch(&utils::api_url(&format!("/api/scheduled-tasks/{}", id)))
@@ -347,12 +400,33 @@ pub fn schedule_dialog(props: &ScheduleDialogProps) -> Html {
                 "max_runtime_minutes" => {
                     f.max_runtime_minutes = input.value().parse().unwrap_or(30)
                 }
+                "permission_timeout" => f.permission_timeout = input.value(),
                 _ => {}
             }
             form.set(f);
         })
     };
 
+    let on_timeout_action = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let mut f = (*form).clone();
+            f.permission_timeout_action = select.value().parse().unwrap_or_default();
+            form.set(f);
+        })
+    };
+
+    let on_team = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let mut f = (*form).clone();
+            f.team_id = select.value().parse().ok();
+            form.set(f);
+        })
+    };
+
     let on_prompt_input = {
         let form = form.clone();
         Callback::from(move |e: InputEvent| {
@@ -429,6 +503,9 @@ pub fn schedule_dialog(props: &ScheduleDialogProps) -> Html {
                             let on_toggle = on_toggle_enabled.clone();
                             let on_del = on_delete.clone();
                             let is_confirming = *confirm_delete == Some(task_id);
+                            let team_name = task.team_id.and_then(|id| {
+                                teams.iter().find(|t| t.id == id).map(|t| t.name.clone())
+                            });
                             html! {
                                 <div class={classes!("sched-task-row", (!task.enabled).then_some("disabled"))}>
                                     <div class="sched-task-info">
@@ -437,22 +514,28 @@ pub fn schedule_dialog(props: &ScheduleDialogProps) -> Html {
                                         if task.timezone != "UTC" {
                                             <span class="sched-task-tz">{ &task.timezone }</span>
                                         }
+                                        if let Some(team_name) = team_name {
+                                            <span class="sched-task-team">{ team_name }</span>
+                                        }
                                     </div>
                                     <div class="sched-task-prompt-preview">{ &task.prompt }</div>
-                                    <div class="sched-task-actions">
-                                        <button class="sched-btn" onclick={Callback::from(move |_| on_edit.emit(task_id))}>
-                                            { "Edit" }
-                                        </button>
-                                        <button class="sched-btn" onclick={Callback::from(move |_| on_toggle.emit(task_id))}>
-                                            { if task.enabled { "Disable" } else { "Enable" } }
-                                        </button>
-                                        <button
-                                            class={classes!("sched-btn", "sched-btn-danger", is_confirming.then_some("confirming"))}
-                                            onclick={Callback::from(move |_| on_del.emit(task_id))}
-                                        >
-                                            { if is_confirming { "Confirm?" } else { "Delete" } }
-                                        </button>
-                                    </div>
+                                    // Only the owner changes a task shared through a team
+                                    if task.owned {
+                                        <div class="sched-task-actions">
+                                            <button class="sched-btn" onclick={Callback::from(move |_| on_edit.emit(task_id))}>
+                                                { "Edit" }
+                                            </button>
+                                            <button class="sched-btn" onclick={Callback::from(move |_| on_toggle.emit(task_id))}>
+                                                { if task.enabled { "Disable" } else { "Enable" } }
+                                            </button>
+                                            <button
+                                                class={classes!("sched-btn", "sched-btn-danger", is_confirming.then_some("confirming"))}
+                                                onclick={Callback::from(move |_| on_del.emit(task_id))}
+                                            >
+                                                { if is_confirming { "Confirm?" } else { "Delete" } }
+                                            </button>
+                                        </div>
+                                    }
                                 </div>
                             }
                         }) }
@@ -535,6 +618,54 @@ pub fn schedule_dialog(props: &ScheduleDialogProps) -> Html {
                                             oninput={on_extra_args_input}
                                         />
                                     </div>
+                                    <div class="sched-field-row">
+                                        <div class="sched-field sched-field-sm">
+                                            <label>{ "Permission timeout (s)" }</label>
+                                            <input
+                                                type="number"
+                                                min="10"
+                                                placeholder="never"
+                                                value={form.permission_timeout.clone()}
+                                                oninput={set_field("permission_timeout")}
+                                            />
+                                        </div>
+                                        <div class="sched-field">
+                                            <label>{ "Then" }</label>
+                                            <select onchange={on_timeout_action}>
+                                                { for [
+                                                    (PermissionTimeoutAction::Deny, "Deny the request"),
+                                                    (PermissionTimeoutAction::Allow, "Approve the request"),
+                                                    (PermissionTimeoutAction::Escalate, "Notify all session members"),
+                                                ].into_iter().map(|(value, label)| html! {
+                                                    <option
+                                                        value={value.as_str()}
+                                                        selected={form.permission_timeout_action == value}
+                                                    >
+                                                        { label }
+                                                    </option>
+                                                })}
+                                            </select>
+                                        </div>
+                                    </div>
+                                    if !teams.is_empty() {
+                                        <div class="sched-field">
+                                            <label>{ "Share with team" }</label>
+                                            <select onchange={on_team}>
+                                                <option value="" selected={form.team_id.is_none()}>
+                                                    { "Nobody" }
+                                                </option>
+                                                { for teams.iter().map(|team| html! {
+                                                    <option
+                                                        value={team.id.to_string()}
+                                                        selected={form.team_id == Some(team.id)}
+                                                    >
+                                                        { &team.name }
+                                                    </option>
+                                                })}
+                                            </select>
+                                            <span class="sched-hint">{ "Team members can follow every run" }</span>
+                                        </div>
+                                    }
                                     <div class="sched-field sched-checkbox">
                                         <label>
                                             <input
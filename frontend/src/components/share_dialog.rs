@@ -1,14 +1,27 @@
 use gloo::events::EventListener;
 use gloo_net::http::Request;
-use shared::api::{AddMemberRequest, UpdateMemberRoleRequest};
+use shared::api::{
+    AddMemberRequest, CreateShareLinkRequest, CreateShareLinkResponse, SessionTeamInfo,
+    SessionTeamsResponse, ShareLinkInfo, ShareLinkListResponse, ShareSessionWithTeamRequest,
+    TeamInfo, TeamListResponse, UpdateMemberRoleRequest,
+};
 use uuid::Uuid;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::spawn_local;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
 
+use crate::components::CopyCommand;
 use crate::utils;
 
+/// Lifetimes offered for a new public link, in hours
+const LINK_LIFETIMES: &[(u32, &str)] = &[
+    (1, "1 hour"),
+    (24, "1 day"),
+    (168, "7 days"),
+    (720, "30 days"),
+];
+
 /// Member info returned from API
 #[derive(Clone, Debug, PartialEq, serde::Deserialize)]
 pub struct MemberInfo {
@@ -40,6 +53,19 @@ pub enum ShareDialogMsg {
     MemberRemoved(Uuid),
     ChangeRole(Uuid, String),
     RoleChanged(Uuid, String),
+    LoadLinks,
+    LinksLoaded(Vec<ShareLinkInfo>),
+    UpdateLinkHours(u32),
+    UpdateLinkPassword(String),
+    CreateLink,
+    LinkCreated(String),
+    RevokeLink(Uuid),
+    LoadTeams,
+    TeamsLoaded(Vec<SessionTeamInfo>, Vec<TeamInfo>),
+    UpdateTeamChoice(Option<Uuid>),
+    UpdateTeamRole(String),
+    ShareWithTeam,
+    UnshareWithTeam(Uuid),
     SetError(String),
 }
 
@@ -49,6 +75,17 @@ pub struct ShareDialog {
     email_input: String,
     new_role: String,
     error: Option<String>,
+    links: Vec<ShareLinkInfo>,
+    link_hours: u32,
+    link_password: String,
+    /// URL of the link just created; its token is not retrievable later
+    new_link_url: Option<String>,
+    /// Teams the session is shared with
+    session_teams: Vec<SessionTeamInfo>,
+    /// The caller's teams, to share with
+    my_teams: Vec<TeamInfo>,
+    team_choice: Option<Uuid>,
+    team_role: String,
     #[allow(dead_code)] // RAII guard — must be held to keep listener active
     _escape_listener: Option<EventListener>,
 }
@@ -59,6 +96,8 @@ impl Component for ShareDialog {
 
     fn create(ctx: &Context<Self>) -> Self {
         ctx.link().send_message(ShareDialogMsg::LoadMembers);
+        ctx.link().send_message(ShareDialogMsg::LoadLinks);
+        ctx.link().send_message(ShareDialogMsg::LoadTeams);
         let on_close = ctx.props().on_close.clone();
         let listener = EventListener::new(&gloo::utils::document(), "keydown", move |event| {
             let e: &web_sys::KeyboardEvent = event.unchecked_ref();
@@ -72,6 +111,14 @@ impl Component for ShareDialog {
             email_input: String::new(),
             new_role: "viewer".to_string(),
             error: None,
+            links: Vec::new(),
+            link_hours: 24,
+            link_password: String::new(),
+            new_link_url: None,
+            session_teams: Vec::new(),
+            my_teams: Vec::new(),
+            team_choice: None,
+            team_role: "viewer".to_string(),
             _escape_listener: Some(listener),
         }
     }
@@ -237,6 +284,187 @@ impl Component for ShareDialog {
                 self.error = None;
                 true
             }
+            ShareDialogMsg::LoadLinks => {
+                let session_id = ctx.props().session_id;
+                let link = ctx.link().clone();
+                spawn_local(async move {
+                    let url = utils::api_url(&format!("/api/sessions/{}/share-links", session_id));
+                    match Request::get(&url).send().await {
+                        Ok(response) if response.ok() => {
+                            if let Ok(data) = response.json::<ShareLinkListResponse>().await {
+                                link.send_message(ShareDialogMsg::LinksLoaded(data.share_links));
+                            }
+                        }
+                        // Only owners manage links; others just don't see any
+                        Ok(_) => {}
+                        Err(e) => log::error!("Failed to load share links: {:?}", e),
+                    }
+                });
+                false
+            }
+            ShareDialogMsg::LinksLoaded(links) => {
+                self.links = links;
+                true
+            }
+            ShareDialogMsg::UpdateLinkHours(hours) => {
+                self.link_hours = hours;
+                true
+            }
+            ShareDialogMsg::UpdateLinkPassword(password) => {
+                self.link_password = password;
+                true
+            }
+            ShareDialogMsg::CreateLink => {
+                let session_id = ctx.props().session_id;
+                let body = CreateShareLinkRequest {
+                    expires_in_hours: self.link_hours,
+                    password: Some(self.link_password.clone()).filter(|p| !p.is_empty()),
+                };
+                let link = ctx.link().clone();
+                spawn_local(async move {
+                    let url = utils::api_url(&format!("/api/sessions/{}/share-links", session_id));
+                    match Request::post(&url).json(&body).unwrap().send().await {
+                        Ok(response) if response.ok() => {
+                            if let Ok(data) = response.json::<CreateShareLinkResponse>().await {
+                                link.send_message(ShareDialogMsg::LinkCreated(data.token));
+                            }
+                        }
+                        Ok(response) => {
+                            log::error!("Failed to create share link: {}", response.status());
+                            link.send_message(ShareDialogMsg::SetError(
+                                "Failed to create link".to_string(),
+                            ));
+                        }
+                        Err(e) => {
+                            log::error!("Failed to create share link: {:?}", e);
+                            link.send_message(ShareDialogMsg::SetError(
+                                "Failed to create link".to_string(),
+                            ));
+                        }
+                    }
+                });
+                false
+            }
+            ShareDialogMsg::LinkCreated(token) => {
+                self.new_link_url = Some(utils::api_url(&format!("/shared/{}", token)));
+                self.link_password.clear();
+                self.error = None;
+                ctx.link().send_message(ShareDialogMsg::LoadLinks);
+                true
+            }
+            ShareDialogMsg::RevokeLink(link_id) => {
+                let session_id = ctx.props().session_id;
+                let link = ctx.link().clone();
+                spawn_local(async move {
+                    let url = utils::api_url(&format!(
+                        "/api/sessions/{}/share-links/{}",
+                        session_id, link_id
+                    ));
+                    match Request::delete(&url).send().await {
+                        Ok(response) if response.status() == 204 => {
+                            link.send_message(ShareDialogMsg::LoadLinks);
+                        }
+                        _ => link.send_message(ShareDialogMsg::SetError(
+                            "Failed to revoke link".to_string(),
+                        )),
+                    }
+                });
+                false
+            }
+            ShareDialogMsg::LoadTeams => {
+                let session_id = ctx.props().session_id;
+                let link = ctx.link().clone();
+                spawn_local(async move {
+                    let url = utils::api_url(&format!("/api/sessions/{}/teams", session_id));
+                    // Only owners see who a session is shared with
+                    let shared = match Request::get(&url).send().await {
+                        Ok(response) if response.ok() => {
+                            match response.json::<SessionTeamsResponse>().await {
+                                Ok(data) => data.teams,
+                                Err(_) => return,
+                            }
+                        }
+                        Ok(_) => return,
+                        Err(e) => {
+                            log::error!("Failed to load session teams: {:?}", e);
+                            return;
+                        }
+                    };
+                    let mine = match Request::get(&utils::api_url("/api/teams")).send().await {
+                        Ok(response) if response.ok() => response
+                            .json::<TeamListResponse>()
+                            .await
+                            .map(|data| data.teams)
+                            .unwrap_or_default(),
+                        _ => Vec::new(),
+                    };
+                    link.send_message(ShareDialogMsg::TeamsLoaded(shared, mine));
+                });
+                false
+            }
+            ShareDialogMsg::TeamsLoaded(shared, mine) => {
+                self.session_teams = shared;
+                self.my_teams = mine;
+                true
+            }
+            ShareDialogMsg::UpdateTeamChoice(team_id) => {
+                self.team_choice = team_id;
+                true
+            }
+            ShareDialogMsg::UpdateTeamRole(role) => {
+                self.team_role = role;
+                true
+            }
+            ShareDialogMsg::ShareWithTeam => {
+                let Some(team_id) = self.team_choice else {
+                    return false;
+                };
+                let session_id = ctx.props().session_id;
+                let body = ShareSessionWithTeamRequest {
+                    team_id,
+                    role: self.team_role.clone(),
+                };
+                let link = ctx.link().clone();
+                spawn_local(async move {
+                    let url = utils::api_url(&format!("/api/sessions/{}/teams", session_id));
+                    match Request::post(&url).json(&body).unwrap().send().await {
+                        Ok(response) if response.ok() => {
+                            link.send_message(ShareDialogMsg::LoadTeams);
+                        }
+                        Ok(response) => {
+                            log::error!("Failed to share with team: {}", response.status());
+                            link.send_message(ShareDialogMsg::SetError(
+                                "Failed to share with team".to_string(),
+                            ));
+                        }
+                        Err(e) => {
+                            log::error!("Failed to share with team: {:?}", e);
+                            link.send_message(ShareDialogMsg::SetError(
+                                "Failed to share with team".to_string(),
+                            ));
+                        }
+                    }
+                });
+                self.team_choice = None;
+                true
+            }
+            ShareDialogMsg::UnshareWithTeam(team_id) => {
+                let session_id = ctx.props().session_id;
+                let link = ctx.link().clone();
+                spawn_local(async move {
+                    let url =
+                        utils::api_url(&format!("/api/sessions/{}/teams/{}", session_id, team_id));
+                    match Request::delete(&url).send().await {
+                        Ok(response) if response.status() == 204 => {
+                            link.send_message(ShareDialogMsg::LoadTeams);
+                        }
+                        _ => link.send_message(ShareDialogMsg::SetError(
+                            "Failed to stop sharing with team".to_string(),
+                        )),
+                    }
+                });
+                false
+            }
             ShareDialogMsg::SetError(error) => {
                 self.error = Some(error);
                 self.loading = false;
@@ -327,6 +555,10 @@ impl Component for ShareDialog {
                             }
                         }
                     </div>
+
+                    { self.view_teams(ctx) }
+
+                    { self.view_links(ctx) }
                 </div>
             </div>
         }
@@ -334,6 +566,163 @@ impl Component for ShareDialog {
 }
 
 impl ShareDialog {
+    fn view_teams(&self, ctx: &Context<Self>) -> Html {
+        if self.my_teams.is_empty() && self.session_teams.is_empty() {
+            return html! {};
+        }
+        let on_team_change = ctx.link().callback(|e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            ShareDialogMsg::UpdateTeamChoice(select.value().parse().ok())
+        });
+        let on_role_change = ctx.link().callback(|e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            ShareDialogMsg::UpdateTeamRole(select.value())
+        });
+        let on_share = ctx.link().callback(|_| ShareDialogMsg::ShareWithTeam);
+
+        html! {
+            <div class="share-dialog-teams">
+                <h3>{ "Teams" }</h3>
+                <p class="share-dialog-hint">
+                    { "Everyone in the team gets this role, including people who join it later." }
+                </p>
+                if !self.my_teams.is_empty() {
+                    <div class="share-dialog-add">
+                        <select onchange={on_team_change}>
+                            <option value="" selected={self.team_choice.is_none()}>{ "Choose a team" }</option>
+                            { for self.my_teams.iter().map(|team| html! {
+                                <option value={team.id.to_string()} selected={self.team_choice == Some(team.id)}>
+                                    { &team.name }
+                                </option>
+                            }) }
+                        </select>
+                        <select value={self.team_role.clone()} onchange={on_role_change}>
+                            <option value="viewer">{ "Viewer" }</option>
+                            <option value="editor">{ "Editor" }</option>
+                        </select>
+                        <button onclick={on_share} disabled={self.team_choice.is_none()}>{ "Share" }</button>
+                    </div>
+                }
+                if !self.session_teams.is_empty() {
+                    <ul>
+                        { for self.session_teams.iter().map(|team| {
+                            let team_id = team.team_id;
+                            let on_unshare = ctx
+                                .link()
+                                .callback(move |_| ShareDialogMsg::UnshareWithTeam(team_id));
+                            html! {
+                                <li class="share-dialog-member">
+                                    <span class="member-name">{ &team.team_name }</span>
+                                    <span class="member-role">
+                                        { if team.role == "editor" { "Editor" } else { "Viewer" } }
+                                    </span>
+                                    <button class="member-remove" onclick={on_unshare} title="Stop sharing">
+                                        { "×" }
+                                    </button>
+                                </li>
+                            }
+                        }) }
+                    </ul>
+                }
+            </div>
+        }
+    }
+
+    fn view_links(&self, ctx: &Context<Self>) -> Html {
+        let on_hours_change = ctx.link().callback(|e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            ShareDialogMsg::UpdateLinkHours(select.value().parse().unwrap_or(24))
+        });
+        let on_password_input = ctx.link().callback(|e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            ShareDialogMsg::UpdateLinkPassword(input.value())
+        });
+        let on_create = ctx.link().callback(|_| ShareDialogMsg::CreateLink);
+
+        html! {
+            <div class="share-dialog-links">
+                <h3>{ "Public links" }</h3>
+                <p class="share-dialog-hint">
+                    { "Anyone with the link can read this session without an account. They cannot send input, answer permissions or see cost." }
+                </p>
+                <div class="share-dialog-add">
+                    <select onchange={on_hours_change}>
+                        { for LINK_LIFETIMES.iter().map(|(hours, label)| html! {
+                            <option value={hours.to_string()} selected={*hours == self.link_hours}>
+                                { format!("Expires in {}", label) }
+                            </option>
+                        }) }
+                    </select>
+                    <input
+                        type="password"
+                        placeholder="Password (optional)"
+                        value={self.link_password.clone()}
+                        oninput={on_password_input}
+                    />
+                    <button onclick={on_create}>{ "Create" }</button>
+                </div>
+                if let Some(url) = &self.new_link_url {
+                    <div class="share-link-new">
+                        <CopyCommand
+                            command={url.clone()}
+                            label={"Copy this link now, it is not shown again".to_string()}
+                        />
+                    </div>
+                }
+                if !self.links.is_empty() {
+                    <ul>
+                        { for self.links.iter().map(|l| self.view_link(ctx, l)) }
+                    </ul>
+                }
+            </div>
+        }
+    }
+
+    fn view_link(&self, ctx: &Context<Self>, share_link: &ShareLinkInfo) -> Html {
+        let link_id = share_link.id;
+        let expired = js_sys::Date::parse(&share_link.expires_at) < js_sys::Date::now();
+        let active = !share_link.revoked && !expired;
+        let status = if share_link.revoked {
+            "Revoked".to_string()
+        } else if expired {
+            "Expired".to_string()
+        } else {
+            format!(
+                "Expires {}",
+                utils::format_timestamp(&share_link.expires_at)
+            )
+        };
+        let views = match share_link.view_count {
+            1 => "1 view".to_string(),
+            n => format!("{} views", n),
+        };
+        let last_viewed = share_link
+            .last_viewed_at
+            .as_deref()
+            .map(|t| format!("Last viewed {}", utils::format_timestamp(t)))
+            .unwrap_or_default();
+        let on_revoke = ctx
+            .link()
+            .callback(move |_| ShareDialogMsg::RevokeLink(link_id));
+
+        html! {
+            <li class={classes!("share-link", (!active).then_some("inactive"))}>
+                <span class="share-link-status">
+                    { status }
+                    if share_link.has_password {
+                        <span class="share-link-lock" title="Password protected">{ " 🔒" }</span>
+                    }
+                </span>
+                <span class="share-link-views" title={last_viewed}>{ views }</span>
+                if active {
+                    <button class="member-remove" onclick={on_revoke} title="Revoke link">
+                        { "×" }
+                    </button>
+                }
+            </li>
+        }
+    }
+
     fn view_member(&self, ctx: &Context<Self>, member: &MemberInfo) -> Html {
         let is_owner = member.role == "owner";
         let user_id = member.user_id;
@@ -1,7 +1,7 @@
 use serde_json::Value;
 use yew::prelude::*;
 
-use crate::components::message_renderer::format_duration;
+use shared::render::format_duration;
 
 pub fn render_bash_tool(input: &Value) -> Html {
     let command = input.get("command").and_then(|v| v.as_str()).unwrap_or("");
@@ -0,0 +1,448 @@
+//! Usage charts — daily cost and tokens from `/api/analytics`
+//!
+//! The admin overview shows everyone's usage; the usage page shows the
+//! signed-in user's own.
+
+use crate::utils;
+use gloo_net::http::Request;
+use shared::api::{AnalyticsGroupBy, AnalyticsResponse, UsageTotals};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+/// Colors of the costliest groups, then of everything else
+const PALETTE: [&str; 5] = ["#7aa2f7", "#e0af68", "#9ece6a", "#bb9af7", "#7dcfff"];
+const OTHER_COLOR: &str = "#565f89";
+const RANGES: [(i64, &str); 4] = [
+    (7, "7 days"),
+    (30, "30 days"),
+    (90, "90 days"),
+    (365, "1 year"),
+];
+
+const CHART_WIDTH: f64 = 720.0;
+const CHART_HEIGHT: f64 = 220.0;
+const AXIS_WIDTH: f64 = 64.0;
+const LABEL_HEIGHT: f64 = 20.0;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Metric {
+    Cost,
+    Tokens,
+}
+
+impl Metric {
+    fn value(&self, usage: &UsageTotals) -> f64 {
+        match self {
+            Metric::Cost => usage.cost_usd,
+            Metric::Tokens => usage.total_tokens() as f64,
+        }
+    }
+
+    fn format(&self, value: f64) -> String {
+        match self {
+            Metric::Cost => utils::format_dollars(value),
+            Metric::Tokens => utils::format_tokens(value as i64),
+        }
+    }
+}
+
+/// The day after a `YYYY-MM-DD` date
+fn next_day(day: &str) -> Option<String> {
+    let mut parts = day.splitn(3, '-').map(|p| p.parse::<u32>().ok());
+    let (year, month, date) = (parts.next()??, parts.next()??, parts.next()??);
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let month_len = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    let (year, month, date) = if date < month_len {
+        (year, month, date + 1)
+    } else if month < 12 {
+        (year, month + 1, 1)
+    } else {
+        (year + 1, 1, 1)
+    };
+    Some(format!("{:04}-{:02}-{:02}", year, month, date))
+}
+
+/// Every day from `from` to `to`, both included
+fn days_in_range(from: &str, to: &str) -> Vec<String> {
+    let mut days = vec![from.to_string()];
+    while days.len() <= 400 && days.last().is_some_and(|d| d.as_str() < to) {
+        match days.last().and_then(|d| next_day(d)) {
+            Some(day) => days.push(day),
+            None => break,
+        }
+    }
+    days
+}
+
+/// One layer of the stacked chart
+#[derive(Debug, PartialEq)]
+struct Series {
+    label: String,
+    color: &'static str,
+    /// One value per day
+    values: Vec<f64>,
+}
+
+/// Daily values of the costliest groups, with the rest folded into "Other"
+fn chart_series(data: &AnalyticsResponse, days: &[String], metric: Metric) -> Vec<Series> {
+    let mut series: Vec<Series> = data
+        .groups
+        .iter()
+        .take(PALETTE.len())
+        .zip(PALETTE)
+        .map(|(group, color)| Series {
+            label: group.label.clone(),
+            color,
+            values: vec![0.0; days.len()],
+        })
+        .collect();
+    let mut other = Series {
+        label: "Other".to_string(),
+        color: OTHER_COLOR,
+        values: vec![0.0; days.len()],
+    };
+    for point in &data.points {
+        let Some(day) = days.iter().position(|d| *d == point.day) else {
+            continue;
+        };
+        let layer = match data.groups.iter().position(|g| g.key == point.key) {
+            Some(i) if i < series.len() => &mut series[i],
+            _ => &mut other,
+        };
+        layer.values[day] += metric.value(&point.usage);
+    }
+    if other.values.iter().any(|v| *v > 0.0) {
+        series.push(other);
+    }
+    series
+}
+
+fn render_chart(days: &[String], series: &[Series], metric: Metric) -> Html {
+    let totals: Vec<f64> = (0..days.len())
+        .map(|i| series.iter().map(|s| s.values[i]).sum())
+        .collect();
+    let max = totals.iter().cloned().fold(0.0, f64::max);
+    if max <= 0.0 {
+        return html! { <p class="usage-empty">{ "No usage in this period." }</p> };
+    }
+
+    let plot_height = CHART_HEIGHT - LABEL_HEIGHT;
+    let slot = (CHART_WIDTH - AXIS_WIDTH) / days.len() as f64;
+    let bar_width = (slot * 0.8).max(1.0);
+    let y = |value: f64| plot_height - value / max * (plot_height - 8.0);
+
+    let mut bars = Vec::new();
+    for (i, day) in days.iter().enumerate() {
+        let x = AXIS_WIDTH + i as f64 * slot + (slot - bar_width) / 2.0;
+        let mut base = 0.0;
+        for layer in series {
+            let value = layer.values[i];
+            if value <= 0.0 {
+                continue;
+            }
+            let (top, bottom) = (y(base + value), y(base));
+            bars.push(html! {
+                <rect
+                    x={format!("{:.1}", x)}
+                    y={format!("{:.1}", top)}
+                    width={format!("{:.1}", bar_width)}
+                    height={format!("{:.1}", (bottom - top).max(0.5))}
+                    fill={layer.color}
+                >
+                    <title>{ format!("{} · {}: {}", day, layer.label, metric.format(value)) }</title>
+                </rect>
+            });
+            base += value;
+        }
+    }
+
+    let gridlines = [0.0, 0.5, 1.0].map(|fraction| {
+        let value = max * fraction;
+        let line_y = format!("{:.1}", y(value));
+        html! {
+            <g class="usage-gridline">
+                <line x1={AXIS_WIDTH.to_string()} x2={CHART_WIDTH.to_string()} y1={line_y.clone()} y2={line_y.clone()} />
+                <text x={(AXIS_WIDTH - 6.0).to_string()} y={line_y} text-anchor="end" dominant-baseline="middle">
+                    { metric.format(value) }
+                </text>
+            </g>
+        }
+    });
+
+    // Label the first, middle and last day
+    let labelled = [0, days.len() / 2, days.len() - 1];
+    let day_labels = labelled.iter().enumerate().map(|(n, &i)| {
+        if n > 0 && labelled[n - 1] == i {
+            return html! {};
+        }
+        let x = AXIS_WIDTH + (i as f64 + 0.5) * slot;
+        html! {
+            <text class="usage-day-label" x={format!("{:.1}", x)} y={(CHART_HEIGHT - 4.0).to_string()} text-anchor="middle">
+                { days[i].get(5..).unwrap_or(&days[i]) }
+            </text>
+        }
+    });
+
+    html! {
+        <svg class="usage-chart" viewBox={format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT)}>
+            { for gridlines }
+            { for bars }
+            { for day_labels }
+        </svg>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct UsageChartsProps {
+    /// Everyone's usage (admins only) rather than the signed-in user's
+    #[prop_or_default]
+    pub all_users: bool,
+}
+
+#[function_component(UsageCharts)]
+pub fn usage_charts(props: &UsageChartsProps) -> Html {
+    let group_by = use_state(AnalyticsGroupBy::default);
+    let range = use_state(|| 30i64);
+    let metric = use_state(|| Metric::Cost);
+    let data = use_state(|| None::<AnalyticsResponse>);
+    let error = use_state(|| None::<String>);
+
+    {
+        let data = data.clone();
+        let error = error.clone();
+        let all_users = props.all_users;
+        use_effect_with((*group_by, *range), move |(group_by, range)| {
+            let group_by = *group_by;
+            let range = *range;
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/analytics");
+                let mut params = vec![
+                    ("group_by", group_by.as_str().to_string()),
+                    ("days", range.to_string()),
+                ];
+                if all_users {
+                    params.push(("all", "true".to_string()));
+                }
+                let response = Request::get(&api_endpoint)
+                    .query(params.iter().map(|(name, value)| (*name, value.as_str())))
+                    .send()
+                    .await;
+                match response {
+                    Ok(response) if response.ok() => {
+                        match response.json::<AnalyticsResponse>().await {
+                            Ok(body) => {
+                                error.set(None);
+                                data.set(Some(body));
+                            }
+                            Err(e) => error.set(Some(format!("Failed to parse usage: {:?}", e))),
+                        }
+                    }
+                    Ok(response) => error.set(Some(format!(
+                        "Failed to load usage (HTTP {})",
+                        response.status()
+                    ))),
+                    Err(e) => error.set(Some(format!("Failed to load usage: {:?}", e))),
+                }
+            });
+        });
+    }
+
+    let on_group_by = {
+        let group_by = group_by.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            group_by.set(select.value().parse().unwrap_or_default());
+        })
+    };
+    let on_range = {
+        let range = range.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            range.set(select.value().parse().unwrap_or(30));
+        })
+    };
+    let metric_button = |value: Metric, label: &'static str| {
+        let metric = metric.clone();
+        let active = *metric == value;
+        html! {
+            <button
+                class={classes!("usage-metric", active.then_some("active"))}
+                onclick={Callback::from(move |_| metric.set(value))}
+            >
+                { label }
+            </button>
+        }
+    };
+
+    let groupings = AnalyticsGroupBy::ALL
+        .into_iter()
+        .filter(|g| props.all_users || *g != AnalyticsGroupBy::User);
+
+    let body = match &*data {
+        None => html! { <p class="usage-empty">{ "Loading usage..." }</p> },
+        Some(data) => {
+            let days = days_in_range(&data.from, &data.to);
+            let series = chart_series(data, &days, *metric);
+            let mut total = UsageTotals::default();
+            for group in &data.groups {
+                total.add(&group.usage);
+            }
+            html! {
+                <>
+                    <div class="usage-summary">
+                        <span><strong>{ utils::format_dollars(total.cost_usd) }</strong>{ " spent" }</span>
+                        <span><strong>{ utils::format_tokens(total.total_tokens()) }</strong>{ " tokens" }</span>
+                        <span><strong>{ total.turns }</strong>{ " turns" }</span>
+                    </div>
+                    { render_chart(&days, &series, *metric) }
+                    <div class="usage-legend">
+                        { for series.iter().map(|s| html! {
+                            <span class="usage-legend-item">
+                                <span class="usage-swatch" style={format!("background: {}", s.color)}></span>
+                                { &s.label }
+                            </span>
+                        }) }
+                    </div>
+                    if !data.groups.is_empty() {
+                        <table class="admin-table usage-table">
+                            <thead>
+                                <tr>
+                                    <th>{ data.group_by.label() }</th>
+                                    <th>{ "Cost" }</th>
+                                    <th>{ "Share" }</th>
+                                    <th>{ "Turns" }</th>
+                                    <th>{ "Input" }</th>
+                                    <th>{ "Output" }</th>
+                                    <th>{ "Cache" }</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                { for data.groups.iter().map(|group| {
+                                    let usage = &group.usage;
+                                    let share = if total.cost_usd > 0.0 { usage.cost_usd / total.cost_usd * 100.0 } else { 0.0 };
+                                    html! {
+                                        <tr key={group.key.clone()}>
+                                            <td title={group.key.clone()}>{ &group.label }</td>
+                                            <td class="numeric">{ utils::format_dollars(usage.cost_usd) }</td>
+                                            <td class="numeric">{ format!("{:.0}%", share) }</td>
+                                            <td class="numeric">{ usage.turns }</td>
+                                            <td class="numeric">{ utils::format_tokens(usage.input_tokens) }</td>
+                                            <td class="numeric">{ utils::format_tokens(usage.output_tokens) }</td>
+                                            <td class="numeric">{ utils::format_tokens(usage.cache_creation_tokens + usage.cache_read_tokens) }</td>
+                                        </tr>
+                                    }
+                                }) }
+                            </tbody>
+                        </table>
+                    }
+                </>
+            }
+        }
+    };
+
+    html! {
+        <div class="usage-charts">
+            <div class="usage-controls">
+                <label>
+                    { "Group by " }
+                    <select onchange={on_group_by}>
+                        { for groupings.map(|g| html! {
+                            <option value={g.as_str()} selected={*group_by == g}>{ g.label() }</option>
+                        }) }
+                    </select>
+                </label>
+                <label>
+                    { "Range " }
+                    <select onchange={on_range}>
+                        { for RANGES.iter().map(|(days, label)| html! {
+                            <option value={days.to_string()} selected={*range == *days}>{ *label }</option>
+                        }) }
+                    </select>
+                </label>
+                <div class="usage-metrics">
+                    { metric_button(Metric::Cost, "Cost") }
+                    { metric_button(Metric::Tokens, "Tokens") }
+                </div>
+            </div>
+            if let Some(message) = (*error).clone() {
+                <div class="admin-error">{ message }</div>
+            }
+            { body }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::api::{AnalyticsGroup, AnalyticsPoint};
+
+    #[test]
+    fn test_next_day() {
+        assert_eq!(next_day("2026-03-24").as_deref(), Some("2026-03-25"));
+        assert_eq!(next_day("2026-03-31").as_deref(), Some("2026-04-01"));
+        assert_eq!(next_day("2026-12-31").as_deref(), Some("2027-01-01"));
+        assert_eq!(next_day("2028-02-28").as_deref(), Some("2028-02-29"));
+        assert_eq!(next_day("2026-02-28").as_deref(), Some("2026-03-01"));
+        assert_eq!(next_day("not a day"), None);
+    }
+
+    #[test]
+    fn test_days_in_range() {
+        assert_eq!(
+            days_in_range("2026-02-27", "2026-03-02"),
+            vec!["2026-02-27", "2026-02-28", "2026-03-01", "2026-03-02"]
+        );
+        assert_eq!(
+            days_in_range("2026-03-24", "2026-03-24"),
+            vec!["2026-03-24"]
+        );
+    }
+
+    fn usage(cost_usd: f64) -> UsageTotals {
+        UsageTotals {
+            cost_usd,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_chart_series_folds_small_groups() {
+        let groups: Vec<AnalyticsGroup> = (0..7)
+            .map(|i| AnalyticsGroup {
+                key: format!("model-{}", i),
+                label: format!("Model {}", i),
+                usage: usage(10.0 - i as f64),
+            })
+            .collect();
+        let points = groups
+            .iter()
+            .map(|g| AnalyticsPoint {
+                day: "2026-03-25".to_string(),
+                key: g.key.clone(),
+                usage: g.usage.clone(),
+            })
+            .collect();
+        let data = AnalyticsResponse {
+            group_by: AnalyticsGroupBy::Model,
+            from: "2026-03-24".to_string(),
+            to: "2026-03-25".to_string(),
+            all_users: true,
+            groups,
+            points,
+        };
+        let days = days_in_range(&data.from, &data.to);
+        let series = chart_series(&data, &days, Metric::Cost);
+        assert_eq!(series.len(), PALETTE.len() + 1);
+        assert_eq!(series[0].values, vec![0.0, 10.0]);
+        let other = series.last().unwrap();
+        assert_eq!(other.label, "Other");
+        // Models 5 and 6 cost 5 + 4
+        assert_eq!(other.values, vec![0.0, 9.0]);
+    }
+}
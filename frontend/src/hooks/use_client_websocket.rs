@@ -2,6 +2,7 @@
 
 use crate::utils;
 use shared::{ClientEndpoint, ServerToClient, WsEndpoint};
+use uuid::Uuid;
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 
@@ -11,6 +12,31 @@ pub struct UseClientWebSocket {
     pub total_spend: f64,
     /// Server shutdown reason (if server is shutting down)
     pub shutdown_reason: Option<String>,
+    /// Most recent permission request escalated after its timeout
+    pub escalation: Option<PermissionEscalation>,
+    /// Clear the current escalation notice
+    pub dismiss_escalation: Callback<()>,
+    /// Most recent spend budget alert
+    pub spend_alert: Option<SpendAlert>,
+    /// Clear the current spend alert
+    pub dismiss_spend_alert: Callback<()>,
+}
+
+/// One of the user's budgets crossed its soft or hard limit
+#[derive(Clone, PartialEq)]
+pub struct SpendAlert {
+    pub message: String,
+    /// The hard limit: sessions were interrupted and input is refused
+    pub hard: bool,
+}
+
+/// A permission request that went unanswered and was escalated to all members
+#[derive(Clone, PartialEq)]
+pub struct PermissionEscalation {
+    pub session_id: Uuid,
+    pub session_name: String,
+    pub tool_name: String,
+    pub waiting_seconds: i64,
 }
 
 /// Calculate exponential backoff delay for reconnection attempts.
@@ -24,7 +50,8 @@ fn calculate_backoff(attempt: u32) -> u32 {
 
 /// Hook for managing the client WebSocket connection.
 ///
-/// Connects to the client WebSocket endpoint and receives spend updates and server shutdown notifications.
+/// Connects to the client WebSocket endpoint and receives spend updates and alerts, permission
+/// escalations and server shutdown notifications.
 /// Automatically reconnects with exponential backoff on disconnection.
 ///
 /// # Returns
@@ -34,14 +61,20 @@ fn calculate_backoff(attempt: u32) -> u32 {
 pub fn use_client_websocket() -> UseClientWebSocket {
     let total_spend = use_state(|| 0.0f64);
     let shutdown_reason = use_state(|| None::<String>);
+    let escalation = use_state(|| None::<PermissionEscalation>);
+    let spend_alert = use_state(|| None::<SpendAlert>);
 
     {
         let total_spend = total_spend.clone();
         let shutdown_reason = shutdown_reason.clone();
+        let escalation = escalation.clone();
+        let spend_alert = spend_alert.clone();
 
         use_effect_with((), move |_| {
             let total_spend = total_spend.clone();
             let shutdown_reason = shutdown_reason.clone();
+            let escalation = escalation.clone();
+            let spend_alert = spend_alert.clone();
 
             spawn_local(async move {
                 let mut attempt: u32 = 0;
@@ -75,6 +108,31 @@ pub fn use_client_websocket() -> UseClientWebSocket {
                                             );
                                             shutdown_reason.set(Some(reason));
                                         }
+                                        ServerToClient::PermissionEscalated {
+                                            session_id,
+                                            session_name,
+                                            request_id: _,
+                                            tool_name,
+                                            waiting_seconds,
+                                        } => {
+                                            escalation.set(Some(PermissionEscalation {
+                                                session_id,
+                                                session_name,
+                                                tool_name,
+                                                waiting_seconds,
+                                            }));
+                                        }
+                                        ServerToClient::SpendAlert { message, hard } => {
+                                            spend_alert.set(Some(SpendAlert { message, hard }));
+                                        }
+                                        ServerToClient::SignedOut => {
+                                            // This browser's login was revoked; reconnecting
+                                            // would only be refused
+                                            if let Some(window) = web_sys::window() {
+                                                let _ = window.location().set_href("/");
+                                            }
+                                            return;
+                                        }
                                         _ => {
                                             shutdown_reason.set(None);
                                         }
@@ -110,8 +168,22 @@ pub fn use_client_websocket() -> UseClientWebSocket {
         });
     }
 
+    let dismiss_escalation = {
+        let escalation = escalation.clone();
+        Callback::from(move |_| escalation.set(None))
+    };
+
+    let dismiss_spend_alert = {
+        let spend_alert = spend_alert.clone();
+        Callback::from(move |_| spend_alert.set(None))
+    };
+
     UseClientWebSocket {
         total_spend: *total_spend,
         shutdown_reason: (*shutdown_reason).clone(),
+        escalation: (*escalation).clone(),
+        dismiss_escalation,
+        spend_alert: (*spend_alert).clone(),
+        dismiss_spend_alert,
     }
 }
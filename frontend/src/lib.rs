@@ -8,8 +8,18 @@ pub mod utils;
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 use pages::{
-    access_denied::AccessDeniedPage, admin::AdminPage, banned::BannedPage,
-    dashboard::DashboardPage, settings::SettingsPage, splash::SplashPage,
+    access_denied::AccessDeniedPage,
+    account_setup::AccountSetupPage,
+    admin::AdminPage,
+    banned::BannedPage,
+    dashboard::DashboardPage,
+    invite::InvitePage,
+    login::LoginPage,
+    search::{MessageLink, SearchPage},
+    settings::SettingsPage,
+    shared::SharedSessionPage,
+    splash::SplashPage,
+    usage::UsagePage,
 };
 use yew::prelude::*;
 use yew_router::prelude::*;
@@ -24,10 +34,26 @@ pub enum Route {
     Settings,
     #[at("/admin")]
     Admin,
+    #[at("/search")]
+    Search,
+    #[at("/usage")]
+    Usage,
+    /// Public read-only view of a session; needs no sign-in
+    #[at("/shared/:token")]
+    Shared { token: String },
     #[at("/banned")]
     Banned,
     #[at("/access-denied")]
     AccessDenied,
+    /// Local account sign-in
+    #[at("/login")]
+    Login,
+    /// Where a local account's setup link leads
+    #[at("/setup-account")]
+    AccountSetup,
+    /// Where an invite link leads
+    #[at("/invite")]
+    Invite,
 }
 
 /// Wrapper for /admin route — provides back-navigation on_close callback
@@ -46,14 +72,42 @@ fn settings_route() -> Html {
     html! { <SettingsPage on_close={on_close} /> }
 }
 
+/// Wrapper for /search route — opens hits on the dashboard
+#[function_component(SearchRoute)]
+fn search_route() -> Html {
+    let navigator = use_navigator().unwrap();
+    let on_close = {
+        let navigator = navigator.clone();
+        Callback::from(move |_| navigator.push(&Route::Dashboard))
+    };
+    let on_open = Callback::from(move |hit: shared::api::SearchHit| {
+        let _ = navigator.push_with_query(&Route::Dashboard, &MessageLink::from(&hit));
+    });
+    html! { <SearchPage on_close={on_close} on_open={on_open} /> }
+}
+
+/// Wrapper for /usage route — provides back-navigation on_close callback
+#[function_component(UsageRoute)]
+fn usage_route() -> Html {
+    let navigator = use_navigator().unwrap();
+    let on_close = Callback::from(move |_| navigator.push(&Route::Dashboard));
+    html! { <UsagePage on_close={on_close} /> }
+}
+
 fn switch(routes: Route) -> Html {
     match routes {
         Route::Home => html! { <SplashPage /> },
         Route::Dashboard => html! { <DashboardPage /> },
         Route::Settings => html! { <SettingsRoute /> },
         Route::Admin => html! { <AdminRoute /> },
+        Route::Search => html! { <SearchRoute /> },
+        Route::Usage => html! { <UsageRoute /> },
+        Route::Shared { token } => html! { <SharedSessionPage token={token} /> },
         Route::Banned => html! { <BannedPage /> },
         Route::AccessDenied => html! { <AccessDeniedPage /> },
+        Route::Login => html! { <LoginPage /> },
+        Route::AccountSetup => html! { <AccountSetupPage /> },
+        Route::Invite => html! { <InvitePage /> },
     }
 }
 
@@ -15,7 +15,7 @@ pub fn access_denied_page() -> Html {
                     <p>{ "This portal restricts access to specific email addresses or domains. Your email is not on the allowlist." }</p>
                 </div>
                 <p class="banned-contact">
-                    { "If you believe this is an error, please contact the portal administrator." }
+                    { "If you believe this is an error, please contact the portal administrator. They can also send you an invite link." }
                 </p>
             </div>
         </div>
@@ -0,0 +1,179 @@
+//! Setup link landing page
+//!
+//! An admin creates a local account (or resets its password) and hands out
+//! `/setup-account?token=...`; the owner chooses a password here and is
+//! signed in, or sent to the login page when the account has a second factor.
+
+use crate::utils;
+use crate::Route;
+use gloo_net::http::Request;
+use serde::Deserialize;
+use shared::api::{AccountSetupInfo, AccountSetupRequest, LocalLoginResponse};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+struct SetupQuery {
+    #[serde(default)]
+    token: String,
+}
+
+#[function_component(AccountSetupPage)]
+pub fn account_setup_page() -> Html {
+    let navigator = use_navigator().unwrap();
+    let token = use_location()
+        .and_then(|l| l.query::<SetupQuery>().ok())
+        .unwrap_or_default()
+        .token;
+    let info = use_state(|| None::<AccountSetupInfo>);
+    let load_error = use_state(|| None::<String>);
+    let password = use_state(String::new);
+    let confirm = use_state(String::new);
+    let submitting = use_state(|| false);
+    let error = use_state(|| None::<String>);
+
+    {
+        let info = info.clone();
+        let load_error = load_error.clone();
+        use_effect_with(token.clone(), move |token| {
+            let token = token.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/auth/local/setup");
+                let response = Request::get(&api_endpoint)
+                    .query([("token", token.as_str())])
+                    .send()
+                    .await;
+                match response {
+                    Ok(response) if response.ok() => {
+                        match response.json::<AccountSetupInfo>().await {
+                            Ok(loaded) => info.set(Some(loaded)),
+                            Err(e) => load_error.set(Some(format!("{:?}", e))),
+                        }
+                    }
+                    Ok(_) => load_error.set(Some(
+                        "This link is invalid or has expired. Ask an admin for a new one."
+                            .to_string(),
+                    )),
+                    Err(e) => load_error.set(Some(format!("{:?}", e))),
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_password = {
+        let password = password.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            password.set(input.value());
+        })
+    };
+    let on_confirm = {
+        let confirm = confirm.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            confirm.set(input.value());
+        })
+    };
+
+    let on_submit = {
+        let token = token.clone();
+        let password = password.clone();
+        let confirm = confirm.clone();
+        let submitting = submitting.clone();
+        let error = error.clone();
+        let navigator = navigator.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            if *password != *confirm {
+                error.set(Some("The passwords do not match".to_string()));
+                return;
+            }
+            let body = AccountSetupRequest {
+                token: token.clone(),
+                password: (*password).clone(),
+            };
+            let submitting = submitting.clone();
+            let error = error.clone();
+            let navigator = navigator.clone();
+            submitting.set(true);
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/auth/local/setup");
+                let response = Request::post(&api_endpoint)
+                    .json(&body)
+                    .unwrap()
+                    .send()
+                    .await;
+                match response {
+                    Ok(response) if response.ok() => {
+                        match response.json::<LocalLoginResponse>().await {
+                            Ok(result) if result.totp_required => navigator.push(&Route::Login),
+                            Ok(_) => navigator.push(&Route::Dashboard),
+                            Err(e) => error.set(Some(format!("{:?}", e))),
+                        }
+                    }
+                    Ok(response) if response.status() == 400 => error.set(Some(
+                        "Choose a password of at least 12 characters".to_string(),
+                    )),
+                    Ok(response) if response.status() == 403 => navigator.push(&Route::Banned),
+                    Ok(response) => error.set(Some(format!(
+                        "Failed to set the password (HTTP {})",
+                        response.status()
+                    ))),
+                    Err(e) => error.set(Some(format!("{:?}", e))),
+                }
+                submitting.set(false);
+            });
+        })
+    };
+
+    let body = if let Some(message) = (*load_error).clone() {
+        html! { <div class="login-error">{ message }</div> }
+    } else if let Some(info) = (*info).clone() {
+        html! {
+            <>
+                <p class="login-hint">
+                    { format!("Choose a password for {} ({}).", info.username, info.email) }
+                    <br />
+                    { format!("This link expires {}.", utils::format_timestamp(&info.expires_at)) }
+                </p>
+                if let Some(message) = (*error).clone() {
+                    <div class="login-error">{ message }</div>
+                }
+                <label for="setup-password">{ "New password" }</label>
+                <input
+                    type="password"
+                    id="setup-password"
+                    autocomplete="new-password"
+                    minlength="12"
+                    value={(*password).clone()}
+                    oninput={on_password}
+                    autofocus=true
+                />
+                <label for="setup-confirm">{ "Repeat password" }</label>
+                <input
+                    type="password"
+                    id="setup-confirm"
+                    autocomplete="new-password"
+                    value={(*confirm).clone()}
+                    oninput={on_confirm}
+                />
+                <button type="submit" disabled={*submitting}>
+                    { if *submitting { "Saving..." } else { "Set password" } }
+                </button>
+            </>
+        }
+    } else {
+        html! { <p class="login-hint">{ "Loading..." }</p> }
+    };
+
+    html! {
+        <div class="login-page">
+            <form class="login-form" onsubmit={on_submit}>
+                <h1>{ "Set up your account" }</h1>
+                { body }
+            </form>
+        </div>
+    }
+}
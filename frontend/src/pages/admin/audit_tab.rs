@@ -0,0 +1,280 @@
+//! Admin audit tab — who changed what, with filters and a JSON export
+
+use crate::utils;
+use gloo_net::http::Request;
+use shared::api::{AuditAction, AuditEventInfo, AuditEventsResponse};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::MouseEvent;
+use yew::prelude::*;
+
+/// Events fetched per page
+const PAGE_SIZE: usize = 100;
+
+/// Filters as typed into the form; empty fields match everything
+#[derive(Clone, Default, PartialEq)]
+struct AuditFilters {
+    action: String,
+    actor: String,
+    /// `YYYY-MM-DD`, as date inputs report it
+    since: String,
+    until: String,
+}
+
+impl AuditFilters {
+    fn params(&self) -> Vec<(&'static str, String)> {
+        [
+            ("action", &self.action),
+            ("actor", &self.actor),
+            ("since", &self.since),
+            ("until", &self.until),
+        ]
+        .into_iter()
+        .filter(|(_, value)| !value.trim().is_empty())
+        .map(|(name, value)| (name, value.trim().to_string()))
+        .collect()
+    }
+
+    /// Download link for everything the filters match
+    fn export_url(&self) -> String {
+        let query: Vec<String> = self
+            .params()
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, js_sys::encode_uri_component(value)))
+            .collect();
+        let url = utils::api_url("/api/admin/audit/export");
+        if query.is_empty() {
+            url
+        } else {
+            format!("{}?{}", url, query.join("&"))
+        }
+    }
+}
+
+/// `key: value` pairs of an event's details, on one line
+fn format_details(details: &serde_json::Value) -> String {
+    let format_value = |value: &serde_json::Value| match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    match details {
+        serde_json::Value::Object(map) => map
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, format_value(value)))
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => format_value(other),
+    }
+}
+
+#[function_component(AdminAuditTab)]
+pub fn admin_audit_tab() -> Html {
+    let form = use_state(AuditFilters::default);
+    let applied = use_state(AuditFilters::default);
+    let events = use_state(Vec::<AuditEventInfo>::new);
+    let has_more = use_state(|| false);
+    let loading = use_state(|| false);
+    let error = use_state(|| None::<String>);
+
+    // Fetch a page; `offset` 0 replaces the list, anything else appends
+    let fetch_page = {
+        let applied = applied.clone();
+        let events = events.clone();
+        let has_more = has_more.clone();
+        let loading = loading.clone();
+        let error = error.clone();
+        Callback::from(move |(filters, offset): (AuditFilters, usize)| {
+            let events = events.clone();
+            let has_more = has_more.clone();
+            let loading = loading.clone();
+            let error = error.clone();
+            applied.set(filters.clone());
+            loading.set(true);
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/admin/audit");
+                let mut params = filters.params();
+                params.push(("limit", PAGE_SIZE.to_string()));
+                params.push(("offset", offset.to_string()));
+                let response = Request::get(&api_endpoint)
+                    .query(params.iter().map(|(name, value)| (*name, value.as_str())))
+                    .send()
+                    .await;
+                match response {
+                    Ok(response) if response.ok() => {
+                        match response.json::<AuditEventsResponse>().await {
+                            Ok(page) => {
+                                let mut list = if offset == 0 {
+                                    Vec::new()
+                                } else {
+                                    (*events).clone()
+                                };
+                                list.extend(page.events);
+                                events.set(list);
+                                has_more.set(page.has_more);
+                                error.set(None);
+                            }
+                            Err(e) => {
+                                error.set(Some(format!("Failed to parse audit events: {:?}", e)))
+                            }
+                        }
+                    }
+                    Ok(response) if response.status() == 400 => {
+                        error.set(Some("Dates must be YYYY-MM-DD".to_string()))
+                    }
+                    Ok(response) => error.set(Some(format!(
+                        "Failed to load audit events (HTTP {})",
+                        response.status()
+                    ))),
+                    Err(e) => error.set(Some(format!("Failed to fetch audit events: {:?}", e))),
+                }
+                loading.set(false);
+            });
+        })
+    };
+
+    {
+        let fetch_page = fetch_page.clone();
+        use_effect_with((), move |_| {
+            fetch_page.emit((AuditFilters::default(), 0));
+            || ()
+        });
+    }
+
+    let on_action = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            form.set(AuditFilters {
+                action: select.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+    let on_actor = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            form.set(AuditFilters {
+                actor: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+    let on_since = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            form.set(AuditFilters {
+                since: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+    let on_until = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            form.set(AuditFilters {
+                until: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_apply = {
+        let form = form.clone();
+        let fetch_page = fetch_page.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            fetch_page.emit(((*form).clone(), 0));
+        })
+    };
+    let on_clear = {
+        let form = form.clone();
+        let fetch_page = fetch_page.clone();
+        Callback::from(move |_: MouseEvent| {
+            form.set(AuditFilters::default());
+            fetch_page.emit((AuditFilters::default(), 0));
+        })
+    };
+    let on_load_more = {
+        let applied = applied.clone();
+        let events = events.clone();
+        let fetch_page = fetch_page.clone();
+        Callback::from(move |_: MouseEvent| {
+            fetch_page.emit(((*applied).clone(), events.len()));
+        })
+    };
+
+    html! {
+        <div class="admin-audit">
+            <form class="admin-audit-filters" onsubmit={on_apply}>
+                <select onchange={on_action}>
+                    <option value="" selected={form.action.is_empty()}>{ "All actions" }</option>
+                    { for AuditAction::ALL.iter().map(|action| html! {
+                        <option value={action.as_str()} selected={form.action == action.as_str()}>
+                            { action.as_str() }
+                        </option>
+                    }) }
+                </select>
+                <input
+                    type="text"
+                    placeholder="Actor email"
+                    value={form.actor.clone()}
+                    oninput={on_actor}
+                />
+                <input type="date" title="From" value={form.since.clone()} onchange={on_since} />
+                <input type="date" title="Until" value={form.until.clone()} onchange={on_until} />
+                <button type="submit" class="admin-toggle">{ "Filter" }</button>
+                <button type="button" class="admin-toggle" onclick={on_clear}>{ "Clear" }</button>
+                <a class="admin-toggle" href={applied.export_url()} download="">{ "Export JSON" }</a>
+            </form>
+
+            if let Some(message) = (*error).clone() {
+                <div class="admin-error">{ message }</div>
+            }
+
+            if events.is_empty() && !*loading {
+                <p class="admin-empty">{ "No audit events match." }</p>
+            } else {
+                <table class="admin-table">
+                    <thead>
+                        <tr>
+                            <th>{ "Time" }</th>
+                            <th>{ "Actor" }</th>
+                            <th>{ "Action" }</th>
+                            <th>{ "Target" }</th>
+                            <th>{ "Details" }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for events.iter().map(|event| {
+                            let actor = event.actor_email.clone().unwrap_or_else(|| {
+                                if event.actor_id.is_some() { "(deleted user)" } else { "system" }.to_string()
+                            });
+                            let target = match event.target_id {
+                                Some(id) => format!("{} {}", event.target_type, &id.to_string()[..8]),
+                                None => event.target_type.clone(),
+                            };
+                            let details = format_details(&event.details);
+                            html! {
+                                <tr key={event.id.to_string()}>
+                                    <td class="timestamp">{ utils::format_timestamp(&event.created_at) }</td>
+                                    <td>{ actor }</td>
+                                    <td><code>{ &event.action }</code></td>
+                                    <td title={event.target_id.map(|id| id.to_string()).unwrap_or_default()}>{ target }</td>
+                                    <td class="admin-audit-details" title={details.clone()}>{ details }</td>
+                                </tr>
+                            }
+                        }) }
+                    </tbody>
+                </table>
+            }
+
+            if *has_more {
+                <button class="admin-toggle" onclick={on_load_more} disabled={*loading}>
+                    { if *loading { "Loading..." } else { "Load more" } }
+                </button>
+            }
+        </div>
+    }
+}
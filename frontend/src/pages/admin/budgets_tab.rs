@@ -0,0 +1,357 @@
+//! Admin budgets tab — daily and monthly spend limits per user and team
+
+use crate::utils;
+use gloo_net::http::Request;
+use shared::api::{BudgetPeriod, SetSpendBudgetRequest, SpendBudgetInfo, SpendBudgetsResponse};
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::MouseEvent;
+use yew::prelude::*;
+
+use super::{AdminTeamInfo, AdminUserInfo};
+
+/// Budget being edited in the form
+#[derive(Clone, PartialEq)]
+struct BudgetForm {
+    /// `user:<id>` or `team:<id>`
+    target: String,
+    period: BudgetPeriod,
+    soft_limit: String,
+    hard_limit: String,
+}
+
+impl Default for BudgetForm {
+    fn default() -> Self {
+        Self {
+            target: String::new(),
+            period: BudgetPeriod::Monthly,
+            soft_limit: String::new(),
+            hard_limit: String::new(),
+        }
+    }
+}
+
+impl BudgetForm {
+    fn from_budget(budget: &SpendBudgetInfo) -> Self {
+        let target = match (budget.user_id, budget.team_id) {
+            (Some(user_id), _) => format!("user:{}", user_id),
+            (None, Some(team_id)) => format!("team:{}", team_id),
+            (None, None) => String::new(),
+        };
+        let amount = |usd: Option<f64>| usd.map(|v| format!("{:.2}", v)).unwrap_or_default();
+        Self {
+            target,
+            period: budget.period,
+            soft_limit: amount(budget.soft_limit_usd),
+            hard_limit: amount(budget.hard_limit_usd),
+        }
+    }
+
+    fn to_request(&self) -> Result<SetSpendBudgetRequest, String> {
+        let (user_id, team_id) = match self.target.split_once(':') {
+            Some(("user", id)) => (id.parse::<Uuid>().ok(), None),
+            Some(("team", id)) => (None, id.parse::<Uuid>().ok()),
+            _ => (None, None),
+        };
+        if user_id.is_none() && team_id.is_none() {
+            return Err("Choose a user or team".to_string());
+        }
+        let parse = |value: &str| -> Result<Option<f64>, String> {
+            let value = value.trim().trim_start_matches('$');
+            if value.is_empty() {
+                return Ok(None);
+            }
+            value
+                .parse::<f64>()
+                .map(Some)
+                .map_err(|_| format!("\"{}\" is not an amount", value))
+        };
+        let soft_limit_usd = parse(&self.soft_limit)?;
+        let hard_limit_usd = parse(&self.hard_limit)?;
+        shared::api::validate_budget_limits(soft_limit_usd, hard_limit_usd)
+            .map_err(str::to_string)?;
+        Ok(SetSpendBudgetRequest {
+            user_id,
+            team_id,
+            period: self.period,
+            soft_limit_usd,
+            hard_limit_usd,
+        })
+    }
+}
+
+fn format_limit(usd: Option<f64>) -> String {
+    usd.map(utils::format_dollars)
+        .unwrap_or_else(|| "-".to_string())
+}
+
+#[derive(Properties, PartialEq)]
+pub struct AdminBudgetsTabProps {
+    pub users: Vec<AdminUserInfo>,
+    pub teams: Vec<AdminTeamInfo>,
+}
+
+#[function_component(AdminBudgetsTab)]
+pub fn admin_budgets_tab(props: &AdminBudgetsTabProps) -> Html {
+    let budgets = use_state(Vec::<SpendBudgetInfo>::new);
+    let form = use_state(BudgetForm::default);
+    let error = use_state(|| None::<String>);
+    let saving = use_state(|| false);
+
+    let fetch_budgets = {
+        let budgets = budgets.clone();
+        let error = error.clone();
+        Callback::from(move |_: ()| {
+            let budgets = budgets.clone();
+            let error = error.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/admin/budgets");
+                match Request::get(&api_endpoint).send().await {
+                    Ok(response) if response.ok() => {
+                        match response.json::<SpendBudgetsResponse>().await {
+                            Ok(data) => budgets.set(data.budgets),
+                            Err(e) => error.set(Some(format!("Failed to parse budgets: {:?}", e))),
+                        }
+                    }
+                    Ok(response) => error.set(Some(format!(
+                        "Failed to load budgets (HTTP {})",
+                        response.status()
+                    ))),
+                    Err(e) => error.set(Some(format!("Failed to fetch budgets: {:?}", e))),
+                }
+            });
+        })
+    };
+
+    {
+        let fetch_budgets = fetch_budgets.clone();
+        use_effect_with((), move |_| {
+            fetch_budgets.emit(());
+            || ()
+        });
+    }
+
+    let on_target = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            form.set(BudgetForm {
+                target: select.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+    let on_period = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let period = select.value().parse().unwrap_or(BudgetPeriod::Monthly);
+            form.set(BudgetForm {
+                period,
+                ..(*form).clone()
+            });
+        })
+    };
+    let on_soft = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            form.set(BudgetForm {
+                soft_limit: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+    let on_hard = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            form.set(BudgetForm {
+                hard_limit: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_save = {
+        let form = form.clone();
+        let error = error.clone();
+        let saving = saving.clone();
+        let fetch_budgets = fetch_budgets.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let body = match form.to_request() {
+                Ok(body) => body,
+                Err(message) => {
+                    error.set(Some(message));
+                    return;
+                }
+            };
+            let form = form.clone();
+            let error = error.clone();
+            let saving = saving.clone();
+            let fetch_budgets = fetch_budgets.clone();
+            saving.set(true);
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/admin/budgets");
+                match Request::put(&api_endpoint)
+                    .json(&body)
+                    .unwrap()
+                    .send()
+                    .await
+                {
+                    Ok(response) if response.ok() => {
+                        error.set(None);
+                        form.set(BudgetForm::default());
+                        fetch_budgets.emit(());
+                    }
+                    Ok(response) => error.set(Some(format!(
+                        "Failed to save budget (HTTP {})",
+                        response.status()
+                    ))),
+                    Err(e) => error.set(Some(format!("Failed to save budget: {:?}", e))),
+                }
+                saving.set(false);
+            });
+        })
+    };
+
+    let on_delete = {
+        let error = error.clone();
+        let fetch_budgets = fetch_budgets.clone();
+        Callback::from(move |budget_id: Uuid| {
+            let error = error.clone();
+            let fetch_budgets = fetch_budgets.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url(&format!("/api/admin/budgets/{}", budget_id));
+                match Request::delete(&api_endpoint).send().await {
+                    Ok(response) if response.status() == 204 => fetch_budgets.emit(()),
+                    Ok(response) => error.set(Some(format!(
+                        "Failed to remove budget (HTTP {})",
+                        response.status()
+                    ))),
+                    Err(e) => error.set(Some(format!("Failed to remove budget: {:?}", e))),
+                }
+            });
+        })
+    };
+
+    let editing = !form.target.is_empty()
+        && budgets
+            .iter()
+            .any(|b| BudgetForm::from_budget(b).target == form.target && b.period == form.period);
+
+    html! {
+        <div class="admin-budgets">
+            <p class="admin-budgets-help">
+                { "Members are notified when spend crosses the soft limit. At the hard limit their sessions are interrupted, and input and launches are refused until the limit is raised or the period resets (UTC). A team's spend is what all of its members spend." }
+            </p>
+
+            <form class="admin-budget-form" onsubmit={on_save}>
+                <select onchange={on_target}>
+                    <option value="" selected={form.target.is_empty()}>{ "User or team..." }</option>
+                    <optgroup label="Teams">
+                        { for props.teams.iter().map(|team| {
+                            let value = format!("team:{}", team.id);
+                            html! {
+                                <option value={value.clone()} selected={form.target == value}>{ &team.name }</option>
+                            }
+                        }) }
+                    </optgroup>
+                    <optgroup label="Users">
+                        { for props.users.iter().map(|user| {
+                            let value = format!("user:{}", user.id);
+                            html! {
+                                <option value={value.clone()} selected={form.target == value}>{ &user.email }</option>
+                            }
+                        }) }
+                    </optgroup>
+                </select>
+                <select onchange={on_period}>
+                    <option value="daily" selected={form.period == BudgetPeriod::Daily}>{ "Daily" }</option>
+                    <option value="monthly" selected={form.period == BudgetPeriod::Monthly}>{ "Monthly" }</option>
+                </select>
+                <input
+                    type="text"
+                    inputmode="decimal"
+                    placeholder="Soft limit ($)"
+                    value={form.soft_limit.clone()}
+                    oninput={on_soft}
+                />
+                <input
+                    type="text"
+                    inputmode="decimal"
+                    placeholder="Hard limit ($)"
+                    value={form.hard_limit.clone()}
+                    oninput={on_hard}
+                />
+                <button type="submit" class="admin-toggle" disabled={*saving}>
+                    { if editing { "Update budget" } else { "Add budget" } }
+                </button>
+            </form>
+
+            if let Some(message) = (*error).clone() {
+                <div class="admin-error">{ message }</div>
+            }
+
+            if budgets.is_empty() {
+                <p class="admin-empty">{ "No budgets are set; spend is unlimited." }</p>
+            } else {
+                <table class="admin-table">
+                    <thead>
+                        <tr>
+                            <th>{ "User / Team" }</th>
+                            <th>{ "Period" }</th>
+                            <th>{ "Spent" }</th>
+                            <th>{ "Soft Limit" }</th>
+                            <th>{ "Hard Limit" }</th>
+                            <th>{ "Status" }</th>
+                            <th>{ "Actions" }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for budgets.iter().map(|budget| {
+                            let on_edit = {
+                                let form = form.clone();
+                                let next = BudgetForm::from_budget(budget);
+                                Callback::from(move |_: MouseEvent| form.set(next.clone()))
+                            };
+                            let on_remove = {
+                                let on_delete = on_delete.clone();
+                                let budget_id = budget.id;
+                                Callback::from(move |_: MouseEvent| on_delete.emit(budget_id))
+                            };
+                            let over_soft = budget.soft_limit_usd.is_some_and(|l| budget.spent_usd >= l);
+                            let (status_class, status) = if budget.blocked {
+                                ("user-status disabled", "Blocked")
+                            } else if over_soft {
+                                ("user-status admin", "Over soft limit")
+                            } else {
+                                ("user-status active", "Within budget")
+                            };
+                            html! {
+                                <tr key={budget.id.to_string()}>
+                                    <td>
+                                        if budget.team_id.is_some() {
+                                            <span class="admin-budget-kind">{ "Team" }</span>
+                                        }
+                                        { &budget.target_name }
+                                    </td>
+                                    <td>{ if budget.period == BudgetPeriod::Daily { "Daily" } else { "Monthly" } }</td>
+                                    <td class="numeric">{ utils::format_dollars(budget.spent_usd) }</td>
+                                    <td class="numeric">{ format_limit(budget.soft_limit_usd) }</td>
+                                    <td class="numeric">{ format_limit(budget.hard_limit_usd) }</td>
+                                    <td><span class={status_class}>{ status }</span></td>
+                                    <td class="actions">
+                                        <button class="admin-toggle" onclick={on_edit}>{ "Edit" }</button>
+                                        <button class="delete-btn" onclick={on_remove}>{ "Remove" }</button>
+                                    </td>
+                                </tr>
+                            }
+                        }) }
+                    </tbody>
+                </table>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,335 @@
+//! Admin invites tab — invite links that get past the email allowlist, with
+//! who redeemed each one
+
+use crate::utils;
+use gloo_net::http::Request;
+use shared::api::{
+    CreateInviteRequest, CreateInviteResponse, InviteInfo, InviteRole, InvitesResponse,
+    MAX_INVITE_DAYS, MAX_INVITE_USES,
+};
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::MouseEvent;
+use yew::prelude::*;
+
+/// Invite being entered in the form
+#[derive(Clone, PartialEq)]
+struct InviteForm {
+    label: String,
+    role: InviteRole,
+    max_uses: String,
+    expires_in_days: String,
+}
+
+impl Default for InviteForm {
+    fn default() -> Self {
+        Self {
+            label: String::new(),
+            role: InviteRole::User,
+            max_uses: "1".to_string(),
+            expires_in_days: "7".to_string(),
+        }
+    }
+}
+
+impl InviteForm {
+    fn to_request(&self) -> Result<CreateInviteRequest, String> {
+        let max_uses = self
+            .max_uses
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|n| (1..=MAX_INVITE_USES).contains(n))
+            .ok_or_else(|| format!("Uses must be between 1 and {}", MAX_INVITE_USES))?;
+        let expires_in_days = self
+            .expires_in_days
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|n| (1..=MAX_INVITE_DAYS).contains(n))
+            .ok_or_else(|| format!("Days must be between 1 and {}", MAX_INVITE_DAYS))?;
+        Ok(CreateInviteRequest {
+            label: Some(self.label.trim().to_string()).filter(|l| !l.is_empty()),
+            role: self.role,
+            max_uses,
+            expires_in_days,
+        })
+    }
+}
+
+fn invite_status(invite: &InviteInfo) -> (&'static str, &'static str) {
+    let expired = js_sys::Date::parse(&invite.expires_at) < js_sys::Date::now();
+    if invite.revoked {
+        ("user-status disabled", "Revoked")
+    } else if expired {
+        ("user-status disabled", "Expired")
+    } else if invite.use_count >= invite.max_uses {
+        ("user-status admin", "Used up")
+    } else {
+        ("user-status active", "Open")
+    }
+}
+
+#[function_component(AdminInvitesTab)]
+pub fn admin_invites_tab() -> Html {
+    let invites = use_state(Vec::<InviteInfo>::new);
+    let form = use_state(InviteForm::default);
+    let created_url = use_state(|| None::<String>);
+    let error = use_state(|| None::<String>);
+    let saving = use_state(|| false);
+
+    let fetch_invites = {
+        let invites = invites.clone();
+        let error = error.clone();
+        Callback::from(move |_: ()| {
+            let invites = invites.clone();
+            let error = error.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/admin/invites");
+                match Request::get(&api_endpoint).send().await {
+                    Ok(response) if response.ok() => {
+                        match response.json::<InvitesResponse>().await {
+                            Ok(data) => invites.set(data.invites),
+                            Err(e) => error.set(Some(format!("Failed to parse invites: {:?}", e))),
+                        }
+                    }
+                    Ok(response) => error.set(Some(format!(
+                        "Failed to load invites (HTTP {})",
+                        response.status()
+                    ))),
+                    Err(e) => error.set(Some(format!("Failed to fetch invites: {:?}", e))),
+                }
+            });
+        })
+    };
+
+    {
+        let fetch_invites = fetch_invites.clone();
+        use_effect_with((), move |_| {
+            fetch_invites.emit(());
+            || ()
+        });
+    }
+
+    let on_label = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            form.set(InviteForm {
+                label: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+    let on_role = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            form.set(InviteForm {
+                role: select.value().parse().unwrap_or_default(),
+                ..(*form).clone()
+            });
+        })
+    };
+    let on_max_uses = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            form.set(InviteForm {
+                max_uses: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+    let on_days = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            form.set(InviteForm {
+                expires_in_days: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_create = {
+        let form = form.clone();
+        let created_url = created_url.clone();
+        let error = error.clone();
+        let saving = saving.clone();
+        let fetch_invites = fetch_invites.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let body = match form.to_request() {
+                Ok(body) => body,
+                Err(message) => {
+                    error.set(Some(message));
+                    return;
+                }
+            };
+            let form = form.clone();
+            let created_url = created_url.clone();
+            let error = error.clone();
+            let saving = saving.clone();
+            let fetch_invites = fetch_invites.clone();
+            saving.set(true);
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/admin/invites");
+                match Request::post(&api_endpoint)
+                    .json(&body)
+                    .unwrap()
+                    .send()
+                    .await
+                {
+                    Ok(response) if response.ok() => {
+                        if let Ok(created) = response.json::<CreateInviteResponse>().await {
+                            created_url.set(Some(created.invite_url));
+                        }
+                        error.set(None);
+                        form.set(InviteForm::default());
+                        fetch_invites.emit(());
+                    }
+                    Ok(response) => error.set(Some(format!(
+                        "Failed to create invite (HTTP {})",
+                        response.status()
+                    ))),
+                    Err(e) => error.set(Some(format!("Failed to create invite: {:?}", e))),
+                }
+                saving.set(false);
+            });
+        })
+    };
+
+    let on_revoke = {
+        let error = error.clone();
+        let fetch_invites = fetch_invites.clone();
+        Callback::from(move |invite_id: Uuid| {
+            let error = error.clone();
+            let fetch_invites = fetch_invites.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url(&format!("/api/admin/invites/{}", invite_id));
+                match Request::delete(&api_endpoint).send().await {
+                    Ok(response) if response.status() == 204 => fetch_invites.emit(()),
+                    Ok(response) => error.set(Some(format!(
+                        "Failed to revoke invite (HTTP {})",
+                        response.status()
+                    ))),
+                    Err(e) => error.set(Some(format!("Failed to revoke invite: {:?}", e))),
+                }
+            });
+        })
+    };
+
+    let on_dismiss_url = {
+        let created_url = created_url.clone();
+        Callback::from(move |_: MouseEvent| created_url.set(None))
+    };
+
+    html! {
+        <div class="admin-invites">
+            <p class="admin-budgets-help">
+                { "Anyone who opens an invite link and then signs in is let in, even when the email allowlist would refuse them, and gets the invite's role. They keep access after the invite expires or is revoked; disable them on the Users tab to remove it." }
+            </p>
+
+            <form class="admin-budget-form" onsubmit={on_create}>
+                <input
+                    type="text"
+                    placeholder="Who is it for? (optional)"
+                    maxlength="255"
+                    value={form.label.clone()}
+                    oninput={on_label}
+                />
+                <select onchange={on_role}>
+                    <option value="user" selected={form.role == InviteRole::User}>{ "User" }</option>
+                    <option value="admin" selected={form.role == InviteRole::Admin}>{ "Admin" }</option>
+                </select>
+                <label class="admin-invite-field">
+                    { "Uses" }
+                    <input type="number" min="1" max={MAX_INVITE_USES.to_string()} value={form.max_uses.clone()} oninput={on_max_uses} />
+                </label>
+                <label class="admin-invite-field">
+                    { "Days" }
+                    <input type="number" min="1" max={MAX_INVITE_DAYS.to_string()} value={form.expires_in_days.clone()} oninput={on_days} />
+                </label>
+                <button type="submit" class="admin-toggle" disabled={*saving}>{ "Create invite" }</button>
+            </form>
+
+            if let Some(message) = (*error).clone() {
+                <div class="admin-error">{ message }</div>
+            }
+
+            if let Some(url) = (*created_url).clone() {
+                <div class="admin-setup-link">
+                    <p>{ "Send this link to the people you are inviting. It is not shown again." }</p>
+                    <code>{ url }</code>
+                    <button class="admin-toggle" onclick={on_dismiss_url}>{ "Done" }</button>
+                </div>
+            }
+
+            if invites.is_empty() {
+                <p class="admin-empty">{ "No invites yet." }</p>
+            } else {
+                <table class="admin-table">
+                    <thead>
+                        <tr>
+                            <th>{ "Invite" }</th>
+                            <th>{ "Role" }</th>
+                            <th>{ "Uses" }</th>
+                            <th>{ "Expires" }</th>
+                            <th>{ "Status" }</th>
+                            <th>{ "Redeemed By" }</th>
+                            <th>{ "Actions" }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for invites.iter().map(|invite| {
+                            let (status_class, status) = invite_status(invite);
+                            let on_revoke = {
+                                let on_revoke = on_revoke.clone();
+                                let invite_id = invite.id;
+                                Callback::from(move |_: MouseEvent| on_revoke.emit(invite_id))
+                            };
+                            html! {
+                                <tr key={invite.id.to_string()}>
+                                    <td>
+                                        { invite.label.clone().unwrap_or_else(|| "-".to_string()) }
+                                        <div class="admin-invite-meta">
+                                            { format!(
+                                                "Created {}{}",
+                                                utils::format_timestamp(&invite.created_at),
+                                                invite.created_by_email.as_ref().map(|e| format!(" by {}", e)).unwrap_or_default()
+                                            ) }
+                                        </div>
+                                    </td>
+                                    <td>{ if invite.role == InviteRole::Admin { "Admin" } else { "User" } }</td>
+                                    <td class="numeric">{ format!("{} / {}", invite.use_count, invite.max_uses) }</td>
+                                    <td>{ utils::format_timestamp(&invite.expires_at) }</td>
+                                    <td><span class={status_class}>{ status }</span></td>
+                                    <td>
+                                        if invite.redemptions.is_empty() {
+                                            { "-" }
+                                        } else {
+                                            <ul class="admin-invite-redemptions">
+                                                { for invite.redemptions.iter().map(|r| html! {
+                                                    <li key={r.user_id.to_string()}>
+                                                        { r.name.clone().map(|n| format!("{} <{}>", n, r.email)).unwrap_or_else(|| r.email.clone()) }
+                                                        <span class="admin-invite-meta">{ format!(" {}", utils::format_timestamp(&r.redeemed_at)) }</span>
+                                                    </li>
+                                                }) }
+                                            </ul>
+                                        }
+                                    </td>
+                                    <td class="actions">
+                                        if !invite.revoked {
+                                            <button class="delete-btn" onclick={on_revoke}>{ "Revoke" }</button>
+                                        }
+                                    </td>
+                                </tr>
+                            }
+                        }) }
+                    </tbody>
+                </table>
+            }
+        </div>
+    }
+}
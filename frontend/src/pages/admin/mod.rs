@@ -3,12 +3,22 @@
 //! Restricted to users with is_admin=true. Provides system overview,
 //! user management, and session management capabilities.
 
+mod audit_tab;
+mod budgets_tab;
+mod invites_tab;
 mod overview_tab;
 mod sessions_tab;
+mod settings_tab;
+mod teams_tab;
 mod users_tab;
 
+use audit_tab::AdminAuditTab;
+use budgets_tab::AdminBudgetsTab;
+use invites_tab::AdminInvitesTab;
 use overview_tab::AdminOverviewTab;
 use sessions_tab::AdminSessionsTab;
+use settings_tab::AdminSettingsTab;
+use teams_tab::AdminTeamsTab;
 use users_tab::AdminUsersTab;
 
 use crate::utils;
@@ -28,6 +38,11 @@ enum AdminTab {
     Overview,
     Users,
     Sessions,
+    Teams,
+    Budgets,
+    Invites,
+    Settings,
+    Audit,
 }
 
 // ============================================================================
@@ -56,6 +71,9 @@ pub struct AdminUserInfo {
     pub is_admin: bool,
     pub disabled: bool,
     pub voice_enabled: bool,
+    /// `google`, an OIDC provider ID, or `local`
+    #[serde(default)]
+    pub auth_provider: String,
     pub created_at: String,
     pub session_count: i64,
     pub total_spend_usd: f64,
@@ -64,6 +82,8 @@ pub struct AdminUserInfo {
 #[derive(Debug, Clone, Deserialize)]
 struct AdminUsersResponse {
     users: Vec<AdminUserInfo>,
+    #[serde(default)]
+    local_accounts_enabled: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
@@ -75,6 +95,8 @@ pub struct AdminSessionInfo {
     pub git_branch: Option<String>,
     pub status: String,
     pub total_cost_usd: f64,
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
     pub last_activity: String,
     pub is_connected: bool,
     #[serde(default)]
@@ -86,6 +108,24 @@ struct AdminSessionsResponse {
     sessions: Vec<AdminSessionInfo>,
 }
 
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct AdminTeamInfo {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: String,
+    pub member_count: i64,
+    pub session_count: i64,
+    pub shared_spend_usd: f64,
+    pub shared_input_tokens: i64,
+    pub shared_output_tokens: i64,
+    pub member_spend_usd: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AdminTeamsResponse {
+    teams: Vec<AdminTeamInfo>,
+}
+
 // ============================================================================
 // Main Admin Page Component
 // ============================================================================
@@ -100,7 +140,9 @@ pub fn admin_page(props: &AdminPageProps) -> Html {
     let active_tab = use_state(|| AdminTab::Overview);
     let stats = use_state(|| None::<AdminStats>);
     let users = use_state(Vec::<AdminUserInfo>::new);
+    let local_accounts_enabled = use_state(|| false);
     let sessions = use_state(Vec::<AdminSessionInfo>::new);
+    let teams = use_state(Vec::<AdminTeamInfo>::new);
     let loading = use_state(|| true);
     let error = use_state(|| None::<String>);
     let current_user_id = use_state(|| None::<Uuid>);
@@ -201,9 +243,11 @@ pub fn admin_page(props: &AdminPageProps) -> Html {
     // Fetch users
     let fetch_users = {
         let users = users.clone();
+        let local_accounts_enabled = local_accounts_enabled.clone();
         let error = error.clone();
         Callback::from(move |_| {
             let users = users.clone();
+            let local_accounts_enabled = local_accounts_enabled.clone();
             let error = error.clone();
             spawn_local(async move {
                 let api_endpoint = utils::api_url("/api/admin/users");
@@ -215,6 +259,7 @@ pub fn admin_page(props: &AdminPageProps) -> Html {
                         match response.json::<AdminUsersResponse>().await {
                             Ok(data) => {
                                 users.set(data.users);
+                                local_accounts_enabled.set(data.local_accounts_enabled);
                             }
                             Err(e) => {
                                 error.set(Some(format!("Failed to parse users: {:?}", e)));
@@ -260,15 +305,48 @@ pub fn admin_page(props: &AdminPageProps) -> Html {
         })
     };
 
+    // Fetch teams
+    let fetch_teams = {
+        let teams = teams.clone();
+        let error = error.clone();
+        Callback::from(move |_| {
+            let teams = teams.clone();
+            let error = error.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/admin/teams");
+                match Request::get(&api_endpoint).send().await {
+                    Ok(response) => {
+                        if response.status() == 403 {
+                            return;
+                        }
+                        match response.json::<AdminTeamsResponse>().await {
+                            Ok(data) => {
+                                teams.set(data.teams);
+                            }
+                            Err(e) => {
+                                error.set(Some(format!("Failed to parse teams: {:?}", e)));
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        error.set(Some(format!("Failed to fetch teams: {:?}", e)));
+                    }
+                }
+            });
+        })
+    };
+
     // Initial data fetch
     {
         let fetch_stats = fetch_stats.clone();
         let fetch_users = fetch_users.clone();
         let fetch_sessions = fetch_sessions.clone();
+        let fetch_teams = fetch_teams.clone();
         use_effect_with((), move |_| {
             fetch_stats.emit(());
             fetch_users.emit(());
             fetch_sessions.emit(());
+            fetch_teams.emit(());
             || ()
         });
     }
@@ -574,6 +652,26 @@ pub fn admin_page(props: &AdminPageProps) -> Html {
         let active_tab = active_tab.clone();
         Callback::from(move |_| active_tab.set(AdminTab::Sessions))
     };
+    let on_teams_tab = {
+        let active_tab = active_tab.clone();
+        Callback::from(move |_| active_tab.set(AdminTab::Teams))
+    };
+    let on_budgets_tab = {
+        let active_tab = active_tab.clone();
+        Callback::from(move |_| active_tab.set(AdminTab::Budgets))
+    };
+    let on_invites_tab = {
+        let active_tab = active_tab.clone();
+        Callback::from(move |_| active_tab.set(AdminTab::Invites))
+    };
+    let on_settings_tab = {
+        let active_tab = active_tab.clone();
+        Callback::from(move |_| active_tab.set(AdminTab::Settings))
+    };
+    let on_audit_tab = {
+        let active_tab = active_tab.clone();
+        Callback::from(move |_| active_tab.set(AdminTab::Audit))
+    };
 
     // Cancel confirmation
     let on_cancel_confirm = {
@@ -638,6 +736,36 @@ pub fn admin_page(props: &AdminPageProps) -> Html {
                                 >
                                     { format!("Sessions ({})", sessions.len()) }
                                 </button>
+                                <button
+                                    class={classes!("tab-btn", if *active_tab == AdminTab::Teams { Some("active") } else { None })}
+                                    onclick={on_teams_tab}
+                                >
+                                    { format!("Teams ({})", teams.len()) }
+                                </button>
+                                <button
+                                    class={classes!("tab-btn", if *active_tab == AdminTab::Budgets { Some("active") } else { None })}
+                                    onclick={on_budgets_tab}
+                                >
+                                    { "Budgets" }
+                                </button>
+                                <button
+                                    class={classes!("tab-btn", if *active_tab == AdminTab::Invites { Some("active") } else { None })}
+                                    onclick={on_invites_tab}
+                                >
+                                    { "Invites" }
+                                </button>
+                                <button
+                                    class={classes!("tab-btn", if *active_tab == AdminTab::Settings { Some("active") } else { None })}
+                                    onclick={on_settings_tab}
+                                >
+                                    { "Settings" }
+                                </button>
+                                <button
+                                    class={classes!("tab-btn", if *active_tab == AdminTab::Audit { Some("active") } else { None })}
+                                    onclick={on_audit_tab}
+                                >
+                                    { "Audit" }
+                                </button>
                             </nav>
 
                             <div class="admin-content">
@@ -656,6 +784,8 @@ pub fn admin_page(props: &AdminPageProps) -> Html {
                                                     on_toggle_disabled={on_toggle_disabled.clone()}
                                                     on_toggle_voice={on_toggle_voice.clone()}
                                                     current_user_id={current_user_id.unwrap_or_default()}
+                                                    local_accounts_enabled={*local_accounts_enabled}
+                                                    on_users_changed={fetch_users.clone()}
                                                 />
                                             }
                                         }
@@ -664,9 +794,38 @@ pub fn admin_page(props: &AdminPageProps) -> Html {
                                                 <AdminSessionsTab
                                                     sessions={(*sessions).clone()}
                                                     on_delete={on_delete_session.clone()}
+                                                    on_changed={fetch_sessions.clone()}
+                                                />
+                                            }
+                                        }
+                                        AdminTab::Teams => {
+                                            html! {
+                                                <AdminTeamsTab teams={(*teams).clone()} />
+                                            }
+                                        }
+                                        AdminTab::Budgets => {
+                                            html! {
+                                                <AdminBudgetsTab
+                                                    users={(*users).clone()}
+                                                    teams={(*teams).clone()}
                                                 />
                                             }
                                         }
+                                        AdminTab::Invites => {
+                                            html! {
+                                                <AdminInvitesTab />
+                                            }
+                                        }
+                                        AdminTab::Settings => {
+                                            html! {
+                                                <AdminSettingsTab />
+                                            }
+                                        }
+                                        AdminTab::Audit => {
+                                            html! {
+                                                <AdminAuditTab />
+                                            }
+                                        }
                                     }
                                 }
                             </div>
@@ -1,7 +1,10 @@
 //! Admin sessions tab — session management table
 
 use crate::utils;
+use gloo_net::http::Request;
+use shared::api::SetSessionCostCapRequest;
 use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
 use web_sys::MouseEvent;
 use yew::prelude::*;
 
@@ -11,6 +14,47 @@ use super::AdminSessionInfo;
 struct SessionRowProps {
     session: AdminSessionInfo,
     on_delete: Callback<Uuid>,
+    on_changed: Callback<()>,
+}
+
+/// Ask for a new cost cap and save it; an empty answer removes the cap.
+fn edit_cost_cap(session_id: Uuid, current: Option<f64>, on_changed: Callback<()>) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let default = current.map(|v| format!("{:.2}", v)).unwrap_or_default();
+    let Ok(Some(answer)) = window.prompt_with_message_and_default(
+        "Cost cap for this session in USD (leave empty for none):",
+        &default,
+    ) else {
+        return;
+    };
+    let answer = answer.trim().trim_start_matches('$');
+    let max_cost_usd = if answer.is_empty() {
+        None
+    } else {
+        match answer.parse::<f64>() {
+            Ok(v) if shared::api::validate_cost_cap(Some(v)).is_ok() => Some(v),
+            _ => {
+                let _ = window.alert_with_message("The cost cap must be a positive amount.");
+                return;
+            }
+        }
+    };
+    spawn_local(async move {
+        let api_endpoint = utils::api_url(&format!("/api/admin/sessions/{}/cost-cap", session_id));
+        let body = SetSessionCostCapRequest { max_cost_usd };
+        match Request::patch(&api_endpoint)
+            .json(&body)
+            .unwrap()
+            .send()
+            .await
+        {
+            Ok(response) if response.status() == 204 => on_changed.emit(()),
+            Ok(response) => log::error!("Failed to set cost cap: HTTP {}", response.status()),
+            Err(e) => log::error!("Failed to set cost cap: {:?}", e),
+        }
+    });
 }
 
 #[function_component(SessionRow)]
@@ -23,6 +67,16 @@ fn session_row(props: &SessionRowProps) -> Html {
         Callback::from(move |_: MouseEvent| callback.emit(session_id))
     };
 
+    let on_cap = {
+        let on_changed = props.on_changed.clone();
+        let session_id = session.id;
+        let current = session.max_cost_usd;
+        Callback::from(move |_: MouseEvent| edit_cost_cap(session_id, current, on_changed.clone()))
+    };
+    let capped = session
+        .max_cost_usd
+        .is_some_and(|cap| session.total_cost_usd >= cap);
+
     let status_class = if session.is_connected {
         "session-status connected"
     } else if session.status == "active" {
@@ -48,8 +102,14 @@ fn session_row(props: &SessionRowProps) -> Html {
             <td class="session-branch">{ session.git_branch.as_deref().unwrap_or("-") }</td>
             <td class={status_class}>{ status_text }</td>
             <td class="numeric">{ utils::format_dollars(session.total_cost_usd) }</td>
+            <td class={classes!("numeric", capped.then_some("cost-capped"))}>
+                { session.max_cost_usd.map(utils::format_dollars).unwrap_or_else(|| "-".to_string()) }
+            </td>
             <td class="timestamp">{ utils::format_timestamp(&session.last_activity) }</td>
             <td class="actions">
+                <button class="admin-toggle" onclick={on_cap} title="Change the session's cost cap">
+                    { "Cap" }
+                </button>
                 <button class="delete-btn" onclick={on_delete} title="Delete session">
                     { "Delete" }
                 </button>
@@ -62,6 +122,8 @@ fn session_row(props: &SessionRowProps) -> Html {
 pub struct AdminSessionsTabProps {
     pub sessions: Vec<AdminSessionInfo>,
     pub on_delete: Callback<Uuid>,
+    /// Reload the sessions after a change
+    pub on_changed: Callback<()>,
 }
 
 #[function_component(AdminSessionsTab)]
@@ -77,6 +139,7 @@ pub fn admin_sessions_tab(props: &AdminSessionsTabProps) -> Html {
                         <th>{ "Branch" }</th>
                         <th>{ "Status" }</th>
                         <th>{ "Cost" }</th>
+                        <th>{ "Cap" }</th>
                         <th>{ "Last Activity" }</th>
                         <th>{ "Actions" }</th>
                     </tr>
@@ -89,6 +152,7 @@ pub fn admin_sessions_tab(props: &AdminSessionsTabProps) -> Html {
                                     key={session.id.to_string()}
                                     session={session.clone()}
                                     on_delete={props.on_delete.clone()}
+                                    on_changed={props.on_changed.clone()}
                                 />
                             }
                         }).collect::<Html>()
@@ -0,0 +1,283 @@
+//! Admin settings tab — instance settings that apply without a restart, with
+//! the environment defaults they replace
+
+use crate::utils;
+use gloo_net::http::Request;
+use shared::api::{InstanceSettings, InstanceSettingsResponse};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+/// Settings being edited, as typed
+#[derive(Clone, PartialEq, Default)]
+struct SettingsForm {
+    app_title: String,
+    splash_text: String,
+    allowed_email_domain: String,
+    allowed_emails: String,
+    message_retention_count: String,
+    message_retention_days: String,
+    session_max_age_days: String,
+    max_image_mb: String,
+}
+
+impl SettingsForm {
+    fn from_settings(settings: &InstanceSettings) -> Self {
+        Self {
+            app_title: settings.app_title.clone(),
+            splash_text: settings.splash_text.clone().unwrap_or_default(),
+            allowed_email_domain: settings.allowed_email_domain.clone().unwrap_or_default(),
+            allowed_emails: settings
+                .allowed_emails
+                .as_ref()
+                .map(|emails| emails.join("\n"))
+                .unwrap_or_default(),
+            message_retention_count: settings.message_retention_count.to_string(),
+            message_retention_days: settings.message_retention_days.to_string(),
+            session_max_age_days: settings.session_max_age_days.to_string(),
+            max_image_mb: settings.max_image_mb.to_string(),
+        }
+    }
+
+    fn to_settings(&self) -> Result<InstanceSettings, String> {
+        fn number<T: std::str::FromStr>(value: &str, name: &str) -> Result<T, String> {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| format!("{} must be a whole number", name))
+        }
+        fn text(value: &str) -> Option<String> {
+            Some(value.trim().to_string()).filter(|v| !v.is_empty())
+        }
+
+        let emails: Vec<String> = self
+            .allowed_emails
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|e| !e.is_empty())
+            .map(str::to_string)
+            .collect();
+        let settings = InstanceSettings {
+            app_title: self.app_title.trim().to_string(),
+            splash_text: text(&self.splash_text),
+            allowed_email_domain: text(&self.allowed_email_domain),
+            allowed_emails: Some(emails).filter(|e| !e.is_empty()),
+            message_retention_count: number(&self.message_retention_count, "Messages kept")?,
+            message_retention_days: number(&self.message_retention_days, "Days kept")?,
+            session_max_age_days: number(&self.session_max_age_days, "Session age")?,
+            max_image_mb: number(&self.max_image_mb, "Image size")?,
+        };
+        settings.validate().map_err(str::to_string)?;
+        Ok(settings)
+    }
+}
+
+/// "Default: ..." hint under a field, marked when the value differs
+fn default_hint(response: &InstanceSettingsResponse, key: &str, default: String) -> Html {
+    let overridden = response.overridden.iter().any(|k| k == key);
+    html! {
+        <span class="admin-settings-default">
+            { format!("Default: {}", if default.is_empty() { "(none)".to_string() } else { default }) }
+            if overridden {
+                <span class="user-status admin">{ "Changed" }</span>
+            }
+        </span>
+    }
+}
+
+#[function_component(AdminSettingsTab)]
+pub fn admin_settings_tab() -> Html {
+    let response = use_state(|| None::<InstanceSettingsResponse>);
+    let form = use_state(SettingsForm::default);
+    let error = use_state(|| None::<String>);
+    let saved = use_state(|| false);
+    let saving = use_state(|| false);
+
+    {
+        let response = response.clone();
+        let form = form.clone();
+        let error = error.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/admin/settings");
+                match Request::get(&api_endpoint).send().await {
+                    Ok(resp) if resp.ok() => match resp.json::<InstanceSettingsResponse>().await {
+                        Ok(data) => {
+                            form.set(SettingsForm::from_settings(&data.settings));
+                            response.set(Some(data));
+                        }
+                        Err(e) => error.set(Some(format!("Failed to parse settings: {:?}", e))),
+                    },
+                    Ok(resp) => error.set(Some(format!(
+                        "Failed to load settings (HTTP {})",
+                        resp.status()
+                    ))),
+                    Err(e) => error.set(Some(format!("Failed to fetch settings: {:?}", e))),
+                }
+            });
+            || ()
+        });
+    }
+
+    let bind = |field: fn(&mut SettingsForm) -> &mut String| {
+        let form = form.clone();
+        let saved = saved.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            let mut next = (*form).clone();
+            *field(&mut next) = input.value();
+            form.set(next);
+            saved.set(false);
+        })
+    };
+    let on_emails = {
+        let form = form.clone();
+        let saved = saved.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlTextAreaElement = e.target_unchecked_into();
+            form.set(SettingsForm {
+                allowed_emails: input.value(),
+                ..(*form).clone()
+            });
+            saved.set(false);
+        })
+    };
+
+    let on_save = {
+        let response = response.clone();
+        let form = form.clone();
+        let error = error.clone();
+        let saved = saved.clone();
+        let saving = saving.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let body = match form.to_settings() {
+                Ok(body) => body,
+                Err(message) => {
+                    error.set(Some(message));
+                    return;
+                }
+            };
+            let response = response.clone();
+            let form = form.clone();
+            let error = error.clone();
+            let saved = saved.clone();
+            let saving = saving.clone();
+            saving.set(true);
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/admin/settings");
+                match Request::put(&api_endpoint)
+                    .json(&body)
+                    .unwrap()
+                    .send()
+                    .await
+                {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(data) = resp.json::<InstanceSettingsResponse>().await {
+                            form.set(SettingsForm::from_settings(&data.settings));
+                            response.set(Some(data));
+                        }
+                        error.set(None);
+                        saved.set(true);
+                    }
+                    Ok(resp) => {
+                        let status = resp.status();
+                        let message = resp.text().await.unwrap_or_default();
+                        error.set(Some(if message.is_empty() {
+                            format!("Failed to save settings (HTTP {})", status)
+                        } else {
+                            message
+                        }));
+                    }
+                    Err(e) => error.set(Some(format!("Failed to save settings: {:?}", e))),
+                }
+                saving.set(false);
+            });
+        })
+    };
+
+    let Some(current) = (*response).clone() else {
+        return html! {
+            <div class="admin-settings">
+                if let Some(message) = (*error).clone() {
+                    <div class="admin-error">{ message }</div>
+                } else {
+                    <p class="admin-empty">{ "Loading settings..." }</p>
+                }
+            </div>
+        };
+    };
+    let defaults = &current.defaults;
+
+    html! {
+        <div class="admin-settings">
+            <p class="admin-budgets-help">
+                { "Changes apply as soon as they are saved, without restarting the server. Values set back to their default follow the environment again." }
+            </p>
+
+            <form class="admin-settings-form" onsubmit={on_save}>
+                <h3>{ "Branding" }</h3>
+                <label>
+                    { "App title" }
+                    <input type="text" maxlength="100" value={form.app_title.clone()} oninput={bind(|f| &mut f.app_title)} />
+                    { default_hint(&current, "app_title", defaults.app_title.clone()) }
+                </label>
+                <label>
+                    { "Splash text (replaces the landing page heading)" }
+                    <input type="text" maxlength="500" value={form.splash_text.clone()} oninput={bind(|f| &mut f.splash_text)} />
+                    { default_hint(&current, "splash_text", defaults.splash_text.clone().unwrap_or_default()) }
+                </label>
+
+                <h3>{ "Who may sign in" }</h3>
+                <label>
+                    { "Allowed email domain" }
+                    <input type="text" placeholder="example.com" value={form.allowed_email_domain.clone()} oninput={bind(|f| &mut f.allowed_email_domain)} />
+                    { default_hint(&current, "allowed_email_domain", defaults.allowed_email_domain.clone().unwrap_or_default()) }
+                </label>
+                <label>
+                    { "Allowed email addresses, one per line" }
+                    <textarea rows="4" value={form.allowed_emails.clone()} oninput={on_emails} />
+                    { default_hint(&current, "allowed_emails", defaults.allowed_emails.clone().map(|e| e.join(", ")).unwrap_or_default()) }
+                </label>
+                <p class="admin-settings-note">
+                    { "Leave both empty to let anyone sign in. Removing someone from the list stops their next sign-in; disable them on the Users tab to end their access now." }
+                </p>
+
+                <h3>{ "Retention" }</h3>
+                <label>
+                    { "Messages kept per session" }
+                    <input type="number" min="1" value={form.message_retention_count.clone()} oninput={bind(|f| &mut f.message_retention_count)} />
+                    { default_hint(&current, "message_retention_count", defaults.message_retention_count.to_string()) }
+                </label>
+                <label>
+                    { "Days messages are kept (0 keeps them)" }
+                    <input type="number" min="0" value={form.message_retention_days.clone()} oninput={bind(|f| &mut f.message_retention_days)} />
+                    { default_hint(&current, "message_retention_days", defaults.message_retention_days.to_string()) }
+                </label>
+                <label>
+                    { "Days idle sessions are kept (0 keeps them)" }
+                    <input type="number" min="0" value={form.session_max_age_days.clone()} oninput={bind(|f| &mut f.session_max_age_days)} />
+                    { default_hint(&current, "session_max_age_days", defaults.session_max_age_days.to_string()) }
+                </label>
+
+                <h3>{ "Proxies" }</h3>
+                <label>
+                    { "Largest image inlined, in MB" }
+                    <input type="number" min="1" max="100" value={form.max_image_mb.clone()} oninput={bind(|f| &mut f.max_image_mb)} />
+                    { default_hint(&current, "max_image_mb", defaults.max_image_mb.to_string()) }
+                </label>
+                <p class="admin-settings-note">
+                    { "Proxies pick up a new image limit when they next connect." }
+                </p>
+
+                if let Some(message) = (*error).clone() {
+                    <div class="admin-error">{ message }</div>
+                }
+                <div class="admin-settings-actions">
+                    <button type="submit" class="admin-toggle" disabled={*saving}>{ "Save settings" }</button>
+                    if *saved {
+                        <span class="admin-settings-saved">{ "Saved" }</span>
+                    }
+                </div>
+            </form>
+        </div>
+    }
+}
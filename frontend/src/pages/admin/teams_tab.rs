@@ -0,0 +1,60 @@
+//! Admin teams tab — what each team shares and spends
+
+use crate::utils;
+use yew::prelude::*;
+
+use super::AdminTeamInfo;
+
+#[derive(Properties, PartialEq)]
+pub struct AdminTeamsTabProps {
+    pub teams: Vec<AdminTeamInfo>,
+}
+
+#[function_component(AdminTeamsTab)]
+pub fn admin_teams_tab(props: &AdminTeamsTabProps) -> Html {
+    if props.teams.is_empty() {
+        return html! {
+            <div class="admin-teams">
+                <p class="admin-empty">{ "No teams have been created yet." }</p>
+            </div>
+        };
+    }
+    html! {
+        <div class="admin-teams">
+            <table class="admin-table">
+                <thead>
+                    <tr>
+                        <th>{ "Team" }</th>
+                        <th>{ "Members" }</th>
+                        <th>{ "Shared Sessions" }</th>
+                        <th>{ "Shared Spend" }</th>
+                        <th>{ "Tokens (in / out)" }</th>
+                        <th>{ "Member Spend" }</th>
+                        <th>{ "Created" }</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for props.teams.iter().map(|team| html! {
+                        <tr key={team.id.to_string()}>
+                            <td>{ &team.name }</td>
+                            <td class="numeric">{ team.member_count }</td>
+                            <td class="numeric">{ team.session_count }</td>
+                            <td class="numeric">{ utils::format_dollars(team.shared_spend_usd) }</td>
+                            <td class="numeric">
+                                { format!(
+                                    "{} / {}",
+                                    utils::format_tokens(team.shared_input_tokens),
+                                    utils::format_tokens(team.shared_output_tokens)
+                                ) }
+                            </td>
+                            <td class="numeric" title="Everything the members have spent, shared or not">
+                                { utils::format_dollars(team.member_spend_usd) }
+                            </td>
+                            <td class="timestamp">{ utils::format_timestamp(&team.created_at) }</td>
+                        </tr>
+                    }) }
+                </tbody>
+            </table>
+        </div>
+    }
+}
@@ -1,7 +1,11 @@
-//! Admin users tab — user management table with sortable columns
+//! Admin users tab — user management table with sortable columns, plus
+//! local account creation and password resets when local accounts are on
 
 use crate::utils;
+use gloo_net::http::Request;
+use shared::api::{CreateLocalAccountRequest, LocalAccountSetupLink, ResetLocalPasswordRequest};
 use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
 use web_sys::MouseEvent;
 use yew::prelude::*;
 
@@ -86,6 +90,7 @@ struct UserRowProps {
     on_toggle_admin: Callback<Uuid>,
     on_toggle_disabled: Callback<Uuid>,
     on_toggle_voice: Callback<Uuid>,
+    on_reset_password: Callback<Uuid>,
     current_user_id: Uuid,
 }
 
@@ -112,6 +117,12 @@ fn user_row(props: &UserRowProps) -> Html {
         Callback::from(move |_: MouseEvent| callback.emit(user_id))
     };
 
+    let on_reset_password = {
+        let callback = props.on_reset_password.clone();
+        let user_id = user.id;
+        Callback::from(move |_: MouseEvent| callback.emit(user_id))
+    };
+
     let status_class = if user.disabled {
         "user-status disabled"
     } else if user.is_admin {
@@ -163,6 +174,15 @@ fn user_row(props: &UserRowProps) -> Html {
                 >
                     { if user.voice_enabled { "Voice: On" } else { "Voice: Off" } }
                 </button>
+                if user.auth_provider == "local" {
+                    <button
+                        class="admin-toggle"
+                        onclick={on_reset_password}
+                        title="Clear the password and issue a new setup link"
+                    >
+                        { "Reset Password" }
+                    </button>
+                }
             </td>
         </tr>
     }
@@ -175,12 +195,191 @@ pub struct AdminUsersTabProps {
     pub on_toggle_disabled: Callback<Uuid>,
     pub on_toggle_voice: Callback<Uuid>,
     pub current_user_id: Uuid,
+    /// Show local account creation and password resets
+    pub local_accounts_enabled: bool,
+    /// Reload the user list after an account is created
+    pub on_users_changed: Callback<()>,
+}
+
+/// New local account being entered in the form
+#[derive(Clone, Default, PartialEq)]
+struct LocalAccountForm {
+    username: String,
+    email: String,
+    name: String,
+    is_admin: bool,
+}
+
+/// The setup link just issued, shown until dismissed since it is not stored
+#[derive(Properties, PartialEq)]
+struct SetupLinkNoticeProps {
+    link: LocalAccountSetupLink,
+    on_dismiss: Callback<MouseEvent>,
+}
+
+#[function_component(SetupLinkNotice)]
+fn setup_link_notice(props: &SetupLinkNoticeProps) -> Html {
+    html! {
+        <div class="admin-setup-link">
+            <p>
+                { format!(
+                    "Send this link to {}. It sets their password and works once, until {}.",
+                    props.link.username,
+                    utils::format_timestamp(&props.link.expires_at)
+                ) }
+            </p>
+            <code>{ &props.link.setup_url }</code>
+            <button class="admin-toggle" onclick={props.on_dismiss.clone()}>{ "Done" }</button>
+        </div>
+    }
 }
 
 #[function_component(AdminUsersTab)]
 pub fn admin_users_tab(props: &AdminUsersTabProps) -> Html {
     let sort_column = use_state(|| None::<SortColumn>);
     let sort_direction = use_state(|| SortDirection::Desc);
+    let form = use_state(LocalAccountForm::default);
+    let setup_link = use_state(|| None::<LocalAccountSetupLink>);
+    let local_error = use_state(|| None::<String>);
+    // Asks which user to reset first, then whether to drop their second factor
+    let reset_target = use_state(|| None::<AdminUserInfo>);
+    let reset_totp = use_state(|| false);
+
+    let on_form_input = |apply: fn(&mut LocalAccountForm, String)| {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            let mut updated = (*form).clone();
+            apply(&mut updated, input.value());
+            form.set(updated);
+        })
+    };
+    let on_username = on_form_input(|f, v| f.username = v);
+    let on_email = on_form_input(|f, v| f.email = v);
+    let on_name = on_form_input(|f, v| f.name = v);
+    let on_is_admin = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            form.set(LocalAccountForm {
+                is_admin: input.checked(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_create = {
+        let form = form.clone();
+        let setup_link = setup_link.clone();
+        let local_error = local_error.clone();
+        let on_users_changed = props.on_users_changed.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let body = CreateLocalAccountRequest {
+                username: form.username.clone(),
+                email: form.email.clone(),
+                name: Some(form.name.clone()).filter(|n| !n.trim().is_empty()),
+                is_admin: form.is_admin,
+            };
+            let form = form.clone();
+            let setup_link = setup_link.clone();
+            let local_error = local_error.clone();
+            let on_users_changed = on_users_changed.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/admin/local-accounts");
+                match Request::post(&api_endpoint).json(&body).unwrap().send().await {
+                    Ok(response) if response.ok() => {
+                        if let Ok(link) = response.json::<LocalAccountSetupLink>().await {
+                            setup_link.set(Some(link));
+                        }
+                        form.set(LocalAccountForm::default());
+                        local_error.set(None);
+                        on_users_changed.emit(());
+                    }
+                    Ok(response) if response.status() == 400 => local_error.set(Some(
+                        "Usernames are 3 to 64 letters, digits, '.', '_' or '-'; the email must be valid"
+                            .to_string(),
+                    )),
+                    Ok(response) if response.status() == 409 => {
+                        local_error.set(Some("That username is taken".to_string()))
+                    }
+                    Ok(response) => local_error.set(Some(format!(
+                        "Failed to create the account (HTTP {})",
+                        response.status()
+                    ))),
+                    Err(e) => local_error.set(Some(format!("Failed to create the account: {:?}", e))),
+                }
+            });
+        })
+    };
+
+    let on_reset_password = {
+        let reset_target = reset_target.clone();
+        let reset_totp = reset_totp.clone();
+        let users = props.users.clone();
+        Callback::from(move |user_id: Uuid| {
+            reset_totp.set(false);
+            reset_target.set(users.iter().find(|u| u.id == user_id).cloned());
+        })
+    };
+    let on_reset_totp = {
+        let reset_totp = reset_totp.clone();
+        Callback::from(move |e: Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            reset_totp.set(input.checked());
+        })
+    };
+    let on_cancel_reset = {
+        let reset_target = reset_target.clone();
+        Callback::from(move |_: MouseEvent| reset_target.set(None))
+    };
+    let on_confirm_reset = {
+        let reset_target = reset_target.clone();
+        let reset_totp = reset_totp.clone();
+        let setup_link = setup_link.clone();
+        let local_error = local_error.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(user) = (*reset_target).clone() else {
+                return;
+            };
+            let body = ResetLocalPasswordRequest {
+                reset_totp: *reset_totp,
+            };
+            let reset_target = reset_target.clone();
+            let setup_link = setup_link.clone();
+            let local_error = local_error.clone();
+            spawn_local(async move {
+                let api_endpoint =
+                    utils::api_url(&format!("/api/admin/users/{}/password-reset", user.id));
+                match Request::post(&api_endpoint)
+                    .json(&body)
+                    .unwrap()
+                    .send()
+                    .await
+                {
+                    Ok(response) if response.ok() => {
+                        if let Ok(link) = response.json::<LocalAccountSetupLink>().await {
+                            setup_link.set(Some(link));
+                            local_error.set(None);
+                        }
+                    }
+                    Ok(response) => local_error.set(Some(format!(
+                        "Failed to reset the password (HTTP {})",
+                        response.status()
+                    ))),
+                    Err(e) => {
+                        local_error.set(Some(format!("Failed to reset the password: {:?}", e)))
+                    }
+                }
+                reset_target.set(None);
+            });
+        })
+    };
+
+    let on_dismiss_link = {
+        let setup_link = setup_link.clone();
+        Callback::from(move |_: MouseEvent| setup_link.set(None))
+    };
 
     let sorted_users = {
         match *sort_column {
@@ -218,6 +417,35 @@ pub fn admin_users_tab(props: &AdminUsersTabProps) -> Html {
 
     html! {
         <div class="admin-users">
+            if props.local_accounts_enabled {
+                <form class="admin-budget-form" onsubmit={on_create}>
+                    <input type="text" placeholder="Username" value={form.username.clone()} oninput={on_username} />
+                    <input type="email" placeholder="Email" value={form.email.clone()} oninput={on_email} />
+                    <input type="text" placeholder="Name (optional)" value={form.name.clone()} oninput={on_name} />
+                    <label class="toggle-label">
+                        <input type="checkbox" checked={form.is_admin} onchange={on_is_admin} />
+                        <span>{ "Admin" }</span>
+                    </label>
+                    <button type="submit" class="admin-toggle">{ "Create Local Account" }</button>
+                </form>
+            }
+            if let Some(message) = (*local_error).clone() {
+                <div class="admin-error">{ message }</div>
+            }
+            if let Some(user) = (*reset_target).clone() {
+                <div class="admin-setup-link">
+                    <p>{ format!("Reset the password of {}? They cannot sign in until they use the new setup link.", user.email) }</p>
+                    <label class="toggle-label">
+                        <input type="checkbox" checked={*reset_totp} onchange={on_reset_totp} />
+                        <span>{ "Also remove two-factor authentication" }</span>
+                    </label>
+                    <button class="ban-toggle" onclick={on_confirm_reset}>{ "Reset Password" }</button>
+                    <button class="admin-toggle" onclick={on_cancel_reset}>{ "Cancel" }</button>
+                </div>
+            }
+            if let Some(link) = (*setup_link).clone() {
+                <SetupLinkNotice link={link} on_dismiss={on_dismiss_link} />
+            }
             <table class="admin-table">
                 <thead>
                     <tr>
@@ -240,6 +468,7 @@ pub fn admin_users_tab(props: &AdminUsersTabProps) -> Html {
                                     on_toggle_admin={props.on_toggle_admin.clone()}
                                     on_toggle_disabled={props.on_toggle_disabled.clone()}
                                     on_toggle_voice={props.on_toggle_voice.clone()}
+                                    on_reset_password={on_reset_password.clone()}
                                     current_user_id={props.current_user_id}
                                 />
                             }
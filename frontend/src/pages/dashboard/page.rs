@@ -3,21 +3,25 @@
 use super::session_rail::{ActivityRef, SessionRail};
 use super::session_view::SessionView;
 use super::types::{
-    load_hidden_sessions, load_inactive_hidden, load_show_cost, save_hidden_sessions,
+    load_hidden_sessions, load_inactive_hidden, load_show_cost, nest_forks, save_hidden_sessions,
     save_inactive_hidden, save_show_cost,
 };
 use crate::components::LaunchDialog;
 use crate::hooks::{use_client_websocket, use_keyboard_nav, use_sessions, KeyboardNavConfig};
 use crate::pages::admin::AdminPage;
+use crate::pages::search::{MessageLink, SearchPage};
 use crate::pages::settings::SettingsPage;
+use crate::pages::usage::UsagePage;
 use crate::utils;
 use gloo_net::http::Request;
+use shared::api::SearchHit;
 use shared::{AppConfig, SessionInfo};
 use std::collections::HashSet;
 use uuid::Uuid;
 use wasm_bindgen_futures::spawn_local;
 use web_sys::MouseEvent;
 use yew::prelude::*;
+use yew_router::prelude::use_location;
 
 // =============================================================================
 // Dashboard Page - Main Orchestrating Component
@@ -34,6 +38,10 @@ pub fn dashboard_page() -> Html {
     let ws_hook = use_client_websocket();
     let total_user_spend = ws_hook.total_spend;
     let server_shutdown_reason = ws_hook.shutdown_reason.clone();
+    let permission_escalation = ws_hook.escalation.clone();
+    let dismiss_escalation = ws_hook.dismiss_escalation.clone();
+    let spend_alert = ws_hook.spend_alert.clone();
+    let dismiss_spend_alert = ws_hook.dismiss_spend_alert.clone();
 
     // Track spend tier for timed animations
     let prev_spend_tier = use_state(|| 0u8);
@@ -44,6 +52,11 @@ pub fn dashboard_page() -> Html {
     let show_launch_dialog = use_state(|| false);
     let show_admin = use_state(|| false);
     let show_settings = use_state(|| false);
+    let show_search = use_state(|| false);
+    let show_usage = use_state(|| false);
+    // Message to open, from a search hit or a `?session=..&message=..` link
+    let location = use_location();
+    let jump_target = use_state(|| location.and_then(|l| l.query::<MessageLink>().ok()));
     let focused_index = use_state(|| 0usize);
     let awaiting_sessions = use_state(HashSet::<Uuid>::new);
     let hidden_sessions = use_state(load_hidden_sessions);
@@ -175,12 +188,15 @@ pub fn dashboard_page() -> Html {
         });
     }
 
-    // Get active sessions sorted by repo name, then hostname
-    // Disconnected sessions are completely hidden from the UI
+    // Get active sessions sorted by repo name, then hostname, with forks
+    // after their parent
+    // Disconnected sessions are completely hidden from the UI, except one
+    // opened from search
     let active_sessions: Vec<SessionInfo> = {
+        let jump_session = jump_target.as_ref().map(|j| j.session);
         let mut sorted: Vec<SessionInfo> = sessions
             .iter()
-            .filter(|s| s.status.as_str() == "active")
+            .filter(|s| s.status.as_str() == "active" || Some(s.id) == jump_session)
             .cloned()
             .collect();
         sorted.sort_by(|a, b| {
@@ -195,7 +211,7 @@ pub fn dashboard_page() -> Html {
                 other => other,
             }
         });
-        sorted
+        nest_forks(sorted)
     };
 
     // On initial load, focus first non-hidden session and activate all non-hidden sessions
@@ -233,6 +249,28 @@ pub fn dashboard_page() -> Html {
         );
     }
 
+    // Focus the session holding the message to open
+    {
+        let active_sessions = active_sessions.clone();
+        let focused_index = focused_index.clone();
+        let activated_sessions = activated_sessions.clone();
+
+        use_effect_with(
+            ((*jump_target).clone(), loading),
+            move |(target, is_loading)| {
+                if let (Some(target), false) = (target, *is_loading) {
+                    if let Some(idx) = active_sessions.iter().position(|s| s.id == target.session) {
+                        focused_index.set(idx);
+                        let mut activated = (*activated_sessions).clone();
+                        activated.insert(target.session);
+                        activated_sessions.set(activated);
+                    }
+                }
+                || ()
+            },
+        );
+    }
+
     // Auto-focus newly launched session when it appears in the session list
     {
         let sessions_at_launch = sessions_at_launch.clone();
@@ -328,6 +366,35 @@ pub fn dashboard_page() -> Html {
         Callback::from(move |_: ()| show_settings.set(false))
     };
 
+    let go_to_search = {
+        let show_search = show_search.clone();
+        Callback::from(move |_| show_search.set(true))
+    };
+
+    let close_search = {
+        let show_search = show_search.clone();
+        Callback::from(move |_: ()| show_search.set(false))
+    };
+
+    let go_to_usage = {
+        let show_usage = show_usage.clone();
+        Callback::from(move |_| show_usage.set(true))
+    };
+
+    let close_usage = {
+        let show_usage = show_usage.clone();
+        Callback::from(move |_: ()| show_usage.set(false))
+    };
+
+    let open_search_hit = {
+        let show_search = show_search.clone();
+        let jump_target = jump_target.clone();
+        Callback::from(move |hit: SearchHit| {
+            jump_target.set(Some(MessageLink::from(&hit)));
+            show_search.set(false);
+        })
+    };
+
     let do_logout = Callback::from(move |_| {
         if let Some(window) = web_sys::window() {
             let _ = window.location().set_href("/api/auth/logout");
@@ -594,6 +661,60 @@ pub fn dashboard_page() -> Html {
                 }
             }
 
+            // A spend budget crossed its soft or hard limit
+            {
+                if let Some(alert) = spend_alert.as_ref() {
+                    let on_dismiss = dismiss_spend_alert.reform(|_: MouseEvent| ());
+                    html! {
+                        <div class={classes!("spend-alert-banner", alert.hard.then_some("hard"))}>
+                            <span class="spend-alert-text">
+                                { if alert.hard { "Spend limit reached: " } else { "Spend alert: " } }
+                                { &alert.message }
+                            </span>
+                            <button onclick={on_dismiss}>{ "Dismiss" }</button>
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+
+            // Permission request escalated after nobody answered it in time
+            {
+                if let Some(escalation) = permission_escalation.as_ref() {
+                    let on_open = {
+                        let on_select_session = on_select_session.clone();
+                        let dismiss_escalation = dismiss_escalation.clone();
+                        let index = active_sessions
+                            .iter()
+                            .position(|s| s.id == escalation.session_id);
+                        Callback::from(move |_| {
+                            if let Some(index) = index {
+                                on_select_session.emit(index);
+                            }
+                            dismiss_escalation.emit(());
+                        })
+                    };
+                    let on_dismiss = dismiss_escalation.reform(|_: MouseEvent| ());
+                    html! {
+                        <div class="permission-escalation-banner">
+                            <span class="escalation-text">
+                                { format!(
+                                    "{} in \"{}\" has been waiting for permission for {}s",
+                                    escalation.tool_name,
+                                    escalation.session_name,
+                                    escalation.waiting_seconds
+                                ) }
+                            </span>
+                            <button class="escalation-open" onclick={on_open}>{ "Open session" }</button>
+                            <button class="escalation-dismiss" onclick={on_dismiss}>{ "Dismiss" }</button>
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+
             // Header
             <header class="focus-flow-header">
                 <h1>{ (*app_title).clone() }</h1>
@@ -667,6 +788,12 @@ pub fn dashboard_page() -> Html {
                             html! {}
                         }
                     }
+                    <button class="header-button" onclick={go_to_search.clone()}>
+                        { "Search" }
+                    </button>
+                    <button class="header-button" onclick={go_to_usage.clone()}>
+                        { "Usage" }
+                    </button>
                     <button class="header-button" onclick={go_to_settings.clone()}>
                         { "Settings" }
                     </button>
@@ -751,6 +878,7 @@ pub fn dashboard_page() -> Html {
                                                 voice_enabled={*voice_enabled}
                                                 current_user_id={(*current_user_id).clone()}
                                                 interrupt_signal={*interrupt_signal}
+                                                jump_to_message={jump_target.as_ref().filter(|j| j.session == session.id).map(|j| j.message)}
                                             />
                                         </div>
                                     }
@@ -825,6 +953,20 @@ pub fn dashboard_page() -> Html {
                 </div>
             }
 
+            // Search modal — full-page overlay preserves dashboard state
+            if *show_search {
+                <div class="full-page-modal">
+                    <SearchPage on_close={close_search.clone()} on_open={open_search_hit.clone()} />
+                </div>
+            }
+
+            // Usage modal — full-page overlay preserves dashboard state
+            if *show_usage {
+                <div class="full-page-modal">
+                    <UsagePage on_close={close_usage.clone()} />
+                </div>
+            }
+
             // Leave confirmation modal
             {
                 if let Some(session_id) = *pending_leave {
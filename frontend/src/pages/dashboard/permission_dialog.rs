@@ -42,6 +42,15 @@ pub struct PermissionDialogProps {
     /// Callback to toggle a multi-select option for a specific question
     /// (question_index, option_index)
     pub on_toggle_option: Callback<(usize, usize)>,
+    /// Index of this request within the session's queue of pending requests
+    #[prop_or_default]
+    pub queue_position: usize,
+    /// Number of pending requests in the queue (0 or 1 hides the queue nav)
+    #[prop_or_default]
+    pub queue_len: usize,
+    /// Callback to show another queued request by index
+    #[prop_or_default]
+    pub on_queue_select: Callback<usize>,
 }
 
 /// Permission dialog component - handles both regular permissions and AskUserQuestion
@@ -49,20 +58,64 @@ pub struct PermissionDialogProps {
 pub fn permission_dialog(props: &PermissionDialogProps) -> Html {
     let perm = &props.permission;
 
-    // Check if this is an AskUserQuestion
-    if perm.tool_name == "AskUserQuestion" {
-        if let Some(parsed) = parse_ask_user_question(&perm.input) {
-            return render_ask_user_question(props, &parsed);
+    let dialog = match perm.tool_name.as_str() {
+        "AskUserQuestion" => match parse_ask_user_question(&perm.input) {
+            Some(parsed) => render_ask_user_question(props, &parsed),
+            None => render_standard_permission(props),
+        },
+        "ExitPlanMode" => render_exitplanmode_permission(props),
+        _ => render_standard_permission(props),
+    };
+
+    if props.queue_len > 1 {
+        html! {
+            <>
+                { render_queue_nav(props) }
+                { dialog }
+            </>
         }
+    } else {
+        dialog
     }
+}
 
-    // Check if this is ExitPlanMode
-    if perm.tool_name == "ExitPlanMode" {
-        return render_exitplanmode_permission(props);
-    }
+/// Render the "Request X of N" navigation shown when several requests are pending
+fn render_queue_nav(props: &PermissionDialogProps) -> Html {
+    let position = props.queue_position;
+    let len = props.queue_len;
+
+    let on_prev = {
+        let cb = props.on_queue_select.clone();
+        Callback::from(move |_| cb.emit(position.saturating_sub(1)))
+    };
+    let on_next = {
+        let cb = props.on_queue_select.clone();
+        Callback::from(move |_| cb.emit((position + 1).min(len - 1)))
+    };
 
-    // Regular permission dialog
-    render_standard_permission(props)
+    html! {
+        <div class="permission-queue-nav">
+            <button
+                class="queue-nav-btn"
+                onclick={on_prev}
+                disabled={position == 0}
+                title="Previous request"
+            >
+                { "‹" }
+            </button>
+            <span class="queue-nav-label">
+                { format!("Request {} of {}", position + 1, len) }
+            </span>
+            <button
+                class="queue-nav-btn"
+                onclick={on_next}
+                disabled={position + 1 >= len}
+                title="Next request"
+            >
+                { "›" }
+            </button>
+        </div>
+    }
 }
 
 /// Render the standard permission dialog (Allow/Deny)
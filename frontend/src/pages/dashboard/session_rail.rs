@@ -640,8 +640,13 @@ pub fn session_rail(props: &SessionRailProps) -> Html {
 
         let in_nav_mode = props.nav_mode;
         let is_status_disconnected = session.status.as_str() != "active";
+        // Forks are listed right after their parent (see `nest_forks`)
+        let fork_parent = session
+            .parent_session_id
+            .and_then(|id| props.sessions.iter().find(|s| s.id == id));
         let pill_class = classes!(
             "session-pill",
+            fork_parent.is_some().then_some("fork"),
             if is_focused { Some("focused") } else { None },
             if is_awaiting { Some("awaiting") } else { None },
             if is_hidden { Some("hidden") } else { None },
@@ -775,6 +780,14 @@ pub fn session_rail(props: &SessionRailProps) -> Html {
                         html! {}
                     }
                 }
+                {
+                    if let Some(parent) = fork_parent {
+                        let tooltip = format!("Forked from {}", parent.session_name);
+                        html! { <span class="pill-agent-badge fork" title={tooltip}>{ "⑂" }</span> }
+                    } else {
+                        html! {}
+                    }
+                }
                 {
                     if session.scheduled_task_id.is_some() {
                         html! { <span class="pill-agent-badge cron">{ "Cron" }</span> }
@@ -1,11 +1,13 @@
 //! SessionView component - Main terminal view for a single session
 
 use crate::components::message_renderer::MessageRenderer;
-use crate::components::{group_messages, MessageGroupRenderer, VoiceInput};
+use crate::components::{
+    fork_point, group_containing, group_messages, MessageGroupRenderer, VoiceInput,
+};
 use crate::utils;
 use gloo::timers::callback::{Interval, Timeout};
 use gloo_net::http::Request;
-use shared::api::{ErrorMessage, PermissionAnswers};
+use shared::api::{ErrorMessage, ForkSessionRequest, PermissionAnswers};
 use shared::{ClientToServer, SendMode, SessionInfo};
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
@@ -32,6 +34,20 @@ fn is_claude_awaiting(messages: impl DoubleEndedIterator<Item = impl AsRef<str>>
         .is_some_and(|t| t == "result")
 }
 
+/// Position of the message with this database id. History messages carry
+/// their id as `_id` (see `LoadHistory`).
+fn find_message(messages: &[String], id: Uuid) -> Option<usize> {
+    let needle = format!("\"_id\":\"{}\"", id);
+    messages.iter().position(|m| m.contains(&needle))
+}
+
+/// Which pane of the session view is showing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SessionPane {
+    Transcript,
+    Permissions,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 enum TaskStatus {
     Running,
@@ -54,6 +70,7 @@ struct TaskEntry {
 }
 
 use super::history::CommandHistory;
+use super::permission_log::PermissionLog;
 use super::types::{PendingPermission, QuestionAnswers, WsSender, MAX_MESSAGES_PER_SESSION};
 use super::websocket::{connect_websocket, send_message, WsEvent};
 use crate::pages::dashboard::permission_dialog::PermissionDialog;
@@ -80,6 +97,9 @@ pub struct SessionViewProps {
     pub current_user_id: Option<String>,
     #[prop_or(0)]
     pub interrupt_signal: u32,
+    /// Message to scroll to and highlight, e.g. a search hit
+    #[prop_or_default]
+    pub jump_to_message: Option<Uuid>,
 }
 
 /// Messages for the SessionView component
@@ -94,6 +114,10 @@ pub enum SessionViewMsg {
     CheckAwaiting,
     ClearCostFlash,
     PermissionRequest(PendingPermission),
+    /// A queued request was answered elsewhere (another tab or user)
+    PermissionResolved(String),
+    /// Show a different request from the queue
+    PermissionFocus(usize),
     ApprovePermission,
     ApprovePermissionAndRemember,
     DenyPermission,
@@ -138,6 +162,12 @@ pub enum SessionViewMsg {
     Noop,
     /// Toggle the tasks sidebar panel
     ToggleTasksPanel,
+    /// Switch between the transcript and the permission log
+    ShowPane(SessionPane),
+    /// Fork a new session from this message
+    ForkAt(Uuid),
+    /// Outcome of a fork request, shown under the transcript
+    ForkStatus(Option<String>),
     /// 1-second tick to update task elapsed times and clean up completed tasks
     TaskTick,
     /// Clear the tab pulse animation class
@@ -162,7 +192,10 @@ pub struct SessionView {
     was_focused: bool,
     total_cost: f64,
     cost_flash: bool,
-    pending_permission: Option<PendingPermission>,
+    /// Unanswered permission requests, oldest first
+    pending_permissions: Vec<PendingPermission>,
+    /// Index into `pending_permissions` of the request shown in the dialog
+    active_permission: usize,
     permission_selected: usize,
     reconnect_attempt: u32,
     #[allow(dead_code)]
@@ -196,6 +229,16 @@ pub struct SessionView {
     input_text: String,
     /// Messages sent but not yet confirmed by the server echo
     pending_sends: Vec<String>,
+    pane: SessionPane,
+    /// Highlighted message (from `jump_to_message`)
+    jump_target: Option<Uuid>,
+    /// Whether the highlighted message still needs scrolling into view
+    jump_pending: bool,
+    jump_ref: NodeRef,
+    /// Progress or error of the last fork request
+    fork_status: Option<String>,
+    #[allow(dead_code)]
+    fork_status_timer: Option<Timeout>,
 }
 
 impl Component for SessionView {
@@ -246,7 +289,8 @@ impl Component for SessionView {
             was_focused: ctx.props().focused,
             total_cost: 0.0,
             cost_flash: false,
-            pending_permission: None,
+            pending_permissions: Vec::new(),
+            active_permission: 0,
             permission_selected: 0,
             reconnect_attempt: 0,
             reconnect_timer: None,
@@ -272,6 +316,12 @@ impl Component for SessionView {
             tab_departing: false,
             input_text: String::new(),
             pending_sends: Vec::new(),
+            pane: SessionPane::Transcript,
+            jump_target: ctx.props().jump_to_message,
+            jump_pending: ctx.props().jump_to_message.is_some(),
+            jump_ref: NodeRef::default(),
+            fork_status: None,
+            fork_status_timer: None,
         }
     }
 
@@ -294,6 +344,13 @@ impl Component for SessionView {
             ctx.link().send_message(SessionViewMsg::Interrupt);
         }
 
+        let jump = ctx.props().jump_to_message;
+        if jump.is_some() && jump != old_props.jump_to_message {
+            self.jump_target = jump;
+            self.jump_pending = true;
+            self.pane = SessionPane::Transcript;
+        }
+
         true
     }
 
@@ -313,7 +370,7 @@ impl Component for SessionView {
             }
         }
 
-        if self.pending_permission.is_some() && ctx.props().focused {
+        if !self.pending_permissions.is_empty() && ctx.props().focused {
             if let Some(el) = self.permission_ref.cast::<web_sys::HtmlElement>() {
                 let _ = el.focus();
             }
@@ -342,6 +399,17 @@ impl Component for SessionView {
                 element.set_scroll_top(element.scroll_height());
             }
         }
+
+        // Scroll to the requested message once it has been rendered
+        if self.jump_pending {
+            if let Some(target) = self.jump_ref.cast::<Element>() {
+                let opts = web_sys::ScrollIntoViewOptions::new();
+                opts.set_block(web_sys::ScrollLogicalPosition::Center);
+                target.scroll_into_view_with_scroll_into_view_options(&opts);
+                *self.should_autoscroll.borrow_mut() = false;
+                self.jump_pending = false;
+            }
+        }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
@@ -537,6 +605,13 @@ impl Component for SessionView {
                                     "_created_at".to_string(),
                                     serde_json::Value::String(m.created_at.clone()),
                                 );
+                                // Inject _id so search hits can be located
+                                if let Some(id) = m.id {
+                                    obj.insert(
+                                        "_id".to_string(),
+                                        serde_json::Value::String(id.to_string()),
+                                    );
+                                }
                             }
                             return val.to_string();
                         }
@@ -553,10 +628,19 @@ impl Component for SessionView {
                 true
             }
             SessionViewMsg::PermissionRequest(perm) => {
-                self.pending_permission = Some(perm);
-                self.permission_selected = 0;
-                self.question_answers.clear();
-                self.multi_select_options.clear();
+                match self
+                    .pending_permissions
+                    .iter_mut()
+                    .find(|p| p.request_id == perm.request_id)
+                {
+                    Some(existing) => *existing = perm,
+                    None => self.pending_permissions.push(perm),
+                }
+                // Only reset the dialog when this request is the one now shown
+                if self.pending_permissions.len() == 1 {
+                    self.active_permission = 0;
+                    self.reset_permission_dialog();
+                }
                 let session_id = ctx.props().session.id;
                 ctx.props().on_awaiting_change.emit((session_id, true));
                 if let Some(el) = self.permission_ref.cast::<web_sys::HtmlElement>() {
@@ -564,6 +648,24 @@ impl Component for SessionView {
                 }
                 true
             }
+            SessionViewMsg::PermissionResolved(request_id) => {
+                if let Some(index) = self
+                    .pending_permissions
+                    .iter()
+                    .position(|p| p.request_id == request_id)
+                {
+                    self.remove_permission(index);
+                    ctx.link().send_message(SessionViewMsg::CheckAwaiting);
+                }
+                true
+            }
+            SessionViewMsg::PermissionFocus(index) => {
+                if index < self.pending_permissions.len() && index != self.active_permission {
+                    self.active_permission = index;
+                    self.reset_permission_dialog();
+                }
+                true
+            }
             SessionViewMsg::PermissionSelectUp => self.handle_permission_select(-1),
             SessionViewMsg::PermissionSelectDown => self.handle_permission_select(1),
             SessionViewMsg::PermissionConfirm => self.handle_permission_confirm(ctx),
@@ -578,6 +680,9 @@ impl Component for SessionView {
             }
             SessionViewMsg::DenyPermission => self.handle_deny_permission(ctx),
             SessionViewMsg::WebSocketConnected(sender) => {
+                // The server replays every still-pending request after registration
+                self.pending_permissions.clear();
+                self.active_permission = 0;
                 self.ws_connected = true;
                 self.ws_sender = Some(sender);
                 self.reconnect_attempt = 0;
@@ -606,7 +711,7 @@ impl Component for SessionView {
                 } else {
                     is_claude_awaiting(self.messages.iter())
                 };
-                let is_awaiting = is_result_awaiting || self.pending_permission.is_some();
+                let is_awaiting = is_result_awaiting || !self.pending_permissions.is_empty();
                 let session_id = ctx.props().session.id;
                 ctx.props()
                     .on_awaiting_change
@@ -828,6 +933,7 @@ impl Component for SessionView {
                         ClientToServer::ClaudeInput {
                             content: serde_json::Value::String(combined),
                             send_mode: None,
+                            traceparent: None,
                         },
                     );
 
@@ -885,6 +991,48 @@ impl Component for SessionView {
                 self.tasks_panel_open = !self.tasks_panel_open;
                 true
             }
+            SessionViewMsg::ShowPane(pane) => {
+                if self.pane == pane {
+                    return false;
+                }
+                self.pane = pane;
+                true
+            }
+            SessionViewMsg::ForkAt(message_id) => {
+                let session_id = ctx.props().session.id;
+                let link = ctx.link().clone();
+                spawn_local(async move {
+                    let url = utils::api_url(&format!("/api/sessions/{}/fork", session_id));
+                    let status = match Request::post(&url)
+                        .json(&ForkSessionRequest { message_id })
+                        .unwrap()
+                        .send()
+                        .await
+                    {
+                        Ok(resp) if resp.ok() => {
+                            "Fork started; it will appear next to this session".to_string()
+                        }
+                        Ok(resp) => {
+                            format!("Could not fork: {}", resp.text().await.unwrap_or_default())
+                        }
+                        Err(e) => format!("Could not fork: {}", e),
+                    };
+                    link.send_message(SessionViewMsg::ForkStatus(Some(status)));
+                });
+                self.fork_status = Some("Starting fork...".to_string());
+                self.fork_status_timer = None;
+                true
+            }
+            SessionViewMsg::ForkStatus(status) => {
+                if status.is_some() {
+                    let link = ctx.link().clone();
+                    self.fork_status_timer = Some(Timeout::new(6_000, move || {
+                        link.send_message(SessionViewMsg::ForkStatus(None));
+                    }));
+                }
+                self.fork_status = status;
+                true
+            }
             SessionViewMsg::ClearTabPulse => {
                 self.tab_anim = None;
                 true
@@ -1014,13 +1162,57 @@ impl Component for SessionView {
             "session-view-input"
         };
 
+        // Forks run on the owner's launcher with Claude's fork support
+        let session = &ctx.props().session;
+        let can_fork = session.my_role == "owner"
+            && session.agent_type == shared::AgentType::Claude
+            && session.launcher_id.is_some();
+
+        let jump_group = self
+            .jump_target
+            .and_then(|id| find_message(&self.messages, id))
+            .map(|index| group_containing(&self.messages, index));
+
         html! {
             <div class="session-view" onclick={close_dropdown}>
-                <div class="session-view-scroll-area">
+                { self.render_pane_tabs(ctx) }
+                if self.pane == SessionPane::Permissions {
+                    <PermissionLog
+                        session_id={ctx.props().session.id}
+                        is_owner={ctx.props().session.my_role == "owner"}
+                    />
+                }
+                <div class={classes!(
+                    "session-view-scroll-area",
+                    (self.pane != SessionPane::Transcript).then_some("hidden")
+                )}>
                     <div class="session-view-messages" ref={self.messages_ref.clone()}>
                         {
-                            group_messages(&self.messages).into_iter().map(|group| {
-                                html! { <MessageGroupRenderer group={group} session_id={Some(ctx.props().session.id)} agent_type={ctx.props().session.agent_type} current_user_id={ctx.props().current_user_id.clone()} /> }
+                            group_messages(&self.messages).into_iter().enumerate().map(|(index, group)| {
+                                let fork_button = fork_point(&group)
+                                    .filter(|_| can_fork)
+                                    .map(|message_id| {
+                                        let onclick = link.callback(move |e: MouseEvent| {
+                                            e.stop_propagation();
+                                            SessionViewMsg::ForkAt(message_id)
+                                        });
+                                        html! {
+                                            <button class="fork-button" title="Start a new session from this point of the conversation" {onclick}>
+                                                { "⑂ Fork from here" }
+                                            </button>
+                                        }
+                                    });
+                                let rendered = html! {
+                                    <>
+                                        <MessageGroupRenderer group={group} session_id={Some(ctx.props().session.id)} agent_type={ctx.props().session.agent_type} current_user_id={ctx.props().current_user_id.clone()} />
+                                        { fork_button }
+                                    </>
+                                };
+                                if Some(index) == jump_group {
+                                    html! { <div class="jump-target" ref={self.jump_ref.clone()}>{ rendered }</div> }
+                                } else {
+                                    rendered
+                                }
                             }).collect::<Html>()
                         }
                         { for self.pending_sends.iter().map(|json| {
@@ -1029,6 +1221,9 @@ impl Component for SessionView {
                     </div>
                     { self.render_tasks_sidebar(ctx) }
                 </div>
+                if let Some(status) = &self.fork_status {
+                    <div class="fork-status">{ status }</div>
+                }
 
                 { self.render_permission_dialog(ctx) }
                 { self.render_upload_bar() }
@@ -1129,6 +1324,11 @@ impl SessionView {
                     .send_message(SessionViewMsg::PermissionRequest(perm));
                 false
             }
+            WsEvent::PermissionResolved(request_id) => {
+                ctx.link()
+                    .send_message(SessionViewMsg::PermissionResolved(request_id));
+                false
+            }
             WsEvent::BranchChanged(branch, pr_url, repo_url) => {
                 ctx.link()
                     .send_message(SessionViewMsg::BranchChanged(branch, pr_url, repo_url));
@@ -1174,6 +1374,8 @@ impl SessionView {
                 } else {
                     Some(send_mode)
                 },
+                // The browser exports no spans, so the backend starts the trace
+                traceparent: None,
             };
             send_message(sender, msg);
         }
@@ -1404,8 +1606,41 @@ impl SessionView {
         true
     }
 
+    /// The request currently shown in the permission dialog
+    fn current_permission(&self) -> Option<&PendingPermission> {
+        self.pending_permissions.get(self.active_permission)
+    }
+
+    /// Remove the request currently shown and move on to the next one.
+    fn take_current_permission(&mut self) -> Option<PendingPermission> {
+        if self.active_permission < self.pending_permissions.len() {
+            Some(self.remove_permission(self.active_permission))
+        } else {
+            None
+        }
+    }
+
+    fn remove_permission(&mut self, index: usize) -> PendingPermission {
+        let removed = self.pending_permissions.remove(index);
+        if index < self.active_permission {
+            self.active_permission -= 1;
+        } else if index == self.active_permission {
+            if self.active_permission >= self.pending_permissions.len() {
+                self.active_permission = 0;
+            }
+            self.reset_permission_dialog();
+        }
+        removed
+    }
+
+    fn reset_permission_dialog(&mut self) {
+        self.permission_selected = 0;
+        self.question_answers.clear();
+        self.multi_select_options.clear();
+    }
+
     fn handle_permission_select(&mut self, delta: i32) -> bool {
-        if let Some(ref perm) = self.pending_permission {
+        if let Some(perm) = self.current_permission() {
             let max = if perm.tool_name == "AskUserQuestion" {
                 if let Some(parsed) = parse_ask_user_question(&perm.input) {
                     parsed
@@ -1438,7 +1673,7 @@ impl SessionView {
     }
 
     fn handle_permission_confirm(&mut self, ctx: &Context<Self>) -> bool {
-        if let Some(ref perm) = self.pending_permission {
+        if let Some(perm) = self.current_permission() {
             if perm.tool_name == "AskUserQuestion" {
                 if !self.question_answers.is_empty() {
                     ctx.link().send_message(SessionViewMsg::SubmitAllAnswers(
@@ -1461,7 +1696,7 @@ impl SessionView {
     }
 
     fn handle_approve_permission(&mut self, ctx: &Context<Self>, remember: bool) -> bool {
-        if let Some(perm) = self.pending_permission.take() {
+        if let Some(perm) = self.take_current_permission() {
             if let Some(ref sender) = self.ws_sender {
                 let msg = ClientToServer::PermissionResponse(shared::PermissionResponseFields {
                     request_id: perm.request_id,
@@ -1485,7 +1720,7 @@ impl SessionView {
     }
 
     fn handle_deny_permission(&mut self, ctx: &Context<Self>) -> bool {
-        if let Some(perm) = self.pending_permission.take() {
+        if let Some(perm) = self.take_current_permission() {
             if let Some(ref sender) = self.ws_sender {
                 let msg = ClientToServer::PermissionResponse(shared::PermissionResponseFields {
                     request_id: perm.request_id,
@@ -1545,7 +1780,7 @@ impl SessionView {
     }
 
     fn handle_submit_answers(&mut self, ctx: &Context<Self>, answers: QuestionAnswers) -> bool {
-        if let Some(perm) = self.pending_permission.take() {
+        if let Some(perm) = self.take_current_permission() {
             if let Some(ref sender) = self.ws_sender {
                 let answers_json = if let Some(parsed) = parse_ask_user_question(&perm.input) {
                     let mut pa = PermissionAnswers::empty();
@@ -1582,7 +1817,7 @@ impl SessionView {
     }
 
     fn render_permission_dialog(&self, ctx: &Context<Self>) -> Html {
-        if let Some(ref perm) = self.pending_permission {
+        if let Some(perm) = self.current_permission() {
             let link = ctx.link();
             let on_select_up = link.callback(|_| SessionViewMsg::PermissionSelectUp);
             let on_select_down = link.callback(|_| SessionViewMsg::PermissionSelectDown);
@@ -1593,6 +1828,7 @@ impl SessionView {
                 link.callback(|(q_idx, answer)| SessionViewMsg::SetQuestionAnswer(q_idx, answer));
             let on_toggle_option = link
                 .callback(|(q_idx, opt_idx)| SessionViewMsg::ToggleQuestionOption(q_idx, opt_idx));
+            let on_queue_select = link.callback(SessionViewMsg::PermissionFocus);
 
             html! {
                 <PermissionDialog
@@ -1608,6 +1844,9 @@ impl SessionView {
                     {on_submit_answers}
                     {on_set_answer}
                     {on_toggle_option}
+                    queue_position={self.active_permission}
+                    queue_len={self.pending_permissions.len()}
+                    {on_queue_select}
                 />
             }
         } else {
@@ -1826,6 +2065,52 @@ impl SessionView {
         }));
     }
 
+    fn render_pane_tabs(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let tab = |pane: SessionPane, label: &'static str| {
+            let onclick = link.callback(move |e: MouseEvent| {
+                e.stop_propagation();
+                SessionViewMsg::ShowPane(pane)
+            });
+            html! {
+                <button
+                    class={classes!("session-pane-tab", (self.pane == pane).then_some("active"))}
+                    {onclick}
+                >
+                    { label }
+                </button>
+            }
+        };
+
+        let session_id = ctx.props().session.id;
+        let export = |format: &'static str, label: &'static str| {
+            html! {
+                <a
+                    class="session-export-link"
+                    href={utils::api_url(&format!("/api/sessions/{}/export?format={}", session_id, format))}
+                    download=""
+                    title={format!("Download the transcript as {}", label)}
+                    onclick={Callback::from(|e: MouseEvent| e.stop_propagation())}
+                >
+                    { label }
+                </a>
+            }
+        };
+
+        html! {
+            <div class="session-pane-tabs">
+                { tab(SessionPane::Transcript, "Transcript") }
+                { tab(SessionPane::Permissions, "Permissions") }
+                <div class="session-export">
+                    <span class="session-export-label">{ "Export" }</span>
+                    { export("md", "Markdown") }
+                    { export("html", "HTML") }
+                    { export("jsonl", "JSONL") }
+                </div>
+            </div>
+        }
+    }
+
     fn render_tasks_sidebar(&self, ctx: &Context<Self>) -> Html {
         let running_count = self
             .active_tasks
@@ -5,9 +5,11 @@
 //! - `types.rs` - Types specific to SessionView (re-exports from parent)
 //! - `websocket.rs` - WebSocket connection management
 //! - `history.rs` - Command history management
+//! - `permission_log.rs` - Audit view of answered permission requests
 
 mod component;
 mod history;
+mod permission_log;
 mod types;
 mod websocket;
 
@@ -0,0 +1,358 @@
+//! Permission log - audit view of every answered permission request in a session,
+//! plus the session's permission timeout setting
+
+use super::types::format_permission_input;
+use crate::utils;
+use gloo_net::http::Request;
+use shared::api::{
+    PermissionDecider, PermissionDecisionInfo, PermissionDecisionListResponse,
+    PermissionTimeoutAction, PermissionTimeoutInfo, UpdatePermissionTimeoutRequest,
+};
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+/// Fetch a session's permission decisions, newest first
+async fn fetch_decisions(session_id: Uuid) -> Option<Vec<PermissionDecisionInfo>> {
+    let api_endpoint = utils::api_url(&format!(
+        "/api/sessions/{}/permission-decisions",
+        session_id
+    ));
+    match Request::get(&api_endpoint).send().await {
+        Ok(response) if response.ok() => response
+            .json::<PermissionDecisionListResponse>()
+            .await
+            .ok()
+            .map(|data| data.decisions),
+        Ok(response) => {
+            log::error!(
+                "Failed to fetch permission decisions: HTTP {}",
+                response.status()
+            );
+            None
+        }
+        Err(e) => {
+            log::error!("Failed to fetch permission decisions: {:?}", e);
+            None
+        }
+    }
+}
+
+fn decider_label(decision: &PermissionDecisionInfo) -> String {
+    match decision.decided_by {
+        PermissionDecider::User => decision
+            .user_name
+            .clone()
+            .unwrap_or_else(|| "Portal user".to_string()),
+        PermissionDecider::Policy => "Policy rule".to_string(),
+        PermissionDecider::Vscode => "VS Code".to_string(),
+        PermissionDecider::Timeout => "Timeout".to_string(),
+    }
+}
+
+fn timeout_api_url(session_id: Uuid) -> String {
+    utils::api_url(&format!("/api/sessions/{}/permission-timeout", session_id))
+}
+
+/// Describe the effective timeout, e.g. "Deny after 60s (from scheduled task)".
+fn describe_timeout(info: &PermissionTimeoutInfo) -> String {
+    let Some(seconds) = info.timeout_seconds else {
+        return "Requests wait for an answer indefinitely".to_string();
+    };
+    let action = match info.action {
+        PermissionTimeoutAction::Deny => "Deny",
+        PermissionTimeoutAction::Allow => "Approve",
+        PermissionTimeoutAction::Escalate => "Notify all members",
+    };
+    let source = if info.inherited {
+        " (from scheduled task)"
+    } else {
+        ""
+    };
+    format!("{} after {}s{}", action, seconds, source)
+}
+
+fn format_latency(ms: i64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms < 60_000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        format!("{}m {}s", ms / 60_000, (ms % 60_000) / 1000)
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PermissionTimeoutControlProps {
+    pub session_id: Uuid,
+    /// Only the owner can change the timeout
+    pub is_owner: bool,
+}
+
+#[function_component(PermissionTimeoutControl)]
+fn permission_timeout_control(props: &PermissionTimeoutControlProps) -> Html {
+    let info = use_state(|| None::<PermissionTimeoutInfo>);
+    let seconds_input = use_state(String::new);
+    let action = use_state(PermissionTimeoutAction::default);
+    let error = use_state(|| None::<String>);
+
+    {
+        let info = info.clone();
+        let seconds_input = seconds_input.clone();
+        let action = action.clone();
+        let session_id = props.session_id;
+        use_effect_with(session_id, move |_| {
+            spawn_local(async move {
+                if let Ok(response) = Request::get(&timeout_api_url(session_id)).send().await {
+                    if let Ok(data) = response.json::<PermissionTimeoutInfo>().await {
+                        if !data.inherited {
+                            seconds_input.set(
+                                data.timeout_seconds
+                                    .map(|s| s.to_string())
+                                    .unwrap_or_default(),
+                            );
+                            action.set(data.action);
+                        }
+                        info.set(Some(data));
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_seconds = {
+        let seconds_input = seconds_input.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            seconds_input.set(input.value());
+        })
+    };
+
+    let on_action = {
+        let action = action.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            action.set(select.value().parse().unwrap_or_default());
+        })
+    };
+
+    let on_save = {
+        let info = info.clone();
+        let seconds_input = seconds_input.clone();
+        let action = action.clone();
+        let error = error.clone();
+        let session_id = props.session_id;
+        Callback::from(move |_| {
+            let trimmed = seconds_input.trim();
+            let timeout_seconds = if trimmed.is_empty() {
+                None
+            } else {
+                match trimmed.parse::<i32>() {
+                    Ok(s) => Some(s),
+                    Err(_) => {
+                        error.set(Some("Timeout must be a number of seconds".to_string()));
+                        return;
+                    }
+                }
+            };
+            let body = UpdatePermissionTimeoutRequest {
+                timeout_seconds,
+                action: *action,
+            };
+            let info = info.clone();
+            let error = error.clone();
+            spawn_local(async move {
+                match Request::put(&timeout_api_url(session_id))
+                    .json(&body)
+                    .unwrap()
+                    .send()
+                    .await
+                {
+                    Ok(response) if response.ok() => {
+                        error.set(None);
+                        if let Ok(data) = response.json::<PermissionTimeoutInfo>().await {
+                            info.set(Some(data));
+                        }
+                    }
+                    Ok(response) => {
+                        let message = response.text().await.unwrap_or_default();
+                        error.set(Some(if message.is_empty() {
+                            format!("Failed to save (HTTP {})", response.status())
+                        } else {
+                            message
+                        }));
+                    }
+                    Err(e) => error.set(Some(format!("Failed to save: {:?}", e))),
+                }
+            });
+        })
+    };
+
+    let Some(current) = (*info).clone() else {
+        return html! {};
+    };
+
+    html! {
+        <div class="permission-timeout">
+            <span class="permission-timeout-summary">
+                { "Unanswered requests: " }{ describe_timeout(&current) }
+            </span>
+            if props.is_owner {
+                <div class="permission-timeout-form">
+                    <input
+                        type="number"
+                        min="10"
+                        placeholder="seconds"
+                        value={(*seconds_input).clone()}
+                        oninput={on_seconds}
+                    />
+                    <select onchange={on_action}>
+                        { for [
+                            (PermissionTimeoutAction::Deny, "Deny"),
+                            (PermissionTimeoutAction::Allow, "Approve"),
+                            (PermissionTimeoutAction::Escalate, "Notify all members"),
+                        ].into_iter().map(|(value, label)| html! {
+                            <option value={value.as_str()} selected={*action == value}>{ label }</option>
+                        })}
+                    </select>
+                    <button onclick={on_save}>{ "Save" }</button>
+                </div>
+            }
+            if let Some(message) = &*error {
+                <span class="permission-timeout-error">{ message }</span>
+            }
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PermissionLogProps {
+    pub session_id: Uuid,
+    #[prop_or_default]
+    pub is_owner: bool,
+}
+
+#[function_component(PermissionLog)]
+pub fn permission_log(props: &PermissionLogProps) -> Html {
+    let decisions = use_state(|| None::<Vec<PermissionDecisionInfo>>);
+    let error = use_state(|| false);
+    let refresh = use_state(|| 0u32);
+
+    {
+        let decisions = decisions.clone();
+        let error = error.clone();
+        let session_id = props.session_id;
+        use_effect_with((session_id, *refresh), move |_| {
+            spawn_local(async move {
+                match fetch_decisions(session_id).await {
+                    Some(list) => {
+                        error.set(false);
+                        decisions.set(Some(list));
+                    }
+                    None => error.set(true),
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_refresh = {
+        let refresh = refresh.clone();
+        Callback::from(move |_| refresh.set(*refresh + 1))
+    };
+
+    let body = match &*decisions {
+        _ if *error => html! {
+            <p class="permission-log-empty">{ "Failed to load permission decisions." }</p>
+        },
+        None => html! { <p class="permission-log-empty">{ "Loading..." }</p> },
+        Some(list) if list.is_empty() => html! {
+            <p class="permission-log-empty">{ "No permission requests have been answered in this session yet." }</p>
+        },
+        Some(list) => html! {
+            <table class="permission-log-table">
+                <thead>
+                    <tr>
+                        <th>{ "Decided" }</th>
+                        <th>{ "Tool" }</th>
+                        <th>{ "Input" }</th>
+                        <th>{ "Decision" }</th>
+                        <th>{ "By" }</th>
+                        <th>{ "Reason" }</th>
+                        <th>{ "Latency" }</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for list.iter().map(|d| {
+                        let (decision_class, decision_label) = match d.allowed {
+                            Some(true) => ("allowed", "Allowed"),
+                            Some(false) => ("denied", "Denied"),
+                            None => ("unknown", "Unknown"),
+                        };
+                        html! {
+                            <tr key={d.id.to_string()}>
+                                <td class="permission-log-time">{ utils::format_timestamp(&d.decided_at) }</td>
+                                <td class="permission-log-tool">{ &d.tool_name }</td>
+                                <td class="permission-log-input">
+                                    <pre>{ format_permission_input(&d.tool_name, &d.input) }</pre>
+                                </td>
+                                <td class={classes!("permission-log-decision", decision_class)}>
+                                    { decision_label }
+                                </td>
+                                <td class="permission-log-decider">{ decider_label(d) }</td>
+                                <td class="permission-log-reason">{ d.reason.as_deref().unwrap_or("") }</td>
+                                <td class="permission-log-latency">{ format_latency(d.latency_ms) }</td>
+                            </tr>
+                        }
+                    })}
+                </tbody>
+            </table>
+        },
+    };
+
+    html! {
+        <div class="permission-log">
+            <div class="permission-log-header">
+                <span>{ "Permission decisions" }</span>
+                <button class="permission-log-refresh" onclick={on_refresh}>{ "Refresh" }</button>
+            </div>
+            <PermissionTimeoutControl session_id={props.session_id} is_owner={props.is_owner} />
+            { body }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_latency() {
+        assert_eq!(format_latency(250), "250ms");
+        assert_eq!(format_latency(4_200), "4.2s");
+        assert_eq!(format_latency(125_000), "2m 5s");
+    }
+
+    #[test]
+    fn test_describe_timeout() {
+        let none = PermissionTimeoutInfo {
+            timeout_seconds: None,
+            action: PermissionTimeoutAction::Deny,
+            inherited: false,
+        };
+        assert_eq!(
+            describe_timeout(&none),
+            "Requests wait for an answer indefinitely"
+        );
+        let inherited = PermissionTimeoutInfo {
+            timeout_seconds: Some(300),
+            action: PermissionTimeoutAction::Escalate,
+            inherited: true,
+        };
+        assert_eq!(
+            describe_timeout(&inherited),
+            "Notify all members after 300s (from scheduled task)"
+        );
+    }
+}
@@ -4,5 +4,5 @@
 
 // Re-export from parent types module
 pub use crate::pages::dashboard::types::{
-    PendingPermission, QuestionAnswers, WsSender, MAX_MESSAGES_PER_SESSION,
+    format_permission_input, PendingPermission, QuestionAnswers, WsSender, MAX_MESSAGES_PER_SESSION,
 };
@@ -18,6 +18,8 @@ pub enum WsEvent {
     Output(String),
     HistoryBatch(Vec<String>),
     Permission(PendingPermission),
+    /// A pending permission request was answered and should leave the queue
+    PermissionResolved(String),
     BranchChanged(Option<String>, Option<String>, Option<String>),
 }
 
@@ -125,6 +127,9 @@ fn handle_proxy_message(msg: ServerToClient, on_event: &Callback<WsEvent>) {
                 permission_suggestions,
             }));
         }
+        ServerToClient::PermissionResolved { request_id } => {
+            on_event.emit(WsEvent::PermissionResolved(request_id));
+        }
         ServerToClient::Error { message } => {
             let error_msg = ErrorMessage::new(message);
             let error_json = serde_json::to_string(&error_msg).unwrap_or_default();
@@ -138,6 +143,11 @@ fn handle_proxy_message(msg: ServerToClient, on_event: &Callback<WsEvent>) {
         } => {
             on_event.emit(WsEvent::BranchChanged(git_branch, pr_url, repo_url));
         }
+        ServerToClient::SignedOut => {
+            if let Some(window) = web_sys::window() {
+                let _ = window.location().set_href("/");
+            }
+        }
         _ => {}
     }
 }
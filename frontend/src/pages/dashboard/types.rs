@@ -1,6 +1,7 @@
 //! Shared types for the dashboard module
 
 use serde::Deserialize;
+use shared::SessionInfo;
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
@@ -28,6 +29,8 @@ pub type WsSender = Rc<RefCell<Option<ws_bridge::yew_client::Sender<shared::Clie
 /// Message data from the API
 #[derive(Clone, PartialEq, Deserialize)]
 pub struct MessageData {
+    #[serde(default)]
+    pub id: Option<Uuid>,
     pub role: String,
     pub content: String,
     /// ISO 8601 timestamp when message was created
@@ -141,6 +144,42 @@ pub fn save_hidden_sessions(hidden: &HashSet<Uuid>) {
     }
 }
 
+/// Reorder sessions so each fork directly follows its parent (depth first),
+/// keeping the existing order among siblings. Forks whose parent is not in
+/// the list keep their place.
+pub fn nest_forks(sessions: Vec<SessionInfo>) -> Vec<SessionInfo> {
+    let present: HashSet<Uuid> = sessions.iter().map(|s| s.id).collect();
+    let parent_of = |s: &SessionInfo| s.parent_session_id.filter(|p| present.contains(p));
+
+    let mut children: HashMap<Uuid, Vec<usize>> = HashMap::new();
+    for (index, session) in sessions.iter().enumerate() {
+        if let Some(parent) = parent_of(session) {
+            children.entry(parent).or_default().push(index);
+        }
+    }
+
+    let mut order = Vec::with_capacity(sessions.len());
+    let mut placed = vec![false; sessions.len()];
+    let mut stack: Vec<usize> = (0..sessions.len())
+        .filter(|&i| parent_of(&sessions[i]).is_none())
+        .rev()
+        .collect();
+    while let Some(index) = stack.pop() {
+        if std::mem::replace(&mut placed[index], true) {
+            continue;
+        }
+        order.push(index);
+        if let Some(forks) = children.get(&sessions[index].id) {
+            stack.extend(forks.iter().rev());
+        }
+    }
+    // Sessions in a parent cycle are unreachable from any root
+    order.extend((0..sessions.len()).filter(|&i| !placed[i]));
+
+    let mut slots: Vec<Option<SessionInfo>> = sessions.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
 /// Calculate exponential backoff delay for reconnection attempts
 pub fn calculate_backoff(attempt: u32) -> u32 {
     const INITIAL_MS: u32 = 1000;
@@ -166,3 +205,43 @@ pub fn format_permission_input(tool_name: &str, input: &serde_json::Value) -> St
         _ => serde_json::to_string_pretty(input).unwrap_or_else(|_| format!("{:?}", input)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: u128, parent: Option<u128>) -> SessionInfo {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::from_u128(id),
+            "user_id": Uuid::nil(),
+            "session_name": format!("s{}", id),
+            "session_key": "",
+            "working_directory": "/repo",
+            "status": "active",
+            "last_activity": "",
+            "created_at": "",
+            "updated_at": "",
+            "my_role": "owner",
+            "parent_session_id": parent.map(Uuid::from_u128),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_nest_forks_follow_parent() {
+        // 4 is a fork of 3 (itself a fork of 1), 5 forks a session not listed
+        let sessions = vec![
+            session(1, None),
+            session(2, None),
+            session(3, Some(1)),
+            session(4, Some(3)),
+            session(5, Some(99)),
+            session(6, Some(1)),
+        ];
+        let ids: Vec<u128> = nest_forks(sessions)
+            .iter()
+            .map(|s| s.id.as_u128())
+            .collect();
+        assert_eq!(ids, vec![1, 3, 4, 6, 2, 5]);
+    }
+}
@@ -0,0 +1,115 @@
+//! Invite link landing page
+//!
+//! An admin sends `/invite?code=...`. Checking the code here leaves it in a
+//! cookie, so signing in with any provider redeems the invite, even when the
+//! email allowlist would turn the user away.
+
+use crate::pages::splash::login_buttons;
+use crate::utils;
+use gloo_net::http::Request;
+use serde::Deserialize;
+use shared::api::{InviteCheckResponse, InviteRole};
+use shared::AppConfig;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+struct InviteQuery {
+    #[serde(default)]
+    code: String,
+}
+
+#[function_component(InvitePage)]
+pub fn invite_page() -> Html {
+    let code = use_location()
+        .and_then(|l| l.query::<InviteQuery>().ok())
+        .unwrap_or_default()
+        .code;
+    let invite = use_state(|| None::<InviteCheckResponse>);
+    let config = use_state(|| None::<AppConfig>);
+    let error = use_state(|| None::<String>);
+
+    {
+        let invite = invite.clone();
+        let config = config.clone();
+        let error = error.clone();
+        use_effect_with(code, move |code| {
+            let code = code.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/auth/invite");
+                let response = Request::get(&api_endpoint)
+                    .query([("code", code.as_str())])
+                    .send()
+                    .await;
+                match response {
+                    Ok(response) if response.ok() => {
+                        match response.json::<InviteCheckResponse>().await {
+                            Ok(checked) => invite.set(Some(checked)),
+                            Err(e) => error.set(Some(format!("{:?}", e))),
+                        }
+                    }
+                    Ok(_) => error.set(Some(
+                        "This invite link is invalid, has expired or has been used up. Ask an admin for a new one."
+                            .to_string(),
+                    )),
+                    Err(e) => error.set(Some(format!("{:?}", e))),
+                }
+
+                let api_endpoint = utils::api_url("/api/config");
+                if let Ok(response) = Request::get(&api_endpoint).send().await {
+                    if let Ok(app_config) = response.json::<AppConfig>().await {
+                        config.set(Some(app_config));
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let body = match (&*error, &*invite, &*config) {
+        (Some(message), _, _) => html! { <p class="tagline">{ message }</p> },
+        (None, Some(invite), Some(config)) => {
+            // Local accounts are created by admins, so an invite can't apply
+            let providers: Vec<_> = config
+                .login_providers
+                .iter()
+                .filter(|p| p.id != "local")
+                .cloned()
+                .collect();
+            let role = match invite.role {
+                InviteRole::Admin => "an admin",
+                InviteRole::User => "a user",
+            };
+            html! {
+                <>
+                    <p class="tagline">
+                        { format!(
+                            "You have been invited to join {} as {}. Sign in before {} to accept.",
+                            config.app_title,
+                            role,
+                            utils::format_timestamp(&invite.expires_at)
+                        ) }
+                    </p>
+                    if providers.is_empty() && !config.login_providers.is_empty() {
+                        <p class="tagline">{ "This portal only has local accounts; ask an admin to create one for you." }</p>
+                    } else {
+                        { login_buttons(&providers) }
+                    }
+                </>
+            }
+        }
+        _ => html! { <p class="tagline">{ "Checking invite..." }</p> },
+    };
+
+    html! {
+        <div class="splash-container">
+            <div class="splash-content splash-minimal">
+                <div class="splash-header">
+                    <h1>{ "You're invited" }</h1>
+                </div>
+                { body }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,179 @@
+//! Local account sign-in
+//!
+//! Username and password, then an authenticator code when the account has a
+//! second factor. Device logins arrive with `?device_user_code=` and go back
+//! to the approval page afterwards.
+
+use crate::utils;
+use crate::Route;
+use gloo_net::http::Request;
+use serde::Deserialize;
+use shared::api::{LocalLoginRequest, LocalLoginResponse};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+struct LoginQuery {
+    device_user_code: Option<String>,
+}
+
+/// Leave for the dashboard, or the device approval page for device logins
+fn finish_sign_in(navigator: &Navigator, device_user_code: Option<&str>) {
+    match device_user_code {
+        Some(code) => {
+            let url = utils::api_url(&format!(
+                "/api/auth/device?user_code={}",
+                js_sys::encode_uri_component(code)
+            ));
+            if let Some(window) = web_sys::window() {
+                let _ = window.location().set_href(&url);
+            }
+        }
+        None => navigator.push(&Route::Dashboard),
+    }
+}
+
+#[function_component(LoginPage)]
+pub fn login_page() -> Html {
+    let navigator = use_navigator().unwrap();
+    let query = use_location()
+        .and_then(|l| l.query::<LoginQuery>().ok())
+        .unwrap_or_default();
+    let username = use_state(String::new);
+    let password = use_state(String::new);
+    let totp_code = use_state(String::new);
+    let totp_required = use_state(|| false);
+    let submitting = use_state(|| false);
+    let error = use_state(|| None::<String>);
+
+    let on_username = {
+        let username = username.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            username.set(input.value());
+        })
+    };
+    let on_password = {
+        let password = password.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            password.set(input.value());
+        })
+    };
+    let on_totp = {
+        let totp_code = totp_code.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            totp_code.set(input.value());
+        })
+    };
+
+    let on_submit = {
+        let username = username.clone();
+        let password = password.clone();
+        let totp_code = totp_code.clone();
+        let totp_required = totp_required.clone();
+        let submitting = submitting.clone();
+        let error = error.clone();
+        let navigator = navigator.clone();
+        let device_user_code = query.device_user_code.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let body = LocalLoginRequest {
+                username: (*username).clone(),
+                password: (*password).clone(),
+                totp_code: Some((*totp_code).clone()).filter(|_| *totp_required),
+            };
+            let totp_required = totp_required.clone();
+            let submitting = submitting.clone();
+            let error = error.clone();
+            let navigator = navigator.clone();
+            let device_user_code = device_user_code.clone();
+            submitting.set(true);
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/auth/local/login");
+                let response = Request::post(&api_endpoint)
+                    .json(&body)
+                    .unwrap()
+                    .send()
+                    .await;
+                match response {
+                    Ok(response) if response.ok() => {
+                        match response.json::<LocalLoginResponse>().await {
+                            Ok(result) if result.totp_required => {
+                                totp_required.set(true);
+                                error.set(None);
+                            }
+                            Ok(_) => finish_sign_in(&navigator, device_user_code.as_deref()),
+                            Err(e) => error.set(Some(format!("Failed to sign in: {:?}", e))),
+                        }
+                    }
+                    Ok(response) if response.status() == 401 => error.set(Some(
+                        if *totp_required {
+                            "That code is not valid"
+                        } else {
+                            "Wrong username or password"
+                        }
+                        .to_string(),
+                    )),
+                    Ok(response) if response.status() == 403 => navigator.push(&Route::Banned),
+                    Ok(response) if response.status() == 429 => {
+                        error.set(Some("Too many attempts, wait a moment".to_string()))
+                    }
+                    Ok(response) => error.set(Some(format!(
+                        "Failed to sign in (HTTP {})",
+                        response.status()
+                    ))),
+                    Err(e) => error.set(Some(format!("Failed to sign in: {:?}", e))),
+                }
+                submitting.set(false);
+            });
+        })
+    };
+
+    html! {
+        <div class="login-page">
+            <form class="login-form" onsubmit={on_submit}>
+                <h1>{ "Sign in" }</h1>
+                if let Some(message) = (*error).clone() {
+                    <div class="login-error">{ message }</div>
+                }
+                if *totp_required {
+                    <label for="login-totp">{ "Authenticator code" }</label>
+                    <input
+                        type="text"
+                        id="login-totp"
+                        inputmode="numeric"
+                        autocomplete="one-time-code"
+                        value={(*totp_code).clone()}
+                        oninput={on_totp}
+                        autofocus=true
+                    />
+                } else {
+                    <label for="login-username">{ "Username" }</label>
+                    <input
+                        type="text"
+                        id="login-username"
+                        autocomplete="username"
+                        value={(*username).clone()}
+                        oninput={on_username}
+                        autofocus=true
+                    />
+                    <label for="login-password">{ "Password" }</label>
+                    <input
+                        type="password"
+                        id="login-password"
+                        autocomplete="current-password"
+                        value={(*password).clone()}
+                        oninput={on_password}
+                    />
+                }
+                <button type="submit" disabled={*submitting}>
+                    { if *submitting { "Signing in..." } else if *totp_required { "Verify" } else { "Sign in" } }
+                </button>
+                <a class="login-back" href="/">{ "Other ways to sign in" }</a>
+            </form>
+        </div>
+    }
+}
@@ -1,6 +1,12 @@
 pub mod access_denied;
+pub mod account_setup;
 pub mod admin;
 pub mod banned;
 pub mod dashboard;
+pub mod invite;
+pub mod login;
+pub mod search;
 pub mod settings;
+pub mod shared;
 pub mod splash;
+pub mod usage;
@@ -0,0 +1,415 @@
+//! Transcript search page
+//!
+//! Full-text search over the messages of every session the user can see.
+//! Choosing a hit hands it to `on_open`, which shows the session with the
+//! matching message scrolled into view.
+
+use crate::utils;
+use gloo_net::http::Request;
+use serde::{Deserialize, Serialize};
+use shared::api::{SearchHit, SearchResponse, SEARCH_HIGHLIGHT_END, SEARCH_HIGHLIGHT_START};
+use shared::SessionInfo;
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+const PAGE_SIZE: usize = 20;
+
+/// A message to open on the dashboard, as `?session=<id>&message=<id>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageLink {
+    pub session: Uuid,
+    pub message: Uuid,
+}
+
+impl From<&SearchHit> for MessageLink {
+    fn from(hit: &SearchHit) -> Self {
+        Self {
+            session: hit.session_id,
+            message: hit.message_id,
+        }
+    }
+}
+
+/// Split a snippet into (text, is_match) runs at the highlight markers
+fn split_highlights(snippet: &str) -> Vec<(String, bool)> {
+    let mut runs = Vec::new();
+    let mut current = String::new();
+    let mut in_match = false;
+    for c in snippet.chars() {
+        let toggles = if in_match {
+            c == SEARCH_HIGHLIGHT_END
+        } else {
+            c == SEARCH_HIGHLIGHT_START
+        };
+        if toggles {
+            if !current.is_empty() {
+                runs.push((std::mem::take(&mut current), in_match));
+            }
+            in_match = !in_match;
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        runs.push((current, in_match));
+    }
+    runs
+}
+
+#[derive(Clone, Default, PartialEq)]
+struct SearchForm {
+    q: String,
+    session_id: String,
+    hostname: String,
+    branch: String,
+    agent_type: String,
+    from: String,
+    to: String,
+}
+
+impl SearchForm {
+    /// Query parameters for `/api/search`, leaving out blank filters
+    fn params(&self, offset: usize) -> Vec<(&'static str, String)> {
+        let mut params = vec![("q", self.q.trim().to_string())];
+        for (name, value) in [
+            ("session_id", &self.session_id),
+            ("hostname", &self.hostname),
+            ("branch", &self.branch),
+            ("agent_type", &self.agent_type),
+            ("from", &self.from),
+            ("to", &self.to),
+        ] {
+            if !value.trim().is_empty() {
+                params.push((name, value.trim().to_string()));
+            }
+        }
+        params.push(("limit", PAGE_SIZE.to_string()));
+        if offset > 0 {
+            params.push(("offset", offset.to_string()));
+        }
+        params
+    }
+}
+
+async fn fetch_search(params: Vec<(&'static str, String)>) -> Result<SearchResponse, String> {
+    let api_endpoint = utils::api_url("/api/search");
+    let response = Request::get(&api_endpoint)
+        .query(params.iter().map(|(name, value)| (*name, value.as_str())))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.ok() {
+        return Err(response.text().await.unwrap_or_default());
+    }
+    response
+        .json::<SearchResponse>()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SearchPageProps {
+    pub on_close: Callback<()>,
+    /// Called with the hit the user chose
+    pub on_open: Callback<SearchHit>,
+}
+
+#[function_component(SearchPage)]
+pub fn search_page(props: &SearchPageProps) -> Html {
+    let form = use_state(SearchForm::default);
+    let sessions = use_state(Vec::<SessionInfo>::new);
+    let hits = use_state(Vec::<SearchHit>::new);
+    let has_more = use_state(|| false);
+    let searching = use_state(|| false);
+    let searched = use_state(|| false);
+    let error = use_state(|| None::<String>);
+
+    // Sessions for the session and host filters
+    {
+        let sessions = sessions.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/sessions");
+                if let Ok(response) = Request::get(&api_endpoint).send().await {
+                    if let Ok(data) = response.json::<serde_json::Value>().await {
+                        if let Some(list) = data.get("sessions") {
+                            if let Ok(parsed) =
+                                serde_json::from_value::<Vec<SessionInfo>>(list.clone())
+                            {
+                                sessions.set(parsed);
+                            }
+                        }
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    // Runs the search; an offset of zero starts over, otherwise appends
+    let run_search = {
+        let form = form.clone();
+        let hits = hits.clone();
+        let has_more = has_more.clone();
+        let searching = searching.clone();
+        let searched = searched.clone();
+        let error = error.clone();
+        Callback::from(move |offset: usize| {
+            if form.q.trim().is_empty() {
+                return;
+            }
+            let params = form.params(offset);
+            let hits = hits.clone();
+            let has_more = has_more.clone();
+            let searching = searching.clone();
+            let searched = searched.clone();
+            let error = error.clone();
+            searching.set(true);
+            spawn_local(async move {
+                match fetch_search(params).await {
+                    Ok(page) => {
+                        let mut all = if offset == 0 {
+                            Vec::new()
+                        } else {
+                            (*hits).clone()
+                        };
+                        all.extend(page.hits);
+                        hits.set(all);
+                        has_more.set(page.has_more);
+                        error.set(None);
+                    }
+                    Err(message) => error.set(Some(message)),
+                }
+                searching.set(false);
+                searched.set(true);
+            });
+        })
+    };
+
+    let on_submit = {
+        let run_search = run_search.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            run_search.emit(0);
+        })
+    };
+
+    let on_load_more = {
+        let run_search = run_search.clone();
+        let count = hits.len();
+        Callback::from(move |_| run_search.emit(count))
+    };
+
+    let on_text = |update: fn(&mut SearchForm, String)| {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            let mut updated = (*form).clone();
+            update(&mut updated, input.value());
+            form.set(updated);
+        })
+    };
+
+    let on_select = |update: fn(&mut SearchForm, String)| {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let mut updated = (*form).clone();
+            update(&mut updated, select.value());
+            form.set(updated);
+        })
+    };
+
+    let go_back = {
+        let on_close = props.on_close.clone();
+        Callback::from(move |_| on_close.emit(()))
+    };
+
+    let mut hostnames: Vec<&str> = sessions.iter().map(|s| s.hostname.as_str()).collect();
+    hostnames.sort_unstable();
+    hostnames.dedup();
+
+    html! {
+        <div class="settings-container">
+            <header class="settings-header">
+                <button class="header-button" onclick={go_back}>
+                    { "< Back" }
+                </button>
+                <h1>{ "Search" }</h1>
+                <span />
+            </header>
+
+            <main class="settings-content">
+                <div class="create-token-form search-form">
+                    <form onsubmit={on_submit}>
+                        <div class="form-group search-terms">
+                            <label for="search-q">{ "Search transcripts" }</label>
+                            <input
+                                type="search"
+                                id="search-q"
+                                placeholder={"e.g., flaky migration test, \"exact phrase\", -excluded"}
+                                value={form.q.clone()}
+                                oninput={on_text(|f, v| f.q = v)}
+                                required=true
+                                autofocus=true
+                            />
+                        </div>
+                        <div class="form-group">
+                            <label for="search-session">{ "Session" }</label>
+                            <select id="search-session" onchange={on_select(|f, v| f.session_id = v)}>
+                                <option value="" selected={form.session_id.is_empty()}>{ "Any" }</option>
+                                { for sessions.iter().map(|s| {
+                                    let id = s.id.to_string();
+                                    html! {
+                                        <option value={id.clone()} selected={form.session_id == id}>
+                                            { format!("{} ({})", utils::extract_folder(&s.working_directory), s.hostname) }
+                                        </option>
+                                    }
+                                }) }
+                            </select>
+                        </div>
+                        <div class="form-group">
+                            <label for="search-host">{ "Host" }</label>
+                            <select id="search-host" onchange={on_select(|f, v| f.hostname = v)}>
+                                <option value="" selected={form.hostname.is_empty()}>{ "Any" }</option>
+                                { for hostnames.iter().map(|h| html! {
+                                    <option value={h.to_string()} selected={form.hostname == *h}>{ *h }</option>
+                                }) }
+                            </select>
+                        </div>
+                        <div class="form-group">
+                            <label for="search-branch">{ "Branch" }</label>
+                            <input
+                                type="text"
+                                id="search-branch"
+                                placeholder="Any"
+                                value={form.branch.clone()}
+                                oninput={on_text(|f, v| f.branch = v)}
+                            />
+                        </div>
+                        <div class="form-group">
+                            <label for="search-agent">{ "Agent" }</label>
+                            <select id="search-agent" onchange={on_select(|f, v| f.agent_type = v)}>
+                                <option value="" selected={form.agent_type.is_empty()}>{ "Any" }</option>
+                                <option value="claude" selected={form.agent_type == "claude"}>{ "Claude" }</option>
+                                <option value="codex" selected={form.agent_type == "codex"}>{ "Codex" }</option>
+                            </select>
+                        </div>
+                        <div class="form-group">
+                            <label for="search-from">{ "From" }</label>
+                            <input
+                                type="date"
+                                id="search-from"
+                                value={form.from.clone()}
+                                oninput={on_text(|f, v| f.from = v)}
+                            />
+                        </div>
+                        <div class="form-group">
+                            <label for="search-to">{ "To" }</label>
+                            <input
+                                type="date"
+                                id="search-to"
+                                value={form.to.clone()}
+                                oninput={on_text(|f, v| f.to = v)}
+                            />
+                        </div>
+                        <button type="submit" class="submit-button" disabled={*searching}>
+                            { if *searching { "Searching..." } else { "Search" } }
+                        </button>
+                    </form>
+                    if let Some(err) = &*error {
+                        <p class="form-error">{ err }</p>
+                    }
+                </div>
+
+                if *searched && hits.is_empty() && error.is_none() {
+                    <div class="empty-state">
+                        <p>{ "No messages match." }</p>
+                    </div>
+                }
+
+                <ul class="search-results">
+                    { for hits.iter().map(|hit| {
+                        let on_open = props.on_open.clone();
+                        let chosen = hit.clone();
+                        let onclick = Callback::from(move |_| on_open.emit(chosen.clone()));
+                        html! {
+                            <li key={hit.message_id.to_string()} class="search-hit" {onclick}>
+                                <div class="search-hit-meta">
+                                    <span class="search-hit-session">
+                                        { utils::extract_folder(&hit.working_directory) }
+                                    </span>
+                                    <span class="search-hit-host">{ &hit.hostname }</span>
+                                    if let Some(branch) = &hit.git_branch {
+                                        <span class="session-branch">{ branch }</span>
+                                    }
+                                    <span class={classes!("search-hit-role", hit.role.clone())}>{ &hit.role }</span>
+                                    <span class="search-hit-time">{ utils::format_timestamp(&hit.created_at) }</span>
+                                </div>
+                                <p class="search-snippet">
+                                    { for split_highlights(&hit.snippet).into_iter().map(|(text, matched)| {
+                                        if matched {
+                                            html! { <mark>{ text }</mark> }
+                                        } else {
+                                            html! { { text } }
+                                        }
+                                    }) }
+                                </p>
+                            </li>
+                        }
+                    }) }
+                </ul>
+
+                if *has_more {
+                    <button class="create-button search-more" onclick={on_load_more} disabled={*searching}>
+                        { "Load more" }
+                    </button>
+                }
+            </main>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_highlights() {
+        assert_eq!(
+            split_highlights("fixed the «flaky» «migration» test"),
+            vec![
+                ("fixed the ".to_string(), false),
+                ("flaky".to_string(), true),
+                (" ".to_string(), false),
+                ("migration".to_string(), true),
+                (" test".to_string(), false),
+            ]
+        );
+        assert_eq!(split_highlights(""), vec![]);
+        assert_eq!(
+            split_highlights("no markers"),
+            vec![("no markers".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn test_params_skip_blank_filters() {
+        let form = SearchForm {
+            q: " flaky test ".to_string(),
+            branch: "main".to_string(),
+            hostname: "  ".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            form.params(0),
+            vec![
+                ("q", "flaky test".to_string()),
+                ("branch", "main".to_string()),
+                ("limit", "20".to_string()),
+            ]
+        );
+        assert!(form.params(40).contains(&("offset", "40".to_string())));
+    }
+}
@@ -0,0 +1,278 @@
+use crate::utils;
+use gloo_net::http::Request;
+use shared::api::{ChangePasswordRequest, LocalAccountInfo, TotpCodeRequest, TotpSetupResponse};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+/// Read a text input into `state`
+fn bind(state: &UseStateHandle<String>) -> Callback<InputEvent> {
+    let state = state.clone();
+    Callback::from(move |e: InputEvent| {
+        let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+        state.set(input.value());
+    })
+}
+
+/// The error text the backend sent, or a generic one
+async fn error_text(response: gloo_net::http::Response, fallback: &str) -> String {
+    let message = response.text().await.unwrap_or_default();
+    if message.is_empty() {
+        fallback.to_string()
+    } else {
+        message
+    }
+}
+
+#[function_component(AccountPanel)]
+pub fn account_panel() -> Html {
+    let account = use_state(|| None::<LocalAccountInfo>);
+    let current_password = use_state(String::new);
+    let new_password = use_state(String::new);
+    let password_feedback = use_state(|| None::<String>);
+    let totp_setup = use_state(|| None::<TotpSetupResponse>);
+    let totp_code = use_state(String::new);
+    let totp_feedback = use_state(|| None::<String>);
+
+    let fetch_account = {
+        let account = account.clone();
+        Callback::from(move |_: ()| {
+            let account = account.clone();
+            spawn_local(async move {
+                let url = utils::api_url("/api/auth/local/account");
+                if let Ok(resp) = Request::get(&url).send().await {
+                    if let Ok(data) = resp.json::<LocalAccountInfo>().await {
+                        account.set(Some(data));
+                    }
+                }
+            });
+        })
+    };
+
+    {
+        let fetch_account = fetch_account.clone();
+        use_effect_with((), move |_| {
+            fetch_account.emit(());
+        });
+    }
+
+    let on_change_password = {
+        let current_password = current_password.clone();
+        let new_password = new_password.clone();
+        let password_feedback = password_feedback.clone();
+        let fetch_account = fetch_account.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let body = ChangePasswordRequest {
+                current_password: (*current_password).clone(),
+                new_password: (*new_password).clone(),
+            };
+            let current_password = current_password.clone();
+            let new_password = new_password.clone();
+            let password_feedback = password_feedback.clone();
+            let fetch_account = fetch_account.clone();
+            spawn_local(async move {
+                let url = utils::api_url("/api/auth/local/password");
+                match Request::post(&url)
+                    .json(&body)
+                    .expect("json body")
+                    .send()
+                    .await
+                {
+                    Ok(resp) if resp.ok() => {
+                        current_password.set(String::new());
+                        new_password.set(String::new());
+                        password_feedback.set(Some("Password changed".to_string()));
+                        fetch_account.emit(());
+                    }
+                    Ok(resp) => password_feedback.set(Some(
+                        error_text(resp, "Failed to change the password").await,
+                    )),
+                    Err(e) => password_feedback.set(Some(format!("{:?}", e))),
+                }
+            });
+        })
+    };
+
+    let on_start_totp = {
+        let totp_setup = totp_setup.clone();
+        let totp_feedback = totp_feedback.clone();
+        Callback::from(move |_: MouseEvent| {
+            let totp_setup = totp_setup.clone();
+            let totp_feedback = totp_feedback.clone();
+            spawn_local(async move {
+                let url = utils::api_url("/api/auth/local/totp/setup");
+                match Request::post(&url).send().await {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(data) = resp.json::<TotpSetupResponse>().await {
+                            totp_setup.set(Some(data));
+                            totp_feedback.set(None);
+                        }
+                    }
+                    Ok(resp) => totp_feedback.set(Some(
+                        error_text(resp, "Failed to set up two-factor authentication").await,
+                    )),
+                    Err(e) => totp_feedback.set(Some(format!("{:?}", e))),
+                }
+            });
+        })
+    };
+
+    // Confirm a code to enable the second factor, or to disable it
+    let on_totp_code = {
+        let account = account.clone();
+        let totp_setup = totp_setup.clone();
+        let totp_code = totp_code.clone();
+        let totp_feedback = totp_feedback.clone();
+        let fetch_account = fetch_account.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let enabled = account.as_ref().is_some_and(|a| a.totp_enabled);
+            let body = TotpCodeRequest {
+                code: (*totp_code).clone(),
+            };
+            let totp_setup = totp_setup.clone();
+            let totp_code = totp_code.clone();
+            let totp_feedback = totp_feedback.clone();
+            let fetch_account = fetch_account.clone();
+            spawn_local(async move {
+                let path = if enabled {
+                    "/api/auth/local/totp/disable"
+                } else {
+                    "/api/auth/local/totp/enable"
+                };
+                let url = utils::api_url(path);
+                match Request::post(&url)
+                    .json(&body)
+                    .expect("json body")
+                    .send()
+                    .await
+                {
+                    Ok(resp) if resp.ok() => {
+                        totp_setup.set(None);
+                        totp_code.set(String::new());
+                        totp_feedback.set(Some(
+                            if enabled {
+                                "Two-factor authentication disabled"
+                            } else {
+                                "Two-factor authentication enabled"
+                            }
+                            .to_string(),
+                        ));
+                        fetch_account.emit(());
+                    }
+                    Ok(resp) => {
+                        totp_feedback.set(Some(error_text(resp, "That code is not valid").await))
+                    }
+                    Err(e) => totp_feedback.set(Some(format!("{:?}", e))),
+                }
+            });
+        })
+    };
+
+    let Some(info) = (*account).clone() else {
+        return html! {
+            <section class="account-section">
+                <div class="loading">
+                    <div class="spinner"></div>
+                    <p>{ "Loading account..." }</p>
+                </div>
+            </section>
+        };
+    };
+
+    if !info.is_local {
+        return html! {
+            <section class="account-section">
+                <div class="section-header">
+                    <h2>{ "Account" }</h2>
+                </div>
+                <div class="empty-state">
+                    <p>{ "You sign in through your identity provider, which manages your password and second factor." }</p>
+                </div>
+            </section>
+        };
+    }
+
+    let code_form = html! {
+        <form class="account-form" onsubmit={on_totp_code}>
+            <label for="account-totp-code">{ "Authenticator code" }</label>
+            <input
+                type="text"
+                id="account-totp-code"
+                inputmode="numeric"
+                autocomplete="one-time-code"
+                value={(*totp_code).clone()}
+                oninput={bind(&totp_code)}
+            />
+            <button type="submit" class="create-button">
+                { if info.totp_enabled { "Disable" } else { "Enable" } }
+            </button>
+        </form>
+    };
+
+    html! {
+        <section class="account-section">
+            <div class="section-header">
+                <h2>{ "Account" }</h2>
+                <p class="section-description">
+                    { format!("Signed in as {} with a local password.", info.username.clone().unwrap_or_default()) }
+                    if let Some(changed) = &info.password_changed_at {
+                        { format!(" Password last changed {}.", utils::format_timestamp(changed)) }
+                    }
+                </p>
+            </div>
+
+            <h3>{ "Change password" }</h3>
+            <form class="account-form" onsubmit={on_change_password}>
+                <label for="account-current-password">{ "Current password" }</label>
+                <input
+                    type="password"
+                    id="account-current-password"
+                    autocomplete="current-password"
+                    value={(*current_password).clone()}
+                    oninput={bind(&current_password)}
+                />
+                <label for="account-new-password">{ "New password (at least 12 characters)" }</label>
+                <input
+                    type="password"
+                    id="account-new-password"
+                    autocomplete="new-password"
+                    minlength="12"
+                    value={(*new_password).clone()}
+                    oninput={bind(&new_password)}
+                />
+                <button type="submit" class="create-button">{ "Change password" }</button>
+                if let Some(message) = &*password_feedback {
+                    <span class="save-feedback">{ message }</span>
+                }
+            </form>
+
+            <h3>{ "Two-factor authentication" }</h3>
+            if info.totp_enabled {
+                <p class="section-description">
+                    { "Enabled. Enter a current code to turn it off." }
+                </p>
+                { code_form }
+            } else if let Some(setup) = (*totp_setup).clone() {
+                <p class="section-description">
+                    { "Add this key to your authenticator app, then enter the code it shows." }
+                </p>
+                <div class="account-totp-secret">
+                    <code>{ &setup.secret }</code>
+                    <code class="account-totp-uri">{ &setup.otpauth_uri }</code>
+                </div>
+                { code_form }
+            } else {
+                <p class="section-description">
+                    { "Ask for a code from an authenticator app each time you sign in." }
+                </p>
+                <button class="create-button" onclick={on_start_totp}>
+                    { "Set up two-factor authentication" }
+                </button>
+            }
+            if let Some(message) = &*totp_feedback {
+                <span class="save-feedback">{ message }</span>
+            }
+        </section>
+    }
+}
@@ -0,0 +1,480 @@
+use crate::utils;
+use gloo_net::http::Request;
+use shared::api::{
+    ApiKeyInfo, ApiKeyListResponse, ApiKeyScope, CreateApiKeyRequest, CreateApiKeyResponse,
+    MAX_API_KEY_DAYS,
+};
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+/// Fetch API keys from API, returning the list
+async fn fetch_api_keys_from_api() -> Option<Vec<ApiKeyInfo>> {
+    let api_endpoint = utils::api_url("/api/api-keys");
+    match Request::get(&api_endpoint).send().await {
+        Ok(response) => {
+            if response.status() == 401 {
+                if let Some(window) = web_sys::window() {
+                    let _ = window.location().set_href("/api/auth/logout");
+                }
+                return None;
+            }
+            response
+                .json::<ApiKeyListResponse>()
+                .await
+                .ok()
+                .map(|data| data.api_keys)
+        }
+        Err(e) => {
+            log::error!("Failed to fetch API keys: {:?}", e);
+            None
+        }
+    }
+}
+
+fn scope_label(scope: ApiKeyScope) -> &'static str {
+    match scope {
+        ApiKeyScope::ReadSessions => "Read sessions",
+        ApiKeyScope::SendInput => "Send input",
+        ApiKeyScope::Launch => "Launch sessions",
+        ApiKeyScope::ManageSchedules => "Manage schedules",
+        ApiKeyScope::Admin => "Admin",
+    }
+}
+
+/// Whether an RFC 3339 timestamp is in the past
+fn is_past(timestamp: &str) -> bool {
+    let parsed = js_sys::Date::parse(timestamp);
+    !parsed.is_nan() && parsed < js_sys::Date::now()
+}
+
+/// Status text for a key, e.g. "Revoked" or "Active"
+fn key_status(key: &ApiKeyInfo, expired: bool) -> &'static str {
+    if key.revoked {
+        "Revoked"
+    } else if expired {
+        "Expired"
+    } else {
+        "Active"
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct ApiKeyRowProps {
+    api_key: ApiKeyInfo,
+    on_revoke: Callback<Uuid>,
+}
+
+#[function_component(ApiKeyRow)]
+fn api_key_row(props: &ApiKeyRowProps) -> Html {
+    let key = &props.api_key;
+    let key_id = key.id;
+    let expired = is_past(&key.expires_at);
+    let usable = !key.revoked && !expired;
+
+    let status_class = if key.revoked {
+        "token-status revoked"
+    } else if expired {
+        "token-status expired"
+    } else {
+        "token-status active"
+    };
+
+    let on_revoke_click = {
+        let on_revoke = props.on_revoke.clone();
+        Callback::from(move |_| on_revoke.emit(key_id))
+    };
+
+    let scopes = key
+        .scopes
+        .iter()
+        .map(|s| s.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    html! {
+        <tr class={if usable { "token-row" } else { "token-row disabled" }}>
+            <td class="token-name">{ &key.name }</td>
+            <td class="api-key-prefix"><code>{ format!("{}…", key.prefix) }</code></td>
+            <td class="api-key-scopes">{ scopes }</td>
+            <td class="token-created">{ utils::format_timestamp(&key.created_at) }</td>
+            <td class="token-last-used">
+                { key.last_used_at.as_ref().map(|t| utils::format_timestamp(t)).unwrap_or_else(|| "Never".to_string()) }
+            </td>
+            <td class="token-expires">{ utils::format_timestamp(&key.expires_at) }</td>
+            <td class={status_class}>{ key_status(key, expired) }</td>
+            <td class="token-actions">
+                if usable {
+                    <button class="revoke-button" onclick={on_revoke_click}>
+                        { "Revoke" }
+                    </button>
+                }
+            </td>
+        </tr>
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct NewApiKeyForm {
+    name: String,
+    scopes: Vec<ApiKeyScope>,
+    expires_in_days: u32,
+}
+
+impl Default for NewApiKeyForm {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            scopes: vec![ApiKeyScope::ReadSessions],
+            expires_in_days: 90,
+        }
+    }
+}
+
+#[function_component(ApiKeysPanel)]
+pub fn api_keys_panel() -> Html {
+    let api_keys = use_state(Vec::<ApiKeyInfo>::new);
+    let loading = use_state(|| true);
+    let form = use_state(NewApiKeyForm::default);
+    let form_error = use_state(|| None::<String>);
+    let created_key = use_state(|| None::<CreateApiKeyResponse>);
+    let show_create_form = use_state(|| false);
+    let confirm_action = use_state(|| None::<(String, Callback<MouseEvent>)>);
+
+    let fetch_api_keys = {
+        let api_keys = api_keys.clone();
+        let loading = loading.clone();
+        Callback::from(move |_| {
+            let api_keys = api_keys.clone();
+            let loading = loading.clone();
+            spawn_local(async move {
+                if let Some(list) = fetch_api_keys_from_api().await {
+                    api_keys.set(list);
+                }
+                loading.set(false);
+            });
+        })
+    };
+
+    // Initial fetch
+    {
+        let fetch_api_keys = fetch_api_keys.clone();
+        use_effect_with((), move |_| {
+            fetch_api_keys.emit(());
+            || ()
+        });
+    }
+
+    let on_revoke = {
+        let api_keys = api_keys.clone();
+        let confirm_action = confirm_action.clone();
+
+        Callback::from(move |key_id: Uuid| {
+            let api_keys = api_keys.clone();
+            let confirm_action_inner = confirm_action.clone();
+
+            let action = Callback::from(move |_: MouseEvent| {
+                let api_keys = api_keys.clone();
+                let confirm_action_inner = confirm_action_inner.clone();
+
+                spawn_local(async move {
+                    let api_endpoint = utils::api_url(&format!("/api/api-keys/{}", key_id));
+                    match Request::delete(&api_endpoint).send().await {
+                        Ok(response) if response.ok() => {
+                            let mut updated: Vec<ApiKeyInfo> = (*api_keys).to_vec();
+                            if let Some(key) = updated.iter_mut().find(|k| k.id == key_id) {
+                                key.revoked = true;
+                            }
+                            api_keys.set(updated);
+                        }
+                        Ok(response) => {
+                            log::error!("Failed to revoke API key: HTTP {}", response.status());
+                        }
+                        Err(e) => {
+                            log::error!("Failed to revoke API key: {:?}", e);
+                        }
+                    }
+                    confirm_action_inner.set(None);
+                });
+            });
+
+            confirm_action.set(Some((
+                "Revoke this API key? Scripts using it will stop working.".to_string(),
+                action,
+            )));
+        })
+    };
+
+    let on_create = {
+        let form = form.clone();
+        let form_error = form_error.clone();
+        let created_key = created_key.clone();
+        let fetch_api_keys = fetch_api_keys.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let data = (*form).clone();
+            if data.name.trim().is_empty() {
+                return;
+            }
+            if data.scopes.is_empty() {
+                form_error.set(Some("Choose at least one scope".to_string()));
+                return;
+            }
+            let form = form.clone();
+            let form_error = form_error.clone();
+            let created_key = created_key.clone();
+            let fetch_api_keys = fetch_api_keys.clone();
+
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/api-keys");
+                let request_body = CreateApiKeyRequest {
+                    name: data.name.trim().to_string(),
+                    scopes: data.scopes,
+                    expires_in_days: data.expires_in_days,
+                };
+                match Request::post(&api_endpoint)
+                    .json(&request_body)
+                    .unwrap()
+                    .send()
+                    .await
+                {
+                    Ok(response) if response.ok() => {
+                        if let Ok(created) = response.json::<CreateApiKeyResponse>().await {
+                            created_key.set(Some(created));
+                        }
+                        form.set(NewApiKeyForm::default());
+                        form_error.set(None);
+                        fetch_api_keys.emit(());
+                    }
+                    Ok(response) => {
+                        let message = response.text().await.unwrap_or_default();
+                        form_error.set(Some(message));
+                    }
+                    Err(e) => {
+                        log::error!("Failed to create API key: {:?}", e);
+                    }
+                }
+            });
+        })
+    };
+
+    let on_name_input = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            let mut updated = (*form).clone();
+            updated.name = input.value();
+            form.set(updated);
+        })
+    };
+
+    let on_days_input = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            let mut updated = (*form).clone();
+            updated.expires_in_days = input.value().parse().unwrap_or(90);
+            form.set(updated);
+        })
+    };
+
+    let toggle_scope = |scope: ApiKeyScope| {
+        let form = form.clone();
+        Callback::from(move |_: Event| {
+            let mut updated = (*form).clone();
+            if updated.scopes.contains(&scope) {
+                updated.scopes.retain(|s| *s != scope);
+            } else {
+                updated.scopes.push(scope);
+            }
+            form.set(updated);
+        })
+    };
+
+    let toggle_create_form = {
+        let show_create_form = show_create_form.clone();
+        let form_error = form_error.clone();
+        let created_key = created_key.clone();
+        Callback::from(move |_| {
+            form_error.set(None);
+            created_key.set(None);
+            show_create_form.set(!*show_create_form);
+        })
+    };
+
+    let cancel_confirm = {
+        let confirm_action = confirm_action.clone();
+        Callback::from(move |_| confirm_action.set(None))
+    };
+
+    html! {
+        <>
+            <section class="tokens-section">
+                <div class="section-header">
+                    <h2>{ "API Keys" }</h2>
+                    <p class="section-description">
+                        { "Personal keys for scripts and CI. Send one as " }
+                        <code>{ "Authorization: Bearer cpk_..." }</code>
+                        { "; it can only call the endpoints its scopes allow." }
+                    </p>
+                    <button class="create-button" onclick={toggle_create_form.clone()}>
+                        { if *show_create_form { "Cancel" } else { "+ Create API Key" } }
+                    </button>
+                </div>
+
+                if *show_create_form {
+                    <div class="create-token-form">
+                        if let Some(created) = &*created_key {
+                            <div class="token-created-success">
+                                <h3>{ "API Key Created" }</h3>
+                                <p class="warning">
+                                    { "Copy this key now. It will not be shown again!" }
+                                </p>
+                                <div class="token-display">
+                                    <code>{ &created.key }</code>
+                                </div>
+                                <p class="expires-info">
+                                    { format!("Expires: {}", utils::format_timestamp(&created.api_key.expires_at)) }
+                                </p>
+                                <button onclick={toggle_create_form.clone()}>{ "Done" }</button>
+                            </div>
+                        } else {
+                            <form onsubmit={on_create}>
+                                <div class="form-group">
+                                    <label for="api-key-name">{ "Key Name" }</label>
+                                    <input
+                                        type="text"
+                                        id="api-key-name"
+                                        placeholder="e.g., GitHub Actions"
+                                        value={form.name.clone()}
+                                        oninput={on_name_input}
+                                        required=true
+                                    />
+                                </div>
+                                <div class="form-group">
+                                    <label>{ "Scopes" }</label>
+                                    <div class="webhook-event-options">
+                                        { for ApiKeyScope::ALL.iter().map(|scope| html! {
+                                            <label class="webhook-event-option">
+                                                <input
+                                                    type="checkbox"
+                                                    checked={form.scopes.contains(scope)}
+                                                    onchange={toggle_scope(*scope)}
+                                                />
+                                                { scope_label(*scope) }
+                                                <code>{ scope.as_str() }</code>
+                                            </label>
+                                        }) }
+                                    </div>
+                                </div>
+                                <div class="form-group">
+                                    <label for="api-key-days">{ "Expires In (days)" }</label>
+                                    <input
+                                        type="number"
+                                        id="api-key-days"
+                                        min="1"
+                                        max={MAX_API_KEY_DAYS.to_string()}
+                                        value={form.expires_in_days.to_string()}
+                                        oninput={on_days_input}
+                                    />
+                                </div>
+                                <button type="submit" class="submit-button">
+                                    { "Create API Key" }
+                                </button>
+                            </form>
+                            if let Some(err) = &*form_error {
+                                <p class="form-error">{ err }</p>
+                            }
+                        }
+                    </div>
+                }
+
+                if *loading {
+                    <div class="loading">
+                        <div class="spinner"></div>
+                        <p>{ "Loading API keys..." }</p>
+                    </div>
+                } else if api_keys.is_empty() {
+                    <div class="empty-state">
+                        <p>{ "No API keys yet." }</p>
+                    </div>
+                } else {
+                    <div class="table-container">
+                        <table class="tokens-table">
+                            <thead>
+                                <tr>
+                                    <th>{ "Name" }</th>
+                                    <th>{ "Key" }</th>
+                                    <th>{ "Scopes" }</th>
+                                    <th>{ "Created" }</th>
+                                    <th>{ "Last Used" }</th>
+                                    <th>{ "Expires" }</th>
+                                    <th>{ "Status" }</th>
+                                    <th>{ "Actions" }</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                { for api_keys.iter().map(|key| html! {
+                                    <ApiKeyRow
+                                        key={key.id.to_string()}
+                                        api_key={key.clone()}
+                                        on_revoke={on_revoke.clone()}
+                                    />
+                                }) }
+                            </tbody>
+                        </table>
+                    </div>
+                }
+            </section>
+
+            if let Some((message, action)) = &*confirm_action {
+                <div class="modal-overlay" onclick={cancel_confirm.clone()}>
+                    <div class="confirm-modal" onclick={Callback::from(|e: MouseEvent| e.stop_propagation())}>
+                        <p>{ message }</p>
+                        <div class="confirm-actions">
+                            <button class="cancel-button" onclick={cancel_confirm.clone()}>
+                                { "Cancel" }
+                            </button>
+                            <button class="confirm-button" onclick={action.clone()}>
+                                { "Confirm" }
+                            </button>
+                        </div>
+                    </div>
+                </div>
+            }
+        </>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_key(revoked: bool) -> ApiKeyInfo {
+        ApiKeyInfo {
+            id: Uuid::nil(),
+            name: "ci".to_string(),
+            prefix: "cpk_0123abcd".to_string(),
+            scopes: vec![ApiKeyScope::ReadSessions],
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            last_used_at: None,
+            expires_at: "2026-04-01T00:00:00Z".to_string(),
+            revoked,
+        }
+    }
+
+    #[test]
+    fn test_key_status() {
+        assert_eq!(key_status(&api_key(false), false), "Active");
+        assert_eq!(key_status(&api_key(false), true), "Expired");
+        // Revocation wins over expiry
+        assert_eq!(key_status(&api_key(true), true), "Revoked");
+    }
+
+    #[test]
+    fn test_every_scope_has_a_label() {
+        for scope in ApiKeyScope::ALL {
+            assert!(!scope_label(scope).is_empty());
+        }
+    }
+}
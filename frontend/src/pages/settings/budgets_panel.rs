@@ -0,0 +1,88 @@
+use crate::utils;
+use gloo_net::http::Request;
+use shared::api::{BudgetPeriod, SpendBudgetInfo, SpendBudgetsResponse};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+fn format_limit(usd: Option<f64>) -> String {
+    usd.map(utils::format_dollars)
+        .unwrap_or_else(|| "-".to_string())
+}
+
+#[function_component(BudgetsPanel)]
+pub fn budgets_panel() -> Html {
+    let budgets = use_state(|| None::<Vec<SpendBudgetInfo>>);
+
+    // Fetch from API on mount
+    {
+        let budgets = budgets.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                let url = utils::api_url("/api/budgets");
+                if let Ok(resp) = Request::get(&url).send().await {
+                    if let Ok(data) = resp.json::<SpendBudgetsResponse>().await {
+                        budgets.set(Some(data.budgets));
+                    }
+                }
+            });
+        });
+    }
+
+    html! {
+        <section class="tokens-section">
+            <div class="section-header">
+                <h2>{ "Spend Budgets" }</h2>
+                <p class="section-description">
+                    { "Limits an admin has set on your spend and your teams' spend. Past the soft limit you are notified; at the hard limit sessions are interrupted and new input and launches are refused until the limit is raised or the period resets (UTC)." }
+                </p>
+            </div>
+            {
+                match &*budgets {
+                    None => html! {
+                        <div class="loading">
+                            <div class="spinner"></div>
+                            <p>{ "Loading budgets..." }</p>
+                        </div>
+                    },
+                    Some(list) if list.is_empty() => html! {
+                        <div class="empty-state">
+                            <p>{ "No budgets apply to you." }</p>
+                        </div>
+                    },
+                    Some(list) => html! {
+                        <div class="table-container">
+                            <table class="tokens-table budgets-table">
+                                <thead>
+                                    <tr>
+                                        <th>{ "Budget" }</th>
+                                        <th>{ "Period" }</th>
+                                        <th>{ "Spent" }</th>
+                                        <th>{ "Soft Limit" }</th>
+                                        <th>{ "Hard Limit" }</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    { for list.iter().map(|budget| html! {
+                                        <tr key={budget.id.to_string()} class={classes!(budget.blocked.then_some("blocked"))}>
+                                            <td>
+                                                if budget.team_id.is_some() {
+                                                    { format!("Team {}", budget.target_name) }
+                                                } else {
+                                                    { "You" }
+                                                }
+                                            </td>
+                                            <td>{ if budget.period == BudgetPeriod::Daily { "Daily" } else { "Monthly" } }</td>
+                                            <td class="numeric">{ utils::format_dollars(budget.spent_usd) }</td>
+                                            <td class="numeric">{ format_limit(budget.soft_limit_usd) }</td>
+                                            <td class="numeric">{ format_limit(budget.hard_limit_usd) }</td>
+                                        </tr>
+                                    }) }
+                                </tbody>
+                            </table>
+                        </div>
+                    },
+                }
+            }
+        </section>
+    }
+}
@@ -0,0 +1,639 @@
+use crate::utils;
+use gloo_net::http::Request;
+use shared::api::{
+    ChatBridgeInfo, ChatBridgeListResponse, CreateChatBridgeRequest, CreateChatBridgeResponse,
+    UpdateChatBridgeRequest,
+};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+/// Fetch chat bridges from API, returning the list
+async fn fetch_bridges_from_api() -> Option<Vec<ChatBridgeInfo>> {
+    let api_endpoint = utils::api_url("/api/chat-bridges");
+    match Request::get(&api_endpoint).send().await {
+        Ok(response) => {
+            if response.status() == 401 {
+                if let Some(window) = web_sys::window() {
+                    let _ = window.location().set_href("/api/auth/logout");
+                }
+                return None;
+            }
+            response
+                .json::<ChatBridgeListResponse>()
+                .await
+                .ok()
+                .map(|data| data.bridges)
+        }
+        Err(e) => {
+            log::error!("Failed to fetch chat bridges: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Parse "chat-user-id = email" lines into a user map. Blank lines are skipped.
+fn parse_user_map(text: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut map = BTreeMap::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        match line.split_once('=') {
+            Some((user, email)) if !user.trim().is_empty() && email.contains('@') => {
+                map.insert(user.trim().to_string(), email.trim().to_string());
+            }
+            _ => {
+                return Err(format!(
+                    "Expected \"chat-user-id = email\", got \"{}\"",
+                    line
+                ))
+            }
+        }
+    }
+    Ok(map)
+}
+
+fn format_user_map(map: &BTreeMap<String, String>) -> String {
+    map.iter()
+        .map(|(user, email)| format!("{} = {}", user, email))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Replace one bridge in the list with its updated version
+fn replace_bridge(list: &[ChatBridgeInfo], updated: ChatBridgeInfo) -> Vec<ChatBridgeInfo> {
+    list.iter()
+        .map(|b| {
+            if b.id == updated.id {
+                updated.clone()
+            } else {
+                b.clone()
+            }
+        })
+        .collect()
+}
+
+#[derive(Properties, PartialEq)]
+struct BridgeSetupProps {
+    bridge: ChatBridgeInfo,
+    on_saved: Callback<ChatBridgeInfo>,
+}
+
+/// Inbound URL and user mapping editor for one bridge
+#[function_component(BridgeSetup)]
+fn bridge_setup(props: &BridgeSetupProps) -> Html {
+    let text = use_state(|| format_user_map(&props.bridge.user_map));
+    let feedback = use_state(|| None::<String>);
+
+    let on_input = {
+        let text = text.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlTextAreaElement = e.target_unchecked_into();
+            text.set(input.value());
+        })
+    };
+
+    let on_save = {
+        let text = text.clone();
+        let feedback = feedback.clone();
+        let on_saved = props.on_saved.clone();
+        let bridge_id = props.bridge.id;
+        Callback::from(move |_: MouseEvent| {
+            let user_map = match parse_user_map(&text) {
+                Ok(map) => map,
+                Err(message) => {
+                    feedback.set(Some(message));
+                    return;
+                }
+            };
+            let feedback = feedback.clone();
+            let on_saved = on_saved.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url(&format!("/api/chat-bridges/{}", bridge_id));
+                let body = UpdateChatBridgeRequest {
+                    user_map: Some(user_map),
+                    ..Default::default()
+                };
+                match Request::patch(&api_endpoint)
+                    .json(&body)
+                    .unwrap()
+                    .send()
+                    .await
+                {
+                    Ok(response) if response.ok() => {
+                        if let Ok(updated) = response.json::<ChatBridgeInfo>().await {
+                            on_saved.emit(updated);
+                        }
+                        feedback.set(Some("Saved!".to_string()));
+                    }
+                    Ok(response) => {
+                        feedback.set(Some(response.text().await.unwrap_or_default()));
+                    }
+                    Err(e) => {
+                        log::error!("Failed to update chat bridge: {:?}", e);
+                    }
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="chat-bridge-setup">
+            <h3>{ format!("Set up {}", props.bridge.name) }</h3>
+            <p>
+                { "Point your chat tool's outgoing webhook or slash command at this URL, signed with the bridge's signing secret:" }
+            </p>
+            <div class="token-display">
+                <code>{ &props.bridge.inbound_url }</code>
+            </div>
+            <div class="form-group">
+                <label for="chat-user-map">{ "Chat users" }</label>
+                <textarea
+                    id="chat-user-map"
+                    rows="4"
+                    placeholder="U04ABCDEF = alice@example.com"
+                    value={(*text).clone()}
+                    oninput={on_input}
+                />
+                <p class="expires-info">
+                    { "One \"chat-user-id = portal email\" per line. Commands from unmapped users are refused." }
+                </p>
+            </div>
+            <div class="chat-bridge-setup-actions">
+                if let Some(message) = &*feedback {
+                    <span class="save-feedback">{ message }</span>
+                }
+                <button class="create-button" onclick={on_save}>{ "Save users" }</button>
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct BridgeRowProps {
+    bridge: ChatBridgeInfo,
+    selected: bool,
+    test_result: Option<String>,
+    on_select: Callback<Uuid>,
+    on_toggle: Callback<(Uuid, bool)>,
+    on_test: Callback<Uuid>,
+    on_delete: Callback<Uuid>,
+}
+
+#[function_component(BridgeRow)]
+fn bridge_row(props: &BridgeRowProps) -> Html {
+    let bridge = &props.bridge;
+    let bridge_id = bridge.id;
+    let enabled = bridge.enabled;
+
+    let on_select = props.on_select.clone();
+    let on_select_click = Callback::from(move |_| on_select.emit(bridge_id));
+    let on_toggle = props.on_toggle.clone();
+    let on_toggle_click = Callback::from(move |_| on_toggle.emit((bridge_id, !enabled)));
+    let on_test = props.on_test.clone();
+    let on_test_click = Callback::from(move |_| on_test.emit(bridge_id));
+    let on_delete = props.on_delete.clone();
+    let on_delete_click = Callback::from(move |_| on_delete.emit(bridge_id));
+
+    let posts: Vec<&str> = [
+        bridge.post_permissions.then_some("Permission requests"),
+        bridge.post_results.then_some("Turn results"),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    html! {
+        <tr class={classes!("webhook-row", props.selected.then_some("selected"), (!enabled).then_some("disabled"))}>
+            <td>{ &bridge.name }</td>
+            <td class="webhook-events">
+                if posts.is_empty() {
+                    <div>{ "Nothing" }</div>
+                }
+                { for posts.iter().map(|p| html! { <div>{ *p }</div> }) }
+            </td>
+            <td>{ bridge.user_map.len() }</td>
+            <td>
+                { if enabled { "Enabled" } else { "Paused" } }
+                if let Some(result) = &props.test_result {
+                    <div class="chat-bridge-test-result">{ result }</div>
+                }
+            </td>
+            <td class="rule-actions">
+                <button class="renew-button" onclick={on_select_click}>
+                    { if props.selected { "Hide setup" } else { "Setup" } }
+                </button>
+                if enabled {
+                    <button class="renew-button" onclick={on_test_click}>{ "Test" }</button>
+                }
+                <button class="renew-button" onclick={on_toggle_click}>
+                    { if enabled { "Pause" } else { "Resume" } }
+                </button>
+                <button class="delete-button" onclick={on_delete_click}>{ "Delete" }</button>
+            </td>
+        </tr>
+    }
+}
+
+#[derive(Clone)]
+struct NewBridgeForm {
+    name: String,
+    url: String,
+    signing_secret: String,
+    post_permissions: bool,
+    post_results: bool,
+}
+
+impl Default for NewBridgeForm {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            url: String::new(),
+            signing_secret: String::new(),
+            post_permissions: true,
+            post_results: true,
+        }
+    }
+}
+
+#[function_component(ChatPanel)]
+pub fn chat_panel() -> Html {
+    let bridges = use_state(Vec::<ChatBridgeInfo>::new);
+    let bridges_loading = use_state(|| true);
+    let form = use_state(NewBridgeForm::default);
+    let show_create_form = use_state(|| false);
+    let form_error = use_state(|| None::<String>);
+    let created = use_state(|| None::<CreateChatBridgeResponse>);
+    let selected = use_state(|| None::<Uuid>);
+    let test_result = use_state(|| None::<(Uuid, String)>);
+
+    let fetch_bridges = {
+        let bridges = bridges.clone();
+        let bridges_loading = bridges_loading.clone();
+        Callback::from(move |_| {
+            let bridges = bridges.clone();
+            let bridges_loading = bridges_loading.clone();
+            spawn_local(async move {
+                if let Some(list) = fetch_bridges_from_api().await {
+                    bridges.set(list);
+                }
+                bridges_loading.set(false);
+            });
+        })
+    };
+
+    // Initial fetch
+    {
+        let fetch_bridges = fetch_bridges.clone();
+        use_effect_with((), move |_| {
+            fetch_bridges.emit(());
+            || ()
+        });
+    }
+
+    let on_delete = {
+        let bridges = bridges.clone();
+        let selected = selected.clone();
+        Callback::from(move |bridge_id: Uuid| {
+            let bridges = bridges.clone();
+            let selected = selected.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url(&format!("/api/chat-bridges/{}", bridge_id));
+                match Request::delete(&api_endpoint).send().await {
+                    Ok(response) if response.ok() => {
+                        let updated: Vec<ChatBridgeInfo> = bridges
+                            .iter()
+                            .filter(|b| b.id != bridge_id)
+                            .cloned()
+                            .collect();
+                        bridges.set(updated);
+                        if *selected == Some(bridge_id) {
+                            selected.set(None);
+                        }
+                    }
+                    Ok(response) => {
+                        log::error!("Failed to delete chat bridge: HTTP {}", response.status());
+                    }
+                    Err(e) => {
+                        log::error!("Failed to delete chat bridge: {:?}", e);
+                    }
+                }
+            });
+        })
+    };
+
+    let on_saved = {
+        let bridges = bridges.clone();
+        Callback::from(move |updated: ChatBridgeInfo| {
+            bridges.set(replace_bridge(&bridges, updated));
+        })
+    };
+
+    let on_toggle = {
+        let on_saved = on_saved.clone();
+        Callback::from(move |(bridge_id, enabled): (Uuid, bool)| {
+            let on_saved = on_saved.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url(&format!("/api/chat-bridges/{}", bridge_id));
+                let body = UpdateChatBridgeRequest {
+                    enabled: Some(enabled),
+                    ..Default::default()
+                };
+                match Request::patch(&api_endpoint)
+                    .json(&body)
+                    .unwrap()
+                    .send()
+                    .await
+                {
+                    Ok(response) if response.ok() => {
+                        if let Ok(updated) = response.json::<ChatBridgeInfo>().await {
+                            on_saved.emit(updated);
+                        }
+                    }
+                    Ok(response) => {
+                        log::error!("Failed to update chat bridge: HTTP {}", response.status());
+                    }
+                    Err(e) => {
+                        log::error!("Failed to update chat bridge: {:?}", e);
+                    }
+                }
+            });
+        })
+    };
+
+    let on_test = {
+        let test_result = test_result.clone();
+        Callback::from(move |bridge_id: Uuid| {
+            let test_result = test_result.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url(&format!("/api/chat-bridges/{}/test", bridge_id));
+                let message = match Request::post(&api_endpoint).send().await {
+                    Ok(response) if response.ok() => "Test message posted".to_string(),
+                    Ok(response) => format!("Test failed: HTTP {}", response.status()),
+                    Err(e) => format!("Test failed: {:?}", e),
+                };
+                test_result.set(Some((bridge_id, message)));
+            });
+        })
+    };
+
+    let on_select = {
+        let selected = selected.clone();
+        Callback::from(move |bridge_id: Uuid| {
+            if *selected == Some(bridge_id) {
+                selected.set(None);
+            } else {
+                selected.set(Some(bridge_id));
+            }
+        })
+    };
+
+    let on_create_bridge = {
+        let form = form.clone();
+        let form_error = form_error.clone();
+        let created = created.clone();
+        let fetch_bridges = fetch_bridges.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let data = (*form).clone();
+            if data.name.trim().is_empty() || data.url.trim().is_empty() {
+                return;
+            }
+            let form = form.clone();
+            let form_error = form_error.clone();
+            let created = created.clone();
+            let fetch_bridges = fetch_bridges.clone();
+
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/chat-bridges");
+                let secret = data.signing_secret.trim();
+                let request_body = CreateChatBridgeRequest {
+                    name: data.name.trim().to_string(),
+                    incoming_webhook_url: data.url.trim().to_string(),
+                    signing_secret: (!secret.is_empty()).then(|| secret.to_string()),
+                    post_permissions: data.post_permissions,
+                    post_results: data.post_results,
+                    user_map: BTreeMap::new(),
+                };
+                match Request::post(&api_endpoint)
+                    .json(&request_body)
+                    .unwrap()
+                    .send()
+                    .await
+                {
+                    Ok(response) if response.ok() => {
+                        if let Ok(response) = response.json::<CreateChatBridgeResponse>().await {
+                            created.set(Some(response));
+                        }
+                        form.set(NewBridgeForm::default());
+                        form_error.set(None);
+                        fetch_bridges.emit(());
+                    }
+                    Ok(response) => {
+                        let message = response.text().await.unwrap_or_default();
+                        form_error.set(Some(message));
+                    }
+                    Err(e) => {
+                        log::error!("Failed to create chat bridge: {:?}", e);
+                    }
+                }
+            });
+        })
+    };
+
+    let update_text = |apply: fn(&mut NewBridgeForm, String)| {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            let mut updated = (*form).clone();
+            apply(&mut updated, input.value());
+            form.set(updated);
+        })
+    };
+    let on_name_input = update_text(|f, v| f.name = v);
+    let on_url_input = update_text(|f, v| f.url = v);
+    let on_secret_input = update_text(|f, v| f.signing_secret = v);
+
+    let update_flag = |apply: fn(&mut NewBridgeForm, bool)| {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            let mut updated = (*form).clone();
+            apply(&mut updated, input.checked());
+            form.set(updated);
+        })
+    };
+    let on_permissions_toggle = update_flag(|f, v| f.post_permissions = v);
+    let on_results_toggle = update_flag(|f, v| f.post_results = v);
+
+    let toggle_create_form = {
+        let show_create_form = show_create_form.clone();
+        let form_error = form_error.clone();
+        let created = created.clone();
+        Callback::from(move |_| {
+            form_error.set(None);
+            created.set(None);
+            show_create_form.set(!*show_create_form);
+        })
+    };
+
+    let selected_bridge = selected.and_then(|id| bridges.iter().find(|b| b.id == id).cloned());
+
+    html! {
+        <section class="tokens-section webhooks-section">
+            <div class="section-header">
+                <h2>{ "Chat" }</h2>
+                <p class="section-description">
+                    { "Post permission requests and turn results to a team chat channel, and answer them \
+                       from the channel with \"approve <ref>\", \"deny <ref> <reason>\" or \
+                       \"send <session> <text>\"." }
+                </p>
+                <button class="create-button" onclick={toggle_create_form.clone()}>
+                    { if *show_create_form { "Cancel" } else { "+ Add Channel" } }
+                </button>
+            </div>
+
+            if *show_create_form {
+                <div class="create-token-form">
+                    if let Some(response) = &*created {
+                        <div class="token-created-success">
+                            <h3>{ "Channel Connected" }</h3>
+                            <p class="warning">
+                                { "Copy this signing secret now. It will not be shown again!" }
+                            </p>
+                            <div class="token-display">
+                                <code>{ &response.signing_secret }</code>
+                            </div>
+                            <p class="expires-info">
+                                { "Send commands to the URL below, signed Slack-style (X-Slack-Signature) or like portal webhooks (X-Portal-Signature). Map your chat users under Setup." }
+                            </p>
+                            <div class="token-display">
+                                <code>{ &response.bridge.inbound_url }</code>
+                            </div>
+                            <button onclick={toggle_create_form.clone()}>{ "Done" }</button>
+                        </div>
+                    } else {
+                        <form onsubmit={on_create_bridge}>
+                            <div class="form-group">
+                                <label for="chat-name">{ "Name" }</label>
+                                <input
+                                    type="text"
+                                    id="chat-name"
+                                    placeholder="e.g., #agents"
+                                    value={form.name.clone()}
+                                    oninput={on_name_input}
+                                    required=true
+                                />
+                            </div>
+                            <div class="form-group">
+                                <label for="chat-url">{ "Incoming webhook URL" }</label>
+                                <input
+                                    type="url"
+                                    id="chat-url"
+                                    placeholder="https://hooks.slack.com/services/..."
+                                    value={form.url.clone()}
+                                    oninput={on_url_input}
+                                    required=true
+                                />
+                            </div>
+                            <div class="form-group">
+                                <label for="chat-secret">{ "Signing secret" }</label>
+                                <input
+                                    type="password"
+                                    id="chat-secret"
+                                    placeholder="Your chat app's signing secret (leave empty to generate one)"
+                                    value={form.signing_secret.clone()}
+                                    oninput={on_secret_input}
+                                />
+                            </div>
+                            <div class="form-group">
+                                <label>{ "Post" }</label>
+                                <div class="webhook-event-options">
+                                    <label class="webhook-event-option">
+                                        <input
+                                            type="checkbox"
+                                            checked={form.post_permissions}
+                                            onchange={on_permissions_toggle}
+                                        />
+                                        { "Permission requests" }
+                                    </label>
+                                    <label class="webhook-event-option">
+                                        <input
+                                            type="checkbox"
+                                            checked={form.post_results}
+                                            onchange={on_results_toggle}
+                                        />
+                                        { "Turn results" }
+                                    </label>
+                                </div>
+                            </div>
+                            <button type="submit" class="submit-button">{ "Add Channel" }</button>
+                        </form>
+                        if let Some(err) = &*form_error {
+                            <p class="form-error">{ err }</p>
+                        }
+                    }
+                </div>
+            }
+
+            if *bridges_loading {
+                <div class="loading">
+                    <div class="spinner"></div>
+                    <p>{ "Loading chat channels..." }</p>
+                </div>
+            } else if bridges.is_empty() {
+                <div class="empty-state">
+                    <p>{ "No chat channels connected yet." }</p>
+                </div>
+            } else {
+                <div class="table-container">
+                    <table class="tokens-table webhooks-table">
+                        <thead>
+                            <tr>
+                                <th>{ "Name" }</th>
+                                <th>{ "Posts" }</th>
+                                <th>{ "Users" }</th>
+                                <th>{ "Status" }</th>
+                                <th>{ "Actions" }</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for bridges.iter().map(|bridge| html! {
+                                <BridgeRow
+                                    key={bridge.id.to_string()}
+                                    bridge={bridge.clone()}
+                                    selected={*selected == Some(bridge.id)}
+                                    test_result={test_result.as_ref().filter(|(id, _)| *id == bridge.id).map(|(_, r)| r.clone())}
+                                    on_select={on_select.clone()}
+                                    on_toggle={on_toggle.clone()}
+                                    on_test={on_test.clone()}
+                                    on_delete={on_delete.clone()}
+                                />
+                            }) }
+                        </tbody>
+                    </table>
+                </div>
+                if let Some(bridge) = selected_bridge {
+                    <BridgeSetup key={bridge.id.to_string()} bridge={bridge.clone()} on_saved={on_saved.clone()} />
+                }
+            }
+        </section>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_user_map() {
+        let map = parse_user_map("U1 = a@example.com\n\n  U2=b@example.com  ").unwrap();
+        assert_eq!(map.get("U1").map(String::as_str), Some("a@example.com"));
+        assert_eq!(map.get("U2").map(String::as_str), Some("b@example.com"));
+        assert_eq!(
+            format_user_map(&map),
+            "U1 = a@example.com\nU2 = b@example.com"
+        );
+        assert!(parse_user_map("U1 a@example.com").is_err());
+        assert!(parse_user_map("= a@example.com").is_err());
+    }
+}
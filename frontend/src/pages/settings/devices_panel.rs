@@ -0,0 +1,243 @@
+use crate::utils;
+use gloo_net::http::Request;
+use shared::api::{WebLoginInfo, WebLoginsResponse};
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+/// Fetch the signed-in browsers from API, returning the list
+async fn fetch_logins_from_api() -> Option<Vec<WebLoginInfo>> {
+    let api_endpoint = utils::api_url("/api/logins");
+    match Request::get(&api_endpoint).send().await {
+        Ok(response) => {
+            if response.status() == 401 {
+                if let Some(window) = web_sys::window() {
+                    let _ = window.location().set_href("/api/auth/logout");
+                }
+                return None;
+            }
+            response
+                .json::<WebLoginsResponse>()
+                .await
+                .ok()
+                .map(|data| data.logins)
+        }
+        Err(e) => {
+            log::error!("Failed to fetch signed-in devices: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Back to the splash page once this browser is signed out
+fn leave() {
+    if let Some(window) = web_sys::window() {
+        let _ = window.location().set_href("/");
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct LoginRowProps {
+    login: WebLoginInfo,
+    on_revoke: Callback<Uuid>,
+}
+
+#[function_component(LoginRow)]
+fn login_row(props: &LoginRowProps) -> Html {
+    let login = &props.login;
+    let on_revoke_click = {
+        let on_revoke = props.on_revoke.clone();
+        let login_id = login.id;
+        Callback::from(move |_: MouseEvent| on_revoke.emit(login_id))
+    };
+
+    html! {
+        <tr class="token-row">
+            <td class="token-name" title={login.user_agent.clone().unwrap_or_default()}>
+                { &login.device }
+                if login.current {
+                    <span class="device-current">{ "This browser" }</span>
+                }
+            </td>
+            <td class="device-ip">{ login.ip_address.clone().unwrap_or_else(|| "-".to_string()) }</td>
+            <td class="token-created">{ utils::format_timestamp(&login.created_at) }</td>
+            <td class="token-last-used">{ utils::format_timestamp(&login.last_seen_at) }</td>
+            <td class="token-actions">
+                <button class="revoke-button" onclick={on_revoke_click}>
+                    { "Sign out" }
+                </button>
+            </td>
+        </tr>
+    }
+}
+
+#[function_component(DevicesPanel)]
+pub fn devices_panel() -> Html {
+    let logins = use_state(Vec::<WebLoginInfo>::new);
+    let loading = use_state(|| true);
+    let confirm_action = use_state(|| None::<(String, Callback<MouseEvent>)>);
+
+    {
+        let logins = logins.clone();
+        let loading = loading.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                if let Some(list) = fetch_logins_from_api().await {
+                    logins.set(list);
+                }
+                loading.set(false);
+            });
+            || ()
+        });
+    }
+
+    let on_revoke = {
+        let logins = logins.clone();
+        let confirm_action = confirm_action.clone();
+
+        Callback::from(move |login_id: Uuid| {
+            let current = logins.iter().any(|l| l.id == login_id && l.current);
+            let logins = logins.clone();
+            let confirm_action_inner = confirm_action.clone();
+
+            let action = Callback::from(move |_: MouseEvent| {
+                let logins = logins.clone();
+                let confirm_action_inner = confirm_action_inner.clone();
+
+                spawn_local(async move {
+                    let api_endpoint = utils::api_url(&format!("/api/logins/{}", login_id));
+                    match Request::delete(&api_endpoint).send().await {
+                        Ok(response) if response.ok() => {
+                            if current {
+                                leave();
+                                return;
+                            }
+                            let updated: Vec<WebLoginInfo> = logins
+                                .iter()
+                                .filter(|l| l.id != login_id)
+                                .cloned()
+                                .collect();
+                            logins.set(updated);
+                        }
+                        Ok(response) => {
+                            log::error!("Failed to sign out device: HTTP {}", response.status());
+                        }
+                        Err(e) => {
+                            log::error!("Failed to sign out device: {:?}", e);
+                        }
+                    }
+                    confirm_action_inner.set(None);
+                });
+            });
+
+            let message = if current {
+                "Sign this browser out?"
+            } else {
+                "Sign this device out? Its open tabs are disconnected right away."
+            };
+            confirm_action.set(Some((message.to_string(), action)));
+        })
+    };
+
+    let on_revoke_all = {
+        let confirm_action = confirm_action.clone();
+        Callback::from(move |_: MouseEvent| {
+            let confirm_action_inner = confirm_action.clone();
+            let action = Callback::from(move |_: MouseEvent| {
+                let confirm_action_inner = confirm_action_inner.clone();
+                spawn_local(async move {
+                    let api_endpoint = utils::api_url("/api/logins/revoke-all");
+                    match Request::post(&api_endpoint).send().await {
+                        Ok(response) if response.ok() => leave(),
+                        Ok(response) => {
+                            log::error!(
+                                "Failed to sign out everywhere: HTTP {}",
+                                response.status()
+                            );
+                            confirm_action_inner.set(None);
+                        }
+                        Err(e) => {
+                            log::error!("Failed to sign out everywhere: {:?}", e);
+                            confirm_action_inner.set(None);
+                        }
+                    }
+                });
+            });
+            confirm_action.set(Some((
+                "Sign out every browser, including this one?".to_string(),
+                action,
+            )));
+        })
+    };
+
+    let cancel_confirm = {
+        let confirm_action = confirm_action.clone();
+        Callback::from(move |_| confirm_action.set(None))
+    };
+
+    html! {
+        <>
+            <section class="tokens-section">
+                <div class="section-header">
+                    <h2>{ "Signed-in Devices" }</h2>
+                    <p class="section-description">
+                        { "Browsers signed in to your account. Sign out any you don't recognize or no longer have; their open tabs are disconnected at once. Browsers unused for 90 days are signed out automatically." }
+                    </p>
+                    <button class="create-button" onclick={on_revoke_all}>
+                        { "Sign out everywhere" }
+                    </button>
+                </div>
+
+                if *loading {
+                    <div class="loading">
+                        <div class="spinner"></div>
+                        <p>{ "Loading signed-in devices..." }</p>
+                    </div>
+                } else if logins.is_empty() {
+                    <div class="empty-state">
+                        <p>{ "No signed-in browsers." }</p>
+                    </div>
+                } else {
+                    <div class="table-container">
+                        <table class="tokens-table">
+                            <thead>
+                                <tr>
+                                    <th>{ "Device" }</th>
+                                    <th>{ "IP Address" }</th>
+                                    <th>{ "Signed In" }</th>
+                                    <th>{ "Last Seen" }</th>
+                                    <th>{ "Actions" }</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                { for logins.iter().map(|login| html! {
+                                    <LoginRow
+                                        key={login.id.to_string()}
+                                        login={login.clone()}
+                                        on_revoke={on_revoke.clone()}
+                                    />
+                                }) }
+                            </tbody>
+                        </table>
+                    </div>
+                }
+            </section>
+
+            if let Some((message, action)) = &*confirm_action {
+                <div class="modal-overlay" onclick={cancel_confirm.clone()}>
+                    <div class="confirm-modal" onclick={Callback::from(|e: MouseEvent| e.stop_propagation())}>
+                        <p>{ message }</p>
+                        <div class="confirm-actions">
+                            <button class="cancel-button" onclick={cancel_confirm.clone()}>
+                                { "Cancel" }
+                            </button>
+                            <button class="confirm-button" onclick={action.clone()}>
+                                { "Confirm" }
+                            </button>
+                        </div>
+                    </div>
+                </div>
+            }
+        </>
+    }
+}
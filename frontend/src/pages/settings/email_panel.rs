@@ -0,0 +1,248 @@
+use crate::utils;
+use gloo_net::http::Request;
+use shared::api::{EmailSettings, EmailSettingsResponse};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+/// Format a UTC hour for the digest picker, e.g. "08:00 UTC"
+fn format_hour(hour: i32) -> String {
+    format!("{:02}:00 UTC", hour)
+}
+
+#[function_component(EmailPanel)]
+pub fn email_panel() -> Html {
+    let settings = use_state(EmailSettings::default);
+    let account = use_state(|| None::<(String, bool)>);
+    let dirty = use_state(|| false);
+    let saving = use_state(|| false);
+    let feedback = use_state(|| None::<String>);
+
+    // Fetch from API on mount
+    {
+        let settings = settings.clone();
+        let account = account.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                let url = utils::api_url("/api/settings/email");
+                if let Ok(resp) = Request::get(&url).send().await {
+                    if let Ok(data) = resp.json::<EmailSettingsResponse>().await {
+                        settings.set(data.settings);
+                        account.set(Some((data.email, data.smtp_configured)));
+                    }
+                }
+            });
+        });
+    }
+
+    let update = |apply: fn(&mut EmailSettings, &web_sys::HtmlInputElement)| {
+        let settings = settings.clone();
+        let dirty = dirty.clone();
+        let feedback = feedback.clone();
+        Callback::from(move |e: Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            let mut updated = (*settings).clone();
+            apply(&mut updated, &input);
+            settings.set(updated);
+            dirty.set(true);
+            feedback.set(None);
+        })
+    };
+    let on_permission_toggle = update(|s, input| {
+        s.permission_wait_minutes = input
+            .checked()
+            .then_some(s.permission_wait_minutes.unwrap_or(10));
+    });
+    let on_permission_minutes = update(|s, input| {
+        if s.permission_wait_minutes.is_some() {
+            s.permission_wait_minutes = Some(input.value().parse().unwrap_or(10));
+        }
+    });
+    let on_errors_toggle = update(|s, input| s.session_errors = input.checked());
+    let on_failures_toggle = update(|s, input| s.scheduled_failures = input.checked());
+    let on_digest_toggle = update(|s, input| s.daily_digest = input.checked());
+
+    let on_digest_hour = {
+        let settings = settings.clone();
+        let dirty = dirty.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let mut updated = (*settings).clone();
+            updated.digest_hour = select.value().parse().unwrap_or(8);
+            settings.set(updated);
+            dirty.set(true);
+        })
+    };
+
+    let on_save = {
+        let settings = settings.clone();
+        let dirty = dirty.clone();
+        let saving = saving.clone();
+        let feedback = feedback.clone();
+        Callback::from(move |_: MouseEvent| {
+            let settings = settings.clone();
+            let dirty = dirty.clone();
+            let saving = saving.clone();
+            let feedback = feedback.clone();
+            spawn_local(async move {
+                saving.set(true);
+                let url = utils::api_url("/api/settings/email");
+                match Request::put(&url)
+                    .json(&*settings)
+                    .expect("json body")
+                    .send()
+                    .await
+                {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(data) = resp.json::<EmailSettingsResponse>().await {
+                            settings.set(data.settings);
+                        }
+                        dirty.set(false);
+                        feedback.set(Some("Saved!".to_string()));
+                    }
+                    Ok(resp) => {
+                        let message = resp.text().await.unwrap_or_default();
+                        feedback.set(Some(if message.is_empty() {
+                            "Save failed".to_string()
+                        } else {
+                            message
+                        }));
+                    }
+                    Err(_) => feedback.set(Some("Save failed".to_string())),
+                }
+                saving.set(false);
+            });
+        })
+    };
+
+    let on_test = {
+        let feedback = feedback.clone();
+        Callback::from(move |_: MouseEvent| {
+            let feedback = feedback.clone();
+            spawn_local(async move {
+                let url = utils::api_url("/api/settings/email/test");
+                match Request::post(&url).send().await {
+                    Ok(resp) if resp.ok() => feedback.set(Some("Test email sent".to_string())),
+                    Ok(resp) => {
+                        let message = resp.text().await.unwrap_or_default();
+                        feedback.set(Some(format!("Test failed: {}", message)));
+                    }
+                    Err(e) => feedback.set(Some(format!("Test failed: {:?}", e))),
+                }
+            });
+        })
+    };
+
+    let Some((email, smtp_configured)) = (*account).clone() else {
+        return html! {
+            <section class="email-section">
+                <div class="loading">
+                    <div class="spinner"></div>
+                    <p>{ "Loading email settings..." }</p>
+                </div>
+            </section>
+        };
+    };
+
+    html! {
+        <section class="email-section">
+            <div class="section-header">
+                <h2>{ "Email Notifications" }</h2>
+                <p class="section-description">
+                    { format!("Mail sent to {} when something needs your attention.", email) }
+                </p>
+                <div class="sound-save-area">
+                    if let Some(msg) = &*feedback {
+                        <span class={classes!(
+                            "save-feedback",
+                            (*dirty).then_some("unsaved"),
+                            (!*dirty).then_some("saved"),
+                        )}>{ msg }</span>
+                    }
+                    if smtp_configured {
+                        <button class="renew-button" onclick={on_test}>{ "Send test" }</button>
+                    }
+                    <button
+                        class={classes!("create-button", (!*dirty).then_some("disabled"))}
+                        onclick={on_save}
+                        disabled={!*dirty || *saving}
+                    >
+                        { if *saving { "Saving..." } else { "Save" } }
+                    </button>
+                </div>
+            </div>
+
+            if !smtp_configured {
+                <div class="empty-state">
+                    <p>{ "This server has no SMTP relay configured, so no mail will be sent. Ask your administrator to set SMTP_HOST." }</p>
+                </div>
+            }
+
+            <div class="email-options">
+                <label class="toggle-label">
+                    <input
+                        type="checkbox"
+                        checked={settings.permission_wait_minutes.is_some()}
+                        onchange={on_permission_toggle}
+                    />
+                    <span>{ "A permission request has been waiting for" }</span>
+                    <input
+                        type="number"
+                        class="email-minutes"
+                        min="1"
+                        max="1440"
+                        value={settings.permission_wait_minutes.unwrap_or(10).to_string()}
+                        disabled={settings.permission_wait_minutes.is_none()}
+                        onchange={on_permission_minutes}
+                    />
+                    <span>{ "minutes" }</span>
+                </label>
+                <label class="toggle-label">
+                    <input
+                        type="checkbox"
+                        checked={settings.session_errors}
+                        onchange={on_errors_toggle}
+                    />
+                    <span>{ "A session finishes a turn with an error" }</span>
+                </label>
+                <label class="toggle-label">
+                    <input
+                        type="checkbox"
+                        checked={settings.scheduled_failures}
+                        onchange={on_failures_toggle}
+                    />
+                    <span>{ "A scheduled run fails" }</span>
+                </label>
+                <label class="toggle-label">
+                    <input
+                        type="checkbox"
+                        checked={settings.daily_digest}
+                        onchange={on_digest_toggle}
+                    />
+                    <span>{ "Daily digest of sessions, spend and scheduled runs at" }</span>
+                    <select
+                        class="email-digest-hour"
+                        disabled={!settings.daily_digest}
+                        onchange={on_digest_hour}
+                    >
+                        { for (0..24).map(|hour| html! {
+                            <option value={hour.to_string()} selected={settings.digest_hour == hour}>
+                                { format_hour(hour) }
+                            </option>
+                        }) }
+                    </select>
+                </label>
+            </div>
+        </section>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_hour() {
+        assert_eq!(format_hour(8), "08:00 UTC");
+        assert_eq!(format_hour(23), "23:00 UTC");
+    }
+}
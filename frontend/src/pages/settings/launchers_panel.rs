@@ -115,6 +115,9 @@ pub fn launchers_panel(props: &LaunchersPanelProps) -> Html {
                 let url = utils::api_url("/api/launchers");
                 if let Ok(resp) = Request::get(&url).send().await {
                     if let Ok(data) = resp.json::<Vec<LauncherInfo>>().await {
+                        // Launchers shared through a team are not ours to renew
+                        let data: Vec<LauncherInfo> =
+                            data.into_iter().filter(|l| l.shared_by.is_none()).collect();
                         on_loaded.emit(data.clone());
                         launchers.set(data);
                     }
@@ -1,21 +1,48 @@
+mod account_panel;
+mod api_keys_panel;
+mod budgets_panel;
+mod chat_panel;
+mod devices_panel;
+mod email_panel;
 mod launchers_panel;
+mod permissions_panel;
 mod sessions_panel;
 mod sounds_panel;
+mod teams_panel;
 mod tokens_panel;
+mod webhooks_panel;
 
+use account_panel::AccountPanel;
+use api_keys_panel::ApiKeysPanel;
+use budgets_panel::BudgetsPanel;
+use chat_panel::ChatPanel;
+use devices_panel::DevicesPanel;
+use email_panel::EmailPanel;
 use launchers_panel::{count_expiring_launchers, LaunchersPanel};
+use permissions_panel::PermissionsPanel;
 use sessions_panel::SessionsPanel;
 use shared::{LauncherInfo, ProxyTokenInfo, SessionInfo};
 use sounds_panel::SoundsPanel;
+use teams_panel::TeamsPanel;
 use tokens_panel::{count_expiring_tokens, TokensPanel};
+use webhooks_panel::WebhooksPanel;
 use yew::prelude::*;
 
 #[derive(Clone, Copy, PartialEq)]
 enum SettingsTab {
     Sessions,
     Tokens,
+    ApiKeys,
     Launchers,
+    Teams,
+    Budgets,
+    Permissions,
+    Webhooks,
+    Chat,
+    Email,
     Sounds,
+    Account,
+    Devices,
 }
 
 #[derive(Properties, PartialEq)]
@@ -63,16 +90,61 @@ pub fn settings_page(props: &SettingsPageProps) -> Html {
         Callback::from(move |_| active_tab.set(SettingsTab::Tokens))
     };
 
+    let on_api_keys_tab = {
+        let active_tab = active_tab.clone();
+        Callback::from(move |_| active_tab.set(SettingsTab::ApiKeys))
+    };
+
     let on_launchers_tab = {
         let active_tab = active_tab.clone();
         Callback::from(move |_| active_tab.set(SettingsTab::Launchers))
     };
 
+    let on_teams_tab = {
+        let active_tab = active_tab.clone();
+        Callback::from(move |_| active_tab.set(SettingsTab::Teams))
+    };
+
+    let on_budgets_tab = {
+        let active_tab = active_tab.clone();
+        Callback::from(move |_| active_tab.set(SettingsTab::Budgets))
+    };
+
+    let on_permissions_tab = {
+        let active_tab = active_tab.clone();
+        Callback::from(move |_| active_tab.set(SettingsTab::Permissions))
+    };
+
+    let on_webhooks_tab = {
+        let active_tab = active_tab.clone();
+        Callback::from(move |_| active_tab.set(SettingsTab::Webhooks))
+    };
+
+    let on_chat_tab = {
+        let active_tab = active_tab.clone();
+        Callback::from(move |_| active_tab.set(SettingsTab::Chat))
+    };
+
+    let on_email_tab = {
+        let active_tab = active_tab.clone();
+        Callback::from(move |_| active_tab.set(SettingsTab::Email))
+    };
+
     let on_sounds_tab = {
         let active_tab = active_tab.clone();
         Callback::from(move |_| active_tab.set(SettingsTab::Sounds))
     };
 
+    let on_account_tab = {
+        let active_tab = active_tab.clone();
+        Callback::from(move |_| active_tab.set(SettingsTab::Account))
+    };
+
+    let on_devices_tab = {
+        let active_tab = active_tab.clone();
+        Callback::from(move |_| active_tab.set(SettingsTab::Devices))
+    };
+
     let go_back = {
         let on_close = props.on_close.clone();
         Callback::from(move |_| on_close.emit(()))
@@ -111,6 +183,12 @@ pub fn settings_page(props: &SettingsPageProps) -> Html {
                         <span class="expiring-badge">{ *expiring_token_count }</span>
                     }
                 </button>
+                <button
+                    class={classes!("tab-button", (*active_tab == SettingsTab::ApiKeys).then_some("active"))}
+                    onclick={on_api_keys_tab}
+                >
+                    { "API Keys" }
+                </button>
                 <button
                     class={classes!("tab-button", (*active_tab == SettingsTab::Launchers).then_some("active"))}
                     onclick={on_launchers_tab}
@@ -120,24 +198,99 @@ pub fn settings_page(props: &SettingsPageProps) -> Html {
                         <span class="expiring-badge">{ *expiring_launcher_count }</span>
                     }
                 </button>
+                <button
+                    class={classes!("tab-button", (*active_tab == SettingsTab::Teams).then_some("active"))}
+                    onclick={on_teams_tab}
+                >
+                    { "Teams" }
+                </button>
+                <button
+                    class={classes!("tab-button", (*active_tab == SettingsTab::Budgets).then_some("active"))}
+                    onclick={on_budgets_tab}
+                >
+                    { "Budgets" }
+                </button>
+                <button
+                    class={classes!("tab-button", (*active_tab == SettingsTab::Permissions).then_some("active"))}
+                    onclick={on_permissions_tab}
+                >
+                    { "Permissions" }
+                </button>
+                <button
+                    class={classes!("tab-button", (*active_tab == SettingsTab::Webhooks).then_some("active"))}
+                    onclick={on_webhooks_tab}
+                >
+                    { "Webhooks" }
+                </button>
+                <button
+                    class={classes!("tab-button", (*active_tab == SettingsTab::Chat).then_some("active"))}
+                    onclick={on_chat_tab}
+                >
+                    { "Chat" }
+                </button>
+                <button
+                    class={classes!("tab-button", (*active_tab == SettingsTab::Email).then_some("active"))}
+                    onclick={on_email_tab}
+                >
+                    { "Email" }
+                </button>
                 <button
                     class={classes!("tab-button", (*active_tab == SettingsTab::Sounds).then_some("active"))}
                     onclick={on_sounds_tab}
                 >
                     { "Sounds" }
                 </button>
+                <button
+                    class={classes!("tab-button", (*active_tab == SettingsTab::Account).then_some("active"))}
+                    onclick={on_account_tab}
+                >
+                    { "Account" }
+                </button>
+                <button
+                    class={classes!("tab-button", (*active_tab == SettingsTab::Devices).then_some("active"))}
+                    onclick={on_devices_tab}
+                >
+                    { "Signed-in devices" }
+                </button>
             </nav>
 
             <main class="settings-content">
                 if *active_tab == SettingsTab::Tokens {
                     <TokensPanel on_tokens_loaded={on_tokens_loaded} />
                 }
+                if *active_tab == SettingsTab::ApiKeys {
+                    <ApiKeysPanel />
+                }
                 if *active_tab == SettingsTab::Launchers {
                     <LaunchersPanel on_launchers_loaded={on_launchers_loaded} />
                 }
+                if *active_tab == SettingsTab::Teams {
+                    <TeamsPanel />
+                }
+                if *active_tab == SettingsTab::Budgets {
+                    <BudgetsPanel />
+                }
+                if *active_tab == SettingsTab::Permissions {
+                    <PermissionsPanel />
+                }
+                if *active_tab == SettingsTab::Webhooks {
+                    <WebhooksPanel />
+                }
+                if *active_tab == SettingsTab::Chat {
+                    <ChatPanel />
+                }
+                if *active_tab == SettingsTab::Email {
+                    <EmailPanel />
+                }
                 if *active_tab == SettingsTab::Sounds {
                     <SoundsPanel />
                 }
+                if *active_tab == SettingsTab::Account {
+                    <AccountPanel />
+                }
+                if *active_tab == SettingsTab::Devices {
+                    <DevicesPanel />
+                }
                 if *active_tab == SettingsTab::Sessions {
                     <SessionsPanel on_sessions_loaded={on_sessions_loaded} />
                 }
@@ -0,0 +1,424 @@
+use crate::utils;
+use gloo_net::http::Request;
+use shared::api::{
+    CreatePermissionRuleRequest, PermissionRuleInfo, PermissionRuleListResponse, PermissionVerdict,
+};
+use shared::SessionInfo;
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+/// Fetch permission rules from API, returning the list
+async fn fetch_rules_from_api() -> Option<Vec<PermissionRuleInfo>> {
+    let api_endpoint = utils::api_url("/api/permission-rules");
+    match Request::get(&api_endpoint).send().await {
+        Ok(response) => {
+            if response.status() == 401 {
+                if let Some(window) = web_sys::window() {
+                    let _ = window.location().set_href("/api/auth/logout");
+                }
+                return None;
+            }
+            response
+                .json::<PermissionRuleListResponse>()
+                .await
+                .ok()
+                .map(|data| data.rules)
+        }
+        Err(e) => {
+            log::error!("Failed to fetch permission rules: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Fetch the sessions the user owns (rules can only be scoped to those)
+async fn fetch_owned_sessions() -> Vec<SessionInfo> {
+    let api_endpoint = utils::api_url("/api/sessions");
+    let Ok(response) = Request::get(&api_endpoint).send().await else {
+        return vec![];
+    };
+    response
+        .json::<serde_json::Value>()
+        .await
+        .ok()
+        .and_then(|data| data.get("sessions").cloned())
+        .and_then(|list| serde_json::from_value::<Vec<SessionInfo>>(list).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| s.my_role == "owner")
+        .collect()
+}
+
+fn verdict_label(verdict: PermissionVerdict) -> &'static str {
+    match verdict {
+        PermissionVerdict::Allow => "Allow",
+        PermissionVerdict::Deny => "Deny",
+        PermissionVerdict::Ask => "Ask",
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct RuleRowProps {
+    rule: PermissionRuleInfo,
+    on_delete: Callback<Uuid>,
+}
+
+#[function_component(RuleRow)]
+fn rule_row(props: &RuleRowProps) -> Html {
+    let rule = &props.rule;
+    let rule_id = rule.id;
+    let on_delete = props.on_delete.clone();
+    let on_delete_click = Callback::from(move |_| on_delete.emit(rule_id));
+
+    let scope = match rule.session_id {
+        Some(id) => id.to_string()[..8].to_string(),
+        None => "All sessions".to_string(),
+    };
+    let verdict_class = format!("rule-verdict {}", rule.verdict.as_str());
+
+    html! {
+        <tr class="rule-row">
+            <td class="rule-tool">{ &rule.tool_name }</td>
+            <td class="rule-pattern">
+                if let Some(glob) = &rule.path_glob {
+                    <div><span class="rule-pattern-kind">{ "path " }</span><code>{ glob }</code></div>
+                }
+                if let Some(re) = &rule.command_regex {
+                    <div><span class="rule-pattern-kind">{ "cmd " }</span><code>{ re }</code></div>
+                }
+                if rule.path_glob.is_none() && rule.command_regex.is_none() {
+                    { "—" }
+                }
+            </td>
+            <td class={verdict_class}>{ verdict_label(rule.verdict) }</td>
+            <td class="rule-priority">{ rule.priority }</td>
+            <td class="rule-scope">{ scope }</td>
+            <td class="rule-description">{ rule.description.as_deref().unwrap_or("") }</td>
+            <td class="rule-actions">
+                <button class="delete-button" onclick={on_delete_click}>{ "Delete" }</button>
+            </td>
+        </tr>
+    }
+}
+
+#[derive(Clone)]
+struct NewRuleForm {
+    tool_name: String,
+    path_glob: String,
+    command_regex: String,
+    verdict: PermissionVerdict,
+    priority: i32,
+    session_id: Option<Uuid>,
+    description: String,
+}
+
+impl Default for NewRuleForm {
+    fn default() -> Self {
+        Self {
+            tool_name: String::new(),
+            path_glob: String::new(),
+            command_regex: String::new(),
+            verdict: PermissionVerdict::Allow,
+            priority: 0,
+            session_id: None,
+            description: String::new(),
+        }
+    }
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    let trimmed = s.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+#[function_component(PermissionsPanel)]
+pub fn permissions_panel() -> Html {
+    let rules = use_state(Vec::<PermissionRuleInfo>::new);
+    let rules_loading = use_state(|| true);
+    let sessions = use_state(Vec::<SessionInfo>::new);
+    let form = use_state(NewRuleForm::default);
+    let show_create_form = use_state(|| false);
+    let form_error = use_state(|| None::<String>);
+
+    let fetch_rules = {
+        let rules = rules.clone();
+        let rules_loading = rules_loading.clone();
+        Callback::from(move |_| {
+            let rules = rules.clone();
+            let rules_loading = rules_loading.clone();
+            spawn_local(async move {
+                if let Some(list) = fetch_rules_from_api().await {
+                    rules.set(list);
+                }
+                rules_loading.set(false);
+            });
+        })
+    };
+
+    // Initial fetch
+    {
+        let fetch_rules = fetch_rules.clone();
+        let sessions = sessions.clone();
+        use_effect_with((), move |_| {
+            fetch_rules.emit(());
+            spawn_local(async move {
+                sessions.set(fetch_owned_sessions().await);
+            });
+            || ()
+        });
+    }
+
+    let on_delete_rule = {
+        let rules = rules.clone();
+        Callback::from(move |rule_id: Uuid| {
+            let rules = rules.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url(&format!("/api/permission-rules/{}", rule_id));
+                match Request::delete(&api_endpoint).send().await {
+                    Ok(response) if response.ok() => {
+                        let updated: Vec<PermissionRuleInfo> =
+                            rules.iter().filter(|r| r.id != rule_id).cloned().collect();
+                        rules.set(updated);
+                    }
+                    Ok(response) => {
+                        log::error!("Failed to delete rule: HTTP {}", response.status());
+                    }
+                    Err(e) => {
+                        log::error!("Failed to delete rule: {:?}", e);
+                    }
+                }
+            });
+        })
+    };
+
+    let on_create_rule = {
+        let form = form.clone();
+        let form_error = form_error.clone();
+        let show_create_form = show_create_form.clone();
+        let fetch_rules = fetch_rules.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let data = (*form).clone();
+            if data.tool_name.trim().is_empty() {
+                return;
+            }
+            let form = form.clone();
+            let form_error = form_error.clone();
+            let show_create_form = show_create_form.clone();
+            let fetch_rules = fetch_rules.clone();
+
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/permission-rules");
+                let request_body = CreatePermissionRuleRequest {
+                    session_id: data.session_id,
+                    tool_name: data.tool_name.trim().to_string(),
+                    path_glob: non_empty(&data.path_glob),
+                    command_regex: non_empty(&data.command_regex),
+                    verdict: data.verdict,
+                    priority: data.priority,
+                    description: non_empty(&data.description),
+                };
+                match Request::post(&api_endpoint)
+                    .json(&request_body)
+                    .unwrap()
+                    .send()
+                    .await
+                {
+                    Ok(response) if response.ok() => {
+                        form.set(NewRuleForm::default());
+                        form_error.set(None);
+                        show_create_form.set(false);
+                        fetch_rules.emit(());
+                    }
+                    Ok(response) => {
+                        let message = response.text().await.unwrap_or_default();
+                        form_error.set(Some(message));
+                    }
+                    Err(e) => {
+                        log::error!("Failed to create rule: {:?}", e);
+                    }
+                }
+            });
+        })
+    };
+
+    let text_input = |apply: fn(&mut NewRuleForm, String)| {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            let mut updated = (*form).clone();
+            apply(&mut updated, input.value());
+            form.set(updated);
+        })
+    };
+    let on_tool_input = text_input(|f, v| f.tool_name = v);
+    let on_glob_input = text_input(|f, v| f.path_glob = v);
+    let on_regex_input = text_input(|f, v| f.command_regex = v);
+    let on_description_input = text_input(|f, v| f.description = v);
+    let on_priority_input = text_input(|f, v| f.priority = v.parse().unwrap_or(0));
+
+    let on_verdict_change = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let mut updated = (*form).clone();
+            updated.verdict = match select.value().as_str() {
+                "deny" => PermissionVerdict::Deny,
+                "ask" => PermissionVerdict::Ask,
+                _ => PermissionVerdict::Allow,
+            };
+            form.set(updated);
+        })
+    };
+
+    let on_scope_change = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let mut updated = (*form).clone();
+            updated.session_id = select.value().parse().ok();
+            form.set(updated);
+        })
+    };
+
+    let toggle_create_form = {
+        let show_create_form = show_create_form.clone();
+        let form_error = form_error.clone();
+        Callback::from(move |_| {
+            form_error.set(None);
+            show_create_form.set(!*show_create_form);
+        })
+    };
+
+    html! {
+        <section class="tokens-section permissions-section">
+            <div class="section-header">
+                <h2>{ "Permission Rules" }</h2>
+                <p class="section-description">
+                    { "Answer tool permission requests automatically. The first matching rule wins: \
+                       session rules before global ones, then highest priority. \"Ask\" always prompts." }
+                </p>
+                <button class="create-button" onclick={toggle_create_form}>
+                    { if *show_create_form { "Cancel" } else { "+ Add Rule" } }
+                </button>
+            </div>
+
+            if *show_create_form {
+                <div class="create-token-form">
+                    <form onsubmit={on_create_rule}>
+                        <div class="form-group">
+                            <label for="rule-tool">{ "Tool" }</label>
+                            <input
+                                type="text"
+                                id="rule-tool"
+                                placeholder="e.g., Bash, Edit, mcp__github__*"
+                                value={form.tool_name.clone()}
+                                oninput={on_tool_input}
+                                required=true
+                            />
+                        </div>
+                        <div class="form-group">
+                            <label for="rule-glob">{ "Path glob (file tools)" }</label>
+                            <input
+                                type="text"
+                                id="rule-glob"
+                                placeholder="e.g., src/**"
+                                value={form.path_glob.clone()}
+                                oninput={on_glob_input}
+                            />
+                        </div>
+                        <div class="form-group">
+                            <label for="rule-regex">{ "Command regex (Bash)" }</label>
+                            <input
+                                type="text"
+                                id="rule-regex"
+                                placeholder="e.g., ^git (status|diff)"
+                                value={form.command_regex.clone()}
+                                oninput={on_regex_input}
+                            />
+                        </div>
+                        <div class="form-group">
+                            <label for="rule-verdict">{ "Verdict" }</label>
+                            <select id="rule-verdict" onchange={on_verdict_change}>
+                                { for [PermissionVerdict::Allow, PermissionVerdict::Deny, PermissionVerdict::Ask].iter().map(|v| html! {
+                                    <option value={v.as_str()} selected={form.verdict == *v}>{ verdict_label(*v) }</option>
+                                }) }
+                            </select>
+                        </div>
+                        <div class="form-group">
+                            <label for="rule-priority">{ "Priority" }</label>
+                            <input
+                                type="number"
+                                id="rule-priority"
+                                value={form.priority.to_string()}
+                                oninput={on_priority_input}
+                            />
+                        </div>
+                        <div class="form-group">
+                            <label for="rule-scope">{ "Applies to" }</label>
+                            <select id="rule-scope" onchange={on_scope_change}>
+                                <option value="" selected={form.session_id.is_none()}>{ "All my sessions" }</option>
+                                { for sessions.iter().map(|s| html! {
+                                    <option value={s.id.to_string()} selected={form.session_id == Some(s.id)}>
+                                        { format!("{} ({})", utils::extract_folder(&s.working_directory), &s.id.to_string()[..8]) }
+                                    </option>
+                                }) }
+                            </select>
+                        </div>
+                        <div class="form-group">
+                            <label for="rule-description">{ "Note" }</label>
+                            <input
+                                type="text"
+                                id="rule-description"
+                                placeholder="Why this rule exists"
+                                value={form.description.clone()}
+                                oninput={on_description_input}
+                            />
+                        </div>
+                        <button type="submit" class="submit-button">{ "Add Rule" }</button>
+                    </form>
+                    if let Some(err) = &*form_error {
+                        <p class="form-error">{ err }</p>
+                    }
+                </div>
+            }
+
+            if *rules_loading {
+                <div class="loading">
+                    <div class="spinner"></div>
+                    <p>{ "Loading rules..." }</p>
+                </div>
+            } else if rules.is_empty() {
+                <div class="empty-state">
+                    <p>{ "No rules yet. Every permission request will be sent to you." }</p>
+                </div>
+            } else {
+                <div class="table-container">
+                    <table class="tokens-table rules-table">
+                        <thead>
+                            <tr>
+                                <th>{ "Tool" }</th>
+                                <th>{ "Match" }</th>
+                                <th>{ "Verdict" }</th>
+                                <th>{ "Priority" }</th>
+                                <th>{ "Scope" }</th>
+                                <th>{ "Note" }</th>
+                                <th>{ "Actions" }</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for rules.iter().map(|rule| html! {
+                                <RuleRow
+                                    key={rule.id.to_string()}
+                                    rule={rule.clone()}
+                                    on_delete={on_delete_rule.clone()}
+                                />
+                            }) }
+                        </tbody>
+                    </table>
+                </div>
+            }
+        </section>
+    }
+}
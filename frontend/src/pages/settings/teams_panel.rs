@@ -0,0 +1,555 @@
+use crate::utils;
+use gloo_net::http::Request;
+use shared::api::{
+    AddTeamMemberRequest, CreateTeamRequest, ShareLauncherRequest, TeamInfo, TeamLauncherInfo,
+    TeamLaunchersResponse, TeamListResponse, TeamMemberInfo, TeamMembersResponse,
+    UpdateTeamMemberRequest,
+};
+use shared::LauncherInfo;
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+async fn fetch_teams() -> Option<Vec<TeamInfo>> {
+    let api_endpoint = utils::api_url("/api/teams");
+    match Request::get(&api_endpoint).send().await {
+        Ok(response) => {
+            if response.status() == 401 {
+                if let Some(window) = web_sys::window() {
+                    let _ = window.location().set_href("/api/auth/logout");
+                }
+                return None;
+            }
+            response
+                .json::<TeamListResponse>()
+                .await
+                .ok()
+                .map(|data| data.teams)
+        }
+        Err(e) => {
+            log::error!("Failed to fetch teams: {:?}", e);
+            None
+        }
+    }
+}
+
+async fn fetch_current_user_id() -> Option<Uuid> {
+    let response = Request::get(&utils::api_url("/api/auth/me"))
+        .send()
+        .await
+        .ok()?;
+    let data = response.json::<serde_json::Value>().await.ok()?;
+    data.get("id")?.as_str()?.parse().ok()
+}
+
+/// Show the body of a failed response, or a generic message.
+async fn error_text(response: gloo_net::http::Response) -> String {
+    let text = response.text().await.unwrap_or_default();
+    if text.is_empty() {
+        format!("Request failed ({})", response.status())
+    } else {
+        text
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct TeamDetailProps {
+    team: TeamInfo,
+    current_user_id: Option<Uuid>,
+    /// Called when membership changes, so the team list can refresh its counts
+    on_changed: Callback<()>,
+}
+
+/// Members of one team and the launchers shared with it.
+#[function_component(TeamDetail)]
+fn team_detail(props: &TeamDetailProps) -> Html {
+    let team_id = props.team.id;
+    let is_owner = props.team.my_role == "owner";
+
+    let members = use_state(Vec::<TeamMemberInfo>::new);
+    let launchers = use_state(Vec::<TeamLauncherInfo>::new);
+    let my_launchers = use_state(Vec::<String>::new);
+    let new_member_email = use_state(String::new);
+    let launcher_to_share = use_state(String::new);
+    let error = use_state(|| None::<String>);
+    let refresh = use_state(|| 0u32);
+
+    {
+        let members = members.clone();
+        let launchers = launchers.clone();
+        let my_launchers = my_launchers.clone();
+        use_effect_with((team_id, *refresh), move |_| {
+            spawn_local(async move {
+                let url = utils::api_url(&format!("/api/teams/{}/members", team_id));
+                if let Ok(resp) = Request::get(&url).send().await {
+                    if let Ok(data) = resp.json::<TeamMembersResponse>().await {
+                        members.set(data.members);
+                    }
+                }
+                let url = utils::api_url(&format!("/api/teams/{}/launchers", team_id));
+                if let Ok(resp) = Request::get(&url).send().await {
+                    if let Ok(data) = resp.json::<TeamLaunchersResponse>().await {
+                        launchers.set(data.launchers);
+                    }
+                }
+                // Only the caller's own connected launchers can be shared
+                if let Ok(resp) = Request::get(&utils::api_url("/api/launchers")).send().await {
+                    if let Ok(data) = resp.json::<Vec<LauncherInfo>>().await {
+                        let mut names: Vec<String> = data
+                            .into_iter()
+                            .filter(|l| l.shared_by.is_none())
+                            .map(|l| l.launcher_name)
+                            .collect();
+                        names.sort();
+                        names.dedup();
+                        my_launchers.set(names);
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    // Run a request and reload the team on success, or show why it failed
+    let run = {
+        let error = error.clone();
+        let refresh = refresh.clone();
+        let on_changed = props.on_changed.clone();
+        Callback::from(move |request: Request| {
+            let error = error.clone();
+            let refresh = refresh.clone();
+            let on_changed = on_changed.clone();
+            spawn_local(async move {
+                match request.send().await {
+                    Ok(response) if response.ok() => {
+                        error.set(None);
+                        refresh.set(*refresh + 1);
+                        on_changed.emit(());
+                    }
+                    Ok(response) => error.set(Some(error_text(response).await)),
+                    Err(e) => error.set(Some(format!("Request failed: {:?}", e))),
+                }
+            });
+        })
+    };
+
+    let on_email_input = {
+        let new_member_email = new_member_email.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            new_member_email.set(input.value());
+        })
+    };
+
+    let on_add_member = {
+        let run = run.clone();
+        let new_member_email = new_member_email.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let email = new_member_email.trim().to_string();
+            if email.is_empty() {
+                return;
+            }
+            let body = AddTeamMemberRequest {
+                email,
+                role: "member".to_string(),
+            };
+            let url = utils::api_url(&format!("/api/teams/{}/members", team_id));
+            run.emit(Request::post(&url).json(&body).unwrap());
+            new_member_email.set(String::new());
+        })
+    };
+
+    let on_role_change = {
+        let run = run.clone();
+        Callback::from(move |(user_id, role): (Uuid, String)| {
+            let url = utils::api_url(&format!("/api/teams/{}/members/{}", team_id, user_id));
+            let body = UpdateTeamMemberRequest { role };
+            run.emit(Request::patch(&url).json(&body).unwrap());
+        })
+    };
+
+    let on_remove_member = {
+        let run = run.clone();
+        Callback::from(move |user_id: Uuid| {
+            let url = utils::api_url(&format!("/api/teams/{}/members/{}", team_id, user_id));
+            run.emit(Request::delete(&url).build().unwrap());
+        })
+    };
+
+    let on_launcher_select = {
+        let launcher_to_share = launcher_to_share.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            launcher_to_share.set(select.value());
+        })
+    };
+
+    let on_share_launcher = {
+        let run = run.clone();
+        let launcher_to_share = launcher_to_share.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            if launcher_to_share.is_empty() {
+                return;
+            }
+            let body = ShareLauncherRequest {
+                launcher_name: (*launcher_to_share).clone(),
+            };
+            let url = utils::api_url(&format!("/api/teams/{}/launchers", team_id));
+            run.emit(Request::post(&url).json(&body).unwrap());
+            launcher_to_share.set(String::new());
+        })
+    };
+
+    let on_unshare_launcher = {
+        let run = run.clone();
+        Callback::from(move |share_id: Uuid| {
+            let url = utils::api_url(&format!("/api/teams/{}/launchers/{}", team_id, share_id));
+            run.emit(Request::delete(&url).build().unwrap());
+        })
+    };
+
+    let shareable: Vec<&String> = my_launchers
+        .iter()
+        .filter(|name| {
+            !launchers
+                .iter()
+                .any(|l| Some(l.owner_id) == props.current_user_id && &l.launcher_name == *name)
+        })
+        .collect();
+
+    html! {
+        <div class="team-detail">
+            <h3>{ format!("{} — members", props.team.name) }</h3>
+            if let Some(err) = &*error {
+                <p class="form-error">{ err }</p>
+            }
+            <table class="tokens-table team-members-table">
+                <thead>
+                    <tr>
+                        <th>{ "Member" }</th>
+                        <th>{ "Role" }</th>
+                        <th>{ "Joined" }</th>
+                        <th>{ "Actions" }</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for members.iter().map(|m| {
+                        let user_id = m.user_id;
+                        let is_me = Some(user_id) == props.current_user_id;
+                        let on_role_change = on_role_change.clone();
+                        let on_role = Callback::from(move |e: Event| {
+                            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+                            on_role_change.emit((user_id, select.value()));
+                        });
+                        let on_remove_member = on_remove_member.clone();
+                        let on_remove = Callback::from(move |_| on_remove_member.emit(user_id));
+                        html! {
+                            <tr key={user_id.to_string()}>
+                                <td>
+                                    { m.name.clone().unwrap_or_else(|| m.email.clone()) }
+                                    if m.name.is_some() {
+                                        <span class="team-member-email">{ &m.email }</span>
+                                    }
+                                </td>
+                                <td>
+                                    if is_owner {
+                                        <select onchange={on_role}>
+                                            { for ["member", "owner"].iter().map(|role| html! {
+                                                <option value={*role} selected={m.role == *role}>{ *role }</option>
+                                            }) }
+                                        </select>
+                                    } else {
+                                        { &m.role }
+                                    }
+                                </td>
+                                <td>{ utils::format_timestamp(&m.created_at) }</td>
+                                <td class="rule-actions">
+                                    if is_owner || is_me {
+                                        <button class="delete-button" onclick={on_remove}>
+                                            { if is_me { "Leave" } else { "Remove" } }
+                                        </button>
+                                    }
+                                </td>
+                            </tr>
+                        }
+                    }) }
+                </tbody>
+            </table>
+            if is_owner {
+                <form class="team-inline-form" onsubmit={on_add_member}>
+                    <input
+                        type="email"
+                        placeholder="colleague@example.com"
+                        value={(*new_member_email).clone()}
+                        oninput={on_email_input}
+                    />
+                    <button type="submit" class="submit-button">{ "Add member" }</button>
+                </form>
+            }
+
+            <h3>{ "Shared launchers" }</h3>
+            <p class="section-description">
+                { "Members can start sessions on these launchers. They run as the launcher's owner, on their machine." }
+            </p>
+            if launchers.is_empty() {
+                <p class="team-empty">{ "No launchers are shared with this team." }</p>
+            } else {
+                <table class="tokens-table team-launchers-table">
+                    <thead>
+                        <tr>
+                            <th>{ "Launcher" }</th>
+                            <th>{ "Owner" }</th>
+                            <th>{ "Status" }</th>
+                            <th>{ "Actions" }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for launchers.iter().map(|l| {
+                            let share_id = l.id;
+                            let mine = Some(l.owner_id) == props.current_user_id;
+                            let on_unshare_launcher = on_unshare_launcher.clone();
+                            let on_unshare = Callback::from(move |_| on_unshare_launcher.emit(share_id));
+                            html! {
+                                <tr key={share_id.to_string()}>
+                                    <td class="token-name">{ &l.launcher_name }</td>
+                                    <td>{ &l.owner_name }</td>
+                                    <td class={if l.connected { "token-status active" } else { "token-status expired" }}>
+                                        { if l.connected { "Connected" } else { "Offline" } }
+                                    </td>
+                                    <td class="rule-actions">
+                                        if mine || is_owner {
+                                            <button class="delete-button" onclick={on_unshare}>{ "Stop sharing" }</button>
+                                        }
+                                    </td>
+                                </tr>
+                            }
+                        }) }
+                    </tbody>
+                </table>
+            }
+            if !shareable.is_empty() {
+                <form class="team-inline-form" onsubmit={on_share_launcher}>
+                    <select onchange={on_launcher_select}>
+                        <option value="" selected={launcher_to_share.is_empty()}>{ "Choose one of your launchers" }</option>
+                        { for shareable.iter().map(|name| html! {
+                            <option value={(*name).clone()} selected={*launcher_to_share == **name}>{ *name }</option>
+                        }) }
+                    </select>
+                    <button type="submit" class="submit-button">{ "Share launcher" }</button>
+                </form>
+            }
+        </div>
+    }
+}
+
+#[function_component(TeamsPanel)]
+pub fn teams_panel() -> Html {
+    let teams = use_state(Vec::<TeamInfo>::new);
+    let loading = use_state(|| true);
+    let current_user_id = use_state(|| None::<Uuid>);
+    let selected = use_state(|| None::<Uuid>);
+    let new_team_name = use_state(String::new);
+    let form_error = use_state(|| None::<String>);
+    let confirm_delete = use_state(|| None::<Uuid>);
+
+    let reload = {
+        let teams = teams.clone();
+        let loading = loading.clone();
+        let selected = selected.clone();
+        Callback::from(move |_| {
+            let teams = teams.clone();
+            let loading = loading.clone();
+            let selected = selected.clone();
+            spawn_local(async move {
+                if let Some(list) = fetch_teams().await {
+                    // Drop the selection if the caller left or deleted that team
+                    if let Some(id) = *selected {
+                        if !list.iter().any(|t| t.id == id) {
+                            selected.set(None);
+                        }
+                    }
+                    teams.set(list);
+                }
+                loading.set(false);
+            });
+        })
+    };
+
+    {
+        let reload = reload.clone();
+        let current_user_id = current_user_id.clone();
+        use_effect_with((), move |_| {
+            reload.emit(());
+            spawn_local(async move {
+                current_user_id.set(fetch_current_user_id().await);
+            });
+            || ()
+        });
+    }
+
+    let on_name_input = {
+        let new_team_name = new_team_name.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            new_team_name.set(input.value());
+        })
+    };
+
+    let on_create = {
+        let new_team_name = new_team_name.clone();
+        let form_error = form_error.clone();
+        let selected = selected.clone();
+        let reload = reload.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let name = new_team_name.trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+            let new_team_name = new_team_name.clone();
+            let form_error = form_error.clone();
+            let selected = selected.clone();
+            let reload = reload.clone();
+            spawn_local(async move {
+                let body = CreateTeamRequest { name };
+                match Request::post(&utils::api_url("/api/teams"))
+                    .json(&body)
+                    .unwrap()
+                    .send()
+                    .await
+                {
+                    Ok(response) if response.ok() => {
+                        if let Ok(team) = response.json::<TeamInfo>().await {
+                            selected.set(Some(team.id));
+                        }
+                        new_team_name.set(String::new());
+                        form_error.set(None);
+                        reload.emit(());
+                    }
+                    Ok(response) => form_error.set(Some(error_text(response).await)),
+                    Err(e) => form_error.set(Some(format!("Request failed: {:?}", e))),
+                }
+            });
+        })
+    };
+
+    let on_delete = {
+        let confirm_delete = confirm_delete.clone();
+        let reload = reload.clone();
+        Callback::from(move |team_id: Uuid| {
+            if *confirm_delete != Some(team_id) {
+                confirm_delete.set(Some(team_id));
+                return;
+            }
+            let confirm_delete = confirm_delete.clone();
+            let reload = reload.clone();
+            spawn_local(async move {
+                let url = utils::api_url(&format!("/api/teams/{}", team_id));
+                if let Err(e) = Request::delete(&url).send().await {
+                    log::error!("Failed to delete team: {:?}", e);
+                }
+                confirm_delete.set(None);
+                reload.emit(());
+            });
+        })
+    };
+
+    let selected_team = selected
+        .and_then(|id| teams.iter().find(|t| t.id == id))
+        .cloned();
+
+    html! {
+        <section class="tokens-section teams-section">
+            <div class="section-header">
+                <h2>{ "Teams" }</h2>
+                <p class="section-description">
+                    { "Share sessions, scheduled tasks and launchers with a group of people at once. \
+                       Team owners manage who is in the team." }
+                </p>
+            </div>
+
+            <form class="team-inline-form" onsubmit={on_create}>
+                <input
+                    type="text"
+                    placeholder="New team name"
+                    maxlength="100"
+                    value={(*new_team_name).clone()}
+                    oninput={on_name_input}
+                />
+                <button type="submit" class="create-button">{ "+ Create Team" }</button>
+            </form>
+            if let Some(err) = &*form_error {
+                <p class="form-error">{ err }</p>
+            }
+
+            if *loading {
+                <div class="loading">
+                    <div class="spinner"></div>
+                    <p>{ "Loading teams..." }</p>
+                </div>
+            } else if teams.is_empty() {
+                <div class="empty-state">
+                    <p>{ "You are not in any teams yet." }</p>
+                </div>
+            } else {
+                <div class="table-container">
+                    <table class="tokens-table teams-table">
+                        <thead>
+                            <tr>
+                                <th>{ "Team" }</th>
+                                <th>{ "Your role" }</th>
+                                <th>{ "Members" }</th>
+                                <th>{ "Actions" }</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for teams.iter().map(|team| {
+                                let team_id = team.id;
+                                let is_selected = *selected == Some(team_id);
+                                let is_confirming = *confirm_delete == Some(team_id);
+                                let on_select = {
+                                    let selected = selected.clone();
+                                    Callback::from(move |_| {
+                                        selected.set(if is_selected { None } else { Some(team_id) })
+                                    })
+                                };
+                                let on_delete = on_delete.clone();
+                                let on_delete_click = Callback::from(move |_| on_delete.emit(team_id));
+                                html! {
+                                    <tr key={team_id.to_string()} class={classes!(is_selected.then_some("selected"))}>
+                                        <td class="token-name">{ &team.name }</td>
+                                        <td>{ &team.my_role }</td>
+                                        <td>{ team.member_count }</td>
+                                        <td class="rule-actions">
+                                            <button class="renew-button" onclick={on_select}>
+                                                { if is_selected { "Close" } else { "Manage" } }
+                                            </button>
+                                            if team.my_role == "owner" {
+                                                <button
+                                                    class={classes!("delete-button", is_confirming.then_some("confirming"))}
+                                                    onclick={on_delete_click}
+                                                >
+                                                    { if is_confirming { "Confirm?" } else { "Delete" } }
+                                                </button>
+                                            }
+                                        </td>
+                                    </tr>
+                                }
+                            }) }
+                        </tbody>
+                    </table>
+                </div>
+                if let Some(team) = selected_team {
+                    <TeamDetail
+                        key={team.id.to_string()}
+                        team={team.clone()}
+                        current_user_id={*current_user_id}
+                        on_changed={reload.clone()}
+                    />
+                }
+            }
+        </section>
+    }
+}
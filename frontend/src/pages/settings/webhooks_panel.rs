@@ -0,0 +1,623 @@
+use crate::utils;
+use gloo::timers::callback::Timeout;
+use gloo_net::http::Request;
+use shared::api::{
+    CreateWebhookRequest, CreateWebhookResponse, UpdateWebhookRequest, WebhookDeliveryInfo,
+    WebhookDeliveryListResponse, WebhookDeliveryStatus, WebhookEvent, WebhookInfo,
+    WebhookListResponse,
+};
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+/// Fetch webhooks from API, returning the list
+async fn fetch_webhooks_from_api() -> Option<Vec<WebhookInfo>> {
+    let api_endpoint = utils::api_url("/api/webhooks");
+    match Request::get(&api_endpoint).send().await {
+        Ok(response) => {
+            if response.status() == 401 {
+                if let Some(window) = web_sys::window() {
+                    let _ = window.location().set_href("/api/auth/logout");
+                }
+                return None;
+            }
+            response
+                .json::<WebhookListResponse>()
+                .await
+                .ok()
+                .map(|data| data.webhooks)
+        }
+        Err(e) => {
+            log::error!("Failed to fetch webhooks: {:?}", e);
+            None
+        }
+    }
+}
+
+async fn fetch_deliveries(webhook_id: Uuid) -> Option<Vec<WebhookDeliveryInfo>> {
+    let api_endpoint = utils::api_url(&format!("/api/webhooks/{}/deliveries", webhook_id));
+    match Request::get(&api_endpoint).send().await {
+        Ok(response) if response.ok() => response
+            .json::<WebhookDeliveryListResponse>()
+            .await
+            .ok()
+            .map(|data| data.deliveries),
+        Ok(response) => {
+            log::error!("Failed to fetch deliveries: HTTP {}", response.status());
+            None
+        }
+        Err(e) => {
+            log::error!("Failed to fetch deliveries: {:?}", e);
+            None
+        }
+    }
+}
+
+fn event_label(event: WebhookEvent) -> &'static str {
+    match event {
+        WebhookEvent::TurnCompleted => "Turn completed",
+        WebhookEvent::PermissionRequested => "Permission requested",
+        WebhookEvent::SessionExited => "Session exited",
+        WebhookEvent::ScheduledRunCompleted => "Scheduled run completed",
+        WebhookEvent::Ping => "Ping",
+    }
+}
+
+/// Describe a delivery's outcome, e.g. "Failed (HTTP 500) after 6 attempts".
+fn describe_delivery(d: &WebhookDeliveryInfo) -> String {
+    let status = match d.status {
+        WebhookDeliveryStatus::Pending if d.attempts == 0 => return "Queued".to_string(),
+        WebhookDeliveryStatus::Pending => "Retrying",
+        WebhookDeliveryStatus::Succeeded => "Delivered",
+        WebhookDeliveryStatus::Failed => "Failed",
+    };
+    let http = d
+        .response_status
+        .map(|code| format!(" (HTTP {})", code))
+        .unwrap_or_default();
+    let attempts = if d.attempts == 1 {
+        "1 attempt".to_string()
+    } else {
+        format!("{} attempts", d.attempts)
+    };
+    format!("{}{} after {}", status, http, attempts)
+}
+
+#[derive(Properties, PartialEq)]
+struct DeliveryLogProps {
+    webhook_id: Uuid,
+    /// Bumped by the panel to reload after sending a test
+    refresh: u32,
+}
+
+#[function_component(DeliveryLog)]
+fn delivery_log(props: &DeliveryLogProps) -> Html {
+    let deliveries = use_state(|| None::<Vec<WebhookDeliveryInfo>>);
+    let local_refresh = use_state(|| 0u32);
+
+    {
+        let deliveries = deliveries.clone();
+        let webhook_id = props.webhook_id;
+        use_effect_with((webhook_id, props.refresh, *local_refresh), move |_| {
+            spawn_local(async move {
+                deliveries.set(Some(fetch_deliveries(webhook_id).await.unwrap_or_default()));
+            });
+            || ()
+        });
+    }
+
+    let on_refresh = {
+        let local_refresh = local_refresh.clone();
+        Callback::from(move |_| local_refresh.set(*local_refresh + 1))
+    };
+
+    let body = match &*deliveries {
+        None => html! { <p class="webhook-deliveries-empty">{ "Loading..." }</p> },
+        Some(list) if list.is_empty() => html! {
+            <p class="webhook-deliveries-empty">{ "Nothing has been sent to this webhook yet." }</p>
+        },
+        Some(list) => html! {
+            <table class="tokens-table webhook-deliveries-table">
+                <thead>
+                    <tr>
+                        <th>{ "Queued" }</th>
+                        <th>{ "Event" }</th>
+                        <th>{ "Result" }</th>
+                        <th>{ "Detail" }</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for list.iter().map(|d| {
+                        let detail = match (&d.last_error, &d.next_attempt_at) {
+                            (Some(err), Some(next)) => format!("{} — next try {}", err, utils::format_timestamp(next)),
+                            (Some(err), None) => err.clone(),
+                            (None, _) => d.delivered_at.as_deref().map(utils::format_timestamp).unwrap_or_default(),
+                        };
+                        html! {
+                            <tr key={d.id.to_string()}>
+                                <td>{ utils::format_timestamp(&d.created_at) }</td>
+                                <td><code>{ &d.event }</code></td>
+                                <td class={classes!("webhook-delivery-status", d.status.as_str())}>
+                                    { describe_delivery(d) }
+                                </td>
+                                <td class="webhook-delivery-detail">{ detail }</td>
+                            </tr>
+                        }
+                    }) }
+                </tbody>
+            </table>
+        },
+    };
+
+    html! {
+        <div class="webhook-deliveries">
+            <div class="webhook-deliveries-header">
+                <h3>{ "Recent deliveries" }</h3>
+                <button class="renew-button" onclick={on_refresh}>{ "Refresh" }</button>
+            </div>
+            { body }
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct WebhookRowProps {
+    webhook: WebhookInfo,
+    selected: bool,
+    on_select: Callback<Uuid>,
+    on_toggle: Callback<(Uuid, bool)>,
+    on_test: Callback<Uuid>,
+    on_delete: Callback<Uuid>,
+}
+
+#[function_component(WebhookRow)]
+fn webhook_row(props: &WebhookRowProps) -> Html {
+    let webhook = &props.webhook;
+    let webhook_id = webhook.id;
+    let enabled = webhook.enabled;
+
+    let on_select = props.on_select.clone();
+    let on_select_click = Callback::from(move |_| on_select.emit(webhook_id));
+    let on_toggle = props.on_toggle.clone();
+    let on_toggle_click = Callback::from(move |_| on_toggle.emit((webhook_id, !enabled)));
+    let on_test = props.on_test.clone();
+    let on_test_click = Callback::from(move |_| on_test.emit(webhook_id));
+    let on_delete = props.on_delete.clone();
+    let on_delete_click = Callback::from(move |_| on_delete.emit(webhook_id));
+
+    html! {
+        <tr class={classes!("webhook-row", props.selected.then_some("selected"), (!enabled).then_some("disabled"))}>
+            <td class="webhook-url"><code>{ &webhook.url }</code></td>
+            <td class="webhook-events">
+                { for webhook.events.iter().map(|e| html! { <div>{ event_label(*e) }</div> }) }
+            </td>
+            <td class="rule-description">{ webhook.description.as_deref().unwrap_or("") }</td>
+            <td>{ if enabled { "Enabled" } else { "Paused" } }</td>
+            <td class="rule-actions">
+                <button class="renew-button" onclick={on_select_click}>
+                    { if props.selected { "Hide log" } else { "Log" } }
+                </button>
+                if enabled {
+                    <button class="renew-button" onclick={on_test_click}>{ "Test" }</button>
+                }
+                <button class="renew-button" onclick={on_toggle_click}>
+                    { if enabled { "Pause" } else { "Resume" } }
+                </button>
+                <button class="delete-button" onclick={on_delete_click}>{ "Delete" }</button>
+            </td>
+        </tr>
+    }
+}
+
+#[derive(Clone, Default)]
+struct NewWebhookForm {
+    url: String,
+    events: Vec<WebhookEvent>,
+    description: String,
+}
+
+#[function_component(WebhooksPanel)]
+pub fn webhooks_panel() -> Html {
+    let webhooks = use_state(Vec::<WebhookInfo>::new);
+    let webhooks_loading = use_state(|| true);
+    let form = use_state(NewWebhookForm::default);
+    let show_create_form = use_state(|| false);
+    let form_error = use_state(|| None::<String>);
+    let created_secret = use_state(|| None::<String>);
+    let selected = use_state(|| None::<Uuid>);
+    let deliveries_refresh = use_state(|| 0u32);
+
+    let fetch_webhooks = {
+        let webhooks = webhooks.clone();
+        let webhooks_loading = webhooks_loading.clone();
+        Callback::from(move |_| {
+            let webhooks = webhooks.clone();
+            let webhooks_loading = webhooks_loading.clone();
+            spawn_local(async move {
+                if let Some(list) = fetch_webhooks_from_api().await {
+                    webhooks.set(list);
+                }
+                webhooks_loading.set(false);
+            });
+        })
+    };
+
+    // Initial fetch
+    {
+        let fetch_webhooks = fetch_webhooks.clone();
+        use_effect_with((), move |_| {
+            fetch_webhooks.emit(());
+            || ()
+        });
+    }
+
+    let on_delete = {
+        let webhooks = webhooks.clone();
+        let selected = selected.clone();
+        Callback::from(move |webhook_id: Uuid| {
+            let webhooks = webhooks.clone();
+            let selected = selected.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url(&format!("/api/webhooks/{}", webhook_id));
+                match Request::delete(&api_endpoint).send().await {
+                    Ok(response) if response.ok() => {
+                        let updated: Vec<WebhookInfo> = webhooks
+                            .iter()
+                            .filter(|w| w.id != webhook_id)
+                            .cloned()
+                            .collect();
+                        webhooks.set(updated);
+                        if *selected == Some(webhook_id) {
+                            selected.set(None);
+                        }
+                    }
+                    Ok(response) => {
+                        log::error!("Failed to delete webhook: HTTP {}", response.status());
+                    }
+                    Err(e) => {
+                        log::error!("Failed to delete webhook: {:?}", e);
+                    }
+                }
+            });
+        })
+    };
+
+    let on_toggle = {
+        let webhooks = webhooks.clone();
+        Callback::from(move |(webhook_id, enabled): (Uuid, bool)| {
+            let webhooks = webhooks.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url(&format!("/api/webhooks/{}", webhook_id));
+                let body = UpdateWebhookRequest {
+                    enabled: Some(enabled),
+                    ..Default::default()
+                };
+                match Request::patch(&api_endpoint)
+                    .json(&body)
+                    .unwrap()
+                    .send()
+                    .await
+                {
+                    Ok(response) if response.ok() => {
+                        if let Ok(updated) = response.json::<WebhookInfo>().await {
+                            let list: Vec<WebhookInfo> = webhooks
+                                .iter()
+                                .map(|w| {
+                                    if w.id == updated.id {
+                                        updated.clone()
+                                    } else {
+                                        w.clone()
+                                    }
+                                })
+                                .collect();
+                            webhooks.set(list);
+                        }
+                    }
+                    Ok(response) => {
+                        log::error!("Failed to update webhook: HTTP {}", response.status());
+                    }
+                    Err(e) => {
+                        log::error!("Failed to update webhook: {:?}", e);
+                    }
+                }
+            });
+        })
+    };
+
+    let on_test = {
+        let selected = selected.clone();
+        let deliveries_refresh = deliveries_refresh.clone();
+        Callback::from(move |webhook_id: Uuid| {
+            let selected = selected.clone();
+            let deliveries_refresh = deliveries_refresh.clone();
+            spawn_local(async move {
+                let api_endpoint = utils::api_url(&format!("/api/webhooks/{}/test", webhook_id));
+                match Request::post(&api_endpoint).send().await {
+                    Ok(response) if response.ok() => {
+                        // Give the worker a moment, then show the result in the log
+                        Timeout::new(1500, move || {
+                            selected.set(Some(webhook_id));
+                            deliveries_refresh.set(*deliveries_refresh + 1);
+                        })
+                        .forget();
+                    }
+                    Ok(response) => {
+                        log::error!("Failed to test webhook: HTTP {}", response.status());
+                    }
+                    Err(e) => {
+                        log::error!("Failed to test webhook: {:?}", e);
+                    }
+                }
+            });
+        })
+    };
+
+    let on_select = {
+        let selected = selected.clone();
+        Callback::from(move |webhook_id: Uuid| {
+            if *selected == Some(webhook_id) {
+                selected.set(None);
+            } else {
+                selected.set(Some(webhook_id));
+            }
+        })
+    };
+
+    let on_create_webhook = {
+        let form = form.clone();
+        let form_error = form_error.clone();
+        let created_secret = created_secret.clone();
+        let fetch_webhooks = fetch_webhooks.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let data = (*form).clone();
+            if data.url.trim().is_empty() {
+                return;
+            }
+            if data.events.is_empty() {
+                form_error.set(Some("Choose at least one event".to_string()));
+                return;
+            }
+            let form = form.clone();
+            let form_error = form_error.clone();
+            let created_secret = created_secret.clone();
+            let fetch_webhooks = fetch_webhooks.clone();
+
+            spawn_local(async move {
+                let api_endpoint = utils::api_url("/api/webhooks");
+                let description = data.description.trim();
+                let request_body = CreateWebhookRequest {
+                    url: data.url.trim().to_string(),
+                    events: data.events,
+                    description: (!description.is_empty()).then(|| description.to_string()),
+                };
+                match Request::post(&api_endpoint)
+                    .json(&request_body)
+                    .unwrap()
+                    .send()
+                    .await
+                {
+                    Ok(response) if response.ok() => {
+                        if let Ok(created) = response.json::<CreateWebhookResponse>().await {
+                            created_secret.set(Some(created.secret));
+                        }
+                        form.set(NewWebhookForm::default());
+                        form_error.set(None);
+                        fetch_webhooks.emit(());
+                    }
+                    Ok(response) => {
+                        let message = response.text().await.unwrap_or_default();
+                        form_error.set(Some(message));
+                    }
+                    Err(e) => {
+                        log::error!("Failed to create webhook: {:?}", e);
+                    }
+                }
+            });
+        })
+    };
+
+    let on_url_input = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            let mut updated = (*form).clone();
+            updated.url = input.value();
+            form.set(updated);
+        })
+    };
+
+    let on_description_input = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            let mut updated = (*form).clone();
+            updated.description = input.value();
+            form.set(updated);
+        })
+    };
+
+    let toggle_event = |event: WebhookEvent| {
+        let form = form.clone();
+        Callback::from(move |_: Event| {
+            let mut updated = (*form).clone();
+            if updated.events.contains(&event) {
+                updated.events.retain(|e| *e != event);
+            } else {
+                updated.events.push(event);
+            }
+            form.set(updated);
+        })
+    };
+
+    let toggle_create_form = {
+        let show_create_form = show_create_form.clone();
+        let form_error = form_error.clone();
+        let created_secret = created_secret.clone();
+        Callback::from(move |_| {
+            form_error.set(None);
+            created_secret.set(None);
+            show_create_form.set(!*show_create_form);
+        })
+    };
+
+    html! {
+        <section class="tokens-section webhooks-section">
+            <div class="section-header">
+                <h2>{ "Webhooks" }</h2>
+                <p class="section-description">
+                    { "Send a signed JSON POST to your own endpoint when sessions finish a turn, \
+                       ask for permission, or exit, and when scheduled runs complete. \
+                       Failed deliveries are retried with backoff." }
+                </p>
+                <button class="create-button" onclick={toggle_create_form.clone()}>
+                    { if *show_create_form { "Cancel" } else { "+ Add Webhook" } }
+                </button>
+            </div>
+
+            if *show_create_form {
+                <div class="create-token-form">
+                    if let Some(secret) = &*created_secret {
+                        <div class="token-created-success">
+                            <h3>{ "Webhook Created" }</h3>
+                            <p class="warning">
+                                { "Copy this signing secret now. It will not be shown again!" }
+                            </p>
+                            <div class="token-display">
+                                <code>{ secret }</code>
+                            </div>
+                            <p class="expires-info">
+                                { "Verify the X-Portal-Signature header with HMAC-SHA256 over \"<X-Portal-Timestamp>.<body>\"." }
+                            </p>
+                            <button onclick={toggle_create_form.clone()}>{ "Done" }</button>
+                        </div>
+                    } else {
+                        <form onsubmit={on_create_webhook}>
+                            <div class="form-group">
+                                <label for="webhook-url">{ "Endpoint URL" }</label>
+                                <input
+                                    type="url"
+                                    id="webhook-url"
+                                    placeholder="https://example.com/hooks/portal"
+                                    value={form.url.clone()}
+                                    oninput={on_url_input}
+                                    required=true
+                                />
+                            </div>
+                            <div class="form-group">
+                                <label>{ "Events" }</label>
+                                <div class="webhook-event-options">
+                                    { for WebhookEvent::SUBSCRIBABLE.iter().map(|event| html! {
+                                        <label class="webhook-event-option">
+                                            <input
+                                                type="checkbox"
+                                                checked={form.events.contains(event)}
+                                                onchange={toggle_event(*event)}
+                                            />
+                                            { event_label(*event) }
+                                            <code>{ event.as_str() }</code>
+                                        </label>
+                                    }) }
+                                </div>
+                            </div>
+                            <div class="form-group">
+                                <label for="webhook-description">{ "Note" }</label>
+                                <input
+                                    type="text"
+                                    id="webhook-description"
+                                    placeholder="e.g., Slack relay"
+                                    value={form.description.clone()}
+                                    oninput={on_description_input}
+                                />
+                            </div>
+                            <button type="submit" class="submit-button">{ "Add Webhook" }</button>
+                        </form>
+                        if let Some(err) = &*form_error {
+                            <p class="form-error">{ err }</p>
+                        }
+                    }
+                </div>
+            }
+
+            if *webhooks_loading {
+                <div class="loading">
+                    <div class="spinner"></div>
+                    <p>{ "Loading webhooks..." }</p>
+                </div>
+            } else if webhooks.is_empty() {
+                <div class="empty-state">
+                    <p>{ "No webhooks yet." }</p>
+                </div>
+            } else {
+                <div class="table-container">
+                    <table class="tokens-table webhooks-table">
+                        <thead>
+                            <tr>
+                                <th>{ "URL" }</th>
+                                <th>{ "Events" }</th>
+                                <th>{ "Note" }</th>
+                                <th>{ "Status" }</th>
+                                <th>{ "Actions" }</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for webhooks.iter().map(|webhook| html! {
+                                <WebhookRow
+                                    key={webhook.id.to_string()}
+                                    webhook={webhook.clone()}
+                                    selected={*selected == Some(webhook.id)}
+                                    on_select={on_select.clone()}
+                                    on_toggle={on_toggle.clone()}
+                                    on_test={on_test.clone()}
+                                    on_delete={on_delete.clone()}
+                                />
+                            }) }
+                        </tbody>
+                    </table>
+                </div>
+                if let Some(webhook_id) = *selected {
+                    <DeliveryLog webhook_id={webhook_id} refresh={*deliveries_refresh} />
+                }
+            }
+        </section>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delivery(
+        status: WebhookDeliveryStatus,
+        attempts: i32,
+        code: Option<i32>,
+    ) -> WebhookDeliveryInfo {
+        WebhookDeliveryInfo {
+            id: Uuid::nil(),
+            event: "ping".to_string(),
+            status,
+            attempts,
+            response_status: code,
+            last_error: None,
+            next_attempt_at: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            delivered_at: None,
+        }
+    }
+
+    #[test]
+    fn test_describe_delivery() {
+        assert_eq!(
+            describe_delivery(&delivery(WebhookDeliveryStatus::Pending, 0, None)),
+            "Queued"
+        );
+        assert_eq!(
+            describe_delivery(&delivery(WebhookDeliveryStatus::Succeeded, 1, Some(200))),
+            "Delivered (HTTP 200) after 1 attempt"
+        );
+        assert_eq!(
+            describe_delivery(&delivery(WebhookDeliveryStatus::Failed, 6, None)),
+            "Failed after 6 attempts"
+        );
+    }
+}
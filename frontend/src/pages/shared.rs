@@ -0,0 +1,261 @@
+//! Public share link viewer
+//!
+//! Opened from `/shared/<token>` without signing in. Asks for the link
+//! password when there is one, then streams the session read-only over
+//! `/ws/shared`. The backend strips cost and sender details before anything
+//! reaches this page, and there is no way to send input from it.
+
+use crate::components::{group_messages, MessageGroupRenderer};
+use crate::utils;
+use gloo_net::http::Request;
+use shared::api::SharedSessionInfo;
+use shared::{ServerToClient, SharedClientToServer, SharedEndpoint, WsEndpoint};
+use std::rc::Rc;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+/// What the viewer socket has delivered so far
+#[derive(Clone, Default, PartialEq)]
+struct Transcript {
+    messages: Vec<String>,
+    connected: bool,
+    /// Set when the backend refused the join or cut the viewer off
+    error: Option<String>,
+}
+
+enum TranscriptAction {
+    Connected,
+    History(Vec<String>),
+    Output(String),
+    Refused(String),
+    Closed,
+}
+
+impl Reducible for Transcript {
+    type Action = TranscriptAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut next = (*self).clone();
+        match action {
+            TranscriptAction::Connected => {
+                next.connected = true;
+                next.error = None;
+            }
+            // A rejoin replays the whole history again
+            TranscriptAction::History(messages) => next.messages = messages,
+            TranscriptAction::Output(message) => next.messages.push(message),
+            TranscriptAction::Refused(message) => {
+                next.connected = false;
+                next.error = Some(message);
+            }
+            TranscriptAction::Closed => next.connected = false,
+        }
+        Rc::new(next)
+    }
+}
+
+async fn fetch_info(token: &str) -> Result<SharedSessionInfo, String> {
+    let api_endpoint = utils::api_url(&format!("/api/shared/{}", token));
+    let response = Request::get(&api_endpoint)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if response.status() == 404 {
+        return Err("This link is invalid, has expired or was revoked.".to_string());
+    }
+    if !response.ok() {
+        return Err(format!("Could not open this link ({})", response.status()));
+    }
+    response
+        .json::<SharedSessionInfo>()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Join the session behind `token` and feed what arrives into `dispatch`.
+fn join(token: String, password: Option<String>, dispatch: UseReducerDispatcher<Transcript>) {
+    spawn_local(async move {
+        let ws_endpoint = utils::ws_url(SharedEndpoint::PATH);
+        let conn = match ws_bridge::yew_client::connect_to::<SharedEndpoint>(&ws_endpoint) {
+            Ok(conn) => conn,
+            Err(e) => {
+                dispatch.dispatch(TranscriptAction::Refused(format!("{:?}", e)));
+                return;
+            }
+        };
+        let (mut sender, mut receiver) = conn.split();
+        if sender
+            .send(SharedClientToServer::Join { token, password })
+            .await
+            .is_err()
+        {
+            dispatch.dispatch(TranscriptAction::Refused(
+                "Could not connect to the session".to_string(),
+            ));
+            return;
+        }
+        dispatch.dispatch(TranscriptAction::Connected);
+
+        while let Some(result) = receiver.recv().await {
+            match result {
+                Ok(ServerToClient::ClaudeOutput { content, .. }) => {
+                    dispatch.dispatch(TranscriptAction::Output(content.to_string()));
+                }
+                Ok(ServerToClient::HistoryBatch { messages }) => {
+                    dispatch.dispatch(TranscriptAction::History(
+                        messages.into_iter().map(|v| v.to_string()).collect(),
+                    ));
+                }
+                Ok(ServerToClient::Error { message }) => {
+                    dispatch.dispatch(TranscriptAction::Refused(message));
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    log::error!("Shared session socket error: {:?}", e);
+                    break;
+                }
+            }
+        }
+        // Keep the sending half open until the stream ends
+        drop(sender);
+        dispatch.dispatch(TranscriptAction::Closed);
+    });
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SharedSessionPageProps {
+    pub token: String,
+}
+
+#[function_component(SharedSessionPage)]
+pub fn shared_session_page(props: &SharedSessionPageProps) -> Html {
+    let info = use_state(|| None::<SharedSessionInfo>);
+    let load_error = use_state(|| None::<String>);
+    let password = use_state(String::new);
+    let transcript = use_reducer(Transcript::default);
+    let messages_ref = use_node_ref();
+
+    {
+        let info = info.clone();
+        let load_error = load_error.clone();
+        let dispatch = transcript.dispatcher();
+        use_effect_with(props.token.clone(), move |token| {
+            let token = token.clone();
+            spawn_local(async move {
+                match fetch_info(&token).await {
+                    Ok(loaded) => {
+                        if !loaded.requires_password {
+                            join(token, None, dispatch);
+                        }
+                        info.set(Some(loaded));
+                    }
+                    Err(message) => load_error.set(Some(message)),
+                }
+            });
+            || ()
+        });
+    }
+
+    // Follow the live output
+    {
+        let messages_ref = messages_ref.clone();
+        use_effect_with(transcript.messages.len(), move |_| {
+            if let Some(el) = messages_ref.cast::<web_sys::Element>() {
+                el.set_scroll_top(el.scroll_height());
+            }
+            || ()
+        });
+    }
+
+    let on_password = {
+        let password = password.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            password.set(input.value());
+        })
+    };
+
+    let on_unlock = {
+        let token = props.token.clone();
+        let password = password.clone();
+        let dispatch = transcript.dispatcher();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            join(token.clone(), Some((*password).clone()), dispatch.clone());
+        })
+    };
+
+    let on_reconnect = {
+        let token = props.token.clone();
+        let password = password.clone();
+        let dispatch = transcript.dispatcher();
+        Callback::from(move |_| {
+            let password = Some((*password).clone()).filter(|p| !p.is_empty());
+            join(token.clone(), password, dispatch.clone());
+        })
+    };
+
+    let body = if let Some(message) = (*load_error).clone() {
+        html! { <div class="shared-session-notice">{ message }</div> }
+    } else if let Some(info) = (*info).clone() {
+        let locked = info.requires_password && !transcript.connected;
+        let groups = group_messages(&transcript.messages);
+        html! {
+            <>
+                if let Some(error) = &transcript.error {
+                    <div class="shared-session-error">{ error }</div>
+                }
+                if locked && transcript.messages.is_empty() {
+                    <form class="shared-session-password" onsubmit={on_unlock}>
+                        <label for="shared-password">{ "This link is password protected" }</label>
+                        <input
+                            type="password"
+                            id="shared-password"
+                            value={(*password).clone()}
+                            oninput={on_password}
+                            autofocus=true
+                        />
+                        <button type="submit">{ "View session" }</button>
+                    </form>
+                } else {
+                    <div class="session-view-messages" ref={messages_ref}>
+                        { for groups.into_iter().map(|group| html! {
+                            <MessageGroupRenderer group={group} agent_type={info.agent_type} />
+                        }) }
+                    </div>
+                    if !transcript.connected && transcript.error.is_none() {
+                        <div class="shared-session-footer">
+                            <span>{ "Disconnected" }</span>
+                            <button onclick={on_reconnect}>{ "Reconnect" }</button>
+                        </div>
+                    }
+                }
+            </>
+        }
+    } else {
+        html! { <div class="shared-session-notice">{ "Loading..." }</div> }
+    };
+
+    let title = info
+        .as_ref()
+        .map(|i| i.session_name.clone())
+        .unwrap_or_else(|| "Shared session".to_string());
+    let expires = info
+        .as_ref()
+        .map(|i| utils::format_timestamp(&i.expires_at));
+
+    html! {
+        <div class="shared-session">
+            <header class="shared-session-header">
+                <h1>{ title }</h1>
+                <span class="shared-session-badge">
+                    { if transcript.connected { "Read-only · live" } else { "Read-only" } }
+                </span>
+                if let Some(expires) = expires {
+                    <span class="shared-session-expiry">{ format!("Link expires {}", expires) }</span>
+                }
+            </header>
+            { body }
+        </div>
+    }
+}
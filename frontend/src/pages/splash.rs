@@ -1,21 +1,21 @@
 use crate::{utils, VERSION};
 use gloo::console;
 use gloo_net::http::Request;
-use shared::AppConfig;
+use shared::{AppConfig, LoginProvider};
 use yew::prelude::*;
 
 #[function_component(SplashPage)]
 pub fn splash_page() -> Html {
-    let splash_text = use_state(|| None::<String>);
+    let config = use_state(|| None::<AppConfig>);
 
     {
-        let splash_text = splash_text.clone();
+        let config = config.clone();
         use_effect_with((), move |_| {
             wasm_bindgen_futures::spawn_local(async move {
                 let api_endpoint = utils::api_url("/api/config");
                 if let Ok(response) = Request::get(&api_endpoint).send().await {
-                    if let Ok(config) = response.json::<AppConfig>().await {
-                        splash_text.set(config.splash_text);
+                    if let Ok(app_config) = response.json::<AppConfig>().await {
+                        config.set(Some(app_config));
                     }
                 }
             });
@@ -23,25 +23,59 @@ pub fn splash_page() -> Html {
         });
     }
 
-    match (*splash_text).clone() {
-        Some(text) => minimal_splash(text),
-        None => marketing_splash(),
+    let buttons = match &*config {
+        Some(config) => login_buttons(&config.login_providers),
+        // Wait for the config rather than offer a provider the server lacks
+        None => html! {},
+    };
+
+    match config.as_ref().and_then(|c| c.splash_text.clone()) {
+        Some(text) => minimal_splash(text, buttons),
+        None => marketing_splash(buttons),
     }
 }
 
-fn login_callback() -> Callback<MouseEvent> {
-    Callback::from(|_| {
-        console::log!("Redirecting to Google OAuth...");
+fn login_callback(login_url: String) -> Callback<MouseEvent> {
+    Callback::from(move |_| {
+        console::log!("Redirecting to sign-in:", login_url.as_str());
         let window = web_sys::window().expect("no global `window` exists");
         let location = window.location();
-        let auth_url = utils::api_url("/api/auth/google");
+        let auth_url = utils::api_url(&login_url);
         let _ = location.set_href(&auth_url);
     })
 }
 
-fn minimal_splash(heading: String) -> Html {
-    let handle_login = login_callback();
+/// One button per login provider; a plain "Sign in" when the server has
+/// none (dev mode signs everyone in as the test user)
+pub(crate) fn login_buttons(providers: &[LoginProvider]) -> Html {
+    if providers.is_empty() {
+        return html! {
+            <button class="login-button" onclick={login_callback("/api/auth/dev-login".to_string())}>
+                { "Sign in" }
+            </button>
+        };
+    }
+
+    html! {
+        <div class="login-buttons">
+            { for providers.iter().map(|provider| {
+                let initial = provider.name.chars().next().unwrap_or('?').to_uppercase().to_string();
+                html! {
+                    <button
+                        key={provider.id.clone()}
+                        class="login-button"
+                        onclick={login_callback(provider.login_url.clone())}
+                    >
+                        <span class="provider-icon">{ initial }</span>
+                        { format!(" Sign in with {}", provider.name) }
+                    </button>
+                }
+            }) }
+        </div>
+    }
+}
 
+fn minimal_splash(heading: String, login_buttons: Html) -> Html {
     html! {
         <div class="splash-container">
             <div class="splash-content splash-minimal">
@@ -49,10 +83,7 @@ fn minimal_splash(heading: String) -> Html {
                     <h1>{ heading }</h1>
                 </div>
 
-                <button class="login-button" onclick={handle_login}>
-                    <span class="google-icon">{ "G" }</span>
-                    { " Sign in with Google" }
-                </button>
+                { login_buttons }
 
                 <div class="splash-footer">
                     <span class="version">{ format!("v{}", VERSION) }</span>
@@ -78,9 +109,7 @@ fn minimal_splash(heading: String) -> Html {
     }
 }
 
-fn marketing_splash() -> Html {
-    let handle_login = login_callback();
-
+fn marketing_splash(login_buttons: Html) -> Html {
     html! {
         <div class="splash-container">
             <div class="splash-content">
@@ -176,14 +205,11 @@ fn marketing_splash() -> Html {
                     </div>
                     <div class="feature">
                         <h3>{ "Secure" }</h3>
-                        <p>{ "Google OAuth authentication and encrypted connections" }</p>
+                        <p>{ "Single sign-on through Google or your own OpenID Connect provider, over encrypted connections" }</p>
                     </div>
                 </div>
 
-                <button class="login-button" onclick={handle_login}>
-                    <span class="google-icon">{ "G" }</span>
-                    { " Sign in with Google" }
-                </button>
+                { login_buttons }
 
                 <div class="splash-footer">
                     <span class="version">{ format!("v{}", VERSION) }</span>
@@ -0,0 +1,44 @@
+//! Personal usage page
+//!
+//! The signed-in user's daily cost and tokens, broken down by model, host,
+//! repository, agent or scheduled task.
+
+use crate::components::UsageCharts;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct UsagePageProps {
+    pub on_close: Callback<()>,
+}
+
+#[function_component(UsagePage)]
+pub fn usage_page(props: &UsagePageProps) -> Html {
+    let go_back = {
+        let on_close = props.on_close.clone();
+        Callback::from(move |_| on_close.emit(()))
+    };
+
+    html! {
+        <div class="settings-container">
+            <header class="settings-header">
+                <button class="header-button" onclick={go_back}>
+                    { "< Back" }
+                </button>
+                <h1>{ "Usage" }</h1>
+                <span />
+            </header>
+
+            <main class="settings-content">
+                <section class="tokens-section">
+                    <div class="section-header">
+                        <h2>{ "Your Usage" }</h2>
+                        <p class="section-description">
+                            { "What your sessions cost per day (UTC). New turns appear within a few minutes." }
+                        </p>
+                    </div>
+                    <UsageCharts />
+                </section>
+            </main>
+        </div>
+    }
+}
@@ -51,6 +51,17 @@ pub fn format_dollars(amount: f64) -> String {
     format!("${}.{}", with_commas, decimal)
 }
 
+/// Format token count with K/M suffix for readability
+pub fn format_tokens(count: i64) -> String {
+    if count >= 1_000_000 {
+        format!("{:.1}M", count as f64 / 1_000_000.0)
+    } else if count >= 1_000 {
+        format!("{:.1}K", count as f64 / 1_000.0)
+    } else {
+        count.to_string()
+    }
+}
+
 /// Format a timestamp string for display (e.g., "2026-01-15 14:30")
 pub fn format_timestamp(ts: &str) -> String {
     let date = js_sys::Date::new(&ts.into());
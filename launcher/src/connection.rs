@@ -1,4 +1,5 @@
 use crate::config::{self, ExpectedSession};
+use crate::local_sessions;
 use crate::process_manager::{ProcessManager, SessionExited, SpawnParams};
 use crate::scheduler::Scheduler;
 use shared::{LauncherEndpoint, LauncherToServer, ServerToLauncher};
@@ -428,13 +429,18 @@ async fn handle_message(
             session_name,
             claude_args,
             agent_type,
+            resume_session_id: adopt_session_id,
+            fork,
             ..
         } => {
-            // Check if this is a scheduled launch or a relaunch of an expected session
-            let (resume_session_id, scheduled_task_id, is_scheduled) = if let Some((
-                resume_id,
-                task_id,
-            )) =
+            // Check if this adopts a local session, forks a session, is a
+            // scheduled launch or a relaunch of an expected session
+            let (resume_session_id, scheduled_task_id, is_scheduled) = if adopt_session_id.is_some()
+            {
+                (adopt_session_id, None, false)
+            } else if fork.is_some() {
+                (None, None, false)
+            } else if let Some((resume_id, task_id)) =
                 scheduler.get_pending_launch_info(&request_id)
             {
                 (resume_id, Some(task_id), true)
@@ -460,6 +466,7 @@ async fn handle_message(
                     agent_type,
                     scheduled_task_id,
                     resume_session_id,
+                    fork,
                 })
                 .await;
 
@@ -513,9 +520,38 @@ async fn handle_message(
                 }
             };
 
+            let spawned = matches!(
+                response,
+                LauncherToServer::LaunchSessionResult { success: true, .. }
+            );
             if ws_sender.send(response).await.is_err() {
                 warn!("Failed to send launch session result");
             }
+
+            // Backfill the adopted session's earlier conversation
+            if let (Some(session_id), true) = (adopt_session_id, spawned) {
+                let history =
+                    tokio::task::spawn_blocking(move || local_sessions::read_history(session_id))
+                        .await;
+                match history {
+                    Ok(Ok(messages)) => {
+                        info!(
+                            "Sending {} history messages for adopted session {}",
+                            messages.len(),
+                            session_id
+                        );
+                        let msg = LauncherToServer::SessionHistory {
+                            session_id,
+                            messages,
+                        };
+                        if ws_sender.send(msg).await.is_err() {
+                            warn!("Failed to send session history");
+                        }
+                    }
+                    Ok(Err(e)) => warn!("Could not read history of {}: {}", session_id, e),
+                    Err(e) => warn!("History reader for {} panicked: {}", session_id, e),
+                }
+            }
         }
         ServerToLauncher::StopSession { session_id } => {
             info!("Stop request for session {}", session_id);
@@ -534,6 +570,29 @@ async fn handle_message(
                 warn!("Failed to send list directories result");
             }
         }
+        ServerToLauncher::ListLocalSessions { request_id } => {
+            // Scanning transcripts touches many files, so keep it off the event loop
+            let running = process_manager.running_session_ids();
+            let result =
+                tokio::task::spawn_blocking(move || local_sessions::list_local_sessions(&running))
+                    .await
+                    .unwrap_or_else(|e| Err(e.to_string()));
+            let response = match result {
+                Ok(sessions) => LauncherToServer::ListLocalSessionsResult {
+                    request_id,
+                    sessions,
+                    error: None,
+                },
+                Err(e) => LauncherToServer::ListLocalSessionsResult {
+                    request_id,
+                    sessions: Vec::new(),
+                    error: Some(e),
+                },
+            };
+            if ws_sender.send(response).await.is_err() {
+                warn!("Failed to send local sessions result");
+            }
+        }
         ServerToLauncher::ScheduleSync { tasks } => {
             info!("Received ScheduleSync with {} task(s)", tasks.len());
             scheduler.update_tasks(tasks);
@@ -0,0 +1,290 @@
+//! Discovery of Claude Code sessions started outside the portal.
+//!
+//! Claude Code keeps one JSONL transcript per session under
+//! `~/.claude/projects/<encoded project path>/<session id>.jsonl` (or under
+//! `$CLAUDE_CONFIG_DIR/projects` when that is set). The launcher lists the
+//! recent ones so a user can adopt a terminal session from the launch dialog,
+//! and reads an adopted session's transcript back to backfill its history.
+
+use shared::{HistoryMessage, LocalSession};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use uuid::Uuid;
+
+/// Sessions listed per project directory, newest first
+const MAX_PER_PROJECT: usize = 5;
+/// Sessions listed in total
+const MAX_SESSIONS: usize = 50;
+/// Transcripts untouched for longer than this are not offered
+const MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// Transcript lines read while looking for the directory and first prompt
+const HEADER_LINES: usize = 200;
+const SUMMARY_CHARS: usize = 120;
+
+/// Directory holding the per-project transcript folders.
+fn projects_dir() -> Option<PathBuf> {
+    match std::env::var_os("CLAUDE_CONFIG_DIR") {
+        Some(dir) => Some(PathBuf::from(dir).join("projects")),
+        None => dirs::home_dir().map(|home| home.join(".claude").join("projects")),
+    }
+}
+
+/// Recent local sessions, skipping any in `exclude` (those already running
+/// under this launcher).
+pub fn list_local_sessions(exclude: &[Uuid]) -> Result<Vec<LocalSession>, String> {
+    let Some(dir) = projects_dir() else {
+        return Err("Could not determine the home directory".to_string());
+    };
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    scan_projects(&dir, exclude, SystemTime::now()).map_err(|e| e.to_string())
+}
+
+fn scan_projects(
+    dir: &Path,
+    exclude: &[Uuid],
+    now: SystemTime,
+) -> std::io::Result<Vec<LocalSession>> {
+    let mut sessions = Vec::new();
+    for project in std::fs::read_dir(dir)?.flatten() {
+        if !project.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            continue;
+        }
+        let Ok(files) = std::fs::read_dir(project.path()) else {
+            continue;
+        };
+
+        let mut transcripts: Vec<(SystemTime, Uuid, PathBuf)> = files
+            .flatten()
+            .filter_map(|file| {
+                let path = file.path();
+                if path.extension()? != "jsonl" {
+                    return None;
+                }
+                let session_id: Uuid = path.file_stem()?.to_str()?.parse().ok()?;
+                let modified = file.metadata().ok()?.modified().ok()?;
+                let fresh = now.duration_since(modified).unwrap_or_default() <= MAX_AGE;
+                (fresh && !exclude.contains(&session_id)).then_some((modified, session_id, path))
+            })
+            .collect();
+        transcripts.sort_by_key(|t| std::cmp::Reverse(t.0));
+
+        sessions.extend(
+            transcripts
+                .into_iter()
+                .filter_map(|(modified, session_id, path)| {
+                    let mut session = read_header(&path)?;
+                    session.session_id = session_id;
+                    session.last_activity = chrono::DateTime::<chrono::Utc>::from(modified)
+                        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+                    Some(session)
+                })
+                .take(MAX_PER_PROJECT),
+        );
+    }
+
+    // RFC 3339 UTC timestamps sort chronologically as strings
+    sessions.sort_by(|a, b| b.last_activity.cmp(&a.last_activity));
+    sessions.truncate(MAX_SESSIONS);
+    Ok(sessions)
+}
+
+/// Working directory, branch and summary from the start of a transcript.
+/// Returns `None` for transcripts with no conversation in them.
+fn read_header(path: &Path) -> Option<LocalSession> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut working_directory = None;
+    let mut git_branch = None;
+    let mut summary = None;
+    let mut first_prompt = None;
+
+    for line in BufReader::new(file).lines().take(HEADER_LINES) {
+        let Ok(line) = line else { break };
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        let field = |key: &str| entry.get(key).and_then(|v| v.as_str());
+
+        if field("type") == Some("summary") && summary.is_none() {
+            summary = field("summary").map(str::to_string);
+        }
+        if working_directory.is_none() {
+            working_directory = field("cwd").map(str::to_string);
+        }
+        if git_branch.is_none() {
+            git_branch = field("gitBranch")
+                .filter(|b| !b.is_empty())
+                .map(str::to_string);
+        }
+        if first_prompt.is_none() && is_conversation(&entry) && field("type") == Some("user") {
+            first_prompt = entry
+                .pointer("/message/content")
+                .and_then(|c| c.as_str())
+                // Slash commands and hook output are wrapped in tags
+                .filter(|text| !text.trim_start().starts_with('<'))
+                .map(str::to_string);
+        }
+        if working_directory.is_some() && git_branch.is_some() && first_prompt.is_some() {
+            break;
+        }
+    }
+
+    Some(LocalSession {
+        session_id: Uuid::nil(),
+        working_directory: working_directory?,
+        last_activity: String::new(),
+        git_branch,
+        summary: summary.or(first_prompt).map(|text| truncate(&text)),
+    })
+}
+
+fn truncate(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    if line.chars().count() > SUMMARY_CHARS {
+        let cut: String = line.chars().take(SUMMARY_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        line.to_string()
+    }
+}
+
+/// Main-thread user and assistant turns, as opposed to subagent sidechains,
+/// injected meta messages and bookkeeping lines
+fn is_conversation(entry: &serde_json::Value) -> bool {
+    let flag = |key: &str| entry.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+    matches!(
+        entry.get("type").and_then(|t| t.as_str()),
+        Some("user" | "assistant")
+    ) && entry.get("message").is_some()
+        && !flag("isSidechain")
+        && !flag("isMeta")
+}
+
+/// The conversation in a session's transcript, converted to the stream-json
+/// messages the proxy would have forwarded had the session run in the portal.
+pub fn read_history(session_id: Uuid) -> anyhow::Result<Vec<HistoryMessage>> {
+    let dir = projects_dir().ok_or_else(|| anyhow::anyhow!("No home directory"))?;
+    let file_name = format!("{}.jsonl", session_id);
+    for project in std::fs::read_dir(&dir)?.flatten() {
+        let path = project.path().join(&file_name);
+        if path.is_file() {
+            return parse_history(&path);
+        }
+    }
+    anyhow::bail!("No transcript for session {}", session_id)
+}
+
+fn parse_history(path: &Path) -> anyhow::Result<Vec<HistoryMessage>> {
+    let file = std::fs::File::open(path)?;
+    let mut messages = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line?) else {
+            continue;
+        };
+        if !is_conversation(&entry) {
+            continue;
+        }
+        messages.push(HistoryMessage {
+            content: serde_json::json!({
+                "type": entry["type"],
+                "message": entry["message"],
+                "session_id": entry.get("sessionId"),
+                "uuid": entry.get("uuid"),
+            }),
+            timestamp: entry
+                .get("timestamp")
+                .and_then(|t| t.as_str())
+                .map(str::to_string),
+        });
+    }
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const SESSION: &str = "0b6c3f7e-57a2-4c1f-9d3e-2f4a8b1c9d10";
+
+    /// A projects directory with one transcript for `SESSION`.
+    fn write_transcript(lines: &[serde_json::Value]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("local-sessions-{}", Uuid::new_v4()));
+        let project = root.join("-home-dev-app");
+        std::fs::create_dir_all(&project).unwrap();
+        let mut file = std::fs::File::create(project.join(format!("{}.jsonl", SESSION))).unwrap();
+        for line in lines {
+            writeln!(file, "{}", line).unwrap();
+        }
+        root
+    }
+
+    fn transcript() -> Vec<serde_json::Value> {
+        vec![
+            serde_json::json!({"type": "file-history-snapshot", "messageId": "x"}),
+            serde_json::json!({
+                "type": "user", "cwd": "/home/dev/app", "gitBranch": "main",
+                "sessionId": SESSION, "uuid": "u1", "timestamp": "2026-03-20T09:30:00.000Z",
+                "message": {"role": "user", "content": "Fix the flaky migration test"}
+            }),
+            serde_json::json!({
+                "type": "assistant", "cwd": "/home/dev/app", "isSidechain": true,
+                "message": {"role": "assistant", "content": [{"type": "text", "text": "subagent"}]}
+            }),
+            serde_json::json!({
+                "type": "assistant", "cwd": "/home/dev/app", "sessionId": SESSION, "uuid": "a1",
+                "timestamp": "2026-03-20T09:30:05.000Z",
+                "message": {"role": "assistant", "content": [{"type": "text", "text": "On it."}]}
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_scan_reads_directory_and_first_prompt() {
+        let root = write_transcript(&transcript());
+        let sessions = scan_projects(&root, &[], SystemTime::now()).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_id.to_string(), SESSION);
+        assert_eq!(sessions[0].working_directory, "/home/dev/app");
+        assert_eq!(sessions[0].git_branch.as_deref(), Some("main"));
+        assert_eq!(
+            sessions[0].summary.as_deref(),
+            Some("Fix the flaky migration test")
+        );
+
+        // Running and stale sessions are not offered
+        let running = [SESSION.parse().unwrap()];
+        assert!(scan_projects(&root, &running, SystemTime::now())
+            .unwrap()
+            .is_empty());
+        let later = SystemTime::now() + MAX_AGE + Duration::from_secs(60);
+        assert!(scan_projects(&root, &[], later).unwrap().is_empty());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_history_skips_sidechains_and_bookkeeping() {
+        let root = write_transcript(&transcript());
+        let path = root
+            .join("-home-dev-app")
+            .join(format!("{}.jsonl", SESSION));
+        let history = parse_history(&path).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].content["type"], "user");
+        assert_eq!(history[0].content["session_id"], SESSION);
+        assert_eq!(
+            history[1].timestamp.as_deref(),
+            Some("2026-03-20T09:30:05.000Z")
+        );
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_truncate_summary() {
+        assert_eq!(truncate("short\nsecond line"), "short");
+        let long = "x".repeat(SUMMARY_CHARS + 10);
+        assert_eq!(truncate(&long).chars().count(), SUMMARY_CHARS + 1);
+    }
+}
@@ -1,5 +1,6 @@
 mod config;
 mod connection;
+mod local_sessions;
 mod pastebin;
 mod process_manager;
 mod scheduler;
@@ -107,6 +108,10 @@ async fn main() -> anyhow::Result<()> {
             tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()),
         )
         .with(tracing_subscriber::fmt::layer())
+        .with(portal_telemetry::layer(
+            "portal-launcher",
+            env!("CARGO_PKG_VERSION"),
+        ))
         .init();
 
     // Handle subcommands before the daemon startup path
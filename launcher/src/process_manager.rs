@@ -29,6 +29,7 @@ pub struct SpawnParams {
     pub agent_type: shared::AgentType,
     pub scheduled_task_id: Option<Uuid>,
     pub resume_session_id: Option<Uuid>,
+    pub fork: Option<shared::SessionFork>,
 }
 
 pub struct ProcessManager {
@@ -101,9 +102,10 @@ impl ProcessManager {
             );
         }
 
-        let (session_id, resume) = match params.resume_session_id {
-            Some(id) => (id, true),
-            None => (Uuid::new_v4(), false),
+        let (session_id, resume) = match (params.resume_session_id, &params.fork) {
+            (Some(id), _) => (id, true),
+            (None, Some(fork)) => (fork.session_id, false),
+            (None, None) => (Uuid::new_v4(), false),
         };
         let default_name = {
             let hostname = hostname::get()
@@ -140,8 +142,9 @@ impl ProcessManager {
         let cancel = CancellationToken::new();
         let cancel_clone = cancel.clone();
 
+        let fork = params.fork;
         let handle = tokio::spawn(async move {
-            let exit_code = run_session_task(proxy_config, cancel_clone).await;
+            let exit_code = run_session_task(proxy_config, fork, cancel_clone).await;
             let _ = exit_tx.send(SessionExited {
                 session_id,
                 exit_code,
@@ -190,10 +193,12 @@ impl ProcessManager {
     }
 }
 
-/// Run a single proxy session as an in-process task.
+/// Run a single proxy session as an in-process task. A fork only applies to
+/// the first Claude process; retries start fresh.
 /// Returns an exit code: Some(0) for normal exit, Some(1) for error, None for abort.
 async fn run_session_task(
     mut config: ProxySessionConfig,
+    mut fork: Option<shared::SessionFork>,
     cancel: CancellationToken,
 ) -> Option<i32> {
     loop {
@@ -205,6 +210,7 @@ async fn run_session_task(
             claude_path: None,
             extra_args: config.claude_args.clone(),
             agent_type: config.agent_type,
+            fork: fork.take(),
         };
 
         let mut claude_session = match ClaudeSession::new(claude_config).await {
@@ -215,7 +221,7 @@ async fn run_session_task(
             }
         };
 
-        let (input_tx, mut input_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+        let (input_tx, mut input_rx) = tokio::sync::mpsc::unbounded_channel();
 
         let result = tokio::select! {
             r = run_connection_loop(&config, &mut claude_session, input_tx, &mut input_rx) => r,
@@ -286,6 +286,8 @@ mod tests {
             enabled: true,
             max_runtime_minutes: 30,
             last_session_id: None,
+            permission_timeout_seconds: None,
+            permission_timeout_action: Default::default(),
         }
     }
 
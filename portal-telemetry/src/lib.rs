@@ -0,0 +1,244 @@
+//! OpenTelemetry tracing for Claude Portal
+//!
+//! A `tracing` layer, built on `tracing-opentelemetry`, that exports spans
+//! through the OpenTelemetry SDK's batch processor as OTLP/HTTP to the
+//! collector named by `OTEL_EXPORTER_OTLP_ENDPOINT`. A span joins a trace
+//! started elsewhere through [`set_parent`], and [`current_traceparent`] gives
+//! the context to put into outgoing messages, so one input can be followed
+//! across the backend and proxy.
+
+use opentelemetry::trace::{
+    SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState, TracerProvider as _,
+};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use shared::trace::TraceContext;
+use std::time::Duration;
+use tracing::{Metadata, Subscriber};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Collector base URL; spans are posted to `<endpoint>/v1/traces`
+pub const ENDPOINT_VAR: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+/// Full traces URL, taking precedence over [`ENDPOINT_VAR`]
+pub const TRACES_ENDPOINT_VAR: &str = "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT";
+/// Overrides the service name each binary reports
+pub const SERVICE_NAME_VAR: &str = "OTEL_SERVICE_NAME";
+
+/// Longest one export request may take
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Crates the exporter itself runs on. Their spans are not exported, which
+/// would feed the exporter its own output.
+const EXPORTER_TARGETS: [&str; 6] = ["opentelemetry", "reqwest", "hyper", "h2", "tower", "rustls"];
+
+/// The traces URL from the standard OTLP environment variables
+fn traces_endpoint(traces: Option<String>, base: Option<String>) -> Option<String> {
+    let non_empty = |v: Option<String>| v.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    non_empty(traces)
+        .or_else(|| non_empty(base).map(|base| format!("{}/v1/traces", base.trim_end_matches('/'))))
+}
+
+fn is_exporter_internal(metadata: &Metadata<'_>) -> bool {
+    let target = metadata.target();
+    EXPORTER_TARGETS.iter().any(|crate_name| {
+        target
+            .strip_prefix(crate_name)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+    })
+}
+
+/// A layer exporting spans to the configured collector, or `None` when no
+/// OTLP endpoint is set. `Option<Layer>` is itself a layer, so the result can
+/// be added to a subscriber either way.
+pub fn layer<S>(service_name: &str, service_version: &str) -> Option<impl Layer<S>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let endpoint = traces_endpoint(
+        std::env::var(TRACES_ENDPOINT_VAR).ok(),
+        std::env::var(ENDPOINT_VAR).ok(),
+    )?;
+    let service_name = std::env::var(SERVICE_NAME_VAR)
+        .ok()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| service_name.to_string());
+
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .with_timeout(EXPORT_TIMEOUT)
+        .build();
+    let exporter = match exporter {
+        Ok(exporter) => exporter,
+        Err(e) => {
+            eprintln!("Failed to start OTLP exporter: {}", e);
+            return None;
+        }
+    };
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder_empty()
+                .with_service_name(service_name)
+                .with_attribute(KeyValue::new(
+                    "service.version",
+                    service_version.to_string(),
+                ))
+                .build(),
+        )
+        .build();
+    Some(tracer_layer(&provider))
+}
+
+/// The `tracing` layer recording spans into `provider`
+fn tracer_layer<S>(provider: &SdkTracerProvider) -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    tracing_opentelemetry::layer()
+        .with_tracer(provider.tracer(env!("CARGO_PKG_NAME")))
+        .with_filter(filter_fn(|metadata| !is_exporter_internal(metadata)))
+}
+
+/// Make `span` continue the trace of a `traceparent` received in a message.
+/// Call it before the span is first entered; `None` or an invalid value
+/// leaves the span in the trace of whatever span it was opened in.
+pub fn set_parent(span: &tracing::Span, traceparent: Option<&str>) {
+    let Some(remote) = traceparent.and_then(TraceContext::parse) else {
+        return;
+    };
+    let parent = SpanContext::new(
+        TraceId::from_bytes(remote.trace_id),
+        SpanId::from_bytes(remote.span_id),
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    );
+    // Fails only without the layer, where there is no trace to join
+    let _ = span.set_parent(Context::new().with_remote_span_context(parent));
+}
+
+/// The `traceparent` of the current span, to carry in an outgoing message.
+/// `None` outside a span or when no exporting layer is installed.
+pub fn current_traceparent() -> Option<String> {
+    let context = tracing::Span::current().context();
+    let span = context.span();
+    let span_context = span.span_context();
+    span_context.is_valid().then(|| {
+        TraceContext {
+            trace_id: span_context.trace_id().to_bytes(),
+            span_id: span_context.span_id().to_bytes(),
+        }
+        .traceparent()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::Status;
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SpanData};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn collect(f: impl FnOnce()) -> Vec<SpanData> {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry().with(tracer_layer(&provider));
+        tracing::subscriber::with_default(subscriber, f);
+        exporter.get_finished_spans().unwrap()
+    }
+
+    #[test]
+    fn test_traces_endpoint() {
+        assert_eq!(traces_endpoint(None, None), None);
+        assert_eq!(
+            traces_endpoint(None, Some("http://localhost:4318/".into())).as_deref(),
+            Some("http://localhost:4318/v1/traces")
+        );
+        assert_eq!(
+            traces_endpoint(
+                Some("http://collector/traces".into()),
+                Some("http://localhost:4318".into())
+            )
+            .as_deref(),
+            Some("http://collector/traces")
+        );
+        assert_eq!(traces_endpoint(Some(" ".into()), None), None);
+    }
+
+    #[test]
+    fn test_spans_join_remote_and_local_parents() {
+        let remote = TraceContext::new_root();
+        let mut propagated = None;
+        let spans = collect(|| {
+            let outer = tracing::info_span!("outer", seq = 7);
+            set_parent(&outer, Some(&remote.traceparent()));
+            let _outer = outer.enter();
+            let inner = tracing::info_span!("inner");
+            let _inner = inner.enter();
+            tracing::error!("send failed");
+            propagated = current_traceparent();
+        });
+
+        // Spans are exported as they close, innermost first
+        assert_eq!(spans.len(), 2);
+        let (inner, outer) = (&spans[0], &spans[1]);
+        let trace_id = TraceId::from_bytes(remote.trace_id);
+        assert_eq!(outer.name, "outer");
+        assert_eq!(outer.span_context.trace_id(), trace_id);
+        assert_eq!(outer.parent_span_id, SpanId::from_bytes(remote.span_id));
+        assert_eq!(inner.span_context.trace_id(), trace_id);
+        assert_eq!(inner.parent_span_id, outer.span_context.span_id());
+        assert!(matches!(inner.status, Status::Error { .. }));
+        assert_eq!(inner.events[0].name, "send failed");
+        assert!(outer
+            .attributes
+            .iter()
+            .any(|kv| kv.key.as_str() == "seq" && kv.value == 7i64.into()));
+
+        let propagated = TraceContext::parse(&propagated.unwrap()).unwrap();
+        assert_eq!(propagated.trace_id, remote.trace_id);
+        assert_eq!(
+            SpanId::from_bytes(propagated.span_id),
+            inner.span_context.span_id()
+        );
+    }
+
+    #[test]
+    fn test_root_span_starts_a_trace() {
+        let spans = collect(|| {
+            let span = tracing::info_span!("root");
+            set_parent(&span, None);
+            set_parent(&span, Some("not a traceparent"));
+            let _entered = span.enter();
+        });
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].parent_span_id, SpanId::INVALID);
+        assert!(spans[0].span_context.is_valid());
+    }
+
+    #[test]
+    fn test_exporter_spans_are_not_traced() {
+        let spans = collect(|| {
+            let _span = tracing::info_span!(target: "hyper::client", "connect").entered();
+            let _span = tracing::info_span!(target: "hyperion", "app").entered();
+        });
+        let names: Vec<_> = spans.iter().map(|s| s.name.as_ref()).collect();
+        assert_eq!(names, ["app"]);
+    }
+
+    #[test]
+    fn test_no_context_without_layer() {
+        let span = tracing::info_span!("untracked");
+        set_parent(&span, Some(&TraceContext::new_root().traceparent()));
+        let _entered = span.enter();
+        assert_eq!(current_traceparent(), None);
+    }
+}
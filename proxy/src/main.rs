@@ -171,11 +171,22 @@ fn init_tracing(session_id_tag: Option<Uuid>, verbose: bool) {
         tracing_subscriber::registry()
             .with(env_filter)
             .with(fmt_layer)
+            .with(portal_telemetry::layer(
+                "portal-proxy",
+                env!("CARGO_PKG_VERSION"),
+            ))
             .init();
         tracing::info!(session_id = %sid, "Proxy starting with session tag");
     } else {
         // Interactive: human-readable format
-        tracing_subscriber::fmt().with_env_filter(env_filter).init();
+        tracing_subscriber::registry()
+            .with(env_filter)
+            .with(tracing_subscriber::fmt::layer())
+            .with(portal_telemetry::layer(
+                "portal-proxy",
+                env!("CARGO_PKG_VERSION"),
+            ))
+            .init();
     }
 }
 
@@ -545,7 +556,7 @@ async fn run_proxy_session(mut config: ProxySessionConfig) -> Result<()> {
         ui::print_started();
 
         // Create input channel (shared across reconnections)
-        let (input_tx, mut input_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+        let (input_tx, mut input_rx) = tokio::sync::mpsc::unbounded_channel();
 
         // Run the connection loop
         let result =
@@ -609,6 +620,7 @@ async fn create_claude_session(config: &ProxySessionConfig) -> Result<ClaudeSess
         claude_path: None,
         extra_args: config.claude_args.clone(),
         agent_type: config.agent_type,
+        fork: None,
     };
 
     if config.resume {
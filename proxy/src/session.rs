@@ -269,7 +269,9 @@ async fn handle_ws_text_message(
     };
 
     match server_msg {
-        ServerToProxy::ClaudeInput { content, send_mode } => {
+        ServerToProxy::ClaudeInput {
+            content, send_mode, ..
+        } => {
             let user_text = match &content {
                 serde_json::Value::String(s) => s.clone(),
                 other => other.to_string(),
@@ -287,6 +289,7 @@ async fn handle_ws_text_message(
             seq,
             content,
             send_mode,
+            ..
         } => {
             let user_text = match &content {
                 serde_json::Value::String(s) => s.clone(),
@@ -181,8 +181,10 @@ async fn run_shim_loop(
     let (output_line_tx, mut output_line_rx) =
         mpsc::unbounded_channel::<(u64, serde_json::Value)>();
 
-    // Channel for permission requests extracted from claude stdout
+    // Channel for permission traffic to the portal: requests extracted from
+    // claude stdout and answers given in VS Code
     let (perm_request_tx, mut perm_request_rx) = mpsc::unbounded_channel::<ProxyToServer>();
+    let local_answer_tx = perm_request_tx.clone();
 
     // Our stdout handle (for forwarding to VS Code)
     let our_stdout = Arc::new(Mutex::new(tokio::io::stdout()));
@@ -317,12 +319,32 @@ async fn run_shim_loop(
             // Check if this is a permission response from VS Code (for dedup tracking)
             if let Ok(value) = serde_json::from_str::<serde_json::Value>(&line) {
                 if value.get("type").and_then(|t| t.as_str()) == Some("control_response") {
-                    if let Some(request_id) = value.get("request_id").and_then(|r| r.as_str()) {
+                    if let Some(answer) = parse_local_permission_answer(&value) {
+                        let request_id = answer.request_id.as_str();
                         let mut perms = permissions_for_stdin.lock().await;
                         if let Some(state) = perms.get_mut(request_id) {
                             if matches!(state, PermissionState::Pending) {
                                 *state = PermissionState::Answered;
                                 debug!("Permission {} answered by VS Code (stdin)", request_id);
+                                if answer.decision.is_none() {
+                                    warn!(
+                                        "Could not read VS Code's decision on permission {}",
+                                        request_id
+                                    );
+                                }
+                                // Let the portal drop it from the queue and audit it,
+                                // even when all we know is that it was answered
+                                let (allow, reason) = match answer.decision {
+                                    Some(decision) => (Some(decision.allow), decision.reason),
+                                    None => (None, None),
+                                };
+                                let _ = local_answer_tx.send(
+                                    ProxyToServer::PermissionAnsweredLocally {
+                                        request_id: answer.request_id.clone(),
+                                        allow,
+                                        reason,
+                                    },
+                                );
                             } else {
                                 // Already answered by portal — still forward to claude
                                 // (claude handles duplicate gracefully)
@@ -412,6 +434,7 @@ async fn run_shim_loop(
                     let msg = ProxyToServer::SequencedOutput {
                         seq: p.seq,
                         content: p.content.clone(),
+                        traceparent: None,
                     };
                     if let Err(e) = conn.send(&msg).await {
                         error!("Failed to replay: {}", e);
@@ -614,7 +637,11 @@ async fn run_shim_connection(
 
             // Claude output ready to send to portal (seq was assigned at buffer push time)
             Some((seq, content)) = output_line_rx.recv() => {
-                let msg = ProxyToServer::SequencedOutput { seq, content };
+                let msg = ProxyToServer::SequencedOutput {
+                    seq,
+                    content,
+                    traceparent: None,
+                };
                 let mut ws = ws_write.lock().await;
                 if let Ok(json) = serde_json::to_string(&msg) {
                     if let Err(e) = ws.send(Message::Text(json.into())).await {
@@ -665,6 +692,67 @@ fn extract_user_text(value: &serde_json::Value) -> Option<String> {
     }
 }
 
+/// A permission answer VS Code wrote to claude's stdin, parsed from a
+/// control_response line.
+#[derive(Debug, PartialEq)]
+struct LocalPermissionAnswer {
+    request_id: String,
+    /// `None` when the line names the request but its decision can't be read
+    decision: Option<LocalDecision>,
+}
+
+#[derive(Debug, PartialEq)]
+struct LocalDecision {
+    allow: bool,
+    reason: Option<String>,
+}
+
+/// Parse a `control_response` written by VS Code to claude's stdin.
+///
+/// Expected format:
+/// `{"type":"control_response","response":{"subtype":"success","request_id":"...","response":{"behavior":"allow",...}}}`
+/// Error responses are treated as denials. Lines in another shape still
+/// yield the request ID (nested, or the older top-level `request_id`) so the
+/// request is known to be answered.
+fn parse_local_permission_answer(value: &serde_json::Value) -> Option<LocalPermissionAnswer> {
+    let payload = value.get("response");
+    let request_id = payload
+        .and_then(|p| p.get("request_id"))
+        .or_else(|| value.get("request_id"))
+        .and_then(|r| r.as_str())?
+        .to_string();
+    Some(LocalPermissionAnswer {
+        request_id,
+        decision: payload.and_then(parse_local_decision),
+    })
+}
+
+fn parse_local_decision(payload: &serde_json::Value) -> Option<LocalDecision> {
+    if payload.get("subtype").and_then(|s| s.as_str()) == Some("error") {
+        return Some(LocalDecision {
+            allow: false,
+            reason: payload
+                .get("error")
+                .and_then(|e| e.as_str())
+                .map(String::from),
+        });
+    }
+
+    let result = payload.get("response")?;
+    let allow = match result.get("behavior").and_then(|b| b.as_str())? {
+        "allow" => true,
+        "deny" => false,
+        _ => return None,
+    };
+    let reason = result
+        .get("message")
+        .and_then(|m| m.as_str())
+        .filter(|m| !m.is_empty())
+        .map(String::from);
+
+    Some(LocalDecision { allow, reason })
+}
+
 /// Build a ControlResponse from a portal PermissionResponse.
 /// Mirrors the logic in session.rs run_main_loop's permission handling.
 fn build_control_response(perm: &PermissionResponseData) -> ControlResponse {
@@ -698,3 +786,97 @@ fn build_control_response(perm: &PermissionResponseData) -> ControlResponse {
         ControlResponse::from_result(&perm.request_id, PermissionResult::deny(reason))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse(value: serde_json::Value) -> Option<LocalPermissionAnswer> {
+        parse_local_permission_answer(&value)
+    }
+
+    fn answer(request_id: &str, decision: Option<(bool, Option<&str>)>) -> LocalPermissionAnswer {
+        LocalPermissionAnswer {
+            request_id: request_id.to_string(),
+            decision: decision.map(|(allow, reason)| LocalDecision {
+                allow,
+                reason: reason.map(String::from),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_parse_local_permission_success() {
+        let allowed = parse(json!({
+            "type": "control_response",
+            "response": {
+                "subtype": "success",
+                "request_id": "req-1",
+                "response": {"behavior": "allow", "updatedInput": {}},
+            },
+        }));
+        assert_eq!(allowed, Some(answer("req-1", Some((true, None)))));
+
+        let denied = parse(json!({
+            "type": "control_response",
+            "response": {
+                "subtype": "success",
+                "request_id": "req-2",
+                "response": {"behavior": "deny", "message": "Not that file"},
+            },
+        }));
+        assert_eq!(
+            denied,
+            Some(answer("req-2", Some((false, Some("Not that file")))))
+        );
+    }
+
+    #[test]
+    fn test_parse_local_permission_error() {
+        let error = parse(json!({
+            "type": "control_response",
+            "response": {"subtype": "error", "request_id": "req-3", "error": "Cancelled"},
+        }));
+        assert_eq!(
+            error,
+            Some(answer("req-3", Some((false, Some("Cancelled")))))
+        );
+    }
+
+    #[test]
+    fn test_parse_local_permission_legacy_shape() {
+        // The request ID at the top level, with no nested decision
+        let legacy = parse(json!({
+            "type": "control_response",
+            "request_id": "req-4",
+            "behavior": "allow",
+        }));
+        assert_eq!(legacy, Some(answer("req-4", None)));
+    }
+
+    #[test]
+    fn test_parse_local_permission_malformed() {
+        // Named request, unreadable decision: still marks it answered
+        let unknown = parse(json!({
+            "type": "control_response",
+            "response": {
+                "subtype": "success",
+                "request_id": "req-5",
+                "response": {"behavior": "maybe"},
+            },
+        }));
+        assert_eq!(unknown, Some(answer("req-5", None)));
+        let no_decision = parse(json!({
+            "type": "control_response",
+            "response": {"subtype": "success", "request_id": "req-6"},
+        }));
+        assert_eq!(no_decision, Some(answer("req-6", None)));
+
+        assert_eq!(parse(json!({"type": "control_response"})), None);
+        assert_eq!(
+            parse(json!({"type": "control_response", "response": {"request_id": 7}})),
+            None
+        );
+    }
+}
@@ -45,6 +45,7 @@ pub struct DeviceCodeResponse {
 
 /// Request to launch a session via a launcher
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct LaunchRequest {
     pub working_directory: String,
     #[serde(default)]
@@ -53,10 +54,17 @@ pub struct LaunchRequest {
     pub claude_args: Vec<String>,
     #[serde(default)]
     pub agent_type: crate::AgentType,
+    /// Adopt this local Claude Code session (see `GET /api/launchers/{id}/local-sessions`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resume_session_id: Option<uuid::Uuid>,
+    /// Stop the session once it has cost this much (USD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cost_usd: Option<f64>,
 }
 
 /// Request body for device code creation
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct DeviceCodeRequest {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub hostname: Option<String>,
@@ -66,12 +74,14 @@ pub struct DeviceCodeRequest {
 
 /// Request body for polling device flow status
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct DeviceFlowPollRequest {
     pub device_code: String,
 }
 
 /// Response for device flow approve/deny actions
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct DeviceFlowActionResponse {
     pub success: bool,
     pub message: String,
@@ -79,6 +89,7 @@ pub struct DeviceFlowActionResponse {
 
 /// Request to update a user's admin/ban/voice settings (admin endpoint)
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct UpdateUserRequest {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub is_admin: Option<bool>,
@@ -95,20 +106,22 @@ pub struct UpdateUserRequest {
     pub ban_reason: Option<Option<String>>,
 }
 
-fn deserialize_double_option<'de, D>(deserializer: D) -> Result<Option<Option<String>>, D::Error>
+fn deserialize_double_option<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
 where
     D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
 {
     // If the field is present, deserialize its value (which may be null)
     Ok(Some(Option::deserialize(deserializer)?))
 }
 
-fn serialize_double_option<S>(
-    value: &Option<Option<String>>,
+fn serialize_double_option<S, T>(
+    value: &Option<Option<T>>,
     serializer: S,
 ) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
+    T: Serialize,
 {
     match value {
         None => serializer.serialize_none(),
@@ -118,6 +131,7 @@ where
 
 /// Request to add a member to a session
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct AddMemberRequest {
     pub email: String,
     pub role: String,
@@ -125,6 +139,7 @@ pub struct AddMemberRequest {
 
 /// Request to update a session member's role
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct UpdateMemberRoleRequest {
     pub role: String,
 }
@@ -162,6 +177,7 @@ impl PermissionAnswers {
 
 /// Response for GET /api/settings/sound
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct SoundSettingsResponse {
     pub sound_config: Option<serde_json::Value>,
 }
@@ -172,6 +188,7 @@ pub struct SoundSettingsResponse {
 
 /// Request to create a scheduled task
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CreateScheduledTaskRequest {
     pub name: String,
     pub cron_expression: String,
@@ -186,6 +203,15 @@ pub struct CreateScheduledTaskRequest {
     pub agent_type: crate::AgentType,
     #[serde(default = "default_max_runtime")]
     pub max_runtime_minutes: i32,
+    /// Resolve unanswered permission requests after this many seconds
+    /// (None = wait forever)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permission_timeout_seconds: Option<i32>,
+    #[serde(default)]
+    pub permission_timeout_action: PermissionTimeoutAction,
+    /// Share every run with this team; the caller must belong to it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team_id: Option<uuid::Uuid>,
 }
 
 fn default_timezone() -> String {
@@ -198,6 +224,7 @@ fn default_max_runtime() -> i32 {
 
 /// Request to update a scheduled task (all fields optional)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct UpdateScheduledTaskRequest {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -219,10 +246,28 @@ pub struct UpdateScheduledTaskRequest {
     pub enabled: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_runtime_minutes: Option<i32>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_double_option",
+        serialize_with = "serialize_double_option"
+    )]
+    pub permission_timeout_seconds: Option<Option<i32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permission_timeout_action: Option<PermissionTimeoutAction>,
+    /// `Some(None)` stops sharing the task with its team
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_double_option",
+        serialize_with = "serialize_double_option"
+    )]
+    pub team_id: Option<Option<uuid::Uuid>>,
 }
 
 /// Info about a scheduled task (returned by list/create endpoints)
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ScheduledTaskInfo {
     pub id: uuid::Uuid,
     pub name: String,
@@ -235,14 +280,1757 @@ pub struct ScheduledTaskInfo {
     pub agent_type: crate::AgentType,
     pub enabled: bool,
     pub max_runtime_minutes: i32,
+    #[serde(default)]
+    pub permission_timeout_seconds: Option<i32>,
+    #[serde(default)]
+    pub permission_timeout_action: PermissionTimeoutAction,
     pub last_session_id: Option<uuid::Uuid>,
     pub last_run_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    /// Team the task's runs are shared with
+    #[serde(default)]
+    pub team_id: Option<uuid::Uuid>,
+    /// False for a task another team member created; only its creator can
+    /// change it
+    #[serde(default = "default_true")]
+    pub owned: bool,
 }
 
 /// Response listing scheduled tasks
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ScheduledTaskListResponse {
     pub tasks: Vec<ScheduledTaskInfo>,
 }
+
+// =============================================================================
+// Permission Rules API Types
+// =============================================================================
+
+/// Outcome of a server-side permission rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionVerdict {
+    /// Answer the proxy with an approval without asking anyone
+    Allow,
+    /// Answer the proxy with a denial without asking anyone
+    Deny,
+    /// Always forward to the web UI, even if a lower-priority rule would match
+    Ask,
+}
+
+impl PermissionVerdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PermissionVerdict::Allow => "allow",
+            PermissionVerdict::Deny => "deny",
+            PermissionVerdict::Ask => "ask",
+        }
+    }
+}
+
+impl std::str::FromStr for PermissionVerdict {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(PermissionVerdict::Allow),
+            "deny" => Ok(PermissionVerdict::Deny),
+            "ask" => Ok(PermissionVerdict::Ask),
+            _ => Err(()),
+        }
+    }
+}
+
+/// What happens to a permission request nobody answers before its timeout
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionTimeoutAction {
+    /// Deny the tool call so the agent can carry on
+    #[default]
+    Deny,
+    /// Approve the tool call
+    Allow,
+    /// Keep waiting, but notify every member of the session
+    Escalate,
+}
+
+impl PermissionTimeoutAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PermissionTimeoutAction::Deny => "deny",
+            PermissionTimeoutAction::Allow => "allow",
+            PermissionTimeoutAction::Escalate => "escalate",
+        }
+    }
+}
+
+impl std::str::FromStr for PermissionTimeoutAction {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "deny" => Ok(PermissionTimeoutAction::Deny),
+            "allow" => Ok(PermissionTimeoutAction::Allow),
+            "escalate" => Ok(PermissionTimeoutAction::Escalate),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Shortest permission timeout accepted by the API
+pub const MIN_PERMISSION_TIMEOUT_SECONDS: i32 = 10;
+/// Longest permission timeout accepted by the API (one week)
+pub const MAX_PERMISSION_TIMEOUT_SECONDS: i32 = 7 * 24 * 3600;
+
+/// Check a requested permission timeout against the allowed range
+pub fn validate_permission_timeout(seconds: Option<i32>) -> Result<(), &'static str> {
+    match seconds {
+        Some(s)
+            if !(MIN_PERMISSION_TIMEOUT_SECONDS..=MAX_PERMISSION_TIMEOUT_SECONDS).contains(&s) =>
+        {
+            Err("Permission timeout must be between 10 seconds and 7 days")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Request to set a session's own permission timeout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdatePermissionTimeoutRequest {
+    /// None clears the session's setting (falls back to its scheduled task)
+    pub timeout_seconds: Option<i32>,
+    #[serde(default)]
+    pub action: PermissionTimeoutAction,
+}
+
+/// A session's effective permission timeout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PermissionTimeoutInfo {
+    /// None = requests wait for an answer indefinitely
+    pub timeout_seconds: Option<i32>,
+    pub action: PermissionTimeoutAction,
+    /// True when the value comes from the session's scheduled task default
+    pub inherited: bool,
+}
+
+/// Request to create a permission rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreatePermissionRuleRequest {
+    /// Restrict the rule to one session (None = all of the user's sessions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<uuid::Uuid>,
+    /// Tool name glob, e.g. "Bash", "Edit" or "mcp__github__*"
+    pub tool_name: String,
+    /// Glob matched against the file path of file tools (Read, Edit, Write, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_glob: Option<String>,
+    /// Regex matched against the command of Bash tool calls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_regex: Option<String>,
+    pub verdict: PermissionVerdict,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Request to update a permission rule (all fields optional)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdatePermissionRuleRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_double_option",
+        serialize_with = "serialize_double_option"
+    )]
+    pub path_glob: Option<Option<String>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_double_option",
+        serialize_with = "serialize_double_option"
+    )]
+    pub command_regex: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<PermissionVerdict>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_double_option",
+        serialize_with = "serialize_double_option"
+    )]
+    pub description: Option<Option<String>>,
+}
+
+/// Info about a permission rule (returned by list/create/update endpoints)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PermissionRuleInfo {
+    pub id: uuid::Uuid,
+    pub session_id: Option<uuid::Uuid>,
+    pub tool_name: String,
+    pub path_glob: Option<String>,
+    pub command_regex: Option<String>,
+    pub verdict: PermissionVerdict,
+    pub priority: i32,
+    pub description: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Response listing permission rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PermissionRuleListResponse {
+    pub rules: Vec<PermissionRuleInfo>,
+}
+
+// =============================================================================
+// Permission Decision Audit API Types
+// =============================================================================
+
+/// Who answered a permission request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionDecider {
+    /// A portal user answered from the web UI
+    User,
+    /// A server-side permission rule answered automatically
+    Policy,
+    /// Answered in the VS Code extension while running through the proxy shim
+    Vscode,
+    /// Resolved automatically after nobody answered in time
+    Timeout,
+}
+
+impl PermissionDecider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PermissionDecider::User => "user",
+            PermissionDecider::Policy => "policy",
+            PermissionDecider::Vscode => "vscode",
+            PermissionDecider::Timeout => "timeout",
+        }
+    }
+}
+
+impl std::str::FromStr for PermissionDecider {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user" => Ok(PermissionDecider::User),
+            "policy" => Ok(PermissionDecider::Policy),
+            "vscode" => Ok(PermissionDecider::Vscode),
+            "timeout" => Ok(PermissionDecider::Timeout),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A recorded answer to a permission request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PermissionDecisionInfo {
+    pub id: uuid::Uuid,
+    pub request_id: String,
+    pub tool_name: String,
+    pub input: serde_json::Value,
+    /// `None` when VS Code answered but its decision couldn't be read
+    pub allowed: Option<bool>,
+    pub decided_by: PermissionDecider,
+    /// The web user who answered (only for `User` decisions)
+    pub user_id: Option<uuid::Uuid>,
+    /// Display name of that user (name, falling back to email)
+    pub user_name: Option<String>,
+    /// The rule that answered (only for `Policy` decisions, None once deleted)
+    pub permission_rule_id: Option<uuid::Uuid>,
+    pub reason: Option<String>,
+    pub requested_at: String,
+    pub decided_at: String,
+    /// Time between the request reaching the portal and its answer
+    pub latency_ms: i64,
+}
+
+/// Response listing a session's permission decisions, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PermissionDecisionListResponse {
+    pub decisions: Vec<PermissionDecisionInfo>,
+}
+
+// =============================================================================
+// Webhooks
+// =============================================================================
+
+/// Session lifecycle events a webhook can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum WebhookEvent {
+    /// Claude finished a turn (a `result` message arrived)
+    #[serde(rename = "session.turn_completed")]
+    TurnCompleted,
+    /// A tool asked for permission
+    #[serde(rename = "permission.requested")]
+    PermissionRequested,
+    /// The agent process exited
+    #[serde(rename = "session.exited")]
+    SessionExited,
+    /// A scheduled task run finished
+    #[serde(rename = "scheduled_run.completed")]
+    ScheduledRunCompleted,
+    /// Test delivery sent from the settings page
+    #[serde(rename = "ping")]
+    Ping,
+}
+
+impl WebhookEvent {
+    /// Events a webhook can subscribe to (ping is always delivered on request)
+    pub const SUBSCRIBABLE: [WebhookEvent; 4] = [
+        WebhookEvent::TurnCompleted,
+        WebhookEvent::PermissionRequested,
+        WebhookEvent::SessionExited,
+        WebhookEvent::ScheduledRunCompleted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::TurnCompleted => "session.turn_completed",
+            WebhookEvent::PermissionRequested => "permission.requested",
+            WebhookEvent::SessionExited => "session.exited",
+            WebhookEvent::ScheduledRunCompleted => "scheduled_run.completed",
+            WebhookEvent::Ping => "ping",
+        }
+    }
+}
+
+impl std::str::FromStr for WebhookEvent {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "session.turn_completed" => Ok(WebhookEvent::TurnCompleted),
+            "permission.requested" => Ok(WebhookEvent::PermissionRequested),
+            "session.exited" => Ok(WebhookEvent::SessionExited),
+            "scheduled_run.completed" => Ok(WebhookEvent::ScheduledRunCompleted),
+            "ping" => Ok(WebhookEvent::Ping),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Delivery state of a single webhook event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    /// Not yet delivered; will be (re)tried at `next_attempt_at`
+    Pending,
+    Succeeded,
+    /// Gave up after the maximum number of attempts
+    Failed,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookDeliveryStatus::Pending => "pending",
+            WebhookDeliveryStatus::Succeeded => "succeeded",
+            WebhookDeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+impl std::str::FromStr for WebhookDeliveryStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(WebhookDeliveryStatus::Pending),
+            "succeeded" => Ok(WebhookDeliveryStatus::Succeeded),
+            "failed" => Ok(WebhookDeliveryStatus::Failed),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Request to create a webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Request to update a webhook (all fields optional)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdateWebhookRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<WebhookEvent>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_double_option",
+        serialize_with = "serialize_double_option"
+    )]
+    pub description: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+/// Webhook information returned by the API (the signing secret is only
+/// returned once, on creation)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookInfo {
+    pub id: uuid::Uuid,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Response to creating a webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateWebhookResponse {
+    pub webhook: WebhookInfo,
+    /// HMAC-SHA256 signing secret; store it now, it is not shown again
+    pub secret: String,
+}
+
+/// List of webhooks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookListResponse {
+    pub webhooks: Vec<WebhookInfo>,
+}
+
+/// One entry in a webhook's delivery log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookDeliveryInfo {
+    pub id: uuid::Uuid,
+    pub event: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    /// HTTP status of the last attempt, if the receiver answered
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<String>,
+    pub created_at: String,
+    pub delivered_at: Option<String>,
+}
+
+/// A webhook's most recent deliveries, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookDeliveryListResponse {
+    pub deliveries: Vec<WebhookDeliveryInfo>,
+}
+
+// =============================================================================
+// Email Notifications
+// =============================================================================
+
+/// Longest permission wait that can trigger a mail (one day)
+pub const MAX_PERMISSION_WAIT_MINUTES: i32 = 24 * 60;
+
+/// A user's email notification preferences
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EmailSettings {
+    /// Mail when a permission request has waited this many minutes (None = off)
+    #[serde(default)]
+    pub permission_wait_minutes: Option<i32>,
+    /// Mail when a turn ends with an error
+    #[serde(default)]
+    pub session_errors: bool,
+    /// Mail when a scheduled run exits with a non-zero code
+    #[serde(default)]
+    pub scheduled_failures: bool,
+    /// Send a daily summary of sessions, spend and scheduled runs
+    #[serde(default)]
+    pub daily_digest: bool,
+    /// UTC hour (0-23) at which the digest is sent
+    #[serde(default = "default_digest_hour")]
+    pub digest_hour: i32,
+}
+
+fn default_digest_hour() -> i32 {
+    8
+}
+
+impl Default for EmailSettings {
+    fn default() -> Self {
+        Self {
+            permission_wait_minutes: None,
+            session_errors: false,
+            scheduled_failures: false,
+            daily_digest: false,
+            digest_hour: default_digest_hour(),
+        }
+    }
+}
+
+impl EmailSettings {
+    /// Check the settings against the allowed ranges
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if let Some(minutes) = self.permission_wait_minutes {
+            if !(1..=MAX_PERMISSION_WAIT_MINUTES).contains(&minutes) {
+                return Err("Permission wait must be between 1 minute and 1 day");
+            }
+        }
+        if !(0..=23).contains(&self.digest_hour) {
+            return Err("Digest hour must be between 0 and 23");
+        }
+        Ok(())
+    }
+}
+
+/// Response for GET/PUT /api/settings/email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EmailSettingsResponse {
+    #[serde(flatten)]
+    pub settings: EmailSettings,
+    /// Address mail is sent to (the account email)
+    pub email: String,
+    /// Whether the server has an SMTP relay configured; nothing is sent otherwise
+    pub smtp_configured: bool,
+}
+
+// =============================================================================
+// Chat Bridges
+// =============================================================================
+
+/// Request to connect a chat channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateChatBridgeRequest {
+    pub name: String,
+    /// The chat tool's incoming webhook URL that messages are posted to
+    pub incoming_webhook_url: String,
+    /// Secret the chat tool signs inbound requests with; generated when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_secret: Option<String>,
+    #[serde(default = "default_true")]
+    pub post_permissions: bool,
+    #[serde(default = "default_true")]
+    pub post_results: bool,
+    /// Chat user ID -> portal account email
+    #[serde(default)]
+    pub user_map: std::collections::BTreeMap<String, String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Request to update a chat bridge (all fields optional)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdateChatBridgeRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incoming_webhook_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_secret: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_permissions: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_results: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_map: Option<std::collections::BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+/// Chat bridge information returned by the API (the signing secret is only
+/// returned once, on creation)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChatBridgeInfo {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub incoming_webhook_url: String,
+    pub post_permissions: bool,
+    pub post_results: bool,
+    pub user_map: std::collections::BTreeMap<String, String>,
+    pub enabled: bool,
+    /// URL to configure as the chat tool's outgoing webhook or slash command
+    pub inbound_url: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Response to creating a chat bridge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateChatBridgeResponse {
+    pub bridge: ChatBridgeInfo,
+    pub signing_secret: String,
+}
+
+/// List of chat bridges
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChatBridgeListResponse {
+    pub bridges: Vec<ChatBridgeInfo>,
+}
+
+// =============================================================================
+// API Keys
+// =============================================================================
+
+/// Longest lifetime of a personal API key
+pub const MAX_API_KEY_DAYS: u32 = 365;
+
+/// What a personal API key may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum ApiKeyScope {
+    /// List sessions and read their messages and permission decisions
+    #[serde(rename = "sessions:read")]
+    ReadSessions,
+    /// Send input to and stop sessions
+    #[serde(rename = "sessions:input")]
+    SendInput,
+    /// List launchers and launch sessions
+    #[serde(rename = "launch")]
+    Launch,
+    /// Create, update and delete scheduled tasks
+    #[serde(rename = "schedules")]
+    ManageSchedules,
+    /// Admin endpoints (the key owner must also be an admin)
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl ApiKeyScope {
+    pub const ALL: [ApiKeyScope; 5] = [
+        ApiKeyScope::ReadSessions,
+        ApiKeyScope::SendInput,
+        ApiKeyScope::Launch,
+        ApiKeyScope::ManageSchedules,
+        ApiKeyScope::Admin,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyScope::ReadSessions => "sessions:read",
+            ApiKeyScope::SendInput => "sessions:input",
+            ApiKeyScope::Launch => "launch",
+            ApiKeyScope::ManageSchedules => "schedules",
+            ApiKeyScope::Admin => "admin",
+        }
+    }
+}
+
+impl std::str::FromStr for ApiKeyScope {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sessions:read" => Ok(ApiKeyScope::ReadSessions),
+            "sessions:input" => Ok(ApiKeyScope::SendInput),
+            "launch" => Ok(ApiKeyScope::Launch),
+            "schedules" => Ok(ApiKeyScope::ManageSchedules),
+            "admin" => Ok(ApiKeyScope::Admin),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Request to create a personal API key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+    /// Key lifetime in days (default: 90)
+    #[serde(default = "default_api_key_days")]
+    pub expires_in_days: u32,
+}
+
+fn default_api_key_days() -> u32 {
+    90
+}
+
+/// API key information returned by the API (the key itself is only returned
+/// once, on creation)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiKeyInfo {
+    pub id: uuid::Uuid,
+    pub name: String,
+    /// First characters of the key, to tell keys apart
+    pub prefix: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    pub expires_at: String,
+    pub revoked: bool,
+}
+
+/// Response to creating an API key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateApiKeyResponse {
+    pub api_key: ApiKeyInfo,
+    /// The bearer key; store it now, it is not shown again
+    pub key: String,
+}
+
+/// List of API keys
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiKeyListResponse {
+    pub api_keys: Vec<ApiKeyInfo>,
+}
+
+/// Input sent to a session over REST (`POST /api/sessions/{id}/input`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SendInputRequest {
+    /// Prompt text, or content blocks as the web client sends them
+    pub content: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub send_mode: Option<crate::SendMode>,
+}
+
+/// Request to fork a session (`POST /api/sessions/{id}/fork`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ForkSessionRequest {
+    /// Last message kept in the fork; must be a Claude reply
+    pub message_id: uuid::Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ForkSessionResponse {
+    pub request_id: uuid::Uuid,
+    /// Id the fork registers under once the launcher has started it
+    pub session_id: uuid::Uuid,
+}
+
+// =============================================================================
+// Transcript Search
+// =============================================================================
+
+/// Marks the start of a matched term in [`SearchHit::snippet`]
+pub const SEARCH_HIGHLIGHT_START: char = '«';
+
+/// Marks the end of a matched term in [`SearchHit::snippet`]
+pub const SEARCH_HIGHLIGHT_END: char = '»';
+
+/// A message matching a transcript search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SearchHit {
+    pub message_id: uuid::Uuid,
+    pub session_id: uuid::Uuid,
+    pub session_name: String,
+    pub working_directory: String,
+    pub hostname: String,
+    pub git_branch: Option<String>,
+    pub agent_type: crate::AgentType,
+    /// Message role (assistant, user, result, portal, ...)
+    pub role: String,
+    pub created_at: String,
+    /// Matching excerpt, with matched terms between `«` and `»`
+    pub snippet: String,
+    pub rank: f32,
+}
+
+/// Response to a transcript search, best matches first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SearchResponse {
+    pub hits: Vec<SearchHit>,
+    /// Whether more hits follow this page
+    pub has_more: bool,
+}
+
+// =============================================================================
+// Public Share Links
+// =============================================================================
+
+/// Longest lifetime a share link may be given, in hours (30 days)
+pub const MAX_SHARE_LINK_HOURS: u32 = 720;
+
+/// Request to create a public read-only link to a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateShareLinkRequest {
+    /// Link lifetime in hours (default: 24)
+    #[serde(default = "default_share_link_hours")]
+    pub expires_in_hours: u32,
+    /// Password viewers must enter before the transcript is shown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+fn default_share_link_hours() -> u32 {
+    24
+}
+
+/// Share link information returned by the API (the token itself is only
+/// returned once, on creation)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ShareLinkInfo {
+    pub id: uuid::Uuid,
+    pub session_id: uuid::Uuid,
+    pub has_password: bool,
+    pub created_at: String,
+    pub expires_at: String,
+    pub revoked: bool,
+    pub view_count: i64,
+    pub last_viewed_at: Option<String>,
+}
+
+/// Response to creating a share link
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateShareLinkResponse {
+    pub share_link: ShareLinkInfo,
+    /// Token for `/shared/{token}`; store it now, it is not shown again
+    pub token: String,
+}
+
+/// Share links of a session, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ShareLinkListResponse {
+    pub share_links: Vec<ShareLinkInfo>,
+}
+
+/// One time a share link was opened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ShareLinkView {
+    pub viewed_at: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// Views of a share link, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ShareLinkViewsResponse {
+    pub views: Vec<ShareLinkView>,
+}
+
+/// What an anonymous visitor learns about a shared session before joining
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SharedSessionInfo {
+    pub session_name: String,
+    pub agent_type: crate::AgentType,
+    pub expires_at: String,
+    /// The viewer must supply the link password when joining
+    pub requires_password: bool,
+}
+
+// =============================================================================
+// Teams
+// =============================================================================
+
+/// Request to create a team; the caller becomes its first owner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateTeamRequest {
+    pub name: String,
+}
+
+/// A team the caller belongs to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TeamInfo {
+    pub id: uuid::Uuid,
+    pub name: String,
+    /// `owner` or `member`
+    pub my_role: String,
+    pub member_count: i64,
+    pub created_at: String,
+}
+
+/// Teams the caller belongs to, by name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TeamListResponse {
+    pub teams: Vec<TeamInfo>,
+}
+
+/// A member of a team
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TeamMemberInfo {
+    pub user_id: uuid::Uuid,
+    pub email: String,
+    pub name: Option<String>,
+    pub role: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TeamMembersResponse {
+    pub members: Vec<TeamMemberInfo>,
+}
+
+/// Request to add a user to a team (team owners only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AddTeamMemberRequest {
+    pub email: String,
+    /// `owner` or `member` (default: member)
+    #[serde(default = "default_team_role")]
+    pub role: String,
+}
+
+fn default_team_role() -> String {
+    "member".to_string()
+}
+
+/// Request to change a team member's role (team owners only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdateTeamMemberRequest {
+    pub role: String,
+}
+
+/// A team a session is shared with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionTeamInfo {
+    pub team_id: uuid::Uuid,
+    pub team_name: String,
+    /// `viewer` or `editor`, granted to every team member
+    pub role: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionTeamsResponse {
+    pub teams: Vec<SessionTeamInfo>,
+}
+
+/// Request to share a session with a team, or change the role it is shared
+/// with (session owners only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ShareSessionWithTeamRequest {
+    pub team_id: uuid::Uuid,
+    pub role: String,
+}
+
+/// A launcher its owner lets the team launch sessions on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TeamLauncherInfo {
+    pub id: uuid::Uuid,
+    pub owner_id: uuid::Uuid,
+    pub owner_name: String,
+    pub launcher_name: String,
+    /// Whether a launcher with this name is connected right now
+    pub connected: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TeamLaunchersResponse {
+    pub launchers: Vec<TeamLauncherInfo>,
+}
+
+/// Request to share one of the caller's launchers with a team, by name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ShareLauncherRequest {
+    pub launcher_name: String,
+}
+
+// =============================================================================
+// Spend Budgets
+// =============================================================================
+
+/// How long spend accumulates against a budget before it starts over (UTC)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetPeriod {
+    Daily,
+    Monthly,
+}
+
+impl BudgetPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetPeriod::Daily => "daily",
+            BudgetPeriod::Monthly => "monthly",
+        }
+    }
+}
+
+impl std::str::FromStr for BudgetPeriod {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "daily" => Ok(BudgetPeriod::Daily),
+            "monthly" => Ok(BudgetPeriod::Monthly),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A user's or team's budget and what has been spent against it this period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SpendBudgetInfo {
+    pub id: uuid::Uuid,
+    pub user_id: Option<uuid::Uuid>,
+    pub team_id: Option<uuid::Uuid>,
+    /// The user's name or email, or the team name
+    pub target_name: String,
+    pub period: BudgetPeriod,
+    /// Spend at which members are notified
+    pub soft_limit_usd: Option<f64>,
+    /// Spend at which sessions are interrupted and input and launches refused
+    pub hard_limit_usd: Option<f64>,
+    pub spent_usd: f64,
+    /// The hard limit is reached
+    pub blocked: bool,
+    pub period_start: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SpendBudgetsResponse {
+    pub budgets: Vec<SpendBudgetInfo>,
+}
+
+/// Request to set the budget of a user or a team (exactly one) for a period,
+/// replacing the one already set (admins only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SetSpendBudgetRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<uuid::Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team_id: Option<uuid::Uuid>,
+    pub period: BudgetPeriod,
+    #[serde(default)]
+    pub soft_limit_usd: Option<f64>,
+    #[serde(default)]
+    pub hard_limit_usd: Option<f64>,
+}
+
+/// Request to change or clear a session's cost cap (admins only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SetSessionCostCapRequest {
+    pub max_cost_usd: Option<f64>,
+}
+
+fn valid_amount(usd: Option<f64>) -> bool {
+    usd.is_none_or(|v| v.is_finite() && v > 0.0)
+}
+
+/// Check a budget's limits: at least one, both positive, soft below hard
+pub fn validate_budget_limits(soft: Option<f64>, hard: Option<f64>) -> Result<(), &'static str> {
+    if soft.is_none() && hard.is_none() {
+        return Err("Set a soft limit, a hard limit or both");
+    }
+    if !valid_amount(soft) || !valid_amount(hard) {
+        return Err("Limits must be positive amounts");
+    }
+    if let (Some(soft), Some(hard)) = (soft, hard) {
+        if soft >= hard {
+            return Err("The soft limit must be below the hard limit");
+        }
+    }
+    Ok(())
+}
+
+/// Check a session cost cap
+pub fn validate_cost_cap(max_cost_usd: Option<f64>) -> Result<(), &'static str> {
+    if valid_amount(max_cost_usd) {
+        Ok(())
+    } else {
+        Err("The cost cap must be a positive amount")
+    }
+}
+
+// =============================================================================
+// Usage Analytics
+// =============================================================================
+
+/// What usage analytics are broken down by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum AnalyticsGroupBy {
+    #[default]
+    Model,
+    User,
+    Host,
+    Repo,
+    AgentType,
+    Task,
+}
+
+impl AnalyticsGroupBy {
+    pub const ALL: [AnalyticsGroupBy; 6] = [
+        AnalyticsGroupBy::Model,
+        AnalyticsGroupBy::User,
+        AnalyticsGroupBy::Host,
+        AnalyticsGroupBy::Repo,
+        AnalyticsGroupBy::AgentType,
+        AnalyticsGroupBy::Task,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnalyticsGroupBy::Model => "model",
+            AnalyticsGroupBy::User => "user",
+            AnalyticsGroupBy::Host => "host",
+            AnalyticsGroupBy::Repo => "repo",
+            AnalyticsGroupBy::AgentType => "agent_type",
+            AnalyticsGroupBy::Task => "task",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AnalyticsGroupBy::Model => "Model",
+            AnalyticsGroupBy::User => "User",
+            AnalyticsGroupBy::Host => "Host",
+            AnalyticsGroupBy::Repo => "Repository",
+            AnalyticsGroupBy::AgentType => "Agent",
+            AnalyticsGroupBy::Task => "Scheduled task",
+        }
+    }
+}
+
+impl std::str::FromStr for AnalyticsGroupBy {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|group_by| group_by.as_str() == s)
+            .ok_or(())
+    }
+}
+
+/// Tokens, cost and time summed over some turns
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UsageTotals {
+    pub turns: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cost_usd: f64,
+    pub duration_ms: i64,
+}
+
+impl UsageTotals {
+    pub fn add(&mut self, other: &UsageTotals) {
+        self.turns += other.turns;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cost_usd += other.cost_usd;
+        self.duration_ms += other.duration_ms;
+    }
+
+    pub fn total_tokens(&self) -> i64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+}
+
+/// One group's usage on one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AnalyticsPoint {
+    /// UTC day, `YYYY-MM-DD`
+    pub day: String,
+    /// The group's `key`
+    pub key: String,
+    pub usage: UsageTotals,
+}
+
+/// A group's usage over the whole range
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AnalyticsGroup {
+    /// Model, user ID, hostname, repository URL, agent type or task ID; empty
+    /// for usage outside any repository or task
+    pub key: String,
+    pub label: String,
+    pub usage: UsageTotals,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AnalyticsResponse {
+    pub group_by: AnalyticsGroupBy,
+    /// First day covered, `YYYY-MM-DD`
+    pub from: String,
+    /// Last day covered (today, UTC)
+    pub to: String,
+    /// Usage of every user rather than only the caller's
+    pub all_users: bool,
+    /// Costliest first
+    pub groups: Vec<AnalyticsGroup>,
+    /// Days without usage are left out
+    pub points: Vec<AnalyticsPoint>,
+}
+
+// =============================================================================
+// Audit Trail
+// =============================================================================
+
+/// An action recorded in the audit trail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    UserUpdate,
+    SessionDelete,
+    SessionCostCap,
+    SessionMemberAdd,
+    SessionMemberUpdate,
+    SessionMemberRemove,
+    BudgetSet,
+    BudgetDelete,
+    TeamCreate,
+    TeamDelete,
+    TeamMemberAdd,
+    TeamMemberUpdate,
+    TeamMemberRemove,
+    ProxyTokenCreate,
+    ProxyTokenRenew,
+    ProxyTokenRevoke,
+    ApiKeyCreate,
+    ApiKeyRevoke,
+    ShareLinkCreate,
+    ShareLinkRevoke,
+    LauncherTokenRenew,
+    ScheduledTaskCreate,
+    ScheduledTaskUpdate,
+    ScheduledTaskDelete,
+    LocalAccountCreate,
+    LocalAccountReset,
+    InviteCreate,
+    InviteRevoke,
+    InviteRedeem,
+    SettingsUpdate,
+    WebLoginRevoke,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 31] = [
+        AuditAction::UserUpdate,
+        AuditAction::SessionDelete,
+        AuditAction::SessionCostCap,
+        AuditAction::SessionMemberAdd,
+        AuditAction::SessionMemberUpdate,
+        AuditAction::SessionMemberRemove,
+        AuditAction::BudgetSet,
+        AuditAction::BudgetDelete,
+        AuditAction::TeamCreate,
+        AuditAction::TeamDelete,
+        AuditAction::TeamMemberAdd,
+        AuditAction::TeamMemberUpdate,
+        AuditAction::TeamMemberRemove,
+        AuditAction::ProxyTokenCreate,
+        AuditAction::ProxyTokenRenew,
+        AuditAction::ProxyTokenRevoke,
+        AuditAction::ApiKeyCreate,
+        AuditAction::ApiKeyRevoke,
+        AuditAction::ShareLinkCreate,
+        AuditAction::ShareLinkRevoke,
+        AuditAction::LauncherTokenRenew,
+        AuditAction::ScheduledTaskCreate,
+        AuditAction::ScheduledTaskUpdate,
+        AuditAction::ScheduledTaskDelete,
+        AuditAction::LocalAccountCreate,
+        AuditAction::LocalAccountReset,
+        AuditAction::InviteCreate,
+        AuditAction::InviteRevoke,
+        AuditAction::InviteRedeem,
+        AuditAction::SettingsUpdate,
+        AuditAction::WebLoginRevoke,
+    ];
+
+    /// Name stored in `audit_events.action`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::UserUpdate => "user.update",
+            AuditAction::SessionDelete => "session.delete",
+            AuditAction::SessionCostCap => "session.cost_cap",
+            AuditAction::SessionMemberAdd => "session_member.add",
+            AuditAction::SessionMemberUpdate => "session_member.update",
+            AuditAction::SessionMemberRemove => "session_member.remove",
+            AuditAction::BudgetSet => "budget.set",
+            AuditAction::BudgetDelete => "budget.delete",
+            AuditAction::TeamCreate => "team.create",
+            AuditAction::TeamDelete => "team.delete",
+            AuditAction::TeamMemberAdd => "team_member.add",
+            AuditAction::TeamMemberUpdate => "team_member.update",
+            AuditAction::TeamMemberRemove => "team_member.remove",
+            AuditAction::ProxyTokenCreate => "proxy_token.create",
+            AuditAction::ProxyTokenRenew => "proxy_token.renew",
+            AuditAction::ProxyTokenRevoke => "proxy_token.revoke",
+            AuditAction::ApiKeyCreate => "api_key.create",
+            AuditAction::ApiKeyRevoke => "api_key.revoke",
+            AuditAction::ShareLinkCreate => "share_link.create",
+            AuditAction::ShareLinkRevoke => "share_link.revoke",
+            AuditAction::LauncherTokenRenew => "launcher.token_renew",
+            AuditAction::ScheduledTaskCreate => "scheduled_task.create",
+            AuditAction::ScheduledTaskUpdate => "scheduled_task.update",
+            AuditAction::ScheduledTaskDelete => "scheduled_task.delete",
+            AuditAction::LocalAccountCreate => "local_account.create",
+            AuditAction::LocalAccountReset => "local_account.reset",
+            AuditAction::InviteCreate => "invite.create",
+            AuditAction::InviteRevoke => "invite.revoke",
+            AuditAction::InviteRedeem => "invite.redeem",
+            AuditAction::SettingsUpdate => "settings.update",
+            AuditAction::WebLoginRevoke => "web_login.revoke",
+        }
+    }
+
+    /// What an event's `target_id` refers to
+    pub fn target_type(&self) -> &'static str {
+        match self {
+            AuditAction::UserUpdate
+            | AuditAction::LocalAccountCreate
+            | AuditAction::LocalAccountReset => "user",
+            AuditAction::SessionDelete
+            | AuditAction::SessionCostCap
+            | AuditAction::SessionMemberAdd
+            | AuditAction::SessionMemberUpdate
+            | AuditAction::SessionMemberRemove => "session",
+            AuditAction::BudgetSet | AuditAction::BudgetDelete => "budget",
+            AuditAction::TeamCreate
+            | AuditAction::TeamDelete
+            | AuditAction::TeamMemberAdd
+            | AuditAction::TeamMemberUpdate
+            | AuditAction::TeamMemberRemove => "team",
+            AuditAction::ProxyTokenCreate
+            | AuditAction::ProxyTokenRenew
+            | AuditAction::ProxyTokenRevoke => "proxy_token",
+            AuditAction::ApiKeyCreate | AuditAction::ApiKeyRevoke => "api_key",
+            AuditAction::ShareLinkCreate | AuditAction::ShareLinkRevoke => "share_link",
+            AuditAction::LauncherTokenRenew => "launcher",
+            AuditAction::ScheduledTaskCreate
+            | AuditAction::ScheduledTaskUpdate
+            | AuditAction::ScheduledTaskDelete => "scheduled_task",
+            AuditAction::InviteCreate | AuditAction::InviteRevoke | AuditAction::InviteRedeem => {
+                "invite"
+            }
+            AuditAction::SettingsUpdate => "settings",
+            AuditAction::WebLoginRevoke => "web_login",
+        }
+    }
+}
+
+impl std::str::FromStr for AuditAction {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AuditAction::ALL
+            .into_iter()
+            .find(|action| action.as_str() == s)
+            .ok_or(())
+    }
+}
+
+/// One entry of the audit trail
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuditEventInfo {
+    pub id: uuid::Uuid,
+    pub created_at: String,
+    /// Who acted; `None` for the server itself, e.g. automatic renewals
+    pub actor_id: Option<uuid::Uuid>,
+    /// The actor's email when the event was recorded
+    pub actor_email: Option<String>,
+    /// e.g. `user.update`; see [`AuditAction`]
+    pub action: String,
+    /// `user`, `session`, `team`, `proxy_token`, ...
+    pub target_type: String,
+    pub target_id: Option<uuid::Uuid>,
+    /// What changed, depending on the action
+    pub details: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuditEventsResponse {
+    /// Newest first
+    pub events: Vec<AuditEventInfo>,
+    /// More events match the filter beyond this page
+    pub has_more: bool,
+}
+
+// =============================================================================
+// Local Accounts
+// =============================================================================
+
+/// Sign in with a local username and password
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LocalLoginRequest {
+    pub username: String,
+    pub password: String,
+    /// Authenticator code, for accounts with a second factor
+    #[serde(default)]
+    pub totp_code: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LocalLoginResponse {
+    /// The password was right but the account needs an authenticator code;
+    /// no session was started
+    pub totp_required: bool,
+}
+
+/// What a setup link is for, shown before the password is chosen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AccountSetupInfo {
+    pub username: String,
+    pub email: String,
+    pub expires_at: String,
+}
+
+/// Choose a password through a setup link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AccountSetupRequest {
+    pub token: String,
+    pub password: String,
+}
+
+/// The caller's local account, if they have one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LocalAccountInfo {
+    /// Whether the caller signs in with a local password at all
+    pub is_local: bool,
+    pub username: Option<String>,
+    pub totp_enabled: bool,
+    pub password_changed_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// A second factor waiting to be confirmed with a first code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TotpSetupResponse {
+    /// Base32 secret, for typing into an authenticator app
+    pub secret: String,
+    /// `otpauth://` URI carrying the same secret
+    pub otpauth_uri: String,
+}
+
+/// An authenticator code, to enable or disable the second factor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TotpCodeRequest {
+    pub code: String,
+}
+
+/// Admin: create a local account; its owner picks a password via the link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateLocalAccountRequest {
+    pub username: String,
+    pub email: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub is_admin: bool,
+}
+
+/// Admin: clear a local password and issue a new setup link
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ResetLocalPasswordRequest {
+    /// Also remove the account's second factor
+    #[serde(default)]
+    pub reset_totp: bool,
+}
+
+/// A setup link, shown only once
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LocalAccountSetupLink {
+    pub user_id: uuid::Uuid,
+    pub username: String,
+    pub setup_url: String,
+    pub expires_at: String,
+}
+
+// =============================================================================
+// Invites
+// =============================================================================
+
+/// Longest an invite may stay open, in days
+pub const MAX_INVITE_DAYS: u32 = 90;
+/// Most sign-ins one invite may admit
+pub const MAX_INVITE_USES: u32 = 1000;
+
+/// What an invite makes the people who redeem it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum InviteRole {
+    #[default]
+    User,
+    Admin,
+}
+
+impl InviteRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InviteRole::User => "user",
+            InviteRole::Admin => "admin",
+        }
+    }
+}
+
+impl std::str::FromStr for InviteRole {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user" => Ok(InviteRole::User),
+            "admin" => Ok(InviteRole::Admin),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Admin: open an invite
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateInviteRequest {
+    /// Who the invite is for, shown only to admins
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub role: InviteRole,
+    /// How many people may sign in with it (default: 1)
+    #[serde(default = "default_invite_uses")]
+    pub max_uses: u32,
+    /// Days until it stops working (default: 7)
+    #[serde(default = "default_invite_days")]
+    pub expires_in_days: u32,
+}
+
+fn default_invite_uses() -> u32 {
+    1
+}
+
+fn default_invite_days() -> u32 {
+    7
+}
+
+/// Someone who signed in through an invite
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct InviteRedemptionInfo {
+    pub user_id: uuid::Uuid,
+    pub email: String,
+    pub name: Option<String>,
+    pub redeemed_at: String,
+}
+
+/// An invite and who redeemed it (the code itself is only returned once, on
+/// creation)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct InviteInfo {
+    pub id: uuid::Uuid,
+    pub label: Option<String>,
+    pub role: InviteRole,
+    pub max_uses: i32,
+    pub use_count: i32,
+    /// Email of the admin who created it, if they still exist
+    pub created_by_email: Option<String>,
+    pub created_at: String,
+    pub expires_at: String,
+    pub revoked: bool,
+    /// Oldest first
+    pub redemptions: Vec<InviteRedemptionInfo>,
+}
+
+/// Invites, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct InvitesResponse {
+    pub invites: Vec<InviteInfo>,
+}
+
+/// Response to creating an invite
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateInviteResponse {
+    pub invite: InviteInfo,
+    /// `/invite?code=...` link to send; it is not shown again
+    pub invite_url: String,
+}
+
+/// What someone opening an invite link is told before signing in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct InviteCheckResponse {
+    pub role: InviteRole,
+    pub expires_at: String,
+}
+
+// =============================================================================
+// Instance Settings
+// =============================================================================
+
+/// Longest app title accepted
+pub const MAX_APP_TITLE_LEN: usize = 100;
+/// Longest splash text accepted
+pub const MAX_SPLASH_TEXT_LEN: usize = 500;
+/// Largest image size proxies may be told to inline, in MB
+pub const MAX_IMAGE_MB_LIMIT: u32 = 100;
+
+/// Instance-wide settings admins can change while the server runs. The
+/// environment supplies the defaults; the database holds what admins changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct InstanceSettings {
+    /// Shown in the top bar and page title (`APP_TITLE`)
+    pub app_title: String,
+    /// Replaces the marketing splash page with this heading (`SPLASH_TEXT`)
+    pub splash_text: Option<String>,
+    /// Only emails at this domain may sign in (`ALLOWED_EMAIL_DOMAIN`)
+    pub allowed_email_domain: Option<String>,
+    /// Only these emails may sign in, besides the domain (`ALLOWED_EMAILS`)
+    pub allowed_emails: Option<Vec<String>>,
+    /// Messages kept per session (`MESSAGE_RETENTION_COUNT`)
+    pub message_retention_count: i64,
+    /// Days messages are kept, 0 to keep them (`MESSAGE_RETENTION_DAYS`)
+    pub message_retention_days: u32,
+    /// Days idle sessions are kept, 0 to keep them (`SESSION_MAX_AGE_DAYS`)
+    pub session_max_age_days: u32,
+    /// Largest image proxies inline, in MB (`PORTAL_MAX_IMAGE_MB`)
+    pub max_image_mb: u32,
+}
+
+impl InstanceSettings {
+    /// Why the settings can't be saved, if they can't
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.app_title.trim().is_empty() || self.app_title.len() > MAX_APP_TITLE_LEN {
+            return Err("The app title must be 1 to 100 characters");
+        }
+        if self
+            .splash_text
+            .as_ref()
+            .is_some_and(|t| t.len() > MAX_SPLASH_TEXT_LEN)
+        {
+            return Err("The splash text must be at most 500 characters");
+        }
+        if self
+            .allowed_email_domain
+            .as_ref()
+            .is_some_and(|d| d.is_empty() || d.contains('@') || d.contains(char::is_whitespace))
+        {
+            return Err("The allowed domain must be a domain name, such as example.com");
+        }
+        if self
+            .allowed_emails
+            .iter()
+            .flatten()
+            .any(|e| !e.contains('@') || e.contains(char::is_whitespace))
+        {
+            return Err("Each allowed email must be an email address");
+        }
+        if self.message_retention_count < 1 {
+            return Err("At least one message must be kept per session");
+        }
+        if !(1..=MAX_IMAGE_MB_LIMIT).contains(&self.max_image_mb) {
+            return Err("The image size limit must be 1 to 100 MB");
+        }
+        Ok(())
+    }
+}
+
+/// The settings in effect, and what the environment would give without
+/// admin changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct InstanceSettingsResponse {
+    pub settings: InstanceSettings,
+    pub defaults: InstanceSettings,
+    /// Settings admins changed from their defaults
+    pub overridden: Vec<String>,
+}
+
+// =============================================================================
+// Signed-in Devices
+// =============================================================================
+
+/// A browser signed in as the current user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebLoginInfo {
+    pub id: uuid::Uuid,
+    /// Browser and operating system read from the user agent, e.g.
+    /// "Firefox on macOS"
+    pub device: String,
+    /// Address the browser signed in from
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: String,
+    pub last_seen_at: String,
+    /// The browser making this request
+    pub current: bool,
+}
+
+/// Response for GET /api/logins
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebLoginsResponse {
+    pub logins: Vec<WebLoginInfo>,
+}
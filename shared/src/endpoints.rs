@@ -3,7 +3,8 @@ use uuid::Uuid;
 pub use ws_bridge::WsEndpoint;
 
 use crate::{
-    AgentType, DirectoryEntry, PermissionSuggestion, SendMode, SessionCost, SessionStatus,
+    AgentType, DirectoryEntry, LocalSession, PermissionSuggestion, SendMode, SessionCost,
+    SessionStatus,
 };
 
 // =============================================================================
@@ -39,6 +40,28 @@ pub struct RegisterFields {
     pub scheduled_task_id: Option<Uuid>,
 }
 
+/// A message read back from a local Claude Code transcript, in the stream-json
+/// shape the proxy forwards, used to backfill an adopted session's history.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistoryMessage {
+    pub content: serde_json::Value,
+    /// RFC 3339 time the message was written to the transcript
+    #[serde(default)]
+    pub timestamp: Option<String>,
+}
+
+/// Where a forked session branches off its parent. The fork gets a new
+/// Claude session id chosen by the backend so the lineage can be recorded
+/// before the fork registers.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionFork {
+    pub parent_session_id: Uuid,
+    pub session_id: Uuid,
+    /// Claude message uuid of the last assistant message kept in the fork
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resume_at: Option<String>,
+}
+
 /// Configuration for a scheduled task, sent from backend to launcher via ScheduleSync.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduledTaskConfig {
@@ -56,6 +79,12 @@ pub struct ScheduledTaskConfig {
     pub max_runtime_minutes: i32,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub last_session_id: Option<Uuid>,
+    /// Default timeout for unanswered permission requests in sessions this
+    /// task spawns (None = wait forever)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permission_timeout_seconds: Option<i32>,
+    #[serde(default)]
+    pub permission_timeout_action: crate::api::PermissionTimeoutAction,
 }
 
 /// Fields for a permission response (shared by server-to-proxy and client-to-server).
@@ -116,6 +145,9 @@ pub enum ProxyToServer {
     SequencedOutput {
         seq: u64,
         content: serde_json::Value,
+        /// W3C trace context of the turn this output answers
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        traceparent: Option<String>,
     },
 
     /// Keepalive heartbeat
@@ -130,6 +162,17 @@ pub enum ProxyToServer {
         permission_suggestions: Vec<PermissionSuggestion>,
     },
 
+    /// A permission request was answered outside the portal (e.g. in VS Code
+    /// when running in shim mode), so the backend can drop and record it
+    PermissionAnsweredLocally {
+        request_id: String,
+        /// `None` when the answer was seen but its decision couldn't be read
+        #[serde(default)]
+        allow: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+
     /// Update session metadata (e.g., git branch changed)
     SessionUpdate {
         session_id: Uuid,
@@ -169,6 +212,9 @@ pub enum ServerToProxy {
         content: serde_json::Value,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         send_mode: Option<SendMode>,
+        /// W3C trace context of the span that forwarded the input
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        traceparent: Option<String>,
     },
 
     /// Sequenced user input
@@ -178,6 +224,9 @@ pub enum ServerToProxy {
         content: serde_json::Value,
         #[serde(skip_serializing_if = "Option::is_none")]
         send_mode: Option<SendMode>,
+        /// W3C trace context of the span that forwarded the input
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        traceparent: Option<String>,
     },
 
     /// User's permission decision
@@ -205,6 +254,18 @@ pub enum ServerToProxy {
     Interrupt,
 }
 
+impl ServerToProxy {
+    /// The trace context slot of an input message, or `None` for messages
+    /// that do not carry one
+    pub fn traceparent_mut(&mut self) -> Option<&mut Option<String>> {
+        match self {
+            ServerToProxy::ClaudeInput { traceparent, .. }
+            | ServerToProxy::SequencedInput { traceparent, .. } => Some(traceparent),
+            _ => None,
+        }
+    }
+}
+
 // =============================================================================
 // Client endpoint: frontend <-> backend (/ws/client)
 // =============================================================================
@@ -229,6 +290,11 @@ pub enum ClientToServer {
         content: serde_json::Value,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         send_mode: Option<SendMode>,
+        /// W3C trace context of a span the client exported, for the input to
+        /// continue its trace. The dashboard sends none; the backend starts
+        /// the trace.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        traceparent: Option<String>,
     },
 
     /// User's permission decision
@@ -269,6 +335,19 @@ pub enum ServerToClient {
         permission_suggestions: Vec<PermissionSuggestion>,
     },
 
+    /// A pending permission request was answered (by any client)
+    PermissionResolved { request_id: String },
+
+    /// A permission request went unanswered past its timeout and is being
+    /// escalated to every member of the session
+    PermissionEscalated {
+        session_id: Uuid,
+        session_name: String,
+        request_id: String,
+        tool_name: String,
+        waiting_seconds: i64,
+    },
+
     /// Error message
     Error { message: String },
 
@@ -289,6 +368,13 @@ pub enum ServerToClient {
         session_costs: Vec<SessionCost>,
     },
 
+    /// One of the user's budgets crossed its soft or hard limit
+    SpendAlert {
+        message: String,
+        /// The hard limit: sessions were interrupted and input is refused
+        hard: bool,
+    },
+
     /// Server is shutting down
     ServerShutdown {
         reason: String,
@@ -315,6 +401,37 @@ pub enum ServerToClient {
         session_id: Uuid,
         exit_code: Option<i32>,
     },
+
+    /// The browser login this socket was opened with was revoked; the
+    /// backend closes the socket after sending this
+    SignedOut,
+}
+
+// =============================================================================
+// Shared endpoint: anonymous viewer <-> backend (/ws/shared)
+// =============================================================================
+
+/// Read-only stream of a session for holders of a public share link. The
+/// backend only forwards redacted output and status over it and ignores
+/// anything but the initial join.
+pub struct SharedEndpoint;
+
+impl WsEndpoint for SharedEndpoint {
+    const PATH: &'static str = "/ws/shared";
+    type ServerMsg = ServerToClient;
+    type ClientMsg = SharedClientToServer;
+}
+
+/// Messages a share link viewer sends to the backend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SharedClientToServer {
+    /// Open the session behind a share link
+    Join {
+        token: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        password: Option<String>,
+    },
 }
 
 // =============================================================================
@@ -389,6 +506,20 @@ pub enum LauncherToServer {
         resolved_path: Option<String>,
     },
 
+    /// Local Claude Code sessions found on the launcher's host
+    ListLocalSessionsResult {
+        request_id: Uuid,
+        #[serde(default)]
+        sessions: Vec<LocalSession>,
+        error: Option<String>,
+    },
+
+    /// Earlier history of an adopted session, sent after it is spawned
+    SessionHistory {
+        session_id: Uuid,
+        messages: Vec<HistoryMessage>,
+    },
+
     /// Request the backend to mint a token and launch a session
     RequestLaunch {
         request_id: Uuid,
@@ -447,6 +578,12 @@ pub enum ServerToLauncher {
         agent_type: AgentType,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         scheduled_task_id: Option<Uuid>,
+        /// Adopt this existing local Claude Code session instead of starting a new one
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        resume_session_id: Option<Uuid>,
+        /// Start as a fork of another session's conversation
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fork: Option<SessionFork>,
     },
 
     /// Request to stop a running session
@@ -455,6 +592,9 @@ pub enum ServerToLauncher {
     /// Request directory listing
     ListDirectories { request_id: Uuid, path: String },
 
+    /// Request the recent local Claude Code sessions on the launcher's host
+    ListLocalSessions { request_id: Uuid },
+
     /// Server is shutting down
     ServerShutdown {
         reason: String,
@@ -523,14 +663,22 @@ mod tests {
             seq: 5,
             content: serde_json::json!({"text": "hello"}),
             send_mode: Some(SendMode::Wiggum),
+            traceparent: Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".into()),
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains(r#""type":"SequencedInput""#));
-        let parsed: ServerToProxy = serde_json::from_str(&json).unwrap();
+        let mut parsed: ServerToProxy = serde_json::from_str(&json).unwrap();
+        assert!(parsed.traceparent_mut().is_some_and(|t| t.is_some()));
         match parsed {
-            ServerToProxy::SequencedInput { seq, send_mode, .. } => {
+            ServerToProxy::SequencedInput {
+                seq,
+                send_mode,
+                traceparent,
+                ..
+            } => {
                 assert_eq!(seq, 5);
                 assert_eq!(send_mode, Some(SendMode::Wiggum));
+                assert!(traceparent.is_some());
             }
             _ => panic!("Wrong variant"),
         }
@@ -541,9 +689,11 @@ mod tests {
         let msg = ClientToServer::ClaudeInput {
             content: serde_json::json!({"text": "hi"}),
             send_mode: None,
+            traceparent: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains(r#""type":"ClaudeInput""#));
+        assert!(!json.contains("traceparent"));
         let parsed: ClientToServer = serde_json::from_str(&json).unwrap();
         match parsed {
             ClientToServer::ClaudeInput { send_mode, .. } => {
@@ -602,9 +752,13 @@ mod tests {
             claude_args: vec!["--verbose".into()],
             agent_type: AgentType::Claude,
             scheduled_task_id: None,
+            resume_session_id: None,
+            fork: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains(r#""type":"LaunchSession""#));
+        assert!(!json.contains("resume_session_id"));
+        assert!(!json.contains(r#""fork""#));
         let parsed: ServerToLauncher = serde_json::from_str(&json).unwrap();
         match parsed {
             ServerToLauncher::LaunchSession {
@@ -619,6 +773,58 @@ mod tests {
         }
     }
 
+    #[test]
+    fn server_to_launcher_fork_roundtrip() {
+        let fork = SessionFork {
+            parent_session_id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            resume_at: Some("msg-uuid".into()),
+        };
+        let msg = ServerToLauncher::LaunchSession {
+            request_id: Uuid::nil(),
+            user_id: Uuid::nil(),
+            auth_token: "token".into(),
+            working_directory: "/home".into(),
+            session_name: None,
+            claude_args: vec![],
+            agent_type: AgentType::Claude,
+            scheduled_task_id: None,
+            resume_session_id: None,
+            fork: Some(fork.clone()),
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        let parsed: ServerToLauncher = serde_json::from_str(&json).unwrap();
+        match parsed {
+            ServerToLauncher::LaunchSession { fork: parsed, .. } => {
+                assert_eq!(parsed, Some(fork));
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn launcher_local_sessions_roundtrip() {
+        let msg = LauncherToServer::ListLocalSessionsResult {
+            request_id: Uuid::nil(),
+            sessions: vec![LocalSession {
+                session_id: Uuid::nil(),
+                working_directory: "/home/dev/app".into(),
+                last_activity: "2026-03-20T09:30:00+00:00".into(),
+                git_branch: Some("main".into()),
+                summary: Some("Fix the flaky test".into()),
+            }],
+            error: None,
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains(r#""type":"ListLocalSessionsResult""#));
+        match serde_json::from_str(&json).unwrap() {
+            LauncherToServer::ListLocalSessionsResult { sessions, .. } => {
+                assert_eq!(sessions[0].working_directory, "/home/dev/app");
+            }
+            other => panic!("Wrong variant: {:?}", other),
+        }
+    }
+
     /// Verify wire-format compatibility of per-endpoint types.
     #[test]
     fn wire_compat_register() {
@@ -699,6 +905,8 @@ mod tests {
                 enabled: true,
                 max_runtime_minutes: 30,
                 last_session_id: None,
+                permission_timeout_seconds: Some(300),
+                permission_timeout_action: crate::api::PermissionTimeoutAction::Escalate,
             }],
         };
         let json = serde_json::to_string(&msg).unwrap();
@@ -747,4 +955,26 @@ mod tests {
         assert!(json.contains(r#""type":"ScheduledRunCompleted""#));
         let _: LauncherToServer = serde_json::from_str(&json).unwrap();
     }
+
+    #[test]
+    fn permission_answered_locally_without_decision() {
+        let unknown = ProxyToServer::PermissionAnsweredLocally {
+            request_id: "req-1".into(),
+            allow: None,
+            reason: None,
+        };
+        let json = serde_json::to_string(&unknown).unwrap();
+        match serde_json::from_str(&json).unwrap() {
+            ProxyToServer::PermissionAnsweredLocally { allow, .. } => assert_eq!(allow, None),
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        let decided = r#"{"type":"PermissionAnsweredLocally","request_id":"req-2","allow":false}"#;
+        match serde_json::from_str(decided).unwrap() {
+            ProxyToServer::PermissionAnsweredLocally { allow, .. } => {
+                assert_eq!(allow, Some(false))
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
 }
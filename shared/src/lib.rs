@@ -16,6 +16,12 @@ pub mod protocol;
 pub mod api;
 pub use api::{ApiError, SoundSettingsResponse};
 
+// Presentation helpers shared by the web client and transcript export
+pub mod render;
+
+// Trace context propagated through WebSocket messages
+pub mod trace;
+
 /// Default backend URL based on build profile.
 /// Release builds point to `wss://txcl.io`, debug builds to `ws://localhost:3000`.
 pub fn default_backend_url() -> &'static str {
@@ -43,6 +49,7 @@ pub use claude_codes::ClaudeOutput;
 
 /// Which agent CLI backs a session
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum AgentType {
     #[default]
@@ -146,6 +153,7 @@ impl SessionStatus {
 
 /// Send mode for user input
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum SendMode {
     /// Normal single message send
@@ -158,13 +166,31 @@ pub enum SendMode {
 
 /// A directory entry returned by the launcher's filesystem listing
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct DirectoryEntry {
     pub name: String,
     pub is_dir: bool,
 }
 
+/// A Claude Code session started outside the portal, found in a launcher
+/// host's transcript store and offered for adoption
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LocalSession {
+    pub session_id: Uuid,
+    pub working_directory: String,
+    /// ISO 8601 timestamp of the transcript's last write
+    pub last_activity: String,
+    #[serde(default)]
+    pub git_branch: Option<String>,
+    /// Transcript summary, or the first prompt when there is none
+    #[serde(default)]
+    pub summary: Option<String>,
+}
+
 /// Info about a connected launcher daemon
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct LauncherInfo {
     pub launcher_id: Uuid,
     pub launcher_name: String,
@@ -180,6 +206,9 @@ pub struct LauncherInfo {
     /// ISO 8601 timestamp when the launcher's auth token expires
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub token_expires_at: Option<String>,
+    /// Owner's name when the launcher is shared with the caller through a team
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_by: Option<String>,
 }
 
 /// API types for HTTP endpoints
@@ -219,6 +248,9 @@ pub struct SessionInfo {
     /// Scheduled task ID if this session was spawned by a scheduled task
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub scheduled_task_id: Option<Uuid>,
+    /// Session this one was forked from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_session_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -321,6 +353,42 @@ impl PortalMessage {
         }
     }
 
+    /// Marker recording that the server answered a permission request on its own
+    pub fn permission_decision(tool_name: String, allowed: bool, reason: String) -> Self {
+        Self {
+            message_type: "portal".to_string(),
+            content: vec![PortalContent::PermissionDecision {
+                tool_name,
+                allowed,
+                reason,
+            }],
+        }
+    }
+
+    /// Marker recording that nobody answered a permission request in time
+    pub fn permission_timeout(
+        tool_name: String,
+        action: api::PermissionTimeoutAction,
+        timeout_seconds: i32,
+    ) -> Self {
+        Self {
+            message_type: "portal".to_string(),
+            content: vec![PortalContent::PermissionTimeout {
+                tool_name,
+                action,
+                timeout_seconds,
+            }],
+        }
+    }
+
+    /// Marker recording that input was refused because a spend limit is reached
+    pub fn spend_limit(reason: String) -> Self {
+        Self {
+            message_type: "portal".to_string(),
+            content: vec![PortalContent::SpendLimit { reason }],
+        }
+    }
+
     pub fn to_json(&self) -> serde_json::Value {
         serde_json::to_value(self).unwrap_or_default()
     }
@@ -340,6 +408,27 @@ pub enum PortalContent {
         #[serde(default, skip_serializing_if = "Option::is_none")]
         file_size: Option<u64>,
     },
+    /// A permission request resolved without a human (e.g. by a policy rule)
+    #[serde(rename = "permission_decision")]
+    PermissionDecision {
+        tool_name: String,
+        allowed: bool,
+        /// Human-readable explanation of why (which rule matched)
+        reason: String,
+    },
+    /// A permission request was resolved (or escalated) after its timeout
+    #[serde(rename = "permission_timeout")]
+    PermissionTimeout {
+        tool_name: String,
+        action: api::PermissionTimeoutAction,
+        timeout_seconds: i32,
+    },
+    /// Input was refused or the agent interrupted because a budget or the
+    /// session's cost cap is used up
+    #[serde(rename = "spend_limit")]
+    SpendLimit {
+        reason: String,
+    },
 }
 
 impl std::fmt::Debug for PortalContent {
@@ -358,6 +447,30 @@ impl std::fmt::Debug for PortalContent {
                 .field("file_path", file_path)
                 .field("file_size", file_size)
                 .finish(),
+            Self::PermissionDecision {
+                tool_name,
+                allowed,
+                reason,
+            } => f
+                .debug_struct("PermissionDecision")
+                .field("tool_name", tool_name)
+                .field("allowed", allowed)
+                .field("reason", reason)
+                .finish(),
+            Self::PermissionTimeout {
+                tool_name,
+                action,
+                timeout_seconds,
+            } => f
+                .debug_struct("PermissionTimeout")
+                .field("tool_name", tool_name)
+                .field("action", action)
+                .field("timeout_seconds", timeout_seconds)
+                .finish(),
+            Self::SpendLimit { reason } => f
+                .debug_struct("SpendLimit")
+                .field("reason", reason)
+                .finish(),
         }
     }
 }
@@ -396,6 +509,7 @@ pub enum DevicePollResponse {
 
 /// Application configuration returned by /api/config endpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct AppConfig {
     /// Custom title for the app (displayed in top bar)
     /// Defaults to "Agent Portal" if not configured; override with APP_TITLE env var
@@ -406,6 +520,22 @@ pub struct AppConfig {
     /// displaying this text as the heading. Set via SPLASH_TEXT env var.
     #[serde(default)]
     pub splash_text: Option<String>,
+    /// Sign-in buttons to show, in order. Empty when the server has no login
+    /// providers configured (dev mode).
+    #[serde(default)]
+    pub login_providers: Vec<LoginProvider>,
+}
+
+/// A way to sign in, as offered on the splash page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LoginProvider {
+    /// `google`, or the ID of an OIDC provider
+    pub id: String,
+    /// Button label, e.g. "Google" or "Keycloak"
+    pub name: String,
+    /// Where the browser goes to start the login
+    pub login_url: String,
 }
 
 #[cfg(test)]
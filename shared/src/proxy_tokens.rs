@@ -57,6 +57,7 @@ impl ProxyInitConfig {
 
 /// Request to create a new proxy token
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CreateProxyTokenRequest {
     /// Human-readable name for the token (e.g., "My laptop", "CI runner")
     pub name: String,
@@ -71,6 +72,7 @@ fn default_expires_in_days() -> u32 {
 
 /// Response after creating a proxy token
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CreateProxyTokenResponse {
     /// The token ID
     pub id: Uuid,
@@ -84,6 +86,7 @@ pub struct CreateProxyTokenResponse {
 
 /// Request to renew an existing proxy token
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct RenewProxyTokenRequest {
     /// New token lifetime in days (default: 30)
     #[serde(default = "default_expires_in_days")]
@@ -92,6 +95,7 @@ pub struct RenewProxyTokenRequest {
 
 /// Info about an existing proxy token (without the secret)
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ProxyTokenInfo {
     pub id: Uuid,
     pub name: String,
@@ -103,6 +107,7 @@ pub struct ProxyTokenInfo {
 
 /// List of proxy tokens
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ProxyTokenListResponse {
     pub tokens: Vec<ProxyTokenInfo>,
 }
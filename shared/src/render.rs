@@ -0,0 +1,135 @@
+//! Presentation helpers shared by the web client and transcript export, so a
+//! session reads the same in the browser and in an exported file.
+
+/// One line of a line-based diff
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine<'a> {
+    Context(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Compute a line-based diff between old and new content, as shown for the
+/// `Edit` tool
+pub fn compute_line_diff<'a>(old_string: &'a str, new_string: &'a str) -> Vec<DiffLine<'a>> {
+    let old_lines: Vec<&str> = old_string.lines().collect();
+    let new_lines: Vec<&str> = new_string.lines().collect();
+    let lcs = longest_common_subsequence(&old_lines, &new_lines);
+
+    let mut result = Vec::new();
+    let mut old_idx = 0;
+    let mut new_idx = 0;
+    let mut lcs_idx = 0;
+
+    while old_idx < old_lines.len() || new_idx < new_lines.len() {
+        if lcs_idx < lcs.len() {
+            let (lcs_old, lcs_new) = lcs[lcs_idx];
+
+            while old_idx < lcs_old {
+                result.push(DiffLine::Removed(old_lines[old_idx]));
+                old_idx += 1;
+            }
+
+            while new_idx < lcs_new {
+                result.push(DiffLine::Added(new_lines[new_idx]));
+                new_idx += 1;
+            }
+
+            result.push(DiffLine::Context(old_lines[old_idx]));
+            old_idx += 1;
+            new_idx += 1;
+            lcs_idx += 1;
+        } else {
+            while old_idx < old_lines.len() {
+                result.push(DiffLine::Removed(old_lines[old_idx]));
+                old_idx += 1;
+            }
+            while new_idx < new_lines.len() {
+                result.push(DiffLine::Added(new_lines[new_idx]));
+                new_idx += 1;
+            }
+        }
+    }
+
+    result
+}
+
+/// Compute longest common subsequence indices for line diff
+fn longest_common_subsequence(old: &[&str], new: &[&str]) -> Vec<(usize, usize)> {
+    let m = old.len();
+    let n = new.len();
+
+    if m == 0 || n == 0 {
+        return Vec::new();
+    }
+
+    let mut dp = vec![vec![0usize; n + 1]; m + 1];
+
+    for i in 1..=m {
+        for j in 1..=n {
+            if old[i - 1] == new[j - 1] {
+                dp[i][j] = dp[i - 1][j - 1] + 1;
+            } else {
+                dp[i][j] = dp[i - 1][j].max(dp[i][j - 1]);
+            }
+        }
+    }
+
+    let mut result = Vec::new();
+    let mut i = m;
+    let mut j = n;
+
+    while i > 0 && j > 0 {
+        if old[i - 1] == new[j - 1] {
+            result.push((i - 1, j - 1));
+            i -= 1;
+            j -= 1;
+        } else if dp[i - 1][j] > dp[i][j - 1] {
+            i -= 1;
+        } else {
+            j -= 1;
+        }
+    }
+
+    result.reverse();
+    result
+}
+
+/// Format a duration for display (e.g. "850ms", "12.3s", "2m 5s")
+pub fn format_duration(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms < 60000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        let mins = ms / 60000;
+        let secs = (ms % 60000) / 1000;
+        format!("{}m {}s", mins, secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_line_diff() {
+        assert_eq!(
+            compute_line_diff("a\nb\nc", "a\nx\nc"),
+            vec![
+                DiffLine::Context("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Added("x"),
+                DiffLine::Context("c"),
+            ]
+        );
+        assert_eq!(compute_line_diff("", "new"), vec![DiffLine::Added("new")]);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(850), "850ms");
+        assert_eq!(format_duration(12_300), "12.3s");
+        assert_eq!(format_duration(125_000), "2m 5s");
+    }
+}
@@ -0,0 +1,125 @@
+//! W3C Trace Context carried inside WebSocket messages
+//!
+//! Input and output messages carry an optional `traceparent` so one user
+//! input can be followed from the backend through the proxy to the agent
+//! and back. See <https://www.w3.org/TR/trace-context/>.
+
+use uuid::Uuid;
+
+/// The identifiers of one span in a trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceContext {
+    pub trace_id: [u8; 16],
+    pub span_id: [u8; 8],
+}
+
+impl TraceContext {
+    /// Start a new trace
+    pub fn new_root() -> Self {
+        Self {
+            trace_id: Uuid::new_v4().into_bytes(),
+            span_id: new_span_id(),
+        }
+    }
+
+    /// A new span in the same trace
+    pub fn child(&self) -> Self {
+        Self {
+            trace_id: self.trace_id,
+            span_id: new_span_id(),
+        }
+    }
+
+    /// Parse a `traceparent` header value. Unknown versions and all-zero ids
+    /// are rejected, as the spec requires.
+    pub fn parse(traceparent: &str) -> Option<Self> {
+        let mut parts = traceparent.trim().split('-');
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let span_id = parts.next()?;
+        let flags = parts.next()?;
+        if version != "00" || parts.next().is_some() || flags.len() != 2 {
+            return None;
+        }
+        let context = Self {
+            trace_id: decode_hex(trace_id)?,
+            span_id: decode_hex(span_id)?,
+        };
+        let valid = context.trace_id != [0; 16] && context.span_id != [0; 8];
+        valid.then_some(context)
+    }
+
+    /// Format as a `traceparent` value, always marked sampled
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-01", self.trace_id_hex(), self.span_id_hex())
+    }
+
+    pub fn trace_id_hex(&self) -> String {
+        encode_hex(&self.trace_id)
+    }
+
+    pub fn span_id_hex(&self) -> String {
+        encode_hex(&self.span_id)
+    }
+}
+
+/// A random span id
+pub fn new_span_id() -> [u8; 8] {
+    // The second half of a v4 UUID has only the two variant bits fixed
+    let bytes = Uuid::new_v4().into_bytes();
+    let mut span_id = [0; 8];
+    span_id.copy_from_slice(&bytes[8..]);
+    span_id
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    if hex.len() != N * 2 || !hex.is_ascii() {
+        return None;
+    }
+    let mut bytes = [0; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traceparent_round_trips() {
+        let root = TraceContext::new_root();
+        let child = root.child();
+        assert_eq!(child.trace_id, root.trace_id);
+        assert_ne!(child.span_id, root.span_id);
+        assert_eq!(TraceContext::parse(&child.traceparent()), Some(child));
+    }
+
+    #[test]
+    fn test_parse_spec_example() {
+        let context =
+            TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").unwrap();
+        assert_eq!(context.trace_id_hex(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(context.span_id_hex(), "00f067aa0ba902b7");
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        for value in [
+            "",
+            "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902zz-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+        ] {
+            assert_eq!(TraceContext::parse(value), None, "{}", value);
+        }
+    }
+}